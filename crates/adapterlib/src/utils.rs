@@ -1 +1,12 @@
+use std::time::Duration;
+
 pub mod datafusion;
+
+/// Calculate exponential backoff delay for retrying requests that failed.
+/// Starts at 0.5s, doubles each retry, caps at 32s.
+pub fn calculate_backoff_delay(retry_count: u32) -> Duration {
+    let base_delay_ms = 500; // 0.5 seconds
+    let max_delay_ms = 32_000; // 32 seconds
+    let delay_ms = std::cmp::min(base_delay_ms << retry_count, max_delay_ms);
+    Duration::from_millis(delay_ms)
+}
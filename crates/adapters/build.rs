@@ -1,4 +1,14 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-env-changed=FELDERA_RUNTIME_OVERRIDE");
+
+    // Generate code for the gRPC connectors' services.  `protox` compiles the
+    // protobuf definitions in Rust, so the build doesn't require `protoc`.
+    #[cfg(feature = "with-grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto");
+        let file_descriptors = protox::compile(["feldera_transport.proto"], ["proto"])?;
+        tonic_build::configure().compile_fds(file_descriptors)?;
+    }
+
     Ok(())
 }
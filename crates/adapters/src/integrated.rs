@@ -6,11 +6,33 @@ use feldera_types::program_schema::Relation;
 use postgres::PostgresOutputEndpoint;
 use std::sync::Weak;
 
+#[cfg(feature = "with-alert")]
+mod alert;
+#[cfg(feature = "with-azure-blob")]
+mod azure_blob;
 #[cfg(feature = "with-deltalake")]
 mod delta_table;
+#[cfg(feature = "with-flight")]
+mod flight;
+#[cfg(feature = "with-gcs")]
+mod gcs;
+#[cfg(feature = "with-iceberg")]
+mod iceberg;
+#[cfg(feature = "with-influxdb")]
+mod influxdb;
+#[cfg(feature = "with-mongodb")]
+mod mongodb;
+#[cfg(feature = "with-mysql")]
+mod mysql;
+#[cfg(feature = "with-nats")]
+mod nats_kv;
+mod partitioned_output;
 mod postgres;
+mod s3;
 
-use crate::integrated::postgres::PostgresInputEndpoint;
+#[cfg(feature = "with-flight")]
+use crate::integrated::flight::FlightInputEndpoint;
+use crate::integrated::postgres::{PostgresCdcInputEndpoint, PostgresInputEndpoint};
 
 /// An integrated output connector implements both transport endpoint
 /// (`OutputEndpoint`) and `Encoder` traits.  It is used to implement
@@ -54,6 +76,15 @@ pub fn create_integrated_output_endpoint(
             schema,
             controller,
         )?),
+        #[cfg(feature = "with-iceberg")]
+        TransportConfig::IcebergOutput(config) => Box::new(iceberg::IcebergOutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
         TransportConfig::PostgresOutput(config) => Box::new(PostgresOutputEndpoint::new(
             endpoint_id,
             endpoint_name,
@@ -62,6 +93,79 @@ pub fn create_integrated_output_endpoint(
             schema,
             controller,
         )?),
+        #[cfg(feature = "with-mysql")]
+        TransportConfig::MySqlOutput(config) => Box::new(mysql::MySqlOutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
+        #[cfg(feature = "with-influxdb")]
+        TransportConfig::InfluxDbOutput(config) => Box::new(influxdb::InfluxDbOutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
+        #[cfg(feature = "with-alert")]
+        TransportConfig::AlertOutput(config) => Box::new(alert::AlertOutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
+        #[cfg(feature = "with-flight")]
+        TransportConfig::ArrowFlightOutput(config) => Box::new(flight::FlightOutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
+        #[cfg(feature = "with-mongodb")]
+        TransportConfig::MongoDbOutput(config) => Box::new(mongodb::MongoDbOutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
+        TransportConfig::S3Output(config) => Box::new(s3::S3OutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
+        #[cfg(feature = "with-gcs")]
+        TransportConfig::GcsOutput(config) => Box::new(gcs::GcsOutputEndpoint::new(
+            endpoint_id,
+            endpoint_name,
+            config,
+            key_schema,
+            schema,
+            controller,
+        )?),
+        #[cfg(feature = "with-azure-blob")]
+        TransportConfig::AzureBlobOutput(config) => {
+            Box::new(azure_blob::AzureBlobOutputEndpoint::new(
+                endpoint_id,
+                endpoint_name,
+                config,
+                key_schema,
+                schema,
+                controller,
+            )?)
+        }
         transport => {
             return Err(ControllerError::unknown_output_transport(
                 endpoint_name,
@@ -97,9 +201,34 @@ pub fn create_integrated_input_endpoint(
         TransportConfig::IcebergInput(config) => Box::new(
             feldera_iceberg::IcebergInputEndpoint::new(endpoint_name, config, consumer),
         ),
+        #[cfg(feature = "with-flight")]
+        TransportConfig::ArrowFlightInput(config) => {
+            Box::new(FlightInputEndpoint::new(endpoint_name, config, consumer))
+        }
         TransportConfig::PostgresInput(config) => {
             Box::new(PostgresInputEndpoint::new(endpoint_name, config, consumer))
         }
+        TransportConfig::PostgresCdcInput(config) => Box::new(PostgresCdcInputEndpoint::new(
+            endpoint_name,
+            config,
+            consumer,
+        )),
+        #[cfg(feature = "with-mysql")]
+        TransportConfig::MySqlCdcInput(config) => Box::new(mysql::MySqlCdcInputEndpoint::new(
+            endpoint_name,
+            config,
+            consumer,
+        )),
+        #[cfg(feature = "with-mongodb")]
+        TransportConfig::MongoDbCdcInput(config) => Box::new(
+            mongodb::MongoDbCdcInputEndpoint::new(endpoint_name, config, consumer),
+        ),
+        #[cfg(feature = "with-nats")]
+        TransportConfig::NatsKvInput(config) => Box::new(nats_kv::NatsKvInputEndpoint::new(
+            endpoint_name,
+            config,
+            consumer,
+        )),
         transport => {
             return Err(ControllerError::unknown_input_transport(
                 endpoint_name,
@@ -0,0 +1,504 @@
+//! Alert output connector.
+//!
+//! The connector renders the records added to a view through a template and
+//! sends them as messages to a Slack incoming webhook or as e-mails.
+
+mod template;
+
+use crate::catalog::{CursorWithPolarity, RecordFormat, SerBatchReader};
+use crate::controller::{ControllerInner, EndpointId};
+use crate::format::{Encoder, OutputConsumer, MAX_DUPLICATES};
+use crate::transport::OutputEndpoint;
+use crate::util::{indexed_operation_type, IndexedOperationType};
+use crate::ControllerError;
+use anyhow::{anyhow, bail, Result as AnyResult};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{AsyncErrorCallback, Step};
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::alert::{AlertEmailConfig, AlertOutputConfig, SmtpSecurity};
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode, Url};
+use serde_json::{json, Map, Value as JsonValue};
+use std::sync::Weak;
+use std::time::Duration;
+use template::Template;
+use tracing::{info_span, span::EnteredSpan, warn};
+
+/// Default for [`AlertOutputConfig::max_alerts_per_batch`].
+const DEFAULT_MAX_ALERTS_PER_BATCH: usize = 100;
+
+/// Default for [`AlertOutputConfig::max_retries`].
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Timeout for delivering a single alert.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An alert rendered from a record.
+struct Alert {
+    /// E-mail subject, if the connector sends e-mails.
+    subject: Option<String>,
+    text: String,
+}
+
+/// Sends alerts as e-mails.
+struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    subject: Template,
+}
+
+impl Mailer {
+    fn new(config: &AlertEmailConfig, schema: &Relation) -> AnyResult<Self> {
+        let host = config.smtp_host.as_str();
+        let builder = match config.security {
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(|e| anyhow!("invalid SMTP host '{host}': {e}"))?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+                .map_err(|e| anyhow!("invalid SMTP host '{host}': {e}"))?,
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        let mut builder = builder
+            .port(
+                config
+                    .smtp_port
+                    .unwrap_or_else(|| config.security.default_port()),
+            )
+            .timeout(Some(REQUEST_TIMEOUT));
+        if let Some(username) = &config.username {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                config.password.clone().unwrap_or_default(),
+            ));
+        }
+
+        let parse_mailbox = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| anyhow!("invalid e-mail address '{address}': {e}"))
+        };
+        let subject = match &config.subject_template {
+            Some(subject) => Template::new(subject, schema)?,
+            None => Template::new(&format!("Feldera alert: {}", schema.name.name()), schema)?,
+        };
+        Ok(Self {
+            transport: builder.build(),
+            from: parse_mailbox(&config.from)?,
+            to: config
+                .to
+                .iter()
+                .map(|address| parse_mailbox(address))
+                .collect::<AnyResult<_>>()?,
+            subject,
+        })
+    }
+
+    /// Sends a single e-mail.
+    ///
+    /// Returns whether the error is transient, along with the error.
+    async fn send(&self, subject: &str, text: &str) -> Result<(), (bool, anyhow::Error)> {
+        let mut message = Message::builder().from(self.from.clone());
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(text.to_string())
+            .map_err(|e| (false, anyhow!("error building e-mail: {e}")))?;
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| (!e.is_permanent(), anyhow!("error sending e-mail: {e}")))
+    }
+}
+
+/// Integrated output connector that sends alerts for the records added to a
+/// view.
+pub struct AlertOutputEndpoint {
+    endpoint_id: EndpointId,
+    endpoint_name: String,
+    config: AlertOutputConfig,
+    controller: Weak<ControllerInner>,
+
+    template: Template,
+    slack: Option<(Client, Url)>,
+    mailer: Option<Mailer>,
+
+    value_schema: Relation,
+    key_schema: Option<Relation>,
+    max_alerts: usize,
+
+    /// Alerts in the current batch that haven't been sent yet.
+    alerts: Vec<Alert>,
+
+    /// The number of alerts in the current batch beyond `max_alerts`.
+    dropped: usize,
+}
+
+impl AlertOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &AlertOutputConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        let invalid = |e: anyhow::Error| {
+            ControllerError::invalid_transport_configuration(endpoint_name, &e.to_string())
+        };
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let template = Template::new(&config.template, value_schema).map_err(invalid)?;
+        let slack = match &config.slack_webhook_url {
+            Some(url) => {
+                let url = Url::parse(url)
+                    .map_err(|e| invalid(anyhow!("invalid 'slack_webhook_url': {e}")))?;
+                let client = Client::builder()
+                    .timeout(REQUEST_TIMEOUT)
+                    .build()
+                    .map_err(|e| {
+                        ControllerError::output_transport_error(
+                            endpoint_name,
+                            true,
+                            anyhow!("error creating HTTP client: {e}"),
+                        )
+                    })?;
+                Some((client, url))
+            }
+            None => None,
+        };
+        let mailer = config
+            .email
+            .as_ref()
+            .map(|email| Mailer::new(email, value_schema))
+            .transpose()
+            .map_err(invalid)?;
+
+        Ok(Self {
+            endpoint_id,
+            endpoint_name: endpoint_name.to_owned(),
+            config: config.clone(),
+            controller,
+            template,
+            slack,
+            mailer,
+            value_schema: value_schema.to_owned(),
+            key_schema: key_schema.to_owned(),
+            max_alerts: config
+                .max_alerts_per_batch
+                .unwrap_or(DEFAULT_MAX_ALERTS_PER_BATCH),
+            alerts: Vec::new(),
+            dropped: 0,
+        })
+    }
+
+    fn span(&self) -> EnteredSpan {
+        info_span!(
+            "alert_output",
+            id = self.endpoint_id,
+            name = self.endpoint_name,
+        )
+        .entered()
+    }
+
+    /// Posts a single message to the Slack webhook.
+    ///
+    /// Returns whether the error is transient, along with the error.
+    async fn send_slack(
+        client: &Client,
+        url: &Url,
+        text: &str,
+    ) -> Result<(), (bool, anyhow::Error)> {
+        let response = client
+            .post(url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(json!({ "text": text }).to_string())
+            .send()
+            .await
+            .map_err(|e| (true, anyhow!("error posting to Slack webhook: {e}")))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let transient = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+        let body = response.text().await.unwrap_or_default();
+        Err((
+            transient,
+            anyhow!("Slack webhook request failed with status {status}: {body}"),
+        ))
+    }
+
+    /// Sends `alert` to every destination, retrying transient errors.
+    fn send(&self, alert: &Alert) -> AnyResult<()> {
+        let max_retries = self.config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let mut error = None;
+        if let Some((client, url)) = &self.slack {
+            if let Err(e) = retry(max_retries, || {
+                TOKIO.block_on(Self::send_slack(client, url, &alert.text))
+            }) {
+                error = Some(e);
+            }
+        }
+        if let Some(mailer) = &self.mailer {
+            let subject = alert.subject.as_deref().unwrap_or_default();
+            if let Err(e) = retry(max_retries, || {
+                TOKIO.block_on(mailer.send(subject, &alert.text))
+            }) {
+                error = Some(e);
+            }
+        }
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Sends the alerts in the current batch.
+    fn flush(&mut self) -> AnyResult<()> {
+        if self.dropped > 0 {
+            let text = format!(
+                "{} more alerts for view '{}' were not sent (see 'max_alerts_per_batch')",
+                self.dropped,
+                self.value_schema.name.name()
+            );
+            warn!("alert: {text}");
+            self.alerts.push(Alert {
+                subject: self
+                    .mailer
+                    .as_ref()
+                    .map(|_| format!("Feldera alert: {}", self.value_schema.name.name())),
+                text,
+            });
+        }
+
+        let mut failed = 0;
+        let mut last_error = None;
+        let mut bytes = 0;
+        for alert in &self.alerts {
+            match self.send(alert) {
+                Ok(()) => bytes += alert.text.len(),
+                Err(e) => {
+                    failed += 1;
+                    last_error = Some(e);
+                }
+            }
+        }
+        if let Some(controller) = self.controller.upgrade() {
+            controller
+                .status
+                .output_buffer(self.endpoint_id, bytes, self.alerts.len() - failed);
+        }
+        let total = self.alerts.len();
+        self.alerts.clear();
+        self.dropped = 0;
+        match last_error {
+            Some(e) => Err(anyhow!(
+                "alert: failed to send {failed} of {total} alerts: {e}"
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Renders JSON `record` as an alert.
+    fn push_alert(&mut self, record: &[u8]) -> AnyResult<()> {
+        if self.alerts.len() >= self.max_alerts {
+            self.dropped += 1;
+            return Ok(());
+        }
+        let record: Map<String, JsonValue> = serde_json::from_slice(record)
+            .map_err(|e| anyhow!("error parsing record as JSON: {e}"))?;
+        self.alerts.push(Alert {
+            subject: self
+                .mailer
+                .as_ref()
+                .map(|mailer| mailer.subject.render(&record)),
+            text: self.template.render(&record),
+        });
+        Ok(())
+    }
+
+    /// Encodes the records added to a view without a unique key.
+    fn encode_records(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let mut cursor =
+            CursorWithPolarity::new(batch.cursor(RecordFormat::Json(JsonFlavor::Postgres))?);
+        let mut buf = Vec::new();
+        while cursor.key_valid() {
+            if !cursor.val_valid() {
+                cursor.step_key();
+                continue;
+            }
+            let w = cursor.weight();
+            if w > 0 {
+                buf.clear();
+                cursor.serialize_key(&mut buf)?;
+                if w > MAX_DUPLICATES {
+                    bail!(
+                        "Unable to output record '{}' with very large weight {w}. Consider adjusting your SQL queries to avoid duplicate output records, e.g., using 'SELECT DISTINCT'.",
+                        String::from_utf8_lossy(&buf)
+                    );
+                }
+                for _ in 0..w {
+                    self.push_alert(&buf)?;
+                }
+            }
+            cursor.step_key();
+        }
+        Ok(())
+    }
+
+    /// Encodes the records inserted or updated in an indexed view.
+    fn encode_indexed(
+        &mut self,
+        batch: &dyn SerBatchReader,
+        key_schema: &Relation,
+    ) -> AnyResult<()> {
+        let mut cursor = batch.cursor(RecordFormat::Json(JsonFlavor::Postgres))?;
+        let mut buf = Vec::new();
+        while cursor.key_valid() {
+            if let Some(op) =
+                indexed_operation_type(&self.value_schema.name, &key_schema.name, cursor.as_mut())?
+            {
+                cursor.rewind_vals();
+                match op {
+                    IndexedOperationType::Insert | IndexedOperationType::Upsert => {
+                        if cursor.weight() < 0 {
+                            cursor.step_val();
+                        }
+                        buf.clear();
+                        cursor.serialize_val(&mut buf)?;
+                        self.push_alert(&buf)?;
+                    }
+                    // Deleting a record doesn't raise an alert.
+                    IndexedOperationType::Delete => (),
+                }
+            }
+            cursor.step_key();
+        }
+        Ok(())
+    }
+}
+
+/// Calls `f` until it succeeds, fails with an error that isn't transient, or
+/// has been retried `max_retries` times.
+fn retry<F>(max_retries: u32, mut f: F) -> AnyResult<()>
+where
+    F: FnMut() -> Result<(), (bool, anyhow::Error)>,
+{
+    let mut retries = 0;
+    loop {
+        match f() {
+            Ok(()) => return Ok(()),
+            Err((true, e)) if retries < max_retries => {
+                retries += 1;
+                warn!("alert: retrying ({retries}/{max_retries}) after error: {e}");
+                std::thread::sleep(
+                    Duration::from_millis(100 << retries.min(6)).min(Duration::from_secs(5)),
+                );
+            }
+            Err((_, e)) => return Err(e),
+        }
+    }
+}
+
+impl OutputConsumer for AlertOutputEndpoint {
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn batch_start(&mut self, _step: Step) {}
+
+    fn push_buffer(&mut self, _: &[u8], _: usize) {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _: Option<&[u8]>,
+        _: Option<&[u8]>,
+        _: &[(&str, Option<&[u8]>)],
+        _num_records: usize,
+    ) {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) {
+        let _guard = self.span();
+        let result = self.flush();
+
+        let Some(controller) = self.controller.upgrade() else {
+            warn!("controller is shutting down: aborting");
+            return;
+        };
+        if let Err(e) = result {
+            // Failing to deliver an alert doesn't stop the pipeline.
+            controller.output_transport_error(
+                self.endpoint_id,
+                &self.endpoint_name,
+                false,
+                e,
+                Some("alert_batch_end"),
+            );
+        }
+    }
+}
+
+impl Encoder for AlertOutputEndpoint {
+    fn consumer(&mut self) -> &mut dyn OutputConsumer {
+        self
+    }
+
+    fn encode(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let _guard = self.span();
+        match self.key_schema.take() {
+            Some(key_schema) => {
+                let result = self.encode_indexed(batch, &key_schema);
+                self.key_schema = Some(key_schema);
+                result
+            }
+            None => self.encode_records(batch),
+        }
+    }
+}
+
+impl OutputEndpoint for AlertOutputEndpoint {
+    fn connect(&mut self, _: AsyncErrorCallback) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        todo!()
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8]) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+
+    fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        todo!()
+    }
+}
@@ -0,0 +1,131 @@
+//! Rendering of JSON records through alert templates.
+//!
+//! A template is text in which `{{column}}` is replaced with the value of
+//! `column` in the record.  Whitespace around the column name is ignored.
+
+use anyhow::{anyhow, bail, Result as AnyResult};
+use feldera_types::program_schema::Relation;
+use serde_json::{Map, Value as JsonValue};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Part {
+    Text(String),
+
+    /// Name of a column in the JSON records.
+    Column(String),
+}
+
+/// A template parsed against the schema of a view.
+#[derive(Debug)]
+pub(super) struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn new(template: &str, schema: &Relation) -> AnyResult<Self> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                parts.push(Part::Text(rest[..start].to_string()));
+            }
+            let Some(len) = rest[start + 2..].find("}}") else {
+                bail!("unterminated '{{{{' in template '{template}'");
+            };
+            let name = rest[start + 2..start + 2 + len].trim();
+            if name.is_empty() {
+                bail!("empty column name in template '{template}'");
+            }
+            let field = schema
+                .field(name)
+                .ok_or_else(|| anyhow!("view '{}' has no column '{name}'", schema.name))?;
+            parts.push(Part::Column(field.name.name()));
+            rest = &rest[start + 2 + len + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(rest.to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Renders the JSON object `record`.
+    pub fn render(&self, record: &Map<String, JsonValue>) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => out.push_str(text),
+                Part::Column(name) => match record.get(name) {
+                    Some(JsonValue::String(s)) => out.push_str(s),
+                    Some(JsonValue::Null) | None => out.push_str("NULL"),
+                    Some(value) => out.push_str(&value.to_string()),
+                },
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Template;
+    use feldera_types::program_schema::{ColumnType, Field, Relation};
+    use serde_json::{json, Value as JsonValue};
+
+    fn schema() -> Relation {
+        Relation::new(
+            "orders".into(),
+            vec![
+                Field::new("id".into(), ColumnType::bigint(false)),
+                Field::new("customer".into(), ColumnType::varchar(true)),
+                Field::new("amount".into(), ColumnType::double(true)),
+                Field::new(
+                    "tags".into(),
+                    ColumnType::array(true, ColumnType::varchar(false)),
+                ),
+            ],
+            false,
+            Default::default(),
+        )
+    }
+
+    fn render(template: &Template, record: JsonValue) -> String {
+        template.render(record.as_object().unwrap())
+    }
+
+    #[test]
+    fn test_render() {
+        let template = Template::new(
+            "Order {{id}} by {{ customer }}: {{amount}} USD {{tags}}",
+            &schema(),
+        )
+        .unwrap();
+        assert_eq!(
+            render(
+                &template,
+                json!({"id": 1, "customer": "Alice", "amount": 1.5, "tags": ["a", "b"]})
+            ),
+            "Order 1 by Alice: 1.5 USD [\"a\",\"b\"]"
+        );
+        assert_eq!(
+            render(
+                &template,
+                json!({"id": 2, "customer": null, "amount": 3, "tags": null})
+            ),
+            "Order 2 by NULL: 3 USD NULL"
+        );
+
+        // Column names are case-insensitive, like unquoted SQL identifiers.
+        let template = Template::new("{{ID}}", &schema()).unwrap();
+        assert_eq!(render(&template, json!({"id": 7})), "7");
+
+        let template = Template::new("no columns", &schema()).unwrap();
+        assert_eq!(render(&template, json!({"id": 7})), "no columns");
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Template::new("{{unknown}}", &schema()).is_err());
+        assert!(Template::new("{{id", &schema()).is_err());
+        assert!(Template::new("{{ }}", &schema()).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Azure Blob Storage output connector.
+//!
+//! Writes partitioned files with [`PartitionedOutputEndpoint`], staging the
+//! blocks of files that don't fit in a single part and committing them when
+//! the file is complete.
+
+use crate::controller::{ControllerInner, EndpointId};
+use crate::integrated::partitioned_output::{
+    ObjectSink, PartitionedOutputConfig, PartitionedOutputEndpoint,
+};
+use crate::transport::azure_blob::{azure_object_store, AzureAccount};
+use crate::transport::object_input::object_path;
+use crate::ControllerError;
+use anyhow::Result as AnyResult;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::azure_blob::AzureBlobOutputConfig;
+use object_store::azure::MicrosoftAzure;
+use object_store::{MultipartUpload, ObjectStore, PutPayload};
+use std::sync::Weak;
+
+/// Integrated output connector that writes a view to files in an Azure Blob
+/// Storage container.
+pub(crate) type AzureBlobOutputEndpoint = PartitionedOutputEndpoint<AzureBlobSink>;
+
+impl AzureBlobOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &AzureBlobOutputConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let store = azure_object_store(&AzureAccount {
+            account: &config.account,
+            container: &config.container,
+            access_key: config.access_key.as_deref(),
+            sas_token: config.sas_token.as_deref(),
+            managed_identity_client_id: config.managed_identity_client_id.as_deref(),
+            endpoint_url: config.endpoint_url.as_deref(),
+        })
+        .map_err(|e| {
+            ControllerError::invalid_transport_configuration(endpoint_name, &e.to_string())
+        })?;
+
+        Self::with_sink(
+            endpoint_id,
+            endpoint_name,
+            PartitionedOutputConfig {
+                prefix: &config.prefix,
+                partition_by: &config.partition_by,
+                format: config.format,
+                max_file_size_bytes: config.max_file_size_bytes,
+                rollover_interval_secs: config.rollover_interval_secs,
+            },
+            key_schema,
+            value_schema,
+            controller,
+            AzureBlobSink {
+                store,
+                container: config.container.clone(),
+            },
+        )
+    }
+}
+
+/// Writes blobs to an Azure Blob Storage container.
+pub(crate) struct AzureBlobSink {
+    store: MicrosoftAzure,
+    container: String,
+}
+
+impl ObjectSink for AzureBlobSink {
+    type Upload = Box<dyn MultipartUpload>;
+
+    fn bucket(&self) -> &str {
+        &self.container
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> AnyResult<()> {
+        self.store
+            .put(&object_path(key)?, PutPayload::from(data))
+            .await?;
+        Ok(())
+    }
+
+    async fn start_upload(&self, key: &str) -> AnyResult<Self::Upload> {
+        Ok(self.store.put_multipart(&object_path(key)?).await?)
+    }
+
+    async fn upload_part(&self, upload: &mut Self::Upload, data: Vec<u8>) -> AnyResult<()> {
+        upload.put_part(PutPayload::from(data)).await?;
+        Ok(())
+    }
+
+    async fn complete_upload(&self, mut upload: Self::Upload) -> AnyResult<()> {
+        upload.complete().await?;
+        Ok(())
+    }
+
+    async fn abort_upload(&self, mut upload: Self::Upload) -> AnyResult<()> {
+        upload.abort().await?;
+        Ok(())
+    }
+}
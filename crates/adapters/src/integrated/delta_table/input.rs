@@ -6,7 +6,7 @@ use crate::util::{root_cause, JobQueue};
 use crate::{ControllerError, InputConsumer, InputReader, PipelineState};
 use anyhow::{anyhow, bail, Error as AnyError, Result as AnyResult};
 use arrow::array::BooleanArray;
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Field, Schema};
 use arrow::error::ArrowError;
 use chrono::{DateTime, Utc};
 use datafusion::common::arrow::array::{AsArray, RecordBatch};
@@ -29,6 +29,7 @@ use deltalake::table::PeekCommit;
 use deltalake::{datafusion, DeltaTable, DeltaTableBuilder};
 use feldera_adapterlib::format::ParseError;
 use feldera_adapterlib::transport::{parse_resume_info, Resume, Watermark};
+use feldera_adapterlib::utils::calculate_backoff_delay;
 use feldera_adapterlib::utils::datafusion::{
     execute_query_collect, execute_singleton_query, timestamp_to_sql_expression,
     validate_sql_expression, validate_timestamp_column,
@@ -52,18 +53,12 @@ use tokio::time::sleep;
 use tracing::{debug, info, trace, warn};
 use url::Url;
 
+/// Name of the column that stores the type of change in Delta change data files.
+const CHANGE_TYPE_COLUMN: &str = "_change_type";
+
 /// Polling interval when following a delta table.
 const POLL_INTERVAL: Duration = Duration::from_millis(1000);
 
-/// Calculate exponential backoff delay for retrying delta log reads.
-/// Starts at 0.5s, doubles each retry, caps at 32s.
-fn calculate_backoff_delay(retry_count: u32) -> Duration {
-    let base_delay_ms = 500; // 0.5 seconds
-    let max_delay_ms = 32_000; // 32 seconds
-    let delay_ms = std::cmp::min(base_delay_ms << retry_count, max_delay_ms);
-    Duration::from_millis(delay_ms)
-}
-
 /// Default object store timeout. When not explicitly set by the user,
 /// we use a large timeout value to avoid this issue:
 /// https://github.com/delta-io/delta-rs/issues/2595, which is common for
@@ -1402,7 +1397,8 @@ impl DeltaTableInputEndpointInner {
 
     /// Apply actions from a transaction log entry.
     ///
-    /// Only `Add` and `Remove` actions are picked up.
+    /// Only `Add` and `Remove` actions are picked up, except in the
+    /// `change_data_feed` mode, where `Cdc` actions take precedence when present.
     async fn process_log_entry(
         &self,
         new_version: i64,
@@ -1418,6 +1414,13 @@ impl DeltaTableInputEndpointInner {
         if self.config.is_cdc() {
             self.process_cdc_transaction(actions, table, cdc_delete_filter, input_stream, receiver)
                 .await;
+        } else if self.config.is_change_data_feed()
+            && actions
+                .iter()
+                .any(|action| matches!(action, Action::Cdc(_)))
+        {
+            self.process_change_data_feed_transaction(actions, table, input_stream, receiver)
+                .await;
         } else {
             let column_names = self.used_column_list(table);
 
@@ -1498,7 +1501,7 @@ impl DeltaTableInputEndpointInner {
 
         // Create a datafusion table backed by these files.
         let table = Arc::new(
-            self.create_parquet_table(table, files, &description)
+            self.create_parquet_table(table, files, &[], &description)
                 .await?,
         );
 
@@ -1535,11 +1538,112 @@ impl DeltaTableInputEndpointInner {
         Ok(())
     }
 
+    /// Process a DeltaLake transaction in the `change_data_feed` mode.
+    ///
+    /// According to the Delta protocol, when a commit contains `Cdc` actions,
+    /// the change data files they point to describe all row-level changes
+    /// performed by the commit, and `Add`/`Remove` actions must be ignored.
+    /// Each row in a change data file has a `_change_type` column:
+    ///
+    /// * `insert` and `update_postimage` rows are ingested as insertions.
+    /// * `delete` and `update_preimage` rows are ingested as deletions.
+    async fn process_change_data_feed_transaction(
+        &self,
+        actions: &[Action],
+        table: &DeltaTable,
+        input_stream: &mut dyn ArrowStream,
+        receiver: &mut Receiver<PipelineState>,
+    ) {
+        let result = self
+            .do_process_change_data_feed_transaction(actions, table, input_stream, receiver)
+            .await;
+
+        // Deregister the table registered by `do_process_change_data_feed_transaction`.
+        // If the table does not exist, there's no harm.
+        let _ = self.datafusion.deregister_table("tmp_table");
+
+        if let Err(e) = result {
+            self.consumer.error(false, e, Some("delta-cdf"));
+        }
+    }
+
+    async fn do_process_change_data_feed_transaction(
+        &self,
+        actions: &[Action],
+        table: &DeltaTable,
+        input_stream: &mut dyn ArrowStream,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        let column_names = self.used_column_list(table);
+
+        // List all change data files in the transaction.
+        let files = actions
+            .iter()
+            .flat_map(|action| match action {
+                Action::Cdc(cdc) => Some(format!(
+                    "{}{}",
+                    table.log_store().object_store_url().as_str(),
+                    cdc.path
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let description = format!(
+            "change data feed transaction consisting of {} files {:?}",
+            files.len(),
+            &files
+        );
+
+        // Create a datafusion table backed by these files. In addition to the
+        // columns of the Delta table, change data files contain the `_change_type`
+        // column.
+        let change_type = Field::new(CHANGE_TYPE_COLUMN, DataType::Utf8, false);
+        let table = Arc::new(
+            self.create_parquet_table(table, files, &[change_type], &description)
+                .await?,
+        );
+
+        self.datafusion.register_table("tmp_table", table).map_err(|e| {
+            anyhow!("internal error processing {description}; {REPORT_ERROR}; error registering Parquet table: {e}")
+        })?;
+
+        let filter = if let Some(filter) = &self.config.filter {
+            format!(" AND ({filter})")
+        } else {
+            "".to_string()
+        };
+
+        // Push deletions before insertions, so that updates are applied correctly
+        // to tables with a primary key.
+        for (change_types, polarity) in [
+            ("'delete', 'update_preimage'", false),
+            ("'insert', 'update_postimage'", true),
+        ] {
+            let query = format!(
+                "SELECT {column_names} FROM tmp_table WHERE {CHANGE_TYPE_COLUMN} IN ({change_types}){filter}"
+            );
+
+            let df = self.datafusion.sql(&query).await.map_err(|e| {
+                anyhow!("invalid 'filter' expression: 'filter' (when specified) must be a valid SQL expression that can be used in a 'SELECT * FROM <table> WHERE <filter>' query, but the following error was encountered when compiling '{query}': {e}")
+            })?;
+
+            self.execute_df(df, polarity, None, &description, input_stream, receiver)
+                .await;
+        }
+
+        Ok(())
+    }
+
     /// Create a table provider from a list of Parquet files.
+    ///
+    /// The schema of the table consists of the columns of the Delta table
+    /// followed by `extra_fields`.
     async fn create_parquet_table(
         &self,
         table: &DeltaTable,
         files: Vec<String>,
+        extra_fields: &[Field],
         description: &str,
     ) -> AnyResult<ListingTable> {
         let Some(schema) = table.schema() else {
@@ -1548,6 +1652,13 @@ impl DeltaTableInputEndpointInner {
         };
 
         let schema: Schema = schema.try_into().map_err(|e| anyhow!("internal error processing {description}; {REPORT_ERROR}; error converting Delta schema {schema:?} to arrow schema: {e}"))?;
+        let schema = if extra_fields.is_empty() {
+            schema
+        } else {
+            let mut fields = schema.fields().iter().cloned().collect::<Vec<_>>();
+            fields.extend(extra_fields.iter().cloned().map(Arc::new));
+            Schema::new_with_metadata(fields, schema.metadata().clone())
+        };
 
         let mut urls = Vec::with_capacity(files.len());
         for file in files.iter() {
@@ -1624,7 +1735,7 @@ impl DeltaTableInputEndpointInner {
 
         // Create a datafusion table backed by these files.
         let table = Arc::new(
-            self.create_parquet_table(table, vec![full_path.clone()], &description)
+            self.create_parquet_table(table, vec![full_path.clone()], &[], &description)
                 .await?,
         );
 
@@ -17,7 +17,7 @@ use deltalake::datafusion::prelude::SessionContext;
 use deltalake::kernel::{DataType, StructField};
 use deltalake::operations::create::CreateBuilder;
 use deltalake::protocol::SaveMode;
-use deltalake::{DeltaOps, DeltaTable, DeltaTableBuilder};
+use deltalake::{DeltaOps, DeltaTable, DeltaTableBuilder, TableProperty};
 use feldera_adapterlib::utils::datafusion::execute_query_collect;
 use feldera_types::config::PipelineConfig;
 use feldera_types::format::json::JsonFlavor;
@@ -1266,6 +1266,101 @@ async fn test_cdc(
     write_pipeline.stop().unwrap();
 }
 
+/// Read the Change Data Feed of a Delta table that is modified using
+/// inserts, deletes, and updates.
+async fn test_change_data_feed(
+    schema: &[Field],
+    table_uri: &str,
+    storage_options: &HashMap<String, String>,
+    data: Vec<DeltaTestStruct>,
+) {
+    init_logging();
+
+    let arrow_schema = Arc::new(ArrowSchema::new(relation_to_arrow_fields(schema, true)));
+
+    let struct_fields = arrow_schema
+        .fields
+        .iter()
+        .map(|f| {
+            StructField::new(
+                f.name(),
+                DataType::try_from(f.data_type()).unwrap(),
+                f.is_nullable(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Create a table with Change Data Feed enabled.
+    let mut table = CreateBuilder::new()
+        .with_location(table_uri)
+        .with_save_mode(SaveMode::Ignore)
+        .with_storage_options(storage_options.clone())
+        .with_columns(struct_fields)
+        .with_configuration_property(TableProperty::EnableChangeDataFeed, Some("true"))
+        .await
+        .unwrap();
+
+    // Follow the table starting from the version created above.
+    let mut input_config = storage_options.clone();
+    input_config.insert("mode".to_string(), "change_data_feed".to_string());
+    input_config.insert("version".to_string(), table.version().to_string());
+
+    let table_uri_clone = table_uri.to_string();
+    let storage_dir = TempDir::new().unwrap();
+    let storage_dir_path = storage_dir.path().to_path_buf();
+    let pipeline = tokio::task::spawn_blocking(move || {
+        delta_read_pipeline::<DeltaTestStruct, DeltaTestKey, _>(
+            &table_uri_clone,
+            &input_config,
+            &[SqlIdentifier::from("bigint")],
+            |x: &DeltaTestStruct| DeltaTestKey { bigint: x.bigint },
+            &storage_dir_path,
+        )
+    })
+    .await
+    .unwrap();
+
+    pipeline.start();
+
+    // Append-only commits don't produce change data files and are ingested
+    // based on the files they add.
+    let mut expected = Vec::new();
+    for chunk in data.chunks(std::cmp::max(data.len() / 2, 1)) {
+        table = write_data_to_table(table, &arrow_schema, chunk).await;
+        expected.extend_from_slice(chunk);
+
+        wait_for_records_materialized(&pipeline, &SqlIdentifier::from("test_output1"), &expected)
+            .await;
+    }
+
+    // Deletes are ingested from change data files.
+    (table, _) = DeltaOps(table)
+        .delete()
+        .with_predicate("bigint % 4 = 0")
+        .await
+        .unwrap();
+    expected.retain(|x| x.bigint % 4 != 0);
+
+    wait_for_records_materialized(&pipeline, &SqlIdentifier::from("test_output1"), &expected).await;
+
+    // Updates are ingested as deletions of pre-images followed by insertions of post-images.
+    let _ = DeltaOps(table)
+        .update()
+        .with_predicate("bigint % 4 = 2")
+        .with_update("string", "'updated'")
+        .await
+        .unwrap();
+    for x in expected.iter_mut() {
+        if x.bigint % 4 == 2 {
+            x.string = "updated".to_string();
+        }
+    }
+
+    wait_for_records_materialized(&pipeline, &SqlIdentifier::from("test_output1"), &expected).await;
+
+    pipeline.stop().unwrap();
+}
+
 /// Generate up to `max_records` _unique_ records.
 fn delta_data(max_records: usize) -> impl Strategy<Value = Vec<DeltaTestStruct>> {
     vec(DeltaTestStruct::arbitrary(), 0..max_records).prop_map(|vec| {
@@ -1332,6 +1427,21 @@ async fn delta_table_cdc_file_test() {
     .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn delta_table_change_data_feed_file_test() {
+    // We cannot use proptest macros in `async` context, so generate
+    // some random data manually.
+    let mut runner = TestRunner::default();
+    let data = delta_data(20_000).new_tree(&mut runner).unwrap().current();
+
+    let relation_schema = DeltaTestStruct::schema();
+
+    let input_table_dir = TempDir::new().unwrap();
+    let input_table_uri = input_table_dir.path().display().to_string();
+
+    test_change_data_feed(&relation_schema, &input_table_uri, &HashMap::new(), data).await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn delta_table_cdc_file_indexed_test() {
     // We cannot use proptest macros in `async` context, so generate
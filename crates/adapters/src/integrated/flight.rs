@@ -0,0 +1,99 @@
+//! Arrow Flight connectors.
+//!
+//! The connectors exchange Arrow record batches with a remote Flight server,
+//! bypassing text formats entirely.
+
+mod input;
+mod output;
+
+#[cfg(test)]
+mod test;
+
+pub use input::FlightInputEndpoint;
+pub use output::FlightOutputEndpoint;
+
+use crate::transport::tls::read_pem;
+use anyhow::{anyhow, Context, Result as AnyResult};
+use arrow_flight::FlightClient;
+use feldera_types::transport::tls::TlsConfig;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tonic::transport::{Certificate, ClientTlsConfig, Endpoint, Identity};
+
+/// Default for the connectors' `connect_timeout_secs`.
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Settings for connecting to a Flight server, shared by all Flight
+/// connectors.
+struct FlightConnection<'a> {
+    uri: &'a str,
+    auth_token: &'a Option<String>,
+    headers: &'a BTreeMap<String, String>,
+    tls: &'a Option<TlsConfig>,
+    connect_timeout_secs: Option<u64>,
+}
+
+impl FlightConnection<'_> {
+    /// Connects to the server.
+    async fn connect(&self) -> AnyResult<FlightClient> {
+        // The URI has been validated, so it has one of the supported schemes.
+        let (scheme, rest) = self
+            .uri
+            .split_once("://")
+            .ok_or_else(|| anyhow!("invalid Flight server URI '{}'", self.uri))?;
+        let use_tls = matches!(scheme, "grpc+tls" | "https");
+        let url = format!("{}://{rest}", if use_tls { "https" } else { "http" });
+
+        let mut endpoint = Endpoint::from_shared(url)
+            .with_context(|| format!("invalid Flight server URI '{}'", self.uri))?
+            .connect_timeout(Duration::from_secs(
+                self.connect_timeout_secs
+                    .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS),
+            ));
+        if use_tls {
+            endpoint = endpoint
+                .tls_config(client_tls_config(
+                    self.tls.as_ref().unwrap_or(&TlsConfig::default()),
+                )?)
+                .context("invalid TLS configuration")?;
+        }
+        let channel = endpoint
+            .connect()
+            .await
+            .with_context(|| format!("error connecting to Flight server '{}'", self.uri))?;
+
+        let mut client = FlightClient::new(channel);
+        if let Some(token) = self.auth_token {
+            client
+                .add_header("authorization", &format!("Bearer {token}"))
+                .context("invalid 'auth_token'")?;
+        }
+        for (name, value) in self.headers {
+            client
+                .add_header(name, value)
+                .with_context(|| format!("invalid header '{name}'"))?;
+        }
+        Ok(client)
+    }
+}
+
+/// Converts connector TLS settings into a tonic TLS configuration.
+fn client_tls_config(tls: &TlsConfig) -> AnyResult<ClientTlsConfig> {
+    let mut config = ClientTlsConfig::new();
+    config = match read_pem("ca_cert", &tls.ca_cert_pem, &tls.ca_cert_path)? {
+        Some(pem) => config.ca_certificate(Certificate::from_pem(pem)),
+        None => config.with_webpki_roots(),
+    };
+    let client_cert = read_pem("client_cert", &tls.client_cert_pem, &tls.client_cert_path)?;
+    let client_key = read_pem("client_key", &tls.client_key_pem, &tls.client_key_path)?;
+    match (client_cert, client_key) {
+        (Some(cert), Some(key)) => config = config.identity(Identity::from_pem(cert, key)),
+        (None, None) => (),
+        _ => {
+            return Err(anyhow!(
+                "client certificate and key must be specified together"
+            ))
+        }
+    }
+    Ok(config)
+}
@@ -0,0 +1,275 @@
+use super::FlightConnection;
+use crate::catalog::{ArrowStream, InputCollectionHandle};
+use crate::format::parquet::default_arrow_serde_config;
+use crate::format::InputBuffer;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, IntegratedInputEndpoint, NonFtInputReaderCommand,
+};
+use crate::{ControllerError, InputConsumer, InputReader, PipelineState};
+use anyhow::{anyhow, Result as AnyResult};
+use arrow_flight::{FlightClient, Ticket};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::format::ParseError;
+use feldera_types::config::FtModel;
+use feldera_types::transport::arrow_flight::ArrowFlightInputConfig;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::thread;
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tracing::{debug, info};
+
+/// Integrated input connector that reads record batches from an Arrow Flight
+/// server with `DoGet`.
+pub struct FlightInputEndpoint {
+    inner: Arc<FlightInputEndpointInner>,
+}
+
+impl FlightInputEndpoint {
+    pub fn new(
+        endpoint_name: &str,
+        config: &ArrowFlightInputConfig,
+        consumer: Box<dyn InputConsumer>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(FlightInputEndpointInner::new(
+                endpoint_name,
+                config.clone(),
+                consumer,
+            )),
+        }
+    }
+}
+
+impl InputEndpoint for FlightInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl IntegratedInputEndpoint for FlightInputEndpoint {
+    fn open(
+        self: Box<Self>,
+        input_handle: &InputCollectionHandle,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(FlightInputReader::new(&self.inner, input_handle)?))
+    }
+}
+
+struct FlightInputReader {
+    sender: Sender<PipelineState>,
+    inner: Arc<FlightInputEndpointInner>,
+}
+
+impl FlightInputReader {
+    fn new(
+        endpoint: &Arc<FlightInputEndpointInner>,
+        input_handle: &InputCollectionHandle,
+    ) -> AnyResult<Self> {
+        endpoint.config.validate().map_err(|e| {
+            ControllerError::invalid_transport_configuration(&endpoint.endpoint_name, &e)
+        })?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let endpoint_clone = endpoint.clone();
+
+        // Used to communicate the status of connector initialization.
+        let (init_status_sender, mut init_status_receiver) =
+            mpsc::channel::<Result<(), ControllerError>>(1);
+
+        let input_stream = input_handle
+            .handle
+            .configure_arrow_deserializer(default_arrow_serde_config().clone())?;
+
+        thread::Builder::new()
+            .name(format!(
+                "{}-arrow-flight-input-tokio-wrapper",
+                endpoint.endpoint_name
+            ))
+            .spawn(move || {
+                TOKIO.block_on(async {
+                    endpoint_clone
+                        .worker_task(input_stream, receiver, init_status_sender)
+                        .await;
+                })
+            })
+            .expect("failed to create Arrow Flight input connector tokio wrapper thread");
+
+        init_status_receiver.blocking_recv().ok_or_else(|| {
+            ControllerError::input_transport_error(
+                &endpoint.endpoint_name,
+                true,
+                anyhow!("worker thread terminated unexpectedly during initialization"),
+            )
+        })??;
+
+        Ok(Self {
+            sender,
+            inner: endpoint.clone(),
+        })
+    }
+}
+
+impl InputReader for FlightInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => self.inner.queue.queue(),
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for FlightInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+struct FlightInputEndpointInner {
+    endpoint_name: String,
+    config: ArrowFlightInputConfig,
+    consumer: Box<dyn InputConsumer>,
+    queue: InputQueue,
+}
+
+impl FlightInputEndpointInner {
+    fn new(
+        endpoint_name: &str,
+        config: ArrowFlightInputConfig,
+        consumer: Box<dyn InputConsumer>,
+    ) -> Self {
+        let queue = InputQueue::new(consumer.clone());
+
+        Self {
+            endpoint_name: endpoint_name.to_string(),
+            config,
+            consumer,
+            queue,
+        }
+    }
+
+    async fn worker_task(
+        self: Arc<Self>,
+        input_stream: Box<dyn ArrowStream>,
+        receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let mut receiver_clone = receiver.clone();
+        select! {
+            _ = self.clone().worker_task_inner(input_stream, receiver, init_status_sender) => {
+                debug!("arrow_flight {}: worker task terminated", &self.endpoint_name);
+            }
+            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                debug!(
+                    "arrow_flight {}: received termination command; worker task canceled",
+                    &self.endpoint_name
+                );
+            }
+        }
+    }
+
+    async fn worker_task_inner(
+        self: Arc<Self>,
+        mut input_stream: Box<dyn ArrowStream>,
+        mut receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let client = match self.connect().await {
+            Ok(client) => client,
+            Err(e) => {
+                let _ = init_status_sender.send(Err(e)).await;
+                return;
+            }
+        };
+        let _ = init_status_sender.send(Ok(())).await;
+        wait_running(&mut receiver).await;
+
+        match self
+            .read(client, input_stream.as_mut(), &mut receiver)
+            .await
+        {
+            Ok(()) => self.consumer.eoi(),
+            Err(e) => self.consumer.error(true, e, Some("flight-input")),
+        }
+    }
+
+    async fn connect(&self) -> Result<FlightClient, ControllerError> {
+        debug!(
+            "arrow_flight {}: connecting to '{}'",
+            &self.endpoint_name, &self.config.uri
+        );
+        let client = FlightConnection {
+            uri: &self.config.uri,
+            auth_token: &self.config.auth_token,
+            headers: &self.config.headers,
+            tls: &self.config.tls,
+            connect_timeout_secs: self.config.connect_timeout_secs,
+        }
+        .connect()
+        .await
+        .map_err(|e| ControllerError::input_transport_error(&self.endpoint_name, true, e))?;
+        info!(
+            "arrow_flight {}: connected to '{}'",
+            &self.endpoint_name, &self.config.uri
+        );
+        Ok(client)
+    }
+
+    /// Reads the stream returned by `DoGet` and queues its record batches.
+    async fn read(
+        &self,
+        mut client: FlightClient,
+        input_stream: &mut dyn ArrowStream,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        let ticket = Ticket::new(self.config.ticket.clone());
+        let mut stream = client
+            .do_get(ticket)
+            .await
+            .map_err(|e| anyhow!("Flight DoGet failed: {e}"))?;
+
+        // Set the ingestion timestamp to the time we start reading the next batch.
+        let mut timestamp = Utc::now();
+        let mut num_batches = 0;
+        while let Some(batch) = stream.next().await {
+            let batch = batch
+                .map_err(|e| anyhow!("error reading record batch {num_batches} from DoGet: {e}"))?;
+            num_batches += 1;
+            wait_running(receiver).await;
+
+            let errors = match input_stream.insert(&batch) {
+                Ok(()) => Vec::new(),
+                Err(e) => vec![ParseError::bin_envelope_error(
+                    format!("error deserializing table records from Arrow record batch {num_batches}: {e}"),
+                    &[],
+                    None,
+                )],
+            };
+            self.queue
+                .push((input_stream.take_all(), errors), timestamp);
+            timestamp = Utc::now();
+        }
+        debug!(
+            "arrow_flight {}: read {num_batches} record batches",
+            &self.endpoint_name
+        );
+        Ok(())
+    }
+}
+
+/// Block until the state is `Running`.
+async fn wait_running(receiver: &mut Receiver<PipelineState>) {
+    // An error indicates that the channel was closed.  It's ok to ignore
+    // the error as this situation will be handled by the top-level select,
+    // which will abort the worker thread.
+    let _ = receiver
+        .wait_for(|state| state == &PipelineState::Running)
+        .await;
+}
@@ -0,0 +1,414 @@
+use super::FlightConnection;
+use crate::catalog::{CursorWithPolarity, SerBatchReader};
+use crate::controller::{ControllerInner, EndpointId};
+use crate::format::parquet::{default_arrow_serde_config, relation_to_arrow_fields};
+use crate::format::MAX_DUPLICATES;
+use crate::transport::Step;
+use crate::util::{indexed_operation_type, IndexedOperationType};
+use crate::{
+    AsyncErrorCallback, ControllerError, Encoder, OutputConsumer, OutputEndpoint, RecordFormat,
+    SerCursor,
+};
+use anyhow::{anyhow, bail, Result as AnyResult};
+use arrow::array::RecordBatch;
+use arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema};
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::{FlightClient, FlightDescriptor};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::program_schema::{Relation, SqlIdentifier};
+use feldera_types::transport::arrow_flight::ArrowFlightOutputConfig;
+use futures::{StreamExt, TryStreamExt};
+use serde::Serialize;
+use serde_arrow::schema::SerdeArrowSchema;
+use serde_arrow::ArrayBuilder;
+use std::sync::{Arc, Weak};
+use tokio::sync::mpsc::{channel, Sender};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, info_span, span::EnteredSpan};
+
+/// Default for [`ArrowFlightOutputConfig::max_batch_records`].
+const DEFAULT_MAX_BATCH_RECORDS: usize = 100_000;
+
+/// The number of record batches that can be waiting to be sent to the
+/// server.
+const QUEUED_BATCHES: usize = 4;
+
+/// Metadata added to each record, representing the type and order of
+/// operations.
+#[derive(Serialize)]
+struct Meta<'a> {
+    /// `i` for insert, `d` for delete, `u` for update.
+    __feldera_op: &'a str,
+
+    /// Timestamp in microseconds since UNIX epoch when the batch of updates
+    /// was output by the pipeline.
+    __feldera_ts: i64,
+}
+
+impl<'a> Meta<'a> {
+    fn new(op: &'a str, ts: i64) -> Self {
+        Meta {
+            __feldera_op: op,
+            __feldera_ts: ts,
+        }
+    }
+}
+
+/// A `DoPut` call in progress.
+struct Put {
+    /// Sends record batches to the call.
+    sender: Sender<RecordBatch>,
+
+    /// The task that runs the call, which gives back the client when the
+    /// call completes.
+    task: JoinHandle<(FlightClient, AnyResult<()>)>,
+}
+
+/// Integrated output connector that sends the changes to a view in each step
+/// to an Arrow Flight server, as a `DoPut` stream of record batches.
+pub struct FlightOutputEndpoint {
+    endpoint_id: EndpointId,
+    endpoint_name: String,
+    controller: Weak<ControllerInner>,
+
+    key_schema: Option<Relation>,
+    value_schema: Relation,
+    serde_arrow_schema: SerdeArrowSchema,
+    arrow_schema: Arc<ArrowSchema>,
+    descriptor: FlightDescriptor,
+    max_batch_records: usize,
+
+    /// The client, unless it is in use by `put`.
+    client: Option<FlightClient>,
+
+    /// The `DoPut` call for the current step, once the step has produced
+    /// its first record batch.
+    put: Option<Put>,
+
+    /// The number of records and bytes sent in the current step.
+    num_records: usize,
+    num_bytes: usize,
+}
+
+impl FlightOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &ArrowFlightOutputConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let mut arrow_fields = relation_to_arrow_fields(&value_schema.fields, false);
+        arrow_fields.push(ArrowField::new("__feldera_op", ArrowDataType::Utf8, true));
+        arrow_fields.push(ArrowField::new("__feldera_ts", ArrowDataType::Int64, true));
+        let serde_arrow_schema =
+            SerdeArrowSchema::try_from(arrow_fields.as_slice()).map_err(|e| {
+                ControllerError::SchemaParseError {
+                    error: format!("Unable to convert schema to Arrow: {e}"),
+                }
+            })?;
+        let arrow_schema = Arc::new(ArrowSchema::new(arrow_fields));
+
+        let path = config
+            .path
+            .clone()
+            .unwrap_or_else(|| vec![value_schema.name.name()]);
+
+        let client = TOKIO
+            .block_on(
+                FlightConnection {
+                    uri: &config.uri,
+                    auth_token: &config.auth_token,
+                    headers: &config.headers,
+                    tls: &config.tls,
+                    connect_timeout_secs: config.connect_timeout_secs,
+                }
+                .connect(),
+            )
+            .map_err(|e| ControllerError::output_transport_error(endpoint_name, true, e))?;
+
+        Ok(Self {
+            endpoint_id,
+            endpoint_name: endpoint_name.to_string(),
+            controller,
+            key_schema: key_schema.clone(),
+            value_schema: value_schema.clone(),
+            serde_arrow_schema,
+            arrow_schema,
+            descriptor: FlightDescriptor::new_path(path),
+            max_batch_records: config
+                .max_batch_records
+                .unwrap_or(DEFAULT_MAX_BATCH_RECORDS),
+            client: Some(client),
+            put: None,
+            num_records: 0,
+            num_bytes: 0,
+        })
+    }
+
+    fn span(&self) -> EnteredSpan {
+        info_span!(
+            "arrow_flight_output",
+            id = self.endpoint_id,
+            name = self.endpoint_name,
+        )
+        .entered()
+    }
+
+    fn view_name(&self) -> &SqlIdentifier {
+        &self.value_schema.name
+    }
+
+    /// Starts a `DoPut` call that streams the record batches sent to the
+    /// returned `Put`.
+    fn start_put(&mut self) -> AnyResult<Put> {
+        let Some(mut client) = self.client.take() else {
+            bail!("the connection to the Flight server was lost after an earlier error");
+        };
+        let (sender, receiver) = channel(QUEUED_BATCHES);
+        let flight_data = FlightDataEncoderBuilder::new()
+            .with_schema(self.arrow_schema.clone())
+            .with_flight_descriptor(Some(self.descriptor.clone()))
+            .build(ReceiverStream::new(receiver).map(Ok));
+        let task = TOKIO.spawn(async move {
+            let result = async {
+                let mut results = client.do_put(flight_data).await?;
+                while results.try_next().await?.is_some() {}
+                Ok::<_, FlightError>(())
+            }
+            .await
+            .map_err(|e| anyhow!("Flight DoPut failed: {e}"));
+            (client, result)
+        });
+        Ok(Put { sender, task })
+    }
+
+    /// Waits for the `DoPut` call for the current step, if any, to complete.
+    fn finish_put(&mut self) -> AnyResult<()> {
+        let Some(Put { sender, task }) = self.put.take() else {
+            return Ok(());
+        };
+
+        // Closing the channel ends the stream of record batches.
+        drop(sender);
+        let (client, result) = TOKIO
+            .block_on(task)
+            .map_err(|e| anyhow!("Flight DoPut task failed: {e}"))?;
+        self.client = Some(client);
+        result
+    }
+
+    /// Sends the records in `builder` to the server.
+    fn send(&mut self, builder: &mut ArrayBuilder) -> AnyResult<()> {
+        let batch = builder
+            .to_record_batch()
+            .map_err(|e| anyhow!("error generating arrow arrays: {e}"))?;
+        self.num_records += batch.num_rows();
+        self.num_bytes += batch.get_array_memory_size();
+
+        if self.put.is_none() {
+            self.put = Some(self.start_put()?);
+        }
+        let sender = &self.put.as_ref().unwrap().sender;
+        if TOKIO.block_on(sender.send(batch)).is_err() {
+            // The call ended early, so it failed.
+            self.finish_put()?;
+            bail!("Flight DoPut ended before all record batches were sent");
+        }
+        Ok(())
+    }
+
+    /// Encodes the records added to or deleted from a view without a unique
+    /// key.
+    fn encode_records(
+        &mut self,
+        batch: &dyn SerBatchReader,
+        builder: &mut ArrayBuilder,
+        micros: i64,
+    ) -> AnyResult<usize> {
+        let mut num_records = 0;
+        let mut cursor = CursorWithPolarity::new(
+            batch.cursor(RecordFormat::Parquet(default_arrow_serde_config().clone()))?,
+        );
+        while cursor.key_valid() {
+            if !cursor.val_valid() {
+                cursor.step_key();
+                continue;
+            }
+
+            let mut w = cursor.weight();
+            if !(-MAX_DUPLICATES..=MAX_DUPLICATES).contains(&w) {
+                bail!("Unable to output record with very large weight {w}. Consider adjusting your SQL queries to avoid duplicate output records, e.g., using 'SELECT DISTINCT'.");
+            }
+
+            while w != 0 {
+                let op = if w > 0 { "i" } else { "d" };
+                cursor.serialize_key_to_arrow_with_metadata(&Meta::new(op, micros), builder)?;
+                w -= w.signum();
+                num_records += 1;
+                if num_records >= self.max_batch_records {
+                    self.send(builder)?;
+                    num_records = 0;
+                }
+            }
+            cursor.step_key();
+        }
+        Ok(num_records)
+    }
+
+    /// Encodes the changes to an indexed view.
+    fn encode_indexed(
+        &mut self,
+        batch: &dyn SerBatchReader,
+        key_schema: &Relation,
+        builder: &mut ArrayBuilder,
+        micros: i64,
+    ) -> AnyResult<usize> {
+        let mut num_records = 0;
+        let mut cursor =
+            batch.cursor(RecordFormat::Parquet(default_arrow_serde_config().clone()))?;
+        while cursor.key_valid() {
+            if let Some(op) =
+                indexed_operation_type(self.view_name(), &key_schema.name, cursor.as_mut())?
+            {
+                cursor.rewind_vals();
+                let op = match op {
+                    IndexedOperationType::Insert => "i",
+                    IndexedOperationType::Delete => "d",
+                    IndexedOperationType::Upsert => {
+                        if cursor.weight() < 0 {
+                            cursor.step_val();
+                        }
+                        "u"
+                    }
+                };
+                cursor.serialize_val_to_arrow_with_metadata(&Meta::new(op, micros), builder)?;
+                num_records += 1;
+                if num_records >= self.max_batch_records {
+                    self.send(builder)?;
+                    num_records = 0;
+                }
+            }
+            cursor.step_key();
+        }
+        Ok(num_records)
+    }
+}
+
+impl OutputConsumer for FlightOutputEndpoint {
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn batch_start(&mut self, _step: Step) {
+        self.num_records = 0;
+        self.num_bytes = 0;
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8], _num_records: usize) {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+        _num_records: usize,
+    ) {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) {
+        let _guard = self.span();
+        let result = self.finish_put();
+        let Some(controller) = self.controller.upgrade() else {
+            return;
+        };
+        match result {
+            Ok(()) => {
+                debug!(
+                    "arrow_flight {}: sent {} records",
+                    self.endpoint_name, self.num_records
+                );
+                controller
+                    .status
+                    .output_buffer(self.endpoint_id, self.num_bytes, self.num_records);
+            }
+            Err(e) => controller.output_transport_error(
+                self.endpoint_id,
+                &self.endpoint_name,
+                false,
+                e,
+                Some("flight_batch_end"),
+            ),
+        }
+    }
+}
+
+impl Encoder for FlightOutputEndpoint {
+    fn consumer(&mut self) -> &mut dyn OutputConsumer {
+        self
+    }
+
+    fn encode(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let _guard = self.span();
+        let micros = Utc::now().timestamp_micros();
+        let mut builder = ArrayBuilder::new(self.serde_arrow_schema.clone())?;
+        let num_records = match self.key_schema.take() {
+            Some(key_schema) => {
+                let result = self.encode_indexed(batch, &key_schema, &mut builder, micros);
+                self.key_schema = Some(key_schema);
+                result?
+            }
+            None => self.encode_records(batch, &mut builder, micros)?,
+        };
+        if num_records > 0 {
+            self.send(&mut builder)?;
+        }
+        Ok(())
+    }
+}
+
+impl OutputEndpoint for FlightOutputEndpoint {
+    fn connect(&mut self, _async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        todo!()
+    }
+
+    fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8]) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
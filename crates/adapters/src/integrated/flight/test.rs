@@ -0,0 +1,464 @@
+use crate::test::{file_to_zset, test_circuit, wait, TestStruct};
+use crate::Controller;
+use arrow::array::{AsArray, BooleanArray, Int64Array, RecordBatch, StringArray};
+use arrow::datatypes::Int64Type;
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::utils::flight_data_to_batches;
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
+};
+use dbsp::circuit::tokio::TOKIO;
+use dbsp::utils::Tup2;
+use dbsp::OrdZSet;
+use feldera_types::config::PipelineConfig;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tempfile::NamedTempFile;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+/// Token that the test server requires clients to present.
+const AUTH_TOKEN: &str = "secret";
+
+/// A `DoPut` call received by the test server.
+struct Put {
+    path: Vec<String>,
+    batches: Vec<RecordBatch>,
+}
+
+#[derive(Default)]
+struct ServerState {
+    puts: Vec<Put>,
+
+    /// Record batches returned by `DoGet`, indexed by ticket.
+    tickets: HashMap<Vec<u8>, Vec<RecordBatch>>,
+}
+
+/// Flight server that records the data sent to it with `DoPut` and serves
+/// preconfigured record batches with `DoGet`.
+struct TestFlightServer {
+    state: Arc<Mutex<ServerState>>,
+}
+
+impl TestFlightServer {
+    fn authenticate<T>(request: &Request<T>) -> Result<(), Status> {
+        match request.metadata().get("authorization") {
+            Some(value) if value == format!("Bearer {AUTH_TOKEN}").as_str() => Ok(()),
+            _ => Err(Status::unauthenticated("invalid token")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl FlightService for TestFlightServer {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info"))
+    }
+
+    async fn poll_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<PollInfo>, Status> {
+        Err(Status::unimplemented("poll_flight_info"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("get_schema"))
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        Self::authenticate(&request)?;
+        let ticket = request.into_inner().ticket;
+        let batches = self
+            .state
+            .lock()
+            .unwrap()
+            .tickets
+            .get(ticket.as_ref())
+            .cloned()
+            .ok_or_else(|| Status::not_found("unknown ticket"))?;
+        Ok(Response::new(
+            FlightDataEncoderBuilder::new()
+                .build(stream::iter(batches.into_iter().map(Ok)))
+                .map_err(Status::from)
+                .boxed(),
+        ))
+    }
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Self::authenticate(&request)?;
+        let flight_data: Vec<FlightData> = request.into_inner().try_collect().await?;
+        let path = flight_data
+            .first()
+            .and_then(|data| data.flight_descriptor.as_ref())
+            .map(|descriptor| descriptor.path.clone())
+            .unwrap_or_default();
+        let batches = flight_data_to_batches(&flight_data)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        self.state.lock().unwrap().puts.push(Put { path, batches });
+        Ok(Response::new(
+            stream::iter([Ok(PutResult::default())]).boxed(),
+        ))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("list_actions"))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("do_exchange"))
+    }
+}
+
+/// Starts a Flight server and returns its address.
+fn start_server() -> (SocketAddr, Arc<Mutex<ServerState>>) {
+    let state = Arc::new(Mutex::new(ServerState::default()));
+    let listener = TOKIO.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+    let addr = listener.local_addr().unwrap();
+    TOKIO.spawn(
+        Server::builder()
+            .add_service(FlightServiceServer::new(TestFlightServer {
+                state: state.clone(),
+            }))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    (addr, state)
+}
+
+/// Runs a pipeline that writes `data` to an Arrow Flight output connector
+/// with transport configuration `config`, and returns the errors reported by
+/// the pipeline.
+fn flight_output_pipeline(
+    data: &[TestStruct],
+    config: serde_json::Value,
+    wait_for: impl Fn(&[String]) -> bool,
+) -> Vec<String> {
+    let mut input_file = NamedTempFile::new().unwrap();
+    for record in data {
+        writeln!(input_file, "{}", serde_json::to_string(record).unwrap()).unwrap();
+    }
+    input_file.flush().unwrap();
+
+    let config: PipelineConfig = serde_json::from_value(json!({
+        "name": "test",
+        "workers": 2,
+        "inputs": {
+            "test_input1": {
+                "stream": "test_input1",
+                "transport": {
+                    "name": "file_input",
+                    "config": {
+                        "path": input_file.path()
+                    }
+                },
+                "format": {
+                    "name": "json",
+                    "config": {
+                        "update_format": "raw"
+                    }
+                }
+            }
+        },
+        "outputs": {
+            "test_output1": {
+                "stream": "test_output1",
+                "transport": {
+                    "name": "arrow_flight_output",
+                    "config": config
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let controller = Controller::with_config(
+        |workers| {
+            Ok(test_circuit::<TestStruct>(
+                workers,
+                &TestStruct::schema(),
+                &[None],
+            ))
+        },
+        &config,
+        Box::new({
+            let errors = errors.clone();
+            move |e, _| errors.lock().unwrap().push(e.to_string())
+        }),
+    )
+    .unwrap();
+    controller.start();
+    wait(
+        || controller.status().pipeline_complete() && wait_for(&errors.lock().unwrap()),
+        10_000,
+    )
+    .unwrap();
+    controller.stop().unwrap();
+
+    let errors = errors.lock().unwrap().clone();
+    errors
+}
+
+fn test_data() -> Vec<TestStruct> {
+    (0..5)
+        .map(|id| TestStruct {
+            id,
+            b: id % 2 == 0,
+            i: if id % 2 == 0 {
+                Some(id as i64 * 10)
+            } else {
+                None
+            },
+            s: format!("record {id}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_flight_output() {
+    let (addr, state) = start_server();
+    let errors = flight_output_pipeline(
+        &test_data(),
+        json!({
+            "uri": format!("grpc://{addr}"),
+            "auth_token": AUTH_TOKEN,
+            "max_batch_records": 2
+        }),
+        |_| true,
+    );
+    assert_eq!(errors, Vec::<String>::new());
+
+    let state = state.lock().unwrap();
+    let mut ids = Vec::new();
+    for put in &state.puts {
+        // The descriptor path defaults to the name of the view.
+        assert_eq!(put.path, vec!["test_output1".to_string()]);
+        for batch in &put.batches {
+            assert!(batch.num_rows() <= 2);
+            let schema = batch.schema();
+            let names = schema
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["id", "b", "i", "s", "__feldera_op", "__feldera_ts"]);
+
+            ids.extend(
+                batch
+                    .column_by_name("id")
+                    .unwrap()
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .iter()
+                    .copied(),
+            );
+            let ops = batch
+                .column_by_name("__feldera_op")
+                .unwrap()
+                .as_string::<i32>();
+            assert!(ops.iter().all(|op| op == Some("i")));
+        }
+    }
+    ids.sort();
+    assert_eq!(ids, [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_flight_output_error() {
+    // The server rejects the token, which the connector reports as an error.
+    let (addr, state) = start_server();
+    let errors = flight_output_pipeline(
+        &test_data(),
+        json!({
+            "uri": format!("grpc://{addr}"),
+            "auth_token": "wrong",
+            "path": ["analytics", "records"]
+        }),
+        |errors| !errors.is_empty(),
+    );
+    assert!(errors[0].contains("DoPut"), "{errors:?}");
+    assert!(state.lock().unwrap().puts.is_empty());
+}
+
+/// Runs a pipeline that reads from an Arrow Flight input connector with
+/// transport configuration `config` until the input is exhausted or the
+/// pipeline reports an error.  Returns the errors and the contents of the
+/// output view.
+fn flight_input_pipeline(config: serde_json::Value) -> (Vec<String>, OrdZSet<TestStruct>) {
+    let output_file = NamedTempFile::new().unwrap();
+
+    let config: PipelineConfig = serde_json::from_value(json!({
+        "name": "test",
+        "workers": 2,
+        "inputs": {
+            "test_input1": {
+                "stream": "test_input1",
+                "transport": {
+                    "name": "arrow_flight_input",
+                    "config": config
+                }
+            }
+        },
+        "outputs": {
+            "test_output1": {
+                "stream": "test_output1",
+                "transport": {
+                    "name": "file_output",
+                    "config": {
+                        "path": output_file.path()
+                    }
+                },
+                "format": {
+                    "name": "json",
+                    "config": {
+                        "update_format": "insert_delete"
+                    }
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let controller = Controller::with_config(
+        |workers| {
+            Ok(test_circuit::<TestStruct>(
+                workers,
+                &TestStruct::schema(),
+                &[None],
+            ))
+        },
+        &config,
+        Box::new({
+            let errors = errors.clone();
+            move |e, _| errors.lock().unwrap().push(e.to_string())
+        }),
+    )
+    .unwrap();
+    controller.start();
+    wait(
+        || controller.status().pipeline_complete() || !errors.lock().unwrap().is_empty(),
+        10_000,
+    )
+    .unwrap();
+    controller.stop().unwrap();
+
+    let errors = errors.lock().unwrap().clone();
+    let zset = file_to_zset::<TestStruct>(&mut File::open(output_file.path()).unwrap());
+    (errors, zset)
+}
+
+/// Converts `records` into a record batch with the schema of `TestStruct`.
+fn record_batch(records: &[TestStruct]) -> RecordBatch {
+    RecordBatch::try_new(
+        TestStruct::arrow_schema(),
+        vec![
+            Arc::new(Int64Array::from_iter_values(
+                records.iter().map(|record| record.id as i64),
+            )),
+            Arc::new(BooleanArray::from_iter(
+                records.iter().map(|record| Some(record.b)),
+            )),
+            Arc::new(Int64Array::from_iter(records.iter().map(|record| record.i))),
+            Arc::new(StringArray::from_iter_values(
+                records.iter().map(|record| record.s.as_str()),
+            )),
+        ],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_flight_input() {
+    let (addr, state) = start_server();
+    let data = test_data();
+    state.lock().unwrap().tickets.insert(
+        b"records".to_vec(),
+        vec![record_batch(&data[0..2]), record_batch(&data[2..])],
+    );
+
+    let (errors, zset) = flight_input_pipeline(json!({
+        "uri": format!("grpc://{addr}"),
+        "ticket": "records",
+        "auth_token": AUTH_TOKEN
+    }));
+    assert_eq!(errors, Vec::<String>::new());
+
+    let expected = OrdZSet::from_tuples(
+        (),
+        data.into_iter()
+            .map(|record| Tup2(Tup2(record, ()), 1))
+            .collect(),
+    );
+    assert_eq!(zset, expected);
+}
+
+#[test]
+fn test_flight_input_error() {
+    // The server doesn't know the ticket, which fails the connector.
+    let (addr, _state) = start_server();
+    let (errors, zset) = flight_input_pipeline(json!({
+        "uri": format!("grpc://{addr}"),
+        "ticket": "missing",
+        "auth_token": AUTH_TOKEN
+    }));
+    assert!(errors[0].contains("DoGet"), "{errors:?}");
+    assert_eq!(zset, OrdZSet::empty());
+}
@@ -0,0 +1,104 @@
+//! Google Cloud Storage output connector.
+//!
+//! Writes partitioned files with [`PartitionedOutputEndpoint`], using
+//! multipart uploads for files that don't fit in a single part.
+
+use crate::controller::{ControllerInner, EndpointId};
+use crate::integrated::partitioned_output::{
+    ObjectSink, PartitionedOutputConfig, PartitionedOutputEndpoint,
+};
+use crate::transport::gcs::gcs_object_store;
+use crate::transport::object_input::object_path;
+use crate::ControllerError;
+use anyhow::Result as AnyResult;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::gcs::GcsOutputConfig;
+use object_store::gcp::GoogleCloudStorage;
+use object_store::{MultipartUpload, ObjectStore, PutPayload};
+use std::sync::Weak;
+
+/// Integrated output connector that writes a view to files in a GCS bucket.
+pub(crate) type GcsOutputEndpoint = PartitionedOutputEndpoint<GcsSink>;
+
+impl GcsOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &GcsOutputConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let store = gcs_object_store(
+            &config.bucket_name,
+            config.service_account_key.as_deref(),
+            config.service_account_path.as_deref(),
+        )
+        .map_err(|e| {
+            ControllerError::invalid_transport_configuration(endpoint_name, &e.to_string())
+        })?;
+
+        Self::with_sink(
+            endpoint_id,
+            endpoint_name,
+            PartitionedOutputConfig {
+                prefix: &config.prefix,
+                partition_by: &config.partition_by,
+                format: config.format,
+                max_file_size_bytes: config.max_file_size_bytes,
+                rollover_interval_secs: config.rollover_interval_secs,
+            },
+            key_schema,
+            value_schema,
+            controller,
+            GcsSink {
+                store,
+                bucket: config.bucket_name.clone(),
+            },
+        )
+    }
+}
+
+/// Writes objects to a GCS bucket.
+pub(crate) struct GcsSink {
+    store: GoogleCloudStorage,
+    bucket: String,
+}
+
+impl ObjectSink for GcsSink {
+    type Upload = Box<dyn MultipartUpload>;
+
+    fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> AnyResult<()> {
+        self.store
+            .put(&object_path(key)?, PutPayload::from(data))
+            .await?;
+        Ok(())
+    }
+
+    async fn start_upload(&self, key: &str) -> AnyResult<Self::Upload> {
+        Ok(self.store.put_multipart(&object_path(key)?).await?)
+    }
+
+    async fn upload_part(&self, upload: &mut Self::Upload, data: Vec<u8>) -> AnyResult<()> {
+        upload.put_part(PutPayload::from(data)).await?;
+        Ok(())
+    }
+
+    async fn complete_upload(&self, mut upload: Self::Upload) -> AnyResult<()> {
+        upload.complete().await?;
+        Ok(())
+    }
+
+    async fn abort_upload(&self, mut upload: Self::Upload) -> AnyResult<()> {
+        upload.abort().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,410 @@
+use crate::catalog::{CursorWithPolarity, SerBatchReader};
+use crate::controller::{ControllerInner, EndpointId};
+use crate::format::parquet::relation_to_arrow_fields;
+use crate::format::MAX_DUPLICATES;
+use crate::transport::Step;
+use crate::util::{indexed_operation_type, IndexedOperationType};
+use crate::{
+    AsyncErrorCallback, ControllerError, Encoder, OutputConsumer, OutputEndpoint, RecordFormat,
+    SerCursor,
+};
+use anyhow::{anyhow, bail, Error as AnyError, Result as AnyResult};
+use arrow::array::RecordBatch;
+use arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_iceberg::{iceberg_output_serde_config, IcebergTableWriter};
+use feldera_types::program_schema::{Relation, SqlIdentifier};
+use feldera_types::transport::iceberg::IcebergWriterConfig;
+use serde::Serialize;
+use serde_arrow::schema::SerdeArrowSchema;
+use serde_arrow::ArrayBuilder;
+use std::sync::{Arc, Weak};
+use std::thread;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::trace;
+
+/// Columns that record the type and time of each change, if the table has
+/// them.
+const META_COLUMNS: [&str; 2] = ["__feldera_op", "__feldera_ts"];
+
+/// Limit on the number of records buffered in memory in the encoder.
+static CHUNK_SIZE: usize = 100_000;
+
+/// Commands sent to the tokio runtime that writes to the table.
+enum Command {
+    Insert(RecordBatch),
+    BatchEnd,
+}
+
+struct IcebergOutputInner {
+    endpoint_id: EndpointId,
+    endpoint_name: String,
+    config: IcebergWriterConfig,
+    serde_arrow_schema: SerdeArrowSchema,
+    arrow_schema: Arc<ArrowSchema>,
+    key_schema: Option<Relation>,
+    value_schema: Relation,
+    controller: Weak<ControllerInner>,
+}
+
+/// Integrated output connector that appends the changes to a view to an
+/// Iceberg table, one snapshot per step.
+pub struct IcebergOutputEndpoint {
+    inner: Arc<IcebergOutputInner>,
+    command_sender: Sender<Command>,
+    response_receiver: Receiver<Result<(), (AnyError, bool)>>,
+
+    /// Whether the table has an `__feldera_op` column, without which
+    /// deletions can't be recorded.
+    has_op_column: bool,
+}
+
+impl IcebergOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &IcebergWriterConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let mut arrow_fields = relation_to_arrow_fields(&value_schema.fields, false);
+        arrow_fields.push(ArrowField::new(META_COLUMNS[0], ArrowDataType::Utf8, true));
+        arrow_fields.push(ArrowField::new(META_COLUMNS[1], ArrowDataType::Int64, true));
+
+        let serde_arrow_schema =
+            SerdeArrowSchema::try_from(arrow_fields.as_slice()).map_err(|e| {
+                ControllerError::SchemaParseError {
+                    error: format!("Unable to convert schema to parquet/arrow: {e}"),
+                }
+            })?;
+
+        let inner = Arc::new(IcebergOutputInner {
+            endpoint_id,
+            endpoint_name: endpoint_name.to_string(),
+            config: config.clone(),
+            serde_arrow_schema,
+            arrow_schema: Arc::new(ArrowSchema::new(arrow_fields)),
+            key_schema: key_schema.clone(),
+            value_schema: value_schema.clone(),
+            controller,
+        });
+        let inner_clone = inner.clone();
+
+        let (init_sender, mut init_receiver) = channel::<AnyResult<bool>>(1);
+        let (command_sender, command_receiver) = channel::<Command>(1);
+        let (response_sender, response_receiver) = channel::<Result<(), (AnyError, bool)>>(1);
+
+        thread::Builder::new()
+            .name(format!("{endpoint_name}-iceberg-output-tokio-wrapper"))
+            .spawn(move || {
+                TOKIO.block_on(Self::worker_task(
+                    inner_clone,
+                    init_sender,
+                    command_receiver,
+                    response_sender,
+                ))
+            })
+            .expect("failed to spawn output iceberg connector tokio wrapper thread");
+
+        let has_op_column = init_receiver
+            .blocking_recv()
+            .ok_or_else(|| {
+                ControllerError::output_transport_error(
+                    endpoint_name,
+                    true,
+                    anyhow!("worker thread terminated unexpectedly during initialization"),
+                )
+            })?
+            .map_err(|e| ControllerError::output_transport_error(endpoint_name, true, e))?;
+
+        Ok(Self {
+            inner,
+            command_sender,
+            response_receiver,
+            has_op_column,
+        })
+    }
+
+    fn view_name(&self) -> &SqlIdentifier {
+        &self.inner.value_schema.name
+    }
+
+    fn command(&mut self, command: Command) -> Result<(), (AnyError, bool)> {
+        self.command_sender
+            .blocking_send(command)
+            .map_err(|_| (anyhow!("worker thread terminated unexpectedly"), true))?;
+        self.response_receiver
+            .blocking_recv()
+            .ok_or_else(|| (anyhow!("worker thread terminated unexpectedly"), true))?
+    }
+
+    fn insert_record_batch(&mut self, builder: &mut ArrayBuilder) -> AnyResult<()> {
+        let batch = builder
+            .to_record_batch()
+            .map_err(|e| anyhow!("error generating arrow arrays: {e}"))?;
+        self.command(Command::Insert(batch))
+            .map_err(|(e, _fatal)| e)
+    }
+
+    /// Fails if `op` can't be recorded in the table.
+    fn check_op(&self, op: &str) -> AnyResult<()> {
+        if op != "i" && !self.has_op_column {
+            bail!(
+                "view '{}' deleted or updated a record, but Iceberg table '{}' has no '__feldera_op' column to record the change",
+                self.view_name(),
+                &self.inner.config.table_name
+            );
+        }
+        Ok(())
+    }
+
+    async fn worker_task(
+        inner: Arc<IcebergOutputInner>,
+        init_sender: Sender<AnyResult<bool>>,
+        mut command_receiver: Receiver<Command>,
+        response_sender: Sender<Result<(), (AnyError, bool)>>,
+    ) {
+        let mut writer = match IcebergTableWriter::open(
+            &inner.config,
+            inner.arrow_schema.clone(),
+            &META_COLUMNS,
+        )
+        .await
+        {
+            Ok(writer) => {
+                let _ = init_sender
+                    .send(Ok(writer.has_column(META_COLUMNS[0])))
+                    .await;
+                writer
+            }
+            Err(e) => {
+                let _ = init_sender.send(Err(e)).await;
+                return;
+            }
+        };
+
+        loop {
+            let result = match command_receiver.recv().await {
+                Some(Command::Insert(batch)) => {
+                    trace!(
+                        "iceberg {}: writing {} records",
+                        &inner.endpoint_name,
+                        batch.num_rows()
+                    );
+                    writer.write(&batch).await
+                }
+                Some(Command::BatchEnd) => match writer.commit().await {
+                    Ok(Some(stats)) => {
+                        if let Some(controller) = inner.controller.upgrade() {
+                            controller.status.output_buffer(
+                                inner.endpoint_id,
+                                stats.num_bytes,
+                                stats.num_records,
+                            )
+                        };
+                        Ok(())
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => Err(e),
+                },
+                None => {
+                    trace!(
+                        "iceberg {}: endpoint is shutting down",
+                        &inner.endpoint_name
+                    );
+                    return;
+                }
+            };
+            // Ignore closed channel, we'll handle it at the next loop iteration.
+            let _ = response_sender.send(result.map_err(|e| (e, false))).await;
+        }
+    }
+}
+
+impl OutputConsumer for IcebergOutputEndpoint {
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn batch_start(&mut self, _step: Step) {}
+
+    fn push_buffer(&mut self, _buffer: &[u8], _num_records: usize) {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+        _num_records: usize,
+    ) {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) {
+        self.command(Command::BatchEnd)
+            .unwrap_or_else(|(e, fatal)| {
+                if let Some(controller) = self.inner.controller.upgrade() {
+                    controller.output_transport_error(
+                        self.inner.endpoint_id,
+                        &self.inner.endpoint_name,
+                        fatal,
+                        e,
+                        Some("iceberg_batch_end"),
+                    )
+                };
+            });
+    }
+}
+
+/// Metadata added to each record, representing the type and order of operations.
+#[derive(Serialize)]
+struct Meta<'a> {
+    /// `i` for insert, `d` for delete, `u` for update.
+    __feldera_op: &'a str,
+
+    /// Timestamp in microseconds since UNIX epoch when the batch of updates
+    /// was output by the pipeline.
+    __feldera_ts: i64,
+}
+
+impl<'a> Meta<'a> {
+    fn new(op: &'a str, ts: i64) -> Self {
+        Meta {
+            __feldera_op: op,
+            __feldera_ts: ts,
+        }
+    }
+}
+
+impl Encoder for IcebergOutputEndpoint {
+    fn consumer(&mut self) -> &mut dyn OutputConsumer {
+        self
+    }
+
+    fn encode(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let micros = Utc::now().timestamp_micros();
+        let mut builder = ArrayBuilder::new(self.inner.serde_arrow_schema.clone())?;
+        let mut num_records = 0;
+
+        let index_name = &self.inner.key_schema.as_ref().map(|s| s.name.to_owned());
+
+        if let Some(index_name) = &index_name {
+            let mut cursor =
+                batch.cursor(RecordFormat::Parquet(iceberg_output_serde_config().clone()))?;
+
+            while cursor.key_valid() {
+                if let Some(op) =
+                    indexed_operation_type(self.view_name(), index_name, cursor.as_mut())?
+                {
+                    cursor.rewind_vals();
+
+                    let op = match op {
+                        IndexedOperationType::Insert => "i",
+                        IndexedOperationType::Delete => "d",
+                        IndexedOperationType::Upsert => {
+                            if cursor.weight() < 0 {
+                                cursor.step_val();
+                            }
+                            "u"
+                        }
+                    };
+                    self.check_op(op)?;
+                    assert!(cursor.val_valid());
+                    cursor.serialize_val_to_arrow_with_metadata(
+                        &Meta::new(op, micros),
+                        &mut builder,
+                    )?;
+
+                    num_records += 1;
+                    if num_records >= CHUNK_SIZE {
+                        self.insert_record_batch(&mut builder)?;
+                        num_records = 0;
+                    }
+                };
+
+                cursor.step_key();
+            }
+        } else {
+            let mut cursor = CursorWithPolarity::new(
+                batch.cursor(RecordFormat::Parquet(iceberg_output_serde_config().clone()))?,
+            );
+            while cursor.key_valid() {
+                if !cursor.val_valid() {
+                    cursor.step_key();
+                    continue;
+                }
+
+                let mut w = cursor.weight();
+                if !(-MAX_DUPLICATES..=MAX_DUPLICATES).contains(&w) {
+                    bail!("Unable to output record with very large weight {w}. Consider adjusting your SQL queries to avoid duplicate output records, e.g., using 'SELECT DISTINCT'.");
+                }
+
+                while w != 0 {
+                    let op = if w > 0 { "i" } else { "d" };
+                    self.check_op(op)?;
+                    cursor.serialize_key_to_arrow_with_metadata(
+                        &Meta::new(op, micros),
+                        &mut builder,
+                    )?;
+                    w -= w.signum();
+
+                    num_records += 1;
+                    if num_records >= CHUNK_SIZE {
+                        self.insert_record_batch(&mut builder)?;
+                        num_records = 0;
+                    }
+                }
+                cursor.step_key();
+            }
+        }
+
+        if num_records > 0 {
+            self.insert_record_batch(&mut builder)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl OutputEndpoint for IcebergOutputEndpoint {
+    fn connect(&mut self, _async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        todo!()
+    }
+
+    fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8]) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
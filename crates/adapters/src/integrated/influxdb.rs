@@ -0,0 +1,360 @@
+//! InfluxDB output connector.
+//!
+//! The connector encodes the records added to a view as points in line
+//! protocol and writes them to the InfluxDB v2 write API.
+
+mod line_protocol;
+
+use crate::catalog::{CursorWithPolarity, RecordFormat, SerBatchReader};
+use crate::controller::{ControllerInner, EndpointId};
+use crate::format::{Encoder, OutputConsumer, MAX_DUPLICATES};
+use crate::transport::OutputEndpoint;
+use crate::util::{indexed_operation_type, IndexedOperationType};
+use crate::ControllerError;
+use anyhow::{anyhow, bail, Result as AnyResult};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{AsyncErrorCallback, Step};
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::influxdb::InfluxDbOutputConfig;
+use line_protocol::PointEncoder;
+use reqwest::{Client, StatusCode, Url};
+use std::sync::Weak;
+use std::time::Duration;
+use tracing::{info_span, span::EnteredSpan, warn};
+
+/// Default for [`InfluxDbOutputConfig::max_points_per_request`].
+const DEFAULT_MAX_POINTS_PER_REQUEST: usize = 5000;
+
+/// Default for [`InfluxDbOutputConfig::max_retries`].
+const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Timeout for a single write request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Integrated output connector that writes a view to an InfluxDB bucket.
+pub struct InfluxDbOutputEndpoint {
+    endpoint_id: EndpointId,
+    endpoint_name: String,
+    config: InfluxDbOutputConfig,
+    controller: Weak<ControllerInner>,
+    client: Client,
+
+    /// URL of the write API, including the query parameters.
+    write_url: Url,
+
+    encoder: PointEncoder,
+    value_schema: Relation,
+    key_schema: Option<Relation>,
+    max_points: usize,
+
+    /// Points that haven't been written yet, in line protocol.
+    points: String,
+    num_points: usize,
+}
+
+impl InfluxDbOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &InfluxDbOutputConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let encoder = PointEncoder::new(value_schema, config).map_err(|e| {
+            ControllerError::invalid_transport_configuration(endpoint_name, &e.to_string())
+        })?;
+
+        let mut write_url = Url::parse(&config.url)
+            .and_then(|url| url.join("api/v2/write"))
+            .map_err(|e| {
+                ControllerError::invalid_transport_configuration(
+                    endpoint_name,
+                    &format!("invalid InfluxDB URL '{}': {e}", config.url),
+                )
+            })?;
+        write_url
+            .query_pairs_mut()
+            .append_pair("org", &config.org)
+            .append_pair("bucket", &config.bucket)
+            .append_pair("precision", config.precision.as_str());
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| {
+                ControllerError::output_transport_error(
+                    endpoint_name,
+                    true,
+                    anyhow!("error creating HTTP client: {e}"),
+                )
+            })?;
+
+        Ok(Self {
+            endpoint_id,
+            endpoint_name: endpoint_name.to_owned(),
+            config: config.clone(),
+            controller,
+            client,
+            write_url,
+            encoder,
+            value_schema: value_schema.to_owned(),
+            key_schema: key_schema.to_owned(),
+            max_points: config
+                .max_points_per_request
+                .unwrap_or(DEFAULT_MAX_POINTS_PER_REQUEST),
+            points: String::new(),
+            num_points: 0,
+        })
+    }
+
+    fn span(&self) -> EnteredSpan {
+        info_span!(
+            "influxdb_output",
+            id = self.endpoint_id,
+            name = self.endpoint_name,
+            bucket = self.config.bucket,
+        )
+        .entered()
+    }
+
+    /// Sends a single write request with the buffered points.
+    async fn send(&self) -> Result<(), (bool, anyhow::Error)> {
+        let mut request = self
+            .client
+            .post(self.write_url.clone())
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(self.points.clone());
+        if let Some(token) = &self.config.token {
+            request = request.header("Authorization", format!("Token {token}"));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| (true, anyhow!("error sending write request: {e}")))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let transient =
+            status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE;
+        let body = response.text().await.unwrap_or_default();
+        Err((
+            transient,
+            anyhow!("write request failed with status {status}: {body}"),
+        ))
+    }
+
+    /// Writes the buffered points, retrying if the server is unavailable.
+    fn flush(&mut self) -> AnyResult<()> {
+        if self.num_points == 0 {
+            return Ok(());
+        }
+
+        let max_retries = self.config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let mut retries = 0;
+        loop {
+            match TOKIO.block_on(self.send()) {
+                Ok(()) => break,
+                Err((true, e)) if retries < max_retries => {
+                    retries += 1;
+                    warn!("influxdb: retrying write ({retries}/{max_retries}) after error: {e}");
+                    std::thread::sleep(
+                        Duration::from_millis(100 << retries.min(6)).min(Duration::from_secs(5)),
+                    );
+                }
+                Err((_, e)) => {
+                    return Err(anyhow!(
+                        "influxdb: failed to write {} points to bucket '{}': {e}",
+                        self.num_points,
+                        self.config.bucket
+                    ))
+                }
+            }
+        }
+
+        if let Some(controller) = self.controller.upgrade() {
+            controller
+                .status
+                .output_buffer(self.endpoint_id, self.points.len(), self.num_points);
+        }
+        self.points.clear();
+        self.num_points = 0;
+        Ok(())
+    }
+
+    /// Adds the point for JSON `record` to the buffer, writing the buffer if
+    /// it is full.
+    fn push_point(&mut self, record: &[u8]) -> AnyResult<()> {
+        if self.encoder.encode(record, &mut self.points)? {
+            self.num_points += 1;
+            if self.num_points >= self.max_points {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the records added to a view without a unique key.
+    fn encode_records(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let mut cursor =
+            CursorWithPolarity::new(batch.cursor(RecordFormat::Json(JsonFlavor::Postgres))?);
+        let mut buf = Vec::new();
+        while cursor.key_valid() {
+            if !cursor.val_valid() {
+                cursor.step_key();
+                continue;
+            }
+            let w = cursor.weight();
+            if w > 0 {
+                buf.clear();
+                cursor.serialize_key(&mut buf)?;
+                if w > MAX_DUPLICATES {
+                    bail!(
+                        "Unable to output record '{}' with very large weight {w}. Consider adjusting your SQL queries to avoid duplicate output records, e.g., using 'SELECT DISTINCT'.",
+                        String::from_utf8_lossy(&buf)
+                    );
+                }
+                for _ in 0..w {
+                    self.push_point(&buf)?;
+                }
+            }
+            cursor.step_key();
+        }
+        Ok(())
+    }
+
+    /// Encodes the records inserted or updated in an indexed view.
+    fn encode_indexed(
+        &mut self,
+        batch: &dyn SerBatchReader,
+        key_schema: &Relation,
+    ) -> AnyResult<()> {
+        let mut cursor = batch.cursor(RecordFormat::Json(JsonFlavor::Postgres))?;
+        let mut buf = Vec::new();
+        while cursor.key_valid() {
+            if let Some(op) =
+                indexed_operation_type(&self.value_schema.name, &key_schema.name, cursor.as_mut())?
+            {
+                cursor.rewind_vals();
+                match op {
+                    IndexedOperationType::Insert | IndexedOperationType::Upsert => {
+                        if cursor.weight() < 0 {
+                            cursor.step_val();
+                        }
+                        buf.clear();
+                        cursor.serialize_val(&mut buf)?;
+                        self.push_point(&buf)?;
+                    }
+                    // Points can't be deleted through the write API.
+                    IndexedOperationType::Delete => (),
+                }
+            }
+            cursor.step_key();
+        }
+        Ok(())
+    }
+}
+
+impl OutputConsumer for InfluxDbOutputEndpoint {
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn batch_start(&mut self, _step: Step) {}
+
+    fn push_buffer(&mut self, _: &[u8], _: usize) {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _: Option<&[u8]>,
+        _: Option<&[u8]>,
+        _: &[(&str, Option<&[u8]>)],
+        _num_records: usize,
+    ) {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) {
+        let _guard = self.span();
+        let result = self.flush();
+        self.points.clear();
+        self.num_points = 0;
+
+        let Some(controller) = self.controller.upgrade() else {
+            warn!("controller is shutting down: aborting");
+            return;
+        };
+        if let Err(e) = result {
+            controller.output_transport_error(
+                self.endpoint_id,
+                &self.endpoint_name,
+                true,
+                e,
+                Some("influxdb_batch_end"),
+            );
+        }
+    }
+}
+
+impl Encoder for InfluxDbOutputEndpoint {
+    fn consumer(&mut self) -> &mut dyn OutputConsumer {
+        self
+    }
+
+    fn encode(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let _guard = self.span();
+        match self.key_schema.take() {
+            Some(key_schema) => {
+                let result = self.encode_indexed(batch, &key_schema);
+                self.key_schema = Some(key_schema);
+                result
+            }
+            None => self.encode_records(batch),
+        }
+    }
+}
+
+impl OutputEndpoint for InfluxDbOutputEndpoint {
+    fn connect(&mut self, _: AsyncErrorCallback) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        todo!()
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8]) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+
+    fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        todo!()
+    }
+}
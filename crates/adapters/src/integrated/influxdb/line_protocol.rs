@@ -0,0 +1,386 @@
+//! Encoding of JSON records as InfluxDB line protocol.
+//!
+//! See <https://docs.influxdata.com/influxdb/v2/reference/syntax/line-protocol/>.
+
+use anyhow::{anyhow, bail, Result as AnyResult};
+use chrono::NaiveDateTime;
+use feldera_types::program_schema::{Relation, SqlType};
+use feldera_types::transport::influxdb::{InfluxDbOutputConfig, InfluxDbPrecision};
+use serde_json::{Map, Value as JsonValue};
+use std::fmt::Write;
+
+/// How a field value is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Integer,
+    Unsigned,
+    Float,
+    Boolean,
+    String,
+}
+
+impl FieldKind {
+    fn from_sql_type(typ: SqlType) -> Self {
+        match typ {
+            SqlType::TinyInt | SqlType::SmallInt | SqlType::Int | SqlType::BigInt => Self::Integer,
+            SqlType::UTinyInt | SqlType::USmallInt | SqlType::UInt | SqlType::UBigInt => {
+                Self::Unsigned
+            }
+            SqlType::Real | SqlType::Double | SqlType::Decimal => Self::Float,
+            SqlType::Boolean => Self::Boolean,
+            _ => Self::String,
+        }
+    }
+}
+
+/// A column written as a tag or a field.
+#[derive(Debug)]
+struct Column {
+    /// Name of the column in the JSON records.
+    name: String,
+
+    /// Escaped tag or field key.
+    key: String,
+
+    kind: FieldKind,
+}
+
+/// How the timestamp column is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimestampKind {
+    /// A `TIMESTAMP` column.
+    Timestamp,
+
+    /// An integer column that already holds the timestamp in the requested
+    /// precision.
+    Integer,
+}
+
+/// Encodes records of a view as points.
+#[derive(Debug)]
+pub(super) struct PointEncoder {
+    /// Escaped measurement name.
+    measurement: String,
+
+    /// Tags, sorted by key, as InfluxDB recommends.
+    tags: Vec<Column>,
+    fields: Vec<Column>,
+    timestamp: Option<(String, TimestampKind)>,
+    precision: InfluxDbPrecision,
+}
+
+fn escape(s: &str, special: &[char], out: &mut String) {
+    for c in s.chars() {
+        if special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+fn escape_measurement(s: &str) -> String {
+    let mut out = String::new();
+    escape(s, &[',', ' '], &mut out);
+    out
+}
+
+fn escape_key(s: &str) -> String {
+    let mut out = String::new();
+    escape(s, &[',', '=', ' '], &mut out);
+    out
+}
+
+impl PointEncoder {
+    pub fn new(schema: &Relation, config: &InfluxDbOutputConfig) -> AnyResult<Self> {
+        let lookup = |name: &str| {
+            schema
+                .field(name)
+                .ok_or_else(|| anyhow!("view '{}' has no column '{name}'", schema.name))
+        };
+
+        let mut tags = Vec::with_capacity(config.tags.len());
+        for tag in &config.tags {
+            let field = lookup(tag)?;
+            tags.push(Column {
+                name: field.name.name(),
+                key: escape_key(&field.name.name()),
+                kind: FieldKind::String,
+            });
+        }
+        tags.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let timestamp = match &config.timestamp {
+            Some(timestamp) => {
+                let field = lookup(timestamp)?;
+                let kind = match field.columntype.typ {
+                    SqlType::Timestamp => TimestampKind::Timestamp,
+                    typ if matches!(
+                        FieldKind::from_sql_type(typ),
+                        FieldKind::Integer | FieldKind::Unsigned
+                    ) =>
+                    {
+                        TimestampKind::Integer
+                    }
+                    _ => bail!(
+                        "timestamp column '{timestamp}' must have type TIMESTAMP or an integer type"
+                    ),
+                };
+                Some((field.name.name(), kind))
+            }
+            None => None,
+        };
+
+        let field_names = match &config.fields {
+            Some(fields) => fields
+                .iter()
+                .map(|name| Ok(lookup(name)?.name.name()))
+                .collect::<AnyResult<Vec<_>>>()?,
+            None => schema
+                .fields
+                .iter()
+                .map(|field| field.name.name())
+                .filter(|name| {
+                    !tags.iter().any(|tag| &tag.name == name)
+                        && timestamp
+                            .as_ref()
+                            .is_none_or(|(timestamp, _)| timestamp != name)
+                })
+                .collect(),
+        };
+        if field_names.is_empty() {
+            bail!("InfluxDB points need at least one field, but all columns of view '{}' are tags or the timestamp", schema.name);
+        }
+        let fields = field_names
+            .into_iter()
+            .map(|name| {
+                let field = lookup(&name)?;
+                Ok(Column {
+                    key: escape_key(&name),
+                    kind: FieldKind::from_sql_type(field.columntype.typ),
+                    name,
+                })
+            })
+            .collect::<AnyResult<Vec<_>>>()?;
+
+        let measurement = config
+            .measurement
+            .clone()
+            .unwrap_or_else(|| schema.name.name());
+
+        Ok(Self {
+            measurement: escape_measurement(&measurement),
+            tags,
+            fields,
+            timestamp,
+            precision: config.precision,
+        })
+    }
+
+    /// Appends the point for JSON `record` to `out`, followed by a new-line.
+    ///
+    /// Returns false, without changing `out`, if the record has no non-null
+    /// fields, since a point needs at least one.
+    pub fn encode(&self, record: &[u8], out: &mut String) -> AnyResult<bool> {
+        let record: Map<String, JsonValue> = serde_json::from_slice(record)?;
+        let start = out.len();
+
+        out.push_str(&self.measurement);
+        for tag in &self.tags {
+            let value = match record.get(&tag.name) {
+                None | Some(JsonValue::Null) => continue,
+                Some(JsonValue::String(s)) => s.clone(),
+                Some(value) => value.to_string(),
+            };
+            // InfluxDB rejects empty tag values.
+            if value.is_empty() {
+                continue;
+            }
+            out.push(',');
+            out.push_str(&tag.key);
+            out.push('=');
+            escape(&value, &[',', '=', ' '], out);
+        }
+
+        let mut separator = ' ';
+        for field in &self.fields {
+            let value = match record.get(&field.name) {
+                None | Some(JsonValue::Null) => continue,
+                Some(value) => value,
+            };
+            let mark = out.len();
+            out.push(separator);
+            out.push_str(&field.key);
+            out.push('=');
+            if !write_field_value(field.kind, value, out) {
+                // Leave out values that line protocol can't represent, such
+                // as infinite floats.
+                out.truncate(mark);
+                continue;
+            }
+            separator = ',';
+        }
+        if separator == ' ' {
+            out.truncate(start);
+            return Ok(false);
+        }
+
+        if let Some((name, kind)) = &self.timestamp {
+            match (kind, record.get(name)) {
+                (_, None | Some(JsonValue::Null)) => (),
+                (TimestampKind::Integer, Some(JsonValue::Number(n))) => {
+                    write!(out, " {n}").unwrap();
+                }
+                (TimestampKind::Timestamp, Some(JsonValue::String(s))) => {
+                    let timestamp = NaiveDateTime::parse_from_str(s, "%F %T%.f")
+                        .map_err(|e| anyhow!("invalid timestamp '{s}': {e}"))?
+                        .and_utc();
+                    let timestamp = match self.precision {
+                        InfluxDbPrecision::Ns => timestamp
+                            .timestamp_nanos_opt()
+                            .ok_or_else(|| anyhow!("timestamp '{s}' is out of range"))?,
+                        InfluxDbPrecision::Us => timestamp.timestamp_micros(),
+                        InfluxDbPrecision::Ms => timestamp.timestamp_millis(),
+                        InfluxDbPrecision::S => timestamp.timestamp(),
+                    };
+                    write!(out, " {timestamp}").unwrap();
+                }
+                (_, Some(value)) => bail!("invalid value {value} in timestamp column '{name}'"),
+            }
+        }
+
+        out.push('\n');
+        Ok(true)
+    }
+}
+
+/// Writes `value` as a field value of `kind` to `out`.  Returns false if the
+/// value can't be represented.
+fn write_field_value(kind: FieldKind, value: &JsonValue, out: &mut String) -> bool {
+    match (kind, value) {
+        (FieldKind::Integer, JsonValue::Number(n)) if n.is_i64() => write!(out, "{n}i").unwrap(),
+        (FieldKind::Unsigned, JsonValue::Number(n)) if n.is_u64() => write!(out, "{n}u").unwrap(),
+        (FieldKind::Float, JsonValue::Number(n)) => match n.as_f64() {
+            Some(f) if f.is_finite() => write!(out, "{f}").unwrap(),
+            _ => return false,
+        },
+        // Decimals are serialized as strings.
+        (FieldKind::Float, JsonValue::String(s)) => match s.parse::<f64>() {
+            Ok(f) if f.is_finite() => write!(out, "{f}").unwrap(),
+            _ => return false,
+        },
+        (FieldKind::Boolean, JsonValue::Bool(b)) => write!(out, "{b}").unwrap(),
+        (_, value) => {
+            let s = match value {
+                JsonValue::String(s) => s.clone(),
+                value => value.to_string(),
+            };
+            out.push('"');
+            escape(&s, &['"', '\\'], out);
+            out.push('"');
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::PointEncoder;
+    use feldera_types::program_schema::{ColumnType, Field, Relation};
+    use feldera_types::transport::influxdb::{InfluxDbOutputConfig, InfluxDbPrecision};
+
+    fn schema() -> Relation {
+        Relation::new(
+            "cpu load".into(),
+            vec![
+                Field::new("host".into(), ColumnType::varchar(true)),
+                Field::new("region".into(), ColumnType::varchar(true)),
+                Field::new("usage".into(), ColumnType::double(true)),
+                Field::new("cores".into(), ColumnType::int(true)),
+                Field::new("ok".into(), ColumnType::boolean(true)),
+                Field::new("note".into(), ColumnType::varchar(true)),
+                Field::new("ts".into(), ColumnType::timestamp(false)),
+            ],
+            false,
+            Default::default(),
+        )
+    }
+
+    fn config() -> InfluxDbOutputConfig {
+        InfluxDbOutputConfig {
+            url: "http://localhost:8086".to_string(),
+            org: "org".to_string(),
+            bucket: "bucket".to_string(),
+            token: None,
+            measurement: None,
+            tags: vec!["region".to_string(), "host".to_string()],
+            fields: None,
+            timestamp: Some("ts".to_string()),
+            precision: InfluxDbPrecision::Ms,
+            max_points_per_request: None,
+            max_retries: None,
+        }
+    }
+
+    fn encode(encoder: &PointEncoder, record: &str) -> Option<String> {
+        let mut out = String::new();
+        encoder
+            .encode(record.as_bytes(), &mut out)
+            .unwrap()
+            .then_some(out)
+    }
+
+    #[test]
+    fn test_encode() {
+        let encoder = PointEncoder::new(&schema(), &config()).unwrap();
+        assert_eq!(
+            encode(
+                &encoder,
+                r#"{"host": "a b", "region": "eu,west", "usage": 0.5, "cores": 8, "ok": true, "note": "say \"hi\"", "ts": "2025-01-01 00:00:01.5"}"#
+            )
+            .unwrap(),
+            "cpu\\ load,host=a\\ b,region=eu\\,west usage=0.5,cores=8i,ok=true,note=\"say \\\"hi\\\"\" 1735689601500\n"
+        );
+
+        // Null tags and fields are left out.
+        assert_eq!(
+            encode(
+                &encoder,
+                r#"{"host": null, "region": "", "usage": 1.0, "cores": null, "ok": null, "note": null, "ts": "2025-01-01 00:00:00"}"#
+            )
+            .unwrap(),
+            "cpu\\ load usage=1 1735689600000\n"
+        );
+
+        // A point needs at least one field.
+        assert_eq!(
+            encode(
+                &encoder,
+                r#"{"host": "a", "region": null, "usage": null, "cores": null, "ok": null, "note": null, "ts": "2025-01-01 00:00:00"}"#
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let mut config = config();
+        config.fields = Some(vec!["usage".to_string()]);
+        config.measurement = Some("cpu".to_string());
+        let encoder = PointEncoder::new(&schema(), &config).unwrap();
+        assert_eq!(
+            encode(
+                &encoder,
+                r#"{"host": "a", "region": "eu", "usage": 2, "cores": 8, "ok": true, "note": "x", "ts": "2025-01-01 00:00:00"}"#
+            )
+            .unwrap(),
+            "cpu,host=a,region=eu usage=2 1735689600000\n"
+        );
+
+        config.tags.push("unknown".to_string());
+        assert!(PointEncoder::new(&schema(), &config).is_err());
+
+        let mut config = self::config();
+        config.timestamp = Some("note".to_string());
+        assert!(PointEncoder::new(&schema(), &config).is_err());
+    }
+}
@@ -0,0 +1,6 @@
+mod input;
+mod output;
+mod value;
+
+pub use input::MongoDbCdcInputEndpoint;
+pub use output::MongoDbOutputEndpoint;
@@ -0,0 +1,684 @@
+//! MongoDB change stream input connector.
+//!
+//! The connector opens a change stream on a collection, optionally reads the
+//! current contents of the collection, and then follows the change stream,
+//! recording its resume token in each checkpoint.
+
+use super::value::document_to_json;
+use crate::transport::{InputEndpoint, InputQueue, InputReaderCommand, IntegratedInputEndpoint};
+use crate::{ControllerError, InputConsumer, InputReader, PipelineState, RecordFormat};
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::catalog::{DeCollectionStream, InputCollectionHandle};
+use feldera_adapterlib::format::{BufferSize, ParseError};
+use feldera_adapterlib::transport::{parse_resume_info, Resume, Watermark};
+use feldera_types::config::FtModel;
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::mongodb::MongoDbCdcReaderConfig;
+use futures_util::TryStreamExt;
+use mongodb::bson::{self, doc, Bson, Document};
+use mongodb::change_stream::event::{ChangeStreamEvent, OperationType, ResumeToken};
+use mongodb::change_stream::ChangeStream;
+use mongodb::options::{FullDocumentBeforeChangeType, FullDocumentType};
+use mongodb::{Client, Collection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::hash::Hasher;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tracing::{debug, info};
+use xxhash_rust::xxh3::Xxh3Default;
+
+/// Delay before reopening the change stream after an error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Minimum interval between recording the resume token when the collection
+/// doesn't change.
+const POSITION_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Integrated input connector that follows the changes to a MongoDB
+/// collection.
+pub struct MongoDbCdcInputEndpoint {
+    endpoint_name: String,
+    config: MongoDbCdcReaderConfig,
+    consumer: Box<dyn InputConsumer>,
+}
+
+impl MongoDbCdcInputEndpoint {
+    pub fn new(
+        endpoint_name: &str,
+        config: &MongoDbCdcReaderConfig,
+        consumer: Box<dyn InputConsumer>,
+    ) -> Self {
+        Self {
+            endpoint_name: endpoint_name.to_string(),
+            config: config.clone(),
+            consumer,
+        }
+    }
+}
+
+impl InputEndpoint for MongoDbCdcInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::ExactlyOnce)
+    }
+}
+
+impl IntegratedInputEndpoint for MongoDbCdcInputEndpoint {
+    fn open(
+        self: Box<Self>,
+        input_handle: &InputCollectionHandle,
+        resume_info: Option<JsonValue>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(MongoDbCdcInputReader::new(
+            self.endpoint_name,
+            self.config,
+            self.consumer,
+            input_handle,
+            resume_info,
+        )?))
+    }
+}
+
+/// Resume info stored in each checkpoint for the [`MongoDbCdcInputEndpoint`].
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+struct MongoDbCdcResumeInfo {
+    /// Resume token of the change stream, as relaxed extended JSON.
+    resume_token: JsonValue,
+}
+
+impl MongoDbCdcResumeInfo {
+    fn new(resume_token: &ResumeToken) -> Self {
+        Self {
+            resume_token: bson::to_bson(resume_token)
+                .expect("resume token is BSON")
+                .into_relaxed_extjson(),
+        }
+    }
+
+    fn resume_token(&self) -> AnyResult<ResumeToken> {
+        let resume_token = Bson::try_from(self.resume_token.clone())
+            .map_err(|e| anyhow!("invalid resume token: {e}"))?;
+        bson::from_bson(resume_token).map_err(|e| anyhow!("invalid resume token: {e}"))
+    }
+}
+
+/// A change to the table, journaled so that the step can be replayed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum Change {
+    Insert(String),
+    Delete(String),
+}
+
+/// Replay data for a step.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ReplayData {
+    changes: Vec<Change>,
+}
+
+/// Auxiliary data associated with each buffer in the queue.
+struct QueuedStep {
+    resume_info: MongoDbCdcResumeInfo,
+
+    /// The changes in the buffer, only recorded if the pipeline is configured
+    /// with exactly-once fault tolerance.
+    changes: Vec<Change>,
+}
+
+struct MongoDbCdcInputReader {
+    sender: Sender<PipelineState>,
+    inner: Arc<MongoDbCdcInputEndpointInner>,
+}
+
+impl MongoDbCdcInputReader {
+    fn new(
+        endpoint_name: String,
+        config: MongoDbCdcReaderConfig,
+        consumer: Box<dyn InputConsumer>,
+        input_handle: &InputCollectionHandle,
+        resume_info: Option<JsonValue>,
+    ) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        // A step taken before the connector ingested anything has no resume
+        // info.
+        let resume_info = match resume_info {
+            Some(resume_info) if !resume_info.is_null() => {
+                let resume_info = parse_resume_info::<MongoDbCdcResumeInfo>(&resume_info)?;
+                info!("mongodb_cdc {endpoint_name}: resuming from {resume_info:?}");
+                Some(resume_info)
+            }
+            _ => None,
+        };
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+
+        // Used to communicate the status of connector initialization.
+        let (init_status_sender, mut init_status_receiver) =
+            mpsc::channel::<Result<(), ControllerError>>(1);
+
+        // Dates and binary data are converted to the Postgres flavor's
+        // formats.
+        let input_stream = input_handle
+            .handle
+            .configure_deserializer(RecordFormat::Json(JsonFlavor::Postgres))?;
+        let replay_stream = input_stream.fork();
+        let schema = input_handle.schema.clone();
+
+        let endpoint = Arc::new(MongoDbCdcInputEndpointInner {
+            queue: InputQueue::new(consumer.clone()),
+            endpoint_name,
+            config,
+            consumer,
+            last_resume_info: Mutex::new(resume_info),
+            replay_stream: Mutex::new(replay_stream),
+        });
+
+        let endpoint_clone = endpoint.clone();
+        thread::Builder::new()
+            .name(format!(
+                "{}-mongodb-cdc-input-tokio-wrapper",
+                endpoint.endpoint_name
+            ))
+            .spawn(move || {
+                TOKIO.block_on(async {
+                    endpoint_clone
+                        .worker_task(input_stream, schema, receiver, init_status_sender)
+                        .await;
+                })
+            })
+            .expect("failed to spawn MongoDB CDC input connector tokio wrapper thread");
+
+        init_status_receiver.blocking_recv().ok_or_else(|| {
+            ControllerError::input_transport_error(
+                &endpoint.endpoint_name,
+                true,
+                anyhow!("worker thread terminated unexpectedly during initialization"),
+            )
+        })??;
+
+        Ok(Self {
+            sender,
+            inner: endpoint,
+        })
+    }
+}
+
+impl InputReader for MongoDbCdcInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command {
+            InputReaderCommand::Replay { metadata, data } => self.inner.replay(metadata, data),
+            InputReaderCommand::Extend => {
+                let _ = self.sender.send_replace(PipelineState::Running);
+            }
+            InputReaderCommand::Pause => {
+                let _ = self.sender.send_replace(PipelineState::Paused);
+            }
+            InputReaderCommand::Queue { .. } => self.inner.queue(),
+            InputReaderCommand::Disconnect => {
+                let _ = self.sender.send_replace(PipelineState::Terminated);
+            }
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for MongoDbCdcInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+struct MongoDbCdcInputEndpointInner {
+    endpoint_name: String,
+    config: MongoDbCdcReaderConfig,
+    consumer: Box<dyn InputConsumer>,
+    queue: InputQueue<QueuedStep>,
+
+    /// Resume token of the last step, which is also where the worker starts
+    /// reading once the pipeline runs.
+    last_resume_info: Mutex<Option<MongoDbCdcResumeInfo>>,
+
+    /// Deserializer used to replay journaled steps.
+    replay_stream: Mutex<Box<dyn DeCollectionStream>>,
+}
+
+impl MongoDbCdcInputEndpointInner {
+    fn queue(&self) {
+        let (total, hasher, steps) = self.queue.flush_with_aux();
+
+        let mut last_resume_info = self.last_resume_info.lock().unwrap();
+        let mut changes = Vec::new();
+        let mut watermarks = Vec::with_capacity(steps.len());
+        for (timestamp, step) in steps {
+            changes.extend(step.changes);
+            *last_resume_info = Some(step.resume_info);
+            watermarks.push(Watermark::new(timestamp, None));
+        }
+
+        let seek = last_resume_info
+            .as_ref()
+            .map_or(JsonValue::Null, |resume_info| {
+                serde_json::to_value(resume_info).unwrap()
+            });
+        let resume = match hasher {
+            Some(hasher) => Resume::Replay {
+                seek,
+                replay: rmpv::ext::to_value(ReplayData { changes }).unwrap(),
+                hash: hasher.finish(),
+            },
+            None if seek.is_null() => Resume::Barrier,
+            None => Resume::Seek { seek },
+        };
+        self.consumer.extended(total, Some(resume), watermarks);
+    }
+
+    fn replay(&self, metadata: JsonValue, data: rmpv::Value) {
+        let ReplayData { changes } = rmpv::ext::from_value(data).unwrap();
+        let mut stream = self.replay_stream.lock().unwrap();
+        for change in &changes {
+            // The changes were journaled after they were deserialized
+            // successfully, so they can't fail now.
+            let _ = match change {
+                Change::Insert(record) => stream.insert(record.as_bytes()),
+                Change::Delete(record) => stream.delete(record.as_bytes()),
+            };
+        }
+
+        let mut hasher = Xxh3Default::new();
+        let mut total = BufferSize::empty();
+        if let Some(mut buffer) = stream.take_all() {
+            total = buffer.len();
+            self.consumer.buffered(total);
+            buffer.hash(&mut hasher);
+            buffer.flush();
+        }
+
+        if !metadata.is_null() {
+            match parse_resume_info::<MongoDbCdcResumeInfo>(&metadata) {
+                Ok(resume_info) => *self.last_resume_info.lock().unwrap() = Some(resume_info),
+                Err(e) => self.consumer.error(true, e, Some("mongodb-cdc-replay")),
+            }
+        }
+        self.consumer.replayed(total, hasher.finish());
+    }
+
+    /// Applies `changes` to `input_stream` and pushes the result to the
+    /// queue, along with `errors`, as of `resume_token`.
+    ///
+    /// Returns false if there was nothing to push.
+    fn push(
+        &self,
+        input_stream: &mut dyn DeCollectionStream,
+        changes: Vec<Change>,
+        mut errors: Vec<ParseError>,
+        resume_token: &ResumeToken,
+    ) -> bool {
+        let journal = self.consumer.pipeline_fault_tolerance() == Some(FtModel::ExactlyOnce);
+        let mut journaled = Vec::new();
+        for (index, change) in changes.into_iter().enumerate() {
+            let (result, record) = match &change {
+                Change::Insert(record) => (input_stream.insert(record.as_bytes()), record),
+                Change::Delete(record) => (input_stream.delete(record.as_bytes()), record),
+            };
+            match result {
+                Ok(()) if journal => journaled.push(change),
+                Ok(()) => (),
+                Err(e) => errors.push(ParseError::text_event_error(
+                    "failed to deserialize change from MongoDB",
+                    e,
+                    index as u64,
+                    Some(record),
+                    None,
+                )),
+            }
+        }
+
+        let buffer = input_stream.take_all();
+        if buffer.is_none() && errors.is_empty() {
+            return false;
+        }
+        self.queue.push_with_aux(
+            (buffer, errors),
+            Utc::now(),
+            QueuedStep {
+                resume_info: MongoDbCdcResumeInfo::new(resume_token),
+                changes: journaled,
+            },
+        );
+        true
+    }
+
+    async fn worker_task(
+        self: Arc<Self>,
+        input_stream: Box<dyn DeCollectionStream>,
+        schema: Relation,
+        receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let mut receiver_clone = receiver.clone();
+        select! {
+            _ = Self::worker_task_inner(self.clone(), input_stream, schema, receiver, init_status_sender) => {
+                debug!("mongodb_cdc {}: worker task terminated", &self.endpoint_name);
+            }
+            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                debug!("mongodb_cdc {}: received termination command; worker task canceled", &self.endpoint_name);
+            }
+        }
+    }
+
+    async fn worker_task_inner(
+        self: Arc<Self>,
+        mut input_stream: Box<dyn DeCollectionStream>,
+        schema: Relation,
+        mut receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let collection = match self.initialize().await {
+            Ok(collection) => collection,
+            Err(e) => {
+                let _ = init_status_sender
+                    .send(Err(ControllerError::input_transport_error(
+                        &self.endpoint_name,
+                        true,
+                        e,
+                    )))
+                    .await;
+                return;
+            }
+        };
+        let _ = init_status_sender.send(Ok(())).await;
+        wait_running(&mut receiver).await;
+
+        let existing_resume_info = self.last_resume_info.lock().unwrap().clone();
+        let resume_token = match existing_resume_info {
+            Some(resume_info) => resume_info.resume_token(),
+            None => self
+                .start(&collection, &mut *input_stream, &schema)
+                .await
+                .context("error reading the initial contents of the collection"),
+        };
+        let mut resume_token = match resume_token {
+            Ok(resume_token) => resume_token,
+            Err(e) => {
+                self.consumer.error(true, e, Some("mongodb-cdc-init"));
+                return;
+            }
+        };
+
+        loop {
+            wait_running(&mut receiver).await;
+            match self
+                .follow(
+                    &collection,
+                    &mut *input_stream,
+                    &schema,
+                    &mut resume_token,
+                    &mut receiver,
+                )
+                .await
+            {
+                Ok(()) => return,
+                Err(e) => self.consumer.error(false, e, Some("mongodb-cdc-stream")),
+            }
+            sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// Connects to the server and checks that the collection exists.
+    async fn initialize(&self) -> AnyResult<Collection<Document>> {
+        debug!("mongodb_cdc {}: connecting", &self.endpoint_name);
+        let client = Client::with_uri_str(&self.config.uri)
+            .await
+            .context("invalid 'uri'")?;
+        let database = client.database(&self.config.database);
+        database
+            .run_command(doc! { "ping": 1 })
+            .await
+            .context("unable to connect to MongoDB server")?;
+
+        let collections = database
+            .list_collection_names()
+            .filter(doc! { "name": &self.config.collection })
+            .await
+            .with_context(|| {
+                format!(
+                    "error listing collections in database '{}'",
+                    self.config.database
+                )
+            })?;
+        if collections.is_empty() {
+            bail!(
+                "collection '{}' does not exist in database '{}'",
+                self.config.collection,
+                self.config.database
+            );
+        }
+
+        Ok(database.collection(&self.config.collection))
+    }
+
+    /// Opens a change stream on `collection` after `resume_token`, or from
+    /// the current time if `resume_token` is `None`.
+    async fn watch(
+        &self,
+        collection: &Collection<Document>,
+        resume_token: Option<ResumeToken>,
+    ) -> AnyResult<ChangeStream<ChangeStreamEvent<Document>>> {
+        let mut watch = collection
+            .watch()
+            .full_document(FullDocumentType::UpdateLookup);
+        if self.config.pre_images {
+            watch = watch.full_document_before_change(FullDocumentBeforeChangeType::WhenAvailable);
+        }
+        if let Some(resume_token) = resume_token {
+            watch = watch.resume_after(resume_token);
+        }
+        watch
+            .await
+            .context("error opening change stream (change streams require a replica set or a sharded cluster)")
+    }
+
+    /// Finds the resume token from which to follow changes and, if the
+    /// configuration asks for it, reads the contents of the collection and
+    /// pushes them to the queue as a single buffer, so that a checkpoint
+    /// never includes a part of them.
+    ///
+    /// The change stream is opened before reading the collection, so the
+    /// changes that it reports may overlap with the contents of the
+    /// collection.  Applying them again is harmless for a table whose primary
+    /// key is `_id`.
+    async fn start(
+        &self,
+        collection: &Collection<Document>,
+        input_stream: &mut dyn DeCollectionStream,
+        schema: &Relation,
+    ) -> AnyResult<ResumeToken> {
+        let stream = self.watch(collection, None).await?;
+        let resume_token = stream.resume_token().ok_or_else(|| {
+            anyhow!("the server did not return a resume token for the change stream")
+        })?;
+        drop(stream);
+
+        if !self.config.snapshot {
+            return Ok(resume_token);
+        }
+
+        info!(
+            "mongodb_cdc {}: reading the contents of collection '{}.{}'",
+            &self.endpoint_name, &self.config.database, &self.config.collection
+        );
+        let timestamp = Utc::now();
+        let journal = self.consumer.pipeline_fault_tolerance() == Some(FtModel::ExactlyOnce);
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        let mut num_records = 0;
+        let mut cursor = collection.find(doc! {}).await?;
+        while let Some(document) = cursor.try_next().await? {
+            num_records += 1;
+            let record = document_to_json(document, schema);
+            match input_stream.insert(record.as_bytes()) {
+                Ok(()) if journal => changes.push(Change::Insert(record)),
+                Ok(()) => (),
+                Err(e) => errors.push(ParseError::text_event_error(
+                    "failed to deserialize document from MongoDB",
+                    e,
+                    num_records,
+                    Some(&record),
+                    None,
+                )),
+            }
+        }
+
+        info!(
+            "mongodb_cdc {}: read {num_records} documents from collection '{}.{}'",
+            &self.endpoint_name, &self.config.database, &self.config.collection
+        );
+        self.queue.push_with_aux(
+            (input_stream.take_all(), errors),
+            timestamp,
+            QueuedStep {
+                resume_info: MongoDbCdcResumeInfo::new(&resume_token),
+                changes,
+            },
+        );
+        Ok(resume_token)
+    }
+
+    /// Follows the change stream from `resume_token`, pushes the changes to
+    /// the queue, and updates `resume_token`, until an error occurs.
+    ///
+    /// Returns `Ok(())` if the change stream was invalidated, e.g., because
+    /// the collection was dropped, after reporting a fatal error.
+    async fn follow(
+        &self,
+        collection: &Collection<Document>,
+        input_stream: &mut dyn DeCollectionStream,
+        schema: &Relation,
+        resume_token: &mut ResumeToken,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        let mut stream = self.watch(collection, Some(resume_token.clone())).await?;
+        info!(
+            "mongodb_cdc {}: following change stream",
+            &self.endpoint_name
+        );
+
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        let mut last_position_update = Instant::now();
+        loop {
+            wait_running(receiver).await;
+
+            // Each call returns the next event, or `None` once the events
+            // in the current batch from the server have been consumed.
+            let event = stream
+                .next_if_any()
+                .await
+                .context("error reading change stream")?;
+            if let Some(event) = event {
+                match event.operation_type {
+                    OperationType::Insert | OperationType::Update | OperationType::Replace => {
+                        if let Some(before) = event.full_document_before_change {
+                            changes.push(Change::Delete(document_to_json(before, schema)));
+                        }
+                        // The document can be missing from an update if it
+                        // was deleted before the server looked it up, in
+                        // which case a later event deletes it.
+                        if let Some(after) = event.full_document {
+                            changes.push(Change::Insert(document_to_json(after, schema)));
+                        }
+                    }
+                    OperationType::Delete => {
+                        match (event.full_document_before_change, event.document_key) {
+                            (Some(before), _) => {
+                                changes.push(Change::Delete(document_to_json(before, schema)))
+                            }
+                            (None, Some(key)) => {
+                                changes.push(Change::Delete(document_to_json(key, schema)))
+                            }
+                            (None, None) => errors.push(ParseError::text_envelope_error(
+                                "delete event without a document key".to_string(),
+                                "",
+                                None,
+                            )),
+                        }
+                    }
+                    OperationType::Drop
+                    | OperationType::Rename
+                    | OperationType::DropDatabase
+                    | OperationType::Invalidate => {
+                        self.push(
+                            input_stream,
+                            std::mem::take(&mut changes),
+                            std::mem::take(&mut errors),
+                            &event.id,
+                        );
+                        self.consumer.error(
+                            true,
+                            anyhow!(
+                                "collection '{}.{}' was dropped or renamed ({:?} event); the connector cannot follow it any further",
+                                self.config.database,
+                                self.config.collection,
+                                event.operation_type
+                            ),
+                            Some("mongodb-cdc-invalidate"),
+                        );
+                        return Ok(());
+                    }
+                    _ => (),
+                }
+                continue;
+            }
+
+            // The batch is done.  Its resume token covers all of the events
+            // in it.
+            let Some(token) = stream.resume_token() else {
+                continue;
+            };
+            *resume_token = token;
+            let pushed = self.push(
+                input_stream,
+                std::mem::take(&mut changes),
+                std::mem::take(&mut errors),
+                resume_token,
+            );
+
+            // Record the resume token while the collection doesn't change, so
+            // that it doesn't fall off the end of the oplog.
+            if pushed || last_position_update.elapsed() >= POSITION_UPDATE_INTERVAL {
+                if !pushed {
+                    self.queue.push_with_aux(
+                        (None, Vec::new()),
+                        Utc::now(),
+                        QueuedStep {
+                            resume_info: MongoDbCdcResumeInfo::new(resume_token),
+                            changes: Vec::new(),
+                        },
+                    );
+                }
+                last_position_update = Instant::now();
+            }
+        }
+    }
+}
+
+/// Block until the state is `Running`.
+async fn wait_running(receiver: &mut Receiver<PipelineState>) {
+    // An error indicates that the channel was closed.  It's ok to ignore
+    // the error as this situation will be handled by the top-level select,
+    // which will abort the worker thread.
+    let _ = receiver
+        .wait_for(|state| state == &PipelineState::Running)
+        .await;
+}
@@ -0,0 +1,519 @@
+//! MongoDB output connector.
+//!
+//! The connector buffers the changes in each step and writes them with the
+//! `update` and `delete` database commands, which perform bulk `replaceOne`
+//! and `deleteOne` operations.
+
+use crate::catalog::{RecordFormat, SerBatchReader};
+use crate::controller::{ControllerInner, EndpointId};
+use crate::format::{Encoder, OutputConsumer};
+use crate::transport::OutputEndpoint;
+use crate::util::{indexed_operation_type, IndexedOperationType};
+use crate::ControllerError;
+use anyhow::{anyhow, bail, Result as AnyResult};
+use chrono::NaiveDateTime;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{AsyncErrorCallback, Step};
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::{Field, Relation, SqlType};
+use feldera_types::transport::mongodb::{MongoDbWriteConcern, MongoDbWriterConfig};
+use mongodb::bson::spec::BinarySubtype;
+use mongodb::bson::{doc, Binary, Bson, DateTime, Decimal128, Document};
+use mongodb::{Client, Database};
+use serde_json::{Map, Value as JsonValue};
+use std::str::FromStr;
+use std::sync::Weak;
+use tracing::{info_span, span::EnteredSpan, warn};
+
+/// Default for [`MongoDbWriterConfig::max_batch_size`].
+const DEFAULT_MAX_BATCH_SIZE: usize = 1000;
+
+/// The maximum total size of the operations in a single command, leaving
+/// room under MongoDB's 16 MiB limit on the size of a document.
+const MAX_BATCH_BYTES: usize = 8 << 20;
+
+/// Integrated output connector that writes a view to a MongoDB collection.
+pub struct MongoDbOutputEndpoint {
+    endpoint_id: EndpointId,
+    endpoint_name: String,
+    config: MongoDbWriterConfig,
+    controller: Weak<ControllerInner>,
+    database: Database,
+    value_schema: Relation,
+    key_schema: Relation,
+
+    /// The write concern, as a document for the `writeConcern` field.
+    write_concern: Option<Document>,
+    max_batch_size: usize,
+
+    /// Operations for the `update` and `delete` commands in the current step,
+    /// with their approximate sizes.
+    updates: Vec<(Document, usize)>,
+    deletes: Vec<(Document, usize)>,
+    num_bytes: usize,
+}
+
+impl MongoDbOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &MongoDbWriterConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let key_schema = key_schema.to_owned().ok_or(ControllerError::not_supported(
+            "MongoDB output connector requires the view to have a unique key. Please specify the `index` property in the connector configuration. For more details, see: https://docs.feldera.com/connectors/unique_keys",
+        ))?;
+
+        let database = TOKIO
+            .block_on(async {
+                let client = Client::with_uri_str(&config.uri).await?;
+                let database = client.database(&config.database);
+                database.run_command(doc! { "ping": 1 }).await?;
+                Ok::<_, mongodb::error::Error>(database)
+            })
+            .map_err(|e| {
+                ControllerError::output_transport_error(
+                    endpoint_name,
+                    true,
+                    anyhow!("unable to connect to MongoDB server: {e}"),
+                )
+            })?;
+
+        Ok(Self {
+            endpoint_id,
+            endpoint_name: endpoint_name.to_owned(),
+            config: config.clone(),
+            controller,
+            database,
+            value_schema: value_schema.to_owned(),
+            key_schema,
+            write_concern: config.write_concern.as_ref().map(write_concern_document),
+            max_batch_size: config.max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE),
+            updates: Vec::new(),
+            deletes: Vec::new(),
+            num_bytes: 0,
+        })
+    }
+
+    fn span(&self) -> EnteredSpan {
+        info_span!(
+            "mongodb_output",
+            id = self.endpoint_id,
+            name = self.endpoint_name,
+            mongodb_collection = self.config.collection,
+        )
+        .entered()
+    }
+
+    /// Runs `command` (`update` or `delete`) with the operations in `ops`
+    /// under `field` (`updates` or `deletes`), in batches.
+    async fn write(&self, command: &str, field: &str, ops: &[(Document, usize)]) -> AnyResult<()> {
+        for batch in batches(ops, self.max_batch_size, MAX_BATCH_BYTES) {
+            let mut document = Document::new();
+            document.insert(command, self.config.collection.as_str());
+            document.insert(
+                field,
+                batch
+                    .iter()
+                    .map(|(op, _)| Bson::Document(op.clone()))
+                    .collect::<Vec<_>>(),
+            );
+            document.insert("ordered", self.config.ordered);
+            if let Some(write_concern) = &self.write_concern {
+                document.insert("writeConcern", write_concern.clone());
+            }
+            let reply = self.database.run_command(document).await?;
+            check_reply(&reply)?;
+        }
+        Ok(())
+    }
+
+    fn batch_end_inner(&self) -> AnyResult<()> {
+        TOKIO.block_on(async {
+            self.write("delete", "deletes", &self.deletes).await?;
+            self.write("update", "updates", &self.updates).await
+        })
+    }
+}
+
+/// Converts the configured write concern into a `writeConcern` document.
+fn write_concern_document(write_concern: &MongoDbWriteConcern) -> Document {
+    let mut document = Document::new();
+    if let Some(w) = &write_concern.w {
+        match w.parse::<i32>() {
+            Ok(w) => document.insert("w", w),
+            Err(_) => document.insert("w", w.as_str()),
+        };
+    }
+    if let Some(journal) = write_concern.journal {
+        document.insert("j", journal);
+    }
+    if let Some(timeout_ms) = write_concern.timeout_ms {
+        document.insert("wtimeout", timeout_ms as i64);
+    }
+    document
+}
+
+/// Splits `ops` into batches of at most `max_ops` operations and, unless an
+/// operation is larger by itself, `max_bytes` bytes.
+fn batches(
+    ops: &[(Document, usize)],
+    max_ops: usize,
+    max_bytes: usize,
+) -> Vec<&[(Document, usize)]> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0;
+    for (index, (_, size)) in ops.iter().enumerate() {
+        if index > start && (index - start >= max_ops || bytes + size > max_bytes) {
+            batches.push(&ops[start..index]);
+            start = index;
+            bytes = 0;
+        }
+        bytes += size;
+    }
+    if start < ops.len() {
+        batches.push(&ops[start..]);
+    }
+    batches
+}
+
+/// Returns an error if the reply to a write command reports write errors.
+fn check_reply(reply: &Document) -> AnyResult<()> {
+    if let Ok(errors) = reply.get_array("writeErrors") {
+        let messages = errors
+            .iter()
+            .filter_map(|error| error.as_document())
+            .map(|error| {
+                format!(
+                    "{} (code {})",
+                    error.get_str("errmsg").unwrap_or("unknown error"),
+                    error.get("code").cloned().unwrap_or(Bson::Null)
+                )
+            })
+            .collect::<Vec<_>>();
+        bail!(
+            "{} write operation(s) failed: {}",
+            errors.len(),
+            messages.join("; ")
+        );
+    }
+    if let Ok(error) = reply.get_document("writeConcernError") {
+        bail!(
+            "write concern error: {}",
+            error.get_str("errmsg").unwrap_or("unknown error")
+        );
+    }
+    Ok(())
+}
+
+/// Decodes a Postgres-style hex string, e.g., `\x0aff`.
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let hex = s.strip_prefix("\\x")?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Converts a JSON value without a known SQL type, e.g., inside a `VARIANT`,
+/// into BSON.
+fn json_to_bson_untyped(value: JsonValue) -> Bson {
+    match value {
+        JsonValue::Null => Bson::Null,
+        JsonValue::Bool(b) => Bson::Boolean(b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Bson::Int64(i),
+            None => Bson::Double(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => Bson::String(s),
+        JsonValue::Array(array) => {
+            Bson::Array(array.into_iter().map(json_to_bson_untyped).collect())
+        }
+        JsonValue::Object(object) => Bson::Document(
+            object
+                .into_iter()
+                .map(|(key, value)| (key, json_to_bson_untyped(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Converts the JSON representation of a value of `field` into BSON.
+fn json_to_bson(field: &Field, value: JsonValue) -> Bson {
+    match (field.columntype.typ, value) {
+        (_, JsonValue::Null) => Bson::Null,
+        (SqlType::TinyInt | SqlType::SmallInt | SqlType::Int, JsonValue::Number(n))
+            if n.as_i64().is_some_and(|i| i32::try_from(i).is_ok()) =>
+        {
+            Bson::Int32(n.as_i64().unwrap() as i32)
+        }
+        (SqlType::Real | SqlType::Double, JsonValue::Number(n)) => {
+            Bson::Double(n.as_f64().unwrap_or_default())
+        }
+        (SqlType::Decimal, JsonValue::String(s)) => match Decimal128::from_str(&s) {
+            Ok(decimal) => Bson::Decimal128(decimal),
+            Err(_) => Bson::String(s),
+        },
+        (SqlType::Timestamp, JsonValue::String(s)) => {
+            match NaiveDateTime::parse_from_str(&s, "%F %T%.f") {
+                Ok(timestamp) => Bson::DateTime(DateTime::from_millis(
+                    timestamp.and_utc().timestamp_millis(),
+                )),
+                Err(_) => Bson::String(s),
+            }
+        }
+        (SqlType::Binary | SqlType::Varbinary, JsonValue::String(s)) => match decode_hex(&s) {
+            Some(bytes) => Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes,
+            }),
+            None => Bson::String(s),
+        },
+        (_, value) => json_to_bson_untyped(value),
+    }
+}
+
+/// Converts a JSON record into a BSON document with the fields in `fields`.
+fn record_to_document(fields: &[Field], record: &[u8]) -> AnyResult<Document> {
+    let mut object: Map<String, JsonValue> = serde_json::from_slice(record)?;
+    Ok(fields
+        .iter()
+        .map(|field| {
+            let name = field.name.name();
+            let value = json_to_bson(field, object.remove(&name).unwrap_or(JsonValue::Null));
+            (name, value)
+        })
+        .collect())
+}
+
+impl OutputConsumer for MongoDbOutputEndpoint {
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn batch_start(&mut self, _step: Step) {
+        self.updates.clear();
+        self.deletes.clear();
+    }
+
+    fn push_buffer(&mut self, _: &[u8], _: usize) {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _: Option<&[u8]>,
+        _: Option<&[u8]>,
+        _: &[(&str, Option<&[u8]>)],
+        _num_records: usize,
+    ) {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) {
+        let _guard = self.span();
+        let num_rows = self.updates.len() + self.deletes.len();
+        let result = self.batch_end_inner();
+        self.updates.clear();
+        self.deletes.clear();
+
+        let Some(controller) = self.controller.upgrade() else {
+            warn!("controller is shutting down: aborting");
+            return;
+        };
+        match result {
+            Ok(()) => controller.status.output_buffer(
+                self.endpoint_id,
+                std::mem::take(&mut self.num_bytes),
+                num_rows,
+            ),
+            Err(e) => {
+                self.num_bytes = 0;
+                controller.output_transport_error(
+                    self.endpoint_id,
+                    &self.endpoint_name,
+                    true,
+                    anyhow!(
+                        "mongodb: failed to write to collection '{}.{}': {e}",
+                        self.config.database,
+                        self.config.collection
+                    ),
+                    Some("mongodb_batch_end"),
+                );
+            }
+        }
+    }
+}
+
+impl Encoder for MongoDbOutputEndpoint {
+    fn consumer(&mut self) -> &mut dyn OutputConsumer {
+        self
+    }
+
+    fn encode(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let mut cursor = batch.cursor(RecordFormat::Json(JsonFlavor::Postgres))?;
+        while cursor.key_valid() {
+            if let Some(op) = indexed_operation_type(
+                &self.value_schema.name,
+                &self.key_schema.name,
+                cursor.as_mut(),
+            )? {
+                cursor.rewind_vals();
+                let mut buf = Vec::new();
+                match op {
+                    IndexedOperationType::Delete => {
+                        cursor.serialize_key(&mut buf)?;
+                        let key = record_to_document(&self.key_schema.fields, &buf)?;
+                        self.deletes
+                            .push((doc! { "q": key, "limit": 1 }, buf.len()));
+                    }
+                    IndexedOperationType::Insert | IndexedOperationType::Upsert => {
+                        if cursor.weight() < 0 {
+                            cursor.step_val();
+                        }
+                        cursor.serialize_val(&mut buf)?;
+                        let document = record_to_document(&self.value_schema.fields, &buf)?;
+                        let mut key_buf = Vec::new();
+                        cursor.serialize_key(&mut key_buf)?;
+                        let key = record_to_document(&self.key_schema.fields, &key_buf)?;
+                        self.updates.push((
+                            doc! { "q": key, "u": document, "upsert": true },
+                            buf.len() + key_buf.len(),
+                        ));
+                    }
+                }
+                self.num_bytes += buf.len();
+            }
+            cursor.step_key();
+        }
+
+        Ok(())
+    }
+}
+
+impl OutputEndpoint for MongoDbOutputEndpoint {
+    fn connect(&mut self, _: AsyncErrorCallback) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        todo!()
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8]) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+
+    fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{batches, check_reply, record_to_document, write_concern_document};
+    use feldera_types::program_schema::{ColumnType, Field};
+    use feldera_types::transport::mongodb::MongoDbWriteConcern;
+    use mongodb::bson::spec::BinarySubtype;
+    use mongodb::bson::{doc, Binary, Bson, DateTime};
+
+    #[test]
+    fn test_record_to_document() {
+        let fields = vec![
+            Field::new("_id".into(), ColumnType::bigint(false)),
+            Field::new("n".into(), ColumnType::int(true)),
+            Field::new("ts".into(), ColumnType::timestamp(true)),
+            Field::new("bin".into(), ColumnType::varbinary(true)),
+            Field::new("s".into(), ColumnType::varchar(true)),
+        ];
+        assert_eq!(
+            record_to_document(
+                &fields,
+                br#"{"_id": 1, "n": 2, "ts": "2025-01-01 00:00:00.5", "bin": "\\x00ff", "s": "x"}"#
+            )
+            .unwrap(),
+            doc! {
+                "_id": 1_i64,
+                "n": 2,
+                "ts": DateTime::from_millis(1735689600500),
+                "bin": Binary { subtype: BinarySubtype::Generic, bytes: vec![0, 255] },
+                "s": "x",
+            }
+        );
+        assert_eq!(
+            record_to_document(&fields[..2], br#"{"_id": 2}"#).unwrap(),
+            doc! { "_id": 2_i64, "n": Bson::Null }
+        );
+    }
+
+    #[test]
+    fn test_batches() {
+        let ops = (0..5).map(|i| (doc! { "i": i }, 10)).collect::<Vec<_>>();
+        let sizes = |batches: Vec<&[_]>| batches.iter().map(|b| b.len()).collect::<Vec<_>>();
+        assert_eq!(sizes(batches(&ops, 2, 100)), vec![2, 2, 1]);
+        assert_eq!(sizes(batches(&ops, 10, 25)), vec![2, 2, 1]);
+        assert_eq!(sizes(batches(&ops, 10, 5)), vec![1, 1, 1, 1, 1]);
+        assert!(batches(&ops[..0], 10, 5).is_empty());
+    }
+
+    #[test]
+    fn test_write_concern() {
+        assert_eq!(
+            write_concern_document(&MongoDbWriteConcern {
+                w: Some("majority".to_string()),
+                journal: Some(true),
+                timeout_ms: Some(5000),
+            }),
+            doc! { "w": "majority", "j": true, "wtimeout": 5000_i64 }
+        );
+        assert_eq!(
+            write_concern_document(&MongoDbWriteConcern {
+                w: Some("2".to_string()),
+                ..Default::default()
+            }),
+            doc! { "w": 2 }
+        );
+    }
+
+    #[test]
+    fn test_check_reply() {
+        assert!(check_reply(&doc! { "ok": 1, "n": 2 }).is_ok());
+        let error = check_reply(&doc! {
+            "ok": 1,
+            "n": 1,
+            "writeErrors": [{ "index": 1, "code": 11000, "errmsg": "duplicate key" }],
+        })
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "1 write operation(s) failed: duplicate key (code 11000)"
+        );
+    }
+}
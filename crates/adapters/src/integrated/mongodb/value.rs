@@ -0,0 +1,141 @@
+//! Conversion of BSON documents into the JSON representation that the
+//! connector's JSON flavor expects.
+
+use chrono::DateTime;
+use feldera_types::program_schema::Relation;
+use mongodb::bson::{Bson, Document};
+use serde_json::{Map, Value as JsonValue};
+use std::fmt::Write;
+
+/// Formats binary data as a Postgres-style hex string, e.g., `\x0aff`.
+fn hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + bytes.len() * 2);
+    s.push_str("\\x");
+    for byte in bytes {
+        write!(s, "{byte:02x}").unwrap();
+    }
+    s
+}
+
+/// Formats milliseconds since the epoch as a timestamp.
+fn timestamp_millis(millis: i64) -> JsonValue {
+    match DateTime::from_timestamp_millis(millis) {
+        Some(timestamp) => JsonValue::String(timestamp.format("%F %T%.f").to_string()),
+        None => JsonValue::from(millis),
+    }
+}
+
+/// Converts a BSON value into JSON.
+///
+/// Types that JSON lacks are converted into the representations that the
+/// corresponding SQL types use: dates into timestamps, decimals into strings,
+/// and binary data into hex strings.  Object IDs become hex strings, so they
+/// can be stored in `VARCHAR` columns.
+pub(super) fn bson_to_json(value: Bson) -> JsonValue {
+    match value {
+        Bson::Null | Bson::Undefined => JsonValue::Null,
+        Bson::Boolean(b) => JsonValue::Bool(b),
+        Bson::Int32(i) => JsonValue::from(i),
+        Bson::Int64(i) => JsonValue::from(i),
+        // Infinity and NaN become null.
+        Bson::Double(d) => JsonValue::from(d),
+        Bson::Decimal128(d) => JsonValue::String(d.to_string()),
+        Bson::String(s) | Bson::Symbol(s) | Bson::JavaScriptCode(s) => JsonValue::String(s),
+        Bson::ObjectId(oid) => JsonValue::String(oid.to_hex()),
+        Bson::DateTime(datetime) => timestamp_millis(datetime.timestamp_millis()),
+        Bson::Timestamp(timestamp) => timestamp_millis(timestamp.time as i64 * 1000),
+        Bson::Binary(binary) => JsonValue::String(hex(&binary.bytes)),
+        Bson::Array(array) => JsonValue::Array(array.into_iter().map(bson_to_json).collect()),
+        Bson::Document(document) => JsonValue::Object(
+            document
+                .into_iter()
+                .map(|(key, value)| (key, bson_to_json(value)))
+                .collect(),
+        ),
+        Bson::RegularExpression(regex) => {
+            JsonValue::String(format!("/{}/{}", regex.pattern, regex.options))
+        }
+        value => JsonValue::String(value.to_string()),
+    }
+}
+
+/// Converts a document into a JSON record for a table with `schema`.
+///
+/// Fields are matched to columns by name, with the same case-sensitivity
+/// rules as SQL identifiers, and fields that aren't columns of the table are
+/// left out.
+pub(super) fn document_to_json(document: Document, schema: &Relation) -> String {
+    let mut object = Map::new();
+    for (key, value) in document {
+        if let Some(field) = schema.fields.iter().find(|field| field.name == key) {
+            object.insert(field.name.name(), bson_to_json(value));
+        }
+    }
+    JsonValue::Object(object).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bson_to_json, document_to_json};
+    use feldera_types::program_schema::{ColumnType, Field, Relation, SqlIdentifier};
+    use mongodb::bson::oid::ObjectId;
+    use mongodb::bson::spec::BinarySubtype;
+    use mongodb::bson::{doc, Binary, Bson, DateTime, Decimal128};
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_bson_to_json() {
+        assert_eq!(bson_to_json(Bson::Null), json!(null));
+        assert_eq!(bson_to_json(Bson::Int32(-1)), json!(-1));
+        assert_eq!(bson_to_json(Bson::Int64(1 << 40)), json!(1u64 << 40));
+        assert_eq!(bson_to_json(Bson::Double(0.5)), json!(0.5));
+        assert_eq!(bson_to_json(Bson::Double(f64::NAN)), json!(null));
+        assert_eq!(
+            bson_to_json(Bson::Decimal128(Decimal128::from_str("12.50").unwrap())),
+            json!("12.50")
+        );
+        assert_eq!(
+            bson_to_json(Bson::ObjectId(
+                ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap()
+            )),
+            json!("65a1b2c3d4e5f60718293a4b")
+        );
+        assert_eq!(
+            bson_to_json(Bson::DateTime(DateTime::from_millis(1735689600500))),
+            json!("2025-01-01 00:00:00.500")
+        );
+        assert_eq!(
+            bson_to_json(Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: vec![0, 255]
+            })),
+            json!("\\x00ff")
+        );
+        assert_eq!(
+            bson_to_json(Bson::Document(doc! {"a": [1, "x"], "b": {"c": true}})),
+            json!({"a": [1, "x"], "b": {"c": true}})
+        );
+    }
+
+    #[test]
+    fn test_document_to_json() {
+        let schema = Relation::new(
+            "t".into(),
+            vec![
+                Field::new("_id".into(), ColumnType::varchar(false)),
+                Field::new("customerid".into(), ColumnType::bigint(true)),
+                Field::new(SqlIdentifier::new("Name", true), ColumnType::varchar(true)),
+            ],
+            false,
+            Default::default(),
+        );
+        assert_eq!(
+            document_to_json(
+                doc! {"_id": "a", "customerId": 1_i64, "Name": "x", "name": "y", "other": 2},
+                &schema
+            ),
+            json!({"_id": "a", "customerid": 1, "Name": "x"}).to_string()
+        );
+    }
+}
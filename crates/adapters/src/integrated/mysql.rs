@@ -0,0 +1,21 @@
+mod gtid;
+mod input;
+mod output;
+mod value;
+
+pub use input::MySqlCdcInputEndpoint;
+pub use output::MySqlOutputEndpoint;
+
+/// Quotes `ident` for use as a MySQL identifier.
+fn quote_identifier(ident: &str) -> String {
+    format!("`{}`", ident.replace('`', "``"))
+}
+
+/// Quotes a table name that is optionally qualified with a database name,
+/// e.g., `shop.orders`.
+fn quote_table_name(name: &str) -> String {
+    name.split('.')
+        .map(quote_identifier)
+        .collect::<Vec<_>>()
+        .join(".")
+}
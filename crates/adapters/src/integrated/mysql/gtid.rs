@@ -0,0 +1,134 @@
+//! MySQL global transaction identifier (GTID) sets.
+
+use mysql_async::{GnoInterval, Sid};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use uuid::Uuid;
+
+/// A set of GTIDs in the format of `gtid_executed`, e.g.,
+/// `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:11-18`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct GtidSet {
+    /// For each server UUID, the sorted, disjoint, and non-adjacent inclusive
+    /// ranges of transaction numbers in the set.
+    intervals: BTreeMap<Uuid, Vec<(u64, u64)>>,
+}
+
+impl GtidSet {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut set = Self::default();
+        for sid in s.split(',').map(str::trim).filter(|sid| !sid.is_empty()) {
+            let mut parts = sid.split(':');
+            let uuid = parts.next().unwrap_or_default();
+            let uuid = Uuid::parse_str(uuid)
+                .map_err(|e| format!("invalid server UUID '{uuid}' in GTID set: {e}"))?;
+            for interval in parts {
+                let (start, end) = interval.split_once('-').unwrap_or((interval, interval));
+                match (start.parse::<u64>(), end.parse::<u64>()) {
+                    (Ok(start), Ok(end)) if start > 0 && start <= end => {
+                        set.add_interval(uuid, start, end)
+                    }
+                    _ => return Err(format!("invalid interval '{interval}' in GTID set")),
+                }
+            }
+        }
+        Ok(set)
+    }
+
+    /// Adds transaction `gno` of server `uuid` to the set.
+    pub fn add(&mut self, uuid: Uuid, gno: u64) {
+        self.add_interval(uuid, gno, gno)
+    }
+
+    fn add_interval(&mut self, uuid: Uuid, start: u64, end: u64) {
+        let intervals = self.intervals.entry(uuid).or_default();
+
+        // Transactions usually arrive in order.
+        if let Some(last) = intervals.last_mut() {
+            if last.0 <= start && start <= last.1 + 1 {
+                last.1 = last.1.max(end);
+                return;
+            }
+        }
+
+        intervals.push((start, end));
+        intervals.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(intervals.len());
+        for &(start, end) in intervals.iter() {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        *intervals = merged;
+    }
+
+    /// Converts the set into the form used to request a binlog stream.
+    pub fn to_sids(&self) -> Vec<Sid<'static>> {
+        self.intervals
+            .iter()
+            .map(|(uuid, intervals)| {
+                // `GnoInterval` ranges exclude their end.
+                intervals
+                    .iter()
+                    .fold(Sid::new(*uuid.as_bytes()), |sid, &(start, end)| {
+                        sid.with_interval(GnoInterval::new(start, end + 1))
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Display for GtidSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, (uuid, intervals)) in self.intervals.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", uuid.hyphenated())?;
+            for &(start, end) in intervals {
+                if start == end {
+                    write!(f, ":{start}")?;
+                } else {
+                    write!(f, ":{start}-{end}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GtidSet;
+    use uuid::Uuid;
+
+    const UUID1: &str = "3e11fa47-71ca-11e1-9e33-c80aa9429562";
+    const UUID2: &str = "8a94f357-aab4-11df-86ab-c80aa9429562";
+
+    #[test]
+    fn test_parse() {
+        let set = GtidSet::parse(&format!("{UUID2}:1-5,\n{UUID1}:11-18:1-3:4:20")).unwrap();
+        assert_eq!(set.to_string(), format!("{UUID1}:1-4:11-18:20,{UUID2}:1-5"));
+        assert_eq!(GtidSet::parse("").unwrap(), GtidSet::default());
+        assert!(GtidSet::parse("not-a-uuid:1-5").is_err());
+        assert!(GtidSet::parse(&format!("{UUID1}:5-1")).is_err());
+        assert!(GtidSet::parse(&format!("{UUID1}:0")).is_err());
+    }
+
+    #[test]
+    fn test_add() {
+        let uuid = Uuid::parse_str(UUID1).unwrap();
+        let mut set = GtidSet::default();
+        set.add(uuid, 1);
+        set.add(uuid, 2);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-2"));
+        set.add(uuid, 5);
+        set.add(uuid, 4);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-2:4-5"));
+        set.add(uuid, 3);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-5"));
+        set.add(uuid, 2);
+        assert_eq!(set.to_string(), format!("{UUID1}:1-5"));
+    }
+}
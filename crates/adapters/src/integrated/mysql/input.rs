@@ -0,0 +1,788 @@
+//! MySQL change data capture (CDC) input connector.
+//!
+//! The connector connects to the server as a replica and reads the row
+//! events for a table from the binary log.  It tracks its position as a GTID
+//! set if the server has GTIDs enabled, and as a binlog file and position
+//! otherwise, e.g., with MariaDB, whose GTIDs are incompatible with MySQL's.
+
+use super::gtid::GtidSet;
+use super::quote_identifier;
+use super::value::{binlog_value_to_json, row_to_json, value_to_json, ColumnInfo};
+use crate::transport::{InputEndpoint, InputQueue, InputReaderCommand, IntegratedInputEndpoint};
+use crate::{ControllerError, InputConsumer, InputReader, PipelineState, RecordFormat};
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::catalog::{DeCollectionStream, InputCollectionHandle};
+use feldera_adapterlib::format::{BufferSize, ParseError};
+use feldera_adapterlib::transport::{parse_resume_info, Resume, Watermark};
+use feldera_types::config::FtModel;
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::mysql::MySqlCdcReaderConfig;
+use futures_util::StreamExt;
+use mysql_async::binlog::events::EventData;
+use mysql_async::binlog::row::BinlogRow;
+use mysql_async::prelude::Queryable;
+use mysql_async::{BinlogStreamRequest, Conn, Opts, Row, Value};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::hash::Hasher;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tracing::{debug, info};
+use uuid::Uuid;
+use xxhash_rust::xxh3::Xxh3Default;
+
+/// Delay before reconnecting after an error reading the binlog.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Minimum interval between recording the position after transactions that
+/// didn't change the table.
+const POSITION_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Session settings that make the server format values the way the JSON
+/// flavor expects them; see [`value_to_json`].
+const SESSION_SETTINGS: &str = "SET time_zone = '+00:00'";
+
+/// Integrated input connector that follows the changes to a MySQL table.
+pub struct MySqlCdcInputEndpoint {
+    endpoint_name: String,
+    config: MySqlCdcReaderConfig,
+    consumer: Box<dyn InputConsumer>,
+}
+
+impl MySqlCdcInputEndpoint {
+    pub fn new(
+        endpoint_name: &str,
+        config: &MySqlCdcReaderConfig,
+        consumer: Box<dyn InputConsumer>,
+    ) -> Self {
+        Self {
+            endpoint_name: endpoint_name.to_string(),
+            config: config.clone(),
+            consumer,
+        }
+    }
+}
+
+impl InputEndpoint for MySqlCdcInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::ExactlyOnce)
+    }
+}
+
+impl IntegratedInputEndpoint for MySqlCdcInputEndpoint {
+    fn open(
+        self: Box<Self>,
+        input_handle: &InputCollectionHandle,
+        resume_info: Option<JsonValue>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(MySqlCdcInputReader::new(
+            self.endpoint_name,
+            self.config,
+            self.consumer,
+            input_handle,
+            resume_info,
+        )?))
+    }
+}
+
+/// Resume info stored in each checkpoint for the [`MySqlCdcInputEndpoint`].
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "snake_case")]
+enum MySqlCdcResumeInfo {
+    /// The GTIDs of the transactions that the connector ingested or skipped.
+    GtidSet(String),
+
+    /// Binlog file and position just after the last transaction that the
+    /// connector ingested or skipped.
+    File { name: String, position: u64 },
+}
+
+/// Position in the binlog.
+enum Position {
+    Gtid(GtidSet),
+    File { name: String, position: u64 },
+}
+
+impl Position {
+    fn from_resume_info(resume_info: &MySqlCdcResumeInfo) -> AnyResult<Self> {
+        Ok(match resume_info {
+            MySqlCdcResumeInfo::GtidSet(gtid_set) => {
+                Self::Gtid(GtidSet::parse(gtid_set).map_err(|e| anyhow!(e))?)
+            }
+            MySqlCdcResumeInfo::File { name, position } => Self::File {
+                name: name.clone(),
+                position: *position,
+            },
+        })
+    }
+
+    fn resume_info(&self) -> MySqlCdcResumeInfo {
+        match self {
+            Self::Gtid(gtid_set) => MySqlCdcResumeInfo::GtidSet(gtid_set.to_string()),
+            Self::File { name, position } => MySqlCdcResumeInfo::File {
+                name: name.clone(),
+                position: *position,
+            },
+        }
+    }
+}
+
+/// A change to the table, journaled so that the step can be replayed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum Change {
+    Insert(String),
+    Delete(String),
+}
+
+/// Replay data for a step.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ReplayData {
+    changes: Vec<Change>,
+}
+
+/// Auxiliary data associated with each buffer in the queue.
+struct QueuedStep {
+    resume_info: MySqlCdcResumeInfo,
+
+    /// The changes in the buffer, only recorded if the pipeline is configured
+    /// with exactly-once fault tolerance.
+    changes: Vec<Change>,
+}
+
+struct MySqlCdcInputReader {
+    sender: Sender<PipelineState>,
+    inner: Arc<MySqlCdcInputEndpointInner>,
+}
+
+impl MySqlCdcInputReader {
+    fn new(
+        endpoint_name: String,
+        config: MySqlCdcReaderConfig,
+        consumer: Box<dyn InputConsumer>,
+        input_handle: &InputCollectionHandle,
+        resume_info: Option<JsonValue>,
+    ) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        // A step taken before the connector ingested anything has no resume
+        // info.
+        let resume_info = match resume_info {
+            Some(resume_info) if !resume_info.is_null() => {
+                let resume_info = parse_resume_info::<MySqlCdcResumeInfo>(&resume_info)?;
+                info!("mysql_cdc {endpoint_name}: resuming from {resume_info:?}");
+                Some(resume_info)
+            }
+            _ => None,
+        };
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+
+        // Used to communicate the status of connector initialization.
+        let (init_status_sender, mut init_status_receiver) =
+            mpsc::channel::<Result<(), ControllerError>>(1);
+
+        // MySQL's text format for dates, times, and decimals matches
+        // Postgres', and binary values are converted to the same hex format.
+        let input_stream = input_handle
+            .handle
+            .configure_deserializer(RecordFormat::Json(JsonFlavor::Postgres))?;
+        let replay_stream = input_stream.fork();
+        let schema = input_handle.schema.clone();
+
+        let endpoint = Arc::new(MySqlCdcInputEndpointInner {
+            queue: InputQueue::new(consumer.clone()),
+            endpoint_name,
+            config,
+            consumer,
+            last_resume_info: Mutex::new(resume_info),
+            replay_stream: Mutex::new(replay_stream),
+        });
+
+        let endpoint_clone = endpoint.clone();
+        thread::Builder::new()
+            .name(format!(
+                "{}-mysql-cdc-input-tokio-wrapper",
+                endpoint.endpoint_name
+            ))
+            .spawn(move || {
+                TOKIO.block_on(async {
+                    endpoint_clone
+                        .worker_task(input_stream, schema, receiver, init_status_sender)
+                        .await;
+                })
+            })
+            .expect("failed to spawn MySQL CDC input connector tokio wrapper thread");
+
+        init_status_receiver.blocking_recv().ok_or_else(|| {
+            ControllerError::input_transport_error(
+                &endpoint.endpoint_name,
+                true,
+                anyhow!("worker thread terminated unexpectedly during initialization"),
+            )
+        })??;
+
+        Ok(Self {
+            sender,
+            inner: endpoint,
+        })
+    }
+}
+
+impl InputReader for MySqlCdcInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command {
+            InputReaderCommand::Replay { metadata, data } => self.inner.replay(metadata, data),
+            InputReaderCommand::Extend => {
+                let _ = self.sender.send_replace(PipelineState::Running);
+            }
+            InputReaderCommand::Pause => {
+                let _ = self.sender.send_replace(PipelineState::Paused);
+            }
+            InputReaderCommand::Queue { .. } => self.inner.queue(),
+            InputReaderCommand::Disconnect => {
+                let _ = self.sender.send_replace(PipelineState::Terminated);
+            }
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for MySqlCdcInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+struct MySqlCdcInputEndpointInner {
+    endpoint_name: String,
+    config: MySqlCdcReaderConfig,
+    consumer: Box<dyn InputConsumer>,
+    queue: InputQueue<QueuedStep>,
+
+    /// Position of the last step, which is also where the worker starts
+    /// reading once the pipeline runs.
+    last_resume_info: Mutex<Option<MySqlCdcResumeInfo>>,
+
+    /// Deserializer used to replay journaled steps.
+    replay_stream: Mutex<Box<dyn DeCollectionStream>>,
+}
+
+impl MySqlCdcInputEndpointInner {
+    fn queue(&self) {
+        let (total, hasher, steps) = self.queue.flush_with_aux();
+
+        let mut last_resume_info = self.last_resume_info.lock().unwrap();
+        let mut changes = Vec::new();
+        let mut watermarks = Vec::with_capacity(steps.len());
+        for (timestamp, step) in steps {
+            changes.extend(step.changes);
+            *last_resume_info = Some(step.resume_info);
+            watermarks.push(Watermark::new(timestamp, None));
+        }
+
+        let seek = last_resume_info
+            .as_ref()
+            .map_or(JsonValue::Null, |resume_info| {
+                serde_json::to_value(resume_info).unwrap()
+            });
+        let resume = match hasher {
+            Some(hasher) => Resume::Replay {
+                seek,
+                replay: rmpv::ext::to_value(ReplayData { changes }).unwrap(),
+                hash: hasher.finish(),
+            },
+            None if seek.is_null() => Resume::Barrier,
+            None => Resume::Seek { seek },
+        };
+        self.consumer.extended(total, Some(resume), watermarks);
+    }
+
+    fn replay(&self, metadata: JsonValue, data: rmpv::Value) {
+        let ReplayData { changes } = rmpv::ext::from_value(data).unwrap();
+        let mut stream = self.replay_stream.lock().unwrap();
+        for change in &changes {
+            // The changes were journaled after they were deserialized
+            // successfully, so they can't fail now.
+            let _ = match change {
+                Change::Insert(record) => stream.insert(record.as_bytes()),
+                Change::Delete(record) => stream.delete(record.as_bytes()),
+            };
+        }
+
+        let mut hasher = Xxh3Default::new();
+        let mut total = BufferSize::empty();
+        if let Some(mut buffer) = stream.take_all() {
+            total = buffer.len();
+            self.consumer.buffered(total);
+            buffer.hash(&mut hasher);
+            buffer.flush();
+        }
+
+        if !metadata.is_null() {
+            match parse_resume_info::<MySqlCdcResumeInfo>(&metadata) {
+                Ok(resume_info) => *self.last_resume_info.lock().unwrap() = Some(resume_info),
+                Err(e) => self.consumer.error(true, e, Some("mysql-cdc-replay")),
+            }
+        }
+        self.consumer.replayed(total, hasher.finish());
+    }
+
+    /// Applies `changes` to `input_stream` and pushes the result to the
+    /// queue, along with `errors`, as of `position`.
+    ///
+    /// Returns false if there was nothing to push.
+    fn push(
+        &self,
+        input_stream: &mut dyn DeCollectionStream,
+        changes: Vec<Change>,
+        mut errors: Vec<ParseError>,
+        position: &Position,
+    ) -> bool {
+        let journal = self.consumer.pipeline_fault_tolerance() == Some(FtModel::ExactlyOnce);
+        let mut journaled = Vec::new();
+        for (index, change) in changes.into_iter().enumerate() {
+            let (result, record) = match &change {
+                Change::Insert(record) => (input_stream.insert(record.as_bytes()), record),
+                Change::Delete(record) => (input_stream.delete(record.as_bytes()), record),
+            };
+            match result {
+                Ok(()) if journal => journaled.push(change),
+                Ok(()) => (),
+                Err(e) => errors.push(ParseError::text_event_error(
+                    "failed to deserialize change from MySQL",
+                    e,
+                    index as u64,
+                    Some(record),
+                    None,
+                )),
+            }
+        }
+
+        let buffer = input_stream.take_all();
+        if buffer.is_none() && errors.is_empty() {
+            return false;
+        }
+        self.queue.push_with_aux(
+            (buffer, errors),
+            Utc::now(),
+            QueuedStep {
+                resume_info: position.resume_info(),
+                changes: journaled,
+            },
+        );
+        true
+    }
+
+    async fn worker_task(
+        self: Arc<Self>,
+        input_stream: Box<dyn DeCollectionStream>,
+        schema: Relation,
+        receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let mut receiver_clone = receiver.clone();
+        select! {
+            _ = Self::worker_task_inner(self.clone(), input_stream, schema, receiver, init_status_sender) => {
+                debug!("mysql_cdc {}: worker task terminated", &self.endpoint_name);
+            }
+            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                debug!("mysql_cdc {}: received termination command; worker task canceled", &self.endpoint_name);
+            }
+        }
+    }
+
+    async fn worker_task_inner(
+        self: Arc<Self>,
+        mut input_stream: Box<dyn DeCollectionStream>,
+        schema: Relation,
+        mut receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let (mut conn, columns, gtid_mode) = match self.initialize().await {
+            Ok(result) => result,
+            Err(e) => {
+                let _ = init_status_sender
+                    .send(Err(ControllerError::input_transport_error(
+                        &self.endpoint_name,
+                        true,
+                        e,
+                    )))
+                    .await;
+                return;
+            }
+        };
+        let _ = init_status_sender.send(Ok(())).await;
+        wait_running(&mut receiver).await;
+
+        let existing_resume_info = self.last_resume_info.lock().unwrap().clone();
+        let position = match existing_resume_info {
+            Some(resume_info) => Position::from_resume_info(&resume_info),
+            None if self.config.snapshot => self
+                .read_snapshot(&mut conn, &mut *input_stream, &schema, &columns, gtid_mode)
+                .await
+                .context("error reading the initial contents of the table"),
+            None => current_position(&mut conn, gtid_mode).await,
+        };
+        let mut position = match position {
+            Ok(position) => position,
+            Err(e) => {
+                self.consumer.error(true, e, Some("mysql-cdc-init"));
+                return;
+            }
+        };
+        let _ = conn.disconnect().await;
+
+        loop {
+            wait_running(&mut receiver).await;
+            if let Err(e) = self
+                .follow(
+                    &mut *input_stream,
+                    &schema,
+                    &columns,
+                    &mut position,
+                    &mut receiver,
+                )
+                .await
+            {
+                self.consumer.error(false, e, Some("mysql-cdc-binlog"));
+            }
+            sleep(RECONNECT_DELAY).await;
+        }
+    }
+
+    /// Connects to the server and configures the session.
+    async fn connect(&self) -> AnyResult<Conn> {
+        debug!("mysql_cdc {}: opening connection", &self.endpoint_name);
+
+        let opts = Opts::from_url(&self.config.uri).context("invalid 'uri'")?;
+        let mut conn = Conn::new(opts)
+            .await
+            .context("unable to connect to MySQL server")?;
+        conn.query_drop(SESSION_SETTINGS)
+            .await
+            .context("error configuring the session")?;
+        Ok(conn)
+    }
+
+    /// Connects to the server, checks that it's configured for the
+    /// connector, and looks up the columns of the table.
+    ///
+    /// Returns the connection, the columns, and whether the server has GTIDs
+    /// enabled.
+    async fn initialize(&self) -> AnyResult<(Conn, Vec<ColumnInfo>, bool)> {
+        let mut conn = self.connect().await?;
+        info!(
+            "mysql_cdc {}: connected to MySQL server version {:?}",
+            &self.endpoint_name,
+            conn.server_version()
+        );
+
+        let variables: Vec<(String, String)> = conn
+            .query("SHOW GLOBAL VARIABLES WHERE Variable_name IN ('log_bin', 'binlog_format', 'binlog_row_image', 'gtid_mode')")
+            .await
+            .context("error reading server variables")?;
+        let variable = |name: &str| {
+            variables
+                .iter()
+                .find(|(variable, _)| variable == name)
+                .map(|(_, value)| value.to_ascii_uppercase())
+        };
+        if variable("log_bin").as_deref() == Some("OFF") {
+            bail!("binary logging is disabled on the server");
+        }
+        if variable("binlog_format").as_deref() != Some("ROW") {
+            bail!("the connector requires 'binlog_format' to be 'ROW'");
+        }
+        if variable("binlog_row_image").is_some_and(|value| value != "FULL") {
+            bail!("the connector requires 'binlog_row_image' to be 'FULL'");
+        }
+        let gtid_mode = variable("gtid_mode").as_deref() == Some("ON");
+
+        let (database, table) = self.config.table.split_once('.').unwrap();
+        let columns = conn
+            .exec_map(
+                "SELECT COLUMN_NAME, LOWER(DATA_TYPE), COLUMN_TYPE FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+                (database, table),
+                |(name, data_type, column_type): (String, String, String)| ColumnInfo {
+                    name,
+                    data_type,
+                    unsigned: column_type.to_ascii_lowercase().contains("unsigned"),
+                },
+            )
+            .await
+            .with_context(|| format!("error looking up table '{}'", self.config.table))?;
+        if columns.is_empty() {
+            bail!("table '{}' does not exist", self.config.table);
+        }
+
+        Ok((conn, columns, gtid_mode))
+    }
+
+    /// Reads the contents of the table and pushes them to the queue as a
+    /// single buffer, so that a checkpoint never includes a part of them.
+    ///
+    /// Returns the binlog position to follow changes from.
+    async fn read_snapshot(
+        &self,
+        conn: &mut Conn,
+        input_stream: &mut dyn DeCollectionStream,
+        schema: &Relation,
+        columns: &[ColumnInfo],
+        gtid_mode: bool,
+    ) -> AnyResult<Position> {
+        let (database, table) = self.config.table.split_once('.').unwrap();
+        info!(
+            "mysql_cdc {}: reading the contents of table '{}'",
+            &self.endpoint_name, &self.config.table
+        );
+
+        // Block writes briefly to start a transaction whose snapshot matches
+        // a known binlog position.
+        let timestamp = Utc::now();
+        conn.query_drop("FLUSH TABLES WITH READ LOCK")
+            .await
+            .context("error locking tables (the connector needs the RELOAD privilege to read the contents of the table)")?;
+        conn.query_drop("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .await?;
+        conn.query_drop("START TRANSACTION WITH CONSISTENT SNAPSHOT")
+            .await?;
+        let position = current_position(conn, gtid_mode).await?;
+        conn.query_drop("UNLOCK TABLES").await?;
+
+        let include = |name: &str| schema.fields.iter().any(|f| f.name == name);
+        let journal = self.consumer.pipeline_fault_tolerance() == Some(FtModel::ExactlyOnce);
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        let mut num_records = 0;
+        conn.query_iter(format!(
+            "SELECT * FROM {}.{}",
+            quote_identifier(database),
+            quote_identifier(table)
+        ))
+        .await?
+        .for_each(|row: Row| {
+            num_records += 1;
+            let record = row_to_json(
+                columns,
+                (0..columns.len()).map(|index| row.as_ref(index)),
+                |column, value: &Value| Ok(value_to_json(column, value)),
+                include,
+            );
+            let result = match &record {
+                Ok(record) => input_stream.insert(record.as_bytes()),
+                Err(e) => Err(anyhow!("{e}")),
+            };
+            match (result, record) {
+                (Ok(()), Ok(record)) if journal => changes.push(Change::Insert(record)),
+                (Ok(()), _) => (),
+                (Err(e), record) => errors.push(ParseError::text_event_error(
+                    "failed to deserialize table record from MySQL",
+                    e,
+                    num_records,
+                    record.ok().as_deref(),
+                    None,
+                )),
+            }
+        })
+        .await?;
+        conn.query_drop("COMMIT").await?;
+
+        info!(
+            "mysql_cdc {}: read {num_records} records from table '{}'",
+            &self.endpoint_name, &self.config.table
+        );
+        self.queue.push_with_aux(
+            (input_stream.take_all(), errors),
+            timestamp,
+            QueuedStep {
+                resume_info: position.resume_info(),
+                changes,
+            },
+        );
+        Ok(position)
+    }
+
+    /// Reads the binlog from `position`, pushes the changes to the table to
+    /// the queue, and updates `position`, until an error occurs.
+    async fn follow(
+        &self,
+        input_stream: &mut dyn DeCollectionStream,
+        schema: &Relation,
+        columns: &[ColumnInfo],
+        position: &mut Position,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        let (database, table) = self.config.table.split_once('.').unwrap();
+        let include = |name: &str| schema.fields.iter().any(|f| f.name == name);
+
+        let conn = self.connect().await?;
+        let request = match &position {
+            Position::Gtid(gtid_set) => BinlogStreamRequest::new(self.config.server_id)
+                .with_gtid()
+                .with_gtid_set(gtid_set.to_sids()),
+            Position::File { name, position } => BinlogStreamRequest::new(self.config.server_id)
+                .with_filename(name.as_bytes())
+                .with_pos(*position),
+        };
+        let mut stream = conn
+            .get_binlog_stream(request)
+            .await
+            .context("error requesting binlog")?;
+        info!("mysql_cdc {}: reading binlog", &self.endpoint_name);
+
+        let mut file = match &position {
+            Position::File { name, .. } => name.clone(),
+            Position::Gtid(_) => String::new(),
+        };
+        let mut gtid = None;
+        let mut in_transaction = false;
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        let mut advanced = false;
+        let mut last_position_update = Instant::now();
+        loop {
+            wait_running(receiver).await;
+            let Some(event) = stream.next().await else {
+                bail!("the server closed the binlog connection");
+            };
+            let event = event.context("error reading binlog")?;
+            let end_position = event.header().log_pos() as u64;
+
+            let mut commit = false;
+            match event.read_data().context("error decoding binlog event")? {
+                Some(EventData::RotateEvent(rotate)) => file = rotate.name().into_owned(),
+                Some(EventData::GtidEvent(event)) => {
+                    gtid = Some((Uuid::from_bytes(event.sid()), event.gno()));
+                }
+                Some(EventData::QueryEvent(event)) => match event.query().as_ref() {
+                    "BEGIN" => {
+                        in_transaction = true;
+                        changes.clear();
+                        errors.clear();
+                    }
+                    "COMMIT" => commit = true,
+                    // A statement outside of a transaction, e.g., DDL, is a
+                    // transaction of its own.
+                    _ => commit = !in_transaction,
+                },
+                Some(EventData::XidEvent(_)) => commit = true,
+                Some(EventData::RowsEvent(rows)) => {
+                    let Some(tme) = stream.get_tme(rows.table_id()) else {
+                        continue;
+                    };
+                    if tme.database_name() != database || tme.table_name() != table {
+                        continue;
+                    }
+                    let convert = |row: &BinlogRow| {
+                        row_to_json(
+                            columns,
+                            (0..columns.len()).map(|index| row.as_ref(index)),
+                            binlog_value_to_json,
+                            include,
+                        )
+                    };
+                    for row in rows.rows(tme) {
+                        let (before, after) = row.context("error decoding binlog row")?;
+                        for (row, insert) in [(before, false), (after, true)] {
+                            let Some(row) = row else {
+                                continue;
+                            };
+                            match convert(&row) {
+                                Ok(record) if insert => changes.push(Change::Insert(record)),
+                                Ok(record) => changes.push(Change::Delete(record)),
+                                Err(e) => errors.push(ParseError::text_envelope_error(e, "", None)),
+                            }
+                        }
+                    }
+                }
+                _ => (),
+            }
+            if !commit {
+                continue;
+            }
+
+            in_transaction = false;
+            match position {
+                Position::Gtid(gtid_set) => {
+                    if let Some((uuid, gno)) = gtid.take() {
+                        gtid_set.add(uuid, gno);
+                    }
+                }
+                Position::File { name, position } => {
+                    name.clone_from(&file);
+                    *position = end_position;
+                }
+            }
+            advanced = !self.push(
+                input_stream,
+                std::mem::take(&mut changes),
+                std::mem::take(&mut errors),
+                position,
+            );
+
+            // Record the position after transactions that didn't change the
+            // table, so that the connector doesn't have to read them again
+            // after resuming.
+            if advanced && last_position_update.elapsed() >= POSITION_UPDATE_INTERVAL {
+                self.queue.push_with_aux(
+                    (None, Vec::new()),
+                    Utc::now(),
+                    QueuedStep {
+                        resume_info: position.resume_info(),
+                        changes: Vec::new(),
+                    },
+                );
+                advanced = false;
+                last_position_update = Instant::now();
+            }
+        }
+    }
+}
+
+/// Returns the current binlog position of the server.
+async fn current_position(conn: &mut Conn, gtid_mode: bool) -> AnyResult<Position> {
+    if gtid_mode {
+        let gtid_set: Option<String> = conn
+            .query_first("SELECT @@GLOBAL.gtid_executed")
+            .await
+            .context("error reading 'gtid_executed'")?;
+        return Ok(Position::Gtid(
+            GtidSet::parse(&gtid_set.unwrap_or_default()).map_err(|e| anyhow!(e))?,
+        ));
+    }
+
+    // MySQL 8.4 replaced `SHOW MASTER STATUS`, which MariaDB still uses.
+    let status: Option<Row> = match conn.query_first("SHOW BINARY LOG STATUS").await {
+        Ok(status) => status,
+        Err(_) => conn
+            .query_first("SHOW MASTER STATUS")
+            .await
+            .context("error reading binlog position")?,
+    };
+    let status = status.ok_or_else(|| anyhow!("binary logging is disabled on the server"))?;
+    match (status.get::<String, _>(0), status.get::<u64, _>(1)) {
+        (Some(name), Some(position)) => Ok(Position::File { name, position }),
+        _ => bail!("unexpected binlog status returned by the server"),
+    }
+}
+
+/// Block until the state is `Running`.
+async fn wait_running(receiver: &mut Receiver<PipelineState>) {
+    // An error indicates that the channel was closed.  It's ok to ignore
+    // the error as this situation will be handled by the top-level select,
+    // which will abort the worker thread.
+    let _ = receiver
+        .wait_for(|state| state == &PipelineState::Running)
+        .await;
+}
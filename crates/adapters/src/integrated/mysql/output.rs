@@ -0,0 +1,559 @@
+//! MySQL output connector.
+//!
+//! The connector buffers the changes in each step and writes them as a single
+//! transaction when the step ends, so that it can retry the whole transaction
+//! if the server aborts it to resolve a deadlock.
+
+use super::{quote_identifier, quote_table_name};
+use crate::catalog::{RecordFormat, SerBatchReader};
+use crate::controller::{ControllerInner, EndpointId};
+use crate::format::{Encoder, OutputConsumer};
+use crate::transport::OutputEndpoint;
+use crate::util::{indexed_operation_type, IndexedOperationType};
+use crate::ControllerError;
+use anyhow::{anyhow, Result as AnyResult};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{AsyncErrorCallback, Step};
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::{Field, Relation, SqlType};
+use feldera_types::transport::mysql::MySqlWriterConfig;
+use mysql_async::prelude::Queryable;
+use mysql_async::{
+    DriverError, Error as MySqlError, Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts, TxOpts,
+    Value,
+};
+use serde_json::{Map, Value as JsonValue};
+use std::sync::Weak;
+use std::time::Duration;
+use tracing::{info, info_span, span::EnteredSpan, warn};
+
+/// Default for [`MySqlWriterConfig::max_records_in_buffer`].
+const DEFAULT_MAX_RECORDS_IN_BUFFER: usize = 1000;
+
+/// Default for [`MySqlWriterConfig::max_connections`].
+const DEFAULT_MAX_CONNECTIONS: usize = 4;
+
+/// Default for [`MySqlWriterConfig::max_retries`].
+const DEFAULT_MAX_RETRIES: u32 = 10;
+
+/// The maximum number of parameters in a prepared statement.
+const MAX_PARAMETERS: usize = u16::MAX as usize;
+
+/// Server error codes for transactions that can succeed if retried.
+const ER_LOCK_WAIT_TIMEOUT: u16 = 1205;
+const ER_LOCK_DEADLOCK: u16 = 1213;
+
+/// Integrated output connector that writes a view to a MySQL table.
+pub struct MySqlOutputEndpoint {
+    endpoint_id: EndpointId,
+    endpoint_name: String,
+    config: MySqlWriterConfig,
+    controller: Weak<ControllerInner>,
+    pool: Pool,
+    value_schema: Relation,
+    key_schema: Relation,
+
+    /// Quoted names of the table and its columns.
+    table: String,
+    columns: Vec<String>,
+    key_columns: Vec<String>,
+
+    /// The maximum number of records in a statement.
+    max_records: usize,
+
+    /// Rows to insert or update, and keys of rows to delete, in the current
+    /// step.
+    upserts: Vec<Vec<Value>>,
+    deletes: Vec<Vec<Value>>,
+    num_bytes: usize,
+
+    /// The step of the current batch.
+    step: Step,
+    /// The first step that hasn't been written to the table yet, according to
+    /// the metadata table.  Always 0 without a metadata table.
+    next_step: Step,
+    /// Whether the current batch was already written in a previous run.
+    skip_step: bool,
+}
+
+impl MySqlOutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &MySqlWriterConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let key_schema = key_schema.to_owned().ok_or(ControllerError::not_supported(
+            "MySQL output connector requires the view to have a unique key. Please specify the `index` property in the connector configuration. For more details, see: https://docs.feldera.com/connectors/unique_keys",
+        ))?;
+
+        let opts = Opts::from_url(&config.uri).map_err(|e| {
+            ControllerError::invalid_transport_configuration(
+                endpoint_name,
+                &format!("error parsing MySQL URI: {e}"),
+            )
+        })?;
+        let max_connections = config.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS);
+        let constraints = PoolConstraints::new(0, max_connections).unwrap();
+        let pool = Pool::new(
+            OptsBuilder::from_opts(opts)
+                .pool_opts(PoolOpts::default().with_constraints(constraints)),
+        );
+
+        let columns = value_schema
+            .fields
+            .iter()
+            .map(|f| quote_identifier(&f.name.name()))
+            .collect::<Vec<_>>();
+        let key_columns = key_schema
+            .fields
+            .iter()
+            .map(|f| quote_identifier(&f.name.name()))
+            .collect::<Vec<_>>();
+        let max_records = config
+            .max_records_in_buffer
+            .unwrap_or(DEFAULT_MAX_RECORDS_IN_BUFFER)
+            .min(MAX_PARAMETERS / columns.len().max(1));
+
+        let mut endpoint = Self {
+            endpoint_id,
+            endpoint_name: endpoint_name.to_owned(),
+            config: config.clone(),
+            controller,
+            pool,
+            table: quote_table_name(&config.table),
+            columns,
+            key_columns,
+            max_records,
+            value_schema: value_schema.to_owned(),
+            key_schema,
+            upserts: Vec::new(),
+            deletes: Vec::new(),
+            num_bytes: 0,
+            step: 0,
+            next_step: 0,
+            skip_step: false,
+        };
+
+        let _guard = endpoint.span();
+        endpoint.next_step = TOKIO
+            .block_on(endpoint.initialize())
+            .map_err(|e| ControllerError::output_transport_error(endpoint_name, true, e))?;
+
+        Ok(endpoint)
+    }
+
+    fn span(&self) -> EnteredSpan {
+        info_span!(
+            "mysql_output",
+            ft = self.config.metadata_table.is_some(),
+            id = self.endpoint_id,
+            name = self.endpoint_name,
+            mysql_table = self.config.table,
+        )
+        .entered()
+    }
+
+    /// Checks that the table exists and has the view's columns, and returns
+    /// the first step that hasn't been written to it yet.
+    async fn initialize(&self) -> AnyResult<Step> {
+        let mut conn = self
+            .pool
+            .get_conn()
+            .await
+            .map_err(|e| anyhow!("unable to connect to MySQL server: {e}"))?;
+
+        conn.query_drop(format!(
+            "SELECT {} FROM {} LIMIT 0",
+            self.columns.join(", "),
+            self.table
+        ))
+        .await
+        .map_err(|e| {
+            anyhow!("error accessing table '{}': {e}\nPlease ensure that the table exists and has a column for each column of the view", self.config.table)
+        })?;
+
+        let Some(metadata_table) = &self.config.metadata_table else {
+            return Ok(0);
+        };
+        let metadata_table_name = quote_table_name(metadata_table);
+        conn.query_drop(format!(
+            "CREATE TABLE IF NOT EXISTS {metadata_table_name} (table_name VARCHAR(255) PRIMARY KEY, step BIGINT UNSIGNED NOT NULL)"
+        ))
+        .await
+        .map_err(|e| anyhow!("failed to create metadata table '{metadata_table}': {e}"))?;
+        let step: Option<u64> = conn
+            .exec_first(
+                format!("SELECT step FROM {metadata_table_name} WHERE table_name = ?"),
+                (self.config.table.as_str(),),
+            )
+            .await
+            .map_err(|e| anyhow!("failed to read metadata table '{metadata_table}': {e}"))?;
+        Ok(step.map_or(0, |step| step + 1))
+    }
+
+    /// Writes the changes in the current step in a single transaction.
+    async fn write_step(&self) -> Result<(), MySqlError> {
+        let mut conn = self.pool.get_conn().await?;
+        let mut transaction = conn.start_transaction(TxOpts::default()).await?;
+
+        for keys in self.deletes.chunks(self.max_records) {
+            transaction
+                .exec_drop(
+                    delete_query(&self.table, &self.key_columns, keys.len()),
+                    keys.concat(),
+                )
+                .await?;
+        }
+        for rows in self.upserts.chunks(self.max_records) {
+            transaction
+                .exec_drop(
+                    upsert_query(&self.table, &self.columns, rows.len()),
+                    rows.concat(),
+                )
+                .await?;
+        }
+
+        // Record the step in the same transaction, so that it is committed
+        // if and only if the step's changes are.
+        if let Some(metadata_table) = &self.config.metadata_table {
+            transaction
+                .exec_drop(
+                    format!(
+                        "INSERT INTO {} (table_name, step) VALUES (?, ?) ON DUPLICATE KEY UPDATE step = VALUES(step)",
+                        quote_table_name(metadata_table)
+                    ),
+                    (self.config.table.as_str(), self.step),
+                )
+                .await?;
+        }
+
+        transaction.commit().await
+    }
+
+    fn batch_end_inner(&mut self) -> AnyResult<()> {
+        if self.upserts.is_empty()
+            && self.deletes.is_empty()
+            && self.config.metadata_table.is_none()
+        {
+            return Ok(());
+        }
+
+        let max_retries = self.config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let mut retries = 0;
+        loop {
+            match TOKIO.block_on(self.write_step()) {
+                Ok(()) => break,
+                Err(e) if retries < max_retries && is_transient(&e) => {
+                    retries += 1;
+                    warn!(
+                        "mysql: retrying step {} ({retries}/{max_retries}) after error: {e}",
+                        self.step
+                    );
+                    std::thread::sleep(
+                        Duration::from_millis(100 << retries.min(6)).min(Duration::from_secs(5)),
+                    );
+                }
+                Err(e) => {
+                    return Err(anyhow!(
+                        "mysql: failed to write step {} to table '{}': {e}",
+                        self.step,
+                        self.config.table
+                    ))
+                }
+            }
+        }
+        self.next_step = self.step + 1;
+        Ok(())
+    }
+}
+
+/// Returns true if a transaction that failed with `error` can succeed if
+/// retried.
+fn is_transient(error: &MySqlError) -> bool {
+    match error {
+        MySqlError::Server(error) => {
+            error.code == ER_LOCK_DEADLOCK || error.code == ER_LOCK_WAIT_TIMEOUT
+        }
+        MySqlError::Io(_) | MySqlError::Driver(DriverError::ConnectionClosed) => true,
+        _ => false,
+    }
+}
+
+/// Returns a statement that inserts `num_rows` rows into `table`, updating
+/// rows with the same key.
+fn upsert_query(table: &str, columns: &[String], num_rows: usize) -> String {
+    let row = format!("({})", vec!["?"; columns.len()].join(", "));
+    let updates = columns
+        .iter()
+        .map(|column| format!("{column} = VALUES({column})"))
+        .collect::<Vec<_>>();
+    format!(
+        "INSERT INTO {table} ({}) VALUES {} ON DUPLICATE KEY UPDATE {}",
+        columns.join(", "),
+        vec![row; num_rows].join(", "),
+        updates.join(", ")
+    )
+}
+
+/// Returns a statement that deletes the rows of `table` with `num_keys` keys.
+fn delete_query(table: &str, key_columns: &[String], num_keys: usize) -> String {
+    let key = format!("({})", vec!["?"; key_columns.len()].join(", "));
+    format!(
+        "DELETE FROM {table} WHERE ({}) IN ({})",
+        key_columns.join(", "),
+        vec![key; num_keys].join(", ")
+    )
+}
+
+/// Decodes a Postgres-style hex string, e.g., `\x0aff`.
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let hex = s.strip_prefix("\\x")?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Converts the JSON representation of a value of `field` into a MySQL value.
+fn json_to_value(field: &Field, value: JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::NULL,
+        JsonValue::Bool(b) => Value::from(b),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::Int(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::UInt(u)
+            } else {
+                Value::Double(n.as_f64().unwrap_or_default())
+            }
+        }
+        JsonValue::String(s) => match field.columntype.typ {
+            SqlType::Binary | SqlType::Varbinary => {
+                Value::Bytes(decode_hex(&s).unwrap_or_else(|| s.into_bytes()))
+            }
+            _ => Value::Bytes(s.into_bytes()),
+        },
+        // Arrays, maps, and user-defined types are stored as JSON.
+        value => Value::Bytes(value.to_string().into_bytes()),
+    }
+}
+
+/// Converts a JSON record into the values of `fields`, in order.
+fn record_to_values(fields: &[Field], record: &[u8]) -> AnyResult<Vec<Value>> {
+    let mut object: Map<String, JsonValue> = serde_json::from_slice(record)?;
+    Ok(fields
+        .iter()
+        .map(|field| {
+            json_to_value(
+                field,
+                object.remove(&field.name.name()).unwrap_or(JsonValue::Null),
+            )
+        })
+        .collect())
+}
+
+impl OutputConsumer for MySqlOutputEndpoint {
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn batch_start(&mut self, step: Step) {
+        self.step = step;
+        self.skip_step = step < self.next_step;
+        self.upserts.clear();
+        self.deletes.clear();
+        if self.skip_step {
+            info!("mysql: skipping step {step} that was already written in a previous run");
+        }
+    }
+
+    fn push_buffer(&mut self, _: &[u8], _: usize) {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _: Option<&[u8]>,
+        _: Option<&[u8]>,
+        _: &[(&str, Option<&[u8]>)],
+        _num_records: usize,
+    ) {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) {
+        if self.skip_step {
+            return;
+        }
+
+        let _guard = self.span();
+        let num_rows = self.upserts.len() + self.deletes.len();
+        let result = self.batch_end_inner();
+        self.upserts.clear();
+        self.deletes.clear();
+
+        let Some(controller) = self.controller.upgrade() else {
+            warn!("controller is shutting down: aborting");
+            return;
+        };
+        match result {
+            Ok(()) => controller.status.output_buffer(
+                self.endpoint_id,
+                std::mem::take(&mut self.num_bytes),
+                num_rows,
+            ),
+            Err(e) => {
+                self.num_bytes = 0;
+                controller.output_transport_error(
+                    self.endpoint_id,
+                    &self.endpoint_name,
+                    true,
+                    e,
+                    Some("mysql_batch_end"),
+                );
+            }
+        }
+    }
+}
+
+impl Encoder for MySqlOutputEndpoint {
+    fn consumer(&mut self) -> &mut dyn OutputConsumer {
+        self
+    }
+
+    fn encode(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        if self.skip_step {
+            return Ok(());
+        }
+
+        let mut cursor = batch.cursor(RecordFormat::Json(JsonFlavor::Postgres))?;
+        while cursor.key_valid() {
+            if let Some(op) = indexed_operation_type(
+                &self.value_schema.name,
+                &self.key_schema.name,
+                cursor.as_mut(),
+            )? {
+                cursor.rewind_vals();
+                let mut buf = Vec::new();
+                match op {
+                    IndexedOperationType::Insert => {
+                        cursor.serialize_val(&mut buf)?;
+                        self.upserts
+                            .push(record_to_values(&self.value_schema.fields, &buf)?);
+                    }
+                    IndexedOperationType::Delete => {
+                        cursor.serialize_key(&mut buf)?;
+                        self.deletes
+                            .push(record_to_values(&self.key_schema.fields, &buf)?);
+                    }
+                    IndexedOperationType::Upsert => {
+                        if cursor.weight() < 0 {
+                            cursor.step_val();
+                        }
+                        cursor.serialize_val(&mut buf)?;
+                        self.upserts
+                            .push(record_to_values(&self.value_schema.fields, &buf)?);
+                    }
+                }
+                self.num_bytes += buf.len();
+            }
+            cursor.step_key();
+        }
+
+        Ok(())
+    }
+}
+
+impl OutputEndpoint for MySqlOutputEndpoint {
+    fn connect(&mut self, _: AsyncErrorCallback) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        todo!()
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8]) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        self.config.metadata_table.is_some()
+    }
+
+    fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_hex, delete_query, record_to_values, upsert_query};
+    use feldera_types::program_schema::{ColumnType, Field};
+    use mysql_async::Value;
+
+    #[test]
+    fn test_queries() {
+        let columns = vec!["`id`".to_string(), "`s`".to_string()];
+        assert_eq!(
+            upsert_query("`t`", &columns, 2),
+            "INSERT INTO `t` (`id`, `s`) VALUES (?, ?), (?, ?) ON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `s` = VALUES(`s`)"
+        );
+        assert_eq!(
+            delete_query("`db`.`t`", &columns[..1], 3),
+            "DELETE FROM `db`.`t` WHERE (`id`) IN ((?), (?), (?))"
+        );
+    }
+
+    #[test]
+    fn test_record_to_values() {
+        let fields = vec![
+            Field::new("id".into(), ColumnType::bigint(false)),
+            Field::new("b".into(), ColumnType::boolean(true)),
+            Field::new("bin".into(), ColumnType::varbinary(true)),
+            Field::new("s".into(), ColumnType::varchar(true)),
+        ];
+        assert_eq!(
+            record_to_values(
+                &fields,
+                br#"{"id": 1, "b": true, "bin": "\\x00ff", "s": "x"}"#
+            )
+            .unwrap(),
+            vec![
+                Value::Int(1),
+                Value::Int(1),
+                Value::Bytes(vec![0, 255]),
+                Value::Bytes(b"x".to_vec())
+            ]
+        );
+        assert_eq!(
+            record_to_values(&fields, br#"{"id": 2}"#).unwrap(),
+            vec![Value::Int(2), Value::NULL, Value::NULL, Value::NULL]
+        );
+        assert_eq!(decode_hex("\\x0"), None);
+    }
+}
@@ -0,0 +1,258 @@
+//! Conversion of MySQL values into the JSON representation that the
+//! connector's JSON flavor expects.
+
+use chrono::DateTime;
+use mysql_async::binlog::value::BinlogValue;
+use mysql_async::Value;
+use serde_json::{Map, Number, Value as JsonValue};
+use std::fmt::Write;
+
+/// A column of the table, as described by `information_schema.COLUMNS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ColumnInfo {
+    pub name: String,
+
+    /// `DATA_TYPE`, in lowercase, e.g., `varchar`.
+    pub data_type: String,
+
+    /// Whether the column has an unsigned integer type.
+    pub unsigned: bool,
+}
+
+/// Formats binary data as a Postgres-style hex string, e.g., `\x0aff`.
+fn hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + bytes.len() * 2);
+    s.push_str("\\x");
+    for byte in bytes {
+        write!(s, "{byte:02x}").unwrap();
+    }
+    s
+}
+
+/// Converts a value in MySQL's text format, or a binary-format value that the
+/// binlog represents as bytes, into JSON.
+fn bytes_to_json(column: &ColumnInfo, bytes: &[u8]) -> JsonValue {
+    let text = || String::from_utf8_lossy(bytes);
+    match column.data_type.as_str() {
+        "tinyint" | "smallint" | "mediumint" | "int" | "bigint" | "float" | "double" | "year" => {
+            text()
+                .parse::<Number>()
+                .map(JsonValue::Number)
+                .unwrap_or_else(|_| JsonValue::String(text().into_owned()))
+        }
+        "bit" => JsonValue::from(
+            bytes
+                .iter()
+                .fold(0u64, |acc, &byte| (acc << 8) | byte as u64),
+        ),
+        "json" => {
+            serde_json::from_slice(bytes).unwrap_or_else(|_| JsonValue::String(text().into_owned()))
+        }
+        "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => {
+            JsonValue::String(hex(bytes))
+        }
+        // The binlog represents `TIMESTAMP` values as seconds since the
+        // epoch, with a fractional part if the column has one, whereas the
+        // text format, with the session time zone set to UTC, is the same as
+        // for `DATETIME`.
+        "timestamp" => {
+            let text = text();
+            let (secs, frac) = text.split_once('.').unwrap_or((&text, ""));
+            let timestamp = match (secs.parse::<i64>(), format!("{frac:0<9}").parse::<u32>()) {
+                (Ok(secs), Ok(nanos)) if frac.len() <= 9 => DateTime::from_timestamp(secs, nanos),
+                _ => None,
+            };
+            match timestamp {
+                Some(timestamp) => JsonValue::String(timestamp.format("%F %T%.f").to_string()),
+                None => JsonValue::String(text.into_owned()),
+            }
+        }
+        _ => JsonValue::String(text().into_owned()),
+    }
+}
+
+/// Converts `value` of `column` into JSON.
+pub(super) fn value_to_json(column: &ColumnInfo, value: &Value) -> JsonValue {
+    match value {
+        Value::NULL => JsonValue::Null,
+        // The binlog doesn't say whether integers are signed.
+        Value::Int(i) if column.unsigned && *i < 0 => {
+            let bits = match column.data_type.as_str() {
+                "tinyint" => 8,
+                "smallint" => 16,
+                "mediumint" => 24,
+                "int" => 32,
+                _ => 64,
+            };
+            JsonValue::from((*i as u64) & (u64::MAX >> (64 - bits)))
+        }
+        Value::Int(i) => JsonValue::from(*i),
+        Value::UInt(u) => JsonValue::from(*u),
+        Value::Float(f) => JsonValue::from(*f),
+        Value::Double(d) => JsonValue::from(*d),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            if column.data_type == "date" {
+                JsonValue::String(format!("{year:04}-{month:02}-{day:02}"))
+            } else {
+                JsonValue::String(format!(
+                    "{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}.{micros:06}"
+                ))
+            }
+        }
+        Value::Time(negative, days, hours, minutes, seconds, micros) => JsonValue::String(format!(
+            "{}{:02}:{minutes:02}:{seconds:02}.{micros:06}",
+            if *negative { "-" } else { "" },
+            *days * 24 + *hours as u32
+        )),
+        Value::Bytes(bytes) => bytes_to_json(column, bytes),
+    }
+}
+
+/// Converts a value from a binlog row event into JSON.
+pub(super) fn binlog_value_to_json(
+    column: &ColumnInfo,
+    value: &BinlogValue<'_>,
+) -> Result<JsonValue, String> {
+    match value {
+        BinlogValue::Value(value) => Ok(value_to_json(column, value)),
+        BinlogValue::Jsonb(value) => JsonValue::try_from(value.clone())
+            .map_err(|e| format!("error decoding the value of JSON column '{}': {e}", column.name)),
+        BinlogValue::JsonDiff(_) => Err(format!(
+            "column '{}' has a partial JSON update, which the connector does not support; set 'binlog_row_value_options' to '' on the server",
+            column.name
+        )),
+    }
+}
+
+/// Converts a row of the table into a JSON object, leaving out the columns
+/// for which `include` returns false.
+pub(super) fn row_to_json<V>(
+    columns: &[ColumnInfo],
+    values: impl IntoIterator<Item = Option<V>>,
+    convert: impl Fn(&ColumnInfo, V) -> Result<JsonValue, String>,
+    include: impl Fn(&str) -> bool,
+) -> Result<String, String> {
+    let mut object = Map::new();
+    let mut values = values.into_iter();
+    for column in columns {
+        let value = values.next().flatten().ok_or_else(|| {
+            format!(
+                "the row has no value for column '{}'; set 'binlog_row_image' to 'FULL' on the server, and restart the connector after changing the schema of the table",
+                column.name
+            )
+        })?;
+        if include(&column.name) {
+            object.insert(column.name.clone(), convert(column, value)?);
+        }
+    }
+    Ok(JsonValue::Object(object).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{row_to_json, value_to_json, ColumnInfo};
+    use mysql_async::Value;
+    use serde_json::json;
+
+    fn column(data_type: &str, unsigned: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: "c".to_string(),
+            data_type: data_type.to_string(),
+            unsigned,
+        }
+    }
+
+    fn bytes(s: &str) -> Value {
+        Value::Bytes(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_value_to_json() {
+        assert_eq!(
+            value_to_json(&column("int", false), &Value::NULL),
+            json!(null)
+        );
+        assert_eq!(
+            value_to_json(&column("int", false), &Value::Int(-1)),
+            json!(-1)
+        );
+        assert_eq!(
+            value_to_json(&column("int", true), &Value::Int(-1)),
+            json!(4294967295u64)
+        );
+        assert_eq!(
+            value_to_json(&column("tinyint", true), &Value::Int(-56)),
+            json!(200)
+        );
+        assert_eq!(
+            value_to_json(&column("bigint", false), &bytes("42")),
+            json!(42)
+        );
+        assert_eq!(
+            value_to_json(&column("decimal", false), &bytes("12.50")),
+            json!("12.50")
+        );
+        assert_eq!(
+            value_to_json(&column("varbinary", false), &Value::Bytes(vec![0, 255])),
+            json!("\\x00ff")
+        );
+        assert_eq!(
+            value_to_json(&column("bit", false), &Value::Bytes(vec![1, 2])),
+            json!(258)
+        );
+        assert_eq!(
+            value_to_json(&column("json", false), &bytes(r#"{"a": [1]}"#)),
+            json!({"a": [1]})
+        );
+        assert_eq!(
+            value_to_json(&column("date", false), &Value::Date(2025, 1, 2, 0, 0, 0, 0)),
+            json!("2025-01-02")
+        );
+        assert_eq!(
+            value_to_json(
+                &column("datetime", false),
+                &Value::Date(2025, 1, 2, 3, 4, 5, 6)
+            ),
+            json!("2025-01-02 03:04:05.000006")
+        );
+        assert_eq!(
+            value_to_json(&column("time", false), &Value::Time(false, 1, 2, 3, 4, 0)),
+            json!("26:03:04.000000")
+        );
+        assert_eq!(
+            value_to_json(&column("timestamp", false), &bytes("1735689600.5")),
+            json!("2025-01-01 00:00:00.500")
+        );
+        assert_eq!(
+            value_to_json(&column("timestamp", false), &bytes("2025-01-01 00:00:00")),
+            json!("2025-01-01 00:00:00")
+        );
+    }
+
+    #[test]
+    fn test_row_to_json() {
+        let columns = vec![
+            ColumnInfo {
+                name: "id".to_string(),
+                data_type: "int".to_string(),
+                unsigned: false,
+            },
+            ColumnInfo {
+                name: "note".to_string(),
+                data_type: "varchar".to_string(),
+                unsigned: false,
+            },
+        ];
+        let convert = |column: &ColumnInfo, value: &Value| Ok(value_to_json(column, value));
+        let row = [Value::Int(1), bytes("x")];
+        assert_eq!(
+            row_to_json(&columns, row.iter().map(Some), convert, |_| true).unwrap(),
+            json!({"id": 1, "note": "x"}).to_string()
+        );
+        assert_eq!(
+            row_to_json(&columns, row.iter().map(Some), convert, |name| name == "id").unwrap(),
+            json!({"id": 1}).to_string()
+        );
+        assert!(row_to_json(&columns, [Some(&Value::Int(1))], convert, |_| true).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::transport::nats::connect;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, IntegratedInputEndpoint, NonFtInputReaderCommand,
+};
+use crate::{InputConsumer, InputReader, PipelineState, RecordFormat};
+use anyhow::{anyhow, Error as AnyError, Result as AnyResult};
+use async_nats::jetstream::{
+    self,
+    kv::{Operation, Watch},
+};
+use bytes::Bytes;
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::catalog::{DeCollectionStream, InputCollectionHandle};
+use feldera_adapterlib::format::ParseError;
+use feldera_types::config::FtModel;
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::transport::nats::NatsKvInputConfig;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::thread;
+use tokio::select;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::{debug, info, info_span, Instrument};
+
+#[cfg(all(test, feature = "nats-test"))]
+mod test;
+
+/// Integrated input connector that mirrors a NATS JetStream key-value bucket
+/// into a table.
+pub struct NatsKvInputEndpoint {
+    endpoint_name: String,
+    config: NatsKvInputConfig,
+    consumer: Box<dyn InputConsumer>,
+}
+
+impl NatsKvInputEndpoint {
+    pub fn new(
+        endpoint_name: &str,
+        config: &NatsKvInputConfig,
+        consumer: Box<dyn InputConsumer>,
+    ) -> Self {
+        Self {
+            endpoint_name: endpoint_name.to_string(),
+            config: config.clone(),
+            consumer,
+        }
+    }
+}
+
+impl InputEndpoint for NatsKvInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl IntegratedInputEndpoint for NatsKvInputEndpoint {
+    fn open(
+        self: Box<Self>,
+        input_handle: &InputCollectionHandle,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(NatsKvInputReader::new(*self, input_handle)?))
+    }
+}
+
+struct NatsKvInputReader {
+    command_sender: UnboundedSender<NonFtInputReaderCommand>,
+}
+
+impl NatsKvInputReader {
+    fn new(endpoint: NatsKvInputEndpoint, input_handle: &InputCollectionHandle) -> AnyResult<Self> {
+        endpoint.config.validate().map_err(|e| anyhow!(e))?;
+
+        let input_stream = input_handle
+            .handle
+            .configure_deserializer(RecordFormat::Json(JsonFlavor::Default))?;
+
+        let span = info_span!(
+            "nats_kv_input",
+            endpoint = endpoint.endpoint_name.clone(),
+            bucket = endpoint.config.bucket.clone()
+        );
+        let watch = TOKIO.block_on(
+            Self::watch(&endpoint.endpoint_name, &endpoint.config).instrument(span.clone()),
+        )?;
+
+        let (command_sender, command_receiver) = unbounded_channel();
+        let consumer = endpoint.consumer;
+        thread::Builder::new()
+            .name("nats-kv-input-tokio-wrapper".to_string())
+            .spawn(move || {
+                TOKIO.block_on(async {
+                    Self::worker_task(watch, input_stream, consumer.clone(), command_receiver)
+                        .instrument(span)
+                        .await
+                        .unwrap_or_else(|e| consumer.error(true, e, None));
+                })
+            })
+            .expect("failed to spawn NATS KV input tokio wrapper thread");
+
+        Ok(Self { command_sender })
+    }
+
+    /// Connects to the server and starts watching the bucket.
+    ///
+    /// Watching with history delivers the latest value of every existing key
+    /// before any new updates, which gives us the initial snapshot.
+    async fn watch(endpoint_name: &str, config: &NatsKvInputConfig) -> AnyResult<Watch> {
+        let client = connect(&config.connection_config, endpoint_name).await?;
+        let store = jetstream::new(client)
+            .get_key_value(&config.bucket)
+            .await
+            .map_err(|e| anyhow!("error opening key-value bucket '{}': {e}", config.bucket))?;
+
+        let keys = config.keys.as_deref().unwrap_or(">");
+        let watch = if config.snapshot {
+            store.watch_with_history(keys).await
+        } else {
+            store.watch(keys).await
+        }
+        .map_err(|e| anyhow!("error watching key-value bucket '{}': {e}", config.bucket))?;
+
+        info!(
+            "NATS KV input: watching keys '{keys}' in bucket '{}'",
+            config.bucket
+        );
+        Ok(watch)
+    }
+
+    async fn worker_task(
+        mut watch: Watch,
+        mut input_stream: Box<dyn DeCollectionStream>,
+        consumer: Box<dyn InputConsumer>,
+        mut command_receiver: UnboundedReceiver<NonFtInputReaderCommand>,
+    ) -> Result<(), AnyError> {
+        let queue = InputQueue::new(consumer.clone());
+
+        // Last value ingested for each key.  A KV delete carries no value, so
+        // we need these to retract the corresponding record from the table.
+        let mut values: HashMap<String, Bytes> = HashMap::new();
+        let mut num_entries = 0;
+        let mut running = false;
+
+        loop {
+            select! {
+                command = command_receiver.recv() => match command {
+                    None | Some(NonFtInputReaderCommand::Transition(PipelineState::Terminated)) => {
+                        debug!("NATS KV input: received termination command");
+                        return Ok(());
+                    }
+                    Some(NonFtInputReaderCommand::Queue) => queue.queue(),
+                    Some(NonFtInputReaderCommand::Transition(state)) => {
+                        running = state == PipelineState::Running;
+                    }
+                },
+                entry = watch.next(), if running => {
+                    let Some(entry) = entry else {
+                        consumer.eoi();
+                        return Ok(());
+                    };
+                    let entry = entry.map_err(|e| anyhow!("error watching key-value bucket: {e}"))?;
+                    let timestamp = Utc::now();
+                    num_entries += 1;
+
+                    let mut errors = Vec::new();
+                    if let Some(old_value) = values.remove(&entry.key) {
+                        if let Err(e) = input_stream.delete(&old_value) {
+                            errors.push(value_error(&entry.key, e, num_entries, &old_value));
+                        }
+                    }
+                    match entry.operation {
+                        Operation::Put => match input_stream.insert(&entry.value) {
+                            Ok(()) => {
+                                values.insert(entry.key, entry.value);
+                            }
+                            Err(e) => {
+                                errors.push(value_error(&entry.key, e, num_entries, &entry.value))
+                            }
+                        },
+                        Operation::Delete | Operation::Purge => {}
+                    }
+
+                    queue.push((input_stream.take_all(), errors), timestamp);
+                }
+            }
+        }
+    }
+}
+
+fn value_error(key: &str, error: AnyError, event_number: u64, value: &[u8]) -> ParseError {
+    ParseError::text_event_error(
+        &format!("failed to deserialize value of key '{key}'"),
+        error,
+        event_number,
+        Some(&String::from_utf8_lossy(value)),
+        None,
+    )
+}
+
+impl InputReader for NatsKvInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        let _ = self.command_sender.send(command.as_nonft().unwrap());
+    }
+
+    fn is_closed(&self) -> bool {
+        self.command_sender.is_closed()
+    }
+}
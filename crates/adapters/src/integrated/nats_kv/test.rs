@@ -0,0 +1,173 @@
+use crate::test::{file_to_zset, test_circuit, wait, TestStruct};
+use crate::Controller;
+use async_nats::jetstream::{self, kv};
+use dbsp::utils::Tup2;
+use dbsp::OrdZSet;
+use feldera_types::config::PipelineConfig;
+use serde_json::json;
+use serial_test::serial;
+use std::env;
+use std::fs::File;
+use tempfile::NamedTempFile;
+
+fn nats_url() -> String {
+    env::var("NATS_URL").unwrap_or("nats://localhost:4222".to_string())
+}
+
+/// Creates an empty bucket, deleting it first if it already exists.
+async fn create_bucket(bucket: &str) -> kv::Store {
+    let client = async_nats::connect(nats_url())
+        .await
+        .expect("failed to connect to NATS");
+    let jetstream = jetstream::new(client);
+    let _ = jetstream.delete_key_value(bucket).await;
+    jetstream
+        .create_key_value(kv::Config {
+            bucket: bucket.to_string(),
+            ..Default::default()
+        })
+        .await
+        .expect("failed to create key-value bucket")
+}
+
+async fn put(store: &kv::Store, record: &TestStruct) {
+    store
+        .put(
+            record.id.to_string(),
+            serde_json::to_vec(record).unwrap().into(),
+        )
+        .await
+        .unwrap();
+}
+
+fn nats_kv_input_pipeline(bucket: &str, output_file_path: &str) -> Controller {
+    let config: PipelineConfig = serde_json::from_value(json!({
+        "name": "test",
+        "workers": 4,
+        "outputs": {
+            "test_output1": {
+                "stream": "test_output1",
+                "transport": {
+                    "name": "file_output",
+                    "config": {
+                        "path": output_file_path
+                    },
+                },
+                "format": {
+                    "name": "json",
+                    "config": {
+                        "update_format": "insert_delete"
+                    }
+                }
+            }
+        },
+        "inputs": {
+            "test_input1": {
+                "stream": "test_input1",
+                "transport": {
+                    "name": "nats_kv_input",
+                    "config": {
+                        "connection_config": {
+                            "server_urls": [nats_url()]
+                        },
+                        "bucket": bucket
+                    }
+                }
+            }
+        }
+    }))
+    .unwrap();
+
+    Controller::with_config(
+        move |workers| {
+            Ok(test_circuit::<TestStruct>(
+                workers,
+                &TestStruct::schema(),
+                &[None],
+            ))
+        },
+        &config,
+        Box::new(move |e, _| {
+            panic!("nats_kv_input_test: error: {e}");
+        }),
+    )
+    .unwrap()
+}
+
+fn expected_zset(records: &[TestStruct]) -> OrdZSet<TestStruct> {
+    OrdZSet::from_tuples(
+        (),
+        records
+            .iter()
+            .map(|x| Tup2(Tup2(x.clone(), ()), 1))
+            .collect(),
+    )
+}
+
+/// Ingest a snapshot of the bucket, then follow updates and deletes.
+#[test]
+#[serial]
+fn test_nats_kv_input() {
+    let bucket = "feldera_test_kv_input";
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let data = vec![
+        TestStruct {
+            id: 1,
+            b: true,
+            i: None,
+            s: "foo".to_string(),
+        },
+        TestStruct {
+            id: 2,
+            b: false,
+            i: Some(5),
+            s: "bar".to_string(),
+        },
+    ];
+
+    let store = runtime.block_on(async {
+        let store = create_bucket(bucket).await;
+        for record in &data {
+            put(&store, record).await;
+        }
+        store
+    });
+
+    let output_file = NamedTempFile::new().unwrap();
+    let controller = nats_kv_input_pipeline(bucket, &output_file.path().display().to_string());
+    controller.start();
+
+    wait(
+        || controller.status().num_total_completed_records() == 2,
+        20_000,
+    )
+    .expect("timeout waiting for the snapshot");
+    assert_eq!(
+        file_to_zset::<TestStruct>(&mut File::open(output_file.path()).unwrap()),
+        expected_zset(&data)
+    );
+
+    // Replace key 1 and delete key 2.
+    let updated = TestStruct {
+        s: "updated".to_string(),
+        ..data[0].clone()
+    };
+    runtime.block_on(async {
+        put(&store, &updated).await;
+        store.delete("2").await.unwrap();
+    });
+
+    // Update = delete + insert, plus one more delete.
+    wait(
+        || controller.status().num_total_completed_records() == 5,
+        20_000,
+    )
+    .expect("timeout waiting for updates");
+    assert_eq!(
+        file_to_zset::<TestStruct>(&mut File::open(output_file.path()).unwrap()),
+        expected_zset(&[updated])
+    );
+
+    controller.stop().unwrap();
+}
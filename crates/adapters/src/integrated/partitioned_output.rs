@@ -0,0 +1,752 @@
+//! Partitioned file output to object stores.
+//!
+//! [`PartitionedOutputEndpoint`] writes the changes to a view into files in
+//! a bucket, partitioned into Hive-style `column=value/` paths by the values
+//! of the configured columns.  Each partition has at most one open file at a
+//! time.  Open files are written as multipart uploads, which are completed
+//! when the file reaches its maximum size or age, so that a file only becomes
+//! visible once it has been written in full.  A background thread checks the
+//! age of the open files, so that they are completed on time even when the
+//! view stops changing.
+//!
+//! The object store is abstracted by the [`ObjectSink`] trait, which is
+//! implemented for each store the connector supports.
+
+use crate::catalog::{CursorWithPolarity, RecordFormat, SerBatchReader, SerCursor};
+use crate::controller::{ControllerInner, EndpointId};
+use crate::format::{Encoder, OutputConsumer, MAX_DUPLICATES};
+use crate::transport::OutputEndpoint;
+use crate::ControllerError;
+use anyhow::{anyhow, bail, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{AsyncErrorCallback, Step};
+use feldera_types::format::csv::CsvParserConfig;
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::s3::ObjectOutputFormat;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::mem::take;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tracing::{info_span, span::EnteredSpan, warn};
+use uuid::Uuid;
+
+/// Default for `max_file_size_bytes`.
+const DEFAULT_MAX_FILE_SIZE_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Default for `rollover_interval_secs`.
+const DEFAULT_ROLLOVER_INTERVAL_SECS: u64 = 300;
+
+/// How often the [`Roller`] checks for files whose rollover interval has
+/// expired.
+const ROLLOVER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Size of the parts of a multipart upload.  S3 requires all parts but the
+/// last to be at least 5 MiB.
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Partition value used for `NULL`, following Hive.
+const NULL_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// An object store that [`PartitionedOutputEndpoint`] writes files to.
+pub(crate) trait ObjectSink: Send + 'static {
+    /// A multipart upload in progress.
+    type Upload: Send;
+
+    /// Name of the bucket or container, for logging.
+    fn bucket(&self) -> &str;
+
+    /// Writes object `key` with a single request.
+    async fn put(&self, key: &str, data: Vec<u8>) -> AnyResult<()>;
+
+    /// Starts a multipart upload of object `key`.
+    async fn start_upload(&self, key: &str) -> AnyResult<Self::Upload>;
+
+    /// Uploads `data` as the next part of `upload`.
+    async fn upload_part(&self, upload: &mut Self::Upload, data: Vec<u8>) -> AnyResult<()>;
+
+    /// Completes `upload`, which makes the object visible.
+    async fn complete_upload(&self, upload: Self::Upload) -> AnyResult<()>;
+
+    /// Aborts `upload`, discarding the parts that have been uploaded.
+    async fn abort_upload(&self, upload: Self::Upload) -> AnyResult<()>;
+}
+
+/// Store-independent configuration of a [`PartitionedOutputEndpoint`].
+pub(crate) struct PartitionedOutputConfig<'a> {
+    pub prefix: &'a str,
+    pub partition_by: &'a [String],
+    pub format: ObjectOutputFormat,
+    pub max_file_size_bytes: Option<u64>,
+    pub rollover_interval_secs: Option<u64>,
+}
+
+/// A file that is being written.
+struct OpenFile<U> {
+    /// Key of the object.
+    key: String,
+
+    /// When the file was opened.
+    opened: Instant,
+
+    /// Data that hasn't been uploaded yet.
+    buffer: Vec<u8>,
+
+    /// Total size of the file so far, including `buffer`.
+    size: u64,
+
+    /// The multipart upload, once the first part has been uploaded.  Files
+    /// that are completed before they reach `PART_SIZE` are written with a
+    /// single request instead.
+    upload: Option<U>,
+}
+
+/// The open files of a [`PartitionedOutputEndpoint`] and the settings for
+/// writing them.  Shared between the endpoint and its [`Roller`].
+struct Writer<S: ObjectSink> {
+    sink: S,
+
+    prefix: String,
+    format: ObjectOutputFormat,
+    max_file_size: u64,
+    rollover_interval: Duration,
+
+    /// Unique identifier of this instance of the connector, which is part of
+    /// every file name so that files written by different runs of the
+    /// pipeline don't collide.
+    run_id: String,
+
+    /// Number of files opened so far.
+    num_files: u64,
+
+    /// Open files, indexed by partition path.
+    files: BTreeMap<String, OpenFile<S::Upload>>,
+}
+
+impl<S: ObjectSink> Writer<S> {
+    /// Returns the key of a new file in the partition with path `partition`.
+    fn new_key(&mut self, partition: &str) -> String {
+        self.num_files += 1;
+        format!(
+            "{}{partition}{}-{}-{:06}.{}",
+            self.prefix,
+            Utc::now().format("%Y%m%dT%H%M%S"),
+            self.run_id,
+            self.num_files,
+            self.format.extension()
+        )
+    }
+
+    /// Uploads the buffered data of `file` as the next part of its multipart
+    /// upload, starting the upload if necessary.
+    async fn upload_part(sink: &S, file: &mut OpenFile<S::Upload>) -> AnyResult<()> {
+        if file.upload.is_none() {
+            file.upload = Some(
+                sink.start_upload(&file.key)
+                    .await
+                    .map_err(|e| anyhow!("error starting upload of '{}': {e}", file.key))?,
+            );
+        }
+        sink.upload_part(file.upload.as_mut().unwrap(), take(&mut file.buffer))
+            .await
+            .map_err(|e| anyhow!("error uploading part of '{}': {e}", file.key))
+    }
+
+    /// Uploads the rest of `file` and makes it visible.
+    async fn complete(sink: &S, mut file: OpenFile<S::Upload>) -> AnyResult<()> {
+        if file.upload.is_none() {
+            return sink
+                .put(&file.key, take(&mut file.buffer))
+                .await
+                .map_err(|e| anyhow!("error writing '{}': {e}", file.key));
+        }
+
+        if !file.buffer.is_empty() {
+            Self::upload_part(sink, &mut file).await?;
+        }
+        sink.complete_upload(file.upload.unwrap())
+            .await
+            .map_err(|e| anyhow!("error completing upload of '{}': {e}", file.key))
+    }
+
+    /// Abandons `file`, discarding the parts that have been uploaded.
+    async fn abort(sink: &S, file: OpenFile<S::Upload>) {
+        if let Some(upload) = file.upload {
+            if let Err(e) = sink.abort_upload(upload).await {
+                warn!("error aborting upload of '{}': {e}", file.key);
+            }
+        }
+    }
+
+    /// Completes the open file in `partition`.
+    fn roll(&mut self, partition: &str) -> AnyResult<()> {
+        let Some(file) = self.files.remove(partition) else {
+            return Ok(());
+        };
+        TOKIO.block_on(Self::complete(&self.sink, file))
+    }
+
+    /// Appends `data` to the open file in `partition`, opening a file if
+    /// necessary.
+    fn append(&mut self, partition: String, data: &[u8]) -> AnyResult<()> {
+        if !self.files.contains_key(&partition) {
+            let key = self.new_key(&partition);
+            self.files.insert(
+                partition.clone(),
+                OpenFile {
+                    key,
+                    opened: Instant::now(),
+                    buffer: Vec::new(),
+                    size: 0,
+                    upload: None,
+                },
+            );
+        }
+        let file = self.files.get_mut(&partition).unwrap();
+        file.buffer.extend_from_slice(data);
+        file.size += data.len() as u64;
+
+        if file.size >= self.max_file_size {
+            self.roll(&partition)?;
+        } else if file.buffer.len() >= PART_SIZE {
+            TOKIO.block_on(Self::upload_part(&self.sink, file))?;
+        }
+        Ok(())
+    }
+
+    /// Completes every file that has been open for longer than the rollover
+    /// interval.
+    fn roll_expired(&mut self) -> AnyResult<()> {
+        let expired = self
+            .files
+            .iter()
+            .filter(|(_, file)| file.opened.elapsed() >= self.rollover_interval)
+            .map(|(partition, _)| partition.clone())
+            .collect::<Vec<_>>();
+        for partition in expired {
+            self.roll(&partition)?;
+        }
+        Ok(())
+    }
+
+    /// Completes every open file, logging errors.
+    fn complete_all(&mut self) {
+        for (_, file) in take(&mut self.files) {
+            if let Err(e) = TOKIO.block_on(Self::complete(&self.sink, file)) {
+                warn!("{e}");
+            }
+        }
+    }
+
+    /// Discards every open file.
+    fn abort_all(&mut self) {
+        for (_, file) in take(&mut self.files) {
+            TOKIO.block_on(Self::abort(&self.sink, file));
+        }
+    }
+}
+
+/// Background thread that completes files once their rollover interval
+/// expires, so that files are rolled over even when the view stops changing.
+struct Roller {
+    /// Dropping the sender stops the thread.
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Roller {
+    fn new<S: ObjectSink>(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        bucket: &str,
+        writer: Arc<Mutex<Writer<S>>>,
+        controller: Weak<ControllerInner>,
+    ) -> Self {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let endpoint_name = endpoint_name.to_owned();
+        let bucket = bucket.to_owned();
+        let thread = thread::Builder::new()
+            .name(format!("{endpoint_name}-rollover"))
+            .spawn(move || {
+                let _guard = span(endpoint_id, &endpoint_name, &bucket);
+                while let Err(RecvTimeoutError::Timeout) =
+                    stopped.recv_timeout(ROLLOVER_CHECK_INTERVAL)
+                {
+                    let mut writer = writer.lock().unwrap();
+                    if let Err(e) = writer.roll_expired() {
+                        // Discard the open files: their contents can't be
+                        // written consistently anymore.
+                        writer.abort_all();
+                        if let Some(controller) = controller.upgrade() {
+                            controller.output_transport_error(
+                                endpoint_id,
+                                &endpoint_name,
+                                true,
+                                e,
+                                Some("partitioned_output_rollover"),
+                            );
+                        }
+                        return;
+                    }
+                }
+            })
+            .expect("failed to create rollover thread");
+        Self {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Roller {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn span(endpoint_id: EndpointId, endpoint_name: &str, bucket: &str) -> EnteredSpan {
+    info_span!(
+        "partitioned_output",
+        id = endpoint_id,
+        name = endpoint_name,
+        bucket = bucket,
+    )
+    .entered()
+}
+
+/// Integrated output connector that writes a view to files in a bucket.
+pub(crate) struct PartitionedOutputEndpoint<S: ObjectSink> {
+    endpoint_id: EndpointId,
+    endpoint_name: String,
+    bucket: String,
+
+    /// Names of the partition columns, as they appear in serialized records.
+    partition_columns: Vec<String>,
+
+    writer: Arc<Mutex<Writer<S>>>,
+    roller: Option<Roller>,
+
+    /// Statistics for the current batch.
+    batch_bytes: usize,
+    batch_records: usize,
+
+    controller: Weak<ControllerInner>,
+}
+
+impl<S: ObjectSink> PartitionedOutputEndpoint<S> {
+    pub fn with_sink(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: PartitionedOutputConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+        sink: S,
+    ) -> Result<Self, ControllerError> {
+        if key_schema.is_some() {
+            return Err(ControllerError::invalid_transport_configuration(
+                endpoint_name,
+                "the connector does not support the 'index' property",
+            ));
+        }
+
+        let partition_columns = config
+            .partition_by
+            .iter()
+            .map(|column| {
+                value_schema
+                    .fields
+                    .iter()
+                    .find(|field| field.name == column)
+                    .map(|field| field.name.name())
+                    .ok_or_else(|| {
+                        ControllerError::invalid_transport_configuration(
+                            endpoint_name,
+                            &format!(
+                                "partition column '{column}' is not a column of view '{}'",
+                                value_schema.name
+                            ),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bucket = sink.bucket().to_owned();
+        let writer = Arc::new(Mutex::new(Writer {
+            sink,
+            prefix: config.prefix.to_owned(),
+            format: config.format,
+            max_file_size: config
+                .max_file_size_bytes
+                .unwrap_or(DEFAULT_MAX_FILE_SIZE_BYTES),
+            rollover_interval: Duration::from_secs(
+                config
+                    .rollover_interval_secs
+                    .unwrap_or(DEFAULT_ROLLOVER_INTERVAL_SECS),
+            ),
+            run_id: Uuid::new_v4().simple().to_string(),
+            num_files: 0,
+            files: BTreeMap::new(),
+        }));
+        let roller = Roller::new(
+            endpoint_id,
+            endpoint_name,
+            &bucket,
+            writer.clone(),
+            controller.clone(),
+        );
+
+        Ok(Self {
+            endpoint_id,
+            endpoint_name: endpoint_name.to_owned(),
+            bucket,
+            partition_columns,
+            writer,
+            roller: Some(roller),
+            batch_bytes: 0,
+            batch_records: 0,
+            controller,
+        })
+    }
+
+    fn span(&self) -> EnteredSpan {
+        span(self.endpoint_id, &self.endpoint_name, &self.bucket)
+    }
+
+    /// Appends `data` to the open file in `partition`.
+    fn append(&mut self, partition: String, data: &[u8]) -> AnyResult<()> {
+        self.writer.lock().unwrap().append(partition, data)?;
+        self.batch_bytes += data.len();
+        self.batch_records += 1;
+        Ok(())
+    }
+
+    /// Returns the path of the partition of the record under `cursor`, e.g.,
+    /// `region=eu/day=2025-01-01/`.
+    fn partition(&self, cursor: &mut dyn SerCursor, buf: &mut Vec<u8>) -> AnyResult<String> {
+        if self.partition_columns.is_empty() {
+            return Ok(String::new());
+        }
+
+        let fields = self
+            .partition_columns
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+        buf.clear();
+        cursor.serialize_key_fields(&fields, buf)?;
+        let values = serde_json::from_slice::<Map<String, JsonValue>>(buf)
+            .map_err(|e| anyhow!("error extracting partition columns from record: {e}"))?;
+        Ok(partition_path(&self.partition_columns, &values))
+    }
+
+    fn encode_batch(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let format = self.writer.lock().unwrap().format;
+        let record_format = match format {
+            ObjectOutputFormat::Json => RecordFormat::Json(JsonFlavor::Default),
+            ObjectOutputFormat::Csv => RecordFormat::Csv(CsvParserConfig::default()),
+        };
+        let mut cursor = CursorWithPolarity::new(batch.cursor(record_format)?);
+
+        // Cursor over the same records that extracts partition values.  It
+        // moves in lockstep with `cursor`.
+        let mut partition_cursor = if self.partition_columns.is_empty() {
+            None
+        } else {
+            Some(CursorWithPolarity::new(
+                batch.cursor(RecordFormat::Json(JsonFlavor::Default))?,
+            ))
+        };
+
+        let mut record = Vec::new();
+        let mut line = Vec::new();
+        while cursor.key_valid() {
+            if cursor.val_valid() {
+                let partition = match &mut partition_cursor {
+                    Some(partition_cursor) => self.partition(partition_cursor, &mut record)?,
+                    None => String::new(),
+                };
+
+                let w = cursor.weight();
+                if !(-MAX_DUPLICATES..=MAX_DUPLICATES).contains(&w) {
+                    bail!("Unable to output record with very large weight {w}. Consider adjusting your SQL queries to avoid duplicate output records, e.g., using 'SELECT DISTINCT'.");
+                }
+
+                match format {
+                    ObjectOutputFormat::Json => {
+                        record.clear();
+                        cursor.serialize_key(&mut record)?;
+                        line.clear();
+                        line.extend_from_slice(if w > 0 {
+                            b"{\"insert\":"
+                        } else {
+                            b"{\"delete\":"
+                        });
+                        line.extend_from_slice(&record);
+                        line.extend_from_slice(b"}\n");
+                        for _ in 0..w.abs() {
+                            self.append(partition.clone(), &line)?;
+                        }
+                    }
+                    ObjectOutputFormat::Csv => {
+                        line.clear();
+                        cursor.serialize_key_weight(&mut line)?;
+                        self.append(partition, &line)?;
+                    }
+                }
+            }
+
+            cursor.step_key();
+            if let Some(partition_cursor) = &mut partition_cursor {
+                partition_cursor.step_key();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the Hive-style path of the partition with the given `values` of
+/// `columns`.
+fn partition_path(columns: &[String], values: &Map<String, JsonValue>) -> String {
+    let mut path = String::new();
+    for column in columns {
+        let value = match values.get(column) {
+            None | Some(JsonValue::Null) => NULL_PARTITION.to_string(),
+            Some(JsonValue::String(s)) => escape_path_component(s),
+            Some(value) => escape_path_component(&value.to_string()),
+        };
+        write!(path, "{}={value}/", escape_path_component(column)).unwrap();
+    }
+    path
+}
+
+/// Escapes the characters that Hive escapes in partition paths, as well as
+/// control characters, as `%XX`.
+fn escape_path_component(s: &str) -> String {
+    if s.is_empty() {
+        return NULL_PARTITION.to_string();
+    }
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii_control() || "\"#%'*/:=?\\{[]^".contains(c) {
+            write!(escaped, "%{:02X}", c as u32).unwrap();
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+impl<S: ObjectSink> Drop for PartitionedOutputEndpoint<S> {
+    fn drop(&mut self) {
+        self.roller.take();
+
+        // Complete the open files, so that the data written to them isn't
+        // lost when the pipeline stops.
+        let _guard = self.span();
+        self.writer.lock().unwrap().complete_all();
+    }
+}
+
+impl<S: ObjectSink> OutputConsumer for PartitionedOutputEndpoint<S> {
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn batch_start(&mut self, _step: Step) {
+        self.batch_bytes = 0;
+        self.batch_records = 0;
+    }
+
+    fn push_buffer(&mut self, _: &[u8], _: usize) {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _: Option<&[u8]>,
+        _: Option<&[u8]>,
+        _: &[(&str, Option<&[u8]>)],
+        _num_records: usize,
+    ) {
+        unreachable!()
+    }
+
+    fn batch_end(&mut self) {
+        let Some(controller) = self.controller.upgrade() else {
+            warn!("controller is shutting down: aborting");
+            return;
+        };
+        controller
+            .status
+            .output_buffer(self.endpoint_id, self.batch_bytes, self.batch_records);
+    }
+}
+
+impl<S: ObjectSink> Encoder for PartitionedOutputEndpoint<S> {
+    fn consumer(&mut self) -> &mut dyn OutputConsumer {
+        self
+    }
+
+    fn encode(&mut self, batch: &dyn SerBatchReader) -> AnyResult<()> {
+        let _guard = self.span();
+        self.encode_batch(batch)
+    }
+}
+
+impl<S: ObjectSink> OutputEndpoint for PartitionedOutputEndpoint<S> {
+    fn connect(&mut self, _: AsyncErrorCallback) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        todo!()
+    }
+
+    fn push_buffer(&mut self, _buffer: &[u8]) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        unreachable!()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+
+    fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
+        todo!()
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{escape_path_component, partition_path, ObjectSink, Roller, Writer};
+    use crate::test::wait;
+    use anyhow::Result as AnyResult;
+    use feldera_types::transport::s3::ObjectOutputFormat;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex, Weak};
+    use std::time::Duration;
+
+    /// In-memory [`ObjectSink`] that records the objects written to it.
+    #[derive(Clone, Default)]
+    struct MockSink {
+        objects: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+    }
+
+    impl ObjectSink for MockSink {
+        type Upload = (String, Vec<u8>);
+
+        fn bucket(&self) -> &str {
+            "mock"
+        }
+
+        async fn put(&self, key: &str, data: Vec<u8>) -> AnyResult<()> {
+            self.objects.lock().unwrap().insert(key.to_string(), data);
+            Ok(())
+        }
+
+        async fn start_upload(&self, key: &str) -> AnyResult<Self::Upload> {
+            Ok((key.to_string(), Vec::new()))
+        }
+
+        async fn upload_part(&self, upload: &mut Self::Upload, data: Vec<u8>) -> AnyResult<()> {
+            upload.1.extend(data);
+            Ok(())
+        }
+
+        async fn complete_upload(&self, upload: Self::Upload) -> AnyResult<()> {
+            self.objects.lock().unwrap().insert(upload.0, upload.1);
+            Ok(())
+        }
+
+        async fn abort_upload(&self, _upload: Self::Upload) -> AnyResult<()> {
+            Ok(())
+        }
+    }
+
+    /// Files are completed once their rollover interval expires, without
+    /// waiting for another batch.
+    #[test]
+    fn test_rollover_idle() {
+        let sink = MockSink::default();
+        let writer = Arc::new(Mutex::new(Writer {
+            sink: sink.clone(),
+            prefix: "out/".to_string(),
+            format: ObjectOutputFormat::Json,
+            max_file_size: u64::MAX,
+            rollover_interval: Duration::ZERO,
+            run_id: "run".to_string(),
+            num_files: 0,
+            files: BTreeMap::new(),
+        }));
+        writer
+            .lock()
+            .unwrap()
+            .append("a=1/".to_string(), b"{}\n")
+            .unwrap();
+
+        let _roller = Roller::new(0, "test", "mock", writer.clone(), Weak::new());
+        wait(|| !sink.objects.lock().unwrap().is_empty(), 10_000)
+            .expect("timeout waiting for the file to be completed");
+
+        let objects = sink.objects.lock().unwrap();
+        let (key, data) = objects.iter().next().unwrap();
+        assert!(key.starts_with("out/a=1/"));
+        assert!(key.ends_with("-run-000001.json"));
+        assert_eq!(data, b"{}\n");
+        assert!(writer.lock().unwrap().files.is_empty());
+    }
+
+    #[test]
+    fn test_partition_path() {
+        let columns = vec!["region".to_string(), "day".to_string(), "n".to_string()];
+        let values = json!({"region": "eu/west", "day": "2025-01-01", "n": 5})
+            .as_object()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            partition_path(&columns, &values),
+            "region=eu%2Fwest/day=2025-01-01/n=5/"
+        );
+
+        let values = json!({"region": null, "day": "", "n": 1.5})
+            .as_object()
+            .unwrap()
+            .clone();
+        assert_eq!(
+            partition_path(&columns, &values),
+            "region=__HIVE_DEFAULT_PARTITION__/day=__HIVE_DEFAULT_PARTITION__/n=1.5/"
+        );
+
+        assert_eq!(partition_path(&[], &values), "");
+    }
+
+    #[test]
+    fn test_escape_path_component() {
+        assert_eq!(escape_path_component("a b-c_d.e"), "a b-c_d.e");
+        assert_eq!(escape_path_component("a=b%c"), "a%3Db%25c");
+        assert_eq!(escape_path_component("12:00\n"), "12%3A00%0A");
+        assert_eq!(escape_path_component("żółw"), "żółw");
+    }
+}
@@ -1,11 +1,28 @@
+mod cdc;
 mod error;
 mod input;
 mod output;
 mod output_macros;
+mod pgoutput;
 mod prepared_statements;
 
 #[cfg(test)]
 mod test;
 
+pub use cdc::PostgresCdcInputEndpoint;
 pub use input::PostgresInputEndpoint;
 pub use output::PostgresOutputEndpoint;
+
+/// Quotes `ident` for use as a PostgreSQL identifier.
+fn quote_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Quotes a table name that is optionally qualified with a schema name,
+/// e.g., `public.orders`.
+fn quote_table_name(name: &str) -> String {
+    name.split('.')
+        .map(quote_identifier)
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -0,0 +1,1031 @@
+//! Postgres change data capture (CDC) input connector.
+//!
+//! The connector reads the changes to a table from a logical replication slot
+//! with the `pgoutput` plugin, using `pg_logical_slot_peek_binary_changes`,
+//! and advances the slot with `pg_replication_slot_advance` once the changes
+//! can no longer be needed to resume the pipeline.
+
+use super::pgoutput::{self, format_lsn, parse_lsn, text_to_json, tuple_to_json, Lsn, Message};
+use super::quote_identifier;
+use crate::transport::{InputEndpoint, InputQueue, InputReaderCommand, IntegratedInputEndpoint};
+use crate::{ControllerError, InputConsumer, InputReader, PipelineState, RecordFormat};
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::catalog::{DeCollectionStream, InputCollectionHandle};
+use feldera_adapterlib::format::{BufferSize, ParseError};
+use feldera_adapterlib::transport::{parse_resume_info, Resume, Watermark};
+use feldera_types::config::FtModel;
+use feldera_types::format::json::JsonFlavor;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::postgres::PostgresCdcReaderConfig;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hasher;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::mpsc;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tokio_postgres::{Client, NoTls, SimpleQueryMessage};
+use tracing::{debug, info, warn};
+use xxhash_rust::xxh3::Xxh3Default;
+
+/// Default for [`PostgresCdcReaderConfig::poll_interval_ms`].
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Number of rows to fetch at a time while reading the snapshot.
+const SNAPSHOT_FETCH_SIZE: usize = 10_000;
+
+/// Session settings that make Postgres format values the way the `postgres`
+/// JSON flavor expects them; see [`text_to_json`].
+const SESSION_SETTINGS: &str = "SET TIME ZONE 'UTC'; SET DateStyle = 'ISO, YMD'; SET bytea_output = 'hex'; SET extra_float_digits = 3";
+
+/// Integrated input connector that follows the changes to a Postgres table.
+pub struct PostgresCdcInputEndpoint {
+    endpoint_name: String,
+    config: PostgresCdcReaderConfig,
+    consumer: Box<dyn InputConsumer>,
+}
+
+impl PostgresCdcInputEndpoint {
+    pub fn new(
+        endpoint_name: &str,
+        config: &PostgresCdcReaderConfig,
+        consumer: Box<dyn InputConsumer>,
+    ) -> Self {
+        Self {
+            endpoint_name: endpoint_name.to_string(),
+            config: config.clone(),
+            consumer,
+        }
+    }
+}
+
+impl InputEndpoint for PostgresCdcInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::ExactlyOnce)
+    }
+}
+
+impl IntegratedInputEndpoint for PostgresCdcInputEndpoint {
+    fn open(
+        self: Box<Self>,
+        input_handle: &InputCollectionHandle,
+        resume_info: Option<JsonValue>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(PostgresCdcInputReader::new(
+            self.endpoint_name,
+            self.config,
+            self.consumer,
+            input_handle,
+            resume_info,
+        )?))
+    }
+}
+
+/// Resume info stored in each checkpoint for the [`PostgresCdcInputEndpoint`].
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+struct PostgresCdcResumeInfo {
+    /// End of the last transaction that the connector ingested or skipped,
+    /// in the textual LSN format.
+    lsn: String,
+
+    /// The snapshot that the contents of the table were read in, as long as
+    /// the connector may still encounter transactions that committed before
+    /// the snapshot was taken.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    snapshot: Option<SnapshotInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+struct SnapshotInfo {
+    /// Write-ahead log position when the snapshot was taken.  Transactions
+    /// that end after this position are not visible in the snapshot.
+    lsn: String,
+
+    /// The snapshot, as returned by `txid_current_snapshot()`.
+    txids: String,
+}
+
+/// A change to the table, journaled so that the step can be replayed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum Change {
+    Insert(String),
+    Delete(String),
+}
+
+/// Replay data for a step.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ReplayData {
+    changes: Vec<Change>,
+}
+
+/// Auxiliary data associated with each buffer in the queue.
+struct QueuedStep {
+    resume_info: PostgresCdcResumeInfo,
+
+    /// The changes in the buffer, only recorded if the pipeline is configured
+    /// with exactly-once fault tolerance.
+    changes: Vec<Change>,
+}
+
+/// Tracks which position the replication slot may be advanced to.
+///
+/// Postgres discards the changes before the slot's position, so the connector
+/// only advances the slot past changes that it won't need to read again:
+///
+/// - With exactly-once fault tolerance, steps are journaled with their data,
+///   so the slot may be advanced past a step once its journal record is
+///   written, which is done by the time the step after it is queued.
+///
+/// - With at-least-once fault tolerance, the pipeline resumes from its last
+///   checkpoint, so the slot may be advanced to a checkpoint once the next
+///   one is requested.
+///
+/// - Without fault tolerance, the slot is advanced as soon as changes are
+///   pushed to the circuit.
+#[derive(Default)]
+struct SlotProgress {
+    /// Positions of the most recent steps.
+    recent_steps: VecDeque<Lsn>,
+
+    /// Position of the most recent step with a checkpoint request.
+    last_checkpoint: Option<Lsn>,
+
+    /// Position to advance the slot to.
+    target: Option<Lsn>,
+}
+
+impl SlotProgress {
+    fn step(&mut self, ft: Option<FtModel>, lsn: Option<Lsn>, checkpoint_requested: bool) {
+        match ft {
+            Some(FtModel::ExactlyOnce) => {
+                if let Some(lsn) = lsn {
+                    self.recent_steps.push_back(lsn);
+                }
+                while self.recent_steps.len() > 2 {
+                    self.target = self.recent_steps.pop_front();
+                }
+            }
+            Some(FtModel::AtLeastOnce) => {
+                if checkpoint_requested {
+                    if self.last_checkpoint.is_some() {
+                        self.target = self.last_checkpoint;
+                    }
+                    if lsn.is_some() {
+                        self.last_checkpoint = lsn;
+                    }
+                }
+            }
+            None => {
+                if lsn.is_some() {
+                    self.target = lsn;
+                }
+            }
+        }
+    }
+}
+
+/// A transaction snapshot, as returned by `txid_current_snapshot()`, in the
+/// format `xmin:xmax:xip_list`.
+#[derive(Debug, PartialEq, Eq)]
+struct TxidSnapshot {
+    xmin: u64,
+    xmax: u64,
+    xip: Vec<u64>,
+}
+
+impl TxidSnapshot {
+    fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split(':');
+        let xmin = parts.next()?.parse().ok()?;
+        let xmax = parts.next()?.parse().ok()?;
+        let xip = match parts.next()? {
+            "" => Vec::new(),
+            xip => xip
+                .split(',')
+                .map(|xid| xid.parse().ok())
+                .collect::<Option<_>>()?,
+        };
+        parts.next().is_none().then_some(Self { xmin, xmax, xip })
+    }
+
+    /// Returns true if the changes of the transaction with the given 32-bit
+    /// transaction ID are visible in the snapshot.
+    ///
+    /// The transaction must have started around the time the snapshot was
+    /// taken, which lets us extend its ID to 64 bits the way Postgres compares
+    /// transaction IDs: as the ID closest to `xmax` with the same low 32 bits.
+    fn is_visible(&self, xid: u32) -> bool {
+        let delta = xid.wrapping_sub(self.xmax as u32) as i32;
+        let xid = self.xmax.wrapping_add_signed(delta as i64);
+        xid < self.xmin || (xid < self.xmax && !self.xip.contains(&xid))
+    }
+}
+
+/// Splits a table name into a schema name and a table name.
+fn split_table_name(table: &str) -> (&str, &str) {
+    table.split_once('.').unwrap_or(("public", table))
+}
+
+struct PostgresCdcInputReader {
+    sender: Sender<PipelineState>,
+    inner: Arc<PostgresCdcInputEndpointInner>,
+}
+
+impl PostgresCdcInputReader {
+    fn new(
+        endpoint_name: String,
+        config: PostgresCdcReaderConfig,
+        consumer: Box<dyn InputConsumer>,
+        input_handle: &InputCollectionHandle,
+        resume_info: Option<JsonValue>,
+    ) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        // A step taken before the connector ingested anything has no resume
+        // info.
+        let resume_info = match resume_info {
+            Some(resume_info) if !resume_info.is_null() => {
+                let resume_info = parse_resume_info::<PostgresCdcResumeInfo>(&resume_info)?;
+                info!(
+                    "postgres_cdc {endpoint_name}: resuming from LSN {}",
+                    resume_info.lsn
+                );
+                Some(resume_info)
+            }
+            _ => None,
+        };
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+
+        // Used to communicate the status of connector initialization.
+        let (init_status_sender, mut init_status_receiver) =
+            mpsc::channel::<Result<(), ControllerError>>(1);
+
+        let input_stream = input_handle
+            .handle
+            .configure_deserializer(RecordFormat::Json(JsonFlavor::Postgres))?;
+        let replay_stream = input_stream.fork();
+        let schema = input_handle.schema.clone();
+
+        let endpoint = Arc::new(PostgresCdcInputEndpointInner {
+            queue: InputQueue::new(consumer.clone()),
+            endpoint_name,
+            config,
+            consumer,
+            last_resume_info: Mutex::new(resume_info),
+            replay_stream: Mutex::new(replay_stream),
+            slot_progress: Mutex::new(SlotProgress::default()),
+        });
+
+        let endpoint_clone = endpoint.clone();
+        thread::Builder::new()
+            .name(format!(
+                "{}-postgres-cdc-input-tokio-wrapper",
+                endpoint.endpoint_name
+            ))
+            .spawn(move || {
+                TOKIO.block_on(async {
+                    endpoint_clone
+                        .worker_task(input_stream, schema, receiver, init_status_sender)
+                        .await;
+                })
+            })
+            .expect("failed to spawn Postgres CDC input connector tokio wrapper thread");
+
+        init_status_receiver.blocking_recv().ok_or_else(|| {
+            ControllerError::input_transport_error(
+                &endpoint.endpoint_name,
+                true,
+                anyhow!("worker thread terminated unexpectedly during initialization"),
+            )
+        })??;
+
+        Ok(Self {
+            sender,
+            inner: endpoint,
+        })
+    }
+}
+
+impl InputReader for PostgresCdcInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command {
+            InputReaderCommand::Replay { metadata, data } => self.inner.replay(metadata, data),
+            InputReaderCommand::Extend => {
+                let _ = self.sender.send_replace(PipelineState::Running);
+            }
+            InputReaderCommand::Pause => {
+                let _ = self.sender.send_replace(PipelineState::Paused);
+            }
+            InputReaderCommand::Queue {
+                checkpoint_requested,
+            } => self.inner.queue(checkpoint_requested),
+            InputReaderCommand::Disconnect => {
+                let _ = self.sender.send_replace(PipelineState::Terminated);
+            }
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.inner.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for PostgresCdcInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+struct PostgresCdcInputEndpointInner {
+    endpoint_name: String,
+    config: PostgresCdcReaderConfig,
+    consumer: Box<dyn InputConsumer>,
+    queue: InputQueue<QueuedStep>,
+
+    /// Position of the last step, which is also where the worker starts
+    /// reading once the pipeline runs.
+    last_resume_info: Mutex<Option<PostgresCdcResumeInfo>>,
+
+    /// Deserializer used to replay journaled steps.
+    replay_stream: Mutex<Box<dyn DeCollectionStream>>,
+
+    slot_progress: Mutex<SlotProgress>,
+}
+
+impl PostgresCdcInputEndpointInner {
+    fn queue(&self, checkpoint_requested: bool) {
+        let (total, hasher, steps) = self.queue.flush_with_aux();
+
+        let mut last_resume_info = self.last_resume_info.lock().unwrap();
+        let mut changes = Vec::new();
+        let mut watermarks = Vec::with_capacity(steps.len());
+        let mut step_lsn = None;
+        for (timestamp, step) in steps {
+            changes.extend(step.changes);
+            step_lsn = parse_lsn(&step.resume_info.lsn);
+            *last_resume_info = Some(step.resume_info);
+            watermarks.push(Watermark::new(timestamp, None));
+        }
+
+        self.slot_progress.lock().unwrap().step(
+            self.consumer.pipeline_fault_tolerance(),
+            step_lsn,
+            checkpoint_requested,
+        );
+
+        let seek = last_resume_info
+            .as_ref()
+            .map_or(JsonValue::Null, |resume_info| {
+                serde_json::to_value(resume_info).unwrap()
+            });
+        let resume = match hasher {
+            Some(hasher) => Resume::Replay {
+                seek,
+                replay: rmpv::ext::to_value(ReplayData { changes }).unwrap(),
+                hash: hasher.finish(),
+            },
+            None if seek.is_null() => Resume::Barrier,
+            None => Resume::Seek { seek },
+        };
+        self.consumer.extended(total, Some(resume), watermarks);
+    }
+
+    fn replay(&self, metadata: JsonValue, data: rmpv::Value) {
+        let ReplayData { changes } = rmpv::ext::from_value(data).unwrap();
+        let mut stream = self.replay_stream.lock().unwrap();
+        for change in &changes {
+            // The changes were journaled after they were deserialized
+            // successfully, so they can't fail now.
+            let _ = match change {
+                Change::Insert(record) => stream.insert(record.as_bytes()),
+                Change::Delete(record) => stream.delete(record.as_bytes()),
+            };
+        }
+
+        let mut hasher = Xxh3Default::new();
+        let mut total = BufferSize::empty();
+        if let Some(mut buffer) = stream.take_all() {
+            total = buffer.len();
+            self.consumer.buffered(total);
+            buffer.hash(&mut hasher);
+            buffer.flush();
+        }
+
+        if !metadata.is_null() {
+            match parse_resume_info::<PostgresCdcResumeInfo>(&metadata) {
+                Ok(resume_info) => *self.last_resume_info.lock().unwrap() = Some(resume_info),
+                Err(e) => self.consumer.error(true, e, Some("postgres-cdc-replay")),
+            }
+        }
+        self.consumer.replayed(total, hasher.finish());
+    }
+
+    async fn worker_task(
+        self: Arc<Self>,
+        input_stream: Box<dyn DeCollectionStream>,
+        schema: Relation,
+        receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let mut receiver_clone = receiver.clone();
+        select! {
+            _ = Self::worker_task_inner(self.clone(), input_stream, schema, receiver, init_status_sender) => {
+                debug!("postgres_cdc {}: worker task terminated", &self.endpoint_name);
+            }
+            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                debug!("postgres_cdc {}: received termination command; worker task canceled", &self.endpoint_name);
+            }
+        }
+    }
+
+    async fn worker_task_inner(
+        self: Arc<Self>,
+        mut input_stream: Box<dyn DeCollectionStream>,
+        schema: Relation,
+        mut receiver: Receiver<PipelineState>,
+        init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
+    ) {
+        let (mut client, column_types, slot_lsn) = match self.initialize().await {
+            Ok(result) => result,
+            Err(e) => {
+                let _ = init_status_sender
+                    .send(Err(ControllerError::input_transport_error(
+                        &self.endpoint_name,
+                        true,
+                        e,
+                    )))
+                    .await;
+                return;
+            }
+        };
+        let _ = init_status_sender.send(Ok(())).await;
+        wait_running(&mut receiver).await;
+
+        let existing_resume_info = self.last_resume_info.lock().unwrap().clone();
+        let mut position = match existing_resume_info {
+            Some(resume_info) => resume_info,
+            None if self.config.snapshot => {
+                match self
+                    .read_snapshot(
+                        &client,
+                        &mut *input_stream,
+                        &schema,
+                        &column_types,
+                        slot_lsn,
+                    )
+                    .await
+                {
+                    Ok(resume_info) => resume_info,
+                    Err(e) => {
+                        self.consumer.error(
+                            true,
+                            e.context("error reading the initial contents of the table"),
+                            Some("postgres-cdc-snapshot"),
+                        );
+                        return;
+                    }
+                }
+            }
+            None => PostgresCdcResumeInfo {
+                lsn: format_lsn(slot_lsn),
+                snapshot: None,
+            },
+        };
+
+        let mut confirmed_lsn = slot_lsn;
+        let poll_interval = self
+            .config
+            .poll_interval_ms
+            .map_or(DEFAULT_POLL_INTERVAL, Duration::from_millis);
+        loop {
+            wait_running(&mut receiver).await;
+
+            if client.is_closed() {
+                match self.connect().await {
+                    Ok(new_client) => client = new_client,
+                    Err(e) => {
+                        self.consumer.error(false, e, Some("postgres-cdc-connect"));
+                        sleep(poll_interval).await;
+                        continue;
+                    }
+                }
+            }
+
+            let target = self.slot_progress.lock().unwrap().target;
+            if let Some(target) = target.filter(|&target| target > confirmed_lsn) {
+                match client
+                    .execute(
+                        "SELECT pg_replication_slot_advance($1, $2::text::pg_lsn)",
+                        &[&self.config.slot, &format_lsn(target)],
+                    )
+                    .await
+                {
+                    Ok(_) => confirmed_lsn = target,
+                    Err(e) => self.consumer.error(
+                        false,
+                        anyhow!(
+                            "error advancing replication slot '{}': {e}",
+                            self.config.slot
+                        ),
+                        Some("postgres-cdc-advance"),
+                    ),
+                }
+            }
+
+            if let Err(e) = self
+                .poll(&client, &mut *input_stream, &schema, &mut position)
+                .await
+            {
+                self.consumer.error(false, e, Some("postgres-cdc-poll"));
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    /// Connects to the database and configures the session.
+    async fn connect(&self) -> AnyResult<Client> {
+        debug!("postgres_cdc {}: opening connection", &self.endpoint_name);
+
+        let (client, connection) = tokio_postgres::connect(self.config.uri.as_str(), NoTls)
+            .await
+            .with_context(|| {
+                format!(
+                    "unable to connect to postgres instance '{}'",
+                    self.config.uri
+                )
+            })?;
+        let endpoint_name = self.endpoint_name.clone();
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                warn!("postgres_cdc {endpoint_name}: connection error: {e}");
+            }
+        });
+        client
+            .batch_execute(SESSION_SETTINGS)
+            .await
+            .context("error configuring the database session")?;
+
+        info!(
+            "postgres_cdc {}: opened connection to '{}'",
+            &self.endpoint_name, &self.config.uri
+        );
+        Ok(client)
+    }
+
+    /// Connects to the database, checks that the table and the publication
+    /// exist, and creates the replication slot if it doesn't exist.
+    ///
+    /// Returns the client, the type of each column of the table, and the
+    /// position of the slot.
+    async fn initialize(&self) -> AnyResult<(Client, HashMap<String, u32>, Lsn)> {
+        let client = self.connect().await?;
+
+        let (schema_name, table_name) = split_table_name(&self.config.table);
+        let qualified_name = format!(
+            "{}.{}",
+            quote_identifier(schema_name),
+            quote_identifier(table_name)
+        );
+        let column_types = client
+            .query(
+                "SELECT attname::text, atttypid FROM pg_attribute WHERE attrelid = $1::text::regclass AND attnum > 0 AND NOT attisdropped",
+                &[&qualified_name],
+            )
+            .await
+            .with_context(|| format!("error looking up table '{}'", self.config.table))?
+            .into_iter()
+            .map(|row| (row.get::<_, String>(0), row.get::<_, u32>(1)))
+            .collect::<HashMap<_, _>>();
+
+        if client
+            .query_opt(
+                "SELECT 1 FROM pg_publication WHERE pubname = $1",
+                &[&self.config.publication],
+            )
+            .await
+            .context("error looking up publication")?
+            .is_none()
+        {
+            bail!(
+                "publication '{}' does not exist; create it with, e.g., 'CREATE PUBLICATION {} FOR TABLE {}'",
+                self.config.publication,
+                self.config.publication,
+                self.config.table
+            );
+        }
+
+        let slot = client
+            .query_opt(
+                "SELECT plugin::text, confirmed_flush_lsn::text FROM pg_replication_slots WHERE slot_name = $1",
+                &[&self.config.slot],
+            )
+            .await
+            .context("error looking up replication slot")?;
+        let slot_lsn = match slot {
+            Some(row) => {
+                let plugin = row.get::<_, Option<String>>(0);
+                if plugin.as_deref() != Some("pgoutput") {
+                    bail!(
+                        "replication slot '{}' must be a logical replication slot that uses the 'pgoutput' plugin",
+                        self.config.slot
+                    );
+                }
+                row.get::<_, Option<String>>(1)
+            }
+            None => {
+                info!(
+                    "postgres_cdc {}: creating replication slot '{}'",
+                    &self.endpoint_name, &self.config.slot
+                );
+                client
+                    .query_one(
+                        "SELECT lsn::text FROM pg_create_logical_replication_slot($1, 'pgoutput')",
+                        &[&self.config.slot],
+                    )
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "error creating replication slot '{}' (is 'wal_level' set to 'logical'?)",
+                            self.config.slot
+                        )
+                    })?
+                    .get::<_, Option<String>>(0)
+            }
+        };
+        let slot_lsn = slot_lsn.as_deref().and_then(parse_lsn).ok_or_else(|| {
+            anyhow!(
+                "replication slot '{}' has no valid position",
+                self.config.slot
+            )
+        })?;
+
+        Ok((client, column_types, slot_lsn))
+    }
+
+    /// Reads the contents of the table and pushes them to the queue as a
+    /// single buffer, so that a checkpoint never includes a part of them.
+    ///
+    /// Returns the position to follow changes from.
+    async fn read_snapshot(
+        &self,
+        client: &Client,
+        input_stream: &mut dyn DeCollectionStream,
+        schema: &Relation,
+        column_types: &HashMap<String, u32>,
+        slot_lsn: Lsn,
+    ) -> AnyResult<PostgresCdcResumeInfo> {
+        let (schema_name, table_name) = split_table_name(&self.config.table);
+        info!(
+            "postgres_cdc {}: reading the contents of table '{}'",
+            &self.endpoint_name, &self.config.table
+        );
+
+        let timestamp = Utc::now();
+        client
+            .batch_execute("BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .await?;
+        let row = client
+            .query_one(
+                "SELECT txid_current_snapshot()::text, pg_current_wal_lsn()::text",
+                &[],
+            )
+            .await?;
+        let snapshot = SnapshotInfo {
+            txids: row.get(0),
+            lsn: row.get(1),
+        };
+        client
+            .batch_execute(&format!(
+                "DECLARE feldera_snapshot NO SCROLL CURSOR FOR SELECT * FROM {}.{}",
+                quote_identifier(schema_name),
+                quote_identifier(table_name)
+            ))
+            .await?;
+
+        let journal = self.consumer.pipeline_fault_tolerance() == Some(FtModel::ExactlyOnce);
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        let mut num_records = 0;
+        loop {
+            let messages = client
+                .simple_query(&format!(
+                    "FETCH {SNAPSHOT_FETCH_SIZE} FROM feldera_snapshot"
+                ))
+                .await?;
+            let mut num_rows = 0;
+            for message in messages {
+                let SimpleQueryMessage::Row(row) = message else {
+                    continue;
+                };
+                num_rows += 1;
+                num_records += 1;
+
+                let mut object = Map::new();
+                for (index, column) in row.columns().iter().enumerate() {
+                    let name = column.name();
+                    if !schema.fields.iter().any(|f| f.name == name) {
+                        // Ignore columns that are not in the Feldera table.
+                        continue;
+                    }
+                    let value = match row.get(index) {
+                        None => JsonValue::Null,
+                        Some(text) => {
+                            text_to_json(column_types.get(name).copied().unwrap_or(0), text)
+                        }
+                    };
+                    object.insert(name.to_string(), value);
+                }
+                let record = JsonValue::Object(object).to_string();
+                match input_stream.insert(record.as_bytes()) {
+                    Ok(()) if journal => changes.push(Change::Insert(record)),
+                    Ok(()) => (),
+                    Err(e) => errors.push(ParseError::text_event_error(
+                        "failed to deserialize table record from Postgres",
+                        e,
+                        num_records,
+                        Some(&record),
+                        None,
+                    )),
+                }
+            }
+            if num_rows < SNAPSHOT_FETCH_SIZE {
+                break;
+            }
+        }
+        client.batch_execute("COMMIT").await?;
+
+        info!(
+            "postgres_cdc {}: read {num_records} records from table '{}'",
+            &self.endpoint_name, &self.config.table
+        );
+        let resume_info = PostgresCdcResumeInfo {
+            lsn: format_lsn(slot_lsn),
+            snapshot: Some(snapshot),
+        };
+        self.queue.push_with_aux(
+            (input_stream.take_all(), errors),
+            timestamp,
+            QueuedStep {
+                resume_info: resume_info.clone(),
+                changes,
+            },
+        );
+        Ok(resume_info)
+    }
+
+    /// Reads the transactions in the replication slot that end after
+    /// `position`, pushes the changes they made to the table to the queue,
+    /// and updates `position`.
+    async fn poll(
+        &self,
+        client: &Client,
+        input_stream: &mut dyn DeCollectionStream,
+        schema: &Relation,
+        position: &mut PostgresCdcResumeInfo,
+    ) -> AnyResult<()> {
+        let (schema_name, table_name) = split_table_name(&self.config.table);
+        let journal = self.consumer.pipeline_fault_tolerance() == Some(FtModel::ExactlyOnce);
+        let include = |name: &str| schema.fields.iter().any(|f| f.name == name);
+
+        let rows = client
+            .query_raw(
+                "SELECT data FROM pg_logical_slot_peek_binary_changes($1, NULL, NULL, 'proto_version', '1', 'publication_names', $2)",
+                [self.config.slot.as_str(), self.config.publication.as_str()],
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "error reading changes from replication slot '{}'",
+                    self.config.slot
+                )
+            })?;
+        let mut rows = pin!(rows);
+
+        let mut relation = None;
+        let mut xid = 0;
+        let mut changes = Vec::new();
+        let mut errors = Vec::new();
+        let mut advanced = false;
+        let mut num_records = 0;
+        while let Some(row) = rows.next().await {
+            let row = row.context("error reading changes from replication slot")?;
+            let message = pgoutput::decode(row.get::<_, &[u8]>(0))
+                .map_err(|e| anyhow!("error decoding logical replication message: {e}"))?;
+
+            // Convert the changes to our table into records, ignoring changes
+            // to other tables in the publication.
+            let mut convert = |relation_id: u32,
+                               tuple: &pgoutput::Tuple,
+                               old: Option<&pgoutput::Tuple>|
+             -> Option<String> {
+                let relation = relation
+                    .as_ref()
+                    .filter(|relation: &&pgoutput::Relation| relation.id == relation_id)?;
+                num_records += 1;
+                match tuple_to_json(relation, tuple, old, include) {
+                    Ok(record) => Some(record),
+                    Err(e) => {
+                        errors.push(ParseError::text_envelope_error(e, "", None));
+                        None
+                    }
+                }
+            };
+            match message {
+                Message::Begin { xid: begin_xid } => {
+                    xid = begin_xid;
+                    changes.clear();
+                }
+                Message::Relation(new_relation) => {
+                    if new_relation.namespace == schema_name && new_relation.name == table_name {
+                        relation = Some(new_relation);
+                    }
+                }
+                Message::Insert {
+                    relation: relation_id,
+                    new,
+                } => {
+                    changes.extend(convert(relation_id, &new, None).map(Change::Insert));
+                }
+                Message::Update {
+                    relation: relation_id,
+                    old,
+                    new,
+                } => {
+                    if let Some(old) = &old {
+                        changes.extend(convert(relation_id, old, None).map(Change::Delete));
+                    }
+                    changes.extend(convert(relation_id, &new, old.as_ref()).map(Change::Insert));
+                }
+                Message::Delete {
+                    relation: relation_id,
+                    old,
+                } => {
+                    changes.extend(convert(relation_id, &old, None).map(Change::Delete));
+                }
+                Message::Truncate { relations } => {
+                    if relation
+                        .as_ref()
+                        .is_some_and(|relation| relations.contains(&relation.id))
+                    {
+                        self.consumer.error(
+                            false,
+                            anyhow!(
+                                "table '{}' was truncated; the connector ignores truncation",
+                                self.config.table
+                            ),
+                            Some("postgres-cdc-truncate"),
+                        );
+                    }
+                }
+                Message::Commit { end_lsn } => {
+                    let changes = std::mem::take(&mut changes);
+                    if parse_lsn(&position.lsn).is_some_and(|lsn| end_lsn <= lsn) {
+                        // Already ingested.
+                        errors.clear();
+                        continue;
+                    }
+                    position.lsn = format_lsn(end_lsn);
+
+                    // Skip transactions that are already included in the
+                    // snapshot.
+                    if let Some(snapshot) = &position.snapshot {
+                        if parse_lsn(&snapshot.lsn).is_some_and(|lsn| end_lsn > lsn) {
+                            position.snapshot = None;
+                        } else if TxidSnapshot::parse(&snapshot.txids)
+                            .is_some_and(|txids| txids.is_visible(xid))
+                        {
+                            errors.clear();
+                            advanced = true;
+                            continue;
+                        }
+                    }
+
+                    let mut journaled = Vec::new();
+                    for change in changes {
+                        let (result, record) = match &change {
+                            Change::Insert(record) => {
+                                (input_stream.insert(record.as_bytes()), record)
+                            }
+                            Change::Delete(record) => {
+                                (input_stream.delete(record.as_bytes()), record)
+                            }
+                        };
+                        match result {
+                            Ok(()) if journal => journaled.push(change),
+                            Ok(()) => (),
+                            Err(e) => errors.push(ParseError::text_event_error(
+                                "failed to deserialize change from Postgres",
+                                e,
+                                num_records,
+                                Some(record),
+                                None,
+                            )),
+                        }
+                    }
+
+                    let buffer = input_stream.take_all();
+                    if buffer.is_none() && errors.is_empty() {
+                        advanced = true;
+                        continue;
+                    }
+                    self.queue.push_with_aux(
+                        (buffer, std::mem::take(&mut errors)),
+                        Utc::now(),
+                        QueuedStep {
+                            resume_info: position.clone(),
+                            changes: journaled,
+                        },
+                    );
+                    advanced = false;
+                }
+                Message::Other => (),
+            }
+        }
+
+        // Record the position after transactions that didn't change the
+        // table, so that the slot can be advanced past them.
+        if advanced {
+            self.queue.push_with_aux(
+                (None, Vec::new()),
+                Utc::now(),
+                QueuedStep {
+                    resume_info: position.clone(),
+                    changes: Vec::new(),
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Block until the state is `Running`.
+async fn wait_running(receiver: &mut Receiver<PipelineState>) {
+    // An error indicates that the channel was closed.  It's ok to ignore
+    // the error as this situation will be handled by the top-level select,
+    // which will abort the worker thread.
+    let _ = receiver
+        .wait_for(|state| state == &PipelineState::Running)
+        .await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SlotProgress, TxidSnapshot};
+    use feldera_types::config::FtModel;
+
+    #[test]
+    fn test_txid_snapshot() {
+        let snapshot = TxidSnapshot::parse("100:105:101,103").unwrap();
+        assert_eq!(
+            snapshot,
+            TxidSnapshot {
+                xmin: 100,
+                xmax: 105,
+                xip: vec![101, 103]
+            }
+        );
+        assert!(snapshot.is_visible(99));
+        assert!(!snapshot.is_visible(101));
+        assert!(snapshot.is_visible(102));
+        assert!(!snapshot.is_visible(105));
+        assert!(TxidSnapshot::parse("100:100:").unwrap().xip.is_empty());
+        assert!(TxidSnapshot::parse("100:abc:").is_none());
+
+        // Transaction IDs around an epoch boundary.
+        let snapshot = TxidSnapshot::parse("4294967390:4294967400:").unwrap();
+        assert!(snapshot.is_visible(90));
+        assert!(!snapshot.is_visible(110));
+        assert!(snapshot.is_visible(u32::MAX));
+    }
+
+    #[test]
+    fn test_slot_progress() {
+        let mut progress = SlotProgress::default();
+        for lsn in 1..=3 {
+            progress.step(Some(FtModel::ExactlyOnce), Some(lsn), false);
+        }
+        assert_eq!(progress.target, Some(1));
+        progress.step(Some(FtModel::ExactlyOnce), None, false);
+        assert_eq!(progress.target, Some(1));
+
+        let mut progress = SlotProgress::default();
+        progress.step(Some(FtModel::AtLeastOnce), Some(1), true);
+        progress.step(Some(FtModel::AtLeastOnce), Some(2), false);
+        assert_eq!(progress.target, None);
+        progress.step(Some(FtModel::AtLeastOnce), Some(3), true);
+        assert_eq!(progress.target, Some(1));
+
+        let mut progress = SlotProgress::default();
+        progress.step(None, Some(5), false);
+        assert_eq!(progress.target, Some(5));
+    }
+}
@@ -1,6 +1,6 @@
 use std::{io::Write, marker::PhantomPinned, str::FromStr, sync::Weak, time::Duration};
 
-use super::{error::BackoffError, prepared_statements::PreparedStatements};
+use super::{error::BackoffError, prepared_statements::PreparedStatements, quote_table_name};
 use crate::{
     buffer_op,
     catalog::{RecordFormat, SerBatchReader},
@@ -49,6 +49,16 @@ pub struct PostgresOutputEndpoint {
     num_bytes: usize,
     num_rows: usize,
     txn_start: std::time::Instant,
+    /// The step of the current batch.
+    step: Step,
+    /// The first step that hasn't been written to the table yet, according to
+    /// the metadata table.  Always 0 without a metadata table.
+    next_step: Step,
+    /// Whether the current batch was already written in a previous run.
+    skip_step: bool,
+    /// Name of the pipeline, which identifies the connector's row in the
+    /// metadata table together with the endpoint and table names.
+    pipeline_name: String,
     _pin: PhantomPinned,
 }
 
@@ -105,6 +115,42 @@ fn set_certs(builder: &mut SslConnectorBuilder, config: &PostgresWriterConfig) -
     Ok(())
 }
 
+/// Creates `metadata_table` if it does not exist and returns the step after the
+/// last one that endpoint `endpoint_name` of pipeline `pipeline_name` wrote to
+/// `table`, or 0 if there is none.
+fn read_next_step(
+    client: &mut Client,
+    metadata_table: &str,
+    pipeline_name: &str,
+    endpoint_name: &str,
+    table: &str,
+) -> Result<Step, BackoffError> {
+    let metadata_table_name = quote_table_name(metadata_table);
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {metadata_table_name} (pipeline_name VARCHAR NOT NULL, endpoint_name VARCHAR NOT NULL, table_name VARCHAR NOT NULL, step BIGINT NOT NULL, PRIMARY KEY (pipeline_name, endpoint_name, table_name))"
+        ))
+        .map_err(|e| {
+            BackoffError::from(e)
+                .context(format!("failed to create metadata table \"{metadata_table}\""))
+        })?;
+
+    let row = client
+        .query_opt(
+            &format!(
+                "SELECT step FROM {metadata_table_name} WHERE pipeline_name = $1 AND endpoint_name = $2 AND table_name = $3"
+            ),
+            &[&pipeline_name, &endpoint_name, &table],
+        )
+        .map_err(|e| {
+            BackoffError::from(e).context(format!(
+                "failed to read metadata table \"{metadata_table}\""
+            ))
+        })?;
+
+    Ok(row.map_or(0, |row| row.get::<_, i64>(0) as Step + 1))
+}
+
 fn connect(config: &PostgresWriterConfig, endpoint_name: &str) -> Result<Client, BackoffError> {
     let pgcnf = postgres::Config::from_str(&config.uri).map_err(|e| {
         BackoffError::Permanent(anyhow!("error parsing postgres connection string: {e}"))
@@ -162,6 +208,22 @@ impl PostgresOutputEndpoint {
                 |e| ControllerError::output_transport_error(endpoint_name, true, e.inner()),
             )?;
 
+        let pipeline_name = controller
+            .upgrade()
+            .and_then(|controller| controller.status.pipeline_config.name.clone())
+            .unwrap_or_default();
+        let next_step = match &config.metadata_table {
+            Some(metadata_table) => read_next_step(
+                &mut client,
+                metadata_table,
+                &pipeline_name,
+                endpoint_name,
+                &table,
+            )
+            .map_err(|e| ControllerError::output_transport_error(endpoint_name, true, e.inner()))?,
+            None => 0,
+        };
+
         let out = Self {
             endpoint_id,
             endpoint_name: endpoint_name.to_owned(),
@@ -182,6 +244,10 @@ impl PostgresOutputEndpoint {
             delete_buf: Vec::with_capacity(config.max_buffer_size_bytes),
             value_schema: value_schema.to_owned(),
             txn_start: std::time::Instant::now(),
+            step: 0,
+            next_step,
+            skip_step: false,
+            pipeline_name,
             _pin: PhantomPinned,
         };
 
@@ -341,7 +407,7 @@ impl PostgresOutputEndpoint {
     fn span(&self) -> EnteredSpan {
         info_span!(
             "postgres_output",
-            ft = false,
+            ft = self.config.metadata_table.is_some(),
             id = self.endpoint_id,
             name = self.endpoint_name,
             pg_table = self.table,
@@ -453,7 +519,25 @@ These statements were successfully prepared before reconnecting. Does the table
                 "postgres: attempted to commit a transaction that hasn't been started"
             )))?;
 
+        // Record the step in the same transaction, so that it is committed
+        // if and only if the step's changes are.
+        if let Some(metadata_table) = &self.config.metadata_table {
+            transaction.execute(
+                &format!(
+                    "INSERT INTO {} (pipeline_name, endpoint_name, table_name, step) VALUES ($1, $2, $3, $4) ON CONFLICT (pipeline_name, endpoint_name, table_name) DO UPDATE SET step = EXCLUDED.step",
+                    quote_table_name(metadata_table)
+                ),
+                &[
+                    &self.pipeline_name,
+                    &self.endpoint_name,
+                    &self.table,
+                    &(self.step as i64),
+                ],
+            )?;
+        }
+
         transaction.commit()?;
+        self.next_step = self.step + 1;
 
         let elapsed = self.txn_start.elapsed();
         tracing::debug!(
@@ -479,7 +563,16 @@ impl OutputConsumer for PostgresOutputEndpoint {
         self.config.max_buffer_size_bytes
     }
 
-    fn batch_start(&mut self, _step: Step) {
+    fn batch_start(&mut self, step: Step) {
+        self.step = step;
+        self.skip_step = step < self.next_step;
+        if self.skip_step {
+            tracing::info!(
+                "postgres: skipping step {step} that was already written in a previous run"
+            );
+            return;
+        }
+
         loop {
             match self.batch_start_inner() {
                 Ok(_) => return,
@@ -520,6 +613,10 @@ impl OutputConsumer for PostgresOutputEndpoint {
     }
 
     fn batch_end(&mut self) {
+        if self.skip_step {
+            return;
+        }
+
         loop {
             match self.batch_end_inner() {
                 Ok(_) => return,
@@ -552,6 +649,10 @@ impl Encoder for PostgresOutputEndpoint {
     }
 
     fn encode(&mut self, batch: &dyn SerBatchReader) -> anyhow::Result<()> {
+        if self.skip_step {
+            return Ok(());
+        }
+
         let mut cursor = batch.cursor(RecordFormat::Json(JsonFlavor::Postgres))?;
 
         while cursor.key_valid() {
@@ -614,7 +715,7 @@ impl OutputEndpoint for PostgresOutputEndpoint {
     }
 
     fn is_fault_tolerant(&self) -> bool {
-        false
+        self.config.metadata_table.is_some()
     }
 
     fn batch_start(&mut self, _step: Step) -> AnyResult<()> {
@@ -697,6 +798,7 @@ mod tests {
             max_records_in_buffer: None,
             max_buffer_size_bytes: usize::pow(2, 20),
             on_conflict_do_nothing: false,
+            metadata_table: None,
         }
     }
 
@@ -842,4 +944,64 @@ mod tests {
 
         drop_table(&mut client, table);
     }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_postgres_metadata_table() {
+        let mut client = postgres_client();
+        let table = "03JWRRNVP4CGER2E3SQQKCZFNQ";
+        let metadata_table = "03JWRRNVP4CGER2E3SQQKCZFNQ_metadata";
+
+        client
+            .execute(
+                &format!(r#"CREATE TABLE "{table}" (id int primary key, s varchar)"#),
+                &[],
+            )
+            .expect("failed to create test table in postgres");
+        client
+            .execute(&format!(r#"DROP TABLE IF EXISTS "{metadata_table}""#), &[])
+            .expect("failed to drop metadata table");
+
+        let idx = relation("v1_idx", vec![int_field("id")], false);
+        let main = relation("v1", vec![int_field("id"), varchar_field("s")], true);
+        let config = PostgresWriterConfig {
+            metadata_table: Some(format!("public.{metadata_table}")),
+            ..make_config(table)
+        };
+        let create = || {
+            PostgresOutputEndpoint::new(
+                EndpointId::default(),
+                "blah",
+                &config,
+                &Some(idx.clone()),
+                &main,
+                Weak::new(),
+            )
+            .unwrap()
+        };
+
+        // The connector creates the metadata table and starts from step 0.
+        assert_eq!(create().next_step, 0);
+
+        let insert_step = |client: &mut postgres::Client, endpoint_name: &str, step: i64| {
+            client
+                .execute(
+                    &format!(
+                        r#"INSERT INTO "{metadata_table}" (pipeline_name, endpoint_name, table_name, step) VALUES ('', $1, $2, $3)"#
+                    ),
+                    &[&endpoint_name, &table, &step],
+                )
+                .expect("failed to insert into metadata table");
+        };
+
+        // Steps written to the same table by another endpoint don't count.
+        insert_step(&mut client, "other", 99);
+        assert_eq!(create().next_step, 0);
+
+        insert_step(&mut client, "blah", 41);
+        assert_eq!(create().next_step, 42);
+
+        drop_table(&mut client, table);
+        drop_table(&mut client, metadata_table);
+    }
 }
@@ -0,0 +1,463 @@
+//! Decoder for messages of the `pgoutput` logical replication plugin,
+//! protocol version 1.
+//!
+//! See <https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html>.
+
+use serde_json::{Map, Number, Value};
+
+/// A log sequence number, i.e., a position in the write-ahead log.
+pub(super) type Lsn = u64;
+
+/// Parses an LSN in its textual form, e.g., `16/B374D848`.
+pub(super) fn parse_lsn(s: &str) -> Option<Lsn> {
+    let (high, low) = s.split_once('/')?;
+    let high = u32::from_str_radix(high, 16).ok()?;
+    let low = u32::from_str_radix(low, 16).ok()?;
+    Some(((high as u64) << 32) | low as u64)
+}
+
+/// Formats `lsn` in its textual form, e.g., `16/B374D848`.
+pub(super) fn format_lsn(lsn: Lsn) -> String {
+    format!("{:X}/{:X}", lsn >> 32, lsn & 0xffff_ffff)
+}
+
+/// A column of a relation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Column {
+    pub name: String,
+    pub type_oid: u32,
+}
+
+/// Description of a table, sent before the first change to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Relation {
+    pub id: u32,
+    pub namespace: String,
+    pub name: String,
+    pub columns: Vec<Column>,
+}
+
+/// The value of a column in a tuple.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum TupleValue {
+    Null,
+
+    /// An unchanged TOASTed value, whose actual value is not sent.
+    Unchanged,
+
+    /// A value in Postgres' text format.
+    Text(String),
+}
+
+pub(super) type Tuple = Vec<TupleValue>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Message {
+    Begin {
+        xid: u32,
+    },
+    Commit {
+        /// End of the transaction in the write-ahead log.
+        end_lsn: Lsn,
+    },
+    Relation(Relation),
+    Insert {
+        relation: u32,
+        new: Tuple,
+    },
+    Update {
+        relation: u32,
+
+        /// The old values of the replica identity columns, or of all of the
+        /// columns with `REPLICA IDENTITY FULL`.  Only sent if the replica
+        /// identity changed or is `FULL`.
+        old: Option<Tuple>,
+        new: Tuple,
+    },
+    Delete {
+        relation: u32,
+        old: Tuple,
+    },
+    Truncate {
+        relations: Vec<u32>,
+    },
+
+    /// A message that doesn't affect the data, e.g., a type description or a
+    /// replication origin.
+    Other,
+}
+
+/// Reads the fields of a message.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.data.len() < n {
+            return Err("truncated message".to_string());
+        }
+        let (bytes, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    /// Reads a NUL-terminated string.
+    fn string(&mut self) -> Result<String, String> {
+        let end = self
+            .data
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| "unterminated string".to_string())?;
+        let s = String::from_utf8_lossy(&self.data[..end]).into_owned();
+        self.data = &self.data[end + 1..];
+        Ok(s)
+    }
+
+    fn tuple(&mut self) -> Result<Tuple, String> {
+        let n = self.u16()?;
+        (0..n)
+            .map(|_| match self.u8()? {
+                b'n' => Ok(TupleValue::Null),
+                b'u' => Ok(TupleValue::Unchanged),
+                b't' => {
+                    let len = self.u32()? as usize;
+                    Ok(TupleValue::Text(
+                        String::from_utf8_lossy(self.bytes(len)?).into_owned(),
+                    ))
+                }
+                kind => Err(format!("unknown tuple value kind '{}'", kind as char)),
+            })
+            .collect()
+    }
+}
+
+/// Decodes a `pgoutput` message.
+pub(super) fn decode(data: &[u8]) -> Result<Message, String> {
+    let mut reader = Reader { data };
+    match reader.u8()? {
+        b'B' => {
+            let _final_lsn = reader.u64()?;
+            let _commit_timestamp = reader.u64()?;
+            Ok(Message::Begin { xid: reader.u32()? })
+        }
+        b'C' => {
+            let _flags = reader.u8()?;
+            let _commit_lsn = reader.u64()?;
+            Ok(Message::Commit {
+                end_lsn: reader.u64()?,
+            })
+        }
+        b'R' => {
+            let id = reader.u32()?;
+            let namespace = reader.string()?;
+            let name = reader.string()?;
+            let _replica_identity = reader.u8()?;
+            let n = reader.u16()?;
+            let columns = (0..n)
+                .map(|_| {
+                    let _flags = reader.u8()?;
+                    let name = reader.string()?;
+                    let type_oid = reader.u32()?;
+                    let _type_modifier = reader.u32()?;
+                    Ok(Column { name, type_oid })
+                })
+                .collect::<Result<_, String>>()?;
+            Ok(Message::Relation(Relation {
+                id,
+                // The namespace of tables in `pg_catalog` is sent as an empty
+                // string.
+                namespace: if namespace.is_empty() {
+                    "pg_catalog".to_string()
+                } else {
+                    namespace
+                },
+                name,
+                columns,
+            }))
+        }
+        b'I' => {
+            let relation = reader.u32()?;
+            match reader.u8()? {
+                b'N' => Ok(Message::Insert {
+                    relation,
+                    new: reader.tuple()?,
+                }),
+                kind => Err(format!("unexpected tuple kind '{}'", kind as char)),
+            }
+        }
+        b'U' => {
+            let relation = reader.u32()?;
+            let (old, kind) = match reader.u8()? {
+                b'K' | b'O' => (Some(reader.tuple()?), reader.u8()?),
+                kind => (None, kind),
+            };
+            match kind {
+                b'N' => Ok(Message::Update {
+                    relation,
+                    old,
+                    new: reader.tuple()?,
+                }),
+                kind => Err(format!("unexpected tuple kind '{}'", kind as char)),
+            }
+        }
+        b'D' => {
+            let relation = reader.u32()?;
+            match reader.u8()? {
+                b'K' | b'O' => Ok(Message::Delete {
+                    relation,
+                    old: reader.tuple()?,
+                }),
+                kind => Err(format!("unexpected tuple kind '{}'", kind as char)),
+            }
+        }
+        b'T' => {
+            let n = reader.u32()?;
+            let _options = reader.u8()?;
+            let relations = (0..n).map(|_| reader.u32()).collect::<Result<_, _>>()?;
+            Ok(Message::Truncate { relations })
+        }
+        b'O' | b'Y' | b'M' => Ok(Message::Other),
+        tag => Err(format!("unknown message type '{}'", tag as char)),
+    }
+}
+
+/// Type OIDs, from `pg_type`.
+const BOOL: u32 = 16;
+const INT8: u32 = 20;
+const INT2: u32 = 21;
+const INT4: u32 = 23;
+const OID: u32 = 26;
+const JSON: u32 = 114;
+const FLOAT4: u32 = 700;
+const FLOAT8: u32 = 701;
+const TIMESTAMPTZ: u32 = 1184;
+const JSONB: u32 = 3802;
+
+/// Converts a value of type `type_oid` in Postgres' text format into the JSON
+/// representation that the `postgres` JSON flavor expects.
+///
+/// Assumes that the session's `TimeZone` is UTC, its `DateStyle` is ISO, and
+/// its `bytea_output` is `hex`.  Values of types that the flavor represents as
+/// strings in the same format as Postgres, e.g., `DATE` or `NUMERIC`, are
+/// passed through as strings.
+pub(super) fn text_to_json(type_oid: u32, text: &str) -> Value {
+    match type_oid {
+        BOOL => Value::Bool(text == "t"),
+        INT2 | INT4 | INT8 | OID | FLOAT4 | FLOAT8 => text
+            .parse::<Number>()
+            .map(Value::Number)
+            .unwrap_or_else(|_| Value::String(text.to_string())),
+        JSON | JSONB => {
+            serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+        }
+        // With UTC as the time zone, timestamps end in `+00`.
+        TIMESTAMPTZ => Value::String(text.strip_suffix("+00").unwrap_or(text).to_string()),
+        _ => Value::String(text.to_string()),
+    }
+}
+
+/// Converts a tuple of `relation` into a JSON object, leaving out the columns
+/// for which `include` returns false.
+///
+/// Unchanged TOASTed values are taken from `old`, if it is available.
+pub(super) fn tuple_to_json(
+    relation: &Relation,
+    tuple: &Tuple,
+    old: Option<&Tuple>,
+    include: impl Fn(&str) -> bool,
+) -> Result<String, String> {
+    let mut object = Map::new();
+    for (index, (column, value)) in relation.columns.iter().zip(tuple).enumerate() {
+        if !include(&column.name) {
+            continue;
+        }
+        let value = match value {
+            TupleValue::Null => Value::Null,
+            TupleValue::Text(text) => text_to_json(column.type_oid, text),
+            TupleValue::Unchanged => match old.and_then(|old| old.get(index)) {
+                Some(TupleValue::Text(text)) => text_to_json(column.type_oid, text),
+                _ => return Err(format!("the value of column '{}' was not sent because it is unchanged and stored out of line (TOASTed); set 'REPLICA IDENTITY FULL' on table '{}.{}' to receive it", column.name, relation.namespace, relation.name)),
+            },
+        };
+        object.insert(column.name.clone(), value);
+    }
+    Ok(Value::Object(object).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn string(s: &str) -> Vec<u8> {
+        let mut bytes = s.as_bytes().to_vec();
+        bytes.push(0);
+        bytes
+    }
+
+    fn tuple(values: &[Option<&str>]) -> Vec<u8> {
+        let mut bytes = (values.len() as u16).to_be_bytes().to_vec();
+        for value in values {
+            match value {
+                None => bytes.push(b'n'),
+                Some(value) => {
+                    bytes.push(b't');
+                    bytes.extend((value.len() as u32).to_be_bytes());
+                    bytes.extend(value.as_bytes());
+                }
+            }
+        }
+        bytes
+    }
+
+    fn relation() -> Relation {
+        Relation {
+            id: 16384,
+            namespace: "public".to_string(),
+            name: "orders".to_string(),
+            columns: vec![
+                Column {
+                    name: "id".to_string(),
+                    type_oid: INT4,
+                },
+                Column {
+                    name: "note".to_string(),
+                    type_oid: 25,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_lsn() {
+        assert_eq!(parse_lsn("16/B374D848"), Some(0x16_B374_D848));
+        assert_eq!(format_lsn(0x16_B374_D848), "16/B374D848");
+        assert_eq!(format_lsn(0), "0/0");
+        assert_eq!(parse_lsn("B374D848"), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut begin = vec![b'B'];
+        begin.extend(0x100u64.to_be_bytes());
+        begin.extend(0u64.to_be_bytes());
+        begin.extend(742u32.to_be_bytes());
+        assert_eq!(decode(&begin), Ok(Message::Begin { xid: 742 }));
+
+        let mut commit = vec![b'C', 0];
+        commit.extend(0x100u64.to_be_bytes());
+        commit.extend(0x128u64.to_be_bytes());
+        commit.extend(0u64.to_be_bytes());
+        assert_eq!(decode(&commit), Ok(Message::Commit { end_lsn: 0x128 }));
+
+        let mut rel = vec![b'R'];
+        rel.extend(16384u32.to_be_bytes());
+        rel.extend(string("public"));
+        rel.extend(string("orders"));
+        rel.push(b'd');
+        rel.extend(2u16.to_be_bytes());
+        for (name, type_oid) in [("id", INT4), ("note", 25)] {
+            rel.push(1);
+            rel.extend(string(name));
+            rel.extend(type_oid.to_be_bytes());
+            rel.extend((-1i32).to_be_bytes());
+        }
+        assert_eq!(decode(&rel), Ok(Message::Relation(relation())));
+
+        let mut insert = vec![b'I'];
+        insert.extend(16384u32.to_be_bytes());
+        insert.push(b'N');
+        insert.extend(tuple(&[Some("1"), None]));
+        assert_eq!(
+            decode(&insert),
+            Ok(Message::Insert {
+                relation: 16384,
+                new: vec![TupleValue::Text("1".to_string()), TupleValue::Null],
+            })
+        );
+
+        let mut update = vec![b'U'];
+        update.extend(16384u32.to_be_bytes());
+        update.push(b'K');
+        update.extend(tuple(&[Some("1"), None]));
+        update.push(b'N');
+        update.extend(tuple(&[Some("2"), Some("x")]));
+        assert_eq!(
+            decode(&update),
+            Ok(Message::Update {
+                relation: 16384,
+                old: Some(vec![TupleValue::Text("1".to_string()), TupleValue::Null]),
+                new: vec![
+                    TupleValue::Text("2".to_string()),
+                    TupleValue::Text("x".to_string())
+                ],
+            })
+        );
+
+        let mut delete = vec![b'D'];
+        delete.extend(16384u32.to_be_bytes());
+        delete.push(b'K');
+        delete.extend(tuple(&[Some("2"), None]));
+        assert_eq!(
+            decode(&delete),
+            Ok(Message::Delete {
+                relation: 16384,
+                old: vec![TupleValue::Text("2".to_string()), TupleValue::Null],
+            })
+        );
+
+        assert!(decode(&insert[..insert.len() - 1]).is_err());
+        assert!(decode(b"Z").is_err());
+    }
+
+    #[test]
+    fn test_tuple_to_json() {
+        let new = vec![TupleValue::Text("1".to_string()), TupleValue::Unchanged];
+        let old = vec![
+            TupleValue::Text("1".to_string()),
+            TupleValue::Text("long".to_string()),
+        ];
+        assert_eq!(
+            tuple_to_json(&relation(), &new, Some(&old), |_| true).unwrap(),
+            json!({"id": 1, "note": "long"}).to_string()
+        );
+        assert!(tuple_to_json(&relation(), &new, None, |_| true)
+            .unwrap_err()
+            .contains("REPLICA IDENTITY FULL"));
+        assert_eq!(
+            tuple_to_json(&relation(), &new, None, |name| name == "id").unwrap(),
+            json!({"id": 1}).to_string()
+        );
+    }
+
+    #[test]
+    fn test_text_to_json() {
+        assert_eq!(text_to_json(BOOL, "t"), json!(true));
+        assert_eq!(text_to_json(INT8, "-42"), json!(-42));
+        assert_eq!(text_to_json(FLOAT8, "1.5"), json!(1.5));
+        assert_eq!(text_to_json(FLOAT8, "NaN"), json!("NaN"));
+        assert_eq!(text_to_json(JSONB, r#"{"a": [1]}"#), json!({"a": [1]}));
+        assert_eq!(
+            text_to_json(TIMESTAMPTZ, "2025-01-01 12:00:00.5+00"),
+            json!("2025-01-01 12:00:00.5")
+        );
+        assert_eq!(text_to_json(1700, "12.50"), json!("12.50"));
+    }
+}
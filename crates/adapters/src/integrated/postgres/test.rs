@@ -1,20 +1,24 @@
-use dbsp::{utils::Tup1, Runtime};
+use dbsp::{circuit::NodeId, utils::Tup1, Runtime};
+use feldera_adapterlib::{catalog::InputCollectionHandle, transport::Resume};
 use feldera_types::{
     deserialize_table_record,
     program_schema::{Relation, SqlIdentifier},
     serde_with_context::{SerializeWithContext, SqlSerdeConfig},
     serialize_table_record,
+    transport::postgres::PostgresCdcReaderConfig,
 };
 use pg::PostgresTestStruct;
 use postgres::NoTls;
-use serde_json::json;
+use serde_json::{json, Value as JsonValue};
 use serial_test::serial;
 use std::{collections::BTreeMap, io::Write, path::Path};
 use tempfile::NamedTempFile;
 
 use crate::{
-    test::{wait, TestStruct},
-    Catalog, Controller,
+    integrated::postgres::PostgresCdcInputEndpoint,
+    test::{wait, MockDeZSet, MockInputConsumer, MockUpdate, TestStruct},
+    transport::IntegratedInputEndpoint,
+    Catalog, Controller, InputReader,
 };
 
 fn postgres_url() -> String {
@@ -1549,3 +1553,193 @@ fn test_pg_simple() {
         .execute(&format!("DROP TABLE {table_name}"), &[])
         .unwrap();
 }
+
+/// Returns the position of replication slot `slot`.
+fn slot_lsn(client: &mut postgres::Client, slot: &str) -> String {
+    client
+        .query_one(
+            "SELECT confirmed_flush_lsn::text FROM pg_replication_slots WHERE slot_name = $1",
+            &[&slot],
+        )
+        .unwrap()
+        .get(0)
+}
+
+/// Tests the Postgres CDC connector: reading the initial contents of a table,
+/// decoding the changes to it from the replication slot, advancing the slot,
+/// and resuming from a checkpoint.
+///
+/// Requires a server with `wal_level = logical`.
+#[test]
+#[serial]
+fn test_pg_cdc() {
+    let url = postgres_url();
+    let table_name = "cdc_test";
+    let publication = "cdc_test_publication";
+    let slot = "cdc_test_slot";
+
+    let mut client = postgres::Client::connect(&url, NoTls).expect("failed to connect to postgres");
+    client
+        .execute(
+            "SELECT pg_drop_replication_slot(slot_name) FROM pg_replication_slots WHERE slot_name = $1",
+            &[&slot],
+        )
+        .unwrap();
+    client
+        .batch_execute(&format!(
+            r#"
+DROP PUBLICATION IF EXISTS {publication};
+DROP TABLE IF EXISTS {table_name};
+CREATE TABLE {table_name} (
+    id int primary key,
+    b bool not null,
+    i int8,
+    s varchar not null
+);
+ALTER TABLE {table_name} REPLICA IDENTITY FULL;
+CREATE PUBLICATION {publication} FOR TABLE {table_name};
+INSERT INTO {table_name} VALUES (1, true, NULL, 'one'), (2, false, 2, 'two');
+"#
+        ))
+        .expect("failed to create test table in postgres");
+
+    let config = PostgresCdcReaderConfig {
+        uri: url.clone(),
+        table: table_name.to_string(),
+        publication: publication.to_string(),
+        slot: slot.to_string(),
+        snapshot: true,
+        poll_interval_ms: Some(100),
+    };
+    let open = |resume_info: Option<JsonValue>| {
+        let consumer = MockInputConsumer::new();
+        let zset = MockDeZSet::<TestStruct, TestStruct>::new();
+        let input_handle =
+            InputCollectionHandle::new(TestStruct::relation_schema(), zset.clone(), NodeId::new(0));
+        let reader = Box::new(PostgresCdcInputEndpoint::new(
+            "cdc",
+            &config,
+            Box::new(consumer.clone()),
+        ))
+        .open(&input_handle, resume_info)
+        .unwrap();
+        reader.extend();
+        (reader, consumer, zset)
+    };
+
+    // Queues steps until `expected` has been received, in any order.
+    let expect = |reader: &dyn InputReader,
+                  zset: &MockDeZSet<TestStruct, TestStruct>,
+                  mut expected: Vec<MockUpdate<TestStruct, TestStruct>>| {
+        wait(
+            || {
+                reader.queue(false);
+                zset.state().flushed.len() >= expected.len()
+            },
+            10_000,
+        )
+        .expect("timeout waiting for changes");
+        let mut flushed = std::mem::take(&mut zset.state().flushed);
+        flushed.sort();
+        expected.sort();
+        assert_eq!(flushed, expected);
+    };
+    let record = |id: u32, b: bool, i: Option<i64>, s: &str| TestStruct {
+        id,
+        b,
+        i,
+        s: s.to_string(),
+    };
+
+    // The initial contents of the table.
+    let (reader, consumer, zset) = open(None);
+    expect(
+        &*reader,
+        &zset,
+        vec![
+            MockUpdate::Insert(record(1, true, None, "one")),
+            MockUpdate::Insert(record(2, false, Some(2), "two")),
+        ],
+    );
+    let initial_lsn = slot_lsn(&mut client, slot);
+
+    // Inserts, updates, and deletes in a single transaction.
+    client
+        .batch_execute(&format!(
+            r#"
+BEGIN;
+INSERT INTO {table_name} VALUES (3, true, 3, 'three');
+UPDATE {table_name} SET s = 'updated' WHERE id = 1;
+DELETE FROM {table_name} WHERE id = 2;
+COMMIT;
+"#
+        ))
+        .unwrap();
+    expect(
+        &*reader,
+        &zset,
+        vec![
+            MockUpdate::Insert(record(3, true, Some(3), "three")),
+            MockUpdate::Delete(record(1, true, None, "one")),
+            MockUpdate::Insert(record(1, true, None, "updated")),
+            MockUpdate::Delete(record(2, false, Some(2), "two")),
+        ],
+    );
+
+    // With exactly-once fault tolerance, the slot is advanced past a step
+    // once two more steps have been queued.
+    for id in [4, 5] {
+        client
+            .execute(
+                &format!("INSERT INTO {table_name} VALUES ($1, false, NULL, 'new')"),
+                &[&(id as i32)],
+            )
+            .unwrap();
+        expect(
+            &*reader,
+            &zset,
+            vec![MockUpdate::Insert(record(id, false, None, "new"))],
+        );
+    }
+    wait(|| slot_lsn(&mut client, slot) != initial_lsn, 10_000)
+        .expect("timeout waiting for the replication slot to advance");
+
+    // Resume from the last step after making changes while disconnected.
+    // Neither the contents of the table nor the changes that were already
+    // ingested are read again.
+    let resume_info = consumer
+        .state()
+        .last_resume
+        .clone()
+        .and_then(Resume::into_seek);
+    assert!(resume_info.is_some());
+    drop(reader);
+    client
+        .batch_execute(&format!(
+            r#"
+INSERT INTO {table_name} VALUES (6, true, 6, 'six');
+DELETE FROM {table_name} WHERE id = 3;
+"#
+        ))
+        .unwrap();
+
+    let (reader, _consumer, zset) = open(resume_info);
+    expect(
+        &*reader,
+        &zset,
+        vec![
+            MockUpdate::Insert(record(6, true, Some(6), "six")),
+            MockUpdate::Delete(record(3, true, Some(3), "three")),
+        ],
+    );
+    drop(reader);
+
+    client
+        .execute("SELECT pg_drop_replication_slot($1)", &[&slot])
+        .unwrap();
+    client
+        .batch_execute(&format!(
+            "DROP PUBLICATION {publication}; DROP TABLE {table_name};"
+        ))
+        .unwrap();
+}
@@ -0,0 +1,174 @@
+//! S3 output connector.
+//!
+//! Writes partitioned files with [`PartitionedOutputEndpoint`], using S3
+//! multipart uploads for files that don't fit in a single part.
+
+use crate::controller::{ControllerInner, EndpointId};
+use crate::integrated::partitioned_output::{
+    ObjectSink, PartitionedOutputConfig, PartitionedOutputEndpoint,
+};
+use crate::transport::s3::s3_client_config;
+use crate::ControllerError;
+use anyhow::{anyhow, Result as AnyResult};
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use aws_sdk_s3::Client;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::s3::S3OutputConfig;
+use std::error::Error;
+use std::sync::Weak;
+
+/// Integrated output connector that writes a view to files in an S3 bucket.
+pub(crate) type S3OutputEndpoint = PartitionedOutputEndpoint<S3Sink>;
+
+impl S3OutputEndpoint {
+    pub fn new(
+        endpoint_id: EndpointId,
+        endpoint_name: &str,
+        config: &S3OutputConfig,
+        key_schema: &Option<Relation>,
+        value_schema: &Relation,
+        controller: Weak<ControllerInner>,
+    ) -> Result<Self, ControllerError> {
+        config
+            .validate()
+            .map_err(|e| ControllerError::invalid_transport_configuration(endpoint_name, &e))?;
+
+        let client = Client::from_conf(s3_client_config(
+            &config.region,
+            config.endpoint_url.as_deref(),
+            false,
+            config.aws_access_key_id.as_deref(),
+            config.aws_secret_access_key.as_deref(),
+        ));
+
+        Self::with_sink(
+            endpoint_id,
+            endpoint_name,
+            PartitionedOutputConfig {
+                prefix: &config.prefix,
+                partition_by: &config.partition_by,
+                format: config.format,
+                max_file_size_bytes: config.max_file_size_bytes,
+                rollover_interval_secs: config.rollover_interval_secs,
+            },
+            key_schema,
+            value_schema,
+            controller,
+            S3Sink {
+                client,
+                bucket: config.bucket_name.clone(),
+            },
+        )
+    }
+}
+
+/// Writes objects to an S3 bucket.
+pub(crate) struct S3Sink {
+    client: Client,
+    bucket: String,
+}
+
+/// Converts an S3 SDK error into an error whose message includes its cause,
+/// which the SDK errors' own messages leave out.
+fn sdk_error(e: impl Error) -> anyhow::Error {
+    anyhow!("{}", DisplayErrorContext(e))
+}
+
+/// S3 multipart upload in progress.
+pub(crate) struct S3Upload {
+    key: String,
+    upload_id: String,
+    parts: Vec<CompletedPart>,
+}
+
+impl ObjectSink for S3Sink {
+    type Upload = S3Upload;
+
+    fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> AnyResult<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(())
+    }
+
+    async fn start_upload(&self, key: &str) -> AnyResult<S3Upload> {
+        let output = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        let upload_id = output
+            .upload_id()
+            .ok_or_else(|| anyhow!("S3 did not return an upload id"))?;
+        Ok(S3Upload {
+            key: key.to_string(),
+            upload_id: upload_id.to_string(),
+            parts: Vec::new(),
+        })
+    }
+
+    async fn upload_part(&self, upload: &mut S3Upload, data: Vec<u8>) -> AnyResult<()> {
+        let part_number = upload.parts.len() as i32 + 1;
+        let output = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(&upload.key)
+            .upload_id(&upload.upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        upload.parts.push(
+            CompletedPart::builder()
+                .set_e_tag(output.e_tag().map(str::to_string))
+                .part_number(part_number)
+                .build(),
+        );
+        Ok(())
+    }
+
+    async fn complete_upload(&self, upload: S3Upload) -> AnyResult<()> {
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&upload.key)
+            .upload_id(&upload.upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(upload.parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(())
+    }
+
+    async fn abort_upload(&self, upload: S3Upload) -> AnyResult<()> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&upload.key)
+            .upload_id(&upload.upload_id)
+            .send()
+            .await
+            .map_err(sdk_error)?;
+        Ok(())
+    }
+}
@@ -18,6 +18,10 @@ pub struct MockInputConsumerState {
     /// Number of times `extended` has been called since the last `reset`.
     pub n_extended: usize,
 
+    /// The resume information passed to the last call to `extended` since
+    /// the last `reset`.
+    pub last_resume: Option<Resume>,
+
     /// The last error received from the endpoint since the last `reset`.
     pub endpoint_error: Option<AnyError>,
 
@@ -32,6 +36,7 @@ impl MockInputConsumerState {
         Self {
             eoi: false,
             n_extended: 0,
+            last_resume: None,
             endpoint_error: None,
             error_cb: None,
         }
@@ -41,6 +46,7 @@ impl MockInputConsumerState {
     pub fn reset(&mut self) {
         self.eoi = false;
         self.n_extended = 0;
+        self.last_resume = None;
         self.endpoint_error = None;
     }
 }
@@ -113,8 +119,10 @@ impl InputConsumer for MockInputConsumer {
 
     fn request_step(&self) {}
 
-    fn extended(&self, _amt: BufferSize, _resume: Option<Resume>, _watermark: Vec<Watermark>) {
-        self.state().n_extended += 1;
+    fn extended(&self, _amt: BufferSize, resume: Option<Resume>, _watermark: Vec<Watermark>) {
+        let mut state = self.state();
+        state.n_extended += 1;
+        state.last_resume = resume;
     }
 }
 
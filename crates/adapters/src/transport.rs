@@ -37,34 +37,134 @@ pub mod http;
 pub mod url;
 
 pub mod clock;
-mod s3;
+pub(crate) mod s3;
+
+#[cfg(feature = "with-gcs")]
+pub(crate) mod gcs;
+
+#[cfg(feature = "with-azure-blob")]
+pub(crate) mod azure_blob;
+
+#[cfg(any(feature = "with-gcs", feature = "with-azure-blob"))]
+pub(crate) mod object_input;
+
+#[cfg(feature = "with-ftp")]
+mod ftp;
+
+#[cfg(feature = "with-http-poller")]
+mod http_poller;
+
+#[cfg(feature = "with-amqp")]
+mod amqp;
+
+#[cfg(feature = "with-grpc")]
+mod grpc;
 
 #[cfg(feature = "with-kafka")]
 pub(crate) mod kafka;
 
+#[cfg(feature = "with-kinesis")]
+mod kinesis;
+
+#[cfg(feature = "with-mqtt")]
+mod mqtt;
+
+#[cfg(feature = "with-nats")]
+pub(crate) mod nats;
+
 #[cfg(feature = "with-nexmark")]
 mod nexmark;
 
 #[cfg(feature = "with-pubsub")]
 mod pubsub;
 
+#[cfg(feature = "with-pulsar")]
+mod pulsar;
+
 #[cfg(feature = "with-redis")]
 mod redis;
 
+#[cfg(feature = "with-sqs")]
+mod sqs;
+
+#[cfg(feature = "with-sse")]
+mod sse;
+
+#[cfg(feature = "with-syslog")]
+mod syslog;
+
+#[cfg(feature = "with-tcp")]
+mod tcp;
+
+#[cfg(feature = "with-unix")]
+mod unix;
+
+#[cfg(feature = "with-webhook")]
+mod webhook;
+
+#[cfg(feature = "with-websocket")]
+mod websocket;
+
+#[cfg(any(
+    feature = "with-nats",
+    feature = "with-mqtt",
+    feature = "with-websocket",
+    feature = "with-tcp",
+    feature = "with-flight"
+))]
+pub(crate) mod tls;
+
 use feldera_types::config::TransportConfig;
 
 #[cfg(feature = "with-redis")]
-use redis::output::RedisOutputEndpoint;
+use redis::{
+    input::RedisStreamsInputEndpoint,
+    output::{RedisOutputEndpoint, RedisStreamsOutputEndpoint},
+};
 
+#[cfg(feature = "with-amqp")]
+use crate::transport::amqp::AmqpInputEndpoint;
+#[cfg(feature = "with-azure-blob")]
+use crate::transport::azure_blob::AzureBlobInputEndpoint;
 #[cfg(test)]
 pub use crate::transport::file::set_barrier;
 use crate::transport::file::{FileInputEndpoint, FileOutputEndpoint};
+#[cfg(feature = "with-ftp")]
+use crate::transport::ftp::FtpInputEndpoint;
+#[cfg(feature = "with-gcs")]
+use crate::transport::gcs::GcsInputEndpoint;
+#[cfg(feature = "with-grpc")]
+use crate::transport::grpc::{GrpcInputEndpoint, GrpcOutputEndpoint};
+#[cfg(feature = "with-http-poller")]
+use crate::transport::http_poller::HttpPollerInputEndpoint;
 #[cfg(feature = "with-kafka")]
 use crate::transport::kafka::{KafkaFtInputEndpoint, KafkaFtOutputEndpoint, KafkaOutputEndpoint};
+#[cfg(feature = "with-kinesis")]
+use crate::transport::kinesis::KinesisOutputEndpoint;
+#[cfg(feature = "with-mqtt")]
+use crate::transport::mqtt::{MqttInputEndpoint, MqttOutputEndpoint};
+#[cfg(feature = "with-nats")]
+use crate::transport::nats::NatsObjectStoreInputEndpoint;
 #[cfg(feature = "with-nexmark")]
 use crate::transport::nexmark::NexmarkEndpoint;
+#[cfg(feature = "with-pulsar")]
+use crate::transport::pulsar::{PulsarInputEndpoint, PulsarOutputEndpoint};
 use crate::transport::s3::S3InputEndpoint;
+#[cfg(feature = "with-sqs")]
+use crate::transport::sqs::SqsInputEndpoint;
+#[cfg(feature = "with-sse")]
+use crate::transport::sse::SseInputEndpoint;
+#[cfg(feature = "with-syslog")]
+use crate::transport::syslog::SyslogInputEndpoint;
+#[cfg(feature = "with-tcp")]
+use crate::transport::tcp::TcpInputEndpoint;
+#[cfg(feature = "with-unix")]
+use crate::transport::unix::{UnixInputEndpoint, UnixOutputEndpoint};
 use crate::transport::url::UrlInputEndpoint;
+#[cfg(feature = "with-webhook")]
+use crate::transport::webhook::WebhookOutputEndpoint;
+#[cfg(feature = "with-websocket")]
+use crate::transport::websocket::{WebsocketInputEndpoint, WebsocketOutputEndpoint};
 use feldera_datagen::GeneratorEndpoint;
 
 pub use feldera_adapterlib::transport::*;
@@ -91,8 +191,76 @@ pub fn input_transport_config_to_endpoint(
         TransportConfig::PubSubInput(config) => Box::new(PubSubInputEndpoint::new(config.clone())?),
         #[cfg(not(feature = "with-pubsub"))]
         TransportConfig::PubSubInput(_) => return Ok(None),
+        #[cfg(feature = "with-nats")]
+        TransportConfig::NatsObjectStoreInput(config) => {
+            Box::new(NatsObjectStoreInputEndpoint::new(config, endpoint_name)?)
+        }
+        #[cfg(not(feature = "with-nats"))]
+        TransportConfig::NatsObjectStoreInput(_) => return Ok(None),
+        #[cfg(feature = "with-mqtt")]
+        TransportConfig::MqttInput(config) => Box::new(MqttInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-mqtt"))]
+        TransportConfig::MqttInput(_) => return Ok(None),
+        #[cfg(feature = "with-amqp")]
+        TransportConfig::AmqpInput(config) => Box::new(AmqpInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-amqp"))]
+        TransportConfig::AmqpInput(_) => return Ok(None),
+        #[cfg(feature = "with-redis")]
+        TransportConfig::RedisStreamsInput(config) => {
+            Box::new(RedisStreamsInputEndpoint::new(config)?)
+        }
+        #[cfg(not(feature = "with-redis"))]
+        TransportConfig::RedisStreamsInput(_) => return Ok(None),
+        #[cfg(feature = "with-pulsar")]
+        TransportConfig::PulsarInput(config) => Box::new(PulsarInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-pulsar"))]
+        TransportConfig::PulsarInput(_) => return Ok(None),
+        #[cfg(feature = "with-sqs")]
+        TransportConfig::SqsInput(config) => Box::new(SqsInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-sqs"))]
+        TransportConfig::SqsInput(_) => return Ok(None),
+        #[cfg(feature = "with-websocket")]
+        TransportConfig::WebsocketInput(config) => Box::new(WebsocketInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-websocket"))]
+        TransportConfig::WebsocketInput(_) => return Ok(None),
+        #[cfg(feature = "with-grpc")]
+        TransportConfig::GrpcInput(config) => Box::new(GrpcInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-grpc"))]
+        TransportConfig::GrpcInput(_) => return Ok(None),
+        #[cfg(feature = "with-tcp")]
+        TransportConfig::TcpInput(config) => Box::new(TcpInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-tcp"))]
+        TransportConfig::TcpInput(_) => return Ok(None),
+        #[cfg(feature = "with-syslog")]
+        TransportConfig::SyslogInput(config) => Box::new(SyslogInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-syslog"))]
+        TransportConfig::SyslogInput(_) => return Ok(None),
+        #[cfg(feature = "with-unix")]
+        TransportConfig::UnixInput(config) => Box::new(UnixInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-unix"))]
+        TransportConfig::UnixInput(_) => return Ok(None),
         TransportConfig::UrlInput(config) => Box::new(UrlInputEndpoint::new(config)),
         TransportConfig::S3Input(config) => Box::new(S3InputEndpoint::new(config)?),
+        #[cfg(feature = "with-gcs")]
+        TransportConfig::GcsInput(config) => Box::new(GcsInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-gcs"))]
+        TransportConfig::GcsInput(_) => return Ok(None),
+        #[cfg(feature = "with-azure-blob")]
+        TransportConfig::AzureBlobInput(config) => Box::new(AzureBlobInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-azure-blob"))]
+        TransportConfig::AzureBlobInput(_) => return Ok(None),
+        #[cfg(feature = "with-ftp")]
+        TransportConfig::FtpInput(config) => Box::new(FtpInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-ftp"))]
+        TransportConfig::FtpInput(_) => return Ok(None),
+        #[cfg(feature = "with-http-poller")]
+        TransportConfig::HttpPollerInput(config) => Box::new(HttpPollerInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-http-poller"))]
+        TransportConfig::HttpPollerInput(_) => return Ok(None),
+        #[cfg(feature = "with-sse")]
+        TransportConfig::SseInput(config) => Box::new(SseInputEndpoint::new(config)?),
+        #[cfg(not(feature = "with-sse"))]
+        TransportConfig::SseInput(_) => return Ok(None),
         TransportConfig::Datagen(config) => Box::new(GeneratorEndpoint::new(config.clone())),
         #[cfg(feature = "with-nexmark")]
         TransportConfig::Nexmark(config) => Box::new(NexmarkEndpoint::new(config.clone())),
@@ -107,9 +275,31 @@ pub fn input_transport_config_to_endpoint(
         | TransportConfig::DeltaTableOutput(_)
         | TransportConfig::PostgresInput(_)
         | TransportConfig::PostgresOutput(_)
+        | TransportConfig::PostgresCdcInput(_)
+        | TransportConfig::MySqlCdcInput(_)
+        | TransportConfig::MySqlOutput(_)
+        | TransportConfig::InfluxDbOutput(_)
+        | TransportConfig::AlertOutput(_)
+        | TransportConfig::ArrowFlightInput(_)
+        | TransportConfig::ArrowFlightOutput(_)
+        | TransportConfig::MongoDbCdcInput(_)
+        | TransportConfig::MongoDbOutput(_)
+        | TransportConfig::S3Output(_)
+        | TransportConfig::GcsOutput(_)
+        | TransportConfig::AzureBlobOutput(_)
+        | TransportConfig::NatsKvInput(_)
         | TransportConfig::HttpOutput
         | TransportConfig::RedisOutput(_)
-        | TransportConfig::IcebergInput(_) => return Ok(None),
+        | TransportConfig::RedisStreamsOutput(_)
+        | TransportConfig::PulsarOutput(_)
+        | TransportConfig::KinesisOutput(_)
+        | TransportConfig::WebsocketOutput(_)
+        | TransportConfig::GrpcOutput(_)
+        | TransportConfig::UnixOutput(_)
+        | TransportConfig::WebhookOutput(_)
+        | TransportConfig::MqttOutput(_)
+        | TransportConfig::IcebergInput(_)
+        | TransportConfig::IcebergOutput(_) => return Ok(None),
     };
     Ok(Some(endpoint))
 }
@@ -145,6 +335,33 @@ pub fn output_transport_config_to_endpoint(
         TransportConfig::RedisOutput(config) => {
             Ok(Some(Box::new(RedisOutputEndpoint::new(config)?)))
         }
+        #[cfg(feature = "with-redis")]
+        TransportConfig::RedisStreamsOutput(config) => {
+            Ok(Some(Box::new(RedisStreamsOutputEndpoint::new(config)?)))
+        }
+        #[cfg(feature = "with-mqtt")]
+        TransportConfig::MqttOutput(config) => Ok(Some(Box::new(MqttOutputEndpoint::new(config)?))),
+        #[cfg(feature = "with-pulsar")]
+        TransportConfig::PulsarOutput(config) => Ok(Some(Box::new(PulsarOutputEndpoint::new(
+            config,
+            fault_tolerant,
+        )?))),
+        #[cfg(feature = "with-kinesis")]
+        TransportConfig::KinesisOutput(config) => {
+            Ok(Some(Box::new(KinesisOutputEndpoint::new(config)?)))
+        }
+        #[cfg(feature = "with-websocket")]
+        TransportConfig::WebsocketOutput(config) => {
+            Ok(Some(Box::new(WebsocketOutputEndpoint::new(config)?)))
+        }
+        #[cfg(feature = "with-grpc")]
+        TransportConfig::GrpcOutput(config) => Ok(Some(Box::new(GrpcOutputEndpoint::new(config)?))),
+        #[cfg(feature = "with-unix")]
+        TransportConfig::UnixOutput(config) => Ok(Some(Box::new(UnixOutputEndpoint::new(config)?))),
+        #[cfg(feature = "with-webhook")]
+        TransportConfig::WebhookOutput(config) => {
+            Ok(Some(Box::new(WebhookOutputEndpoint::new(config)?)))
+        }
         _ => Ok(None),
     }
 }
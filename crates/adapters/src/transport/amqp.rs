@@ -0,0 +1,349 @@
+//! Input transport for AMQP 0.9.1 brokers, such as RabbitMQ.
+
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Context, Error as AnyError, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::amqp::{AmqpExchangeKind, AmqpInputConfig};
+use futures::StreamExt;
+use lapin::options::{
+    BasicAckOptions, BasicConsumeOptions, BasicQosOptions, ExchangeDeclareOptions,
+    QueueBindOptions, QueueDeclareOptions,
+};
+use lapin::types::FieldTable;
+use lapin::{Channel, Connection, ConnectionProperties, Consumer, ExchangeKind};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tracing::{debug, info_span, Instrument};
+
+#[cfg(test)]
+mod test;
+
+/// Default for [`AmqpInputConfig::prefetch_count`].
+const DEFAULT_PREFETCH_COUNT: u16 = 1000;
+
+/// Delay before reconnecting to the broker after a connection error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+pub(crate) struct AmqpInputEndpoint {
+    config: Arc<AmqpInputConfig>,
+}
+
+impl AmqpInputEndpoint {
+    pub(crate) fn new(config: AmqpInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for AmqpInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for AmqpInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(AmqpInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+/// A connection to the broker, with a consumer for the connector's queue.
+struct AmqpSession {
+    /// The channel is closed when the connection is dropped.
+    _connection: Connection,
+    channel: Channel,
+    consumer: Consumer,
+}
+
+impl AmqpSession {
+    /// Connects to the broker, declares the queue, exchanges, and bindings
+    /// specified in `config`, and starts consuming from the queue.
+    async fn open(config: &AmqpInputConfig) -> AnyResult<Self> {
+        let connection = Connection::connect(&config.uri, ConnectionProperties::default())
+            .await
+            .context("error connecting to the AMQP broker")?;
+        let channel = connection
+            .create_channel()
+            .await
+            .context("error opening an AMQP channel")?;
+        channel
+            .basic_qos(
+                config.prefetch_count.unwrap_or(DEFAULT_PREFETCH_COUNT),
+                BasicQosOptions::default(),
+            )
+            .await
+            .context("error setting the AMQP prefetch count")?;
+
+        for exchange in &config.declare_exchanges {
+            let kind = match exchange.kind {
+                AmqpExchangeKind::Direct => ExchangeKind::Direct,
+                AmqpExchangeKind::Fanout => ExchangeKind::Fanout,
+                AmqpExchangeKind::Topic => ExchangeKind::Topic,
+                AmqpExchangeKind::Headers => ExchangeKind::Headers,
+            };
+            let options = ExchangeDeclareOptions {
+                durable: exchange.durable,
+                auto_delete: exchange.auto_delete,
+                ..Default::default()
+            };
+            channel
+                .exchange_declare(&exchange.name, kind, options, FieldTable::default())
+                .await
+                .with_context(|| format!("error declaring AMQP exchange '{}'", exchange.name))?;
+        }
+
+        let queue = match &config.declare_queue {
+            Some(declaration) => {
+                let options = QueueDeclareOptions {
+                    durable: declaration.durable,
+                    exclusive: declaration.exclusive,
+                    auto_delete: declaration.auto_delete,
+                    ..Default::default()
+                };
+                let queue = channel
+                    .queue_declare(&config.queue, options, FieldTable::default())
+                    .await
+                    .with_context(|| format!("error declaring AMQP queue '{}'", config.queue))?;
+                queue.name().as_str().to_string()
+            }
+            None => config.queue.clone(),
+        };
+
+        for binding in &config.bindings {
+            channel
+                .queue_bind(
+                    &queue,
+                    &binding.exchange,
+                    &binding.routing_key,
+                    QueueBindOptions::default(),
+                    FieldTable::default(),
+                )
+                .await
+                .with_context(|| {
+                    format!(
+                        "error binding AMQP queue '{queue}' to exchange '{}' with routing key '{}'",
+                        binding.exchange, binding.routing_key
+                    )
+                })?;
+        }
+
+        let consumer = channel
+            .basic_consume(
+                &queue,
+                config.consumer_tag.as_deref().unwrap_or_default(),
+                BasicConsumeOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .with_context(|| format!("error consuming from AMQP queue '{queue}'"))?;
+        debug!("AMQP input: consuming from queue '{queue}'");
+
+        Ok(Self {
+            _connection: connection,
+            channel,
+            consumer,
+        })
+    }
+}
+
+/// Identifies a message to acknowledge.
+#[derive(Clone, Copy)]
+struct DeliveryTag {
+    /// Delivery tags are scoped to a channel, so after reconnecting, messages
+    /// received in earlier sessions can no longer be acknowledged.  The broker
+    /// redelivers them instead.
+    session: u64,
+    tag: u64,
+}
+
+struct AmqpInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Parsed messages, each with the delivery tag to acknowledge once the
+    /// message has been flushed to the circuit.
+    queue: Arc<InputQueue<DeliveryTag>>,
+
+    /// Sends delivery tags to acknowledge to the worker task.
+    acks: UnboundedSender<DeliveryTag>,
+}
+
+impl AmqpInputReader {
+    fn new(
+        config: Arc<AmqpInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("amqp_input", queue = config.queue.clone());
+        let session = TOKIO.block_on(AmqpSession::open(&config).instrument(span.clone()))?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let (acks, acks_receiver) = unbounded_channel();
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("amqp-input-tokio-wrapper".to_string())
+            .spawn({
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        select! {
+                            _ = Self::worker_task(session, config, consumer.clone(), parser, queue, acks_receiver, receiver).instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("AMQP input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn AMQP input tokio wrapper thread");
+
+        Ok(Self {
+            sender,
+            consumer,
+            queue,
+            acks,
+        })
+    }
+
+    async fn worker_task(
+        mut session: AmqpSession,
+        config: Arc<AmqpInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue<DeliveryTag>>,
+        mut acks: UnboundedReceiver<DeliveryTag>,
+        mut receiver: Receiver<PipelineState>,
+    ) {
+        let mut session_id = 0;
+        loop {
+            let error = Self::consume(
+                &mut session,
+                session_id,
+                parser.as_mut(),
+                &queue,
+                &mut acks,
+                &mut receiver,
+            )
+            .await;
+            consumer.error(
+                false,
+                anyhow!("AMQP connection error (reconnecting): {error:#}"),
+                Some("amqp-connection"),
+            );
+
+            session = loop {
+                sleep(RECONNECT_DELAY).await;
+                match AmqpSession::open(&config).await {
+                    Ok(session) => break session,
+                    Err(e) => consumer.error(false, e, Some("amqp-connection")),
+                }
+            };
+            session_id += 1;
+            debug!("AMQP input: reconnected to the broker");
+        }
+    }
+
+    /// Consumes messages from `session` until an error occurs.
+    async fn consume(
+        session: &mut AmqpSession,
+        session_id: u64,
+        parser: &mut dyn Parser,
+        queue: &InputQueue<DeliveryTag>,
+        acks: &mut UnboundedReceiver<DeliveryTag>,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyError {
+        loop {
+            // The queue is only read while the pipeline is running.  Once
+            // `prefetch_count` messages are unacknowledged, the broker stops
+            // delivering messages, so they wait at the broker while the
+            // pipeline is paused.
+            let running = *receiver.borrow() == PipelineState::Running;
+            select! {
+                delivery = session.consumer.next(), if running => match delivery {
+                    Some(Ok(delivery)) => {
+                        // Use the time when we received the message as the ingestion timestamp.
+                        let timestamp = Utc::now();
+                        let tag = DeliveryTag {
+                            session: session_id,
+                            tag: delivery.delivery_tag,
+                        };
+                        queue.push_with_aux(parser.parse(&delivery.data), timestamp, tag);
+                    }
+                    Some(Err(e)) => return e.into(),
+                    None => return anyhow!("the broker canceled the consumer"),
+                },
+                Some(ack) = acks.recv() => {
+                    if ack.session == session_id {
+                        if let Err(e) = session
+                            .channel
+                            .basic_ack(ack.tag, BasicAckOptions { multiple: true })
+                            .await
+                        {
+                            return anyhow!("error acknowledging AMQP messages: {e}");
+                        }
+                    }
+                }
+                _ = receiver.changed() => {}
+            }
+        }
+    }
+}
+
+impl InputReader for AmqpInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+
+                // Messages are delivered and flushed in order, so acknowledging
+                // the last flushed message acknowledges all of them.
+                if let Some((_timestamp, tag)) = consumed.last() {
+                    let _ = self.acks.send(*tag);
+                }
+                let watermarks = consumed
+                    .into_iter()
+                    .map(|(timestamp, _tag)| Watermark::new(timestamp, None))
+                    .collect();
+                self.consumer.extended(total, None, watermarks);
+            }
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for AmqpInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
@@ -0,0 +1,101 @@
+//! Azure Blob Storage input connector.
+//!
+//! Reads blobs with [ObjectInputReader].
+
+use crate::transport::object_input::{ObjectInputReader, ObjectSelection};
+use crate::transport::InputEndpoint;
+use crate::{InputConsumer, InputReader, Parser, TransportInputEndpoint};
+use anyhow::{anyhow, Result as AnyResult};
+use feldera_types::transport::azure_blob::AzureBlobInputConfig;
+use feldera_types::{config::FtModel, program_schema::Relation};
+use object_store::azure::{AzureConfigKey, MicrosoftAzure, MicrosoftAzureBuilder};
+use std::sync::Arc;
+
+/// Credentials and endpoint for accessing an Azure storage account.
+pub(crate) struct AzureAccount<'a> {
+    pub account: &'a str,
+    pub container: &'a str,
+    pub access_key: Option<&'a str>,
+    pub sas_token: Option<&'a str>,
+    pub managed_identity_client_id: Option<&'a str>,
+    pub endpoint_url: Option<&'a str>,
+}
+
+/// Creates a client for the container in `account`.
+///
+/// Without an access key or SAS token, the client uses credentials from the
+/// environment, which include workload identity and managed identities.
+pub(crate) fn azure_object_store(account: &AzureAccount) -> AnyResult<MicrosoftAzure> {
+    let mut builder = MicrosoftAzureBuilder::from_env()
+        .with_account(account.account)
+        .with_container_name(account.container);
+    if let Some(access_key) = account.access_key {
+        builder = builder.with_access_key(access_key);
+    }
+    if let Some(sas_token) = account.sas_token {
+        builder = builder.with_config(AzureConfigKey::SasKey, sas_token.trim_start_matches('?'));
+    }
+    if let Some(client_id) = account.managed_identity_client_id {
+        builder = builder.with_client_id(client_id);
+    }
+    if let Some(endpoint_url) = account.endpoint_url {
+        builder = builder.with_endpoint(endpoint_url.to_string());
+    }
+    builder.build().map_err(|e| {
+        anyhow!(
+            "error creating Azure Blob Storage client for container '{}' in account '{}': {e}",
+            account.container,
+            account.account
+        )
+    })
+}
+
+pub(crate) struct AzureBlobInputEndpoint {
+    config: Arc<AzureBlobInputConfig>,
+}
+
+impl AzureBlobInputEndpoint {
+    pub(crate) fn new(config: AzureBlobInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for AzureBlobInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::ExactlyOnce)
+    }
+}
+
+impl TransportInputEndpoint for AzureBlobInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        seek: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        let store = azure_object_store(&AzureAccount {
+            account: &self.config.account,
+            container: &self.config.container,
+            access_key: self.config.access_key.as_deref(),
+            sas_token: self.config.sas_token.as_deref(),
+            managed_identity_client_id: self.config.managed_identity_client_id.as_deref(),
+            endpoint_url: self.config.endpoint_url.as_deref(),
+        })?;
+        Ok(Box::new(ObjectInputReader::new(
+            ObjectSelection {
+                connector: "Azure Blob Storage",
+                container: self.config.container.clone(),
+                key: self.config.key.clone(),
+                prefix: self.config.prefix.clone(),
+            },
+            consumer,
+            parser,
+            Arc::new(store),
+            seek,
+        )?))
+    }
+}
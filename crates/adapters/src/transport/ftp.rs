@@ -0,0 +1,10 @@
+//! SFTP/FTP input connector, which polls a directory on a remote server for
+//! new files.
+
+mod client;
+mod input;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::FtpInputEndpoint;
@@ -0,0 +1,233 @@
+//! Clients for listing and reading the files in a directory on an SFTP or FTP
+//! server.
+
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use async_trait::async_trait;
+use feldera_types::transport::ftp::{FtpInputConfig, FtpProtocol};
+use russh::client::{self, Handle, Handler};
+use russh::keys::{decode_secret_key, PublicKey, PublicKeyBase64};
+use russh_sftp::client::SftpSession;
+use std::sync::Arc;
+use suppaftp::types::FileType;
+use suppaftp::FtpStream;
+use tokio::task::spawn_blocking;
+
+/// A server that hosts the directory to read.
+#[async_trait]
+pub(super) trait RemoteServer: Send + Sync {
+    /// Connects to the server and opens the directory.
+    async fn connect(&self) -> AnyResult<Box<dyn RemoteDirectory>>;
+}
+
+/// A connection to a directory on a remote server.
+#[async_trait]
+pub(super) trait RemoteDirectory: Send {
+    /// Returns the names of the files in the directory.
+    async fn list(&mut self) -> AnyResult<Vec<String>>;
+
+    /// Returns the contents of file `name` in the directory.
+    async fn read(&mut self, name: &str) -> AnyResult<Vec<u8>>;
+}
+
+/// The server and directory given by an [FtpInputConfig].
+pub(super) struct FtpServer {
+    pub config: Arc<FtpInputConfig>,
+}
+
+#[async_trait]
+impl RemoteServer for FtpServer {
+    async fn connect(&self) -> AnyResult<Box<dyn RemoteDirectory>> {
+        match self.config.protocol {
+            FtpProtocol::Sftp => Ok(Box::new(SftpDirectory::connect(&self.config).await?)),
+            FtpProtocol::Ftp => Ok(Box::new(FtpDirectory::connect(&self.config).await?)),
+        }
+    }
+}
+
+/// Checks the SFTP server's public key against the configured one, if any.
+struct SshHandler {
+    server_public_key: Option<String>,
+}
+
+impl Handler for SshHandler {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKey,
+    ) -> Result<bool, Self::Error> {
+        Ok(match &self.server_public_key {
+            // OpenSSH format is `<algorithm> <base64 key> [comment]`.
+            Some(expected) => {
+                expected.split_whitespace().nth(1)
+                    == Some(server_public_key.public_key_base64().as_str())
+            }
+            None => true,
+        })
+    }
+}
+
+struct SftpDirectory {
+    // Keeps the SSH connection open.
+    _session: Handle<SshHandler>,
+    sftp: SftpSession,
+    directory: String,
+}
+
+impl SftpDirectory {
+    async fn connect(config: &FtpInputConfig) -> AnyResult<Self> {
+        let host = &config.host;
+        let port = config.port.unwrap_or(config.protocol.default_port());
+        let mut session = client::connect(
+            Arc::new(client::Config::default()),
+            (host.as_str(), port),
+            SshHandler {
+                server_public_key: config.server_public_key.clone(),
+            },
+        )
+        .await
+        .with_context(|| format!("error connecting to SFTP server '{host}:{port}'"))?;
+
+        let authenticated = match (&config.private_key, &config.password) {
+            (Some(private_key), _) => {
+                let key = decode_secret_key(private_key, config.private_key_passphrase.as_deref())
+                    .context("error decoding 'private_key'")?;
+                session
+                    .authenticate_publickey(&config.username, Arc::new(key))
+                    .await?
+            }
+            (None, Some(password)) => {
+                session
+                    .authenticate_password(&config.username, password)
+                    .await?
+            }
+            (None, None) => false,
+        };
+        if !authenticated {
+            bail!(
+                "SFTP server '{host}:{port}' rejected the credentials of user '{}'",
+                config.username
+            );
+        }
+
+        let channel = session.channel_open_session().await?;
+        channel
+            .request_subsystem(true, "sftp")
+            .await
+            .with_context(|| format!("error starting SFTP session with '{host}:{port}'"))?;
+        let sftp = SftpSession::new(channel.into_stream())
+            .await
+            .with_context(|| format!("error starting SFTP session with '{host}:{port}'"))?;
+
+        Ok(Self {
+            _session: session,
+            sftp,
+            directory: config.directory.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl RemoteDirectory for SftpDirectory {
+    async fn list(&mut self) -> AnyResult<Vec<String>> {
+        let entries = self
+            .sftp
+            .read_dir(&self.directory)
+            .await
+            .with_context(|| format!("error listing directory '{}'", self.directory))?;
+        Ok(entries
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.file_name())
+            .collect())
+    }
+
+    async fn read(&mut self, name: &str) -> AnyResult<Vec<u8>> {
+        let path = format!("{}/{name}", self.directory);
+        self.sftp
+            .read(&path)
+            .await
+            .with_context(|| format!("error reading file '{path}'"))
+    }
+}
+
+/// FTP client.
+///
+/// The FTP client is synchronous, so its operations run in blocking tasks.
+struct FtpDirectory {
+    /// `None` only while an operation runs.
+    stream: Option<FtpStream>,
+}
+
+impl FtpDirectory {
+    async fn connect(config: &FtpInputConfig) -> AnyResult<Self> {
+        let host = config.host.clone();
+        let port = config.port.unwrap_or(config.protocol.default_port());
+        let username = config.username.clone();
+        let password = config.password.clone().unwrap_or_default();
+        let directory = config.directory.clone();
+        let stream = spawn_blocking(move || {
+            let mut stream = FtpStream::connect((host.as_str(), port))
+                .with_context(|| format!("error connecting to FTP server '{host}:{port}'"))?;
+            stream.login(&username, &password).with_context(|| {
+                format!("FTP server '{host}:{port}' rejected the credentials of user '{username}'")
+            })?;
+            stream.transfer_type(FileType::Binary)?;
+            stream
+                .cwd(&directory)
+                .with_context(|| format!("error changing to directory '{directory}'"))?;
+            AnyResult::Ok(stream)
+        })
+        .await??;
+        Ok(Self {
+            stream: Some(stream),
+        })
+    }
+
+    /// Runs `f` with the FTP connection in a blocking task.
+    async fn run<T, F>(&mut self, f: F) -> AnyResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut FtpStream) -> AnyResult<T> + Send + 'static,
+    {
+        let mut stream = self
+            .stream
+            .take()
+            .ok_or_else(|| anyhow!("FTP connection was lost"))?;
+        let (stream, result) = spawn_blocking(move || {
+            let result = f(&mut stream);
+            (stream, result)
+        })
+        .await?;
+        self.stream = Some(stream);
+        result
+    }
+}
+
+#[async_trait]
+impl RemoteDirectory for FtpDirectory {
+    async fn list(&mut self) -> AnyResult<Vec<String>> {
+        self.run(|stream| {
+            let names = stream.nlst(None).context("error listing directory")?;
+            // Some servers return paths rather than names.
+            Ok(names
+                .into_iter()
+                .map(|name| match name.rsplit_once('/') {
+                    Some((_, name)) => name.to_string(),
+                    None => name,
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn read(&mut self, name: &str) -> AnyResult<Vec<u8>> {
+        let name = name.to_string();
+        self.run(move |stream| {
+            let contents = stream
+                .retr_as_buffer(&name)
+                .with_context(|| format!("error reading file '{name}'"))?;
+            Ok(contents.into_inner())
+        })
+        .await
+    }
+}
@@ -0,0 +1,322 @@
+use super::client::{FtpServer, RemoteDirectory, RemoteServer};
+use crate::format::StreamSplitter;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{parse_resume_info, Resume};
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::ftp::FtpInputConfig;
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tracing::{debug, info, info_span, Instrument};
+
+/// Default for [`FtpInputConfig::poll_interval_secs`].
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+pub(crate) struct FtpInputEndpoint {
+    config: Arc<FtpInputConfig>,
+    pattern: Pattern,
+}
+
+impl FtpInputEndpoint {
+    pub(crate) fn new(config: FtpInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+        let pattern = config.pattern.as_deref().unwrap_or("*");
+        let pattern =
+            Pattern::new(pattern).map_err(|e| anyhow!("invalid 'pattern' '{pattern}': {e}"))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            pattern,
+        })
+    }
+}
+
+impl InputEndpoint for FtpInputEndpoint {
+    /// The connector resumes by skipping the files that it read before the
+    /// checkpoint.  Files that it was reading at the time of the checkpoint
+    /// are read again from the start.
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::AtLeastOnce)
+    }
+}
+
+impl TransportInputEndpoint for FtpInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        let resume_info = resume_info
+            .map(|resume_info| parse_resume_info::<Metadata>(&resume_info))
+            .transpose()?;
+        let poll_interval = Duration::from_secs(
+            self.config
+                .poll_interval_secs
+                .unwrap_or(DEFAULT_POLL_INTERVAL_SECS),
+        );
+        Ok(Box::new(FtpInputReader::new(
+            Arc::new(FtpServer {
+                config: self.config.clone(),
+            }),
+            self.pattern.clone(),
+            poll_interval,
+            consumer,
+            parser,
+            resume_info.unwrap_or_default(),
+        )))
+    }
+}
+
+/// Checkpointed state of the connector.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Metadata {
+    /// Names of the files that have been read in full.
+    ///
+    /// Files that are removed from the directory are removed from this set,
+    /// so that it doesn't grow without bound.
+    pub files: BTreeSet<String>,
+}
+
+/// Change to [Metadata] that takes effect once the buffers queued before it
+/// have been flushed to the circuit.
+pub(super) enum Progress {
+    /// The file has been read in full.
+    Read(String),
+
+    /// The files have been removed from the directory.
+    Removed(Vec<String>),
+}
+
+pub(super) struct FtpInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+    queue: Arc<InputQueue<Option<Progress>>>,
+
+    /// State as of the buffers flushed so far.
+    pub(super) metadata: Mutex<Metadata>,
+}
+
+impl FtpInputReader {
+    pub(super) fn new(
+        server: Arc<dyn RemoteServer>,
+        pattern: Pattern,
+        poll_interval: Duration,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        resume_info: Metadata,
+    ) -> Self {
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("ftp-input-tokio-wrapper".to_string())
+            .spawn({
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                let files = resume_info.files.clone();
+                move || {
+                    let span = info_span!("ftp_input");
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        let poller = Poller {
+                            server,
+                            pattern,
+                            consumer,
+                            splitter: StreamSplitter::new(parser.splitter()),
+                            parser,
+                            queue,
+                            files,
+                            receiver,
+                        };
+                        select! {
+                            _ = poller.run(poll_interval).instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("FTP input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn FTP input tokio wrapper thread");
+
+        Self {
+            sender,
+            consumer,
+            queue,
+            metadata: Mutex::new(resume_info),
+        }
+    }
+}
+
+/// Polls the directory for new files and queues their contents.
+struct Poller {
+    server: Arc<dyn RemoteServer>,
+    pattern: Pattern,
+    consumer: Box<dyn InputConsumer>,
+    parser: Box<dyn Parser>,
+    splitter: StreamSplitter,
+    queue: Arc<InputQueue<Option<Progress>>>,
+
+    /// Names of the files that have been queued in full.
+    files: BTreeSet<String>,
+    receiver: Receiver<PipelineState>,
+}
+
+impl Poller {
+    async fn run(mut self, poll_interval: Duration) {
+        let mut directory = None;
+        loop {
+            // Files are only read while the pipeline is running.
+            if self
+                .receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            if let Err(e) = self.poll(&mut directory).await {
+                // Reconnect on the next poll.
+                directory = None;
+                self.consumer.error(false, e, Some("ftp-poll"));
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    /// Lists the directory and queues the contents of the new files in it.
+    async fn poll(&mut self, directory: &mut Option<Box<dyn RemoteDirectory>>) -> AnyResult<()> {
+        if directory.is_none() {
+            *directory = Some(self.server.connect().await?);
+        }
+        let directory = directory.as_mut().unwrap();
+        let names = directory
+            .list()
+            .await?
+            .into_iter()
+            .filter(|name| self.pattern.matches(name))
+            .collect::<BTreeSet<_>>();
+
+        let removed = self.files.difference(&names).cloned().collect::<Vec<_>>();
+        if !removed.is_empty() {
+            for name in &removed {
+                self.files.remove(name);
+            }
+            self.queue.push_with_aux(
+                (None, Vec::new()),
+                Utc::now(),
+                Some(Progress::Removed(removed)),
+            );
+        }
+
+        let new_files = names.difference(&self.files).cloned().collect::<Vec<_>>();
+        for name in new_files {
+            if self
+                .receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+
+            // Use the time when we start reading the file as the timestamp
+            // for all buffers derived from it.
+            let timestamp = Utc::now();
+            info!("FTP input: reading file '{name}'");
+            let contents = match directory.read(&name).await {
+                Ok(contents) => contents,
+                Err(e) => {
+                    // Try again on the next poll.
+                    self.consumer.error(false, e, Some("ftp-read"));
+                    continue;
+                }
+            };
+
+            self.splitter.reset();
+            self.splitter.append(&contents);
+            while let Some(chunk) = self.splitter.next(true) {
+                let (buffer, errors) = self.parser.parse(chunk);
+                let errors = errors
+                    .into_iter()
+                    .map(|e| {
+                        e.map_description(|desc| format!("error parsing file '{name}': {desc}"))
+                    })
+                    .collect::<Vec<_>>();
+                self.queue.push_with_aux((buffer, errors), timestamp, None);
+            }
+            self.queue.push_with_aux(
+                (None, Vec::new()),
+                Utc::now(),
+                Some(Progress::Read(name.clone())),
+            );
+            self.files.insert(name);
+        }
+        Ok(())
+    }
+}
+
+impl InputReader for FtpInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command {
+            InputReaderCommand::Queue { .. } => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let mut metadata = self.metadata.lock().unwrap();
+                let mut watermarks = Vec::with_capacity(consumed.len());
+                for (timestamp, progress) in consumed {
+                    match progress {
+                        Some(Progress::Read(name)) => {
+                            metadata.files.insert(name);
+                        }
+                        Some(Progress::Removed(names)) => {
+                            for name in &names {
+                                metadata.files.remove(name);
+                            }
+                        }
+                        None => (),
+                    }
+                    watermarks.push(Watermark::new(timestamp, None));
+                }
+                let resume = Resume::Seek {
+                    seek: serde_json::to_value(&*metadata).unwrap(),
+                };
+                drop(metadata);
+                self.consumer.extended(total, Some(resume), watermarks);
+            }
+            InputReaderCommand::Replay { .. } => self.consumer.error(
+                true,
+                anyhow!("the FTP input connector does not support replay"),
+                None,
+            ),
+            command => match command.as_nonft().unwrap() {
+                NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+                NonFtInputReaderCommand::Queue => unreachable!(),
+            },
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for FtpInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
@@ -0,0 +1,164 @@
+use super::client::{RemoteDirectory, RemoteServer};
+use super::input::{FtpInputReader, Metadata};
+use crate::test::{mock_parser_pipeline, wait, MockDeZSet, MockInputConsumer};
+use crate::InputReader;
+use anyhow::{anyhow, Result as AnyResult};
+use async_trait::async_trait;
+use feldera_types::config::FormatConfig;
+use feldera_types::{deserialize_without_context, program_schema::Relation};
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, PartialOrd, Ord)]
+struct TestStruct {
+    i: i64,
+}
+deserialize_without_context!(TestStruct);
+
+/// In-memory directory.
+#[derive(Clone, Default)]
+struct MemoryServer {
+    files: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+}
+
+impl MemoryServer {
+    fn put(&self, name: &str, contents: &str) {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), contents.as_bytes().to_vec());
+    }
+
+    fn remove(&self, name: &str) {
+        self.files.lock().unwrap().remove(name);
+    }
+}
+
+#[async_trait]
+impl RemoteServer for MemoryServer {
+    async fn connect(&self) -> AnyResult<Box<dyn RemoteDirectory>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[async_trait]
+impl RemoteDirectory for MemoryServer {
+    async fn list(&mut self) -> AnyResult<Vec<String>> {
+        Ok(self.files.lock().unwrap().keys().cloned().collect())
+    }
+
+    async fn read(&mut self, name: &str) -> AnyResult<Vec<u8>> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("no such file '{name}'"))
+    }
+}
+
+fn test_setup(
+    server: &MemoryServer,
+    pattern: &str,
+    resume_info: Metadata,
+) -> (
+    FtpInputReader,
+    MockInputConsumer,
+    MockDeZSet<TestStruct, TestStruct>,
+) {
+    let format: FormatConfig = serde_json::from_str(r#"{"name": "csv"}"#).unwrap();
+    let (consumer, parser, input_handle) =
+        mock_parser_pipeline::<TestStruct, TestStruct>(&Relation::empty(), &format).unwrap();
+    consumer.on_error(Some(Box::new(|_, _| ())));
+    let reader = FtpInputReader::new(
+        Arc::new(server.clone()),
+        Pattern::new(pattern).unwrap(),
+        Duration::from_millis(100),
+        Box::new(consumer.clone()),
+        Box::new(parser),
+        resume_info,
+    );
+    (reader, consumer, input_handle)
+}
+
+/// Waits until `n` records have been flushed and returns them.
+fn wait_for_records(
+    reader: &FtpInputReader,
+    input_handle: &MockDeZSet<TestStruct, TestStruct>,
+    n: usize,
+) -> Vec<i64> {
+    wait(
+        || {
+            reader.queue(false);
+            input_handle.state().flushed.len() >= n
+        },
+        10000,
+    )
+    .unwrap();
+    input_handle
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().i)
+        .collect()
+}
+
+/// Waits until the checkpointed state lists exactly the files in `names`.
+fn wait_for_files(reader: &FtpInputReader, names: &[&str]) {
+    let names = names
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<BTreeSet<_>>();
+    wait(
+        || {
+            reader.queue(false);
+            reader.metadata.lock().unwrap().files == names
+        },
+        10000,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_ftp_poll() {
+    let server = MemoryServer::default();
+    server.put("a.csv", "1\n2\n");
+    server.put("b.csv", "3\n");
+    server.put("c.txt", "100\n");
+
+    let (reader, _consumer, input_handle) = test_setup(&server, "*.csv", Metadata::default());
+    reader.extend();
+    assert_eq!(wait_for_records(&reader, &input_handle, 3), vec![1, 2, 3]);
+    wait_for_files(&reader, &["a.csv", "b.csv"]);
+
+    // New files are picked up by a later poll; files that were read
+    // already aren't read again, and removed files are forgotten.
+    server.remove("a.csv");
+    server.put("d.csv", "4\n");
+    assert_eq!(
+        wait_for_records(&reader, &input_handle, 4),
+        vec![1, 2, 3, 4]
+    );
+    wait_for_files(&reader, &["b.csv", "d.csv"]);
+}
+
+#[test]
+fn test_ftp_resume() {
+    let server = MemoryServer::default();
+    server.put("a.csv", "1\n2\n");
+    server.put("b.csv", "3\n");
+
+    let (reader, _consumer, input_handle) = test_setup(
+        &server,
+        "*",
+        Metadata {
+            files: BTreeSet::from(["a.csv".to_string()]),
+        },
+    );
+    reader.extend();
+    assert_eq!(wait_for_records(&reader, &input_handle, 1), vec![3]);
+    wait_for_files(&reader, &["a.csv", "b.csv"]);
+}
@@ -0,0 +1,80 @@
+//! Google Cloud Storage input connector.
+//!
+//! Reads objects with [ObjectInputReader].
+
+use crate::transport::object_input::{ObjectInputReader, ObjectSelection};
+use crate::transport::InputEndpoint;
+use crate::{InputConsumer, InputReader, Parser, TransportInputEndpoint};
+use anyhow::{anyhow, Result as AnyResult};
+use feldera_types::transport::gcs::GcsInputConfig;
+use feldera_types::{config::FtModel, program_schema::Relation};
+use object_store::gcp::{GoogleCloudStorage, GoogleCloudStorageBuilder};
+use std::sync::Arc;
+
+/// Creates a client for GCS bucket `bucket`.
+///
+/// Without a service account key, the client uses application default
+/// credentials, which include workload identity.
+pub(crate) fn gcs_object_store(
+    bucket: &str,
+    service_account_key: Option<&str>,
+    service_account_path: Option<&str>,
+) -> AnyResult<GoogleCloudStorage> {
+    let mut builder = GoogleCloudStorageBuilder::from_env().with_bucket_name(bucket);
+    if let Some(key) = service_account_key {
+        builder = builder.with_service_account_key(key);
+    }
+    if let Some(path) = service_account_path {
+        builder = builder.with_service_account_path(path);
+    }
+    builder
+        .build()
+        .map_err(|e| anyhow!("error creating GCS client for bucket '{bucket}': {e}"))
+}
+
+pub(crate) struct GcsInputEndpoint {
+    config: Arc<GcsInputConfig>,
+}
+
+impl GcsInputEndpoint {
+    pub(crate) fn new(config: GcsInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for GcsInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::ExactlyOnce)
+    }
+}
+
+impl TransportInputEndpoint for GcsInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        seek: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        let store = gcs_object_store(
+            &self.config.bucket_name,
+            self.config.service_account_key.as_deref(),
+            self.config.service_account_path.as_deref(),
+        )?;
+        Ok(Box::new(ObjectInputReader::new(
+            ObjectSelection {
+                connector: "GCS",
+                container: self.config.bucket_name.clone(),
+                key: self.config.key.clone(),
+                prefix: self.config.prefix.clone(),
+            },
+            consumer,
+            parser,
+            Arc::new(store),
+            seek,
+        )?))
+    }
+}
@@ -0,0 +1,31 @@
+//! Transport adapters for gRPC.
+//!
+//! The services are defined in `proto/feldera_transport.proto`.
+
+use tonic::metadata::MetadataMap;
+
+mod input;
+mod output;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::GrpcInputEndpoint;
+pub(crate) use output::GrpcOutputEndpoint;
+
+/// Code generated from `proto/feldera_transport.proto`.
+mod proto {
+    tonic::include_proto!("feldera.transport.v1");
+}
+
+/// Default for the connectors' `bind_address`.
+const DEFAULT_BIND_ADDRESS: &str = "0.0.0.0";
+
+/// Checks that the client presented `auth_token` as a bearer token.
+fn is_authorized(metadata: &MetadataMap, auth_token: &str) -> bool {
+    metadata
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        == Some(auth_token)
+}
@@ -0,0 +1,305 @@
+use super::proto::ingest_server::{Ingest, IngestServer};
+use super::proto::{PushRequest, PushResponse};
+use super::{is_authorized, DEFAULT_BIND_ADDRESS};
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::grpc::GrpcInputConfig;
+use std::sync::Arc;
+use std::thread;
+use tokio::net::TcpListener;
+use tokio::select;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_stream::wrappers::{TcpListenerStream, UnboundedReceiverStream};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{debug, info_span, Instrument};
+
+/// Default for [`GrpcInputConfig::max_queued_requests`].
+const DEFAULT_MAX_QUEUED_REQUESTS: usize = 100;
+
+/// Default for [`GrpcInputConfig::max_message_size`].
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+pub(crate) struct GrpcInputEndpoint {
+    config: Arc<GrpcInputConfig>,
+}
+
+impl GrpcInputEndpoint {
+    pub(crate) fn new(config: GrpcInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for GrpcInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for GrpcInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(GrpcInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+/// Acknowledgment of a request, sent once its data has been flushed to the
+/// circuit.
+struct Ack {
+    stream: UnboundedSender<Result<PushResponse, Status>>,
+    id: u64,
+}
+
+/// Auxiliary data for a request in the input queue.
+struct Pending {
+    /// Counts the request against the `max_queued_requests` limit of its
+    /// stream until the request is flushed.
+    _permit: OwnedSemaphorePermit,
+
+    /// The acknowledgment to send, if `acks` is set.
+    ack: Option<Ack>,
+}
+
+/// Implementation of the `Ingest` service.
+struct IngestService {
+    config: Arc<GrpcInputConfig>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Forked for each stream.
+    parser: Box<dyn Parser>,
+    queue: Arc<InputQueue<Pending>>,
+    receiver: Receiver<PipelineState>,
+}
+
+impl IngestService {
+    /// Reads requests from `stream` until the client closes it, the stream
+    /// fails, or the connector terminates.
+    async fn stream_task(
+        mut stream: Streaming<PushRequest>,
+        responses: UnboundedSender<Result<PushResponse, Status>>,
+        acks: bool,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue<Pending>>,
+        consumer: Box<dyn InputConsumer>,
+        max_queued_requests: usize,
+        mut receiver: Receiver<PipelineState>,
+    ) {
+        let queued = Arc::new(Semaphore::new(max_queued_requests));
+        loop {
+            // Requests are only read while the pipeline is running and the
+            // stream has fewer than `max_queued_requests` requests in the
+            // queue.  Otherwise, gRPC flow control makes the client wait.
+            match receiver
+                .wait_for(|state| state != &PipelineState::Paused)
+                .await
+                .map(|state| *state)
+            {
+                Ok(PipelineState::Running) => {}
+                _ => return,
+            }
+            let permit = select! {
+                permit = queued.clone().acquire_owned() => permit.unwrap(),
+                _ = receiver.changed() => continue,
+            };
+            let request = select! {
+                request = stream.message() => request,
+                _ = receiver.changed() => continue,
+            };
+
+            match request {
+                Ok(Some(request)) => {
+                    // Use the time when we received the request as the ingestion timestamp.
+                    let timestamp = Utc::now();
+                    queue.push_with_aux(
+                        parser.parse(&request.data),
+                        timestamp,
+                        Pending {
+                            _permit: permit,
+                            ack: acks.then(|| Ack {
+                                stream: responses.clone(),
+                                id: request.id,
+                            }),
+                        },
+                    );
+                }
+                Ok(None) => return,
+                Err(status) => {
+                    consumer.error(
+                        false,
+                        anyhow!("error receiving from gRPC client: {status}"),
+                        Some("grpc-receive"),
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Ingest for IngestService {
+    type PushStream = UnboundedReceiverStream<Result<PushResponse, Status>>;
+
+    async fn push(
+        &self,
+        request: Request<Streaming<PushRequest>>,
+    ) -> Result<Response<Self::PushStream>, Status> {
+        if let Some(auth_token) = &self.config.auth_token {
+            if !is_authorized(request.metadata(), auth_token) {
+                return Err(Status::unauthenticated(
+                    "missing or invalid authentication token",
+                ));
+            }
+        }
+
+        // The response stream stays open until the client closes its request
+        // stream and all of the acknowledgments have been sent, which is when
+        // the stream task and all of the stream's requests in the queue have
+        // dropped their senders.
+        let (sender, responses) = unbounded_channel();
+        TOKIO.spawn(
+            Self::stream_task(
+                request.into_inner(),
+                sender,
+                self.config.acks,
+                self.parser.fork(),
+                self.queue.clone(),
+                self.consumer.clone(),
+                self.config
+                    .max_queued_requests
+                    .unwrap_or(DEFAULT_MAX_QUEUED_REQUESTS),
+                self.receiver.clone(),
+            )
+            .in_current_span(),
+        );
+        Ok(Response::new(UnboundedReceiverStream::new(responses)))
+    }
+}
+
+struct GrpcInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Parsed requests, each with its acknowledgment.
+    queue: Arc<InputQueue<Pending>>,
+}
+
+impl GrpcInputReader {
+    fn new(
+        config: Arc<GrpcInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("grpc_input", port = config.port);
+        let bind_address = config
+            .bind_address
+            .as_deref()
+            .unwrap_or(DEFAULT_BIND_ADDRESS);
+
+        // Fail early if the port is not available.
+        let listener = TOKIO
+            .block_on(TcpListener::bind((bind_address, config.port)))
+            .with_context(|| {
+                format!(
+                    "error listening for gRPC connections on {bind_address}:{}",
+                    config.port
+                )
+            })?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        let max_message_size = config.max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE);
+        let service = IngestServer::new(IngestService {
+            config,
+            consumer: consumer.clone(),
+            parser,
+            queue: queue.clone(),
+            receiver: receiver.clone(),
+        })
+        .max_decoding_message_size(max_message_size);
+        thread::Builder::new()
+            .name("grpc-input-tokio-wrapper".to_string())
+            .spawn({
+                let consumer = consumer.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        let server = Server::builder()
+                            .add_service(service)
+                            .serve_with_incoming(TcpListenerStream::new(listener));
+                        select! {
+                            result = server.instrument(span) => {
+                                if let Err(e) = result {
+                                    consumer.error(true, anyhow!("gRPC server failed: {e}"), None);
+                                }
+                            }
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("gRPC input: received termination command; server stopped");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn gRPC input tokio wrapper thread");
+
+        Ok(Self {
+            sender,
+            consumer,
+            queue,
+        })
+    }
+}
+
+impl InputReader for GrpcInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let watermarks = consumed
+                    .into_iter()
+                    .map(|(timestamp, pending)| {
+                        if let Some(ack) = pending.ack {
+                            let _ = ack.stream.send(Ok(PushResponse { id: ack.id }));
+                        }
+                        Watermark::new(timestamp, None)
+                    })
+                    .collect();
+                self.consumer.extended(total, None, watermarks);
+            }
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for GrpcInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
@@ -0,0 +1,232 @@
+use super::proto::egress_server::{Egress, EgressServer};
+use super::proto::{OutputMessage, SubscribeRequest};
+use super::{is_authorized, DEFAULT_BIND_ADDRESS};
+use crate::transport::Step;
+use crate::{AsyncErrorCallback, OutputEndpoint};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::transport::grpc::GrpcOutputConfig;
+use std::mem::take;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{channel, Sender};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::{debug, info_span, Instrument};
+
+/// Default for [`GrpcOutputConfig::max_subscribers`].
+const DEFAULT_MAX_SUBSCRIBERS: usize = 100;
+
+/// Default for [`GrpcOutputConfig::max_queued_messages`].
+const DEFAULT_MAX_QUEUED_MESSAGES: usize = 1000;
+
+/// Default for [`GrpcOutputConfig::max_message_size`].
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// A client that called `Subscribe`.
+struct Subscriber {
+    /// The subscriber's response stream.  Its capacity is one more than
+    /// `max_queued_messages`, which leaves room to tell a subscriber that falls
+    /// behind why it is disconnected.
+    sender: Sender<Result<OutputMessage, Status>>,
+
+    /// Counts the subscriber against `max_subscribers`.
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Subscriber {
+    /// Queues `message` for the subscriber.  Returns false if the subscriber
+    /// should be dropped.
+    fn send(&self, message: &OutputMessage) -> bool {
+        if self.sender.capacity() <= 1 {
+            let _ = self.sender.try_send(Err(Status::resource_exhausted(
+                "subscriber fell too far behind",
+            )));
+            debug!("gRPC output: disconnecting a subscriber that fell behind");
+            return false;
+        }
+        self.sender.try_send(Ok(message.clone())).is_ok()
+    }
+}
+
+/// Implementation of the `Egress` service.
+struct EgressService {
+    config: Arc<GrpcOutputConfig>,
+
+    /// Subscribers that joined since the start of the current step.
+    new_subscribers: Arc<Mutex<Vec<Subscriber>>>,
+
+    /// Limits the number of subscribers to `max_subscribers`.
+    permits: Arc<Semaphore>,
+}
+
+#[tonic::async_trait]
+impl Egress for EgressService {
+    type SubscribeStream = ReceiverStream<Result<OutputMessage, Status>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        if let Some(auth_token) = &self.config.auth_token {
+            if !is_authorized(request.metadata(), auth_token) {
+                return Err(Status::unauthenticated(
+                    "missing or invalid authentication token",
+                ));
+            }
+        }
+        let permit = self
+            .permits
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| Status::resource_exhausted("too many subscribers"))?;
+
+        let max_queued_messages = self
+            .config
+            .max_queued_messages
+            .unwrap_or(DEFAULT_MAX_QUEUED_MESSAGES);
+        let (sender, receiver) = channel(max_queued_messages + 1);
+        self.new_subscribers.lock().unwrap().push(Subscriber {
+            sender,
+            _permit: permit,
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+/// Streams output to gRPC clients that subscribe to it.
+pub(crate) struct GrpcOutputEndpoint {
+    config: Arc<GrpcOutputConfig>,
+
+    /// Subscribers that joined since the start of the current step.  They
+    /// start receiving output with the next step, so that each subscriber
+    /// only sees whole steps.
+    new_subscribers: Arc<Mutex<Vec<Subscriber>>>,
+
+    /// Subscribers that receive the output of the current step.
+    subscribers: Vec<Subscriber>,
+
+    /// The current step.
+    step: Step,
+
+    /// The server task, once connected.
+    server: Option<JoinHandle<()>>,
+}
+
+impl GrpcOutputEndpoint {
+    pub(crate) fn new(config: GrpcOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            new_subscribers: Arc::new(Mutex::new(Vec::new())),
+            subscribers: Vec::new(),
+            step: 0,
+            server: None,
+        })
+    }
+
+    fn send(&mut self, message: OutputMessage) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(&message));
+    }
+}
+
+impl OutputEndpoint for GrpcOutputEndpoint {
+    fn connect(&mut self, async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        let span = info_span!("grpc_output", ft = false, port = self.config.port);
+        let bind_address = self
+            .config
+            .bind_address
+            .as_deref()
+            .unwrap_or(DEFAULT_BIND_ADDRESS);
+        let listener = TOKIO
+            .block_on(TcpListener::bind((bind_address, self.config.port)))
+            .with_context(|| {
+                format!(
+                    "error listening for gRPC connections on {bind_address}:{}",
+                    self.config.port
+                )
+            })?;
+
+        let service = EgressServer::new(EgressService {
+            config: self.config.clone(),
+            new_subscribers: self.new_subscribers.clone(),
+            permits: Arc::new(Semaphore::new(
+                self.config
+                    .max_subscribers
+                    .unwrap_or(DEFAULT_MAX_SUBSCRIBERS),
+            )),
+        });
+        self.server = Some(
+            TOKIO.spawn(
+                async move {
+                    if let Err(e) = Server::builder()
+                        .add_service(service)
+                        .serve_with_incoming(TcpListenerStream::new(listener))
+                        .await
+                    {
+                        async_error_callback(true, anyhow!("gRPC server failed: {e}"), None);
+                    }
+                }
+                .instrument(span),
+            ),
+        );
+        Ok(())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        self.config
+            .max_message_size
+            .unwrap_or(DEFAULT_MAX_MESSAGE_SIZE)
+    }
+
+    fn batch_start(&mut self, step: Step) -> AnyResult<()> {
+        self.step = step;
+        self.subscribers
+            .retain(|subscriber| !subscriber.sender.is_closed());
+        self.subscribers
+            .append(&mut take(&mut *self.new_subscribers.lock().unwrap()));
+        Ok(())
+    }
+
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        self.send(OutputMessage {
+            step: self.step,
+            data: buffer.to_vec(),
+            key: None,
+        });
+        Ok(())
+    }
+
+    /// gRPC messages have no headers, so only the key and the value are sent.
+    /// A deletion, which has no value, is sent with empty `data`.
+    fn push_key(
+        &mut self,
+        key: Option<&[u8]>,
+        val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        self.send(OutputMessage {
+            step: self.step,
+            data: val.unwrap_or_default().to_vec(),
+            key: key.map(|key| key.to_vec()),
+        });
+        Ok(())
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for GrpcOutputEndpoint {
+    fn drop(&mut self) {
+        if let Some(server) = self.server.take() {
+            server.abort();
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use super::proto::egress_client::EgressClient;
+use super::proto::ingest_client::IngestClient;
+use super::proto::{OutputMessage, PushRequest, SubscribeRequest};
+use super::GrpcOutputEndpoint;
+use crate::test::{mock_input_pipeline, wait, DEFAULT_TIMEOUT_MS};
+use crate::OutputEndpoint;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::deserialize_without_context;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::grpc::{GrpcInputConfig, GrpcOutputConfig};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serial_test::serial;
+use tokio_stream::Stream;
+use tonic::{Code, Request};
+
+/// Port that the input connector listens on in tests.
+const PORT: u16 = 18768;
+
+/// Port that the output connector listens on in tests.
+const OUTPUT_PORT: u16 = 18769;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+struct TestStruct {
+    s: String,
+    i: i64,
+}
+
+deserialize_without_context!(TestStruct);
+
+impl TestStruct {
+    fn new(s: &str, i: i64) -> Self {
+        Self {
+            s: s.to_string(),
+            i,
+        }
+    }
+}
+
+/// Returns a request with `token` as the bearer token.
+fn authorized<T>(message: T, token: Option<&str>) -> Request<T> {
+    let mut request = Request::new(message);
+    if let Some(token) = token {
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {token}").parse().unwrap());
+    }
+    request
+}
+
+/// Returns a `Push` request that streams `data` with consecutive ids,
+/// authenticated with `token`.
+fn push_request(data: &[&str], token: Option<&str>) -> Request<impl Stream<Item = PushRequest>> {
+    let requests = data
+        .iter()
+        .enumerate()
+        .map(|(id, data)| PushRequest {
+            data: data.as_bytes().to_vec(),
+            id: id as u64,
+        })
+        .collect::<Vec<_>>();
+    authorized(tokio_stream::iter(requests), token)
+}
+
+fn test_grpc_input(acks: bool) {
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "grpc_input",
+            "config": {
+                "bind_address": "127.0.0.1",
+                "port": PORT,
+                "auth_token": "secret",
+                "acks": acks
+            }
+        },
+        "format": {
+            "name": "csv",
+            "config": {}
+        }
+    }))
+    .unwrap();
+
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    let url = format!("http://127.0.0.1:{PORT}");
+    let mut client = TOKIO.block_on(IngestClient::connect(url.clone())).unwrap();
+
+    // A missing or wrong token is rejected.
+    for token in [None, Some("wrong")] {
+        let status = TOKIO
+            .block_on(client.push(push_request(&["foo,1\n"], token)))
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+    }
+
+    // Two streams send three requests.  The responses only arrive once the
+    // data has been queued, so collect them in the background.
+    let responses = ["foo,1\nbar,2\n", "baz,3\n"]
+        .into_iter()
+        .map(|data| {
+            let mut client = client.clone();
+            let request = push_request(&[data, ""], Some("secret"));
+            TOKIO.spawn(async move {
+                client
+                    .push(request)
+                    .await
+                    .unwrap()
+                    .into_inner()
+                    .map(|response| response.unwrap().id)
+                    .collect::<Vec<_>>()
+                    .await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    wait(
+        || {
+            endpoint.queue(false);
+            zset.state().flushed.len() >= 3
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+
+    let mut records = zset
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().clone())
+        .collect::<Vec<_>>();
+    records.sort_by_key(|record| record.i);
+    assert_eq!(
+        records,
+        vec![
+            TestStruct::new("foo", 1),
+            TestStruct::new("bar", 2),
+            TestStruct::new("baz", 3),
+        ]
+    );
+
+    // Each request, including the empty ones, is acknowledged in order.
+    for responses in responses {
+        wait(
+            || {
+                endpoint.queue(false);
+                responses.is_finished()
+            },
+            DEFAULT_TIMEOUT_MS,
+        )
+        .unwrap();
+        let ids = TOKIO.block_on(responses).unwrap();
+        assert_eq!(ids, if acks { vec![0, 1] } else { vec![] });
+    }
+}
+
+#[test]
+#[serial]
+fn test_grpc_input_without_acks() {
+    test_grpc_input(false);
+}
+
+#[test]
+#[serial]
+fn test_grpc_input_with_acks() {
+    test_grpc_input(true);
+}
+
+#[test]
+fn test_grpc_input_config_validation() {
+    let config = |extra: serde_json::Value| -> GrpcInputConfig {
+        let mut config = json!({ "port": 50051 });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "bind_address": "127.0.0.1", "auth_token": "secret", "acks": true, "max_queued_requests": 1, "max_message_size": 1024 }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "port": 0 })).contains("'port' must not be 0"));
+    assert!(error(json!({ "bind_address": "" })).contains("'bind_address'"));
+    assert!(error(json!({ "auth_token": "" })).contains("'auth_token'"));
+    assert!(error(json!({ "max_queued_requests": 0 })).contains("'max_queued_requests'"));
+    assert!(error(json!({ "max_message_size": 0 })).contains("'max_message_size'"));
+}
+
+#[test]
+#[serial]
+fn test_grpc_output() {
+    let config: GrpcOutputConfig = serde_json::from_value(json!({
+        "bind_address": "127.0.0.1",
+        "port": OUTPUT_PORT,
+        "auth_token": "secret",
+        "max_subscribers": 2
+    }))
+    .unwrap();
+    let mut endpoint = GrpcOutputEndpoint::new(config).unwrap();
+    endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+
+    let mut client = TOKIO
+        .block_on(EgressClient::connect(format!(
+            "http://127.0.0.1:{OUTPUT_PORT}"
+        )))
+        .unwrap();
+    let mut subscribe =
+        |token| TOKIO.block_on(client.subscribe(authorized(SubscribeRequest {}, token)));
+
+    // A missing or wrong token is rejected.
+    for token in [None, Some("wrong")] {
+        assert_eq!(subscribe(token).unwrap_err().code(), Code::Unauthenticated);
+    }
+
+    // Output from before a client subscribes is not sent to it.
+    let mut subscriber1 = subscribe(Some("secret")).unwrap().into_inner();
+    endpoint.batch_start(1).unwrap();
+    endpoint.push_buffer(b"foo,1\n").unwrap();
+    endpoint.batch_end().unwrap();
+
+    let mut subscriber2 = subscribe(Some("secret")).unwrap().into_inner();
+    assert_eq!(
+        subscribe(Some("secret")).unwrap_err().code(),
+        Code::ResourceExhausted
+    );
+    endpoint.batch_start(2).unwrap();
+    endpoint.push_buffer(b"bar,2\n").unwrap();
+    endpoint
+        .push_key(Some(b"key"), Some(b"baz,3\n"), &[])
+        .unwrap();
+    endpoint.push_key(Some(b"deleted"), None, &[]).unwrap();
+    endpoint.batch_end().unwrap();
+
+    let message = |step, data: &[u8], key: Option<&[u8]>| OutputMessage {
+        step,
+        data: data.to_vec(),
+        key: key.map(|key| key.to_vec()),
+    };
+    let step2 = vec![
+        message(2, b"bar,2\n", None),
+        message(2, b"baz,3\n", Some(b"key")),
+        message(2, b"", Some(b"deleted")),
+    ];
+    TOKIO.block_on(async {
+        let mut expected = vec![message(1, b"foo,1\n", None)];
+        expected.extend(step2.clone());
+        for message in expected {
+            assert_eq!(subscriber1.message().await.unwrap(), Some(message));
+        }
+        for message in step2 {
+            assert_eq!(subscriber2.message().await.unwrap(), Some(message));
+        }
+    });
+}
+
+#[test]
+fn test_grpc_output_config_validation() {
+    let config = |extra: serde_json::Value| -> GrpcOutputConfig {
+        let mut config = json!({ "port": 50052 });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "bind_address": "127.0.0.1", "auth_token": "secret", "max_subscribers": 1, "max_queued_messages": 1, "max_message_size": 1024 }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "port": 0 })).contains("'port' must not be 0"));
+    assert!(error(json!({ "bind_address": "" })).contains("'bind_address'"));
+    assert!(error(json!({ "auth_token": "" })).contains("'auth_token'"));
+    assert!(error(json!({ "max_subscribers": 0 })).contains("'max_subscribers'"));
+    assert!(error(json!({ "max_queued_messages": 0 })).contains("'max_queued_messages'"));
+    assert!(error(json!({ "max_message_size": 0 })).contains("'max_message_size'"));
+}
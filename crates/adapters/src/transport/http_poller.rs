@@ -0,0 +1,10 @@
+//! HTTP poller input connector, which periodically fetches a REST endpoint,
+//! following pagination and an incremental cursor.
+
+mod client;
+mod input;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::HttpPollerInputEndpoint;
@@ -0,0 +1,97 @@
+//! HTTP client for fetching pages from the polled endpoint.
+
+use anyhow::{anyhow, bail, Result as AnyResult};
+use async_trait::async_trait;
+use feldera_types::transport::http_poller::HttpPollerInputConfig;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, LINK};
+use reqwest::Client;
+use std::time::Duration;
+use url::Url;
+
+/// Default for [`HttpPollerInputConfig::timeout_secs`].
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// The maximum number of bytes of an error response to include in an error
+/// message.
+const MAX_ERROR_BODY: usize = 1024;
+
+/// Response to a `GET` request.
+pub(super) struct HttpResponse {
+    /// The `Link` headers in the response, joined with `, `.
+    pub link: Option<String>,
+
+    /// Response body.
+    pub body: Vec<u8>,
+}
+
+/// An HTTP endpoint that the connector polls.
+#[async_trait]
+pub(super) trait HttpSource: Send + Sync {
+    /// Sends a `GET` request for `url` and returns the response, or an error
+    /// if the request failed or the response status isn't successful.
+    async fn get(&self, url: &Url) -> AnyResult<HttpResponse>;
+}
+
+/// [HttpSource] that sends requests with the headers in an
+/// [HttpPollerInputConfig].
+pub(super) struct ReqwestSource {
+    client: Client,
+}
+
+impl ReqwestSource {
+    pub(super) fn new(config: &HttpPollerInputConfig) -> AnyResult<Self> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| anyhow!("invalid HTTP header name '{name}': {e}"))?;
+            let mut header_value = HeaderValue::from_str(value)
+                .map_err(|e| anyhow!("invalid value for HTTP header '{name}': {e}"))?;
+            header_value.set_sensitive(true);
+            headers.insert(header_name, header_value);
+        }
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(
+                config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            ))
+            .build()
+            .map_err(|e| anyhow!("error creating HTTP client: {e}"))?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl HttpSource for ReqwestSource {
+    async fn get(&self, url: &Url) -> AnyResult<HttpResponse> {
+        let response = self
+            .client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| anyhow!("error sending request to '{url}': {e}"))?;
+
+        let status = response.status();
+        let link = response
+            .headers()
+            .get_all(LINK)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>();
+        let link = (!link.is_empty()).then(|| link.join(", "));
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| anyhow!("error reading response from '{url}': {e}"))?;
+        if !status.is_success() {
+            let body = String::from_utf8_lossy(&body[..body.len().min(MAX_ERROR_BODY)]);
+            bail!("request to '{url}' failed with status {status}: {body}");
+        }
+
+        Ok(HttpResponse {
+            link,
+            body: body.to_vec(),
+        })
+    }
+}
@@ -0,0 +1,428 @@
+use super::client::{HttpSource, ReqwestSource};
+use crate::format::StreamSplitter;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, bail, Result as AnyResult};
+use chrono::{DateTime, Utc};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{parse_resume_info, Resume};
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::http_poller::{HttpPollerInputConfig, HttpPollerPagination};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tracing::{debug, info_span, warn, Instrument};
+use url::Url;
+
+/// Default for [`HttpPollerInputConfig::poll_interval_secs`].
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Default for [`HttpPollerInputConfig::max_pages_per_poll`].
+const DEFAULT_MAX_PAGES_PER_POLL: u32 = 1000;
+
+pub(crate) struct HttpPollerInputEndpoint {
+    config: Arc<HttpPollerInputConfig>,
+    url: Url,
+}
+
+impl HttpPollerInputEndpoint {
+    pub(crate) fn new(config: HttpPollerInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+        let url =
+            Url::parse(&config.url).map_err(|e| anyhow!("invalid 'url' '{}': {e}", config.url))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            url,
+        })
+    }
+}
+
+impl InputEndpoint for HttpPollerInputEndpoint {
+    /// The connector resumes by polling the endpoint with the incremental
+    /// cursor as of the checkpoint.  Records from a poll that was in progress
+    /// at the time of the checkpoint are fetched again.
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::AtLeastOnce)
+    }
+}
+
+impl TransportInputEndpoint for HttpPollerInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        let resume_info = resume_info
+            .map(|resume_info| parse_resume_info::<Metadata>(&resume_info))
+            .transpose()?;
+        Ok(Box::new(HttpPollerInputReader::new(
+            Arc::new(ReqwestSource::new(&self.config)?),
+            self.config.clone(),
+            self.url.clone(),
+            consumer,
+            parser,
+            resume_info.unwrap_or_else(|| Metadata {
+                cursor: self.config.initial_cursor.clone(),
+            }),
+        )))
+    }
+}
+
+/// Checkpointed state of the connector.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Metadata {
+    /// The incremental cursor to pass to the next poll, if any.
+    pub cursor: Option<String>,
+}
+
+pub(super) struct HttpPollerInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Each poll is followed by an empty buffer with the state after the
+    /// poll, which takes effect once the buffers queued before it have been
+    /// flushed to the circuit.
+    queue: Arc<InputQueue<Option<Metadata>>>,
+
+    /// State as of the buffers flushed so far.
+    pub(super) metadata: Mutex<Metadata>,
+}
+
+impl HttpPollerInputReader {
+    pub(super) fn new(
+        source: Arc<dyn HttpSource>,
+        config: Arc<HttpPollerInputConfig>,
+        url: Url,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        resume_info: Metadata,
+    ) -> Self {
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("http-poller-input-tokio-wrapper".to_string())
+            .spawn({
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                let cursor = resume_info.cursor.clone();
+                move || {
+                    let span = info_span!("http_poller_input", url = %url);
+                    let poll_interval = Duration::from_secs(
+                        config
+                            .poll_interval_secs
+                            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS),
+                    );
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        let poller = Poller {
+                            source,
+                            config,
+                            url,
+                            consumer,
+                            splitter: StreamSplitter::new(parser.splitter()),
+                            parser,
+                            queue,
+                            cursor,
+                            receiver,
+                        };
+                        select! {
+                            _ = poller.run(poll_interval).instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("HTTP poller input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn HTTP poller input tokio wrapper thread");
+
+        Self {
+            sender,
+            consumer,
+            queue,
+            metadata: Mutex::new(resume_info),
+        }
+    }
+}
+
+/// Polls the endpoint and queues the records in its responses.
+struct Poller {
+    source: Arc<dyn HttpSource>,
+    config: Arc<HttpPollerInputConfig>,
+    url: Url,
+    consumer: Box<dyn InputConsumer>,
+    parser: Box<dyn Parser>,
+    splitter: StreamSplitter,
+    queue: Arc<InputQueue<Option<Metadata>>>,
+
+    /// The incremental cursor to pass to the next poll.
+    cursor: Option<String>,
+    receiver: Receiver<PipelineState>,
+}
+
+impl Poller {
+    async fn run(mut self, poll_interval: Duration) {
+        loop {
+            // The endpoint is only polled while the pipeline is running.
+            if self
+                .receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            if let Err(e) = self.poll().await {
+                // Try again on the next poll, starting from the same cursor.
+                self.consumer.error(false, e, Some("http-poll"));
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    /// `true` if the connector needs to parse responses as JSON to find
+    /// records, the next page, or the incremental cursor.
+    fn needs_json(&self) -> bool {
+        self.config.records_pointer.is_some()
+            || self.config.pagination == HttpPollerPagination::Cursor
+            || self.config.cursor_pointer.is_some()
+    }
+
+    /// Fetches all the pages of a response and queues their records.
+    async fn poll(&mut self) -> AnyResult<()> {
+        let max_pages = self
+            .config
+            .max_pages_per_poll
+            .unwrap_or(DEFAULT_MAX_PAGES_PER_POLL);
+
+        // Use the time when we start polling as the timestamp for all buffers
+        // derived from the poll.
+        let timestamp = Utc::now();
+        let mut next_url = Some(match (&self.config.cursor_param, &self.cursor) {
+            (Some(param), Some(cursor)) => with_query_param(&self.url, param, cursor),
+            _ => self.url.clone(),
+        });
+        let mut cursor = self.cursor.clone();
+        let mut pages = 0;
+        while let Some(url) = next_url.take() {
+            if pages == max_pages {
+                warn!(
+                    "HTTP poller input: fetched the maximum of {max_pages} pages in a poll, the remaining pages will not be fetched (see 'max_pages_per_poll')"
+                );
+                break;
+            }
+            if self
+                .receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+
+            debug!("HTTP poller input: fetching '{url}'");
+            let response = self.source.get(&url).await?;
+            pages += 1;
+
+            let json = if self.needs_json() {
+                Some(
+                    serde_json::from_slice::<JsonValue>(&response.body)
+                        .map_err(|e| anyhow!("error parsing response from '{url}' as JSON: {e}"))?,
+                )
+            } else {
+                None
+            };
+
+            if let Some(pointer) = &self.config.cursor_pointer {
+                if let Some(value) = pointer_value(json.as_ref().unwrap(), pointer) {
+                    cursor = Some(value);
+                }
+            }
+
+            next_url = match self.config.pagination {
+                HttpPollerPagination::None => None,
+                HttpPollerPagination::LinkHeader => response
+                    .link
+                    .as_deref()
+                    .and_then(|link| next_link(link, &url)),
+                HttpPollerPagination::Cursor => pointer_value(
+                    json.as_ref().unwrap(),
+                    self.config.page_cursor_pointer.as_ref().unwrap(),
+                )
+                .map(|page_cursor| {
+                    with_query_param(
+                        &url,
+                        self.config.page_cursor_param.as_ref().unwrap(),
+                        &page_cursor,
+                    )
+                }),
+            };
+
+            self.parse_page(&url, response.body, json, timestamp)?;
+        }
+
+        self.cursor = cursor;
+        self.queue.push_with_aux(
+            (None, Vec::new()),
+            Utc::now(),
+            Some(Metadata {
+                cursor: self.cursor.clone(),
+            }),
+        );
+        Ok(())
+    }
+
+    /// Parses the records in a page and queues them.
+    fn parse_page(
+        &mut self,
+        url: &Url,
+        body: Vec<u8>,
+        json: Option<JsonValue>,
+        timestamp: DateTime<Utc>,
+    ) -> AnyResult<()> {
+        let data = match &self.config.records_pointer {
+            Some(pointer) => {
+                let Some(records) = json.as_ref().unwrap().pointer(pointer) else {
+                    // An empty page may omit the records.
+                    return Ok(());
+                };
+                let JsonValue::Array(records) = records else {
+                    bail!("'records_pointer' '{pointer}' does not point to an array of records in the response from '{url}'");
+                };
+
+                // Pass the records to the parser as newline-delimited JSON.
+                let mut data = Vec::new();
+                for record in records {
+                    serde_json::to_writer(&mut data, record).unwrap();
+                    data.push(b'\n');
+                }
+                data
+            }
+            None => body,
+        };
+
+        self.splitter.reset();
+        self.splitter.append(&data);
+        while let Some(chunk) = self.splitter.next(true) {
+            let (buffer, errors) = self.parser.parse(chunk);
+            let errors = errors
+                .into_iter()
+                .map(|e| {
+                    e.map_description(|desc| format!("error parsing response from '{url}': {desc}"))
+                })
+                .collect::<Vec<_>>();
+            self.queue.push_with_aux((buffer, errors), timestamp, None);
+        }
+        Ok(())
+    }
+}
+
+/// Returns `url` with query parameter `name` set to `value`, replacing any
+/// existing values of the parameter.
+pub(super) fn with_query_param(url: &Url, name: &str, value: &str) -> Url {
+    let pairs = url
+        .query_pairs()
+        .filter(|(key, _)| key != name)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+    let mut url = url.clone();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(name, value);
+    url
+}
+
+/// Returns the value that `pointer` points to in `json` as a string, or
+/// `None` if it is missing, null, or an empty string.
+pub(super) fn pointer_value(json: &JsonValue, pointer: &str) -> Option<String> {
+    match json.pointer(pointer)? {
+        JsonValue::Null => None,
+        JsonValue::String(s) if s.is_empty() => None,
+        JsonValue::String(s) => Some(s.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Returns the target of the `next` link in the value of a `Link` header,
+/// resolved relative to `base`.
+pub(super) fn next_link(header: &str, base: &Url) -> Option<Url> {
+    // Each link has the form `<target>; param1=value1; param2=value2`, and
+    // links are separated by commas.
+    let mut rest = header;
+    while let Some(start) = rest.find('<') {
+        let end = start + rest[start..].find('>')?;
+        let target = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let params = &rest[..rest.find('<').unwrap_or(rest.len())];
+        let is_next = params.split([';', ',']).any(|param| {
+            param.split_once('=').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_matches('"')
+                        .split_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("next"))
+            })
+        });
+        if is_next {
+            return base.join(target).ok();
+        }
+    }
+    None
+}
+
+impl InputReader for HttpPollerInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command {
+            InputReaderCommand::Queue { .. } => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let mut metadata = self.metadata.lock().unwrap();
+                let mut watermarks = Vec::with_capacity(consumed.len());
+                for (timestamp, progress) in consumed {
+                    if let Some(progress) = progress {
+                        *metadata = progress;
+                    }
+                    watermarks.push(Watermark::new(timestamp, None));
+                }
+                let resume = Resume::Seek {
+                    seek: serde_json::to_value(&*metadata).unwrap(),
+                };
+                drop(metadata);
+                self.consumer.extended(total, Some(resume), watermarks);
+            }
+            InputReaderCommand::Replay { .. } => self.consumer.error(
+                true,
+                anyhow!("the HTTP poller input connector does not support replay"),
+                None,
+            ),
+            command => match command.as_nonft().unwrap() {
+                NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+                NonFtInputReaderCommand::Queue => unreachable!(),
+            },
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for HttpPollerInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
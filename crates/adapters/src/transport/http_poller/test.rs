@@ -0,0 +1,280 @@
+use super::client::{HttpResponse, HttpSource};
+use super::input::{next_link, with_query_param, HttpPollerInputReader, Metadata};
+use crate::test::{mock_parser_pipeline, wait, MockDeZSet, MockInputConsumer};
+use crate::InputReader;
+use anyhow::{anyhow, Result as AnyResult};
+use async_trait::async_trait;
+use feldera_types::config::FormatConfig;
+use feldera_types::transport::http_poller::HttpPollerInputConfig;
+use feldera_types::{deserialize_without_context, program_schema::Relation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, PartialOrd, Ord)]
+struct TestStruct {
+    i: i64,
+}
+deserialize_without_context!(TestStruct);
+
+/// In-memory endpoint that serves a fixed response for each URL and records
+/// the URLs that were requested.
+#[derive(Clone, Default)]
+struct MemorySource {
+    pages: Arc<Mutex<BTreeMap<String, (Option<String>, String)>>>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MemorySource {
+    fn put(&self, url: &str, link: Option<&str>, body: &str) {
+        self.pages.lock().unwrap().insert(
+            url.to_string(),
+            (link.map(|link| link.to_string()), body.to_string()),
+        );
+    }
+
+    fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl HttpSource for MemorySource {
+    async fn get(&self, url: &Url) -> AnyResult<HttpResponse> {
+        self.requests.lock().unwrap().push(url.to_string());
+        let (link, body) = self
+            .pages
+            .lock()
+            .unwrap()
+            .get(url.as_str())
+            .cloned()
+            .ok_or_else(|| anyhow!("no such page '{url}'"))?;
+        Ok(HttpResponse {
+            link,
+            body: body.into_bytes(),
+        })
+    }
+}
+
+fn test_setup(
+    source: &MemorySource,
+    config: serde_json::Value,
+    resume_info: Metadata,
+) -> (
+    HttpPollerInputReader,
+    MockInputConsumer,
+    MockDeZSet<TestStruct, TestStruct>,
+) {
+    let config: HttpPollerInputConfig = serde_json::from_value(config).unwrap();
+    config.validate().unwrap();
+    let format: FormatConfig =
+        serde_json::from_value(json!({"name": "json", "config": {"update_format": "raw"}}))
+            .unwrap();
+    let (consumer, parser, input_handle) =
+        mock_parser_pipeline::<TestStruct, TestStruct>(&Relation::empty(), &format).unwrap();
+    consumer.on_error(Some(Box::new(|_, _| ())));
+    let url = Url::parse(&config.url).unwrap();
+    let reader = HttpPollerInputReader::new(
+        Arc::new(source.clone()),
+        Arc::new(config),
+        url,
+        Box::new(consumer.clone()),
+        Box::new(parser),
+        resume_info,
+    );
+    (reader, consumer, input_handle)
+}
+
+/// Waits until `n` records have been flushed and returns them.
+fn wait_for_records(
+    reader: &HttpPollerInputReader,
+    input_handle: &MockDeZSet<TestStruct, TestStruct>,
+    n: usize,
+) -> Vec<i64> {
+    wait(
+        || {
+            reader.queue(false);
+            input_handle.state().flushed.len() >= n
+        },
+        10000,
+    )
+    .unwrap();
+    input_handle
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().i)
+        .collect()
+}
+
+/// Waits until the checkpointed cursor is `cursor`.
+fn wait_for_cursor(reader: &HttpPollerInputReader, cursor: &str) {
+    wait(
+        || {
+            reader.queue(false);
+            reader.metadata.lock().unwrap().cursor.as_deref() == Some(cursor)
+        },
+        10000,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_next_link() {
+    let base = Url::parse("https://example.com/items?page=1").unwrap();
+    assert_eq!(
+        next_link(
+            r#"<https://example.com/items?page=2>; rel="next", <https://example.com/items?page=5>; rel="last""#,
+            &base
+        )
+        .unwrap()
+        .as_str(),
+        "https://example.com/items?page=2"
+    );
+    assert_eq!(
+        next_link(
+            r#"</items?page=1>; rel="prev first", </items?page=3>; rel="next""#,
+            &base
+        )
+        .unwrap()
+        .as_str(),
+        "https://example.com/items?page=3"
+    );
+    assert_eq!(
+        next_link(r#"<https://example.com/items?page=1>; rel="first""#, &base),
+        None
+    );
+}
+
+#[test]
+fn test_with_query_param() {
+    let url = Url::parse("https://example.com/items?limit=10&cursor=a").unwrap();
+    assert_eq!(
+        with_query_param(&url, "cursor", "b c").as_str(),
+        "https://example.com/items?limit=10&cursor=b+c"
+    );
+    let url = Url::parse("https://example.com/items").unwrap();
+    assert_eq!(
+        with_query_param(&url, "since", "5").as_str(),
+        "https://example.com/items?since=5"
+    );
+}
+
+#[test]
+fn test_link_header_pagination() {
+    let source = MemorySource::default();
+    source.put(
+        "http://example.com/items",
+        Some(r#"<http://example.com/items?page=2>; rel="next""#),
+        r#"{"i": 1} {"i": 2}"#,
+    );
+    source.put("http://example.com/items?page=2", None, r#"{"i": 3}"#);
+
+    let (reader, _consumer, input_handle) = test_setup(
+        &source,
+        json!({
+            "url": "http://example.com/items",
+            "pagination": "link_header",
+            "poll_interval_secs": 3600
+        }),
+        Metadata::default(),
+    );
+    reader.extend();
+    assert_eq!(wait_for_records(&reader, &input_handle, 3), vec![1, 2, 3]);
+    assert_eq!(
+        source.requests(),
+        vec![
+            "http://example.com/items".to_string(),
+            "http://example.com/items?page=2".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_cursor_pagination() {
+    let source = MemorySource::default();
+    source.put(
+        "http://example.com/items?since=5",
+        None,
+        r#"{"data": [{"i": 6}, {"i": 7}], "next": "p2", "max": 7}"#,
+    );
+    source.put(
+        "http://example.com/items?since=5&page=p2",
+        None,
+        r#"{"data": [{"i": 8}], "next": null, "max": 8}"#,
+    );
+    source.put(
+        "http://example.com/items?since=8",
+        None,
+        r#"{"data": [{"i": 9}], "max": 9}"#,
+    );
+
+    let (reader, _consumer, input_handle) = test_setup(
+        &source,
+        json!({
+            "url": "http://example.com/items",
+            "records_pointer": "/data",
+            "pagination": "cursor",
+            "page_cursor_param": "page",
+            "page_cursor_pointer": "/next",
+            "cursor_param": "since",
+            "cursor_pointer": "/max",
+            "initial_cursor": "5",
+            "poll_interval_secs": 1
+        }),
+        Metadata {
+            cursor: Some("5".to_string()),
+        },
+    );
+    reader.extend();
+
+    // The second poll starts from the cursor returned by the last page of the
+    // first poll.
+    assert_eq!(
+        wait_for_records(&reader, &input_handle, 4),
+        vec![6, 7, 8, 9]
+    );
+    wait_for_cursor(&reader, "9");
+    assert_eq!(
+        source.requests()[0..3],
+        [
+            "http://example.com/items?since=5".to_string(),
+            "http://example.com/items?since=5&page=p2".to_string(),
+            "http://example.com/items?since=8".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_http_poller_resume() {
+    let source = MemorySource::default();
+    source.put(
+        "http://example.com/items?since=2",
+        None,
+        r#"{"data": [{"i": 3}], "max": 3}"#,
+    );
+
+    let (reader, _consumer, input_handle) = test_setup(
+        &source,
+        json!({
+            "url": "http://example.com/items",
+            "records_pointer": "/data",
+            "cursor_param": "since",
+            "cursor_pointer": "/max",
+            "initial_cursor": "0",
+            "poll_interval_secs": 3600
+        }),
+        Metadata {
+            cursor: Some("2".to_string()),
+        },
+    );
+    reader.extend();
+    assert_eq!(wait_for_records(&reader, &input_handle, 1), vec![3]);
+    wait_for_cursor(&reader, "3");
+    assert_eq!(
+        source.requests(),
+        vec!["http://example.com/items?since=2".to_string()]
+    );
+}
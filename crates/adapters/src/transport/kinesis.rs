@@ -0,0 +1,270 @@
+//! Output transport for AWS Kinesis Data Streams.
+
+use crate::{AsyncErrorCallback, OutputEndpoint};
+use anyhow::{anyhow, bail, Result as AnyResult};
+use aws_sdk_kinesis::config::retry::RetryConfig;
+use aws_sdk_kinesis::config::{Credentials, Region};
+use aws_sdk_kinesis::error::DisplayErrorContext;
+use aws_sdk_kinesis::primitives::Blob;
+use aws_sdk_kinesis::types::PutRecordsRequestEntry;
+use aws_sdk_kinesis::Client;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::transport::kinesis::{KinesisOutputConfig, MAX_PARTITION_KEY_LEN};
+use sha2::{Digest, Sha256};
+use std::cmp::min;
+use std::mem::take;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::span::EnteredSpan;
+use tracing::{debug, info_span};
+
+#[cfg(test)]
+mod test;
+
+/// Maximum number of records in a `PutRecords` request.
+const MAX_RECORDS_PER_REQUEST: usize = 500;
+
+/// Maximum size of a `PutRecords` request, including partition keys.
+const MAX_REQUEST_SIZE: usize = 5 * 1024 * 1024;
+
+/// Maximum size of a record, including its partition key.
+const MAX_RECORD_SIZE: usize = 1024 * 1024;
+
+/// Default for [`KinesisOutputConfig::max_retries`].
+const DEFAULT_MAX_RETRIES: u32 = 10;
+
+/// Delay before the first retry of rejected records.  The delay doubles with
+/// each retry, up to [`MAX_BACKOFF`].
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Maximum delay between retries of rejected records.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+fn span(config: &KinesisOutputConfig) -> EnteredSpan {
+    info_span!(
+        "kinesis_output",
+        ft = false,
+        stream_name = config.stream_name.clone()
+    )
+    .entered()
+}
+
+/// Publishes output to a Kinesis data stream.
+pub(crate) struct KinesisOutputEndpoint {
+    config: KinesisOutputConfig,
+    client: Option<Client>,
+
+    /// Records for the next `PutRecords` request.
+    records: Vec<PutRecordsRequestEntry>,
+
+    /// Size of `records`, including partition keys.
+    request_size: usize,
+}
+
+impl KinesisOutputEndpoint {
+    pub(crate) fn new(config: KinesisOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config,
+            client: None,
+            records: Vec::new(),
+            request_size: 0,
+        })
+    }
+
+    /// Returns the partition key for a record with the given `key`.
+    fn partition_key(&self, key: Option<&[u8]>) -> String {
+        match key {
+            Some(key) => match std::str::from_utf8(key) {
+                Ok(key) if !key.is_empty() && key.chars().count() <= MAX_PARTITION_KEY_LEN => {
+                    key.to_string()
+                }
+                _ => format!("{:x}", Sha256::digest(key)),
+            },
+            None => self
+                .config
+                .partition_key
+                .clone()
+                .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>())),
+        }
+    }
+
+    /// Adds a record to the next request, first sending the records collected
+    /// so far if the request would exceed Kinesis limits.
+    fn push(&mut self, partition_key: String, data: &[u8]) -> AnyResult<()> {
+        let size = partition_key.len() + data.len();
+        if self.records.len() >= MAX_RECORDS_PER_REQUEST
+            || self.request_size + size > MAX_REQUEST_SIZE
+        {
+            self.flush()?;
+        }
+
+        let record = PutRecordsRequestEntry::builder()
+            .partition_key(partition_key)
+            .data(Blob::new(data))
+            .build()?;
+        self.records.push(record);
+        self.request_size += size;
+        Ok(())
+    }
+
+    /// Sends the collected records to Kinesis, retrying rejected records with
+    /// exponential backoff.
+    fn flush(&mut self) -> AnyResult<()> {
+        let mut records = take(&mut self.records);
+        self.request_size = 0;
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let _guard = span(&self.config);
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| anyhow!("Kinesis: trying to write before connecting: unreachable"))?;
+        let stream_name = &self.config.stream_name;
+        let max_retries = self.config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        TOKIO.block_on(async {
+            let mut backoff = INITIAL_BACKOFF;
+            let mut retries = 0;
+            loop {
+                let response = client
+                    .put_records()
+                    .stream_name(stream_name)
+                    .set_records(Some(records.clone()))
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        anyhow!(
+                            "error writing to Kinesis stream '{stream_name}': {}",
+                            DisplayErrorContext(e)
+                        )
+                    })?;
+                if response.failed_record_count().unwrap_or(0) == 0 {
+                    return Ok(());
+                }
+
+                // Results are in the same order as the records in the
+                // request.  Kinesis rejects records when the stream's
+                // throughput limits are exceeded or after internal failures,
+                // both of which are worth retrying.
+                let mut error = None;
+                records = response
+                    .records()
+                    .iter()
+                    .zip(records)
+                    .filter_map(|(result, record)| {
+                        let code = result.error_code()?;
+                        error.get_or_insert_with(|| {
+                            format!("{code}: {}", result.error_message().unwrap_or_default())
+                        });
+                        Some(record)
+                    })
+                    .collect();
+                let error = error.unwrap_or_default();
+                if retries >= max_retries {
+                    bail!(
+                        "Kinesis stream '{stream_name}' rejected {} records after {max_retries} retries ({error})",
+                        records.len()
+                    );
+                }
+                debug!(
+                    "Kinesis stream '{stream_name}' rejected {} records ({error}); retrying in {backoff:?}",
+                    records.len()
+                );
+                sleep(backoff).await;
+                backoff = min(backoff * 2, MAX_BACKOFF);
+                retries += 1;
+            }
+        })
+    }
+}
+
+fn to_kinesis_config(config: &KinesisOutputConfig) -> aws_sdk_kinesis::Config {
+    // Requests that fail as a whole, e.g., because of throttling, are retried
+    // by the SDK.
+    let mut config_builder = aws_sdk_kinesis::Config::builder()
+        .region(Region::new(config.region.clone()))
+        .retry_config(
+            RetryConfig::standard()
+                .with_max_attempts(config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES) + 1)
+                .with_initial_backoff(INITIAL_BACKOFF)
+                .with_max_backoff(MAX_BACKOFF),
+        );
+
+    if let Some(endpoint) = &config.endpoint_url {
+        config_builder = config_builder.endpoint_url(endpoint);
+    }
+
+    if let (Some(access_key), Some(secret_key)) =
+        (&config.aws_access_key_id, &config.aws_secret_access_key)
+    {
+        let credentials =
+            Credentials::new(access_key, secret_key, None, None, "credential-provider");
+
+        config_builder.credentials_provider(credentials).build()
+    } else {
+        let provider = TOKIO.block_on(async {
+            aws_config::default_provider::credentials::default_provider().await
+        });
+
+        config_builder.credentials_provider(provider).build()
+    }
+}
+
+impl OutputEndpoint for KinesisOutputEndpoint {
+    fn connect(&mut self, _async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        let client = Client::from_conf(to_kinesis_config(&self.config));
+
+        // Fail early if the stream doesn't exist or isn't accessible.
+        let _guard = span(&self.config);
+        TOKIO
+            .block_on(
+                client
+                    .describe_stream_summary()
+                    .stream_name(&self.config.stream_name)
+                    .send(),
+            )
+            .map_err(|e| {
+                anyhow!(
+                    "error accessing Kinesis stream '{}': {}",
+                    self.config.stream_name,
+                    DisplayErrorContext(e)
+                )
+            })?;
+
+        self.client = Some(client);
+        Ok(())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        // Leave room for the partition key, up to 4 bytes per character.
+        MAX_RECORD_SIZE - 4 * MAX_PARTITION_KEY_LEN
+    }
+
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        let partition_key = self.partition_key(None);
+        self.push(partition_key, buffer)
+    }
+
+    /// Kinesis records have no headers, so `headers` is ignored.  A deletion,
+    /// which has no value, is published as an empty record.
+    fn push_key(
+        &mut self,
+        key: Option<&[u8]>,
+        val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        let partition_key = self.partition_key(key);
+        self.push(partition_key, val.unwrap_or_default())
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        self.flush()
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
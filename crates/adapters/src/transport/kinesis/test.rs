@@ -0,0 +1,187 @@
+use super::KinesisOutputEndpoint;
+use crate::OutputEndpoint;
+use aws_sdk_kinesis::config::{Credentials, Region};
+use aws_sdk_kinesis::types::{ShardIteratorType, StreamStatus};
+use aws_sdk_kinesis::Client;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::transport::kinesis::KinesisOutputConfig;
+use serde_json::json;
+use serial_test::serial;
+use std::env;
+use std::time::Duration;
+
+/// URL of a LocalStack instance that provides the Kinesis API.
+fn localstack_url() -> String {
+    env::var("LOCALSTACK_URL").unwrap_or("http://localhost:4566".to_string())
+}
+
+fn client() -> Client {
+    let config = aws_sdk_kinesis::Config::builder()
+        .region(Region::new("us-east-1"))
+        .endpoint_url(localstack_url())
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .build();
+    Client::from_conf(config)
+}
+
+/// Creates `stream` with a single shard, deleting it first if it exists, and
+/// waits until it is active.
+async fn create_stream(client: &Client, stream: &str) {
+    if client
+        .delete_stream()
+        .stream_name(stream)
+        .send()
+        .await
+        .is_ok()
+    {
+        while client
+            .describe_stream_summary()
+            .stream_name(stream)
+            .send()
+            .await
+            .is_ok()
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+    client
+        .create_stream()
+        .stream_name(stream)
+        .shard_count(1)
+        .send()
+        .await
+        .unwrap();
+    loop {
+        let summary = client
+            .describe_stream_summary()
+            .stream_name(stream)
+            .send()
+            .await
+            .unwrap();
+        if summary
+            .stream_description_summary()
+            .unwrap()
+            .stream_status()
+            == &StreamStatus::Active
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Reads all records from the single shard of `stream` and returns their
+/// partition keys and data.
+async fn read_stream(client: &Client, stream: &str) -> Vec<(String, String)> {
+    let shards = client
+        .list_shards()
+        .stream_name(stream)
+        .send()
+        .await
+        .unwrap();
+    let shard_id = shards.shards()[0].shard_id();
+    let mut iterator = client
+        .get_shard_iterator()
+        .stream_name(stream)
+        .shard_id(shard_id)
+        .shard_iterator_type(ShardIteratorType::TrimHorizon)
+        .send()
+        .await
+        .unwrap()
+        .shard_iterator
+        .unwrap();
+    let mut records = Vec::new();
+    loop {
+        let output = client
+            .get_records()
+            .shard_iterator(&iterator)
+            .send()
+            .await
+            .unwrap();
+        if output.records().is_empty() {
+            return records;
+        }
+        records.extend(output.records().iter().map(|record| {
+            (
+                record.partition_key().to_string(),
+                String::from_utf8(record.data().as_ref().to_vec()).unwrap(),
+            )
+        }));
+        iterator = output.next_shard_iterator.unwrap();
+    }
+}
+
+#[test]
+#[serial]
+fn test_kinesis_output() {
+    let stream = "feldera_test_kinesis_output";
+    let client = client();
+    TOKIO.block_on(create_stream(&client, stream));
+
+    let config: KinesisOutputConfig = serde_json::from_value(json!({
+        "stream_name": stream,
+        "region": "us-east-1",
+        "endpoint_url": localstack_url(),
+        "aws_access_key_id": "test",
+        "aws_secret_access_key": "test",
+        "partition_key": "unkeyed"
+    }))
+    .unwrap();
+    let mut endpoint = KinesisOutputEndpoint::new(config).unwrap();
+    endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+
+    // More records than fit in a single `PutRecords` request.
+    let long_key = "k".repeat(300);
+    endpoint.batch_start(0).unwrap();
+    endpoint.push_buffer(b"first").unwrap();
+    endpoint
+        .push_key(Some(b"k1".as_slice()), Some(b"second".as_slice()), &[])
+        .unwrap();
+    endpoint
+        .push_key(Some(long_key.as_bytes()), Some(b"third".as_slice()), &[])
+        .unwrap();
+    for i in 0..600 {
+        endpoint.push_buffer(format!("{i}").as_bytes()).unwrap();
+    }
+    endpoint.batch_end().unwrap();
+
+    let records = TOKIO.block_on(read_stream(&client, stream));
+    assert_eq!(records.len(), 603);
+    assert_eq!(records[0], ("unkeyed".to_string(), "first".to_string()));
+    assert_eq!(records[1], ("k1".to_string(), "second".to_string()));
+
+    // Keys longer than 256 characters are hashed.
+    assert_eq!(records[2].0.len(), 64);
+    assert_eq!(records[2].1, "third");
+    assert_eq!(records[602], ("unkeyed".to_string(), "599".to_string()));
+}
+
+#[test]
+fn test_kinesis_output_config_validation() {
+    let config = |extra: serde_json::Value| -> KinesisOutputConfig {
+        let mut config = json!({
+            "stream_name": "events",
+            "region": "us-east-1"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "aws_access_key_id": "id", "aws_secret_access_key": "secret" }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "stream_name": "" })).contains("'stream_name' must not be empty"));
+    assert!(error(json!({ "region": "" })).contains("'region' must not be empty"));
+    assert!(error(json!({ "aws_access_key_id": "id" })).contains("'aws_secret_access_key' not set"));
+    assert!(
+        error(json!({ "aws_secret_access_key": "secret" })).contains("'aws_access_key_id' not set")
+    );
+    assert!(error(json!({ "partition_key": "" })).contains("'partition_key'"));
+    assert!(error(json!({ "partition_key": "k".repeat(257) })).contains("'partition_key'"));
+}
@@ -0,0 +1,380 @@
+//! Transport adapter for MQTT brokers.
+//!
+//! `rumqttc` implements MQTT 3.1.1 and MQTT 5 as two separate clients.
+//! [`MqttClient`] and [`MqttEventLoop`] wrap both, so that connectors can
+//! support either protocol version without duplicating their logic.
+
+use crate::transport::tls::tls_client_config;
+use anyhow::{anyhow, Result as AnyResult};
+use bytes::Bytes;
+use feldera_types::transport::mqtt::{MqttConnectionConfig, MqttProtocolVersion, MqttQos};
+use rumqttc::v5::mqttbytes::v5 as packets_v5;
+use rumqttc::{NetworkOptions, TlsConfiguration, Transport};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+mod input;
+mod output;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::MqttInputEndpoint;
+pub(crate) use output::MqttOutputEndpoint;
+
+/// Default for [`MqttConnectionConfig::max_packet_size`].
+const DEFAULT_MAX_PACKET_SIZE: u32 = 1024 * 1024;
+
+/// Capacity of the channel that carries requests from an [`MqttClient`] to
+/// its event loop.
+///
+/// Messages are acknowledged with `try_ack`, which fails if the channel is
+/// full.  At most `u16::MAX` messages, one per packet identifier, can await
+/// acknowledgment at any time, so with this capacity acknowledgments never
+/// fail for lack of space.
+const REQUEST_CHANNEL_CAPACITY: usize = u16::MAX as usize + 64;
+
+/// Delay before reconnecting to the broker after a connection error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Client handle used to send requests to the broker.
+#[derive(Clone)]
+pub(crate) enum MqttClient {
+    V311(rumqttc::AsyncClient),
+    V5(rumqttc::v5::AsyncClient),
+}
+
+/// Event loop that maintains the connection to the broker.
+///
+/// The connection only makes progress while the event loop is polled.
+/// Polling after a connection error reconnects to the broker.
+pub(crate) enum MqttEventLoop {
+    V311(Box<rumqttc::EventLoop>),
+    V5(Box<rumqttc::v5::EventLoop>),
+}
+
+/// Handle used to acknowledge a QoS 1 message.
+///
+/// The acknowledgment only needs the packet identifier of the message, so
+/// there is no need to retain the message itself.
+pub(crate) struct MqttAck {
+    pkid: u16,
+}
+
+/// An event that is relevant to connectors.
+pub(crate) enum MqttEvent {
+    /// Connected or reconnected to the broker.
+    Connected { session_present: bool },
+
+    /// Received a message.  `ack` is `None` for QoS 0 messages, which are not
+    /// acknowledged.
+    Message {
+        payload: Bytes,
+        ack: Option<MqttAck>,
+    },
+
+    /// The broker rejected a subscription to a topic filter.
+    SubscriptionRejected { reason: String },
+
+    /// The broker rejected a message published by the client.  Only MQTT 5
+    /// brokers report this; MQTT 3.1.1 brokers silently drop such messages
+    /// or close the connection.
+    PublishRejected { reason: String },
+
+    /// Any other event.
+    Other,
+}
+
+/// Creates a client and an event loop for the broker described by `config`.
+///
+/// With `manual_acks`, received QoS 1 messages must be acknowledged with
+/// [`MqttClient::ack`].  The client does not connect until the event loop is
+/// first polled.
+pub(crate) fn create_client(
+    config: &MqttConnectionConfig,
+    manual_acks: bool,
+) -> AnyResult<(MqttClient, MqttEventLoop)> {
+    let address = config.broker_address().map_err(|e| anyhow!(e))?;
+    let client_id = config
+        .client_id
+        .clone()
+        .unwrap_or_else(|| format!("feldera{:016x}", rand::random::<u64>()));
+    let transport = if address.tls {
+        let tls = config.tls.clone().unwrap_or_default();
+        Transport::tls_with_config(TlsConfiguration::Rustls(Arc::new(tls_client_config(&tls)?)))
+    } else {
+        Transport::tcp()
+    };
+    let max_packet_size = config.max_packet_size.unwrap_or(DEFAULT_MAX_PACKET_SIZE);
+    let mut network_options = NetworkOptions::new();
+    if let Some(secs) = config.connection_timeout_secs {
+        network_options.set_connection_timeout(secs);
+    }
+
+    debug!(
+        "MQTT: creating {:?} client '{client_id}' for '{}:{}'",
+        config.protocol_version, address.host, address.port
+    );
+    match config.protocol_version {
+        MqttProtocolVersion::V311 => {
+            let mut options = rumqttc::MqttOptions::new(client_id, address.host, address.port);
+            options
+                .set_transport(transport)
+                .set_clean_session(config.clean_session)
+                .set_manual_acks(manual_acks)
+                .set_max_packet_size(max_packet_size as usize, max_packet_size as usize);
+            if let Some(secs) = config.keep_alive_secs {
+                options.set_keep_alive(Duration::from_secs(secs));
+            }
+            if let Some(username) = &config.username {
+                options.set_credentials(username, config.password.clone().unwrap_or_default());
+            }
+            let (client, mut eventloop) =
+                rumqttc::AsyncClient::new(options, REQUEST_CHANNEL_CAPACITY);
+            eventloop.set_network_options(network_options);
+            Ok((
+                MqttClient::V311(client),
+                MqttEventLoop::V311(Box::new(eventloop)),
+            ))
+        }
+        MqttProtocolVersion::V5 => {
+            let mut options = rumqttc::v5::MqttOptions::new(client_id, address.host, address.port);
+            options
+                .set_transport(transport)
+                .set_clean_start(config.clean_session)
+                .set_manual_acks(manual_acks)
+                .set_max_packet_size(Some(max_packet_size))
+                .set_network_options(network_options);
+            if let Some(secs) = config.keep_alive_secs {
+                options.set_keep_alive(Duration::from_secs(secs));
+            }
+            if let Some(username) = &config.username {
+                options.set_credentials(username, config.password.clone().unwrap_or_default());
+            }
+            let (client, eventloop) =
+                rumqttc::v5::AsyncClient::new(options, REQUEST_CHANNEL_CAPACITY);
+            Ok((
+                MqttClient::V5(client),
+                MqttEventLoop::V5(Box::new(eventloop)),
+            ))
+        }
+    }
+}
+
+/// Creates a client for the broker described by `config` and waits until it
+/// connects, so that an unreachable broker or invalid credentials are reported
+/// right away.
+pub(crate) async fn connect(
+    config: &MqttConnectionConfig,
+    manual_acks: bool,
+) -> AnyResult<(MqttClient, MqttEventLoop)> {
+    let (client, mut eventloop) = create_client(config, manual_acks)?;
+    loop {
+        if let MqttEvent::Connected { .. } = eventloop.poll().await.map_err(|e| {
+            anyhow!(
+                "error connecting to MQTT broker '{}': {e}",
+                config.server_url
+            )
+        })? {
+            return Ok((client, eventloop));
+        }
+    }
+}
+
+impl MqttClient {
+    /// Subscribes to `filters`.  The broker reports the outcome through a
+    /// [`MqttEvent::SubscriptionRejected`] event, if it rejects any of them.
+    pub(crate) async fn subscribe(&self, filters: &[String], qos: MqttQos) -> AnyResult<()> {
+        match self {
+            MqttClient::V311(client) => {
+                let qos = match qos {
+                    MqttQos::AtMostOnce => rumqttc::QoS::AtMostOnce,
+                    MqttQos::AtLeastOnce => rumqttc::QoS::AtLeastOnce,
+                };
+                client
+                    .subscribe_many(
+                        filters
+                            .iter()
+                            .map(|filter| rumqttc::SubscribeFilter::new(filter.clone(), qos)),
+                    )
+                    .await
+                    .map_err(|e| anyhow!("error subscribing to MQTT topics: {e}"))
+            }
+            MqttClient::V5(client) => {
+                let qos = match qos {
+                    MqttQos::AtMostOnce => rumqttc::v5::mqttbytes::QoS::AtMostOnce,
+                    MqttQos::AtLeastOnce => rumqttc::v5::mqttbytes::QoS::AtLeastOnce,
+                };
+                client
+                    .subscribe_many(
+                        filters
+                            .iter()
+                            .map(|filter| packets_v5::Filter::new(filter.clone(), qos)),
+                    )
+                    .await
+                    .map_err(|e| anyhow!("error subscribing to MQTT topics: {e}"))
+            }
+        }
+    }
+
+    /// Publishes `payload` to `topic`.
+    ///
+    /// With MQTT 5, `user_properties` are attached to the message.  MQTT 3.1.1
+    /// messages cannot carry properties, so they are discarded.
+    ///
+    /// Waits if too many requests are already queued for the event loop.
+    pub(crate) async fn publish(
+        &self,
+        topic: &str,
+        qos: MqttQos,
+        retain: bool,
+        payload: &[u8],
+        user_properties: Vec<(String, String)>,
+    ) -> AnyResult<()> {
+        match self {
+            MqttClient::V311(client) => {
+                let qos = match qos {
+                    MqttQos::AtMostOnce => rumqttc::QoS::AtMostOnce,
+                    MqttQos::AtLeastOnce => rumqttc::QoS::AtLeastOnce,
+                };
+                client
+                    .publish(topic, qos, retain, payload.to_vec())
+                    .await
+                    .map_err(|e| anyhow!("error publishing MQTT message to topic '{topic}': {e}"))
+            }
+            MqttClient::V5(client) => {
+                let qos = match qos {
+                    MqttQos::AtMostOnce => rumqttc::v5::mqttbytes::QoS::AtMostOnce,
+                    MqttQos::AtLeastOnce => rumqttc::v5::mqttbytes::QoS::AtLeastOnce,
+                };
+                let properties = packets_v5::PublishProperties {
+                    user_properties,
+                    ..Default::default()
+                };
+                client
+                    .publish_with_properties(
+                        topic,
+                        qos,
+                        retain,
+                        Bytes::copy_from_slice(payload),
+                        properties,
+                    )
+                    .await
+                    .map_err(|e| anyhow!("error publishing MQTT message to topic '{topic}': {e}"))
+            }
+        }
+    }
+
+    /// Acknowledges a message.
+    pub(crate) fn ack(&self, ack: &MqttAck) -> AnyResult<()> {
+        match self {
+            MqttClient::V311(client) => {
+                let mut publish =
+                    rumqttc::Publish::new("", rumqttc::QoS::AtLeastOnce, Vec::<u8>::new());
+                publish.pkid = ack.pkid;
+                client
+                    .try_ack(&publish)
+                    .map_err(|e| anyhow!("error acknowledging MQTT message: {e}"))
+            }
+            MqttClient::V5(client) => {
+                let mut publish = packets_v5::Publish::new(
+                    "",
+                    rumqttc::v5::mqttbytes::QoS::AtLeastOnce,
+                    Vec::<u8>::new(),
+                    None,
+                );
+                publish.pkid = ack.pkid;
+                client
+                    .try_ack(&publish)
+                    .map_err(|e| anyhow!("error acknowledging MQTT message: {e}"))
+            }
+        }
+    }
+}
+
+impl MqttEventLoop {
+    /// Polls the connection for the next event.
+    ///
+    /// After an error, the next call attempts to reconnect to the broker.
+    pub(crate) async fn poll(&mut self) -> AnyResult<MqttEvent> {
+        match self {
+            MqttEventLoop::V311(eventloop) => {
+                use rumqttc::{Event, Packet, SubscribeReasonCode};
+
+                match eventloop.poll().await? {
+                    Event::Incoming(Packet::ConnAck(connack)) => Ok(MqttEvent::Connected {
+                        session_present: connack.session_present,
+                    }),
+                    Event::Incoming(Packet::Publish(publish)) => Ok(MqttEvent::Message {
+                        ack: (publish.qos != rumqttc::QoS::AtMostOnce)
+                            .then_some(MqttAck { pkid: publish.pkid }),
+                        payload: publish.payload,
+                    }),
+                    Event::Incoming(Packet::SubAck(suback))
+                        if suback.return_codes.contains(&SubscribeReasonCode::Failure) =>
+                    {
+                        Ok(MqttEvent::SubscriptionRejected {
+                            reason: "the broker returned a failure code".to_string(),
+                        })
+                    }
+                    _ => Ok(MqttEvent::Other),
+                }
+            }
+            MqttEventLoop::V5(eventloop) => {
+                use packets_v5::{Packet, PubAckReason, SubscribeReasonCode};
+                use rumqttc::v5::Event;
+
+                match eventloop.poll().await? {
+                    Event::Incoming(Packet::ConnAck(connack)) => Ok(MqttEvent::Connected {
+                        session_present: connack.session_present,
+                    }),
+                    Event::Incoming(Packet::Publish(publish)) => Ok(MqttEvent::Message {
+                        ack: (publish.qos != rumqttc::v5::mqttbytes::QoS::AtMostOnce)
+                            .then_some(MqttAck { pkid: publish.pkid }),
+                        payload: publish.payload,
+                    }),
+                    Event::Incoming(Packet::SubAck(suback)) => {
+                        match suback
+                            .return_codes
+                            .iter()
+                            .find(|code| !matches!(code, SubscribeReasonCode::Success(_)))
+                        {
+                            Some(code) => Ok(MqttEvent::SubscriptionRejected {
+                                reason: match suback
+                                    .properties
+                                    .and_then(|properties| properties.reason_string)
+                                {
+                                    Some(reason) => format!("{code:?} ({reason})"),
+                                    None => format!("{code:?}"),
+                                },
+                            }),
+                            None => Ok(MqttEvent::Other),
+                        }
+                    }
+                    Event::Incoming(Packet::PubAck(puback))
+                        if !matches!(
+                            puback.reason,
+                            PubAckReason::Success | PubAckReason::NoMatchingSubscribers
+                        ) =>
+                    {
+                        Ok(MqttEvent::PublishRejected {
+                            reason: match puback
+                                .properties
+                                .and_then(|properties| properties.reason_string)
+                            {
+                                Some(reason) => format!("{:?} ({reason})", puback.reason),
+                                None => format!("{:?}", puback.reason),
+                            },
+                        })
+                    }
+                    Event::Incoming(Packet::Disconnect(disconnect)) => Err(anyhow!(
+                        "MQTT broker closed the connection: {:?}",
+                        disconnect.reason_code
+                    )),
+                    _ => Ok(MqttEvent::Other),
+                }
+            }
+        }
+    }
+}
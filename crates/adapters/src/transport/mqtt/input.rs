@@ -0,0 +1,207 @@
+use super::{connect, MqttAck, MqttClient, MqttEvent, MqttEventLoop, RECONNECT_DELAY};
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, bail, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::mqtt::{MqttInputConfig, MqttQos};
+use std::sync::Arc;
+use std::thread;
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tracing::{debug, info_span, Instrument};
+
+pub(crate) struct MqttInputEndpoint {
+    config: Arc<MqttInputConfig>,
+}
+
+impl MqttInputEndpoint {
+    pub(crate) fn new(config: MqttInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for MqttInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for MqttInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(MqttInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+struct MqttInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+    client: MqttClient,
+
+    /// Parsed messages, each with the acknowledgment to send once the
+    /// message has been flushed to the circuit.
+    queue: Arc<InputQueue<Option<MqttAck>>>,
+}
+
+impl MqttInputReader {
+    fn new(
+        config: Arc<MqttInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!(
+            "mqtt_input",
+            server_url = config.connection_config.server_url.clone()
+        );
+        let manual_acks = config.qos == MqttQos::AtLeastOnce;
+        let (client, eventloop) = TOKIO
+            .block_on(connect(&config.connection_config, manual_acks).instrument(span.clone()))?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("mqtt-input-tokio-wrapper".to_string())
+            .spawn({
+                let client = client.clone();
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        select! {
+                            result = Self::worker_task(client, eventloop, config, consumer.clone(), parser, queue, receiver).instrument(span) => {
+                                if let Err(e) = result {
+                                    consumer.error(true, e, None);
+                                }
+                            }
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("MQTT input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn MQTT input tokio wrapper thread");
+
+        Ok(Self {
+            sender,
+            consumer,
+            client,
+            queue,
+        })
+    }
+
+    async fn worker_task(
+        client: MqttClient,
+        mut eventloop: MqttEventLoop,
+        config: Arc<MqttInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue<Option<MqttAck>>>,
+        mut receiver: Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        let filters = config.topic_filters();
+        client.subscribe(&filters, config.qos).await?;
+
+        loop {
+            // The broker is only read while the pipeline is running, so
+            // messages wait at the broker while it is paused.
+            wait_running(&mut receiver).await;
+            let event = select! {
+                event = eventloop.poll() => event,
+                _ = receiver.changed() => continue,
+            };
+
+            match event {
+                Ok(MqttEvent::Connected { session_present }) => {
+                    debug!("MQTT input: reconnected to the broker");
+                    // Subscriptions are part of the session, so they must be
+                    // renewed if the broker did not keep the session.
+                    if !session_present {
+                        client.subscribe(&filters, config.qos).await?;
+                    }
+                }
+                Ok(MqttEvent::Message { payload, ack }) => {
+                    // Use the time when we received the message as the ingestion timestamp.
+                    let timestamp = Utc::now();
+                    queue.push_with_aux(parser.parse(&payload), timestamp, ack);
+                }
+                Ok(MqttEvent::SubscriptionRejected { reason }) => {
+                    bail!(
+                        "MQTT broker rejected the subscription to topic filters {filters:?}: {reason}"
+                    );
+                }
+                Ok(MqttEvent::PublishRejected { .. } | MqttEvent::Other) => {}
+                Err(e) => {
+                    consumer.error(
+                        false,
+                        anyhow!("MQTT connection error (reconnecting): {e}"),
+                        Some("mqtt-connection"),
+                    );
+                    sleep(RECONNECT_DELAY).await;
+                }
+            }
+        }
+    }
+}
+
+impl InputReader for MqttInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let mut watermarks = Vec::with_capacity(consumed.len());
+                for (timestamp, ack) in consumed {
+                    watermarks.push(Watermark::new(timestamp, None));
+                    if let Some(ack) = ack {
+                        if let Err(e) = self.client.ack(&ack) {
+                            self.consumer.error(false, e, Some("mqtt-ack"));
+                        }
+                    }
+                }
+                self.consumer.extended(total, None, watermarks);
+            }
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for MqttInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/// Block until the state is `Running`.
+async fn wait_running(receiver: &mut Receiver<PipelineState>) {
+    // An error indicates that the channel was closed.  It's ok to ignore
+    // the error as this situation will be handled by the top-level select,
+    // which will abort the worker thread.
+    let _ = receiver
+        .wait_for(|state| state == &PipelineState::Running)
+        .await;
+}
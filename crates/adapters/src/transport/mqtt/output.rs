@@ -0,0 +1,144 @@
+use super::{connect, MqttClient, MqttEvent, DEFAULT_MAX_PACKET_SIZE, RECONNECT_DELAY};
+use crate::{AsyncErrorCallback, OutputEndpoint};
+use anyhow::{anyhow, Result as AnyResult};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::transport::mqtt::MqttOutputConfig;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::span::EnteredSpan;
+use tracing::{debug, info_span, Instrument};
+
+/// Max overhead added by the MQTT PUBLISH packet header to each message,
+/// excluding the topic name.  Payload size plus this overhead plus the length
+/// of the topic must not exceed the maximum packet size.
+const MAX_PACKET_OVERHEAD: usize = 64;
+
+fn span(config: &MqttOutputConfig) -> EnteredSpan {
+    info_span!(
+        "mqtt_output",
+        ft = false,
+        server_url = config.connection_config.server_url.clone(),
+        topic = config.topic.clone()
+    )
+    .entered()
+}
+
+/// Publishes output to an MQTT topic.
+pub(crate) struct MqttOutputEndpoint {
+    config: MqttOutputConfig,
+    client: Option<MqttClient>,
+
+    /// Task that polls the event loop, which sends published messages to the
+    /// broker and reconnects after connection errors.
+    event_loop_task: Option<JoinHandle<()>>,
+}
+
+impl MqttOutputEndpoint {
+    pub(crate) fn new(config: MqttOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config,
+            client: None,
+            event_loop_task: None,
+        })
+    }
+
+    fn publish(&self, payload: &[u8], user_properties: Vec<(String, String)>) -> AnyResult<()> {
+        let _guard = span(&self.config);
+        let client = self.client.as_ref().ok_or_else(|| {
+            anyhow!("MQTT: trying to publish before connecting to the broker: unreachable")
+        })?;
+        TOKIO.block_on(client.publish(
+            &self.config.topic,
+            self.config.qos,
+            self.config.retain,
+            payload,
+            user_properties,
+        ))
+    }
+}
+
+impl OutputEndpoint for MqttOutputEndpoint {
+    fn connect(&mut self, async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        let span = span(&self.config);
+        let (client, mut eventloop) =
+            TOKIO.block_on(connect(&self.config.connection_config, false))?;
+
+        let event_loop_task = async move {
+            loop {
+                match eventloop.poll().await {
+                    Ok(MqttEvent::Connected { .. }) => {
+                        debug!("MQTT output: reconnected to the broker");
+                    }
+                    Ok(MqttEvent::PublishRejected { reason }) => async_error_callback(
+                        false,
+                        anyhow!("MQTT broker rejected a published message: {reason}"),
+                        Some("mqtt-publish"),
+                    ),
+                    Ok(_) => {}
+                    Err(e) => {
+                        async_error_callback(
+                            false,
+                            anyhow!("MQTT connection error (reconnecting): {e}"),
+                            Some("mqtt-connection"),
+                        );
+                        sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        };
+        self.event_loop_task = Some(TOKIO.spawn(event_loop_task.instrument(span.exit())));
+        self.client = Some(client);
+
+        Ok(())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        let max_packet_size = self
+            .config
+            .connection_config
+            .max_packet_size
+            .unwrap_or(DEFAULT_MAX_PACKET_SIZE) as usize;
+        max_packet_size
+            .saturating_sub(MAX_PACKET_OVERHEAD + self.config.topic.len())
+            .max(1)
+    }
+
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        self.publish(buffer, Vec::new())
+    }
+
+    /// MQTT messages do not have keys, so only the value is published.  A
+    /// deletion, which has no value, is published as an empty message, which
+    /// also clears the retained message of the topic if `retain` is set.
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        val: Option<&[u8]>,
+        headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        let user_properties = headers
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.unwrap_or_default()).into_owned(),
+                )
+            })
+            .collect();
+        self.publish(val.unwrap_or_default(), user_properties)
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for MqttOutputEndpoint {
+    fn drop(&mut self) {
+        if let Some(task) = self.event_loop_task.take() {
+            task.abort();
+        }
+    }
+}
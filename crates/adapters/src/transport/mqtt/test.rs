@@ -0,0 +1,429 @@
+use super::{create_client, MqttOutputEndpoint};
+use crate::test::{mock_input_pipeline, wait, MockDeZSet, DEFAULT_TIMEOUT_MS};
+use crate::{InputReader, OutputEndpoint};
+use feldera_types::deserialize_without_context;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::mqtt::{
+    validate_topic_filter, validate_topic_name, MqttBrokerAddress, MqttConnectionConfig,
+    MqttInputConfig, MqttOutputConfig, MqttProtocolVersion,
+};
+use feldera_types::transport::tls::TlsConfig;
+use rumqttc::{Client, Event, Incoming, MqttOptions, Outgoing, QoS};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serial_test::serial;
+use std::env;
+use std::thread::sleep;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+struct TestStruct {
+    s: String,
+    b: bool,
+    i: i64,
+}
+
+deserialize_without_context!(TestStruct);
+
+impl TestStruct {
+    fn new(s: &str, b: bool, i: i64) -> Self {
+        Self {
+            s: s.to_string(),
+            b,
+            i,
+        }
+    }
+}
+
+fn mqtt_url() -> String {
+    env::var("MQTT_URL").unwrap_or("mqtt://localhost:1883".to_string())
+}
+
+fn connection_config(server_url: &str) -> MqttConnectionConfig {
+    serde_json::from_value(json!({ "server_url": server_url })).unwrap()
+}
+
+/// Publishes `messages` as `(topic, payload)` pairs with QoS 1 and waits
+/// until the broker has acknowledged all of them.
+fn publish(messages: &[(String, &str)], retain: bool) {
+    let address = connection_config(&mqtt_url()).broker_address().unwrap();
+    let options = MqttOptions::new("feldera-test-publisher", address.host, address.port);
+    let (client, mut connection) = Client::new(options, messages.len() + 1);
+    for (topic, payload) in messages {
+        client
+            .publish(topic, QoS::AtLeastOnce, retain, payload.as_bytes())
+            .unwrap();
+    }
+    client.disconnect().unwrap();
+    for event in connection.iter() {
+        if let Event::Outgoing(Outgoing::Disconnect) = event.expect("MQTT publisher failed") {
+            break;
+        }
+    }
+}
+
+/// Reads the records received by the input pipeline until there are `n` of
+/// them, and returns them sorted.
+fn received(
+    endpoint: &dyn InputReader,
+    zset: &MockDeZSet<TestStruct, TestStruct>,
+    n: usize,
+) -> Vec<TestStruct> {
+    wait(
+        || {
+            endpoint.queue(false);
+            zset.state().flushed.len() >= n
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+
+    let mut records = zset
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().clone())
+        .collect::<Vec<_>>();
+    records.sort_by(|a, b| a.s.cmp(&b.s));
+    records
+}
+
+#[test]
+#[serial]
+fn test_mqtt_input() {
+    let prefix = "feldera/test/mqtt_input";
+    let messages = [
+        (format!("{prefix}/1"), "foo,true,10\n"),
+        (format!("{prefix}/2"), "bar,false,-10\nbaz,true,0\n"),
+    ];
+
+    // Retained messages are delivered as soon as the connector subscribes.
+    publish(&messages, true);
+
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "mqtt_input",
+            "config": {
+                "connection_config": {
+                    "server_url": mqtt_url()
+                },
+                "topics": [format!("{prefix}/+")],
+                "qos": "at_least_once"
+            }
+        },
+        "format": {
+            "name": "csv",
+            "config": {}
+        }
+    }))
+    .unwrap();
+
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    assert_eq!(
+        received(endpoint.as_ref(), &zset, 3),
+        vec![
+            TestStruct::new("bar", false, -10),
+            TestStruct::new("baz", true, 0),
+            TestStruct::new("foo", true, 10),
+        ]
+    );
+
+    // Clear the retained messages.
+    publish(
+        &messages
+            .iter()
+            .map(|(topic, _)| (topic.clone(), ""))
+            .collect::<Vec<_>>(),
+        true,
+    );
+}
+
+#[test]
+#[serial]
+fn test_mqtt_v5_shared_subscription_input() {
+    let topic = "feldera/test/mqtt_v5_shared_input";
+
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "mqtt_input",
+            "config": {
+                "connection_config": {
+                    "server_url": mqtt_url(),
+                    "protocol_version": "5"
+                },
+                "topics": [topic],
+                "shared_group": "feldera",
+                "qos": "at_most_once"
+            }
+        },
+        "format": {
+            "name": "csv",
+            "config": {}
+        }
+    }))
+    .unwrap();
+
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    // Brokers don't deliver retained messages to shared subscriptions, so
+    // give the connector time to subscribe before publishing.
+    sleep(Duration::from_millis(1000));
+    publish(
+        &[(topic.to_string(), "foo,true,10\nbar,false,-10\n")],
+        false,
+    );
+
+    assert_eq!(
+        received(endpoint.as_ref(), &zset, 2),
+        vec![
+            TestStruct::new("bar", false, -10),
+            TestStruct::new("foo", true, 10),
+        ]
+    );
+}
+
+#[test]
+#[serial]
+fn test_mqtt_output() {
+    let topic = "feldera/test/mqtt_output";
+
+    // Subscribe before publishing, so that no messages are missed.
+    let address = connection_config(&mqtt_url()).broker_address().unwrap();
+    let options = MqttOptions::new("feldera-test-subscriber", address.host, address.port);
+    let (client, mut connection) = Client::new(options, 10);
+    client.subscribe(topic, QoS::AtLeastOnce).unwrap();
+    for event in connection.iter() {
+        if let Event::Incoming(Incoming::SubAck(_)) = event.expect("MQTT subscriber failed") {
+            break;
+        }
+    }
+
+    let config: MqttOutputConfig = serde_json::from_value(json!({
+        "connection_config": {
+            "server_url": mqtt_url(),
+            "protocol_version": "5"
+        },
+        "topic": topic
+    }))
+    .unwrap();
+    let mut endpoint = MqttOutputEndpoint::new(config).unwrap();
+    endpoint
+        .connect(Box::new(|_fatal, _error, _tag| {}))
+        .unwrap();
+    endpoint.push_buffer(b"foo,true,10\n").unwrap();
+    endpoint
+        .push_key(Some(b"key"), Some(b"bar,false,-10\n"), &[("h", Some(b"v"))])
+        .unwrap();
+
+    let mut payloads = Vec::new();
+    for event in connection.iter() {
+        if let Event::Incoming(Incoming::Publish(publish)) = event.expect("MQTT subscriber failed")
+        {
+            assert_eq!(publish.topic, topic);
+            payloads.push(String::from_utf8(publish.payload.to_vec()).unwrap());
+            if payloads.len() == 2 {
+                break;
+            }
+        }
+    }
+    assert_eq!(payloads, vec!["foo,true,10\n", "bar,false,-10\n"]);
+}
+
+#[test]
+fn test_broker_address() {
+    let address = |url: &str| connection_config(url).broker_address();
+
+    assert_eq!(
+        address("mqtt://broker").unwrap(),
+        MqttBrokerAddress {
+            host: "broker".to_string(),
+            port: 1883,
+            tls: false
+        }
+    );
+    assert_eq!(
+        address("mqtts://broker.example.com/").unwrap(),
+        MqttBrokerAddress {
+            host: "broker.example.com".to_string(),
+            port: 8883,
+            tls: true
+        }
+    );
+    assert_eq!(
+        address("tcp://10.0.0.1:1884").unwrap(),
+        MqttBrokerAddress {
+            host: "10.0.0.1".to_string(),
+            port: 1884,
+            tls: false
+        }
+    );
+    assert_eq!(
+        address("ssl://[::1]:8884").unwrap(),
+        MqttBrokerAddress {
+            host: "::1".to_string(),
+            port: 8884,
+            tls: true
+        }
+    );
+
+    for (url, expected) in [
+        ("broker:1883", "expected a URL"),
+        ("http://broker", "the scheme must be"),
+        ("mqtt://:1883", "missing host"),
+        ("mqtt://broker:port", "invalid port 'port'"),
+        ("mqtt://broker:70000", "invalid port '70000'"),
+        ("mqtt://user@broker", "scheme, host, and optional port"),
+        ("mqtt://broker/topic", "scheme, host, and optional port"),
+        ("mqtt://[::1", "missing ']'"),
+    ] {
+        let err = address(url).unwrap_err();
+        assert!(err.contains(expected), "{url}: {err}");
+    }
+}
+
+#[test]
+fn test_topic_filter_validation() {
+    for filter in [
+        "sensors",
+        "sensors/+/temperature",
+        "sensors/#",
+        "#",
+        "+",
+        "/leading/slash",
+        "$share/group/sensors/#",
+    ] {
+        validate_topic_filter(filter).unwrap();
+    }
+
+    for (filter, expected) in [
+        ("", "must not be empty"),
+        ("sensors/temp+", "whole level"),
+        ("sensors/#/temperature", "must be the last level"),
+        ("sensors#", "whole level"),
+    ] {
+        let err = validate_topic_filter(filter).unwrap_err();
+        assert!(err.contains(expected), "{filter}: {err}");
+    }
+}
+
+#[test]
+fn test_topic_name_validation() {
+    for topic in ["sensors", "sensors/1/temperature", "/leading/slash"] {
+        validate_topic_name(topic).unwrap();
+    }
+
+    for (topic, expected) in [
+        ("", "must not be empty"),
+        ("sensors/+/temperature", "must not contain wildcards"),
+        ("sensors/#", "must not contain wildcards"),
+    ] {
+        let err = validate_topic_name(topic).unwrap_err();
+        assert!(err.contains(expected), "{topic}: {err}");
+    }
+
+    let config: MqttOutputConfig = serde_json::from_value(json!({
+        "connection_config": { "server_url": "mqtt://broker" },
+        "topic": "alerts/#"
+    }))
+    .unwrap();
+    assert!(config
+        .validate()
+        .unwrap_err()
+        .contains("must not contain wildcards"));
+}
+
+#[test]
+fn test_mqtt_input_config_validation() {
+    let config = |extra: serde_json::Value| -> MqttInputConfig {
+        let mut config = json!({
+            "connection_config": { "server_url": "mqtt://broker" },
+            "topics": ["sensors/#"]
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    let shared = config(json!({ "shared_group": "feldera" }));
+    shared.validate().unwrap();
+    assert_eq!(shared.topic_filters(), vec!["$share/feldera/sensors/#"]);
+
+    assert!(error(json!({ "topics": [] })).contains("at least one topic filter"));
+    assert!(error(json!({ "topics": ["a/#/b"] })).contains("must be the last level"));
+    assert!(error(json!({ "shared_group": "a/b" })).contains("invalid 'shared_group'"));
+    assert!(error(json!({
+        "topics": ["$share/group/sensors/#"],
+        "shared_group": "feldera"
+    }))
+    .contains("already a shared subscription"));
+
+    let connection_error = |connection_config: serde_json::Value| {
+        error(json!({ "connection_config": connection_config }))
+    };
+    assert!(connection_error(json!({
+        "server_url": "mqtt://broker",
+        "tls": {}
+    }))
+    .contains("requires an 'mqtts://' server URL"));
+    assert!(connection_error(json!({
+        "server_url": "mqtts://broker",
+        "tls": { "client_cert_pem": "cert" }
+    }))
+    .contains("without a client key"));
+    assert!(connection_error(json!({
+        "server_url": "mqtt://broker",
+        "clean_session": false
+    }))
+    .contains("'client_id' is required"));
+    assert!(connection_error(json!({
+        "server_url": "mqtt://broker",
+        "password": "secret"
+    }))
+    .contains("requires a 'username'"));
+    assert!(connection_error(json!({
+        "server_url": "mqtt://broker",
+        "keep_alive_secs": 100000
+    }))
+    .contains("'keep_alive_secs' must be at most"));
+    assert!(connection_error(json!({
+        "server_url": "mqtt://broker",
+        "max_packet_size": 0
+    }))
+    .contains("'max_packet_size' must be greater than 0"));
+}
+
+#[test]
+fn test_create_client() {
+    for protocol_version in [MqttProtocolVersion::V311, MqttProtocolVersion::V5] {
+        let mut config = connection_config("mqtt://localhost");
+        config.protocol_version = protocol_version;
+        config.username = Some("user".to_string());
+        config.password = Some("password".to_string());
+        config.keep_alive_secs = Some(30);
+        create_client(&config, true).unwrap();
+
+        // `mqtts://` URLs use the built-in root certificates by default.
+        let mut config = connection_config("mqtts://localhost");
+        config.protocol_version = protocol_version;
+        create_client(&config, false).unwrap();
+
+        // Errors in TLS files are reported when the client is created.
+        config.tls = Some(TlsConfig {
+            ca_cert_path: Some("/nonexistent/ca.pem".to_string()),
+            ..Default::default()
+        });
+        let err = create_client(&config, false).err().unwrap().to_string();
+        assert!(err.contains("error reading 'ca_cert_path' file"), "{err}");
+    }
+}
@@ -0,0 +1,179 @@
+use crate::transport::tls::tls_client_config;
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use async_nats::{Client, ServerAddr};
+use context::apply_context;
+use feldera_types::transport::nats::{Auth, ConnectOptions};
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::debug;
+
+mod context;
+mod object_store;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use object_store::NatsObjectStoreInputEndpoint;
+
+/// Converts connector connection options into `async_nats` client options.
+pub(crate) fn translate_connect_options(
+    config: &ConnectOptions,
+    endpoint_name: &str,
+) -> AnyResult<async_nats::ConnectOptions> {
+    // Settings loaded from a context haven't been validated yet.
+    config.validate().map_err(|e| anyhow!(e))?;
+
+    let name = config
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("feldera/{endpoint_name}"));
+    let mut options = async_nats::ConnectOptions::new().name(name);
+    if let Some(prefix) = &config.inbox_prefix {
+        options = options.custom_inbox_prefix(prefix);
+    }
+
+    match &config.auth {
+        None => {}
+        Some(Auth::UserPassword { username, password }) => {
+            options = options.user_and_password(username.clone(), password.clone());
+        }
+        Some(Auth::Token { token }) => {
+            options = options.token(token.clone());
+        }
+        Some(Auth::Nkey { seed, seed_path }) => {
+            options = options.nkey(read_nkey_seed(seed, seed_path)?);
+        }
+        Some(Auth::Credentials {
+            credentials,
+            credentials_path,
+        }) => {
+            let credentials = read_inline_or_file("credentials", credentials, credentials_path)?;
+            options = options
+                .credentials(&credentials)
+                .map_err(|e| anyhow!("invalid NATS credentials: {e}"))?;
+        }
+        Some(Auth::AuthCallout {
+            sentinel_credentials,
+            sentinel_credentials_path,
+            token,
+            username,
+            password,
+        }) => {
+            let credentials = read_inline_or_file(
+                "sentinel_credentials",
+                sentinel_credentials,
+                sentinel_credentials_path,
+            )?;
+            options = options
+                .credentials(&credentials)
+                .map_err(|e| anyhow!("invalid NATS sentinel credentials: {e}"))?;
+            if let Some(token) = token {
+                options = options.token(token.clone());
+            }
+            if let Some(username) = username {
+                options = options
+                    .user_and_password(username.clone(), password.clone().unwrap_or_default());
+            }
+        }
+    }
+
+    if config.retry_on_initial_connect {
+        options = options.retry_on_initial_connect();
+    }
+    if config.retain_servers_order {
+        options = options.retain_servers_order();
+    }
+    if let Some(secs) = config.ping_interval_secs {
+        options = options.ping_interval(Duration::from_secs(secs));
+    }
+    if let Some(secs) = config.connection_timeout_secs {
+        options = options.connection_timeout(Duration::from_secs(secs));
+    }
+    if let Some(max_reconnects) = config.max_reconnects {
+        options = options.max_reconnects(max_reconnects);
+    }
+    if let Some(ms) = config.reconnect_delay_ms {
+        let delay = Duration::from_millis(ms);
+        options = options.reconnect_delay_callback(move |_attempts| delay);
+    }
+    if let Some(size) = config.reconnect_buffer_size {
+        options = options.client_capacity(size);
+    }
+
+    if let Some(tls) = &config.tls {
+        options = options
+            .require_tls(true)
+            .tls_client_config(tls_client_config(tls)?);
+    }
+
+    Ok(options)
+}
+
+/// Connects to the NATS cluster described by `config`.
+pub(crate) async fn connect(config: &ConnectOptions, endpoint_name: &str) -> AnyResult<Client> {
+    let config = apply_context(config)?;
+    let addrs = server_addrs(&config)?;
+    let options = translate_connect_options(&config, endpoint_name)?;
+
+    let urls = config.server_urls.join(", ");
+    debug!("NATS: connecting to '{urls}'");
+    options
+        .connect(addrs)
+        .await
+        .map_err(|e| anyhow!("error connecting to NATS server(s) '{urls}': {e}"))
+}
+
+/// Parses the server URLs in `config`.
+pub(crate) fn server_addrs(config: &ConnectOptions) -> AnyResult<Vec<ServerAddr>> {
+    if config.server_urls.is_empty() {
+        bail!(
+            "'server_urls' must specify at least one server, either directly or through 'context'"
+        );
+    }
+    config
+        .server_urls
+        .iter()
+        .map(|url| {
+            let addr = ServerAddr::from_str(url)
+                .map_err(|e| anyhow!("invalid NATS server URL '{url}': {e}"))?;
+            // The client never upgrades WebSocket connections to TLS, so
+            // `tls` would be silently ignored for `ws://` URLs.
+            if config.tls.is_some() && addr.scheme() == "ws" {
+                bail!(
+                    "'tls' cannot be used with WebSocket URL '{url}'; use a 'wss://' URL instead"
+                );
+            }
+            Ok(addr)
+        })
+        .collect()
+}
+
+/// Returns the NKey seed, specified either inline or as a path to a file.
+fn read_nkey_seed(seed: &Option<String>, seed_path: &Option<String>) -> AnyResult<String> {
+    let seed = read_inline_or_file("seed", seed, seed_path)?;
+
+    // Validate the seed here, so that a malformed seed is reported when the
+    // connector is created rather than when connecting.
+    let seed = seed.trim().to_string();
+    nkeys::KeyPair::from_seed(&seed).map_err(|e| anyhow!("invalid NKey seed: {e}"))?;
+    Ok(seed)
+}
+
+/// Returns the value of an authentication setting that is specified either
+/// inline, as `{name}`, or as a path to a file, as `{name}_path`.
+/// [`ConnectOptions::validate`] checks that exactly one of them is set.
+fn read_inline_or_file(
+    name: &str,
+    value: &Option<String>,
+    path: &Option<String>,
+) -> AnyResult<String> {
+    match value {
+        Some(value) => Ok(value.clone()),
+        None => {
+            let path = path.as_deref().unwrap_or_default();
+            fs::read_to_string(path)
+                .with_context(|| format!("error reading '{name}_path' file '{path}'"))
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Support for `nats` CLI context files.
+//!
+//! A context is a JSON file created by `nats context save`, stored in
+//! `~/.config/nats/context/<name>.json`.  See
+//! <https://docs.nats.io/using-nats/nats-tools/nats_cli#configuration-contexts>.
+
+use anyhow::{anyhow, Context, Result as AnyResult};
+use feldera_types::transport::nats::{Auth, ConnectOptions};
+use feldera_types::transport::tls::TlsConfig;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Connection settings stored in a `nats` CLI context file.
+///
+/// Context files contain additional settings, e.g., for JetStream domains,
+/// which are ignored.  Settings that are not set are stored as empty strings.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct NatsContext {
+    /// Comma-separated list of server URLs.
+    url: String,
+    token: String,
+    user: String,
+    password: String,
+    /// Path to a credentials file.
+    creds: String,
+    /// Path to an NKey seed file.
+    nkey: String,
+    /// Path to the client certificate.
+    cert: String,
+    /// Path to the client key.
+    key: String,
+    /// Path to the CA certificate.
+    ca: String,
+    inbox_prefix: String,
+}
+
+/// Returns `config` with the settings from its context file, if any, filled
+/// in.
+///
+/// Settings specified in `config` take precedence over the context.
+pub(crate) fn apply_context(config: &ConnectOptions) -> AnyResult<Cow<'_, ConnectOptions>> {
+    let Some(context) = &config.context else {
+        return Ok(Cow::Borrowed(config));
+    };
+
+    let path = context_path(context)?;
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("error reading NATS context file '{}'", path.display()))?;
+    let context: NatsContext = serde_json::from_str(&contents)
+        .with_context(|| format!("error parsing NATS context file '{}'", path.display()))?;
+
+    Ok(Cow::Owned(merge_context(config.clone(), context)))
+}
+
+/// Returns the path of the context file for `context`, which is either a
+/// context name or a path to a context file.
+fn context_path(context: &str) -> AnyResult<PathBuf> {
+    if context.contains('/') || context.ends_with(".json") {
+        return Ok(PathBuf::from(context));
+    }
+
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home::home_dir()
+            .ok_or_else(|| {
+                anyhow!("cannot locate NATS context '{context}': home directory is unknown")
+            })?
+            .join(".config"),
+    };
+    Ok(config_dir
+        .join("nats")
+        .join("context")
+        .join(format!("{context}.json")))
+}
+
+fn merge_context(mut config: ConnectOptions, context: NatsContext) -> ConnectOptions {
+    fn non_empty(s: String) -> Option<String> {
+        (!s.is_empty()).then_some(s)
+    }
+
+    if config.server_urls.is_empty() {
+        config.server_urls = context
+            .url
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(String::from)
+            .collect();
+    }
+
+    if config.auth.is_none() {
+        config.auth = if !context.creds.is_empty() {
+            Some(Auth::Credentials {
+                credentials: None,
+                credentials_path: Some(context.creds),
+            })
+        } else if !context.nkey.is_empty() {
+            Some(Auth::Nkey {
+                seed: None,
+                seed_path: Some(context.nkey),
+            })
+        } else if !context.token.is_empty() {
+            Some(Auth::Token {
+                token: context.token,
+            })
+        } else if !context.user.is_empty() {
+            Some(Auth::UserPassword {
+                username: context.user,
+                password: context.password,
+            })
+        } else {
+            None
+        };
+    }
+
+    if config.tls.is_none()
+        && !(context.ca.is_empty() && context.cert.is_empty() && context.key.is_empty())
+    {
+        config.tls = Some(TlsConfig {
+            ca_cert_path: non_empty(context.ca),
+            client_cert_path: non_empty(context.cert),
+            client_key_path: non_empty(context.key),
+            ..Default::default()
+        });
+    }
+
+    if config.inbox_prefix.is_none() {
+        config.inbox_prefix = non_empty(context.inbox_prefix);
+    }
+
+    config
+}
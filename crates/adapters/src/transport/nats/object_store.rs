@@ -0,0 +1,222 @@
+use super::connect;
+use crate::format::StreamSplitter;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Result as AnyResult};
+use async_nats::jetstream::{self, object_store::ObjectStore};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::nats::NatsObjectStoreInputConfig;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::thread;
+use tokio::io::AsyncReadExt;
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tracing::{debug, info, info_span, Instrument};
+
+/// Size of the buffer used to read object contents.
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+pub(crate) struct NatsObjectStoreInputEndpoint {
+    endpoint_name: String,
+    config: Arc<NatsObjectStoreInputConfig>,
+}
+
+impl NatsObjectStoreInputEndpoint {
+    pub(crate) fn new(config: NatsObjectStoreInputConfig, endpoint_name: &str) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            endpoint_name: endpoint_name.to_string(),
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for NatsObjectStoreInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for NatsObjectStoreInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(NatsObjectStoreInputReader::new(
+            &self.endpoint_name,
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+struct NatsObjectStoreInputReader {
+    sender: Sender<PipelineState>,
+    queue: Arc<InputQueue>,
+}
+
+impl NatsObjectStoreInputReader {
+    fn new(
+        endpoint_name: &str,
+        config: Arc<NatsObjectStoreInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("nats_object_store_input", bucket = config.bucket.clone());
+        let store =
+            TOKIO.block_on(Self::open_store(endpoint_name, &config).instrument(span.clone()))?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("nats-object-store-input-tokio-wrapper".to_string())
+            .spawn({
+                let queue = queue.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        select! {
+                            result = Self::worker_task(store, config, consumer.clone(), parser, queue, receiver).instrument(span) => {
+                                if let Err(e) = result {
+                                    consumer.error(true, e, None);
+                                }
+                            }
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("NATS object store input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn NATS object store input tokio wrapper thread");
+
+        Ok(Self { sender, queue })
+    }
+
+    async fn open_store(
+        endpoint_name: &str,
+        config: &NatsObjectStoreInputConfig,
+    ) -> AnyResult<ObjectStore> {
+        let client = connect(&config.connection_config, endpoint_name).await?;
+        jetstream::new(client)
+            .get_object_store(&config.bucket)
+            .await
+            .map_err(|e| anyhow!("error opening object store bucket '{}': {e}", config.bucket))
+    }
+
+    async fn worker_task(
+        store: ObjectStore,
+        config: Arc<NatsObjectStoreInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue>,
+        mut receiver: Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        wait_running(&mut receiver).await;
+
+        // Listing only returns objects that are not deleted.  Watching with
+        // history returns the latest version of each existing object, followed
+        // by subsequent changes, which may include deletions.
+        let mut objects: BoxStream<'_, AnyResult<_>> = if config.watch {
+            store
+                .watch_with_history()
+                .await
+                .map_err(|e| anyhow!("error watching object store bucket: {e}"))?
+                .map(|info| info.map_err(|e| anyhow!("error watching object store bucket: {e}")))
+                .boxed()
+        } else {
+            store
+                .list()
+                .await
+                .map_err(|e| anyhow!("error listing object store bucket: {e}"))?
+                .map(|info| info.map_err(|e| anyhow!("error listing object store bucket: {e}")))
+                .boxed()
+        };
+
+        let mut splitter = StreamSplitter::new(parser.splitter());
+        let mut buffer = vec![0; READ_BUFFER_SIZE];
+
+        while let Some(info) = objects.next().await {
+            let info = info?;
+            if info.deleted {
+                continue;
+            }
+            if let Some(prefix) = &config.prefix {
+                if !info.name.starts_with(prefix) {
+                    continue;
+                }
+            }
+
+            info!("NATS object store input: reading object '{}'", info.name);
+            let mut object = store
+                .get(&info.name)
+                .await
+                .map_err(|e| anyhow!("error opening object '{}': {e}", info.name))?;
+
+            splitter.reset();
+            loop {
+                wait_running(&mut receiver).await;
+
+                // Use the time when we start reading the chunk as the ingestion timestamp.
+                let timestamp = Utc::now();
+                let n = object
+                    .read(&mut buffer)
+                    .await
+                    .map_err(|e| anyhow!("error reading object '{}': {e}", info.name))?;
+                splitter.append(&buffer[..n]);
+
+                let eof = n == 0;
+                while let Some(chunk) = splitter.next(eof) {
+                    queue.push(parser.parse(chunk), timestamp);
+                }
+                if eof {
+                    break;
+                }
+            }
+        }
+
+        consumer.eoi();
+        Ok(())
+    }
+}
+
+impl InputReader for NatsObjectStoreInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => self.queue.queue(),
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for NatsObjectStoreInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/// Block until the state is `Running`.
+async fn wait_running(receiver: &mut Receiver<PipelineState>) {
+    // An error indicates that the channel was closed.  It's ok to ignore
+    // the error as this situation will be handled by the top-level select,
+    // which will abort the worker thread.
+    let _ = receiver
+        .wait_for(|state| state == &PipelineState::Running)
+        .await;
+}
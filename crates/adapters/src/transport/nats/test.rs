@@ -0,0 +1,477 @@
+use super::context::apply_context;
+use super::{server_addrs, translate_connect_options};
+use feldera_types::transport::nats::{Auth, ConnectOptions};
+use feldera_types::transport::tls::TlsConfig;
+use serde_json::json;
+use std::env;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn nats_url() -> String {
+    env::var("NATS_URL").unwrap_or("nats://localhost:4222".to_string())
+}
+
+#[cfg(feature = "nats-test")]
+mod service {
+    use super::nats_url;
+    use crate::test::{mock_input_pipeline, wait, DEFAULT_TIMEOUT_MS};
+    use async_nats::jetstream::{self, object_store};
+    use feldera_types::deserialize_without_context;
+    use feldera_types::program_schema::Relation;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use serial_test::serial;
+
+    #[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+    struct TestStruct {
+        s: String,
+        b: bool,
+        i: i64,
+    }
+
+    deserialize_without_context!(TestStruct);
+
+    impl TestStruct {
+        fn new(s: &str, b: bool, i: i64) -> Self {
+            Self {
+                s: s.to_string(),
+                b,
+                i,
+            }
+        }
+    }
+
+    /// Creates an empty object store bucket, deleting it first if it already exists.
+    async fn create_bucket(bucket: &str) -> object_store::ObjectStore {
+        let client = async_nats::connect(nats_url())
+            .await
+            .expect("failed to connect to NATS");
+        let jetstream = jetstream::new(client);
+        let _ = jetstream.delete_object_store(bucket).await;
+        jetstream
+            .create_object_store(object_store::Config {
+                bucket: bucket.to_string(),
+                ..Default::default()
+            })
+            .await
+            .expect("failed to create object store bucket")
+    }
+
+    /// Read all objects with a matching prefix from a bucket.
+    #[test]
+    #[serial]
+    fn test_nats_object_store_input() {
+        let bucket = "feldera_test_object_store_input";
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            let store = create_bucket(bucket).await;
+            for (name, contents) in [
+                ("data/1.csv", "foo,true,10\nbar,false,-10\n"),
+                ("data/2.csv", "baz,true,0"),
+                ("other.csv", "ignored,true,1\n"),
+            ] {
+                store
+                    .put(name, &mut contents.as_bytes())
+                    .await
+                    .expect("failed to put object");
+            }
+        });
+
+        let config = serde_json::from_value(json!({
+            "stream": "test_input",
+            "transport": {
+                "name": "nats_object_store_input",
+                "config": {
+                    "connection_config": {
+                        "server_urls": [nats_url()]
+                    },
+                    "bucket": bucket,
+                    "prefix": "data/"
+                }
+            },
+            "format": {
+                "name": "csv",
+                "config": {}
+            }
+        }))
+        .unwrap();
+
+        let (endpoint, consumer, _parser, zset) =
+            mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+
+        endpoint.extend();
+        wait(
+            || {
+                endpoint.queue(false);
+                consumer.state().eoi
+            },
+            DEFAULT_TIMEOUT_MS,
+        )
+        .unwrap();
+        endpoint.queue(false);
+
+        let mut records = zset
+            .state()
+            .flushed
+            .iter()
+            .map(|upd| upd.unwrap_insert().clone())
+            .collect::<Vec<_>>();
+        records.sort_by(|a, b| a.s.cmp(&b.s));
+        assert_eq!(
+            records,
+            vec![
+                TestStruct::new("bar", false, -10),
+                TestStruct::new("baz", true, 0),
+                TestStruct::new("foo", true, 10),
+            ]
+        );
+    }
+}
+
+fn tls_options(tls: TlsConfig) -> ConnectOptions {
+    ConnectOptions {
+        server_urls: vec![nats_url()],
+        tls: Some(tls),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_tls_config_validation() {
+    // Default settings use the built-in root certificates.
+    translate_connect_options(&tls_options(TlsConfig::default()), "test").unwrap();
+
+    translate_connect_options(
+        &tls_options(TlsConfig {
+            insecure_skip_verify: true,
+            ..Default::default()
+        }),
+        "test",
+    )
+    .unwrap();
+
+    let err = translate_connect_options(
+        &tls_options(TlsConfig {
+            ca_cert_pem: Some(String::new()),
+            ca_cert_path: Some("/tmp/ca.pem".to_string()),
+            ..Default::default()
+        }),
+        "test",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("mutually exclusive"), "{err}");
+
+    let err = translate_connect_options(
+        &tls_options(TlsConfig {
+            ca_cert_pem: Some("not a certificate".to_string()),
+            ..Default::default()
+        }),
+        "test",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("no certificates found"), "{err}");
+
+    let err = translate_connect_options(
+        &tls_options(TlsConfig {
+            client_key_pem: Some("key".to_string()),
+            ..Default::default()
+        }),
+        "test",
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("without a client certificate"),
+        "{err}"
+    );
+}
+
+fn nkey_options(seed: Option<String>, seed_path: Option<String>) -> ConnectOptions {
+    ConnectOptions {
+        server_urls: vec![nats_url()],
+        auth: Some(Auth::Nkey { seed, seed_path }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_nkey_config_validation() {
+    let seed = nkeys::KeyPair::new_user().seed().unwrap();
+
+    translate_connect_options(&nkey_options(Some(seed.clone()), None), "test").unwrap();
+
+    let mut seed_file = NamedTempFile::new().unwrap();
+    writeln!(seed_file, "{seed}").unwrap();
+    let seed_path = seed_file.path().display().to_string();
+    translate_connect_options(&nkey_options(None, Some(seed_path.clone())), "test").unwrap();
+
+    let err =
+        translate_connect_options(&nkey_options(Some(seed), Some(seed_path)), "test").unwrap_err();
+    assert!(err.to_string().contains("mutually exclusive"), "{err}");
+
+    let err = translate_connect_options(&nkey_options(None, None), "test").unwrap_err();
+    assert!(err.to_string().contains("requires either"), "{err}");
+
+    let err = translate_connect_options(&nkey_options(Some("SUBAD".to_string()), None), "test")
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid NKey seed"), "{err}");
+}
+
+fn credentials_options(
+    credentials: Option<String>,
+    credentials_path: Option<String>,
+) -> ConnectOptions {
+    ConnectOptions {
+        server_urls: vec![nats_url()],
+        auth: Some(Auth::Credentials {
+            credentials,
+            credentials_path,
+        }),
+        ..Default::default()
+    }
+}
+
+/// Returns the contents of a `.creds` file with a dummy JWT and a new seed.
+fn test_credentials() -> String {
+    let seed = nkeys::KeyPair::new_user().seed().unwrap();
+    format!(
+        "-----BEGIN NATS USER JWT-----
+eyJ0eXAiOiJKV1QiLCJhbGciOiJlZDI1NTE5LW5rZXkifQ.e30.c2lnbmF0dXJl
+------END NATS USER JWT------
+
+-----BEGIN USER NKEY SEED-----
+{seed}
+------END USER NKEY SEED------
+"
+    )
+}
+
+#[test]
+fn test_credentials_config_validation() {
+    let credentials = test_credentials();
+
+    translate_connect_options(
+        &credentials_options(Some(credentials.clone()), None),
+        "test",
+    )
+    .unwrap();
+
+    let mut credentials_file = NamedTempFile::new().unwrap();
+    write!(credentials_file, "{credentials}").unwrap();
+    let credentials_path = credentials_file.path().display().to_string();
+    translate_connect_options(
+        &credentials_options(None, Some(credentials_path.clone())),
+        "test",
+    )
+    .unwrap();
+
+    let err = translate_connect_options(
+        &credentials_options(Some(credentials), Some(credentials_path)),
+        "test",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("mutually exclusive"), "{err}");
+
+    let err = translate_connect_options(
+        &credentials_options(None, Some("/nonexistent/user.creds".to_string())),
+        "test",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("error reading"), "{err}");
+
+    let err = translate_connect_options(
+        &credentials_options(Some("garbage".to_string()), None),
+        "test",
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("invalid NATS credentials"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_server_urls_validation() {
+    let addrs = server_addrs(&ConnectOptions {
+        server_urls: vec![
+            "nats://nats1:4222".to_string(),
+            "tls://nats2:4222".to_string(),
+        ],
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(addrs.len(), 2);
+
+    let err = server_addrs(&ConnectOptions::default()).unwrap_err();
+    assert!(err.to_string().contains("at least one server"), "{err}");
+
+    let err = server_addrs(&ConnectOptions {
+        server_urls: vec!["http://nats1:4222".to_string()],
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(err.to_string().contains("invalid NATS server URL"), "{err}");
+
+    // WebSocket URLs.
+    server_addrs(&ConnectOptions {
+        server_urls: vec!["ws://nats1:8080".to_string()],
+        ..Default::default()
+    })
+    .unwrap();
+    server_addrs(&ConnectOptions {
+        server_urls: vec!["wss://nats1:443".to_string()],
+        tls: Some(TlsConfig::default()),
+        ..Default::default()
+    })
+    .unwrap();
+    let err = server_addrs(&ConnectOptions {
+        server_urls: vec!["ws://nats1:8080".to_string()],
+        tls: Some(TlsConfig::default()),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(err.to_string().contains("wss://"), "{err}");
+}
+
+#[test]
+fn test_tuning_config_validation() {
+    translate_connect_options(
+        &ConnectOptions {
+            server_urls: vec![nats_url()],
+            ping_interval_secs: Some(10),
+            connection_timeout_secs: Some(1),
+            max_reconnects: Some(3),
+            reconnect_delay_ms: Some(500),
+            reconnect_buffer_size: Some(16),
+            ..Default::default()
+        },
+        "test",
+    )
+    .unwrap();
+
+    let err = translate_connect_options(
+        &ConnectOptions {
+            server_urls: vec![nats_url()],
+            reconnect_buffer_size: Some(0),
+            ..Default::default()
+        },
+        "test",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("greater than 0"), "{err}");
+}
+
+#[test]
+fn test_context_file() {
+    let mut context_file = NamedTempFile::with_suffix(".json").unwrap();
+    write!(
+        context_file,
+        "{}",
+        json!({
+            "description": "test context",
+            "url": "nats://nats1:4222, nats://nats2:4222",
+            "user": "feldera",
+            "password": "secret",
+            "creds": "",
+            "ca": "/etc/nats/ca.pem",
+            "inbox_prefix": "_INBOX_feldera",
+            "jetstream_domain": "hub"
+        })
+    )
+    .unwrap();
+    let context = context_file.path().display().to_string();
+
+    // Settings from the context fill in unspecified options.
+    let config = ConnectOptions {
+        context: Some(context.clone()),
+        ..Default::default()
+    };
+    let merged = apply_context(&config).unwrap();
+    assert_eq!(
+        merged.server_urls,
+        vec![
+            "nats://nats1:4222".to_string(),
+            "nats://nats2:4222".to_string()
+        ]
+    );
+    assert_eq!(
+        merged.auth,
+        Some(Auth::UserPassword {
+            username: "feldera".to_string(),
+            password: "secret".to_string(),
+        })
+    );
+    assert_eq!(
+        merged.tls,
+        Some(TlsConfig {
+            ca_cert_path: Some("/etc/nats/ca.pem".to_string()),
+            ..Default::default()
+        })
+    );
+    assert_eq!(merged.inbox_prefix.as_deref(), Some("_INBOX_feldera"));
+
+    // Explicit options take precedence over the context.
+    let config = ConnectOptions {
+        server_urls: vec!["nats://nats3:4222".to_string()],
+        auth: Some(Auth::Token {
+            token: "token".to_string(),
+        }),
+        context: Some(context),
+        ..Default::default()
+    };
+    let merged = apply_context(&config).unwrap();
+    assert_eq!(merged.server_urls, vec!["nats://nats3:4222".to_string()]);
+    assert_eq!(
+        merged.auth,
+        Some(Auth::Token {
+            token: "token".to_string(),
+        })
+    );
+
+    let err = apply_context(&ConnectOptions {
+        context: Some("/nonexistent/context.json".to_string()),
+        ..Default::default()
+    })
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("error reading NATS context"),
+        "{err}"
+    );
+}
+
+fn auth_callout_options(token: Option<&str>, username: Option<&str>) -> ConnectOptions {
+    ConnectOptions {
+        server_urls: vec![nats_url()],
+        auth: Some(Auth::AuthCallout {
+            sentinel_credentials: Some(test_credentials()),
+            sentinel_credentials_path: None,
+            token: token.map(String::from),
+            username: username.map(String::from),
+            password: username.map(|_| "password".to_string()),
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_auth_callout_config_validation() {
+    translate_connect_options(&auth_callout_options(Some("eyJhbGciOi..."), None), "test").unwrap();
+    translate_connect_options(&auth_callout_options(None, Some("feldera")), "test").unwrap();
+
+    let err = translate_connect_options(&auth_callout_options(None, None), "test").unwrap_err();
+    assert!(err.to_string().contains("requires a 'token'"), "{err}");
+
+    let config = ConnectOptions {
+        server_urls: vec![nats_url()],
+        auth: Some(Auth::AuthCallout {
+            sentinel_credentials: None,
+            sentinel_credentials_path: None,
+            token: Some("token".to_string()),
+            username: None,
+            password: None,
+        }),
+        ..Default::default()
+    };
+    let err = translate_connect_options(&config, "test").unwrap_err();
+    assert!(err.to_string().contains("requires either"), "{err}");
+}
@@ -0,0 +1,547 @@
+//! Input reader shared by the connectors that read files from an object
+//! store, such as Google Cloud Storage and Azure Blob Storage.
+//!
+//! The reader reads the selected objects one at a time, in lexicographic
+//! order of their names.  Its resume information is the name of the object
+//! being read and the offset up to which it has been read, which is enough to
+//! continue from the same point after a restart.
+
+use super::InputReaderCommand;
+use crate::transport::InputQueue;
+use crate::{format::StreamSplitter, InputConsumer, InputReader, Parser};
+use anyhow::{anyhow, Result as AnyResult};
+use async_channel::{unbounded, Receiver, Sender};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::{
+    transport::{parse_resume_info, Resume, Watermark},
+    PipelineState,
+};
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{GetOptions, GetRange, ObjectStore};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, thread};
+use tokio::sync::watch::{channel as watch_channel, error::RecvError, Receiver as WatchReceiver};
+use tracing::{info, info_span, Instrument};
+
+/// Returns the path of the object named `name`.
+pub(crate) fn object_path(name: &str) -> AnyResult<Path> {
+    Path::parse(name).map_err(|e| anyhow!("invalid object name '{name}': {e}"))
+}
+
+/// The objects for [ObjectInputReader] to read.
+pub(crate) struct ObjectSelection {
+    /// Name of the connector for log and error messages, e.g., `GCS`.
+    pub connector: &'static str,
+
+    /// Name of the bucket or container the objects are in.
+    pub container: String,
+
+    /// Read a single object with this name.
+    pub key: Option<String>,
+
+    /// Read all objects whose names start with this prefix.  Ignored if `key`
+    /// is set.
+    pub prefix: Option<String>,
+}
+
+/// Position up to which the selected objects have been read.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Metadata {
+    /// The object being read, or the last object that has been read in full.
+    /// `None` if no object has been read yet.
+    key: Option<String>,
+
+    /// Offset in `key` up to which it has been read, or `None` if it has been
+    /// read in full.
+    offset: Option<u64>,
+}
+
+pub(crate) struct ObjectInputReader {
+    sender: Sender<InputReaderCommand>,
+}
+
+impl InputReader for ObjectInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        let _ = self.sender.send_blocking(command);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+impl Drop for ObjectInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+impl ObjectInputReader {
+    /// Starts reading the objects in `selection` from `store`, from the
+    /// position in `seek`, if any.
+    pub(crate) fn new(
+        selection: ObjectSelection,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        store: Arc<dyn ObjectStore>,
+        seek: Option<serde_json::Value>,
+    ) -> AnyResult<Self> {
+        let resume_info = match seek {
+            Some(resume_info) => parse_resume_info::<Metadata>(&resume_info)?,
+            None => Metadata::default(),
+        };
+        Ok(Self::new_inner(
+            selection,
+            consumer,
+            parser,
+            store,
+            resume_info,
+        ))
+    }
+
+    fn new_inner(
+        selection: ObjectSelection,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        store: Arc<dyn ObjectStore>,
+        resume_info: Metadata,
+    ) -> Self {
+        let (sender, receiver) = unbounded();
+        thread::Builder::new()
+            .name("object-input-tokio-wrapper".to_string())
+            .spawn(move || {
+                let span = info_span!(
+                    "object_input",
+                    connector = selection.connector,
+                    container = selection.container.clone()
+                );
+                TOKIO.block_on(
+                    Self::worker_task(store, selection, consumer, parser, receiver, resume_info)
+                        .instrument(span),
+                )
+            })
+            .expect("failed to create object input connector tokio wrapper thread");
+        Self { sender }
+    }
+
+    async fn worker_task(
+        store: Arc<dyn ObjectStore>,
+        selection: ObjectSelection,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        command_receiver: Receiver<InputReaderCommand>,
+        mut position: Metadata,
+    ) {
+        let queue = Arc::new(InputQueue::<Metadata>::new(consumer.clone()));
+        let (status_sender, status_receiver) = watch_channel(PipelineState::Paused);
+
+        let connector = selection.connector;
+
+        // This task reads the objects and queues the parsed buffers, each
+        // with the position just after it.
+        tokio::spawn({
+            let queue = queue.clone();
+            let consumer = consumer.clone();
+            let position = position.clone();
+            let status_receiver = status_receiver.clone();
+            async move {
+                let mut reader = ObjectReader {
+                    store,
+                    parser,
+                    queue,
+                    status_receiver: status_receiver.clone(),
+                };
+                match reader.read_objects(&selection, position).await {
+                    Ok(()) => {
+                        if *status_receiver.borrow() != PipelineState::Terminated {
+                            consumer.eoi();
+                        }
+                    }
+                    // The channel was closed: the connector is shutting down.
+                    Err(ReadError::Closed) => (),
+                    Err(ReadError::Fatal(e)) => consumer.error(true, e, Some("object-list")),
+                }
+            }
+        });
+
+        loop {
+            match command_receiver.recv().await {
+                Ok(InputReaderCommand::Replay { .. }) => {
+                    panic!("replay command is not supported by the {connector} input connector; this is a bug, please report it to developers")
+                }
+                Ok(InputReaderCommand::Extend) => {
+                    let _ = status_sender.send_replace(PipelineState::Running);
+                }
+                Ok(InputReaderCommand::Pause) => {
+                    let _ = status_sender.send_replace(PipelineState::Paused);
+                }
+                Ok(InputReaderCommand::Queue { .. }) => {
+                    let (total, hasher, consumed) = queue.flush_with_aux();
+                    let mut watermarks = Vec::with_capacity(consumed.len());
+                    for (timestamp, aux) in consumed {
+                        position = aux;
+                        watermarks.push(Watermark::new(timestamp, None));
+                    }
+                    consumer.extended(
+                        total,
+                        Some(Resume::new_metadata_only(
+                            serde_json::to_value(&position).unwrap(),
+                            hasher.map(|h| h.finish()),
+                        )),
+                        watermarks,
+                    );
+                }
+                Ok(InputReaderCommand::Disconnect) | Err(_) => {
+                    let _ = status_sender.send_replace(PipelineState::Terminated);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Error that stops [ObjectReader].
+enum ReadError {
+    /// The connector is shutting down.
+    Closed,
+
+    /// An error that prevents reading further objects.
+    Fatal(anyhow::Error),
+}
+
+impl From<RecvError> for ReadError {
+    fn from(_: RecvError) -> Self {
+        Self::Closed
+    }
+}
+
+/// Reads and parses objects.
+struct ObjectReader {
+    store: Arc<dyn ObjectStore>,
+    parser: Box<dyn Parser>,
+    queue: Arc<InputQueue<Metadata>>,
+    status_receiver: WatchReceiver<PipelineState>,
+}
+
+impl ObjectReader {
+    /// Reads the objects in `selection`, starting from `start`.
+    async fn read_objects(
+        &mut self,
+        selection: &ObjectSelection,
+        start: Metadata,
+    ) -> Result<(), ReadError> {
+        let mut splitter = StreamSplitter::new(self.parser.splitter());
+
+        // Finish reading the object that was being read.
+        if let Metadata {
+            key: Some(key),
+            offset: Some(offset),
+        } = &start
+        {
+            self.read_object(&mut splitter, key, *offset).await?;
+        }
+
+        if let Some(key) = &selection.key {
+            if start.key.is_none() {
+                self.read_object(&mut splitter, key, 0).await?;
+            }
+            return Ok(());
+        }
+
+        // Object stores list objects by directory, so list the directory that
+        // contains the prefix and filter out the objects that don't match it.
+        let prefix = selection.prefix.as_deref().unwrap_or_default();
+        let directory = match prefix.rfind('/') {
+            Some(index) => Some(object_path(&prefix[..index]).map_err(ReadError::Fatal)?),
+            None => None,
+        };
+        let store = self.store.clone();
+        let mut objects = match &start.key {
+            Some(key) => store.list_with_offset(
+                directory.as_ref(),
+                &object_path(key).map_err(ReadError::Fatal)?,
+            ),
+            None => store.list(directory.as_ref()),
+        };
+        while let Some(object) = objects.next().await {
+            let object = object.map_err(|e| {
+                ReadError::Fatal(anyhow!("error listing objects with prefix '{prefix}': {e}"))
+            })?;
+            let key = object.location.as_ref();
+            if key.starts_with(prefix) {
+                self.read_object(&mut splitter, key, 0).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads object `key` starting from `offset`.
+    ///
+    /// Errors fetching or reading the object are reported as non-fatal and
+    /// skip the rest of the object.
+    async fn read_object(
+        &mut self,
+        splitter: &mut StreamSplitter,
+        key: &str,
+        offset: u64,
+    ) -> Result<(), ReadError> {
+        wait_running(&mut self.status_receiver).await?;
+        info!("reading object '{key}' from offset {offset}");
+
+        let path = object_path(key).map_err(ReadError::Fatal)?;
+        let options = GetOptions {
+            range: (offset > 0).then_some(GetRange::Offset(offset as usize)),
+            ..Default::default()
+        };
+        match self.store.get_opts(&path, options).await {
+            Ok(object) => {
+                let mut stream = object.into_stream();
+                splitter.seek(offset);
+                let mut eoi = false;
+                while !eoi {
+                    wait_running(&mut self.status_receiver).await?;
+
+                    // Use the time when we start reading the next chunk of the
+                    // object as the timestamp for all buffers derived from it.
+                    let timestamp = Utc::now();
+
+                    match stream.next().await {
+                        Some(Err(e)) => {
+                            self.queue.consumer.error(
+                                false,
+                                anyhow!("error reading object '{key}': {e}"),
+                                Some("object-read"),
+                            );
+                            break;
+                        }
+                        Some(Ok(bytes)) => splitter.append(&bytes),
+                        None => eoi = true,
+                    }
+
+                    while let Some(chunk) = splitter.next(eoi) {
+                        let (buffer, errors) = self.parser.parse(chunk);
+                        let errors = errors
+                            .into_iter()
+                            .map(|e| {
+                                e.map_description(|desc| {
+                                    format!("error parsing object '{key}': {desc}")
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        self.queue.push_with_aux(
+                            (buffer, errors),
+                            timestamp,
+                            Metadata {
+                                key: Some(key.to_string()),
+                                offset: Some(splitter.position()),
+                            },
+                        );
+                    }
+                }
+            }
+            Err(e) => self.queue.consumer.error(
+                false,
+                anyhow!("could not fetch object '{key}': {e}"),
+                Some("object-fetch"),
+            ),
+        }
+
+        // Mark the object as read in full.
+        self.queue.push_with_aux(
+            (None, Vec::new()),
+            Utc::now(),
+            Metadata {
+                key: Some(key.to_string()),
+                offset: None,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Block until the state is `Running`.
+async fn wait_running(receiver: &mut WatchReceiver<PipelineState>) -> Result<(), RecvError> {
+    // An error indicates that the channel was closed.
+    receiver
+        .wait_for(|state| state == &PipelineState::Running)
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Metadata, ObjectInputReader, ObjectSelection};
+    use crate::test::{mock_parser_pipeline, wait, MockDeZSet, MockInputConsumer, MockInputParser};
+    use crate::InputReader;
+    use dbsp::circuit::tokio::TOKIO;
+    use feldera_types::{
+        config::FormatConfig, deserialize_without_context, program_schema::Relation,
+    };
+    use object_store::{memory::InMemory, path::Path, ObjectStore, PutPayload};
+    use serde::{Deserialize, Serialize};
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, PartialOrd, Ord)]
+    struct TestStruct {
+        i: i64,
+    }
+    deserialize_without_context!(TestStruct);
+
+    fn key(key: &str) -> ObjectSelection {
+        ObjectSelection {
+            connector: "test",
+            container: "test-bucket".to_string(),
+            key: Some(key.to_string()),
+            prefix: None,
+        }
+    }
+
+    fn prefix(prefix: &str) -> ObjectSelection {
+        ObjectSelection {
+            connector: "test",
+            container: "test-bucket".to_string(),
+            key: None,
+            prefix: Some(prefix.to_string()),
+        }
+    }
+
+    /// Returns an in-memory store with the given objects.
+    fn store(objects: &[(&str, &str)]) -> Arc<InMemory> {
+        let store = Arc::new(InMemory::new());
+        for (name, contents) in objects {
+            TOKIO
+                .block_on(store.put(
+                    &Path::from(*name),
+                    PutPayload::from(contents.as_bytes().to_vec()),
+                ))
+                .unwrap();
+        }
+        store
+    }
+
+    fn test_setup(
+        selection: ObjectSelection,
+        store: Arc<InMemory>,
+        resume_info: Metadata,
+    ) -> (
+        Box<dyn InputReader>,
+        MockInputConsumer,
+        MockInputParser,
+        MockDeZSet<TestStruct, TestStruct>,
+    ) {
+        let format: FormatConfig = serde_json::from_str(r#"{"name": "csv"}"#).unwrap();
+        let (consumer, parser, input_handle) =
+            mock_parser_pipeline::<TestStruct, TestStruct>(&Relation::empty(), &format).unwrap();
+        consumer.on_error(Some(Box::new(|_, _| ())));
+        let reader = Box::new(ObjectInputReader::new_inner(
+            selection,
+            Box::new(consumer.clone()),
+            Box::new(parser.clone()),
+            store,
+            resume_info,
+        )) as Box<dyn InputReader>;
+        (reader, consumer, parser, input_handle)
+    }
+
+    /// Reads from `store` and checks that the records in `expected` are
+    /// ingested, in order.
+    fn run_test(
+        selection: ObjectSelection,
+        store: Arc<InMemory>,
+        resume_info: Metadata,
+        expected: &[i64],
+    ) {
+        let (reader, consumer, parser, input_handle) = test_setup(selection, store, resume_info);
+        // No outputs should be produced at this point.
+        assert!(parser.state().data.is_empty());
+        assert!(!consumer.state().eoi);
+
+        // Unpause the endpoint, wait for the data to appear at the output.
+        reader.extend();
+        wait(
+            || {
+                reader.queue(false);
+                consumer.state().eoi && input_handle.state().flushed.len() == expected.len()
+            },
+            10000,
+        )
+        .unwrap();
+
+        let outputs = input_handle
+            .state()
+            .flushed
+            .iter()
+            .map(|upd| upd.unwrap_insert().i)
+            .collect::<Vec<_>>();
+        assert_eq!(outputs, expected);
+    }
+
+    #[test]
+    fn single_key_read() {
+        let store = store(&[("obj1", "1\n2\n3\n"), ("obj2", "4\n")]);
+        run_test(key("obj1"), store, Metadata::default(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn prefix_read() {
+        let store = store(&[
+            ("data/b.csv", "3\n4\n"),
+            ("data/a.csv", "1\n2\n"),
+            ("database.csv", "5\n"),
+            ("other/c.csv", "6\n"),
+        ]);
+        run_test(
+            prefix("data/"),
+            store.clone(),
+            Metadata::default(),
+            &[1, 2, 3, 4],
+        );
+        run_test(
+            prefix("data"),
+            store.clone(),
+            Metadata::default(),
+            &[1, 2, 3, 4, 5],
+        );
+        run_test(prefix(""), store, Metadata::default(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn resume() {
+        let store = store(&[("data/a.csv", "1\n2\n"), ("data/b.csv", "3\n4\n")]);
+
+        // Resume in the middle of an object.
+        run_test(
+            prefix("data/"),
+            store.clone(),
+            Metadata {
+                key: Some("data/a.csv".to_string()),
+                offset: Some(2),
+            },
+            &[2, 3, 4],
+        );
+
+        // Resume after an object.
+        run_test(
+            prefix("data/"),
+            store.clone(),
+            Metadata {
+                key: Some("data/a.csv".to_string()),
+                offset: None,
+            },
+            &[3, 4],
+        );
+
+        // Resume after the only object.
+        run_test(
+            key("data/a.csv"),
+            store,
+            Metadata {
+                key: Some("data/a.csv".to_string()),
+                offset: None,
+            },
+            &[],
+        );
+    }
+}
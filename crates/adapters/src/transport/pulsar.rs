@@ -0,0 +1,70 @@
+//! Transport adapters for Apache Pulsar.
+
+use anyhow::{Context, Result as AnyResult};
+use pulsar::proto::MessageIdData;
+use pulsar::{Authentication, Pulsar, TokioExecutor};
+use serde::{Deserialize, Serialize};
+
+mod input;
+mod output;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::PulsarInputEndpoint;
+pub(crate) use output::PulsarOutputEndpoint;
+
+/// Connects to the Pulsar service at `service_url`, authenticating with
+/// `auth_token`, if any.
+async fn connect(service_url: &str, auth_token: Option<&str>) -> AnyResult<Pulsar<TokioExecutor>> {
+    let mut builder = Pulsar::builder(service_url, TokioExecutor);
+    if let Some(token) = auth_token {
+        builder = builder.with_auth(Authentication {
+            name: "token".to_string(),
+            data: token.as_bytes().to_vec(),
+        });
+    }
+    builder
+        .build()
+        .await
+        .with_context(|| format!("error connecting to the Pulsar service at '{service_url}'"))
+}
+
+/// Position of a message within a topic, or within a partition of a
+/// partitioned topic.
+///
+/// Messages in a topic are ordered by their IDs, so the derived ordering
+/// orders messages within a topic.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct PulsarMessageId {
+    ledger_id: u64,
+    entry_id: u64,
+
+    /// Index of the message in a batch of messages that the producer
+    /// published together, if any.
+    batch_index: Option<i32>,
+    partition: Option<i32>,
+}
+
+impl From<&MessageIdData> for PulsarMessageId {
+    fn from(id: &MessageIdData) -> Self {
+        Self {
+            ledger_id: id.ledger_id,
+            entry_id: id.entry_id,
+            batch_index: id.batch_index,
+            partition: id.partition,
+        }
+    }
+}
+
+impl From<&PulsarMessageId> for MessageIdData {
+    fn from(id: &PulsarMessageId) -> Self {
+        Self {
+            ledger_id: id.ledger_id,
+            entry_id: id.entry_id,
+            batch_index: id.batch_index,
+            partition: id.partition,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use super::{connect, PulsarMessageId};
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{parse_resume_info, Resume};
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::pulsar::{
+    PulsarInputConfig, PulsarStartPosition, PulsarSubscriptionType,
+};
+use futures::StreamExt;
+use pulsar::consumer::InitialPosition;
+use pulsar::{Consumer, ConsumerOptions, Pulsar, SubType, TokioExecutor};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tokio::select;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tracing::{debug, info_span, Instrument};
+
+/// Default for [`PulsarInputConfig::receiver_queue_size`].
+const DEFAULT_RECEIVER_QUEUE_SIZE: u32 = 1000;
+
+pub(crate) struct PulsarInputEndpoint {
+    config: Arc<PulsarInputConfig>,
+}
+
+impl PulsarInputEndpoint {
+    pub(crate) fn new(config: PulsarInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for PulsarInputEndpoint {
+    /// With a subscription that delivers each topic's messages in order to a
+    /// single consumer, the connector can resume by seeking each topic just
+    /// past the last message that it passed to the pipeline.
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        self.config
+            .subscription_type
+            .is_ordered()
+            .then_some(FtModel::AtLeastOnce)
+    }
+}
+
+impl TransportInputEndpoint for PulsarInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        let resume_info = resume_info
+            .map(|resume_info| parse_resume_info::<Metadata>(&resume_info))
+            .transpose()?;
+        Ok(Box::new(PulsarInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+            resume_info,
+        )?))
+    }
+}
+
+/// Checkpointed position of the connector: for each topic, or partition of a
+/// partitioned topic, the ID of the last message passed to the pipeline.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct Metadata {
+    positions: BTreeMap<String, PulsarMessageId>,
+}
+
+/// A message to acknowledge.
+struct MessagePosition {
+    topic: String,
+    id: PulsarMessageId,
+}
+
+async fn subscribe(
+    pulsar: &Pulsar<TokioExecutor>,
+    config: &PulsarInputConfig,
+) -> AnyResult<Consumer<Vec<u8>, TokioExecutor>> {
+    let subscription_type = match config.subscription_type {
+        PulsarSubscriptionType::Exclusive => SubType::Exclusive,
+        PulsarSubscriptionType::Failover => SubType::Failover,
+        PulsarSubscriptionType::Shared => SubType::Shared,
+        PulsarSubscriptionType::KeyShared => SubType::KeyShared,
+    };
+    let initial_position = match config.start_position {
+        PulsarStartPosition::Earliest => InitialPosition::Earliest,
+        PulsarStartPosition::Latest => InitialPosition::Latest,
+    };
+    let mut builder = pulsar
+        .consumer()
+        .with_topics(&config.topics)
+        .with_subscription(&config.subscription)
+        .with_subscription_type(subscription_type)
+        .with_batch_size(
+            config
+                .receiver_queue_size
+                .unwrap_or(DEFAULT_RECEIVER_QUEUE_SIZE),
+        )
+        .with_options(ConsumerOptions::default().with_initial_position(initial_position));
+    if let Some(consumer_name) = &config.consumer_name {
+        builder = builder.with_consumer_name(consumer_name);
+    }
+    builder
+        .build()
+        .await
+        .with_context(|| format!("error subscribing to Pulsar topics {:?}", config.topics))
+}
+
+struct PulsarInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Parsed messages, each with the position of the message, which is
+    /// acknowledged once the message has been flushed to the circuit.
+    queue: Arc<InputQueue<MessagePosition>>,
+
+    /// Sends positions of flushed messages to the worker task, which
+    /// acknowledges them.
+    acks: UnboundedSender<Vec<MessagePosition>>,
+
+    /// Position of the last flushed message in each topic, if the connector
+    /// is fault tolerant.
+    positions: Option<Mutex<Metadata>>,
+}
+
+impl PulsarInputReader {
+    fn new(
+        config: Arc<PulsarInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        resume_info: Option<Metadata>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("pulsar_input", subscription = config.subscription.clone());
+        let (pulsar, pulsar_consumer) = TOKIO.block_on(
+            async {
+                let pulsar = connect(&config.service_url, config.auth_token.as_deref()).await?;
+                let mut pulsar_consumer = subscribe(&pulsar, &config).await?;
+
+                // Rewind the subscription to the checkpointed position of
+                // each topic.  Messages acknowledged after the checkpoint
+                // are delivered again.
+                if let Some(resume_info) = &resume_info {
+                    for (topic, id) in &resume_info.positions {
+                        pulsar_consumer
+                            .seek(
+                                Some(vec![topic.clone()]),
+                                Some(id.into()),
+                                None,
+                                pulsar.clone(),
+                            )
+                            .await
+                            .with_context(|| {
+                                format!("error seeking Pulsar topic '{topic}' to {id:?}")
+                            })?;
+                    }
+                }
+                AnyResult::Ok((pulsar, pulsar_consumer))
+            }
+            .instrument(span.clone()),
+        )?;
+
+        let fault_tolerant = config.subscription_type.is_ordered();
+        let resume_info = resume_info.unwrap_or_default();
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let (acks, acks_receiver) = unbounded_channel();
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("pulsar-input-tokio-wrapper".to_string())
+            .spawn({
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                let skip = resume_info.positions.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        select! {
+                            _ = Self::worker_task(pulsar, pulsar_consumer, consumer.clone(), parser, queue, skip, acks_receiver, receiver).instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("Pulsar input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn Pulsar input tokio wrapper thread");
+
+        Ok(Self {
+            sender,
+            consumer,
+            queue,
+            acks,
+            positions: fault_tolerant.then(|| Mutex::new(resume_info)),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn worker_task(
+        // Keeps the connection to the service open.
+        _pulsar: Pulsar<TokioExecutor>,
+        mut pulsar_consumer: Consumer<Vec<u8>, TokioExecutor>,
+        consumer: Box<dyn InputConsumer>,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue<MessagePosition>>,
+        mut skip: BTreeMap<String, PulsarMessageId>,
+        mut acks: UnboundedReceiver<Vec<MessagePosition>>,
+        mut receiver: Receiver<PipelineState>,
+    ) {
+        loop {
+            // Messages are only received while the pipeline is running.
+            // Once `receiver_queue_size` messages are buffered, the broker
+            // stops sending messages, so they wait at the broker while the
+            // pipeline is paused.
+            let running = *receiver.borrow() == PipelineState::Running;
+            select! {
+                message = pulsar_consumer.next(), if running => match message {
+                    Some(Ok(message)) => {
+                        // Use the time when we received the message as the ingestion timestamp.
+                        let timestamp = Utc::now();
+                        let id = PulsarMessageId::from(message.message_id());
+
+                        // After seeking, the broker may deliver messages up to
+                        // and including the checkpointed position again.
+                        if let Some(last) = skip.get(&message.topic) {
+                            if &id <= last {
+                                continue;
+                            }
+                            skip.remove(&message.topic);
+                        }
+
+                        queue.push_with_aux(
+                            parser.parse(&message.payload.data),
+                            timestamp,
+                            MessagePosition {
+                                topic: message.topic.clone(),
+                                id,
+                            },
+                        );
+                    }
+                    Some(Err(e)) => consumer.error(
+                        false,
+                        anyhow!("error receiving Pulsar message: {e}"),
+                        Some("pulsar-receive"),
+                    ),
+                    None => {
+                        consumer.error(true, anyhow!("Pulsar consumer closed unexpectedly"), None);
+                        return;
+                    }
+                },
+                Some(positions) = acks.recv() => {
+                    for position in positions {
+                        if let Err(e) = pulsar_consumer
+                            .ack_with_id(&position.topic, (&position.id).into())
+                            .await
+                        {
+                            consumer.error(
+                                false,
+                                anyhow!("error acknowledging Pulsar message: {e}"),
+                                Some("pulsar-ack"),
+                            );
+                        }
+                    }
+                }
+                _ = receiver.changed() => {}
+            }
+        }
+    }
+}
+
+impl InputReader for PulsarInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command {
+            InputReaderCommand::Queue { .. } => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let mut positions = Vec::with_capacity(consumed.len());
+                let watermarks = consumed
+                    .into_iter()
+                    .map(|(timestamp, position)| {
+                        positions.push(position);
+                        Watermark::new(timestamp, None)
+                    })
+                    .collect();
+
+                let resume = self.positions.as_ref().map(|metadata| {
+                    let mut metadata = metadata.lock().unwrap();
+                    for position in &positions {
+                        metadata
+                            .positions
+                            .insert(position.topic.clone(), position.id.clone());
+                    }
+                    Resume::Seek {
+                        seek: serde_json::to_value(&*metadata).unwrap(),
+                    }
+                });
+                if !positions.is_empty() {
+                    let _ = self.acks.send(positions);
+                }
+                self.consumer.extended(total, resume, watermarks);
+            }
+            InputReaderCommand::Replay { .. } => self.consumer.error(
+                true,
+                anyhow!("the Pulsar input connector does not support replay"),
+                None,
+            ),
+            command => match command.as_nonft().unwrap() {
+                NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+                NonFtInputReaderCommand::Queue => unreachable!(),
+            },
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for PulsarInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
@@ -0,0 +1,314 @@
+use super::connect;
+use crate::transport::Step;
+use crate::{AsyncErrorCallback, OutputEndpoint};
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::transport::pulsar::PulsarOutputConfig;
+use futures::future::try_join_all;
+use futures::StreamExt;
+use pulsar::consumer::InitialPosition;
+use pulsar::producer::SendFuture;
+use pulsar::{Consumer, ConsumerOptions, Producer, Pulsar, SubType, TokioExecutor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing::span::EnteredSpan;
+use tracing::{info, info_span};
+use uuid::Uuid;
+
+/// Default for [`PulsarOutputConfig::max_message_size`].
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 5 * 1024 * 1024;
+
+/// Name of the message property that holds the [`OutputPosition`] of each
+/// message published by a fault-tolerant connector.
+const POSITION_PROPERTY: &str = "feldera_position";
+
+/// How long to wait for the last message in the topic after seeking to it.
+const READ_LAST_MESSAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn span(config: &PulsarOutputConfig, fault_tolerant: bool) -> EnteredSpan {
+    info_span!(
+        "pulsar_output",
+        ft = fault_tolerant,
+        topic = config.topic.clone()
+    )
+    .entered()
+}
+
+/// Position of a message in the output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct OutputPosition {
+    /// The step number.
+    step: Step,
+
+    /// An index within the step.  The first message output in a step has
+    /// substep 0, the second has substep 1, and so on.
+    substep: u64,
+}
+
+/// Publishes output to a Pulsar topic.
+///
+/// The Rust Pulsar client does not support transactions, so a fault-tolerant
+/// connector cannot hide a step's messages from readers until the step is
+/// complete.  Instead, it records the position of each message in a message
+/// property.  On startup, it reads the position of the last message in the
+/// topic and discards output up to that position, which the pipeline
+/// produces again as it replays steps.  Thus, each message is published
+/// exactly once, even if a step was only partially published when the
+/// pipeline stopped.
+pub(crate) struct PulsarOutputEndpoint {
+    config: PulsarOutputConfig,
+    fault_tolerant: bool,
+
+    /// Keeps the connection to the service open.
+    _pulsar: Option<Pulsar<TokioExecutor>>,
+    producer: Option<Producer<TokioExecutor>>,
+
+    /// Receipts for the messages published in the current step, which
+    /// `batch_end` waits for.
+    receipts: Vec<SendFuture>,
+
+    /// The position of the last message published by an earlier run, if
+    /// fault tolerant.  Output up to this position is discarded.
+    last_position: Option<OutputPosition>,
+
+    /// The position of the next message, if fault tolerant.
+    next_position: OutputPosition,
+}
+
+impl PulsarOutputEndpoint {
+    pub(crate) fn new(config: PulsarOutputConfig, fault_tolerant: bool) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config,
+            fault_tolerant,
+            _pulsar: None,
+            producer: None,
+            receipts: Vec::new(),
+            last_position: None,
+            next_position: OutputPosition {
+                step: 0,
+                substep: 0,
+            },
+        })
+    }
+
+    /// Returns the position of the last message in `topic`, or `None` if
+    /// the topic is empty.
+    async fn read_last_position(
+        pulsar: &Pulsar<TokioExecutor>,
+        topic: &str,
+    ) -> AnyResult<Option<OutputPosition>> {
+        // Read the topic through a temporary non-durable subscription, which
+        // the broker deletes when the consumer disconnects.
+        let mut consumer: Consumer<Vec<u8>, TokioExecutor> = pulsar
+            .consumer()
+            .with_topic(topic)
+            .with_subscription(format!("feldera-{}", Uuid::new_v4()))
+            .with_subscription_type(SubType::Exclusive)
+            .with_options(
+                ConsumerOptions::default()
+                    .durable(false)
+                    .with_initial_position(InitialPosition::Latest),
+            )
+            .build()
+            .await
+            .with_context(|| format!("error subscribing to Pulsar topic '{topic}'"))?;
+
+        let Some(last) = consumer
+            .get_last_message_id()
+            .await
+            .with_context(|| {
+                format!("error reading the last message ID of Pulsar topic '{topic}'")
+            })?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+        let last_message_id = last.last_message_id;
+
+        // The broker reports an entry ID of -1 for a topic without messages.
+        if last_message_id.entry_id == u64::MAX {
+            return Ok(None);
+        }
+
+        consumer
+            .seek(None, Some(last_message_id.clone()), None, pulsar.clone())
+            .await
+            .with_context(|| {
+                format!("error seeking to the last message of Pulsar topic '{topic}'")
+            })?;
+        let message = match timeout(READ_LAST_MESSAGE_TIMEOUT, consumer.next()).await {
+            Ok(Some(message)) => message.with_context(|| {
+                format!("error reading the last message of Pulsar topic '{topic}'")
+            })?,
+            Ok(None) => bail!("Pulsar consumer for topic '{topic}' closed unexpectedly"),
+            // The topic has a last message ID but we can't read the message,
+            // e.g., because it expired.  Without its position, we can't tell
+            // which output was already published, so fail rather than publish
+            // duplicates.
+            Err(_) => bail!(
+                "Pulsar topic '{topic}' reports last message {last_message_id:?}, but the message could not be read within {READ_LAST_MESSAGE_TIMEOUT:?}; cannot determine which output was already published"
+            ),
+        };
+
+        let position = message
+            .metadata()
+            .properties
+            .iter()
+            .find(|property| property.key == POSITION_PROPERTY)
+            .and_then(|property| serde_json::from_str(&property.value).ok())
+            .with_context(|| {
+                format!(
+                    "message {:?} in Pulsar topic '{topic}' should have a '{POSITION_PROPERTY}' property with its step and substep",
+                    message.message_id()
+                )
+            })?;
+        Ok(Some(position))
+    }
+
+    fn publish(
+        &mut self,
+        key: Option<String>,
+        payload: Vec<u8>,
+        mut properties: HashMap<String, String>,
+    ) -> AnyResult<()> {
+        let _guard = span(&self.config, self.fault_tolerant);
+        if self.fault_tolerant {
+            let position = self.next_position;
+            self.next_position.substep += 1;
+            if self.last_position.is_some_and(|last| position <= last) {
+                return Ok(());
+            }
+            properties.insert(
+                POSITION_PROPERTY.to_string(),
+                serde_json::to_string(&position).unwrap(),
+            );
+        }
+
+        let producer = self.producer.as_mut().ok_or_else(|| {
+            anyhow!("Pulsar: trying to publish before connecting to the broker: unreachable")
+        })?;
+        let mut message = producer
+            .create_message()
+            .with_content(payload)
+            .with_properties(properties);
+        if let Some(key) = key {
+            message = message.with_key(key);
+        }
+        let receipt = TOKIO
+            .block_on(message.send_non_blocking())
+            .with_context(|| format!("error publishing to Pulsar topic '{}'", self.config.topic))?;
+        self.receipts.push(receipt);
+        Ok(())
+    }
+}
+
+impl OutputEndpoint for PulsarOutputEndpoint {
+    fn connect(&mut self, _async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        let _guard = span(&self.config, self.fault_tolerant);
+        let config = &self.config;
+        let (pulsar, producer, last_position) = TOKIO.block_on(async {
+            let pulsar = connect(&config.service_url, config.auth_token.as_deref()).await?;
+
+            // Messages published to different partitions of a topic are
+            // not ordered with respect to each other, so the last message
+            // in the topic doesn't tell us what was published before it.
+            let last_position = if self.fault_tolerant {
+                let partitions = pulsar
+                    .lookup_partitioned_topic_number(&config.topic)
+                    .await
+                    .with_context(|| {
+                        format!("error looking up Pulsar topic '{}'", config.topic)
+                    })?;
+                if partitions > 0 {
+                    bail!(
+                        "fault-tolerant Pulsar output requires a non-partitioned topic, but '{}' has {partitions} partitions",
+                        config.topic
+                    );
+                }
+                Self::read_last_position(&pulsar, &config.topic).await?
+            } else {
+                None
+            };
+
+            let mut builder = pulsar.producer().with_topic(&config.topic);
+            if let Some(producer_name) = &config.producer_name {
+                builder = builder.with_name(producer_name);
+            }
+            let producer = builder.build().await.with_context(|| {
+                format!("error creating a producer for Pulsar topic '{}'", config.topic)
+            })?;
+            AnyResult::Ok((pulsar, producer, last_position))
+        })?;
+
+        if let Some(last_position) = last_position {
+            info!(
+                "discarding output up to step {} substep {} that was already published in a previous run",
+                last_position.step, last_position.substep
+            );
+        }
+        self._pulsar = Some(pulsar);
+        self.producer = Some(producer);
+        self.last_position = last_position;
+        Ok(())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        self.config
+            .max_message_size
+            .unwrap_or(DEFAULT_MAX_MESSAGE_SIZE)
+    }
+
+    fn batch_start(&mut self, step: Step) -> AnyResult<()> {
+        self.next_position = OutputPosition { step, substep: 0 };
+        Ok(())
+    }
+
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        self.publish(None, buffer.to_vec(), HashMap::new())
+    }
+
+    /// The key becomes the message key, which determines the partition of a
+    /// partitioned topic, and the headers become message properties.  A
+    /// deletion, which has no value, is published as an empty message.
+    fn push_key(
+        &mut self,
+        key: Option<&[u8]>,
+        val: Option<&[u8]>,
+        headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        let properties = headers
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.unwrap_or_default()).into_owned(),
+                )
+            })
+            .collect();
+        self.publish(
+            key.map(|key| String::from_utf8_lossy(key).into_owned()),
+            val.unwrap_or_default().to_vec(),
+            properties,
+        )
+    }
+
+    /// Waits until the broker has persisted all of the messages published in
+    /// the step.
+    fn batch_end(&mut self) -> AnyResult<()> {
+        let _guard = span(&self.config, self.fault_tolerant);
+        let receipts = std::mem::take(&mut self.receipts);
+        TOKIO
+            .block_on(try_join_all(receipts))
+            .with_context(|| format!("error publishing to Pulsar topic '{}'", self.config.topic))?;
+        Ok(())
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        self.fault_tolerant
+    }
+}
@@ -0,0 +1,247 @@
+use feldera_types::transport::pulsar::{PulsarInputConfig, PulsarOutputConfig};
+use serde_json::json;
+
+#[cfg(feature = "pulsar-test")]
+mod service {
+    use crate::test::{mock_input_pipeline, wait, DEFAULT_TIMEOUT_MS};
+    use crate::transport::pulsar::{connect, PulsarOutputEndpoint};
+    use crate::OutputEndpoint;
+    use dbsp::circuit::tokio::TOKIO;
+    use feldera_types::deserialize_without_context;
+    use feldera_types::program_schema::Relation;
+    use feldera_types::transport::pulsar::PulsarOutputConfig;
+    use futures::StreamExt;
+    use pulsar::consumer::InitialPosition;
+    use pulsar::{Consumer, ConsumerOptions, TokioExecutor};
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use serial_test::serial;
+    use std::env;
+    use uuid::Uuid;
+
+    #[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+    struct TestStruct {
+        s: String,
+        i: i64,
+    }
+
+    deserialize_without_context!(TestStruct);
+
+    fn pulsar_url() -> String {
+        env::var("PULSAR_URL").unwrap_or("pulsar://localhost:6650".to_string())
+    }
+
+    /// Publishes `payloads` to `topic` and waits until the broker has
+    /// acknowledged all of them.
+    async fn publish(topic: &str, payloads: &[&str]) {
+        let pulsar = connect(&pulsar_url(), None).await.unwrap();
+        let mut producer = pulsar.producer().with_topic(topic).build().await.unwrap();
+        for payload in payloads {
+            producer
+                .send_non_blocking(payload.as_bytes().to_vec())
+                .await
+                .unwrap()
+                .await
+                .expect("the broker did not acknowledge the message");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_pulsar_input() {
+        // Use a fresh topic for each run, so that the subscription starts
+        // empty.
+        let topic = format!(
+            "persistent://public/default/feldera-test-{}",
+            Uuid::new_v4()
+        );
+
+        let config = serde_json::from_value(json!({
+            "stream": "test_input",
+            "transport": {
+                "name": "pulsar_input",
+                "config": {
+                    "service_url": pulsar_url(),
+                    "topics": [topic],
+                    "subscription": "feldera-test",
+                    "start_position": "earliest",
+                    "receiver_queue_size": 2
+                }
+            },
+            "format": {
+                "name": "csv",
+                "config": {}
+            }
+        }))
+        .unwrap();
+
+        // Messages published before the subscription is created are read
+        // because it starts from the earliest message.
+        TOKIO.block_on(publish(&topic, &["foo,1\n"]));
+
+        let (endpoint, _consumer, _parser, zset) =
+            mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+        endpoint.extend();
+
+        TOKIO.block_on(publish(&topic, &["bar,2\n", "baz,3\n"]));
+
+        wait(
+            || {
+                endpoint.queue(false);
+                zset.state().flushed.len() >= 3
+            },
+            DEFAULT_TIMEOUT_MS,
+        )
+        .unwrap();
+
+        let records = zset
+            .state()
+            .flushed
+            .iter()
+            .map(|upd| upd.unwrap_insert().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            vec![
+                TestStruct {
+                    s: "foo".to_string(),
+                    i: 1
+                },
+                TestStruct {
+                    s: "bar".to_string(),
+                    i: 2
+                },
+                TestStruct {
+                    s: "baz".to_string(),
+                    i: 3
+                },
+            ]
+        );
+    }
+
+    /// Reads `n` messages from the beginning of `topic` and returns their
+    /// payloads.
+    async fn read(topic: &str, n: usize) -> Vec<String> {
+        let pulsar = connect(&pulsar_url(), None).await.unwrap();
+        let mut consumer: Consumer<Vec<u8>, TokioExecutor> = pulsar
+            .consumer()
+            .with_topic(topic)
+            .with_subscription("feldera-test-reader")
+            .with_options(
+                ConsumerOptions::default().with_initial_position(InitialPosition::Earliest),
+            )
+            .build()
+            .await
+            .unwrap();
+        let mut payloads = Vec::new();
+        while payloads.len() < n {
+            let message = consumer.next().await.unwrap().unwrap();
+            payloads.push(String::from_utf8(message.payload.data.clone()).unwrap());
+        }
+        payloads
+    }
+
+    #[test]
+    #[serial]
+    fn test_pulsar_output_ft() {
+        let topic = format!(
+            "persistent://public/default/feldera-test-{}",
+            Uuid::new_v4()
+        );
+        let endpoint = || {
+            let config: PulsarOutputConfig = serde_json::from_value(json!({
+                "service_url": pulsar_url(),
+                "topic": topic
+            }))
+            .unwrap();
+            let mut endpoint = PulsarOutputEndpoint::new(config, true).unwrap();
+            endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+            endpoint
+        };
+
+        let mut first = endpoint();
+        first.batch_start(0).unwrap();
+        first.push_buffer(b"a").unwrap();
+        first.push_buffer(b"b").unwrap();
+        first.batch_end().unwrap();
+        first.batch_start(1).unwrap();
+        first.push_buffer(b"c").unwrap();
+        first.batch_end().unwrap();
+        drop(first);
+
+        // Replaying the steps that were published, including step 1, which
+        // was only partially published, only publishes the new output.
+        let mut second = endpoint();
+        second.batch_start(0).unwrap();
+        second.push_buffer(b"a").unwrap();
+        second.push_buffer(b"b").unwrap();
+        second.batch_end().unwrap();
+        second.batch_start(1).unwrap();
+        second.push_buffer(b"c").unwrap();
+        second.push_buffer(b"d").unwrap();
+        second.batch_end().unwrap();
+        second.batch_start(2).unwrap();
+        second
+            .push_key(Some(b"k".as_slice()), Some(b"e".as_slice()), &[])
+            .unwrap();
+        second.batch_end().unwrap();
+
+        assert_eq!(TOKIO.block_on(read(&topic, 5)), ["a", "b", "c", "d", "e"]);
+    }
+}
+
+#[test]
+fn test_pulsar_input_config_validation() {
+    let config = |extra: serde_json::Value| -> PulsarInputConfig {
+        let mut config = json!({
+            "service_url": "pulsar://localhost:6650",
+            "topics": ["persistent://public/default/events"],
+            "subscription": "feldera"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "service_url": "pulsar+ssl://broker:6651", "subscription_type": "key_shared" }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "service_url": "http://broker:8080" }))
+        .contains("invalid Pulsar service URL"));
+    assert!(error(json!({ "topics": [] })).contains("at least one topic"));
+    assert!(error(json!({ "topics": [""] })).contains("empty topic names"));
+    assert!(error(json!({ "subscription": "" })).contains("'subscription' must not be empty"));
+    assert!(error(json!({ "receiver_queue_size": 0 })).contains("'receiver_queue_size'"));
+}
+
+#[test]
+fn test_pulsar_output_config_validation() {
+    let config = |extra: serde_json::Value| -> PulsarOutputConfig {
+        let mut config = json!({
+            "service_url": "pulsar://localhost:6650",
+            "topic": "persistent://public/default/events"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "max_message_size": 1048576 }))
+        .validate()
+        .unwrap();
+
+    assert!(
+        error(json!({ "service_url": "localhost:6650" })).contains("invalid Pulsar service URL")
+    );
+    assert!(error(json!({ "topic": "" })).contains("'topic' must not be empty"));
+    assert!(error(json!({ "max_message_size": 0 })).contains("'max_message_size'"));
+}
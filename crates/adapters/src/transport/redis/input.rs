@@ -0,0 +1,466 @@
+//! Input transport that reads entries from Redis Streams as a member of a
+//! consumer group.
+
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputBuffer, InputConsumer, InputReader, ParseError, Parser, PipelineState};
+use anyhow::{anyhow, Context, Error as AnyError, Result as AnyResult};
+use chrono::Utc;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::redis::{RedisStreamStartPosition, RedisStreamsInputConfig};
+use redis::streams::{StreamId, StreamReadOptions, StreamReadReply};
+use redis::{Commands, Connection};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tracing::{debug, info_span};
+
+/// Default for [`RedisStreamsInputConfig::batch_size`].
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// Maximum time that `XREADGROUP` blocks waiting for new entries.  This
+/// bounds how long the worker takes to react to state changes and
+/// acknowledgments.
+const BLOCK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Polling interval while the pipeline is paused.
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Delay before reconnecting to the server after a connection error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Number of pending entries claimed with each `XAUTOCLAIM` command.
+const CLAIM_BATCH_SIZE: usize = 100;
+
+pub(crate) struct RedisStreamsInputEndpoint {
+    config: Arc<RedisStreamsInputConfig>,
+}
+
+impl RedisStreamsInputEndpoint {
+    pub(crate) fn new(config: RedisStreamsInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for RedisStreamsInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for RedisStreamsInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(RedisStreamsInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+/// Identifies a stream entry to acknowledge.
+struct EntryId {
+    /// Index of the stream in [`RedisStreamsInputConfig::streams`].
+    stream: usize,
+    id: String,
+}
+
+fn connect(config: &RedisStreamsInputConfig) -> AnyResult<Connection> {
+    redis::Client::open(config.connection_string.as_str())
+        .context("error parsing Redis connection string")?
+        .get_connection()
+        .context("error connecting to the Redis server")
+}
+
+/// Creates the consumer group for each stream, unless it already exists.
+fn create_groups(connection: &mut Connection, config: &RedisStreamsInputConfig) -> AnyResult<()> {
+    let start_id = match &config.start_position {
+        RedisStreamStartPosition::Earliest => "0",
+        RedisStreamStartPosition::Latest => "$",
+        RedisStreamStartPosition::Id(id) => id.as_str(),
+    };
+    for stream in &config.streams {
+        match connection.xgroup_create_mkstream::<_, _, _, ()>(stream, &config.group, start_id) {
+            Ok(()) => debug!(
+                "Redis input: created consumer group '{}' for stream '{stream}'",
+                config.group
+            ),
+            Err(e) if e.code() == Some("BUSYGROUP") => (),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!(
+                        "error creating consumer group '{}' for stream '{stream}'",
+                        config.group
+                    )
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Transfers entries that have been pending for other consumers in the group
+/// for at least `min_idle_ms` to the connector's consumer.
+fn claim_pending(
+    connection: &mut Connection,
+    config: &RedisStreamsInputConfig,
+    min_idle_ms: u64,
+) -> AnyResult<()> {
+    for stream in &config.streams {
+        let mut start = "0-0".to_string();
+        loop {
+            let (next, claimed, _deleted): (String, Vec<String>, redis::Value) =
+                redis::cmd("XAUTOCLAIM")
+                    .arg(stream)
+                    .arg(&config.group)
+                    .arg(&config.consumer)
+                    .arg(min_idle_ms)
+                    .arg(&start)
+                    .arg("COUNT")
+                    .arg(CLAIM_BATCH_SIZE)
+                    .arg("JUSTID")
+                    .query(connection)
+                    .with_context(|| {
+                        format!("error claiming pending entries of stream '{stream}'")
+                    })?;
+            if !claimed.is_empty() {
+                debug!(
+                    "Redis input: claimed {} pending entries of stream '{stream}'",
+                    claimed.len()
+                );
+            }
+            if next == "0-0" {
+                break;
+            }
+            start = next;
+        }
+    }
+    Ok(())
+}
+
+struct RedisStreamsInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Parsed entries, each with the ID to acknowledge once the entry has
+    /// been flushed to the circuit.
+    queue: Arc<InputQueue<EntryId>>,
+
+    /// Sends IDs of flushed entries to the worker thread, which acknowledges
+    /// them.
+    acks: UnboundedSender<Vec<EntryId>>,
+}
+
+impl RedisStreamsInputReader {
+    fn new(
+        config: Arc<RedisStreamsInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let _guard = info_span!("redis_streams_input", group = config.group.clone()).entered();
+        let mut connection = connect(&config)?;
+        if config.create_group {
+            create_groups(&mut connection, &config)?;
+        }
+        if let Some(min_idle_ms) = config.claim_min_idle_ms {
+            claim_pending(&mut connection, &config, min_idle_ms)?;
+        }
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let (acks, acks_receiver) = unbounded_channel();
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("redis-streams-input".to_string())
+            .spawn({
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                let span = info_span!("redis_streams_input", group = config.group.clone());
+                move || {
+                    let _guard = span.entered();
+                    Self::worker_thread(
+                        connection,
+                        config,
+                        consumer,
+                        parser,
+                        queue,
+                        acks_receiver,
+                        receiver,
+                    );
+                    debug!("Redis input: received termination command; worker thread exiting");
+                }
+            })
+            .expect("failed to spawn Redis Streams input thread");
+
+        Ok(Self {
+            sender,
+            consumer,
+            queue,
+            acks,
+        })
+    }
+
+    fn worker_thread(
+        mut connection: Connection,
+        config: Arc<RedisStreamsInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue<EntryId>>,
+        mut acks: UnboundedReceiver<Vec<EntryId>>,
+        receiver: Receiver<PipelineState>,
+    ) {
+        let mut worker = Worker {
+            config: &config,
+            parser: parser.as_mut(),
+            queue: &queue,
+            receiver: &receiver,
+            // Start by reading the entries that were delivered to this
+            // consumer before but never acknowledged, e.g., because the
+            // pipeline stopped before they were flushed.
+            ids: vec!["0".to_string(); config.streams.len()],
+            unacked: Vec::new(),
+        };
+        loop {
+            let Some(error) = worker.consume(&mut connection, &mut acks) else {
+                return;
+            };
+            consumer.error(
+                false,
+                anyhow!("Redis connection error (reconnecting): {error:#}"),
+                Some("redis-connection"),
+            );
+
+            connection = loop {
+                thread::sleep(RECONNECT_DELAY);
+                if *receiver.borrow() == PipelineState::Terminated {
+                    return;
+                }
+                match connect(&config) {
+                    Ok(connection) => break connection,
+                    Err(e) => consumer.error(false, e, Some("redis-connection")),
+                }
+            };
+            debug!("Redis input: reconnected to the server");
+        }
+    }
+}
+
+/// State of the worker thread that survives reconnecting to the server.
+struct Worker<'a> {
+    config: &'a RedisStreamsInputConfig,
+    parser: &'a mut dyn Parser,
+    queue: &'a InputQueue<EntryId>,
+    receiver: &'a Receiver<PipelineState>,
+
+    /// For each stream, the ID to pass to `XREADGROUP`: `>` to read new
+    /// entries, or the ID after which to continue reading the consumer's
+    /// pending entries.
+    ids: Vec<String>,
+
+    /// Entries that have been flushed but not yet acknowledged.
+    unacked: Vec<EntryId>,
+}
+
+impl Worker<'_> {
+    /// Reads entries from `connection` until the pipeline terminates, which
+    /// returns `None`, or an error occurs.
+    fn consume(
+        &mut self,
+        connection: &mut Connection,
+        acks: &mut UnboundedReceiver<Vec<EntryId>>,
+    ) -> Option<AnyError> {
+        loop {
+            loop {
+                match acks.try_recv() {
+                    Ok(ids) => self.unacked.extend(ids),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return None,
+                }
+            }
+            if let Err(e) = self.ack(connection) {
+                return Some(e);
+            }
+
+            let state = *self.receiver.borrow();
+            match state {
+                PipelineState::Terminated => return None,
+                PipelineState::Paused => {
+                    thread::sleep(PAUSED_POLL_INTERVAL);
+                    continue;
+                }
+                PipelineState::Running => (),
+            }
+
+            if let Err(e) = self.read(connection) {
+                return Some(e);
+            }
+        }
+    }
+
+    /// Acknowledges the entries in `self.unacked`.  Entries are kept for
+    /// retrying after reconnecting if acknowledging them fails.
+    fn ack(&mut self, connection: &mut Connection) -> AnyResult<()> {
+        if self.unacked.is_empty() {
+            return Ok(());
+        }
+        let mut by_stream = BTreeMap::<usize, Vec<&str>>::new();
+        for entry in &self.unacked {
+            by_stream
+                .entry(entry.stream)
+                .or_default()
+                .push(entry.id.as_str());
+        }
+        for (stream, ids) in by_stream {
+            let stream = &self.config.streams[stream];
+            connection
+                .xack::<_, _, _, ()>(stream, &self.config.group, &ids)
+                .with_context(|| format!("error acknowledging entries of stream '{stream}'"))?;
+        }
+        self.unacked.clear();
+        Ok(())
+    }
+
+    /// Reads one batch of entries from each stream and pushes them to the
+    /// queue.
+    fn read(&mut self, connection: &mut Connection) -> AnyResult<()> {
+        let batch_size = self.config.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+        let mut options = StreamReadOptions::default()
+            .group(&self.config.group, &self.config.consumer)
+            .count(batch_size);
+
+        // Pending entries are returned immediately, so only block when
+        // waiting for new entries from all of the streams.
+        if self.ids.iter().all(|id| id == ">") {
+            options = options.block(BLOCK_TIMEOUT.as_millis() as usize);
+        }
+
+        let reply: Option<StreamReadReply> = connection
+            .xread_options(&self.config.streams, &self.ids, &options)
+            .context("error reading from Redis streams")?;
+        let keys = reply.map(|reply| reply.keys).unwrap_or_default();
+
+        // Use the time when we received the entries as the ingestion timestamp.
+        let timestamp = Utc::now();
+        for key in keys {
+            let Some(stream) = self.config.streams.iter().position(|s| *s == key.key) else {
+                continue;
+            };
+            if self.ids[stream] != ">" {
+                // Continue reading pending entries after the last one we
+                // received, until there are no more.
+                match key.ids.last() {
+                    Some(entry) => self.ids[stream] = entry.id.clone(),
+                    None => {
+                        debug!(
+                            "Redis input: finished reading pending entries of stream '{}'",
+                            key.key
+                        );
+                        self.ids[stream] = ">".to_string();
+                    }
+                }
+            }
+            for entry in key.ids {
+                let buffer = self.parse(&entry);
+                self.queue.push_with_aux(
+                    buffer,
+                    timestamp,
+                    EntryId {
+                        stream,
+                        id: entry.id,
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(&mut self, entry: &StreamId) -> (Option<Box<dyn InputBuffer>>, Vec<ParseError>) {
+        match &self.config.payload_field {
+            Some(field) => match entry.map.get(field).map(redis::from_redis_value::<Vec<u8>>) {
+                Some(Ok(payload)) => self.parser.parse(&payload),
+                Some(Err(e)) => (
+                    None,
+                    vec![ParseError::bin_envelope_error(
+                        format!(
+                            "field '{field}' of stream entry '{}' is not a string: {e}",
+                            entry.id
+                        ),
+                        &[],
+                        None,
+                    )],
+                ),
+                None => (
+                    None,
+                    vec![ParseError::bin_envelope_error(
+                        format!("stream entry '{}' has no field '{field}'", entry.id),
+                        &[],
+                        None,
+                    )],
+                ),
+            },
+            None => {
+                let object = entry
+                    .map
+                    .iter()
+                    .map(|(field, value)| {
+                        let value = redis::from_redis_value::<Vec<u8>>(value)
+                            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                            .unwrap_or_default();
+                        (field.clone(), serde_json::Value::String(value))
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+                self.parser
+                    .parse(serde_json::Value::Object(object).to_string().as_bytes())
+            }
+        }
+    }
+}
+
+impl InputReader for RedisStreamsInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let mut ids = Vec::with_capacity(consumed.len());
+                let watermarks = consumed
+                    .into_iter()
+                    .map(|(timestamp, id)| {
+                        ids.push(id);
+                        Watermark::new(timestamp, None)
+                    })
+                    .collect();
+                if !ids.is_empty() {
+                    let _ = self.acks.send(ids);
+                }
+                self.consumer.extended(total, None, watermarks);
+            }
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for RedisStreamsInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
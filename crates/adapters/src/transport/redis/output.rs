@@ -1,24 +1,53 @@
 use anyhow::{anyhow, Result as AnyResult};
 use feldera_adapterlib::transport::{AsyncErrorCallback, OutputEndpoint};
-use feldera_types::transport::redis::RedisOutputConfig;
+use feldera_types::transport::redis::{
+    RedisDataStructure, RedisOutputConfig, RedisStreamsOutputConfig,
+};
+use redis::streams::StreamMaxlen;
 use redis::{ConnectionInfo, Pipeline};
 use std::str::FromStr;
 use tracing::{info_span, span::EnteredSpan};
 
+/// Where [`RedisOutputEndpoint`] stores records.
+enum Target {
+    /// Each record under its own key.
+    Keys,
+
+    /// Each record as a field of the hash with the given key.
+    Hash(String),
+
+    /// The key of each record as a member of the sorted set with key `set`,
+    /// scored by the value of `score_field`.
+    SortedSet { set: String, score_field: String },
+}
+
 /// Handles output to Redis.
 ///
 /// This connector sets the output pair.
 pub struct RedisOutputEndpoint {
     config: ConnectionInfo,
+    target: Target,
     pool: Option<r2d2::Pool<redis::Client>>,
     pipeline: Option<redis::Pipeline>,
 }
 
 impl RedisOutputEndpoint {
     pub fn new(config: RedisOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        let target = match config.data_structure {
+            RedisDataStructure::Keys => Target::Keys,
+            RedisDataStructure::Hash => Target::Hash(config.key.unwrap_or_default()),
+            RedisDataStructure::SortedSet => Target::SortedSet {
+                set: config.key.unwrap_or_default(),
+                score_field: config.score_field.unwrap_or_default(),
+            },
+        };
+
         Ok(Self {
             config: ConnectionInfo::from_str(&config.connection_string)
                 .map_err(|e| anyhow!("error parsing Redis connection string: {e}"))?,
+            target,
             pool: None,
             pipeline: None,
         })
@@ -79,11 +108,16 @@ impl OutputEndpoint for RedisOutputEndpoint {
             "redis: trying to push data before pipeline is initialized: unreachable"
         ))?;
 
-        if let Some(val) = val {
-            pipeline.set(key, val);
-        } else {
-            pipeline.del(key);
-        }
+        match (&self.target, val) {
+            (Target::Keys, Some(val)) => pipeline.set(key, val),
+            (Target::Keys, None) => pipeline.del(key),
+            (Target::Hash(hash), Some(val)) => pipeline.hset(hash, key, val),
+            (Target::Hash(hash), None) => pipeline.hdel(hash, key),
+            (Target::SortedSet { set, score_field }, Some(val)) => {
+                pipeline.zadd(set, key, score(val, score_field)?)
+            }
+            (Target::SortedSet { set, .. }, None) => pipeline.zrem(set, key),
+        };
 
         Ok(())
     }
@@ -117,3 +151,169 @@ impl OutputEndpoint for RedisOutputEndpoint {
         false
     }
 }
+
+/// Extracts the value of `score_field` from the JSON-encoded record `val`.
+fn score(val: &[u8], score_field: &str) -> AnyResult<f64> {
+    let record: serde_json::Value = serde_json::from_slice(val)
+        .map_err(|e| anyhow!("redis: error parsing record as JSON: {e}"))?;
+    match record.get(score_field) {
+        Some(serde_json::Value::Number(score)) => score.as_f64(),
+        // Decimals may be encoded as strings.
+        Some(serde_json::Value::String(score)) => score.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        anyhow!("redis: score field '{score_field}' of record {record} is missing or not a number")
+    })
+}
+
+/// Appends output to a Redis stream, or publishes it to a Pub/Sub channel.
+pub struct RedisStreamsOutputEndpoint {
+    config: RedisStreamsOutputConfig,
+    connection_info: ConnectionInfo,
+    pool: Option<r2d2::Pool<redis::Client>>,
+    pipeline: Option<redis::Pipeline>,
+}
+
+impl RedisStreamsOutputEndpoint {
+    pub fn new(config: RedisStreamsOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            connection_info: ConnectionInfo::from_str(&config.connection_string)
+                .map_err(|e| anyhow!("error parsing Redis connection string: {e}"))?,
+            config,
+            pool: None,
+            pipeline: None,
+        })
+    }
+
+    pub fn span(&self) -> EnteredSpan {
+        info_span!(
+            "redis_streams_output",
+            ft = false,
+            config = self.connection_info.addr.to_string()
+        )
+        .entered()
+    }
+
+    /// Appends an entry with the given fields to the stream, or publishes the
+    /// payload to the channel.
+    fn add(&mut self, payload: Option<&[u8]>, fields: &[(&str, &[u8])]) -> AnyResult<()> {
+        let pipeline = self.pipeline.as_mut().ok_or(anyhow!(
+            "redis: trying to push data before pipeline is initialized: unreachable"
+        ))?;
+
+        if let Some(stream) = &self.config.stream {
+            let mut items = Vec::with_capacity(fields.len() + 1);
+            if let Some(payload) = payload {
+                items.push((self.config.payload_field.as_str(), payload));
+            }
+            items.extend_from_slice(fields);
+            match self.config.max_len {
+                Some(max_len) => {
+                    let max_len = if self.config.exact_trimming {
+                        StreamMaxlen::Equals(max_len as usize)
+                    } else {
+                        StreamMaxlen::Approx(max_len as usize)
+                    };
+                    pipeline.xadd_maxlen(stream, max_len, "*", &items)
+                }
+                None => pipeline.xadd(stream, "*", &items),
+            };
+        } else if let Some(channel) = &self.config.channel {
+            pipeline.publish(channel, payload.unwrap_or_default());
+        }
+
+        Ok(())
+    }
+}
+
+impl OutputEndpoint for RedisStreamsOutputEndpoint {
+    fn connect(&mut self, _: AsyncErrorCallback) -> anyhow::Result<()> {
+        let _guard = self.span();
+        let client = redis::Client::open(self.connection_info.clone())
+            .map_err(|e| anyhow!("error connecting to the Redis server: {e}"))?;
+        let pool = r2d2::Pool::builder()
+            .build(client)
+            .map_err(|e| anyhow!("error opening a connection pool to the Redis server: {e}"))?;
+
+        self.pool = Some(pool);
+
+        Ok(())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    // Creates a [`redis::Pipeline`] that is atomic, so that readers see the
+    // entries of each batch at once.
+    fn batch_start(&mut self, _step: feldera_adapterlib::transport::Step) -> AnyResult<()> {
+        let mut pipeline = Pipeline::new();
+        pipeline.atomic();
+        self.pipeline = Some(pipeline);
+        Ok(())
+    }
+
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        let _guard = self.span();
+        self.add(Some(buffer), &[])
+    }
+
+    /// The key is stored in the `key_field` of the stream entry and each
+    /// header in a field of the same name.  A deletion, which has no value,
+    /// is appended as an entry without a `payload_field`.
+    ///
+    /// Pub/Sub messages have no fields, so only the value is published to a
+    /// channel, and a deletion is published as an empty message.
+    fn push_key(
+        &mut self,
+        key: Option<&[u8]>,
+        val: Option<&[u8]>,
+        headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        let _guard = self.span();
+
+        let key_field = self.config.key_field.clone();
+        let mut fields = Vec::with_capacity(headers.len() + 1);
+        if let Some(key) = key {
+            fields.push((key_field.as_str(), key));
+        }
+        fields.extend(
+            headers
+                .iter()
+                .filter_map(|(name, value)| value.map(|value| (*name, value))),
+        );
+        self.add(val, &fields)
+    }
+
+    // Executes the transaction.
+    fn batch_end(&mut self) -> AnyResult<()> {
+        let mut conn = self
+            .pool
+            .clone()
+            .ok_or(anyhow!(
+                "redis: trying to get connection from pool before the pool is initialized: unreachable"
+            ))?
+            .get().map_err(|e| anyhow!("redis: error trying to get a connection from redis connection pool: {e}"))?;
+
+        let pipeline = std::mem::take(&mut self.pipeline);
+
+        let pipeline = pipeline.ok_or(anyhow!(
+            "redis: batch_end called before batch_start: unreachable"
+        ))?;
+
+        let count = pipeline.cmd_iter().count();
+
+        pipeline
+            .exec(&mut conn)
+            .map_err(|e| anyhow!("redis: error committing Redis transaction; {count} uncommitted messages will be lost: {e}"))?;
+
+        Ok(())
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
@@ -1,10 +1,18 @@
 use feldera_sqllib::{ByteArray, Date, SqlDecimal, SqlString, Timestamp, Uuid, Variant, F32, F64};
 use feldera_types::{
+    deserialize_without_context,
     format::json::JsonFlavor,
+    program_schema::Relation,
     serde_with_context::{SerializeWithContext, SqlSerdeConfig},
+    transport::redis::{RedisOutputConfig, RedisStreamsInputConfig, RedisStreamsOutputConfig},
 };
-use redis::Commands;
+use redis::{
+    streams::{StreamPendingReply, StreamRangeReply},
+    Commands,
+};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use serial_test::serial;
 use std::{
     collections::{BTreeMap, HashSet},
     env,
@@ -13,9 +21,13 @@ use std::{
 };
 use tempfile::NamedTempFile;
 
+use super::output::{RedisOutputEndpoint, RedisStreamsOutputEndpoint};
 use crate::{
-    test::{data::TestStruct, test_circuit, wait, DeltaTestStruct},
-    Controller,
+    test::{
+        data::TestStruct, mock_input_pipeline, test_circuit, wait, DeltaTestStruct,
+        DEFAULT_TIMEOUT_MS,
+    },
+    Controller, OutputEndpoint,
 };
 
 fn redis_url() -> String {
@@ -334,3 +346,332 @@ fn test_redis_output_fail() {
 
     assert!(err.to_string().contains("not yet supported"));
 }
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+struct StreamRecord {
+    s: String,
+    i: i64,
+}
+
+deserialize_without_context!(StreamRecord);
+
+#[test]
+#[serial]
+fn test_redis_streams_input() {
+    let streams = ["feldera_test_streams_input1", "feldera_test_streams_input2"];
+    let group = "feldera_test_group";
+
+    let client = redis::Client::open(redis_url()).unwrap();
+    let mut conn = client.get_connection().unwrap();
+    let _: () = conn.del(&streams).unwrap();
+
+    // Entries added before the group is created are read because the group
+    // starts from the beginning of the streams.
+    let _: String = conn.xadd(streams[0], "*", &[("payload", "foo,1")]).unwrap();
+
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "redis_streams_input",
+            "config": {
+                "connection_string": redis_url(),
+                "streams": streams,
+                "group": group,
+                "start_position": "earliest",
+                "payload_field": "payload"
+            }
+        },
+        "format": {
+            "name": "csv",
+            "config": {}
+        }
+    }))
+    .unwrap();
+
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<StreamRecord, StreamRecord>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    let _: String = conn.xadd(streams[1], "*", &[("payload", "bar,2")]).unwrap();
+    let _: String = conn.xadd(streams[0], "*", &[("payload", "baz,3")]).unwrap();
+
+    wait(
+        || {
+            endpoint.queue(false);
+            zset.state().flushed.len() >= 3
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+
+    let mut records = zset
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().clone())
+        .collect::<Vec<_>>();
+    records.sort_by(|a, b| a.s.cmp(&b.s));
+    assert_eq!(
+        records,
+        vec![
+            StreamRecord {
+                s: "bar".to_string(),
+                i: 2
+            },
+            StreamRecord {
+                s: "baz".to_string(),
+                i: 3
+            },
+            StreamRecord {
+                s: "foo".to_string(),
+                i: 1
+            },
+        ]
+    );
+
+    // All flushed entries are eventually acknowledged.
+    wait(
+        || {
+            streams.iter().all(|stream| {
+                let pending: StreamPendingReply = conn.xpending(stream, group).unwrap();
+                pending.count() == 0
+            })
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_redis_streams_input_config_validation() {
+    let config = |extra: serde_json::Value| -> RedisStreamsInputConfig {
+        let mut config = json!({
+            "connection_string": "redis://localhost:6379/0",
+            "streams": ["events"],
+            "group": "feldera"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "start_position": { "id": "1526919030474-55" } }))
+        .validate()
+        .unwrap();
+    config(json!({ "start_position": { "id": "1526919030474" } }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "streams": [] })).contains("'streams'"));
+    assert!(error(json!({ "group": "" })).contains("'group' must not be empty"));
+    assert!(error(json!({ "consumer": "" })).contains("'consumer' must not be empty"));
+    assert!(error(json!({ "batch_size": 0 })).contains("'batch_size'"));
+    assert!(
+        error(json!({ "start_position": { "id": "latest" } })).contains("invalid 'start_position'")
+    );
+}
+
+#[test]
+#[serial]
+fn test_redis_streams_output() {
+    let stream = "feldera_test_streams_output";
+
+    let client = redis::Client::open(redis_url()).unwrap();
+    let mut conn = client.get_connection().unwrap();
+    let _: () = conn.del(stream).unwrap();
+
+    let config: RedisStreamsOutputConfig = serde_json::from_value(json!({
+        "connection_string": redis_url(),
+        "stream": stream,
+        "max_len": 2,
+        "exact_trimming": true
+    }))
+    .unwrap();
+    let mut endpoint = RedisStreamsOutputEndpoint::new(config).unwrap();
+    endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+
+    endpoint.batch_start(0).unwrap();
+    endpoint.push_buffer(b"first").unwrap();
+    endpoint
+        .push_key(
+            Some(b"k1".as_slice()),
+            Some(b"second".as_slice()),
+            &[("op", Some(b"insert".as_slice()))],
+        )
+        .unwrap();
+    endpoint
+        .push_key(Some(b"k2".as_slice()), None, &[])
+        .unwrap();
+    endpoint.batch_end().unwrap();
+
+    // The stream is trimmed to the two most recent entries.
+    let reply: StreamRangeReply = conn.xrange_all(stream).unwrap();
+    let entries = reply
+        .ids
+        .iter()
+        .map(|entry| {
+            entry
+                .map
+                .iter()
+                .map(|(field, value)| {
+                    (
+                        field.clone(),
+                        redis::from_redis_value::<String>(value).unwrap(),
+                    )
+                })
+                .collect::<BTreeMap<_, _>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            BTreeMap::from([
+                ("key".to_string(), "k1".to_string()),
+                ("op".to_string(), "insert".to_string()),
+                ("payload".to_string(), "second".to_string()),
+            ]),
+            BTreeMap::from([("key".to_string(), "k2".to_string())]),
+        ]
+    );
+}
+
+#[test]
+fn test_redis_streams_output_config_validation() {
+    let config = |extra: serde_json::Value| -> RedisStreamsOutputConfig {
+        let mut config = json!({
+            "connection_string": "redis://localhost:6379/0"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({ "stream": "events", "max_len": 1000 }))
+        .validate()
+        .unwrap();
+    config(json!({ "channel": "events" })).validate().unwrap();
+
+    assert!(error(json!({})).contains("one of 'stream' or 'channel' must be set"));
+    assert!(error(json!({ "stream": "events", "channel": "events" }))
+        .contains("only one of 'stream' and 'channel'"));
+    assert!(error(json!({ "stream": "" })).contains("'stream' must not be empty"));
+    assert!(error(json!({ "channel": "events", "max_len": 10 })).contains("'max_len'"));
+    assert!(
+        error(json!({ "stream": "events", "key_field": "payload" })).contains("must be different")
+    );
+}
+
+#[test]
+#[serial]
+fn test_redis_output_data_structures() {
+    let hash = "feldera_test_output_hash";
+    let set = "feldera_test_output_sorted_set";
+
+    let client = redis::Client::open(redis_url()).unwrap();
+    let mut conn = client.get_connection().unwrap();
+    let _: () = conn.del(&[hash, set]).unwrap();
+
+    let open = |config: serde_json::Value| {
+        let mut config = config;
+        config["connection_string"] = json!(redis_url());
+        let config: RedisOutputConfig = serde_json::from_value(config).unwrap();
+        let mut endpoint = RedisOutputEndpoint::new(config).unwrap();
+        endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+        endpoint
+    };
+    let mut hash_endpoint = open(json!({ "data_structure": "hash", "key": hash }));
+    let mut set_endpoint = open(json!({
+        "data_structure": "sorted_set",
+        "key": set,
+        "score_field": "score"
+    }));
+
+    let records = [
+        ("alice", r#"{"name":"alice","score":10}"#),
+        ("bob", r#"{"name":"bob","score":"2.5"}"#),
+        ("carol", r#"{"name":"carol","score":7}"#),
+    ];
+    for endpoint in [&mut hash_endpoint, &mut set_endpoint] {
+        endpoint.batch_start(0).unwrap();
+        for (key, val) in records {
+            endpoint
+                .push_key(Some(key.as_bytes()), Some(val.as_bytes()), &[])
+                .unwrap();
+        }
+        endpoint.batch_end().unwrap();
+
+        endpoint.batch_start(1).unwrap();
+        endpoint
+            .push_key(Some(b"carol".as_slice()), None, &[])
+            .unwrap();
+        endpoint.batch_end().unwrap();
+    }
+
+    let contents: BTreeMap<String, String> = conn.hgetall(hash).unwrap();
+    assert_eq!(
+        contents,
+        BTreeMap::from([
+            ("alice".to_string(), records[0].1.to_string()),
+            ("bob".to_string(), records[1].1.to_string()),
+        ])
+    );
+
+    let members: Vec<(String, f64)> = conn.zrange_withscores(set, 0, -1).unwrap();
+    assert_eq!(
+        members,
+        vec![("bob".to_string(), 2.5), ("alice".to_string(), 10.0)]
+    );
+
+    // Records without a numeric score are rejected.
+    set_endpoint.batch_start(2).unwrap();
+    let error = set_endpoint
+        .push_key(
+            Some(b"dave".as_slice()),
+            Some(br#"{"name":"dave"}"#.as_slice()),
+            &[],
+        )
+        .unwrap_err();
+    assert!(error.to_string().contains("score field 'score'"));
+}
+
+#[test]
+fn test_redis_output_config_validation() {
+    let config = |extra: serde_json::Value| -> RedisOutputConfig {
+        let mut config = json!({
+            "connection_string": "redis://localhost:6379/0"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "data_structure": "hash", "key": "view" }))
+        .validate()
+        .unwrap();
+    config(json!({ "data_structure": "sorted_set", "key": "view", "score_field": "score" }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "key": "view" })).contains("'key' only applies"));
+    assert!(error(json!({ "data_structure": "hash" })).contains("'key' must be set"));
+    assert!(error(json!({ "data_structure": "hash", "key": "" })).contains("'key' must be set"));
+    assert!(
+        error(json!({ "data_structure": "sorted_set", "key": "view" }))
+            .contains("'score_field' must be set")
+    );
+    assert!(
+        error(json!({ "data_structure": "hash", "key": "view", "score_field": "score" }))
+            .contains("'score_field' only applies")
+    );
+}
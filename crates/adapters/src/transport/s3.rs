@@ -767,20 +767,36 @@ impl<T> BufferedReceiver<T> {
 }
 
 fn to_s3_config(config: &Arc<S3InputConfig>) -> aws_sdk_s3::Config {
+    s3_client_config(
+        &config.region,
+        config.endpoint_url.as_deref(),
+        config.no_sign_request,
+        config.aws_access_key_id.as_deref(),
+        config.aws_secret_access_key.as_deref(),
+    )
+}
+
+/// Builds the configuration of an S3 client, reading credentials from the
+/// environment unless they are specified.
+pub(crate) fn s3_client_config(
+    region: &str,
+    endpoint_url: Option<&str>,
+    no_sign_request: bool,
+    access_key: Option<&str>,
+    secret_key: Option<&str>,
+) -> aws_sdk_s3::Config {
     let mut config_builder =
-        aws_sdk_s3::Config::builder().region(aws_types::region::Region::new(config.region.clone()));
+        aws_sdk_s3::Config::builder().region(aws_types::region::Region::new(region.to_string()));
 
-    if let Some(endpoint) = &config.endpoint_url {
+    if let Some(endpoint) = endpoint_url {
         config_builder = config_builder.endpoint_url(endpoint);
     }
 
-    if config.no_sign_request {
+    if no_sign_request {
         return config_builder.build();
     }
 
-    if let (Some(access_key), Some(secret_key)) =
-        (&config.aws_access_key_id, &config.aws_secret_access_key)
-    {
+    if let (Some(access_key), Some(secret_key)) = (access_key, secret_key) {
         let credentials = aws_sdk_s3::config::Credentials::new(
             access_key,
             secret_key,
@@ -0,0 +1,444 @@
+//! Input transport for AWS SQS queues.
+
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Result as AnyResult};
+use aws_sdk_sqs::config::{Credentials, Region};
+use aws_sdk_sqs::error::DisplayErrorContext;
+use aws_sdk_sqs::types::{
+    BatchResultErrorEntry, ChangeMessageVisibilityBatchRequestEntry,
+    DeleteMessageBatchRequestEntry, QueueAttributeName,
+};
+use aws_sdk_sqs::Client;
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::sqs::SqsInputConfig;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::{interval, sleep, MissedTickBehavior};
+use tokio::{join, select};
+use tracing::{debug, info_span, Instrument};
+use uuid::Uuid;
+
+#[cfg(test)]
+mod test;
+
+/// Default for [`SqsInputConfig::wait_time_secs`].
+const DEFAULT_WAIT_TIME_SECS: u32 = 20;
+
+/// Default for [`SqsInputConfig::max_messages`].
+const DEFAULT_MAX_MESSAGES: u32 = 10;
+
+/// Default for [`SqsInputConfig::visibility_timeout_secs`].
+const DEFAULT_VISIBILITY_TIMEOUT_SECS: u32 = 30;
+
+/// Maximum number of entries in a `DeleteMessageBatch` or
+/// `ChangeMessageVisibilityBatch` request.
+const MAX_BATCH_ENTRIES: usize = 10;
+
+/// Delay before receiving messages again after an error.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+pub(crate) struct SqsInputEndpoint {
+    config: Arc<SqsInputConfig>,
+}
+
+impl SqsInputEndpoint {
+    pub(crate) fn new(config: SqsInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for SqsInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for SqsInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(SqsInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+fn to_sqs_config(config: &SqsInputConfig) -> aws_sdk_sqs::Config {
+    let mut config_builder =
+        aws_sdk_sqs::Config::builder().region(Region::new(config.region.clone()));
+
+    if let Some(endpoint) = &config.endpoint_url {
+        config_builder = config_builder.endpoint_url(endpoint);
+    }
+
+    if let (Some(access_key), Some(secret_key)) =
+        (&config.aws_access_key_id, &config.aws_secret_access_key)
+    {
+        let credentials =
+            Credentials::new(access_key, secret_key, None, None, "credential-provider");
+
+        config_builder.credentials_provider(credentials).build()
+    } else {
+        let provider = TOKIO.block_on(async {
+            aws_config::default_provider::credentials::default_provider().await
+        });
+
+        config_builder.credentials_provider(provider).build()
+    }
+}
+
+/// Identifies a received message, to delete it once it has been flushed to
+/// the circuit.
+struct Receipt {
+    message_id: String,
+    receipt_handle: String,
+}
+
+/// Receipt handles of messages that have been received but not deleted,
+/// indexed by message ID.  The connector extends the visibility timeout of
+/// these messages until it deletes them.
+type InFlight = Arc<Mutex<HashMap<String, String>>>;
+
+/// Formats the first of `failed` batch entries for an error message.
+fn describe_failures(failed: &[BatchResultErrorEntry]) -> String {
+    let first = &failed[0];
+    format!(
+        "{} entries failed, e.g., {}: {}",
+        failed.len(),
+        first.code(),
+        first.message().unwrap_or_default()
+    )
+}
+
+struct SqsInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Parsed messages, each with the receipt to delete the message once it
+    /// has been flushed to the circuit.
+    queue: Arc<InputQueue<Receipt>>,
+
+    /// Sends receipts of flushed messages to the worker, which deletes them.
+    deletes: UnboundedSender<Vec<Receipt>>,
+}
+
+impl SqsInputReader {
+    fn new(
+        config: Arc<SqsInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("sqs_input", queue_url = config.queue_url.clone());
+        let client = Client::from_conf(to_sqs_config(&config));
+
+        // Fail early if the queue doesn't exist or isn't accessible.
+        TOKIO
+            .block_on(
+                client
+                    .get_queue_attributes()
+                    .queue_url(&config.queue_url)
+                    .attribute_names(QueueAttributeName::QueueArn)
+                    .send()
+                    .instrument(span.clone()),
+            )
+            .map_err(|e| {
+                anyhow!(
+                    "error accessing SQS queue '{}': {}",
+                    config.queue_url,
+                    DisplayErrorContext(e)
+                )
+            })?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let (deletes, deletes_receiver) = unbounded_channel();
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        let in_flight = InFlight::default();
+        thread::Builder::new()
+            .name("sqs-input-tokio-wrapper".to_string())
+            .spawn({
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        let worker = async {
+                            join!(
+                                Self::receive_task(client.clone(), config.clone(), consumer.clone(), parser, queue, in_flight.clone(), receiver),
+                                Self::maintenance_task(client, config, consumer, in_flight, deletes_receiver),
+                            )
+                        };
+                        select! {
+                            _ = worker.instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("SQS input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn SQS input tokio wrapper thread");
+
+        Ok(Self {
+            sender,
+            consumer,
+            queue,
+            deletes,
+        })
+    }
+
+    /// Receives messages while the pipeline is running.
+    async fn receive_task(
+        client: Client,
+        config: Arc<SqsInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue<Receipt>>,
+        in_flight: InFlight,
+        mut receiver: Receiver<PipelineState>,
+    ) {
+        loop {
+            if receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let mut request = client
+                .receive_message()
+                .queue_url(&config.queue_url)
+                .max_number_of_messages(config.max_messages.unwrap_or(DEFAULT_MAX_MESSAGES) as i32)
+                .wait_time_seconds(config.wait_time_secs.unwrap_or(DEFAULT_WAIT_TIME_SECS) as i32)
+                .visibility_timeout(
+                    config
+                        .visibility_timeout_secs
+                        .unwrap_or(DEFAULT_VISIBILITY_TIMEOUT_SECS) as i32,
+                );
+            if config.is_fifo() {
+                // If the request fails and the SDK retries it, the attempt ID
+                // makes SQS return the same messages again, instead of hiding
+                // them until their visibility timeout expires, which would
+                // block their message groups.
+                request = request.receive_request_attempt_id(Uuid::new_v4().to_string());
+            }
+
+            match request.send().await {
+                Ok(output) => {
+                    // Use the time when we received the messages as the ingestion timestamp.
+                    let timestamp = Utc::now();
+
+                    // SQS returns the messages of each FIFO message group in
+                    // order, and they are queued in the same order.
+                    for message in output.messages.unwrap_or_default() {
+                        let (Some(message_id), Some(receipt_handle)) =
+                            (message.message_id, message.receipt_handle)
+                        else {
+                            continue;
+                        };
+                        in_flight
+                            .lock()
+                            .unwrap()
+                            .insert(message_id.clone(), receipt_handle.clone());
+                        queue.push_with_aux(
+                            parser.parse(message.body.unwrap_or_default().as_bytes()),
+                            timestamp,
+                            Receipt {
+                                message_id,
+                                receipt_handle,
+                            },
+                        );
+                    }
+                }
+                Err(e) => {
+                    consumer.error(
+                        false,
+                        anyhow!("error receiving SQS messages: {}", DisplayErrorContext(e)),
+                        Some("sqs-receive"),
+                    );
+                    sleep(RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+
+    /// Deletes flushed messages and periodically extends the visibility
+    /// timeout of messages that have not been deleted yet.
+    async fn maintenance_task(
+        client: Client,
+        config: Arc<SqsInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        in_flight: InFlight,
+        mut deletes: UnboundedReceiver<Vec<Receipt>>,
+    ) {
+        let visibility_timeout = config
+            .visibility_timeout_secs
+            .unwrap_or(DEFAULT_VISIBILITY_TIMEOUT_SECS);
+
+        // Extend the visibility timeout well before it expires.
+        let mut ticker = interval(Duration::from_secs(visibility_timeout.into()) / 2);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            select! {
+                receipts = deletes.recv() => {
+                    let Some(receipts) = receipts else {
+                        return;
+                    };
+                    {
+                        let mut in_flight = in_flight.lock().unwrap();
+                        for receipt in &receipts {
+                            in_flight.remove(&receipt.message_id);
+                        }
+                    }
+                    if let Err(e) = Self::delete(&client, &config.queue_url, &receipts).await {
+                        consumer.error(false, e, Some("sqs-delete"));
+                    }
+                }
+                _ = ticker.tick() => {
+                    let receipt_handles = in_flight
+                        .lock()
+                        .unwrap()
+                        .values()
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if let Err(e) = Self::extend_visibility(
+                        &client,
+                        &config.queue_url,
+                        &receipt_handles,
+                        visibility_timeout,
+                    )
+                    .await
+                    {
+                        consumer.error(false, e, Some("sqs-visibility"));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Deletes the messages with the given `receipts` from the queue.
+    async fn delete(client: &Client, queue_url: &str, receipts: &[Receipt]) -> AnyResult<()> {
+        for chunk in receipts.chunks(MAX_BATCH_ENTRIES) {
+            let entries = chunk
+                .iter()
+                .enumerate()
+                .map(|(index, receipt)| {
+                    DeleteMessageBatchRequestEntry::builder()
+                        .id(index.to_string())
+                        .receipt_handle(&receipt.receipt_handle)
+                        .build()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let output = client
+                .delete_message_batch()
+                .queue_url(queue_url)
+                .set_entries(Some(entries))
+                .send()
+                .await
+                .map_err(|e| anyhow!("error deleting SQS messages: {}", DisplayErrorContext(e)))?;
+            if !output.failed().is_empty() {
+                return Err(anyhow!(
+                    "error deleting SQS messages: {}",
+                    describe_failures(output.failed())
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resets the visibility timeout of the messages with the given
+    /// `receipt_handles` to `visibility_timeout` seconds from now.
+    async fn extend_visibility(
+        client: &Client,
+        queue_url: &str,
+        receipt_handles: &[String],
+        visibility_timeout: u32,
+    ) -> AnyResult<()> {
+        for chunk in receipt_handles.chunks(MAX_BATCH_ENTRIES) {
+            let entries = chunk
+                .iter()
+                .enumerate()
+                .map(|(index, receipt_handle)| {
+                    ChangeMessageVisibilityBatchRequestEntry::builder()
+                        .id(index.to_string())
+                        .receipt_handle(receipt_handle)
+                        .visibility_timeout(visibility_timeout as i32)
+                        .build()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let output = client
+                .change_message_visibility_batch()
+                .queue_url(queue_url)
+                .set_entries(Some(entries))
+                .send()
+                .await
+                .map_err(|e| {
+                    anyhow!(
+                        "error extending the visibility timeout of SQS messages: {}",
+                        DisplayErrorContext(e)
+                    )
+                })?;
+            if !output.failed().is_empty() {
+                return Err(anyhow!(
+                    "error extending the visibility timeout of SQS messages: {}",
+                    describe_failures(output.failed())
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl InputReader for SqsInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let mut receipts = Vec::with_capacity(consumed.len());
+                let watermarks = consumed
+                    .into_iter()
+                    .map(|(timestamp, receipt)| {
+                        receipts.push(receipt);
+                        Watermark::new(timestamp, None)
+                    })
+                    .collect();
+                if !receipts.is_empty() {
+                    let _ = self.deletes.send(receipts);
+                }
+                self.consumer.extended(total, None, watermarks);
+            }
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for SqsInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
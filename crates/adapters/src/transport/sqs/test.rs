@@ -0,0 +1,204 @@
+use crate::test::{mock_input_pipeline, wait, DEFAULT_TIMEOUT_MS};
+use aws_sdk_sqs::config::{Credentials, Region};
+use aws_sdk_sqs::types::QueueAttributeName;
+use aws_sdk_sqs::Client;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::deserialize_without_context;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::sqs::SqsInputConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serial_test::serial;
+use std::env;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+struct TestStruct {
+    s: String,
+    i: i64,
+}
+
+deserialize_without_context!(TestStruct);
+
+impl TestStruct {
+    fn new(s: &str, i: i64) -> Self {
+        Self {
+            s: s.to_string(),
+            i,
+        }
+    }
+}
+
+/// URL of a LocalStack instance that provides the SQS API.
+fn localstack_url() -> String {
+    env::var("LOCALSTACK_URL").unwrap_or("http://localhost:4566".to_string())
+}
+
+fn client() -> Client {
+    let config = aws_sdk_sqs::Config::builder()
+        .region(Region::new("us-east-1"))
+        .endpoint_url(localstack_url())
+        .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+        .build();
+    Client::from_conf(config)
+}
+
+/// Creates an empty queue named `name` and returns its URL.
+async fn create_queue(client: &Client, name: &str) -> String {
+    if let Ok(output) = client.get_queue_url().queue_name(name).send().await {
+        let _ = client
+            .delete_queue()
+            .queue_url(output.queue_url().unwrap())
+            .send()
+            .await;
+    }
+    let mut request = client.create_queue().queue_name(name);
+    if name.ends_with(".fifo") {
+        request = request
+            .attributes(QueueAttributeName::FifoQueue, "true")
+            .attributes(QueueAttributeName::ContentBasedDeduplication, "true");
+    }
+    request.send().await.unwrap().queue_url.unwrap()
+}
+
+/// Returns the number of messages in `queue_url`, including messages that
+/// have been received but not deleted.
+async fn count_messages(client: &Client, queue_url: &str) -> usize {
+    let output = client
+        .get_queue_attributes()
+        .queue_url(queue_url)
+        .attribute_names(QueueAttributeName::ApproximateNumberOfMessages)
+        .attribute_names(QueueAttributeName::ApproximateNumberOfMessagesNotVisible)
+        .send()
+        .await
+        .unwrap();
+    output
+        .attributes()
+        .unwrap()
+        .values()
+        .map(|count| count.parse::<usize>().unwrap())
+        .sum()
+}
+
+fn test_sqs_input(queue_name: &str) {
+    let client = client();
+    let queue_url = TOKIO.block_on(create_queue(&client, queue_name));
+
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "sqs_input",
+            "config": {
+                "queue_url": queue_url,
+                "region": "us-east-1",
+                "endpoint_url": localstack_url(),
+                "aws_access_key_id": "test",
+                "aws_secret_access_key": "test",
+                "wait_time_secs": 1,
+                "max_messages": 2
+            }
+        },
+        "format": {
+            "name": "csv",
+            "config": {}
+        }
+    }))
+    .unwrap();
+
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    TOKIO.block_on(async {
+        for body in ["foo,1\n", "bar,2\n", "baz,3\n"] {
+            let mut request = client
+                .send_message()
+                .queue_url(&queue_url)
+                .message_body(body);
+            if queue_name.ends_with(".fifo") {
+                request = request.message_group_id("group");
+            }
+            request.send().await.unwrap();
+        }
+    });
+
+    wait(
+        || {
+            endpoint.queue(false);
+            zset.state().flushed.len() >= 3
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+
+    let mut records = zset
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().clone())
+        .collect::<Vec<_>>();
+    let expected = vec![
+        TestStruct::new("foo", 1),
+        TestStruct::new("bar", 2),
+        TestStruct::new("baz", 3),
+    ];
+    if queue_name.ends_with(".fifo") {
+        // FIFO queues preserve the order of the messages.
+        assert_eq!(records, expected);
+    } else {
+        records.sort_by_key(|record| record.i);
+        assert_eq!(records, expected);
+    }
+
+    // Flushed messages are deleted from the queue.
+    wait(
+        || TOKIO.block_on(count_messages(&client, &queue_url)) == 0,
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+}
+
+#[test]
+#[serial]
+fn test_sqs_input_standard() {
+    test_sqs_input("feldera_test_sqs_input");
+}
+
+#[test]
+#[serial]
+fn test_sqs_input_fifo() {
+    test_sqs_input("feldera_test_sqs_input.fifo");
+}
+
+#[test]
+fn test_sqs_input_config_validation() {
+    let config = |extra: serde_json::Value| -> SqsInputConfig {
+        let mut config = json!({
+            "queue_url": "https://sqs.us-east-1.amazonaws.com/123456789012/events",
+            "region": "us-east-1"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "wait_time_secs": 0, "max_messages": 1, "visibility_timeout_secs": 600 }))
+        .validate()
+        .unwrap();
+    assert!(!config(json!({})).is_fifo());
+    assert!(config(
+        json!({ "queue_url": "https://sqs.us-east-1.amazonaws.com/123456789012/events.fifo" })
+    )
+    .is_fifo());
+
+    assert!(error(json!({ "queue_url": "" })).contains("'queue_url' must not be empty"));
+    assert!(error(json!({ "region": "" })).contains("'region' must not be empty"));
+    assert!(error(json!({ "aws_access_key_id": "id" })).contains("'aws_secret_access_key' not set"));
+    assert!(error(json!({ "wait_time_secs": 21 })).contains("'wait_time_secs'"));
+    assert!(error(json!({ "max_messages": 0 })).contains("'max_messages'"));
+    assert!(error(json!({ "max_messages": 11 })).contains("'max_messages'"));
+    assert!(error(json!({ "visibility_timeout_secs": 0 })).contains("'visibility_timeout_secs'"));
+}
@@ -0,0 +1,10 @@
+//! Server-sent events (SSE) input connector, which maintains a long-lived
+//! connection to an event stream and resumes it using `Last-Event-ID`.
+
+mod event;
+mod input;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::SseInputEndpoint;
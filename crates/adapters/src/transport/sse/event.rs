@@ -0,0 +1,154 @@
+//! Parser for the `text/event-stream` format, as specified in the [HTML
+//! standard](https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation).
+
+use std::mem::take;
+use std::time::Duration;
+
+/// Byte order mark, which is ignored at the start of a stream.
+const BOM: &str = "\u{feff}";
+
+/// An event dispatched by [EventStreamParser].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Event {
+    /// Event type, `message` if the event has no `event` field.
+    pub event_type: String,
+
+    /// Event data, with the values of multiple `data` fields separated by
+    /// newlines.
+    pub data: String,
+
+    /// The last event ID as of this event, which is the ID to send in the
+    /// `Last-Event-ID` header to resume the stream after this event.
+    pub last_event_id: Option<String>,
+}
+
+/// Incrementally parses an event stream that arrives in arbitrary chunks.
+#[derive(Debug, Default)]
+pub(super) struct EventStreamParser {
+    /// The incomplete line at the end of the data received so far.
+    line: Vec<u8>,
+
+    /// Whether the last byte received was a carriage return, so that a
+    /// following line feed is part of the same line break.
+    after_cr: bool,
+
+    /// Whether the next line is the first line of the stream.
+    first_line: bool,
+
+    /// Data, event type, and last event ID buffers.
+    data: String,
+    event_type: String,
+    last_event_id: Option<String>,
+
+    /// The reconnection time specified by the last `retry` field, if it
+    /// hasn't been retrieved yet.
+    retry: Option<Duration>,
+}
+
+impl EventStreamParser {
+    /// Creates a parser for a stream that is resumed after the event with ID
+    /// `last_event_id`.
+    pub fn new(last_event_id: Option<String>) -> Self {
+        Self {
+            first_line: true,
+            last_event_id,
+            ..Self::default()
+        }
+    }
+
+    /// The last event ID received so far.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Prepares to parse a new stream after a reconnection, discarding any
+    /// partially received event.  The last event ID is retained.
+    pub fn reset(&mut self) {
+        *self = Self {
+            last_event_id: self.last_event_id.take(),
+            ..Self::new(None)
+        };
+    }
+
+    /// Returns the reconnection time specified by a `retry` field since the
+    /// last call, if any.
+    pub fn take_retry(&mut self) -> Option<Duration> {
+        self.retry.take()
+    }
+
+    /// Parses `bytes` and returns the events that they complete.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+        for &byte in bytes {
+            if take(&mut self.after_cr) && byte == b'\n' {
+                continue;
+            }
+            match byte {
+                b'\n' => self.end_line(&mut events),
+                b'\r' => {
+                    self.after_cr = true;
+                    self.end_line(&mut events);
+                }
+                byte => self.line.push(byte),
+            }
+        }
+        events
+    }
+
+    fn end_line(&mut self, events: &mut Vec<Event>) {
+        let line = take(&mut self.line);
+        let line = String::from_utf8_lossy(&line);
+        let line = if take(&mut self.first_line) {
+            line.strip_prefix(BOM).unwrap_or(&line)
+        } else {
+            &line
+        };
+
+        if line.is_empty() {
+            self.dispatch(events);
+            return;
+        }
+        if line.starts_with(':') {
+            // Comment.
+            return;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event_type = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => {
+                self.last_event_id = (!value.is_empty()).then(|| value.to_string());
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(ms) = value.parse() {
+                    self.retry = Some(Duration::from_millis(ms));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn dispatch(&mut self, events: &mut Vec<Event>) {
+        let mut data = take(&mut self.data);
+        let event_type = take(&mut self.event_type);
+        if data.is_empty() {
+            return;
+        }
+        data.pop();
+        events.push(Event {
+            event_type: if event_type.is_empty() {
+                "message".to_string()
+            } else {
+                event_type
+            },
+            data,
+            last_event_id: self.last_event_id.clone(),
+        });
+    }
+}
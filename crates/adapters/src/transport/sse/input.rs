@@ -0,0 +1,415 @@
+use super::event::{Event, EventStreamParser};
+use crate::format::StreamSplitter;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+    Watermark,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, bail, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_adapterlib::transport::{parse_resume_info, Resume};
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::sse::SseInputConfig;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, CACHE_CONTROL, CONTENT_TYPE};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::time::sleep;
+use tracing::{debug, info, info_span, Instrument};
+use url::Url;
+
+/// Default for [`SseInputConfig::reconnect_delay_ms`].
+const DEFAULT_RECONNECT_DELAY_MS: u64 = 3000;
+
+/// Default for [`SseInputConfig::max_reconnect_delay_ms`].
+const DEFAULT_MAX_RECONNECT_DELAY_MS: u64 = 60_000;
+
+/// The maximum number of bytes of an error response to include in an error
+/// message.
+const MAX_ERROR_BODY: usize = 1024;
+
+/// Header that carries the ID of the last event received, when
+/// reconnecting.
+const LAST_EVENT_ID: &str = "Last-Event-ID";
+
+pub(crate) struct SseInputEndpoint {
+    config: Arc<SseInputConfig>,
+    url: Url,
+}
+
+impl SseInputEndpoint {
+    pub(crate) fn new(config: SseInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+        let url =
+            Url::parse(&config.url).map_err(|e| anyhow!("invalid 'url' '{}': {e}", config.url))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            url,
+        })
+    }
+}
+
+impl InputEndpoint for SseInputEndpoint {
+    /// The connector resumes by reconnecting with the ID of the last event as
+    /// of the checkpoint in the `Last-Event-ID` header.  Whether the server
+    /// replays the events that follow it depends on the server.
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        Some(FtModel::AtLeastOnce)
+    }
+}
+
+impl TransportInputEndpoint for SseInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        let resume_info = resume_info
+            .map(|resume_info| parse_resume_info::<Metadata>(&resume_info))
+            .transpose()?;
+        Ok(Box::new(SseInputReader::new(
+            &self.config,
+            self.url.clone(),
+            consumer,
+            parser,
+            resume_info.unwrap_or_else(|| Metadata {
+                last_event_id: self.config.last_event_id.clone(),
+            }),
+        )?))
+    }
+}
+
+/// Checkpointed state of the connector.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Metadata {
+    /// The ID of the last event received, if any.
+    pub last_event_id: Option<String>,
+}
+
+pub(super) struct SseInputReader {
+    sender: Sender<PipelineState>,
+    consumer: Box<dyn InputConsumer>,
+
+    /// Each event with an ID is followed by an empty buffer with the new
+    /// last event ID, which takes effect once the buffers queued
+    /// before it have been flushed to the circuit.
+    queue: Arc<InputQueue<Option<Metadata>>>,
+
+    /// State as of the buffers flushed so far.
+    pub(super) metadata: Mutex<Metadata>,
+}
+
+impl SseInputReader {
+    pub(super) fn new(
+        config: &Arc<SseInputConfig>,
+        url: Url,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        resume_info: Metadata,
+    ) -> AnyResult<Self> {
+        let client = client(config)?;
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("sse-input-tokio-wrapper".to_string())
+            .spawn({
+                let config = config.clone();
+                let consumer = consumer.clone();
+                let queue = queue.clone();
+                let last_event_id = resume_info.last_event_id.clone();
+                move || {
+                    let span = info_span!("sse_input", url = %url);
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        let reconnect_delay = Duration::from_millis(
+                            config
+                                .reconnect_delay_ms
+                                .unwrap_or(DEFAULT_RECONNECT_DELAY_MS),
+                        );
+                        let listener = Listener {
+                            client,
+                            config,
+                            url,
+                            consumer,
+                            splitter: StreamSplitter::new(parser.splitter()),
+                            parser,
+                            queue,
+                            events: EventStreamParser::new(last_event_id),
+                            reconnect_delay,
+                            receiver,
+                        };
+                        select! {
+                            _ = listener.run().instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("SSE input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn SSE input tokio wrapper thread");
+
+        Ok(Self {
+            sender,
+            consumer,
+            queue,
+            metadata: Mutex::new(resume_info),
+        })
+    }
+}
+
+/// Returns an HTTP client that sends the headers in `config`.
+fn client(config: &SseInputConfig) -> AnyResult<Client> {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, HeaderValue::from_static("text/event-stream"));
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    for (name, value) in &config.headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| anyhow!("invalid HTTP header name '{name}': {e}"))?;
+        let mut header_value = HeaderValue::from_str(value)
+            .map_err(|e| anyhow!("invalid value for HTTP header '{name}': {e}"))?;
+        header_value.set_sensitive(true);
+        headers.insert(header_name, header_value);
+    }
+
+    // The connection is long-lived, so there is no overall request timeout.
+    Client::builder()
+        .default_headers(headers)
+        .build()
+        .map_err(|e| anyhow!("error creating HTTP client: {e}"))
+}
+
+/// Reads the event stream, reconnecting whenever the connection is closed,
+/// and queues the records in its events.
+struct Listener {
+    client: Client,
+    config: Arc<SseInputConfig>,
+    url: Url,
+    consumer: Box<dyn InputConsumer>,
+    parser: Box<dyn Parser>,
+    splitter: StreamSplitter,
+    queue: Arc<InputQueue<Option<Metadata>>>,
+    events: EventStreamParser,
+
+    /// The delay before reconnecting after the connection is closed.
+    reconnect_delay: Duration,
+    receiver: Receiver<PipelineState>,
+}
+
+impl Listener {
+    async fn run(mut self) {
+        let max_reconnect_delay = Duration::from_millis(
+            self.config
+                .max_reconnect_delay_ms
+                .unwrap_or(DEFAULT_MAX_RECONNECT_DELAY_MS),
+        );
+
+        // The number of consecutive failed connection attempts.
+        let mut failures = 0;
+        loop {
+            // The connector only connects while the pipeline is running.
+            if self
+                .receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            match self.connect().await {
+                Ok(Some(response)) => {
+                    failures = 0;
+                    match self.read(response).await {
+                        Ok(()) => info!("SSE input: server closed the event stream, reconnecting"),
+                        Err(e) => self.consumer.error(false, e, Some("sse-read")),
+                    }
+                }
+                Ok(None) => {
+                    info!("SSE input: server responded with status 204 (No Content), end of input");
+                    self.consumer.eoi();
+                    return;
+                }
+                Err(e) => {
+                    failures += 1;
+                    if self
+                        .config
+                        .max_reconnect_attempts
+                        .is_some_and(|max| failures > max)
+                    {
+                        self.consumer.error(
+                            true,
+                            e.context(format!(
+                                "giving up after {failures} consecutive failed connection attempts (see 'max_reconnect_attempts')"
+                            )),
+                            Some("sse-connect"),
+                        );
+                        return;
+                    }
+                    self.consumer.error(false, e, Some("sse-connect"));
+                }
+            }
+
+            // Back off exponentially after consecutive failed attempts.
+            let delay = min(
+                self.reconnect_delay.saturating_mul(1 << min(failures, 16)),
+                max_reconnect_delay,
+            );
+            sleep(delay).await;
+        }
+    }
+
+    /// Connects to the event stream and returns the response, or `None` if
+    /// the server indicated that there are no more events.
+    async fn connect(&mut self) -> AnyResult<Option<Response>> {
+        self.events.reset();
+        let mut request = self.client.get(self.url.clone());
+        if let Some(last_event_id) = self.events.last_event_id() {
+            request = request.header(LAST_EVENT_ID, last_event_id);
+        }
+
+        debug!(
+            "SSE input: connecting to '{}' with last event ID {:?}",
+            self.url,
+            self.events.last_event_id()
+        );
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("error connecting to '{}': {e}", self.url))?;
+        let status = response.status();
+        if status == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let body = response.bytes().await.unwrap_or_default();
+            let body = String::from_utf8_lossy(&body[..body.len().min(MAX_ERROR_BODY)]);
+            bail!(
+                "request to '{}' failed with status {status}: {body}",
+                self.url
+            );
+        }
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if !content_type.starts_with("text/event-stream") {
+            bail!(
+                "expected content type 'text/event-stream' from '{}' but received '{content_type}'",
+                self.url
+            );
+        }
+        Ok(Some(response))
+    }
+
+    /// Reads events from `response` until the server closes the stream.
+    async fn read(&mut self, mut response: Response) -> AnyResult<()> {
+        loop {
+            // Stop reading while the pipeline is paused.  The server may close
+            // the connection in the meantime, in which case we resume from the
+            // last event ID.
+            if self
+                .receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+            let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| anyhow!("error reading event stream from '{}': {e}", self.url))?
+            else {
+                return Ok(());
+            };
+            for event in self.events.push(&chunk) {
+                self.process_event(event);
+            }
+            if let Some(retry) = self.events.take_retry() {
+                debug!("SSE input: server set reconnection time to {retry:?}");
+                self.reconnect_delay = retry;
+            }
+        }
+    }
+
+    /// Parses the data in `event` and queues it, followed by the new last
+    /// event ID.
+    fn process_event(&mut self, event: Event) {
+        let timestamp = Utc::now();
+        let selected = self
+            .config
+            .event_types
+            .as_ref()
+            .is_none_or(|event_types| event_types.contains(&event.event_type));
+        if selected {
+            self.splitter.reset();
+            self.splitter.append(event.data.as_bytes());
+            while let Some(chunk) = self.splitter.next(true) {
+                let (buffer, errors) = self.parser.parse(chunk);
+                self.queue.push_with_aux((buffer, errors), timestamp, None);
+            }
+        }
+        if event.last_event_id.is_some() {
+            self.queue.push_with_aux(
+                (None, Vec::new()),
+                timestamp,
+                Some(Metadata {
+                    last_event_id: event.last_event_id,
+                }),
+            );
+        }
+    }
+}
+
+impl InputReader for SseInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command {
+            InputReaderCommand::Queue { .. } => {
+                let (total, _hasher, consumed) = self.queue.flush_with_aux();
+                let mut metadata = self.metadata.lock().unwrap();
+                let mut watermarks = Vec::with_capacity(consumed.len());
+                for (timestamp, progress) in consumed {
+                    if let Some(progress) = progress {
+                        *metadata = progress;
+                    }
+                    watermarks.push(Watermark::new(timestamp, None));
+                }
+                let resume = Resume::Seek {
+                    seek: serde_json::to_value(&*metadata).unwrap(),
+                };
+                drop(metadata);
+                self.consumer.extended(total, Some(resume), watermarks);
+            }
+            InputReaderCommand::Replay { .. } => self.consumer.error(
+                true,
+                anyhow!("the SSE input connector does not support replay"),
+                None,
+            ),
+            command => match command.as_nonft().unwrap() {
+                NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+                NonFtInputReaderCommand::Queue => unreachable!(),
+            },
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for SseInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
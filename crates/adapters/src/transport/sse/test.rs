@@ -0,0 +1,278 @@
+use super::event::{Event, EventStreamParser};
+use super::input::{Metadata, SseInputReader};
+use crate::test::{mock_parser_pipeline, wait, MockDeZSet, MockInputConsumer};
+use crate::InputReader;
+use actix::System;
+use actix_web::http::StatusCode;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use feldera_types::config::FormatConfig;
+use feldera_types::transport::sse::SseInputConfig;
+use feldera_types::{deserialize_without_context, program_schema::Relation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use url::Url;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, PartialOrd, Ord)]
+struct TestStruct {
+    i: i64,
+}
+deserialize_without_context!(TestStruct);
+
+#[derive(Default)]
+struct ServerState {
+    /// Responses to send, in order, as a status and, for status 200, an
+    /// event stream.  Once empty, the server responds with 204.
+    responses: VecDeque<(u16, &'static str)>,
+
+    /// The `Last-Event-ID` header of each request received.
+    last_event_ids: Vec<Option<String>>,
+}
+
+/// Starts an HTTP server that sends `responses` to successive requests, and
+/// returns its address.
+fn start_server(responses: &[(u16, &'static str)]) -> (SocketAddr, Arc<Mutex<ServerState>>) {
+    let state = Arc::new(Mutex::new(ServerState {
+        responses: responses.iter().copied().collect(),
+        last_event_ids: Vec::new(),
+    }));
+    let (sender, receiver) = channel();
+    thread::Builder::new()
+        .name("sse-input-test".to_string())
+        .spawn({
+            let state = state.clone();
+            move || {
+                System::new().block_on(async {
+                    let server = HttpServer::new(move || {
+                        App::new().app_data(web::Data::new(state.clone())).route(
+                            "/events",
+                            web::get().to(
+                                |request: HttpRequest,
+                                 state: web::Data<Arc<Mutex<ServerState>>>| async move {
+                                    let mut state = state.lock().unwrap();
+                                    state.last_event_ids.push(
+                                        request
+                                            .headers()
+                                            .get("Last-Event-ID")
+                                            .map(|id| id.to_str().unwrap().to_string()),
+                                    );
+                                    match state.responses.pop_front() {
+                                        Some((200, body)) => HttpResponse::Ok()
+                                            .content_type("text/event-stream")
+                                            .body(body),
+                                        Some((status, _)) => HttpResponse::build(
+                                            StatusCode::from_u16(status).unwrap(),
+                                        )
+                                        .finish(),
+                                        None => HttpResponse::NoContent().finish(),
+                                    }
+                                },
+                            ),
+                        )
+                    })
+                    .workers(1)
+                    .bind(("127.0.0.1", 0))
+                    .unwrap();
+                    sender.send(server.addrs()[0]).unwrap();
+                    server.run().await.unwrap();
+                });
+            }
+        })
+        .expect("failed to spawn test thread");
+    (receiver.recv().unwrap(), state)
+}
+
+fn test_setup(
+    config: serde_json::Value,
+    resume_info: Metadata,
+) -> (
+    SseInputReader,
+    MockInputConsumer,
+    MockDeZSet<TestStruct, TestStruct>,
+) {
+    let config: SseInputConfig = serde_json::from_value(config).unwrap();
+    config.validate().unwrap();
+    let format: FormatConfig =
+        serde_json::from_value(json!({"name": "json", "config": {"update_format": "raw"}}))
+            .unwrap();
+    let (consumer, parser, input_handle) =
+        mock_parser_pipeline::<TestStruct, TestStruct>(&Relation::empty(), &format).unwrap();
+    consumer.on_error(Some(Box::new(|_, _| ())));
+    let url = Url::parse(&config.url).unwrap();
+    let reader = SseInputReader::new(
+        &Arc::new(config),
+        url,
+        Box::new(consumer.clone()),
+        Box::new(parser),
+        resume_info,
+    )
+    .unwrap();
+    (reader, consumer, input_handle)
+}
+
+/// Waits until the connector reaches the end of input, and returns the
+/// records that it flushed.
+fn wait_for_eoi(
+    reader: &SseInputReader,
+    consumer: &MockInputConsumer,
+    input_handle: &MockDeZSet<TestStruct, TestStruct>,
+) -> Vec<i64> {
+    wait(
+        || {
+            reader.queue(false);
+            consumer.state().eoi && reader.is_closed()
+        },
+        10000,
+    )
+    .unwrap();
+    reader.queue(false);
+    input_handle
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().i)
+        .collect()
+}
+
+fn event(event_type: &str, data: &str, last_event_id: Option<&str>) -> Event {
+    Event {
+        event_type: event_type.to_string(),
+        data: data.to_string(),
+        last_event_id: last_event_id.map(|id| id.to_string()),
+    }
+}
+
+#[test]
+fn test_event_stream_parser() {
+    let mut parser = EventStreamParser::new(Some("0".to_string()));
+
+    // Byte order mark, comments, and fields split across chunks.
+    assert_eq!(parser.push(b"\xef\xbb\xbf: hello\ndata: a"), vec![]);
+    assert_eq!(
+        parser.push(b"bc\n\nevent: update\nid: 1\ndata:x\ndata: y\n\n"),
+        vec![
+            event("message", "abc", Some("0")),
+            event("update", "x\ny", Some("1"))
+        ]
+    );
+
+    // CRLF split across chunks, CR line endings, and reconnection time.
+    assert_eq!(parser.push(b"retry: 500\r"), vec![]);
+    assert_eq!(parser.take_retry(), Some(Duration::from_millis(500)));
+    assert_eq!(parser.take_retry(), None);
+    assert_eq!(
+        parser.push(b"\nid: 2\r\ndata: z\r\r"),
+        vec![event("message", "z", Some("2"))]
+    );
+
+    // An event without data is not dispatched, but its ID is retained.
+    assert_eq!(
+        parser.push(b"id: 3\nevent: ping\n\ndata\n\n"),
+        vec![event("message", "", Some("3"))]
+    );
+    assert_eq!(parser.last_event_id(), Some("3"));
+
+    // Resetting discards a partial event but keeps the last event ID.
+    assert_eq!(parser.push(b"id: 4\ndata: partial\n"), vec![]);
+    parser.reset();
+    assert_eq!(parser.last_event_id(), Some("4"));
+    assert_eq!(
+        parser.push(b"data: w\n\n"),
+        vec![event("message", "w", Some("4"))]
+    );
+
+    // An empty ID resets the last event ID.
+    assert_eq!(
+        parser.push(b"id\ndata: v\n\n"),
+        vec![event("message", "v", None)]
+    );
+}
+
+#[test]
+fn test_sse_input() {
+    let (addr, state) = start_server(&[
+        (
+            200,
+            ": comment\n\
+             id: 1\ndata: {\"i\": 1}\n\n\
+             event: ping\nid: 2\ndata: {\"i\": 100}\n\n\
+             id: 3\ndata: {\"i\": 2}\ndata: {\"i\": 3}\n\n\
+             retry: 10\n\n\
+             data: {\"i\": 1000}\n",
+        ),
+        (200, "id: 4\r\ndata: {\"i\": 4}\r\n\r\n"),
+    ]);
+    let (reader, consumer, input_handle) = test_setup(
+        json!({
+            "url": format!("http://{addr}/events"),
+            "event_types": ["message"],
+            "reconnect_delay_ms": 10
+        }),
+        Metadata::default(),
+    );
+    reader.extend();
+
+    // Events of other types, and the incomplete event at the end of the
+    // first stream, are ignored.
+    assert_eq!(
+        wait_for_eoi(&reader, &consumer, &input_handle),
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(
+        state.lock().unwrap().last_event_ids,
+        vec![None, Some("3".to_string()), Some("4".to_string())]
+    );
+    assert_eq!(
+        reader.metadata.lock().unwrap().last_event_id.as_deref(),
+        Some("4")
+    );
+}
+
+#[test]
+fn test_sse_resume() {
+    // The connector reconnects after a failed connection attempt, starting
+    // from the checkpointed event ID.
+    let (addr, state) = start_server(&[(503, ""), (200, "id: 8\ndata: {\"i\": 8}\n\n")]);
+    let (reader, consumer, input_handle) = test_setup(
+        json!({
+            "url": format!("http://{addr}/events"),
+            "last_event_id": "0",
+            "reconnect_delay_ms": 10
+        }),
+        Metadata {
+            last_event_id: Some("7".to_string()),
+        },
+    );
+    reader.extend();
+    assert_eq!(wait_for_eoi(&reader, &consumer, &input_handle), vec![8]);
+    assert_eq!(
+        state.lock().unwrap().last_event_ids,
+        vec![
+            Some("7".to_string()),
+            Some("7".to_string()),
+            Some("8".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_sse_max_reconnect_attempts() {
+    let (addr, state) = start_server(&[(500, ""), (500, ""), (500, "")]);
+    let (reader, consumer, _input_handle) = test_setup(
+        json!({
+            "url": format!("http://{addr}/events"),
+            "reconnect_delay_ms": 10,
+            "max_reconnect_attempts": 1
+        }),
+        Metadata::default(),
+    );
+    reader.extend();
+    wait(|| reader.is_closed(), 10000).unwrap();
+    assert!(!consumer.state().eoi);
+    assert_eq!(state.lock().unwrap().last_event_ids.len(), 2);
+}
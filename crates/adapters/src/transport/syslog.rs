@@ -0,0 +1,364 @@
+//! Transport adapter that receives syslog messages over UDP or TCP.
+
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::syslog::{SyslogInputConfig, SyslogProtocol};
+use message::{decode, trim_message};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::sleep;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+mod message;
+
+#[cfg(test)]
+mod test;
+
+/// Default for [`SyslogInputConfig::bind_address`].
+const DEFAULT_BIND_ADDRESS: &str = "0.0.0.0";
+
+/// Default for [`SyslogInputConfig::max_connections`].
+const DEFAULT_MAX_CONNECTIONS: usize = 100;
+
+/// Default for [`SyslogInputConfig::max_message_size`].
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 65535;
+
+/// Delay before accepting connections again after an error, e.g., because
+/// the process ran out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+pub(crate) struct SyslogInputEndpoint {
+    config: Arc<SyslogInputConfig>,
+}
+
+impl SyslogInputEndpoint {
+    pub(crate) fn new(config: SyslogInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for SyslogInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for SyslogInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(SyslogInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+/// Socket that the connector receives messages on.
+enum Socket {
+    Udp(UdpSocket),
+    Tcp(TcpListener),
+}
+
+/// Passes messages to the parser, after decoding them unless the connector
+/// is configured with `raw`.
+struct MessageHandler {
+    raw: bool,
+    parser: Box<dyn Parser>,
+    queue: Arc<InputQueue>,
+
+    /// Buffer for decoded messages.
+    json: Vec<u8>,
+}
+
+impl MessageHandler {
+    fn new(raw: bool, parser: Box<dyn Parser>, queue: Arc<InputQueue>) -> Self {
+        Self {
+            raw,
+            parser,
+            queue,
+            json: Vec::new(),
+        }
+    }
+
+    fn fork(&self) -> Self {
+        Self::new(self.raw, self.parser.fork(), self.queue.clone())
+    }
+
+    fn handle(&mut self, message: &[u8]) {
+        // Use the time when we received the message as the ingestion timestamp.
+        let now = Utc::now();
+        let message = trim_message(message);
+        let data = if self.raw {
+            message
+        } else {
+            self.json.clear();
+            serde_json::to_writer(
+                &mut self.json,
+                &decode(&String::from_utf8_lossy(message), now),
+            )
+            .unwrap();
+            self.json.push(b'\n');
+            &self.json
+        };
+        self.queue.push(self.parser.parse(data), now);
+    }
+}
+
+struct SyslogInputReader {
+    sender: Sender<PipelineState>,
+    queue: Arc<InputQueue>,
+}
+
+impl SyslogInputReader {
+    fn new(
+        config: Arc<SyslogInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("syslog_input", port = config.port);
+        let bind_address = config
+            .bind_address
+            .as_deref()
+            .unwrap_or(DEFAULT_BIND_ADDRESS);
+
+        // Fail early if the port is not available.
+        let address = (bind_address, config.port);
+        let socket = match config.protocol {
+            SyslogProtocol::Udp => TOKIO.block_on(UdpSocket::bind(address)).map(Socket::Udp),
+            SyslogProtocol::Tcp => TOKIO.block_on(TcpListener::bind(address)).map(Socket::Tcp),
+        }
+        .with_context(|| {
+            format!(
+                "error listening for syslog messages on {bind_address}:{}",
+                config.port
+            )
+        })?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        let handler = MessageHandler::new(config.raw, parser, queue.clone());
+        thread::Builder::new()
+            .name("syslog-input-tokio-wrapper".to_string())
+            .spawn(move || {
+                TOKIO.block_on(async {
+                    let mut receiver_clone = receiver.clone();
+                    let worker = async {
+                        match socket {
+                            Socket::Udp(socket) => {
+                                Self::udp_task(socket, config, consumer, handler, receiver).await
+                            }
+                            Socket::Tcp(listener) => {
+                                Self::accept_task(listener, config, consumer, handler, receiver)
+                                    .await
+                            }
+                        }
+                    };
+                    select! {
+                        _ = worker.instrument(span) => {}
+                        _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                            debug!("syslog input: received termination command; worker task canceled");
+                        }
+                    }
+                })
+            })
+            .expect("failed to spawn syslog input tokio wrapper thread");
+
+        Ok(Self { sender, queue })
+    }
+
+    /// Receives one message per datagram.
+    ///
+    /// Datagrams are only received while the pipeline is running.  While it
+    /// is paused, datagrams that don't fit in the socket's receive buffer are
+    /// lost.
+    async fn udp_task(
+        socket: UdpSocket,
+        config: Arc<SyslogInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        mut handler: MessageHandler,
+        mut receiver: Receiver<PipelineState>,
+    ) {
+        let mut buffer = vec![0; config.max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE)];
+        loop {
+            if receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            match socket.recv(&mut buffer).await {
+                Ok(n) => handler.handle(&buffer[..n]),
+                Err(e) => consumer.error(
+                    false,
+                    anyhow!("error receiving syslog datagram: {e}"),
+                    Some("syslog-receive"),
+                ),
+            }
+        }
+    }
+
+    /// Accepts TCP connections and spawns a task to serve each of them.
+    ///
+    /// Dropping this future closes all of the connections.
+    async fn accept_task(
+        listener: TcpListener,
+        config: Arc<SyslogInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        handler: MessageHandler,
+        receiver: Receiver<PipelineState>,
+    ) {
+        let connections = Arc::new(Semaphore::new(
+            config.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS),
+        ));
+        let max_message_size = config.max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE);
+        let mut tasks = JoinSet::new();
+        loop {
+            select! {
+                result = listener.accept() => match result {
+                    Ok((stream, peer)) => {
+                        let Ok(permit) = connections.clone().try_acquire_owned() else {
+                            warn!("closing syslog connection from {peer}: too many connections");
+                            continue;
+                        };
+                        let consumer = consumer.clone();
+                        let handler = handler.fork();
+                        let receiver = receiver.clone();
+                        tasks.spawn(
+                            async move {
+                                info!("syslog client connected");
+                                if let Err(e) = read_messages(stream, max_message_size, handler, receiver).await {
+                                    consumer.error(false, e, Some("syslog-receive"));
+                                }
+                                info!("syslog client disconnected");
+                                drop(permit);
+                            }
+                            .instrument(info_span!("syslog_connection", %peer)),
+                        );
+                    }
+                    Err(e) => {
+                        consumer.error(
+                            false,
+                            anyhow!("error accepting syslog connection: {e}"),
+                            Some("syslog-accept"),
+                        );
+                        sleep(ACCEPT_RETRY_DELAY).await;
+                    }
+                },
+                Some(_) = tasks.join_next() => {}
+            }
+        }
+    }
+}
+
+/// Reads messages from a TCP connection until the client closes it.
+///
+/// Each message is framed either by octet counting, i.e., preceded by its
+/// length in bytes and a space, or by a trailing newline (RFC 6587).  The
+/// framing of each message is recognized by whether it starts with a digit,
+/// since a syslog message always starts with `<`.
+///
+/// Messages are only read while the pipeline is running.  While it is
+/// paused, TCP flow control makes clients wait.
+async fn read_messages(
+    stream: TcpStream,
+    max_message_size: usize,
+    mut handler: MessageHandler,
+    mut receiver: Receiver<PipelineState>,
+) -> AnyResult<()> {
+    let mut reader = BufReader::new(stream);
+    let mut message = Vec::new();
+    loop {
+        if receiver
+            .wait_for(|state| state == &PipelineState::Running)
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+
+        let buffer = reader
+            .fill_buf()
+            .await
+            .context("error receiving from syslog client")?;
+        let Some(&first) = buffer.first() else {
+            return Ok(());
+        };
+
+        message.clear();
+        if first.is_ascii_digit() {
+            let mut length = Vec::new();
+            (&mut reader)
+                .take(10)
+                .read_until(b' ', &mut length)
+                .await
+                .context("error receiving from syslog client")?;
+            let length = std::str::from_utf8(&length)
+                .ok()
+                .and_then(|length| length.strip_suffix(' '))
+                .and_then(|length| length.parse::<usize>().ok())
+                .ok_or_else(|| anyhow!("syslog client sent an invalid message length"))?;
+            if length > max_message_size {
+                bail!("syslog client sent a message longer than 'max_message_size' ({max_message_size} bytes); closing the connection");
+            }
+            message.resize(length, 0);
+            reader
+                .read_exact(&mut message)
+                .await
+                .context("error receiving from syslog client")?;
+        } else {
+            let n = (&mut reader)
+                .take(max_message_size as u64 + 1)
+                .read_until(b'\n', &mut message)
+                .await
+                .context("error receiving from syslog client")?;
+            if n > max_message_size && !message.ends_with(b"\n") {
+                bail!("syslog client sent a message longer than 'max_message_size' ({max_message_size} bytes); closing the connection");
+            }
+        }
+        handler.handle(&message);
+    }
+}
+
+impl InputReader for SyslogInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => self.queue.queue(),
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for SyslogInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
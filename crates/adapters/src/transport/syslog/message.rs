@@ -0,0 +1,227 @@
+//! Decoding of syslog messages in the RFC 5424 and RFC 3164 formats.
+
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeDelta, Utc};
+use serde::Serialize;
+
+/// Facility of messages without a priority (RFC 3164 section 4.3.3).
+const DEFAULT_FACILITY: u8 = 1;
+
+/// Severity of messages without a priority (RFC 3164 section 4.3.3).
+const DEFAULT_SEVERITY: u8 = 5;
+
+/// Format of timestamps in decoded messages, which is the default format of
+/// `TIMESTAMP` columns in the `json` format.
+const TIMESTAMP_FORMAT: &str = "%F %T%.f";
+
+/// A decoded syslog message.
+///
+/// Fields that are missing from the message, or that are `-` in an RFC 5424
+/// message, are `None`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(super) struct SyslogMessage<'a> {
+    pub facility: u8,
+    pub severity: u8,
+
+    /// Timestamp converted to UTC and formatted as `YYYY-MM-DD hh:mm:ss`,
+    /// with fractional seconds if the message has them.
+    pub timestamp: Option<String>,
+    pub hostname: Option<&'a str>,
+    pub app_name: Option<&'a str>,
+    pub proc_id: Option<&'a str>,
+    pub msg_id: Option<&'a str>,
+
+    /// RFC 5424 structured data, as it appears in the message.
+    pub structured_data: Option<&'a str>,
+    pub message: &'a str,
+}
+
+/// Strips the trailing newline, if any, and any trailing NUL bytes, which
+/// some senders use to terminate messages.
+pub(super) fn trim_message(message: &[u8]) -> &[u8] {
+    let mut message = message;
+    while let [rest @ .., b'\n' | b'\r' | b'\0'] = message {
+        message = rest;
+    }
+    message
+}
+
+/// Decodes `message`, which must not have a trailing newline.
+///
+/// Decoding never fails: a message that is not in the RFC 5424 format is
+/// decoded as an RFC 3164 message, which has so few requirements that any
+/// text qualifies.  `now` is used to fill in the year of RFC 3164
+/// timestamps, which don't include one.
+pub(super) fn decode(message: &str, now: DateTime<Utc>) -> SyslogMessage<'_> {
+    let (facility, severity, rest) = match split_priority(message) {
+        Some((priority, rest)) => (priority / 8, priority % 8, rest),
+        None => (DEFAULT_FACILITY, DEFAULT_SEVERITY, message),
+    };
+    decode_rfc5424(facility, severity, rest)
+        .unwrap_or_else(|| decode_rfc3164(facility, severity, rest, now))
+}
+
+/// Splits a `<PRI>` prefix off `message` and returns the priority and the
+/// rest of the message.
+fn split_priority(message: &str) -> Option<(u8, &str)> {
+    let rest = message.strip_prefix('<')?;
+    let end = rest.find('>')?;
+    let digits = &rest[..end];
+    if digits.is_empty() || digits.len() > 3 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let priority = digits.parse::<u8>().ok().filter(|&p| p <= 191)?;
+    Some((priority, &rest[end + 1..]))
+}
+
+/// Splits the next space-separated field off `s`.  Returns `None` if there
+/// is no field or if it is not followed by a space or the end of `s`.
+fn split_field(s: &str) -> Option<(&str, &str)> {
+    let (field, rest) = s.split_once(' ').unwrap_or((s, ""));
+    (!field.is_empty()).then_some((field, rest))
+}
+
+/// Maps the RFC 5424 nil value `-` to `None`.
+fn nil(field: &str) -> Option<&str> {
+    (field != "-").then_some(field)
+}
+
+/// Decodes the part of an RFC 5424 message after the priority, or returns
+/// `None` if it is not in that format.
+fn decode_rfc5424(facility: u8, severity: u8, message: &str) -> Option<SyslogMessage<'_>> {
+    let (version, rest) = split_field(message)?;
+    if version != "1" {
+        return None;
+    }
+    let (timestamp, rest) = split_field(rest)?;
+    let timestamp = match nil(timestamp) {
+        Some(timestamp) => Some(
+            DateTime::parse_from_rfc3339(timestamp)
+                .ok()?
+                .naive_utc()
+                .format(TIMESTAMP_FORMAT)
+                .to_string(),
+        ),
+        None => None,
+    };
+    let (hostname, rest) = split_field(rest)?;
+    let (app_name, rest) = split_field(rest)?;
+    let (proc_id, rest) = split_field(rest)?;
+    let (msg_id, rest) = split_field(rest)?;
+    let (structured_data, rest) = split_structured_data(rest)?;
+    let message = rest.strip_prefix(' ').unwrap_or(rest);
+
+    Some(SyslogMessage {
+        facility,
+        severity,
+        timestamp,
+        hostname: nil(hostname),
+        app_name: nil(app_name),
+        proc_id: nil(proc_id),
+        msg_id: nil(msg_id),
+        structured_data: nil(structured_data),
+        // The message may start with a byte order mark.
+        message: message.strip_prefix('\u{feff}').unwrap_or(message),
+    })
+}
+
+/// Splits RFC 5424 structured data, i.e., `-` or one or more `[...]`
+/// elements, off `s`.
+fn split_structured_data(s: &str) -> Option<(&str, &str)> {
+    if let Some(rest) = s.strip_prefix('-') {
+        return (rest.is_empty() || rest.starts_with(' ')).then_some(("-", rest));
+    }
+
+    let mut in_element = false;
+    let mut in_value = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_value => escaped = true,
+            '"' if in_element => in_value = !in_value,
+            '[' if !in_element => in_element = true,
+            ']' if in_element && !in_value => in_element = false,
+            _ if !in_element => {
+                return (i > 0 && c == ' ').then_some((&s[..i], &s[i..]));
+            }
+            _ => (),
+        }
+    }
+    (!s.is_empty() && !in_element).then_some((s, ""))
+}
+
+/// Decodes the part of an RFC 3164 message after the priority:
+/// `Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MESSAGE`.
+///
+/// Parts that are missing or malformed are left out, and whatever can't be
+/// decoded becomes part of the message.
+fn decode_rfc3164(
+    facility: u8,
+    severity: u8,
+    message: &str,
+    now: DateTime<Utc>,
+) -> SyslogMessage<'_> {
+    let mut decoded = SyslogMessage {
+        facility,
+        severity,
+        timestamp: None,
+        hostname: None,
+        app_name: None,
+        proc_id: None,
+        msg_id: None,
+        structured_data: None,
+        message,
+    };
+
+    let Some((timestamp, rest)) = message
+        .get(..15)
+        .and_then(|timestamp| Some((parse_rfc3164_timestamp(timestamp, now)?, &message[15..])))
+    else {
+        return decoded;
+    };
+    decoded.timestamp = Some(timestamp.format(TIMESTAMP_FORMAT).to_string());
+    let Some(rest) = rest.strip_prefix(' ') else {
+        decoded.message = rest;
+        return decoded;
+    };
+    let Some((hostname, rest)) = split_field(rest) else {
+        decoded.message = rest;
+        return decoded;
+    };
+    decoded.hostname = Some(hostname);
+    decoded.message = rest;
+
+    // The tag is a run of alphanumeric characters, but in practice it often
+    // contains other characters, such as `-`, `.`, or `/`.
+    let tag_end = rest.find(['[', ':', ' ']).unwrap_or(rest.len());
+    let (tag, after_tag) = rest.split_at(tag_end);
+    let (proc_id, after_tag) = match after_tag.strip_prefix('[').and_then(|s| s.split_once(']')) {
+        Some((proc_id, rest)) => (Some(proc_id), rest),
+        None => (None, after_tag),
+    };
+    if let Some(message) = after_tag.strip_prefix(':') {
+        if !tag.is_empty() {
+            decoded.app_name = Some(tag);
+            decoded.proc_id = proc_id;
+            decoded.message = message.strip_prefix(' ').unwrap_or(message);
+        }
+    }
+    decoded
+}
+
+/// Parses an RFC 3164 timestamp, e.g., `Oct 11 22:14:15` or
+/// `Oct  1 22:14:15`, in the year that puts it closest to `now`.
+fn parse_rfc3164_timestamp(timestamp: &str, now: DateTime<Utc>) -> Option<NaiveDateTime> {
+    let year = now.year();
+    let parse = |year: i32| {
+        NaiveDateTime::parse_from_str(&format!("{year} {timestamp}"), "%Y %b %e %H:%M:%S").ok()
+    };
+
+    // A message from late December may arrive in early January.
+    let timestamp = parse(year)?;
+    if timestamp - now.naive_utc() > TimeDelta::days(1) {
+        parse(year - 1)
+    } else {
+        Some(timestamp)
+    }
+}
@@ -0,0 +1,296 @@
+use super::message::{decode, SyslogMessage};
+use crate::test::{mock_input_pipeline, wait, DEFAULT_TIMEOUT_MS};
+use chrono::{DateTime, Utc};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::deserialize_without_context;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::syslog::SyslogInputConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serial_test::serial;
+use std::future::Future;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Port that the connector listens on in tests.
+const PORT: u16 = 18771;
+
+fn utc(timestamp: &str) -> DateTime<Utc> {
+    timestamp.parse().unwrap()
+}
+
+#[test]
+fn test_decode_rfc5424() {
+    let now = utc("2025-06-01T00:00:00Z");
+    assert_eq!(
+        decode("<34>1 2003-10-11T22:14:15.003Z mymachine.example.com su - ID47 - \u{feff}'su root' failed for lonvick on /dev/pts/8", now),
+        SyslogMessage {
+            facility: 4,
+            severity: 2,
+            timestamp: Some("2003-10-11 22:14:15.003".to_string()),
+            hostname: Some("mymachine.example.com"),
+            app_name: Some("su"),
+            proc_id: None,
+            msg_id: Some("ID47"),
+            structured_data: None,
+            message: "'su root' failed for lonvick on /dev/pts/8",
+        }
+    );
+
+    // Timestamps are converted to UTC.  Structured data values may contain
+    // escaped quotes and brackets.
+    assert_eq!(
+        decode(
+            r#"<165>1 2003-08-24T05:14:15.000003-07:00 192.0.2.1 myproc 8710 - [exampleSDID@32473 iut="3" eventSource="App \"x\" [y\]"][examplePriority@32473 class="high"] %% It's time"#,
+            now
+        ),
+        SyslogMessage {
+            facility: 20,
+            severity: 5,
+            timestamp: Some("2003-08-24 12:14:15.000003".to_string()),
+            hostname: Some("192.0.2.1"),
+            app_name: Some("myproc"),
+            proc_id: Some("8710"),
+            msg_id: None,
+            structured_data: Some(
+                r#"[exampleSDID@32473 iut="3" eventSource="App \"x\" [y\]"][examplePriority@32473 class="high"]"#
+            ),
+            message: "%% It's time",
+        }
+    );
+
+    // The message is optional.
+    assert_eq!(
+        decode("<13>1 - - - - - -", now),
+        SyslogMessage {
+            facility: 1,
+            severity: 5,
+            timestamp: None,
+            hostname: None,
+            app_name: None,
+            proc_id: None,
+            msg_id: None,
+            structured_data: None,
+            message: "",
+        }
+    );
+}
+
+#[test]
+fn test_decode_rfc3164() {
+    let now = utc("2025-12-01T00:00:00Z");
+    assert_eq!(
+        decode(
+            "<34>Oct 11 22:14:15 mymachine su: 'su root' failed for lonvick on /dev/pts/8",
+            now
+        ),
+        SyslogMessage {
+            facility: 4,
+            severity: 2,
+            timestamp: Some("2025-10-11 22:14:15".to_string()),
+            hostname: Some("mymachine"),
+            app_name: Some("su"),
+            proc_id: None,
+            msg_id: None,
+            structured_data: None,
+            message: "'su root' failed for lonvick on /dev/pts/8",
+        }
+    );
+    assert_eq!(
+        decode(
+            "<38>Feb  5 17:32:18 10.0.0.99 sshd[1234]: Accepted publickey for alice",
+            now
+        ),
+        SyslogMessage {
+            facility: 4,
+            severity: 6,
+            timestamp: Some("2025-02-05 17:32:18".to_string()),
+            hostname: Some("10.0.0.99"),
+            app_name: Some("sshd"),
+            proc_id: Some("1234"),
+            msg_id: None,
+            structured_data: None,
+            message: "Accepted publickey for alice",
+        }
+    );
+
+    // A message from late December that arrives in January is from the
+    // previous year.
+    assert_eq!(
+        decode(
+            "<13>Dec 31 23:59:59 host message",
+            utc("2026-01-01T00:00:01Z")
+        )
+        .timestamp,
+        Some("2025-12-31 23:59:59".to_string())
+    );
+
+    // Without a tag, everything after the hostname is the message.
+    let decoded = decode("<13>Oct 11 22:14:15 host just a message", now);
+    assert_eq!(decoded.app_name, None);
+    assert_eq!(decoded.message, "just a message");
+
+    // Messages without a priority or a timestamp are still accepted.
+    assert_eq!(
+        decode("hello world", now),
+        SyslogMessage {
+            facility: 1,
+            severity: 5,
+            timestamp: None,
+            hostname: None,
+            app_name: None,
+            proc_id: None,
+            msg_id: None,
+            structured_data: None,
+            message: "hello world",
+        }
+    );
+    assert_eq!(decode("<999>hello", now).message, "<999>hello");
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+struct LogRecord {
+    severity: i64,
+    hostname: Option<String>,
+    app_name: Option<String>,
+    message: String,
+}
+
+deserialize_without_context!(LogRecord);
+
+impl LogRecord {
+    fn new(severity: i64, hostname: &str, app_name: &str, message: &str) -> Self {
+        Self {
+            severity,
+            hostname: Some(hostname.to_string()),
+            app_name: Some(app_name.to_string()),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Runs a pipeline with a syslog input connector that uses `protocol`, runs
+/// `send` to send messages to it, and returns the records that the pipeline
+/// received, once there are `expected_records` of them.
+fn run_pipeline(
+    protocol: &str,
+    expected_records: usize,
+    send: impl Future<Output = ()>,
+) -> Vec<LogRecord> {
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "syslog_input",
+            "config": {
+                "protocol": protocol,
+                "bind_address": "127.0.0.1",
+                "port": PORT
+            }
+        },
+        "format": {
+            "name": "json",
+            "config": {
+                "update_format": "raw"
+            }
+        }
+    }))
+    .unwrap();
+
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<LogRecord, LogRecord>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    TOKIO.block_on(send);
+
+    wait(
+        || {
+            endpoint.queue(false);
+            zset.state().flushed.len() >= expected_records
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+
+    let mut records = zset
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().clone())
+        .collect::<Vec<_>>();
+    records.sort_by(|a, b| a.message.cmp(&b.message));
+    records
+}
+
+#[test]
+#[serial]
+fn test_syslog_udp() {
+    let records = run_pipeline("udp", 2, async {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket.connect(("127.0.0.1", PORT)).await.unwrap();
+        socket
+            .send(b"<11>1 2025-01-01T00:00:00Z host1 app1 - - - message 1\n")
+            .await
+            .unwrap();
+        socket
+            .send(b"<14>Jan  1 00:00:00 host2 app2[42]: message 2")
+            .await
+            .unwrap();
+    });
+    assert_eq!(
+        records,
+        vec![
+            LogRecord::new(3, "host1", "app1", "message 1"),
+            LogRecord::new(6, "host2", "app2", "message 2"),
+        ]
+    );
+}
+
+#[test]
+#[serial]
+fn test_syslog_tcp() {
+    let records = run_pipeline("tcp", 3, async {
+        // Octet-counted and newline-terminated messages can be mixed.
+        let mut client = TcpStream::connect(("127.0.0.1", PORT)).await.unwrap();
+        let message = "<11>1 - host1 app1 - - - message 1\nwith a newline";
+        client
+            .write_all(format!("{} {message}", message.len()).as_bytes())
+            .await
+            .unwrap();
+        client
+            .write_all(b"<12>1 - host2 app2 - - - message 2\n<13>1 - host3 app3 - - - message 3")
+            .await
+            .unwrap();
+    });
+    assert_eq!(
+        records,
+        vec![
+            LogRecord::new(3, "host1", "app1", "message 1\nwith a newline"),
+            LogRecord::new(4, "host2", "app2", "message 2"),
+            LogRecord::new(5, "host3", "app3", "message 3"),
+        ]
+    );
+}
+
+#[test]
+fn test_syslog_config_validation() {
+    let config = |extra: serde_json::Value| -> SyslogInputConfig {
+        let mut config = json!({ "port": 514 });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "protocol": "tcp", "bind_address": "127.0.0.1", "max_connections": 1, "max_message_size": 1024, "raw": true }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "port": 0 })).contains("'port' must not be 0"));
+    assert!(error(json!({ "bind_address": "" })).contains("'bind_address'"));
+    assert!(error(json!({ "max_connections": 1 })).contains("requires the 'tcp' protocol"));
+    assert!(error(json!({ "protocol": "tcp", "max_connections": 0 })).contains("'max_connections'"));
+    assert!(error(json!({ "max_message_size": 0 })).contains("'max_message_size'"));
+}
@@ -0,0 +1,297 @@
+//! Transport adapter that accepts newline-delimited records over TCP.
+
+use crate::transport::tls::tls_server_config;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::tcp::TcpInputConfig;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::sleep;
+use tokio_rustls::TlsAcceptor;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+#[cfg(test)]
+mod test;
+
+/// Default for [`TcpInputConfig::bind_address`].
+const DEFAULT_BIND_ADDRESS: &str = "0.0.0.0";
+
+/// Default for [`TcpInputConfig::max_connections`].
+const DEFAULT_MAX_CONNECTIONS: usize = 100;
+
+/// Default for [`TcpInputConfig::max_line_length`].
+const DEFAULT_MAX_LINE_LENGTH: usize = 1024 * 1024;
+
+/// Delay before accepting connections again after an error, e.g., because
+/// the process ran out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+pub(crate) struct TcpInputEndpoint {
+    config: Arc<TcpInputConfig>,
+}
+
+impl TcpInputEndpoint {
+    pub(crate) fn new(config: TcpInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for TcpInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for TcpInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(TcpInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+struct TcpInputReader {
+    sender: Sender<PipelineState>,
+    queue: Arc<InputQueue>,
+}
+
+impl TcpInputReader {
+    fn new(
+        config: Arc<TcpInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("tcp_input", port = config.port);
+        let acceptor = match &config.tls {
+            Some(tls) => Some(TlsAcceptor::from(Arc::new(tls_server_config(tls)?))),
+            None => None,
+        };
+        let bind_address = config
+            .bind_address
+            .as_deref()
+            .unwrap_or(DEFAULT_BIND_ADDRESS);
+
+        // Fail early if the port is not available.
+        let listener = TOKIO
+            .block_on(TcpListener::bind((bind_address, config.port)))
+            .with_context(|| {
+                format!(
+                    "error listening for TCP connections on {bind_address}:{}",
+                    config.port
+                )
+            })?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("tcp-input-tokio-wrapper".to_string())
+            .spawn({
+                let queue = queue.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        select! {
+                            _ = Self::accept_task(listener, acceptor, config, consumer, parser, queue, receiver).instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("TCP input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn TCP input tokio wrapper thread");
+
+        Ok(Self { sender, queue })
+    }
+
+    /// Accepts connections and spawns a task to serve each of them.
+    ///
+    /// Dropping this future closes all of the connections.
+    async fn accept_task(
+        listener: TcpListener,
+        acceptor: Option<TlsAcceptor>,
+        config: Arc<TcpInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        queue: Arc<InputQueue>,
+        receiver: Receiver<PipelineState>,
+    ) {
+        let connections = Arc::new(Semaphore::new(
+            config.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS),
+        ));
+        let max_line_length = config.max_line_length.unwrap_or(DEFAULT_MAX_LINE_LENGTH);
+        let mut tasks = JoinSet::new();
+        loop {
+            select! {
+                result = listener.accept() => match result {
+                    Ok((stream, peer)) => {
+                        let Ok(permit) = connections.clone().try_acquire_owned() else {
+                            warn!("closing TCP connection from {peer}: too many connections");
+                            continue;
+                        };
+                        let acceptor = acceptor.clone();
+                        let consumer = consumer.clone();
+                        let parser = parser.fork();
+                        let queue = queue.clone();
+                        let receiver = receiver.clone();
+                        tasks.spawn(
+                            async move {
+                                Self::connection_task(
+                                    stream,
+                                    acceptor,
+                                    max_line_length,
+                                    consumer,
+                                    parser,
+                                    queue,
+                                    receiver,
+                                )
+                                .await;
+                                drop(permit);
+                            }
+                            .instrument(info_span!("tcp_connection", %peer)),
+                        );
+                    }
+                    Err(e) => {
+                        consumer.error(
+                            false,
+                            anyhow!("error accepting TCP connection: {e}"),
+                            Some("tcp-accept"),
+                        );
+                        sleep(ACCEPT_RETRY_DELAY).await;
+                    }
+                },
+                Some(_) = tasks.join_next() => {}
+            }
+        }
+    }
+
+    /// Serves a client connection, after the TLS handshake if `acceptor` is
+    /// set.
+    async fn connection_task(
+        stream: TcpStream,
+        acceptor: Option<TlsAcceptor>,
+        max_line_length: usize,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        queue: Arc<InputQueue>,
+        receiver: Receiver<PipelineState>,
+    ) {
+        let result = match acceptor {
+            Some(acceptor) => match acceptor.accept(stream).await {
+                Ok(stream) => {
+                    info!("TCP client connected");
+                    read_lines(stream, max_line_length, parser, &queue, receiver).await
+                }
+                Err(e) => {
+                    debug!("TLS handshake failed: {e}");
+                    return;
+                }
+            },
+            None => {
+                info!("TCP client connected");
+                read_lines(stream, max_line_length, parser, &queue, receiver).await
+            }
+        };
+        if let Err(e) = result {
+            consumer.error(false, e, Some("tcp-receive"));
+        }
+        info!("TCP client disconnected");
+    }
+}
+
+/// Parses each line read from `stream` until it reaches end of file or the
+/// connector terminates.
+///
+/// Lines are only read while the pipeline is running.  While it is paused,
+/// TCP flow control makes clients wait.
+async fn read_lines<S>(
+    stream: S,
+    max_line_length: usize,
+    mut parser: Box<dyn Parser>,
+    queue: &InputQueue,
+    mut receiver: Receiver<PipelineState>,
+) -> AnyResult<()>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+    let mut line = Vec::new();
+    loop {
+        if receiver
+            .wait_for(|state| state == &PipelineState::Running)
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+
+        line.clear();
+        let n = (&mut reader)
+            .take(max_line_length as u64)
+            .read_until(b'\n', &mut line)
+            .await
+            .context("error receiving from TCP client")?;
+        if n == 0 {
+            return Ok(());
+        }
+        if !line.ends_with(b"\n") {
+            if n == max_line_length {
+                return Err(anyhow!(
+                    "TCP client sent a line longer than 'max_line_length' ({max_line_length} bytes); closing the connection"
+                ));
+            }
+
+            // The client closed the connection without terminating the last
+            // line.
+            line.push(b'\n');
+        }
+
+        // Use the time when we received the line as the ingestion timestamp.
+        queue.push(parser.parse(&line), Utc::now());
+    }
+}
+
+impl InputReader for TcpInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => self.queue.queue(),
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for TcpInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
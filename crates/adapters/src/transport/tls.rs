@@ -0,0 +1,183 @@
+//! TLS support shared by connectors that use rustls, either to connect to
+//! servers or to accept connections from clients.
+
+use crate::ensure_default_crypto_provider;
+use anyhow::{bail, Context, Result as AnyResult};
+use feldera_types::transport::tls::{TlsConfig, TlsServerConfig};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, ServerConfig, SignatureScheme};
+use std::fs;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Returns the PEM contents of a setting that can be specified either inline
+/// or as a path to a file.
+pub(crate) fn read_pem(
+    name: &str,
+    pem: &Option<String>,
+    path: &Option<String>,
+) -> AnyResult<Option<Vec<u8>>> {
+    match (pem, path) {
+        (Some(_), Some(_)) => {
+            bail!("'{name}_pem' and '{name}_path' are mutually exclusive; please specify only one")
+        }
+        (Some(pem), None) => Ok(Some(pem.as_bytes().to_vec())),
+        (None, Some(path)) => {
+            Ok(Some(fs::read(path).with_context(|| {
+                format!("error reading '{name}_path' file '{path}'")
+            })?))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+/// Builds a rustls client configuration from connector TLS settings.
+pub(crate) fn tls_client_config(tls: &TlsConfig) -> AnyResult<ClientConfig> {
+    ensure_default_crypto_provider();
+
+    let builder = ClientConfig::builder();
+    let builder = if tls.insecure_skip_verify {
+        warn!("TLS: server certificate verification is disabled ('insecure_skip_verify' is set)");
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification(
+                CryptoProvider::get_default()
+                    .expect("default crypto provider is installed")
+                    .clone(),
+            )))
+    } else {
+        let mut roots = RootCertStore::empty();
+        match read_pem("ca_cert", &tls.ca_cert_pem, &tls.ca_cert_path)? {
+            Some(pem) => {
+                for cert in CertificateDer::pem_slice_iter(&pem) {
+                    roots
+                        .add(cert.context("error parsing CA certificate")?)
+                        .context("invalid CA certificate")?;
+                }
+                if roots.is_empty() {
+                    bail!("no certificates found in the CA certificate PEM");
+                }
+            }
+            None => roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()),
+        }
+        builder.with_root_certificates(roots)
+    };
+
+    let client_cert = read_pem("client_cert", &tls.client_cert_pem, &tls.client_cert_path)?;
+    let client_key = read_pem("client_key", &tls.client_key_pem, &tls.client_key_path)?;
+    let config = match (client_cert, client_key) {
+        (Some(cert), Some(key)) => {
+            let cert_chain = CertificateDer::pem_slice_iter(&cert)
+                .collect::<Result<Vec<_>, _>>()
+                .context("error parsing client certificate")?;
+            let key = PrivateKeyDer::from_pem_slice(&key).context("error parsing client key")?;
+            builder
+                .with_client_auth_cert(cert_chain, key)
+                .context("invalid client certificate or key")?
+        }
+        (None, None) => builder.with_no_client_auth(),
+        (Some(_), None) => bail!("client certificate specified without a client key"),
+        (None, Some(_)) => bail!("client key specified without a client certificate"),
+    };
+
+    Ok(config)
+}
+
+/// Builds a rustls server configuration from connector TLS settings.
+pub(crate) fn tls_server_config(tls: &TlsServerConfig) -> AnyResult<ServerConfig> {
+    ensure_default_crypto_provider();
+
+    let cert = read_pem("cert", &tls.cert_pem, &tls.cert_path)?
+        .context("server certificate ('cert_pem' or 'cert_path') is required")?;
+    let key = read_pem("key", &tls.key_pem, &tls.key_path)?
+        .context("server private key ('key_pem' or 'key_path') is required")?;
+    let cert_chain = CertificateDer::pem_slice_iter(&cert)
+        .collect::<Result<Vec<_>, _>>()
+        .context("error parsing server certificate")?;
+    let key = PrivateKeyDer::from_pem_slice(&key).context("error parsing server key")?;
+
+    let builder = ServerConfig::builder();
+    let builder = match read_pem(
+        "client_ca_cert",
+        &tls.client_ca_cert_pem,
+        &tls.client_ca_cert_path,
+    )? {
+        Some(pem) => {
+            let mut roots = RootCertStore::empty();
+            for cert in CertificateDer::pem_slice_iter(&pem) {
+                roots
+                    .add(cert.context("error parsing client CA certificate")?)
+                    .context("invalid client CA certificate")?;
+            }
+            if roots.is_empty() {
+                bail!("no certificates found in the client CA certificate PEM");
+            }
+            builder.with_client_cert_verifier(
+                WebPkiClientVerifier::builder(Arc::new(roots))
+                    .build()
+                    .context("error configuring client certificate verification")?,
+            )
+        }
+        None => builder.with_no_client_auth(),
+    };
+    builder
+        .with_single_cert(cert_chain, key)
+        .context("invalid server certificate or key")
+}
+
+/// Certificate verifier that accepts any server certificate, used to
+/// implement `insecure_skip_verify`.
+///
+/// Handshake signatures are still checked so that the connection fails if the
+/// server does not own the certificate it presents.
+#[derive(Debug)]
+struct NoCertificateVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,10 @@
+//! Transport adapters for Unix domain sockets.
+
+mod input;
+mod output;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::UnixInputEndpoint;
+pub(crate) use output::UnixOutputEndpoint;
@@ -0,0 +1,247 @@
+use crate::format::StreamSplitter;
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::unix::UnixInputConfig;
+use std::fs;
+use std::os::unix::fs::FileTypeExt;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tokio::time::sleep;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+/// Default for [`UnixInputConfig::max_connections`].
+const DEFAULT_MAX_CONNECTIONS: usize = 100;
+
+/// Size of the buffer for reading from a connection.
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Delay before accepting connections again after an error, e.g., because
+/// the process ran out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+pub(crate) struct UnixInputEndpoint {
+    config: Arc<UnixInputConfig>,
+}
+
+impl UnixInputEndpoint {
+    pub(crate) fn new(config: UnixInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for UnixInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for UnixInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(UnixInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+/// Removes a socket at `path` that no process listens on anymore.
+///
+/// Binding a socket fails if the path exists, even if the process that
+/// created the socket is gone.
+fn remove_stale_socket(path: &str) {
+    let is_socket =
+        fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket());
+    if is_socket && std::os::unix::net::UnixStream::connect(path).is_err() {
+        info!("removing stale Unix domain socket '{path}'");
+        let _ = fs::remove_file(path);
+    }
+}
+
+struct UnixInputReader {
+    config: Arc<UnixInputConfig>,
+    sender: Sender<PipelineState>,
+    queue: Arc<InputQueue>,
+}
+
+impl UnixInputReader {
+    fn new(
+        config: Arc<UnixInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("unix_input", path = config.path);
+
+        // Fail early if the socket can't be created.
+        remove_stale_socket(&config.path);
+        let listener = {
+            let _runtime = TOKIO.enter();
+            UnixListener::bind(&config.path)
+                .with_context(|| format!("error creating Unix domain socket '{}'", config.path))?
+        };
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("unix-input-tokio-wrapper".to_string())
+            .spawn({
+                let config = config.clone();
+                let queue = queue.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        select! {
+                            _ = Self::accept_task(listener, config, consumer, parser, queue, receiver).instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("Unix domain socket input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn Unix domain socket input tokio wrapper thread");
+
+        Ok(Self {
+            config,
+            sender,
+            queue,
+        })
+    }
+
+    /// Accepts connections and spawns a task to serve each of them.
+    ///
+    /// Dropping this future closes all of the connections.
+    async fn accept_task(
+        listener: UnixListener,
+        config: Arc<UnixInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        queue: Arc<InputQueue>,
+        receiver: Receiver<PipelineState>,
+    ) {
+        let connections = Arc::new(Semaphore::new(
+            config.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS),
+        ));
+        let mut tasks = JoinSet::new();
+        loop {
+            select! {
+                result = listener.accept() => match result {
+                    Ok((stream, _address)) => {
+                        let Ok(permit) = connections.clone().try_acquire_owned() else {
+                            warn!("closing Unix domain socket connection: too many connections");
+                            continue;
+                        };
+                        let consumer = consumer.clone();
+                        let parser = parser.fork();
+                        let queue = queue.clone();
+                        let receiver = receiver.clone();
+                        tasks.spawn(
+                            async move {
+                                info!("Unix domain socket client connected");
+                                if let Err(e) = read_stream(stream, parser, &queue, receiver).await {
+                                    consumer.error(false, e, Some("unix-receive"));
+                                }
+                                info!("Unix domain socket client disconnected");
+                                drop(permit);
+                            }
+                            .in_current_span(),
+                        );
+                    }
+                    Err(e) => {
+                        consumer.error(
+                            false,
+                            anyhow!("error accepting Unix domain socket connection: {e}"),
+                            Some("unix-accept"),
+                        );
+                        sleep(ACCEPT_RETRY_DELAY).await;
+                    }
+                },
+                Some(_) = tasks.join_next() => {}
+            }
+        }
+    }
+}
+
+/// Parses the data read from `stream` until the client closes it, splitting
+/// it into chunks of whole records with the parser's splitter.
+///
+/// Data is only read while the pipeline is running.  While it is paused,
+/// clients that keep writing block once the socket buffer is full.
+async fn read_stream(
+    mut stream: UnixStream,
+    mut parser: Box<dyn Parser>,
+    queue: &InputQueue,
+    mut receiver: Receiver<PipelineState>,
+) -> AnyResult<()> {
+    let mut splitter = StreamSplitter::new(parser.splitter());
+    let mut buffer = vec![0; READ_BUFFER_SIZE];
+    loop {
+        if receiver
+            .wait_for(|state| state == &PipelineState::Running)
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+
+        let n = stream
+            .read(&mut buffer)
+            .await
+            .context("error receiving from Unix domain socket client")?;
+        let eoi = n == 0;
+        splitter.append(&buffer[..n]);
+
+        // Use the time when we received the data as the ingestion timestamp.
+        let now = Utc::now();
+        while let Some(chunk) = splitter.next(eoi) {
+            queue.push(parser.parse(chunk), now);
+        }
+        if eoi {
+            return Ok(());
+        }
+    }
+}
+
+impl InputReader for UnixInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => self.queue.queue(),
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for UnixInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+        let _ = fs::remove_file(&self.config.path);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{AsyncErrorCallback, OutputEndpoint};
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use feldera_types::transport::unix::UnixOutputConfig;
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::thread::sleep;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Default for [`UnixOutputConfig::max_reconnect_attempts`].
+const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+/// Delay before the first attempt to reconnect, which doubles after each
+/// failed attempt.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(100);
+
+/// Maximum delay between attempts to reconnect.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+pub(crate) struct UnixOutputEndpoint {
+    config: UnixOutputConfig,
+    stream: Option<UnixStream>,
+}
+
+impl UnixOutputEndpoint {
+    pub(crate) fn new(config: UnixOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config,
+            stream: None,
+        })
+    }
+
+    /// Connects to the socket, retrying with exponential backoff up to
+    /// `max_reconnect_attempts` times.
+    fn reconnect(&mut self) -> AnyResult<&mut UnixStream> {
+        self.stream = None;
+        let max_attempts = self
+            .config
+            .max_reconnect_attempts
+            .unwrap_or(DEFAULT_MAX_RECONNECT_ATTEMPTS);
+        let mut delay = INITIAL_RECONNECT_DELAY;
+        let mut attempt = 0;
+        let stream = loop {
+            match UnixStream::connect(&self.config.path) {
+                Ok(stream) => break stream,
+                Err(e) if attempt < max_attempts => {
+                    warn!(
+                        "error connecting to Unix domain socket '{}' (retrying in {delay:?}): {e}",
+                        self.config.path
+                    );
+                    sleep(delay);
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!(
+                            "error connecting to Unix domain socket '{}'",
+                            self.config.path
+                        )
+                    })
+                }
+            }
+        };
+        info!("connected to Unix domain socket '{}'", self.config.path);
+        Ok(self.stream.insert(stream))
+    }
+}
+
+impl OutputEndpoint for UnixOutputEndpoint {
+    fn connect(&mut self, _async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        self.reconnect()?;
+        Ok(())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    /// If writing fails, e.g., because the reader restarted, reconnects and
+    /// writes the whole buffer again.  The reader may therefore receive part
+    /// of the buffer twice.
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        if let Some(stream) = &mut self.stream {
+            match stream.write_all(buffer) {
+                Ok(()) => return Ok(()),
+                Err(e) => warn!(
+                    "error writing to Unix domain socket '{}' (reconnecting): {e}",
+                    self.config.path
+                ),
+            }
+        }
+        let path = self.config.path.clone();
+        self.reconnect()?
+            .write_all(buffer)
+            .with_context(|| format!("error writing to Unix domain socket '{path}'"))
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        bail!(
+            "Unix domain socket output transport does not support key-value pairs. \
+This output endpoint was configured with a data format that produces outputs as key-value pairs; \
+however the Unix domain socket transport does not support this representation."
+        );
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,169 @@
+use super::UnixOutputEndpoint;
+use crate::test::{mock_input_pipeline, wait, DEFAULT_TIMEOUT_MS};
+use crate::OutputEndpoint;
+use feldera_types::deserialize_without_context;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::unix::{UnixInputConfig, UnixOutputConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::{Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use tempfile::TempDir;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+struct TestStruct {
+    s: String,
+    i: i64,
+}
+
+deserialize_without_context!(TestStruct);
+
+impl TestStruct {
+    fn new(s: &str, i: i64) -> Self {
+        Self {
+            s: s.to_string(),
+            i,
+        }
+    }
+}
+
+#[test]
+fn test_unix_input() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("input.sock");
+
+    // A stale socket, with no process listening on it, is replaced.
+    drop(UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "unix_input",
+            "config": {
+                "path": path,
+            }
+        },
+        "format": {
+            "name": "csv",
+            "config": {}
+        }
+    }))
+    .unwrap();
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    // Records can span writes, and the last record doesn't need a
+    // terminator.
+    let mut client1 = UnixStream::connect(&path).unwrap();
+    let mut client2 = UnixStream::connect(&path).unwrap();
+    client1.write_all(b"foo,1\nba").unwrap();
+    client2.write_all(b"baz,3").unwrap();
+    client1.write_all(b"r,2\n").unwrap();
+    drop(client1);
+    drop(client2);
+
+    wait(
+        || {
+            endpoint.queue(false);
+            zset.state().flushed.len() >= 3
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+    let mut records = zset
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().clone())
+        .collect::<Vec<_>>();
+    records.sort_by_key(|record| record.i);
+    assert_eq!(
+        records,
+        vec![
+            TestStruct::new("foo", 1),
+            TestStruct::new("bar", 2),
+            TestStruct::new("baz", 3),
+        ]
+    );
+
+    // The socket is removed when the connector stops.
+    drop(endpoint);
+    assert!(!path.exists());
+}
+
+/// Reads everything that one client writes to `listener`.
+fn read_connection(listener: &UnixListener) -> Vec<u8> {
+    let mut data = Vec::new();
+    listener.accept().unwrap().0.read_to_end(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_unix_output() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("output.sock");
+    let config: UnixOutputConfig = serde_json::from_value(json!({
+        "path": path,
+        "max_reconnect_attempts": 1
+    }))
+    .unwrap();
+
+    // Nothing listens on the socket.
+    let mut endpoint = UnixOutputEndpoint::new(config).unwrap();
+    let error = endpoint.connect(Box::new(|_, _, _| ())).unwrap_err();
+    assert!(format!("{error:#}").contains("error connecting to Unix domain socket"));
+
+    let listener = UnixListener::bind(&path).unwrap();
+    endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+    endpoint.batch_start(1).unwrap();
+    endpoint.push_buffer(b"foo,1\n").unwrap();
+    endpoint.push_buffer(b"bar,2\n").unwrap();
+    endpoint.batch_end().unwrap();
+    assert!(endpoint
+        .push_key(Some(b"key"), Some(b"baz,3\n"), &[])
+        .is_err());
+    drop(endpoint);
+    assert_eq!(read_connection(&listener), b"foo,1\nbar,2\n");
+}
+
+#[test]
+fn test_unix_config_validation() {
+    let input = |extra: serde_json::Value| -> UnixInputConfig {
+        let mut config = json!({ "path": "/tmp/input.sock" });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let output = |extra: serde_json::Value| -> UnixOutputConfig {
+        let mut config = json!({ "path": "/tmp/output.sock" });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+
+    input(json!({})).validate().unwrap();
+    input(json!({ "max_connections": 1 })).validate().unwrap();
+    output(json!({})).validate().unwrap();
+    output(json!({ "max_reconnect_attempts": 0 }))
+        .validate()
+        .unwrap();
+
+    assert!(input(json!({ "path": "" }))
+        .validate()
+        .unwrap_err()
+        .contains("'path'"));
+    assert!(input(json!({ "max_connections": 0 }))
+        .validate()
+        .unwrap_err()
+        .contains("'max_connections'"));
+    assert!(output(json!({ "path": "" }))
+        .validate()
+        .unwrap_err()
+        .contains("'path'"));
+}
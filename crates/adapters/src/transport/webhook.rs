@@ -0,0 +1,250 @@
+//! Output transport that sends encoded buffers to a webhook in HTTP `POST`
+//! requests.
+
+use crate::{AsyncErrorCallback, OutputEndpoint};
+use anyhow::{anyhow, bail, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::transport::webhook::WebhookOutputConfig;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, Response, StatusCode};
+use sha2::Sha256;
+use std::cmp::min;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::span::EnteredSpan;
+use tracing::{debug, info_span};
+use url::Url;
+
+#[cfg(test)]
+mod test;
+
+/// Default for [`WebhookOutputConfig::content_type`].
+const DEFAULT_CONTENT_TYPE: &str = "application/json";
+
+/// Default for [`WebhookOutputConfig::timeout_secs`].
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Default for [`WebhookOutputConfig::max_retries`].
+const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Default for [`WebhookOutputConfig::initial_backoff_ms`].
+const DEFAULT_INITIAL_BACKOFF_MS: u64 = 100;
+
+/// Default for [`WebhookOutputConfig::max_backoff_ms`].
+const DEFAULT_MAX_BACKOFF_MS: u64 = 10_000;
+
+/// Default for [`WebhookOutputConfig::signature_header`].
+const DEFAULT_SIGNATURE_HEADER: &str = "X-Feldera-Signature";
+
+/// Header that carries the time at which a signed request was sent.
+pub(crate) const TIMESTAMP_HEADER: &str = "X-Feldera-Timestamp";
+
+/// The maximum number of bytes of an error response to include in an error
+/// message.
+const MAX_ERROR_BODY: usize = 1024;
+
+fn span(config: &WebhookOutputConfig) -> EnteredSpan {
+    info_span!("webhook_output", ft = false, url = config.url.clone()).entered()
+}
+
+/// Returns the signature of a request with `body` sent at `timestamp`.
+pub(crate) fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Sends output to a webhook.
+pub(crate) struct WebhookOutputEndpoint {
+    config: WebhookOutputConfig,
+    url: Url,
+    signature_header: HeaderName,
+    client: Option<Client>,
+}
+
+impl WebhookOutputEndpoint {
+    pub(crate) fn new(config: WebhookOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+        let url =
+            Url::parse(&config.url).map_err(|e| anyhow!("invalid 'url' '{}': {e}", config.url))?;
+        let signature_header = config
+            .signature_header
+            .as_deref()
+            .unwrap_or(DEFAULT_SIGNATURE_HEADER);
+        let signature_header = HeaderName::from_bytes(signature_header.as_bytes())
+            .map_err(|e| anyhow!("invalid 'signature_header' '{signature_header}': {e}"))?;
+
+        Ok(Self {
+            config,
+            url,
+            signature_header,
+            client: None,
+        })
+    }
+
+    /// Sends a single request with `body`.
+    ///
+    /// Returns an error if the request should be retried, with the delay
+    /// requested by the server, if any, or the response if it shouldn't.
+    async fn send(
+        &self,
+        client: &Client,
+        body: &[u8],
+    ) -> Result<Response, (anyhow::Error, Option<Duration>)> {
+        let mut request = client.post(self.url.clone()).body(body.to_vec());
+        if let Some(secret) = &self.config.hmac_secret {
+            let timestamp = Utc::now().timestamp();
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(&self.signature_header, sign(secret, timestamp, body));
+        }
+
+        let response = request.send().await.map_err(|e| {
+            (
+                anyhow!("error sending request to '{}': {e}", self.url),
+                None,
+            )
+        })?;
+        let status = response.status();
+        if status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status.is_server_error()
+        {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+                .map(Duration::from_secs);
+            let body = response.text().await.unwrap_or_default();
+            return Err((
+                anyhow!(
+                    "request to '{}' failed with status {status}: {}",
+                    self.url,
+                    truncate(&body)
+                ),
+                retry_after,
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Sends `body` to the webhook, retrying with exponential backoff.
+    fn post(&self, body: &[u8]) -> AnyResult<()> {
+        let _guard = span(&self.config);
+        let client = self
+            .client
+            .as_ref()
+            .ok_or_else(|| anyhow!("webhook: trying to write before connecting: unreachable"))?;
+        let max_retries = self.config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let max_backoff =
+            Duration::from_millis(self.config.max_backoff_ms.unwrap_or(DEFAULT_MAX_BACKOFF_MS));
+        TOKIO.block_on(async {
+            let mut backoff = Duration::from_millis(
+                self.config
+                    .initial_backoff_ms
+                    .unwrap_or(DEFAULT_INITIAL_BACKOFF_MS),
+            );
+            let mut retries = 0;
+            loop {
+                match self.send(client, body).await {
+                    Ok(response) => {
+                        let status = response.status();
+                        if status.is_success() {
+                            return Ok(());
+                        }
+                        let body = response.text().await.unwrap_or_default();
+                        bail!(
+                            "request to '{}' failed with status {status}: {}",
+                            self.url,
+                            truncate(&body)
+                        );
+                    }
+                    Err((error, _)) if retries >= max_retries => {
+                        bail!("{error} (after {max_retries} retries)");
+                    }
+                    Err((error, retry_after)) => {
+                        let delay = retry_after.map_or(backoff, |delay| min(delay, max_backoff));
+                        debug!("{error}; retrying in {delay:?}");
+                        sleep(delay).await;
+                        backoff = min(backoff * 2, max_backoff);
+                        retries += 1;
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Truncates the body of an error response for inclusion in an error
+/// message.
+fn truncate(body: &str) -> &str {
+    match body.char_indices().nth(MAX_ERROR_BODY) {
+        Some((index, _)) => &body[..index],
+        None => body,
+    }
+}
+
+impl OutputEndpoint for WebhookOutputEndpoint {
+    fn connect(&mut self, _async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_str(
+                self.config
+                    .content_type
+                    .as_deref()
+                    .unwrap_or(DEFAULT_CONTENT_TYPE),
+            )
+            .map_err(|e| anyhow!("invalid 'content_type': {e}"))?,
+        );
+        for (name, value) in &self.config.headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| anyhow!("invalid HTTP header name '{name}': {e}"))?;
+            let mut header_value = HeaderValue::from_str(value)
+                .map_err(|e| anyhow!("invalid value for HTTP header '{name}': {e}"))?;
+            header_value.set_sensitive(true);
+            headers.insert(header_name, header_value);
+        }
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(
+                self.config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            ))
+            .build()
+            .map_err(|e| anyhow!("error creating HTTP client: {e}"))?;
+        self.client = Some(client);
+        Ok(())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        self.post(buffer)
+    }
+
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        _val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        bail!(
+            "webhook output transport does not support key-value pairs. \
+This output endpoint was configured with a data format that produces outputs as key-value pairs; \
+however the webhook transport does not support this representation."
+        );
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,165 @@
+use super::{sign, WebhookOutputEndpoint, TIMESTAMP_HEADER};
+use crate::OutputEndpoint;
+use actix::System;
+use actix_web::http::StatusCode;
+use actix_web::web::{self, Bytes};
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer};
+use feldera_types::transport::webhook::WebhookOutputConfig;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A request received by the test server.
+#[derive(Debug)]
+struct Request {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Default)]
+struct ServerState {
+    /// Statuses to respond with, in order.  Once empty, the server responds
+    /// with 200.
+    statuses: VecDeque<u16>,
+    requests: Vec<Request>,
+}
+
+/// Starts an HTTP server that records the requests it receives and responds
+/// with `statuses`, and returns its address.
+fn start_server(statuses: &[u16]) -> (SocketAddr, Arc<Mutex<ServerState>>) {
+    let state = Arc::new(Mutex::new(ServerState {
+        statuses: statuses.iter().copied().collect(),
+        requests: Vec::new(),
+    }));
+    let (sender, receiver) = channel();
+    thread::Builder::new()
+        .name("webhook-output-test".to_string())
+        .spawn({
+            let state = state.clone();
+            move || {
+                System::new().block_on(async {
+                    let server = HttpServer::new(move || {
+                        App::new().app_data(web::Data::new(state.clone())).route(
+                            "/hook",
+                            web::post().to(
+                                |request: HttpRequest,
+                                 body: Bytes,
+                                 state: web::Data<Arc<Mutex<ServerState>>>| async move {
+                                    let mut state = state.lock().unwrap();
+                                    state.requests.push(Request {
+                                        headers: request
+                                            .headers()
+                                            .iter()
+                                            .map(|(name, value)| {
+                                                (
+                                                    name.to_string(),
+                                                    value.to_str().unwrap().to_string(),
+                                                )
+                                            })
+                                            .collect(),
+                                        body: body.to_vec(),
+                                    });
+                                    let status = state.statuses.pop_front().unwrap_or(200);
+                                    HttpResponse::build(StatusCode::from_u16(status).unwrap())
+                                        .finish()
+                                },
+                            ),
+                        )
+                    })
+                    .workers(1)
+                    .bind(("127.0.0.1", 0))
+                    .unwrap();
+                    sender.send(server.addrs()[0]).unwrap();
+                    server.run().await.unwrap();
+                });
+            }
+        })
+        .expect("failed to spawn test thread");
+    (receiver.recv().unwrap(), state)
+}
+
+fn connected_endpoint(config: serde_json::Value) -> WebhookOutputEndpoint {
+    let config: WebhookOutputConfig = serde_json::from_value(config).unwrap();
+    let mut endpoint = WebhookOutputEndpoint::new(config).unwrap();
+    endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+    endpoint
+}
+
+#[test]
+fn test_sign() {
+    // Reference value computed with
+    // `printf '1700000000.hello' | openssl dgst -sha256 -hmac secret`.
+    assert_eq!(
+        sign("secret", 1700000000, b"hello"),
+        "sha256=47b1df0ab12338b2685470b0d2b37033add7c3b2bc8172f313e77413f1bb78c8"
+    );
+}
+
+#[test]
+fn test_webhook_output() {
+    let (addr, state) = start_server(&[503, 429]);
+    let mut endpoint = connected_endpoint(json!({
+        "url": format!("http://{addr}/hook"),
+        "headers": {"Authorization": "Bearer token"},
+        "content_type": "text/csv",
+        "initial_backoff_ms": 10,
+        "hmac_secret": "secret"
+    }));
+    endpoint.batch_start(1).unwrap();
+    endpoint.push_buffer(b"foo,1\nbar,2\n").unwrap();
+    endpoint.batch_end().unwrap();
+
+    // The first two attempts fail with retryable errors.
+    let state = state.lock().unwrap();
+    assert_eq!(state.requests.len(), 3);
+    for request in &state.requests {
+        assert_eq!(request.body, b"foo,1\nbar,2\n");
+        assert_eq!(request.header("authorization"), Some("Bearer token"));
+        assert_eq!(request.header("content-type"), Some("text/csv"));
+
+        let timestamp: i64 = request.header(TIMESTAMP_HEADER).unwrap().parse().unwrap();
+        assert_eq!(
+            request.header("x-feldera-signature"),
+            Some(sign("secret", timestamp, &request.body).as_str())
+        );
+    }
+}
+
+#[test]
+fn test_webhook_output_errors() {
+    // Client errors are not retried.
+    let (addr, state) = start_server(&[400]);
+    let mut endpoint = connected_endpoint(json!({
+        "url": format!("http://{addr}/hook"),
+    }));
+    let error = endpoint.push_buffer(b"{}").unwrap_err();
+    assert!(format!("{error:#}").contains("400"));
+    assert_eq!(state.lock().unwrap().requests.len(), 1);
+    assert_eq!(
+        state.lock().unwrap().requests[0].header(TIMESTAMP_HEADER),
+        None
+    );
+
+    // Retryable errors are reported once retries are exhausted.
+    let (addr, state) = start_server(&[500, 500, 500]);
+    let mut endpoint = connected_endpoint(json!({
+        "url": format!("http://{addr}/hook"),
+        "max_retries": 2,
+        "initial_backoff_ms": 10
+    }));
+    let error = endpoint.push_buffer(b"{}").unwrap_err();
+    assert!(format!("{error:#}").contains("after 2 retries"));
+    assert_eq!(state.lock().unwrap().requests.len(), 3);
+}
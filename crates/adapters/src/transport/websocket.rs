@@ -0,0 +1,10 @@
+//! Transport adapters for WebSocket.
+
+mod input;
+mod output;
+
+#[cfg(test)]
+mod test;
+
+pub(crate) use input::WebsocketInputEndpoint;
+pub(crate) use output::WebsocketOutputEndpoint;
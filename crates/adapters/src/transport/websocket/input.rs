@@ -0,0 +1,304 @@
+use crate::transport::{
+    InputEndpoint, InputQueue, InputReaderCommand, NonFtInputReaderCommand, TransportInputEndpoint,
+};
+use crate::{InputConsumer, InputReader, Parser, PipelineState};
+use anyhow::{anyhow, Context, Result as AnyResult};
+use chrono::Utc;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::config::FtModel;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::websocket::WebsocketInputConfig;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::select;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::sleep;
+use tokio_tungstenite::accept_hdr_async_with_config;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::{header, StatusCode};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, info_span, Instrument};
+
+/// Default for [`WebsocketInputConfig::bind_address`].
+const DEFAULT_BIND_ADDRESS: &str = "0.0.0.0";
+
+/// Default for [`WebsocketInputConfig::path`].
+const DEFAULT_PATH: &str = "/";
+
+/// Default for [`WebsocketInputConfig::max_connections`].
+const DEFAULT_MAX_CONNECTIONS: usize = 100;
+
+/// Default for [`WebsocketInputConfig::max_message_size`].
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Delay before accepting connections again after an error, e.g., because
+/// the process ran out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+pub(crate) struct WebsocketInputEndpoint {
+    config: Arc<WebsocketInputConfig>,
+}
+
+impl WebsocketInputEndpoint {
+    pub(crate) fn new(config: WebsocketInputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        Ok(Self {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl InputEndpoint for WebsocketInputEndpoint {
+    fn fault_tolerance(&self) -> Option<FtModel> {
+        None
+    }
+}
+
+impl TransportInputEndpoint for WebsocketInputEndpoint {
+    fn open(
+        &self,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        _schema: Relation,
+        _resume_info: Option<serde_json::Value>,
+    ) -> AnyResult<Box<dyn InputReader>> {
+        Ok(Box::new(WebsocketInputReader::new(
+            self.config.clone(),
+            consumer,
+            parser,
+        )?))
+    }
+}
+
+/// Builds a response that rejects a connection request.
+fn error_response(status: StatusCode, message: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(message.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+/// Checks that a client presented `auth_token`, either as a bearer token or
+/// in the `token` query parameter.
+fn is_authorized(request: &Request, auth_token: &str) -> bool {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if bearer == Some(auth_token) {
+        return true;
+    }
+
+    request.uri().query().is_some_and(|query| {
+        form_urlencoded::parse(query.as_bytes())
+            .any(|(name, value)| name == "token" && value == auth_token)
+    })
+}
+
+struct WebsocketInputReader {
+    sender: Sender<PipelineState>,
+    queue: Arc<InputQueue>,
+}
+
+impl WebsocketInputReader {
+    fn new(
+        config: Arc<WebsocketInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+    ) -> AnyResult<Self> {
+        let span = info_span!("websocket_input", port = config.port);
+        let bind_address = config
+            .bind_address
+            .as_deref()
+            .unwrap_or(DEFAULT_BIND_ADDRESS);
+
+        // Fail early if the port is not available.
+        let listener = TOKIO
+            .block_on(TcpListener::bind((bind_address, config.port)))
+            .with_context(|| {
+                format!(
+                    "error listening for WebSocket connections on {bind_address}:{}",
+                    config.port
+                )
+            })?;
+
+        let (sender, receiver) = channel(PipelineState::Paused);
+        let queue = Arc::new(InputQueue::new(consumer.clone()));
+        thread::Builder::new()
+            .name("websocket-input-tokio-wrapper".to_string())
+            .spawn({
+                let queue = queue.clone();
+                move || {
+                    TOKIO.block_on(async {
+                        let mut receiver_clone = receiver.clone();
+                        select! {
+                            _ = Self::accept_task(listener, config, consumer, parser, queue, receiver).instrument(span) => {}
+                            _ = receiver_clone.wait_for(|state| state == &PipelineState::Terminated) => {
+                                debug!("WebSocket input: received termination command; worker task canceled");
+                            }
+                        }
+                    })
+                }
+            })
+            .expect("failed to spawn WebSocket input tokio wrapper thread");
+
+        Ok(Self { sender, queue })
+    }
+
+    /// Accepts connections and spawns a task to serve each of them.
+    ///
+    /// Dropping this future closes all of the connections.
+    async fn accept_task(
+        listener: TcpListener,
+        config: Arc<WebsocketInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        parser: Box<dyn Parser>,
+        queue: Arc<InputQueue>,
+        receiver: Receiver<PipelineState>,
+    ) {
+        let connections = Arc::new(Semaphore::new(
+            config.max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS),
+        ));
+        let mut tasks = JoinSet::new();
+        loop {
+            select! {
+                result = listener.accept() => match result {
+                    Ok((stream, peer)) => {
+                        let permit = connections.clone().try_acquire_owned().ok();
+                        tasks.spawn(
+                            Self::connection_task(
+                                stream,
+                                permit,
+                                config.clone(),
+                                consumer.clone(),
+                                parser.fork(),
+                                queue.clone(),
+                                receiver.clone(),
+                            )
+                            .instrument(info_span!("websocket_connection", %peer)),
+                        );
+                    }
+                    Err(e) => {
+                        consumer.error(
+                            false,
+                            anyhow!("error accepting WebSocket connection: {e}"),
+                            Some("websocket-accept"),
+                        );
+                        sleep(ACCEPT_RETRY_DELAY).await;
+                    }
+                },
+                Some(_) = tasks.join_next() => {}
+            }
+        }
+    }
+
+    /// Serves a client connection.  `permit` is `None` if the connector
+    /// already serves the maximum number of connections, in which case the
+    /// connection request is rejected.
+    async fn connection_task(
+        stream: TcpStream,
+        permit: Option<OwnedSemaphorePermit>,
+        config: Arc<WebsocketInputConfig>,
+        consumer: Box<dyn InputConsumer>,
+        mut parser: Box<dyn Parser>,
+        queue: Arc<InputQueue>,
+        mut receiver: Receiver<PipelineState>,
+    ) {
+        let path = config.path.as_deref().unwrap_or(DEFAULT_PATH);
+        let check_request = |request: &Request, response: Response| {
+            if request.uri().path() != path {
+                return Err(error_response(
+                    StatusCode::NOT_FOUND,
+                    &format!("no WebSocket endpoint at '{}'", request.uri().path()),
+                ));
+            }
+            if let Some(auth_token) = &config.auth_token {
+                if !is_authorized(request, auth_token) {
+                    return Err(error_response(
+                        StatusCode::UNAUTHORIZED,
+                        "missing or invalid authentication token",
+                    ));
+                }
+            }
+            if permit.is_none() {
+                return Err(error_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "too many WebSocket connections",
+                ));
+            }
+            Ok(response)
+        };
+        let websocket_config = WebSocketConfig::default().max_message_size(Some(
+            config.max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE),
+        ));
+        let mut websocket =
+            match accept_hdr_async_with_config(stream, check_request, Some(websocket_config)).await
+            {
+                Ok(websocket) => websocket,
+                Err(e) => {
+                    debug!("rejected WebSocket connection: {e}");
+                    return;
+                }
+            };
+        info!("WebSocket client connected");
+
+        loop {
+            // Messages are only read while the pipeline is running.  While it
+            // is paused, TCP flow control makes clients wait.
+            if receiver
+                .wait_for(|state| state == &PipelineState::Running)
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            // Use the time when we received the message as the ingestion timestamp.
+            match websocket.next().await {
+                Some(Ok(Message::Text(text))) => {
+                    queue.push(parser.parse(text.as_bytes()), Utc::now())
+                }
+                Some(Ok(Message::Binary(data))) => queue.push(parser.parse(&data), Utc::now()),
+                Some(Ok(Message::Close(_))) | None => break,
+
+                // The library answers pings.
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    consumer.error(
+                        false,
+                        anyhow!("error receiving from WebSocket client: {e}"),
+                        Some("websocket-receive"),
+                    );
+                    break;
+                }
+            }
+        }
+        info!("WebSocket client disconnected");
+    }
+}
+
+impl InputReader for WebsocketInputReader {
+    fn request(&self, command: InputReaderCommand) {
+        match command.as_nonft().unwrap() {
+            NonFtInputReaderCommand::Queue => self.queue.queue(),
+            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.queue.is_empty() && self.sender.is_closed()
+    }
+}
+
+impl Drop for WebsocketInputReader {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
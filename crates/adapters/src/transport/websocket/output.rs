@@ -0,0 +1,266 @@
+use crate::transport::tls::tls_client_config;
+use crate::{AsyncErrorCallback, OutputEndpoint};
+use anyhow::{anyhow, bail, Context, Result as AnyResult};
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::transport::websocket::WebsocketOutputConfig;
+use futures::{FutureExt, SinkExt, StreamExt};
+use std::cmp::min;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout_at, Instant};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{
+    connect_async_tls_with_config, Connector, MaybeTlsStream, WebSocketStream,
+};
+use tracing::span::EnteredSpan;
+use tracing::{info_span, warn};
+
+/// Default for [`WebsocketOutputConfig::ack_timeout_ms`].
+const DEFAULT_ACK_TIMEOUT_MS: u64 = 30_000;
+
+/// Default for [`WebsocketOutputConfig::max_reconnect_attempts`].
+const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+/// Default for [`WebsocketOutputConfig::max_message_size`].
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Delay before the first attempt to reconnect.  The delay doubles with each
+/// attempt, up to [`MAX_BACKOFF`].
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Maximum delay between attempts to reconnect.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+type Websocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+fn span(config: &WebsocketOutputConfig) -> EnteredSpan {
+    info_span!("websocket_output", ft = false, url = config.url.clone()).entered()
+}
+
+/// Sends output to a WebSocket server.
+pub(crate) struct WebsocketOutputEndpoint {
+    config: WebsocketOutputConfig,
+
+    /// TLS configuration for `wss://` URLs.
+    connector: Option<Connector>,
+
+    /// The connection to the server, or `None` if the connection failed and
+    /// the connector has not reconnected yet.
+    websocket: Option<Websocket>,
+
+    /// Messages that the server has not acknowledged yet, oldest first, if
+    /// `config.acks` is set.
+    unacked: VecDeque<Message>,
+}
+
+impl WebsocketOutputEndpoint {
+    pub(crate) fn new(config: WebsocketOutputConfig) -> AnyResult<Self> {
+        config.validate().map_err(|e| anyhow!(e))?;
+
+        let connector = if config.is_tls() {
+            let tls = config.tls.clone().unwrap_or_default();
+            Some(Connector::Rustls(Arc::new(tls_client_config(&tls)?)))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            config,
+            connector,
+            websocket: None,
+            unacked: VecDeque::new(),
+        })
+    }
+
+    /// Opens a connection to the server and sends the messages that it has
+    /// not acknowledged.
+    async fn connect_once(&self) -> AnyResult<Websocket> {
+        let mut request = self
+            .config
+            .url
+            .as_str()
+            .into_client_request()
+            .with_context(|| format!("invalid WebSocket URL '{}'", self.config.url))?;
+        for (name, value) in &self.config.headers {
+            request.headers_mut().insert(
+                HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("invalid HTTP header name '{name}'"))?,
+                HeaderValue::from_str(value)
+                    .with_context(|| format!("invalid value for HTTP header '{name}'"))?,
+            );
+        }
+        let (mut websocket, _response) =
+            connect_async_tls_with_config(request, None, true, self.connector.clone())
+                .await
+                .with_context(|| {
+                    format!("error connecting to WebSocket server '{}'", self.config.url)
+                })?;
+
+        for message in &self.unacked {
+            websocket.feed(message.clone()).await?;
+        }
+        websocket.flush().await?;
+        Ok(websocket)
+    }
+
+    /// Connects to the server, retrying with exponential backoff.
+    async fn reconnect(&mut self) -> AnyResult<()> {
+        let max_attempts = self
+            .config
+            .max_reconnect_attempts
+            .unwrap_or(DEFAULT_MAX_RECONNECT_ATTEMPTS);
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempts = 0;
+        loop {
+            match self.connect_once().await {
+                Ok(websocket) => {
+                    self.websocket = Some(websocket);
+                    return Ok(());
+                }
+                Err(e) if attempts >= max_attempts => {
+                    return Err(e.context(format!("giving up after {attempts} reconnect attempts")))
+                }
+                Err(e) => {
+                    warn!("{e:#}; reconnecting in {backoff:?}");
+                    sleep(backoff).await;
+                    backoff = min(backoff * 2, MAX_BACKOFF);
+                    attempts += 1;
+                }
+            }
+        }
+    }
+
+    /// Drops the connection after an error.  The next message reconnects.
+    fn drop_connection(&mut self, error: impl std::fmt::Display) {
+        warn!(
+            "connection to WebSocket server '{}' failed ({error}); reconnecting",
+            self.config.url
+        );
+        self.websocket = None;
+    }
+
+    async fn send(&mut self, message: Message) -> AnyResult<()> {
+        if self.config.acks {
+            self.unacked.push_back(message.clone());
+        }
+        loop {
+            if let Some(websocket) = &mut self.websocket {
+                match websocket.send(message.clone()).await {
+                    Ok(()) => return Ok(()),
+                    Err(e) => self.drop_connection(e),
+                }
+            }
+            self.reconnect().await?;
+            if self.config.acks {
+                // Reconnecting sent `message` along with the other
+                // unacknowledged messages.
+                return Ok(());
+            }
+        }
+    }
+
+    /// Waits until the server has acknowledged all of the messages sent so
+    /// far.
+    async fn wait_for_acks(&mut self) -> AnyResult<()> {
+        let ack_timeout =
+            Duration::from_millis(self.config.ack_timeout_ms.unwrap_or(DEFAULT_ACK_TIMEOUT_MS));
+        let deadline = Instant::now() + ack_timeout;
+        while !self.unacked.is_empty() {
+            let Some(websocket) = &mut self.websocket else {
+                self.reconnect().await?;
+                continue;
+            };
+            match timeout_at(deadline, websocket.next()).await {
+                Ok(Some(Ok(Message::Text(_) | Message::Binary(_)))) => {
+                    self.unacked.pop_front();
+                }
+                Ok(Some(Ok(Message::Close(_)))) | Ok(None) => {
+                    self.drop_connection("connection closed by the server")
+                }
+                Ok(Some(Err(e))) => self.drop_connection(e),
+
+                // The library answers pings.
+                Ok(Some(Ok(_))) => {}
+                Err(_) => {
+                    // The messages are sent again after reconnecting.
+                    let unacked = self.unacked.len();
+                    self.websocket = None;
+                    bail!(
+                        "WebSocket server '{}' did not acknowledge {unacked} messages within {ack_timeout:?}",
+                        self.config.url
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes the messages that the server has sent, without waiting,
+    /// which answers pings and detects closed connections.
+    fn poll_incoming(&mut self) {
+        let _runtime = TOKIO.enter();
+        while let Some(websocket) = &mut self.websocket {
+            match websocket.next().now_or_never() {
+                None => break,
+                Some(Some(Ok(Message::Close(_)))) | Some(None) => {
+                    self.drop_connection("connection closed by the server")
+                }
+                Some(Some(Err(e))) => self.drop_connection(e),
+                Some(Some(Ok(_))) => {}
+            }
+        }
+    }
+}
+
+impl OutputEndpoint for WebsocketOutputEndpoint {
+    fn connect(&mut self, _async_error_callback: AsyncErrorCallback) -> AnyResult<()> {
+        let _guard = span(&self.config);
+        TOKIO.block_on(self.reconnect())
+    }
+
+    fn max_buffer_size_bytes(&self) -> usize {
+        self.config
+            .max_message_size
+            .unwrap_or(DEFAULT_MAX_MESSAGE_SIZE)
+    }
+
+    /// Output that is valid UTF-8, such as JSON or CSV, is sent as text
+    /// messages, and other output, such as Avro, as binary messages.
+    fn push_buffer(&mut self, buffer: &[u8]) -> AnyResult<()> {
+        let message = match String::from_utf8(buffer.to_vec()) {
+            Ok(text) => Message::text(text),
+            Err(e) => Message::binary(e.into_bytes()),
+        };
+        let _guard = span(&self.config);
+        TOKIO.block_on(self.send(message))
+    }
+
+    /// WebSocket messages have no keys or headers, so only the value is sent.
+    /// A deletion, which has no value, is sent as an empty message.
+    fn push_key(
+        &mut self,
+        _key: Option<&[u8]>,
+        val: Option<&[u8]>,
+        _headers: &[(&str, Option<&[u8]>)],
+    ) -> AnyResult<()> {
+        self.push_buffer(val.unwrap_or_default())
+    }
+
+    fn batch_end(&mut self) -> AnyResult<()> {
+        let _guard = span(&self.config);
+        if self.config.acks {
+            TOKIO.block_on(self.wait_for_acks())
+        } else {
+            self.poll_incoming();
+            Ok(())
+        }
+    }
+
+    fn is_fault_tolerant(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,297 @@
+use super::WebsocketOutputEndpoint;
+use crate::test::{mock_input_pipeline, wait, DEFAULT_TIMEOUT_MS};
+use crate::OutputEndpoint;
+use dbsp::circuit::tokio::TOKIO;
+use feldera_types::deserialize_without_context;
+use feldera_types::program_schema::Relation;
+use feldera_types::transport::websocket::{WebsocketInputConfig, WebsocketOutputConfig};
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serial_test::serial;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header, StatusCode};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{accept_async, connect_async};
+
+/// Port that the connector listens on in tests.
+const PORT: u16 = 18765;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
+struct TestStruct {
+    s: String,
+    i: i64,
+}
+
+deserialize_without_context!(TestStruct);
+
+impl TestStruct {
+    fn new(s: &str, i: i64) -> Self {
+        Self {
+            s: s.to_string(),
+            i,
+        }
+    }
+}
+
+/// Returns the HTTP status with which the connector rejects a connection
+/// request to `url` with `token` as the bearer token.
+async fn rejection_status(url: &str, token: Option<&str>) -> StatusCode {
+    let mut request = url.into_client_request().unwrap();
+    if let Some(token) = token {
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            format!("Bearer {token}").parse().unwrap(),
+        );
+    }
+    match connect_async(request).await {
+        Err(WsError::Http(response)) => response.status(),
+        result => panic!("expected the connection to be rejected, got {result:?}"),
+    }
+}
+
+#[test]
+#[serial]
+fn test_websocket_input() {
+    let config = serde_json::from_value(json!({
+        "stream": "test_input",
+        "transport": {
+            "name": "websocket_input",
+            "config": {
+                "bind_address": "127.0.0.1",
+                "port": PORT,
+                "path": "/ingest",
+                "auth_token": "secret"
+            }
+        },
+        "format": {
+            "name": "csv",
+            "config": {}
+        }
+    }))
+    .unwrap();
+
+    let (endpoint, _consumer, _parser, zset) =
+        mock_input_pipeline::<TestStruct, TestStruct>(config, Relation::empty()).unwrap();
+    endpoint.extend();
+
+    let url = format!("ws://127.0.0.1:{PORT}/ingest");
+    TOKIO.block_on(async {
+        // A wrong path, or a missing or wrong token, are rejected.
+        assert_eq!(
+            rejection_status(&format!("ws://127.0.0.1:{PORT}/other"), Some("secret")).await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(rejection_status(&url, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            rejection_status(&url, Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        // Two clients, one presenting the token in a header and the other in
+        // the query string, send text and binary messages.
+        let mut request = url.as_str().into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        let (mut client1, _) = connect_async(request).await.unwrap();
+        let (mut client2, _) = connect_async(format!("{url}?token=secret")).await.unwrap();
+
+        client1.send(Message::text("foo,1\nbar,2\n")).await.unwrap();
+        client2
+            .send(Message::binary(b"baz,3\n".to_vec()))
+            .await
+            .unwrap();
+        client1.close(None).await.unwrap();
+        client2.close(None).await.unwrap();
+    });
+
+    wait(
+        || {
+            endpoint.queue(false);
+            zset.state().flushed.len() >= 3
+        },
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+
+    let mut records = zset
+        .state()
+        .flushed
+        .iter()
+        .map(|upd| upd.unwrap_insert().clone())
+        .collect::<Vec<_>>();
+    records.sort_by_key(|record| record.i);
+    assert_eq!(
+        records,
+        vec![
+            TestStruct::new("foo", 1),
+            TestStruct::new("bar", 2),
+            TestStruct::new("baz", 3),
+        ]
+    );
+}
+
+#[test]
+fn test_websocket_input_config_validation() {
+    let config = |extra: serde_json::Value| -> WebsocketInputConfig {
+        let mut config = json!({ "port": 8080 });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "bind_address": "127.0.0.1", "path": "/ingest", "auth_token": "secret", "max_connections": 1, "max_message_size": 1024 }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "port": 0 })).contains("'port' must not be 0"));
+    assert!(error(json!({ "bind_address": "" })).contains("'bind_address'"));
+    assert!(error(json!({ "path": "ingest" })).contains("'path' must start with '/'"));
+    assert!(error(json!({ "auth_token": "" })).contains("'auth_token'"));
+    assert!(error(json!({ "max_connections": 0 })).contains("'max_connections'"));
+    assert!(error(json!({ "max_message_size": 0 })).contains("'max_message_size'"));
+}
+
+/// Runs a WebSocket server on `port` and returns the text messages
+/// received on each connection.
+///
+/// If `acks` is set, the server acknowledges each message.  If
+/// `drop_first_after` is set, the server closes the first connection after
+/// receiving that many messages, without acknowledging them.
+async fn run_server(
+    port: u16,
+    acks: bool,
+    drop_first_after: Option<usize>,
+) -> Arc<Mutex<Vec<Vec<String>>>> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+    let connections = Arc::new(Mutex::new(Vec::new()));
+    TOKIO.spawn({
+        let connections = connections.clone();
+        async move {
+            let mut drop_after = drop_first_after;
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut websocket = accept_async(stream).await.unwrap();
+                let index = {
+                    let mut connections = connections.lock().unwrap();
+                    connections.push(Vec::new());
+                    connections.len() - 1
+                };
+                let limit = drop_after.take();
+                while let Some(Ok(message)) = websocket.next().await {
+                    let Message::Text(text) = message else {
+                        continue;
+                    };
+                    let received = {
+                        let mut connections = connections.lock().unwrap();
+                        connections[index].push(text.to_string());
+                        connections[index].len()
+                    };
+                    if Some(received) == limit {
+                        break;
+                    }
+                    if acks && limit.is_none() {
+                        websocket.send(Message::text("ok")).await.unwrap();
+                    }
+                }
+            }
+        }
+    });
+    connections
+}
+
+fn output_config(port: u16, acks: bool) -> WebsocketOutputConfig {
+    serde_json::from_value(json!({
+        "url": format!("ws://127.0.0.1:{port}/feed"),
+        "headers": { "Authorization": "Bearer secret" },
+        "acks": acks,
+        "ack_timeout_ms": 5000
+    }))
+    .unwrap()
+}
+
+#[test]
+#[serial]
+fn test_websocket_output() {
+    let connections = TOKIO.block_on(run_server(18766, false, None));
+
+    let mut endpoint = WebsocketOutputEndpoint::new(output_config(18766, false)).unwrap();
+    endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+    endpoint.batch_start(0).unwrap();
+    endpoint.push_buffer(b"foo,1\n").unwrap();
+    endpoint
+        .push_key(Some(b"key".as_slice()), Some(b"bar,2\n".as_slice()), &[])
+        .unwrap();
+    endpoint.batch_end().unwrap();
+
+    wait(
+        || connections.lock().unwrap().concat().len() == 2,
+        DEFAULT_TIMEOUT_MS,
+    )
+    .unwrap();
+    assert_eq!(
+        *connections.lock().unwrap(),
+        vec![vec!["foo,1\n".to_string(), "bar,2\n".to_string()]]
+    );
+}
+
+#[test]
+#[serial]
+fn test_websocket_output_acks() {
+    // The server drops the first connection after two messages, so the
+    // connector has to reconnect and send all three messages again.
+    let connections = TOKIO.block_on(run_server(18767, true, Some(2)));
+
+    let mut endpoint = WebsocketOutputEndpoint::new(output_config(18767, true)).unwrap();
+    endpoint.connect(Box::new(|_, _, _| ())).unwrap();
+    endpoint.batch_start(0).unwrap();
+    for message in ["a", "b", "c"] {
+        endpoint.push_buffer(message.as_bytes()).unwrap();
+    }
+    endpoint.batch_end().unwrap();
+
+    let connections = connections.lock().unwrap().clone();
+    assert_eq!(connections.len(), 2);
+    assert_eq!(connections[0], vec!["a", "b"]);
+    assert_eq!(connections[1], vec!["a", "b", "c"]);
+
+    // Acknowledged messages are not sent again.
+    endpoint.batch_start(1).unwrap();
+    endpoint.push_buffer(b"d").unwrap();
+    endpoint.batch_end().unwrap();
+}
+
+#[test]
+fn test_websocket_output_config_validation() {
+    let config = |extra: serde_json::Value| -> WebsocketOutputConfig {
+        let mut config = json!({ "url": "wss://example.com/feed" });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(config).unwrap()
+    };
+    let error = |extra| config(extra).validate().unwrap_err();
+
+    config(json!({})).validate().unwrap();
+    config(json!({ "url": "ws://localhost:8080", "acks": true, "ack_timeout_ms": 1000, "max_reconnect_attempts": 0 }))
+        .validate()
+        .unwrap();
+    config(json!({ "tls": { "insecure_skip_verify": true } }))
+        .validate()
+        .unwrap();
+
+    assert!(error(json!({ "url": "http://example.com" }))
+        .contains("must start with 'ws://' or 'wss://'"));
+    assert!(error(json!({ "url": "ws://example.com", "tls": {} }))
+        .contains("'tls' requires a 'wss://' URL"));
+    assert!(error(json!({ "ack_timeout_ms": 0 })).contains("'ack_timeout_ms'"));
+    assert!(error(json!({ "max_message_size": 0 })).contains("'max_message_size'"));
+}
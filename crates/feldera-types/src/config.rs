@@ -7,19 +7,45 @@
 
 use crate::secret_resolver::default_secrets_directory;
 use crate::transport::adhoc::AdHocInputConfig;
+use crate::transport::alert::AlertOutputConfig;
+use crate::transport::amqp::AmqpInputConfig;
+use crate::transport::arrow_flight::{ArrowFlightInputConfig, ArrowFlightOutputConfig};
+use crate::transport::azure_blob::{AzureBlobInputConfig, AzureBlobOutputConfig};
 use crate::transport::clock::ClockConfig;
 use crate::transport::datagen::DatagenInputConfig;
 use crate::transport::delta_table::{DeltaTableReaderConfig, DeltaTableWriterConfig};
 use crate::transport::file::{FileInputConfig, FileOutputConfig};
+use crate::transport::ftp::FtpInputConfig;
+use crate::transport::gcs::{GcsInputConfig, GcsOutputConfig};
+use crate::transport::grpc::{GrpcInputConfig, GrpcOutputConfig};
 use crate::transport::http::HttpInputConfig;
-use crate::transport::iceberg::IcebergReaderConfig;
+use crate::transport::http_poller::HttpPollerInputConfig;
+use crate::transport::iceberg::{IcebergReaderConfig, IcebergWriterConfig};
+use crate::transport::influxdb::InfluxDbOutputConfig;
 use crate::transport::kafka::{KafkaInputConfig, KafkaOutputConfig};
+use crate::transport::kinesis::KinesisOutputConfig;
+use crate::transport::mongodb::{MongoDbCdcReaderConfig, MongoDbWriterConfig};
+use crate::transport::mqtt::{MqttInputConfig, MqttOutputConfig};
+use crate::transport::mysql::{MySqlCdcReaderConfig, MySqlWriterConfig};
+use crate::transport::nats::{NatsKvInputConfig, NatsObjectStoreInputConfig};
 use crate::transport::nexmark::NexmarkInputConfig;
-use crate::transport::postgres::{PostgresReaderConfig, PostgresWriterConfig};
+use crate::transport::postgres::{
+    PostgresCdcReaderConfig, PostgresReaderConfig, PostgresWriterConfig,
+};
 use crate::transport::pubsub::PubSubInputConfig;
-use crate::transport::redis::RedisOutputConfig;
-use crate::transport::s3::S3InputConfig;
+use crate::transport::pulsar::{PulsarInputConfig, PulsarOutputConfig};
+use crate::transport::redis::{
+    RedisOutputConfig, RedisStreamsInputConfig, RedisStreamsOutputConfig,
+};
+use crate::transport::s3::{S3InputConfig, S3OutputConfig};
+use crate::transport::sqs::SqsInputConfig;
+use crate::transport::sse::SseInputConfig;
+use crate::transport::syslog::SyslogInputConfig;
+use crate::transport::tcp::TcpInputConfig;
+use crate::transport::unix::{UnixInputConfig, UnixOutputConfig};
 use crate::transport::url::UrlInputConfig;
+use crate::transport::webhook::WebhookOutputConfig;
+use crate::transport::websocket::{WebsocketInputConfig, WebsocketOutputConfig};
 use core::fmt;
 use serde::de::{self, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -1385,15 +1411,53 @@ pub enum TransportConfig {
     KafkaInput(KafkaInputConfig),
     KafkaOutput(KafkaOutputConfig),
     PubSubInput(PubSubInputConfig),
+    NatsKvInput(NatsKvInputConfig),
+    NatsObjectStoreInput(NatsObjectStoreInputConfig),
+    MqttInput(MqttInputConfig),
+    MqttOutput(MqttOutputConfig),
+    AmqpInput(AmqpInputConfig),
+    RedisStreamsInput(RedisStreamsInputConfig),
+    RedisStreamsOutput(RedisStreamsOutputConfig),
+    PulsarInput(PulsarInputConfig),
+    PulsarOutput(PulsarOutputConfig),
+    KinesisOutput(KinesisOutputConfig),
+    SqsInput(SqsInputConfig),
+    WebsocketInput(WebsocketInputConfig),
+    WebsocketOutput(WebsocketOutputConfig),
+    GrpcInput(GrpcInputConfig),
+    GrpcOutput(GrpcOutputConfig),
+    TcpInput(TcpInputConfig),
+    SyslogInput(SyslogInputConfig),
+    UnixInput(UnixInputConfig),
+    UnixOutput(UnixOutputConfig),
     UrlInput(UrlInputConfig),
     S3Input(S3InputConfig),
+    S3Output(S3OutputConfig),
+    GcsInput(GcsInputConfig),
+    GcsOutput(GcsOutputConfig),
+    AzureBlobInput(AzureBlobInputConfig),
+    AzureBlobOutput(AzureBlobOutputConfig),
+    FtpInput(FtpInputConfig),
+    HttpPollerInput(HttpPollerInputConfig),
+    WebhookOutput(WebhookOutputConfig),
+    SseInput(SseInputConfig),
+    AlertOutput(AlertOutputConfig),
+    ArrowFlightInput(ArrowFlightInputConfig),
+    ArrowFlightOutput(ArrowFlightOutputConfig),
     DeltaTableInput(DeltaTableReaderConfig),
     DeltaTableOutput(DeltaTableWriterConfig),
     RedisOutput(RedisOutputConfig),
     // Prevent rust from complaining about large size difference between enum variants.
     IcebergInput(Box<IcebergReaderConfig>),
+    IcebergOutput(Box<IcebergWriterConfig>),
     PostgresInput(PostgresReaderConfig),
     PostgresOutput(PostgresWriterConfig),
+    PostgresCdcInput(PostgresCdcReaderConfig),
+    MySqlCdcInput(MySqlCdcReaderConfig),
+    MySqlOutput(MySqlWriterConfig),
+    InfluxDbOutput(InfluxDbOutputConfig),
+    MongoDbCdcInput(MongoDbCdcReaderConfig),
+    MongoDbOutput(MongoDbWriterConfig),
     Datagen(DatagenInputConfig),
     Nexmark(NexmarkInputConfig),
     /// Direct HTTP input: cannot be instantiated through API
@@ -1413,13 +1477,51 @@ impl TransportConfig {
             TransportConfig::KafkaInput(_) => "kafka_input".to_string(),
             TransportConfig::KafkaOutput(_) => "kafka_output".to_string(),
             TransportConfig::PubSubInput(_) => "pub_sub_input".to_string(),
+            TransportConfig::NatsKvInput(_) => "nats_kv_input".to_string(),
+            TransportConfig::NatsObjectStoreInput(_) => "nats_object_store_input".to_string(),
+            TransportConfig::MqttInput(_) => "mqtt_input".to_string(),
+            TransportConfig::MqttOutput(_) => "mqtt_output".to_string(),
+            TransportConfig::AmqpInput(_) => "amqp_input".to_string(),
+            TransportConfig::RedisStreamsInput(_) => "redis_streams_input".to_string(),
+            TransportConfig::RedisStreamsOutput(_) => "redis_streams_output".to_string(),
+            TransportConfig::PulsarInput(_) => "pulsar_input".to_string(),
+            TransportConfig::PulsarOutput(_) => "pulsar_output".to_string(),
+            TransportConfig::KinesisOutput(_) => "kinesis_output".to_string(),
+            TransportConfig::SqsInput(_) => "sqs_input".to_string(),
+            TransportConfig::WebsocketInput(_) => "websocket_input".to_string(),
+            TransportConfig::WebsocketOutput(_) => "websocket_output".to_string(),
+            TransportConfig::GrpcInput(_) => "grpc_input".to_string(),
+            TransportConfig::GrpcOutput(_) => "grpc_output".to_string(),
+            TransportConfig::TcpInput(_) => "tcp_input".to_string(),
+            TransportConfig::SyslogInput(_) => "syslog_input".to_string(),
+            TransportConfig::UnixInput(_) => "unix_input".to_string(),
+            TransportConfig::UnixOutput(_) => "unix_output".to_string(),
             TransportConfig::UrlInput(_) => "url_input".to_string(),
             TransportConfig::S3Input(_) => "s3_input".to_string(),
+            TransportConfig::S3Output(_) => "s3_output".to_string(),
+            TransportConfig::GcsInput(_) => "gcs_input".to_string(),
+            TransportConfig::GcsOutput(_) => "gcs_output".to_string(),
+            TransportConfig::AzureBlobInput(_) => "azure_blob_input".to_string(),
+            TransportConfig::AzureBlobOutput(_) => "azure_blob_output".to_string(),
+            TransportConfig::FtpInput(_) => "ftp_input".to_string(),
+            TransportConfig::HttpPollerInput(_) => "http_poller_input".to_string(),
+            TransportConfig::WebhookOutput(_) => "webhook_output".to_string(),
+            TransportConfig::SseInput(_) => "sse_input".to_string(),
+            TransportConfig::AlertOutput(_) => "alert_output".to_string(),
+            TransportConfig::ArrowFlightInput(_) => "arrow_flight_input".to_string(),
+            TransportConfig::ArrowFlightOutput(_) => "arrow_flight_output".to_string(),
             TransportConfig::DeltaTableInput(_) => "delta_table_input".to_string(),
             TransportConfig::DeltaTableOutput(_) => "delta_table_output".to_string(),
             TransportConfig::IcebergInput(_) => "iceberg_input".to_string(),
+            TransportConfig::IcebergOutput(_) => "iceberg_output".to_string(),
             TransportConfig::PostgresInput(_) => "postgres_input".to_string(),
             TransportConfig::PostgresOutput(_) => "postgres_output".to_string(),
+            TransportConfig::PostgresCdcInput(_) => "postgres_cdc_input".to_string(),
+            TransportConfig::MySqlCdcInput(_) => "mysql_cdc_input".to_string(),
+            TransportConfig::MySqlOutput(_) => "mysql_output".to_string(),
+            TransportConfig::InfluxDbOutput(_) => "influxdb_output".to_string(),
+            TransportConfig::MongoDbCdcInput(_) => "mongodb_cdc_input".to_string(),
+            TransportConfig::MongoDbOutput(_) => "mongodb_output".to_string(),
             TransportConfig::Datagen(_) => "datagen".to_string(),
             TransportConfig::Nexmark(_) => "nexmark".to_string(),
             TransportConfig::HttpInput(_) => "http_input".to_string(),
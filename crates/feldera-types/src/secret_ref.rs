@@ -128,6 +128,16 @@ pub enum MaybeSecretRefParseError {
     },
 }
 
+/// Returns true if `value` is regarded as a reference to a secret; see
+/// [`MaybeSecretRef::new`].
+///
+/// Connector configurations are validated before secret references are
+/// resolved, so checks of a setting's value are skipped when the setting is
+/// a secret reference.
+pub fn is_secret_ref(value: &str) -> bool {
+    value.starts_with("${secret:") && value.ends_with('}')
+}
+
 impl MaybeSecretRef {
     /// Determines whether a string is just a plain string or a reference to a secret.
     ///
@@ -147,7 +157,7 @@ impl MaybeSecretRef {
     ///
     /// Note that here is not checked whether the secret reference can actually be resolved.
     pub fn new(value: String) -> Result<MaybeSecretRef, MaybeSecretRefParseError> {
-        if value.starts_with("${secret:") && value.ends_with('}') {
+        if is_secret_ref(&value) {
             // Because the pattern only has ASCII characters, they are encoded as single bytes.
             // The secret reference is extracted by slicing away the first 9 bytes and the last byte.
             let from_idx_incl = 9;
@@ -237,6 +237,7 @@ mod tests {
         resolve_potential_secret_reference_string, resolve_secret_references_in_connector_config,
         resolve_secret_references_in_json, SecretRefResolutionError,
     };
+    use crate::transport::nats::Auth;
     use serde_json::json;
     use std::collections::BTreeSet;
     use std::fs::{create_dir_all, File};
@@ -561,4 +562,54 @@ mod tests {
             Some("${secret:kubernetes:e/f}".to_string())
         );
     }
+
+    #[test]
+    fn secret_resolution_nats_auth() {
+        // Create file at: <tempdir>/kubernetes/nats/password
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path();
+        let name_dir = &dir_path.join("kubernetes").join("nats");
+        create_dir_all(name_dir).unwrap();
+        let mut file = File::create(name_dir.join("password")).unwrap();
+        file.write_all(b"secret-password").unwrap();
+
+        let connector_config: ConnectorConfig = serde_json::from_value(json!({
+            "transport": {
+                "name": "nats_kv_input",
+                "config": {
+                    "connection_config": {
+                        "server_urls": ["nats://localhost:4222"],
+                        "auth": {
+                            "user_password": {
+                                "username": "feldera",
+                                "password": "${secret:kubernetes:nats/password}"
+                            }
+                        }
+                    },
+                    "bucket": "test"
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            discover_secret_references_in_connector_config(&connector_config).unwrap(),
+            BTreeSet::from([SecretRef::Kubernetes {
+                name: "nats".to_string(),
+                data_key: "password".to_string(),
+            }])
+        );
+
+        let resolved =
+            resolve_secret_references_in_connector_config(dir_path, &connector_config).unwrap();
+        let TransportConfig::NatsKvInput(config) = resolved.transport else {
+            unreachable!();
+        };
+        assert_eq!(
+            config.connection_config.auth,
+            Some(Auth::UserPassword {
+                username: "feldera".to_string(),
+                password: "secret-password".to_string(),
+            })
+        );
+    }
 }
@@ -1,14 +1,37 @@
 pub mod adhoc;
+pub mod alert;
+pub mod amqp;
+pub mod arrow_flight;
+pub mod azure_blob;
 pub mod clock;
 pub mod datagen;
 pub mod delta_table;
 pub mod file;
+pub mod ftp;
+pub mod gcs;
+pub mod grpc;
 pub mod http;
+pub mod http_poller;
 pub mod iceberg;
+pub mod influxdb;
 pub mod kafka;
+pub mod kinesis;
+pub mod mongodb;
+pub mod mqtt;
+pub mod mysql;
+pub mod nats;
 pub mod nexmark;
 pub mod postgres;
 pub mod pubsub;
+pub mod pulsar;
 pub mod redis;
 pub mod s3;
+pub mod sqs;
+pub mod sse;
+pub mod syslog;
+pub mod tcp;
+pub mod tls;
+pub mod unix;
 pub mod url;
+pub mod webhook;
+pub mod websocket;
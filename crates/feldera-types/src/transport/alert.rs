@@ -0,0 +1,136 @@
+use crate::secret_ref::is_secret_ref;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// How the alert connector secures its connection to the SMTP server.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Connect in plain text and upgrade the connection with `STARTTLS`.
+    /// The default port is 587.
+    #[default]
+    StartTls,
+
+    /// Connect with TLS.  The default port is 465.
+    Tls,
+
+    /// Connect in plain text, without encryption.  The default port is 25.
+    None,
+}
+
+impl SmtpSecurity {
+    /// The default port of the SMTP server.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Self::StartTls => 587,
+            Self::Tls => 465,
+            Self::None => 25,
+        }
+    }
+}
+
+/// E-mail settings of the alert connector.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AlertEmailConfig {
+    /// Host name of the SMTP server, e.g., `smtp.example.com`.
+    pub smtp_host: String,
+
+    /// Port of the SMTP server.
+    ///
+    /// Default: depends on `security`.
+    pub smtp_port: Option<u16>,
+
+    /// How to secure the connection to the SMTP server.
+    #[serde(default)]
+    pub security: SmtpSecurity,
+
+    /// User name used to authenticate with the SMTP server.
+    ///
+    /// Default: no authentication.
+    pub username: Option<String>,
+
+    /// Password used to authenticate with the SMTP server.
+    pub password: Option<String>,
+
+    /// Sender address, e.g., `Feldera <alerts@example.com>`.
+    pub from: String,
+
+    /// Recipient addresses.
+    pub to: Vec<String>,
+
+    /// Template for the subject of each e-mail, with the same syntax as
+    /// `template`.
+    ///
+    /// Default: `Feldera alert: <view name>`.
+    pub subject_template: Option<String>,
+}
+
+/// Alert output connector configuration.
+///
+/// The connector renders each record added to the view through `template`
+/// and sends the result as a message to a Slack incoming webhook, as an
+/// e-mail, or both.  Deleted records are ignored.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AlertOutputConfig {
+    /// Template for the text of each alert.  `{{column}}` is replaced with
+    /// the value of `column` in the record, e.g.,
+    /// `Order {{id}} of {{amount}} USD looks fraudulent`.
+    pub template: String,
+
+    /// URL of a Slack
+    /// [incoming webhook](https://api.slack.com/messaging/webhooks) to post
+    /// alerts to.
+    pub slack_webhook_url: Option<String>,
+
+    /// E-mail settings, to send alerts as e-mails.
+    pub email: Option<AlertEmailConfig>,
+
+    /// The maximum number of alerts to send for a single batch of changes to
+    /// the view.  If a batch has more records, the connector sends an
+    /// additional alert with the number of alerts that it left out.
+    ///
+    /// Default: 100.
+    pub max_alerts_per_batch: Option<usize>,
+
+    /// The number of times to retry sending an alert that failed because of
+    /// a network error or because the server was unavailable or rate-limited
+    /// the connector, before reporting an error.
+    ///
+    /// Default: 3.
+    pub max_retries: Option<u32>,
+}
+
+impl AlertOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// looking at the schema of the view.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.template.is_empty() {
+            return Err("'template' must not be empty".to_string());
+        }
+        if self.slack_webhook_url.is_none() && self.email.is_none() {
+            return Err("at least one of 'slack_webhook_url' and 'email' must be set".to_string());
+        }
+        if let Some(url) = &self.slack_webhook_url {
+            if !is_secret_ref(url) && !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(format!(
+                    "'slack_webhook_url' must be an 'http://' or 'https://' URL, but it is '{url}'"
+                ));
+            }
+        }
+        if let Some(email) = &self.email {
+            if email.smtp_host.is_empty() {
+                return Err("'smtp_host' must not be empty".to_string());
+            }
+            if email.to.is_empty() {
+                return Err("'to' must list at least one recipient".to_string());
+            }
+            if email.password.is_some() && email.username.is_none() {
+                return Err("'password' is set but 'username' is not".to_string());
+            }
+        }
+        if self.max_alerts_per_batch == Some(0) {
+            return Err("'max_alerts_per_batch' must not be 0".to_string());
+        }
+        Ok(())
+    }
+}
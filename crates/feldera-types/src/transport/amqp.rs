@@ -0,0 +1,185 @@
+use crate::secret_ref::is_secret_ref;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Type of an AMQP exchange, which determines how it routes messages to the
+/// queues bound to it.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AmqpExchangeKind {
+    /// Routes a message to the queues whose binding key equals the routing
+    /// key of the message.
+    #[default]
+    Direct,
+
+    /// Routes every message to all bound queues.
+    Fanout,
+
+    /// Routes a message to the queues whose binding pattern matches the
+    /// routing key of the message.
+    Topic,
+
+    /// Routes messages based on their headers rather than routing keys.
+    Headers,
+}
+
+/// Options for declaring the queue that the connector consumes from.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AmqpQueueDeclaration {
+    /// Keep the queue when the broker restarts.
+    ///
+    /// Default: `true`.
+    #[serde(default = "default_true")]
+    pub durable: bool,
+
+    /// Restrict the queue to the connector's connection, and delete it when
+    /// the connection closes.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub exclusive: bool,
+
+    /// Delete the queue when its last consumer unsubscribes.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub auto_delete: bool,
+}
+
+/// Options for declaring an exchange.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AmqpExchangeDeclaration {
+    /// Name of the exchange.
+    pub name: String,
+
+    /// Type of the exchange.
+    ///
+    /// Default: `direct`.
+    #[serde(default)]
+    pub kind: AmqpExchangeKind,
+
+    /// Keep the exchange when the broker restarts.
+    ///
+    /// Default: `true`.
+    #[serde(default = "default_true")]
+    pub durable: bool,
+
+    /// Delete the exchange when the last queue is unbound from it.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub auto_delete: bool,
+}
+
+/// Binding of the connector's queue to an exchange.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AmqpQueueBinding {
+    /// Name of the exchange.
+    pub exchange: String,
+
+    /// Routing key, or routing pattern for `topic` exchanges, e.g.,
+    /// `sensors.*.temperature`.
+    ///
+    /// Default: the empty string.
+    #[serde(default)]
+    pub routing_key: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// AMQP 0.9.1 (e.g., RabbitMQ) input connector configuration.
+///
+/// The connector consumes messages from a queue and parses the payload of
+/// each message using the connector's `format`.  Messages are acknowledged
+/// after they have been passed to the pipeline.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AmqpInputConfig {
+    /// URI of the broker, in the form
+    /// `amqp://[<username>[:<password>]@]<host>[:<port>][/<vhost>]`.
+    ///
+    /// Use the `amqps://` scheme to connect over TLS.  The virtual host must
+    /// be percent-encoded, e.g., `amqp://broker/%2f` for the default `/`
+    /// virtual host, which is also used if the URI does not specify one.
+    pub uri: String,
+
+    /// Name of the queue to consume from.
+    ///
+    /// May be empty if `declare_queue` is set, in which case the broker
+    /// generates a unique name for the queue.
+    pub queue: String,
+
+    /// Declare the queue before consuming from it, creating it if it does not
+    /// exist.
+    ///
+    /// Default: the queue must already exist.
+    pub declare_queue: Option<AmqpQueueDeclaration>,
+
+    /// Exchanges to declare before binding the queue to them.
+    ///
+    /// Default: no exchanges are declared.
+    #[serde(default)]
+    pub declare_exchanges: Vec<AmqpExchangeDeclaration>,
+
+    /// Bindings of the queue to exchanges.
+    ///
+    /// Default: no bindings are created.
+    #[serde(default)]
+    pub bindings: Vec<AmqpQueueBinding>,
+
+    /// Maximum number of messages that the broker delivers to the connector
+    /// before they are acknowledged.
+    ///
+    /// This bounds the number of messages buffered by the connector, e.g.,
+    /// while the pipeline is paused.
+    ///
+    /// Default: 1000.
+    pub prefetch_count: Option<u16>,
+
+    /// Consumer tag that identifies the connector to the broker, e.g., in the
+    /// RabbitMQ management UI.
+    ///
+    /// Default: a tag generated by the broker.
+    pub consumer_tag: Option<String>,
+}
+
+impl AmqpInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the broker.
+    pub fn validate(&self) -> Result<(), String> {
+        let scheme = self.uri.split_once("://").map(|(scheme, _)| scheme);
+        if !is_secret_ref(&self.uri) && !matches!(scheme, Some("amqp" | "amqps")) {
+            return Err(format!(
+                "invalid AMQP URI '{}': expected a URI such as 'amqp://broker:5672/%2f' or 'amqps://broker:5671/%2f'",
+                self.uri
+            ));
+        }
+        if self.queue.is_empty() && self.declare_queue.is_none() {
+            return Err(
+                "'queue' must not be empty unless 'declare_queue' is set, in which case the broker generates a queue name"
+                    .to_string(),
+            );
+        }
+        if self.prefetch_count == Some(0) {
+            return Err("'prefetch_count' must be greater than 0".to_string());
+        }
+        for exchange in &self.declare_exchanges {
+            if exchange.name.is_empty() {
+                return Err(
+                    "cannot declare the default exchange: 'declare_exchanges' entries must have a nonempty 'name'"
+                        .to_string(),
+                );
+            }
+        }
+        for binding in &self.bindings {
+            if binding.exchange.is_empty() {
+                return Err(
+                    "cannot bind to the default exchange: 'bindings' entries must have a nonempty 'exchange'"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::secret_ref::is_secret_ref;
+use crate::transport::tls::TlsConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Checks the settings that all Arrow Flight connectors use to connect to a
+/// Flight server.
+fn validate_connection(
+    uri: &str,
+    auth_token: &Option<String>,
+    tls: &Option<TlsConfig>,
+) -> Result<(), String> {
+    // The URI is checked once secrets are resolved.
+    let plain_text_scheme = if is_secret_ref(uri) {
+        None
+    } else {
+        let Some((scheme, _)) = uri.split_once("://") else {
+            return Err(format!(
+                "'uri' must be a 'grpc://', 'grpc+tcp://', or 'grpc+tls://' URI, but it is '{uri}'"
+            ));
+        };
+        match scheme {
+            "grpc" | "grpc+tcp" | "http" => Some(scheme),
+            "grpc+tls" | "https" => None,
+            _ => return Err(format!(
+                "'uri' must be a 'grpc://', 'grpc+tcp://', or 'grpc+tls://' URI, but it is '{uri}'"
+            )),
+        }
+    };
+    if auth_token.as_ref().is_some_and(|token| token.is_empty()) {
+        return Err("'auth_token' must not be empty".to_string());
+    }
+    if let Some(tls) = tls {
+        if let Some(scheme) = plain_text_scheme {
+            return Err(format!(
+                "'tls' is set, but 'uri' uses the plain text '{scheme}://' scheme; use 'grpc+tls://' instead"
+            ));
+        }
+        if tls.insecure_skip_verify {
+            return Err(
+                "'insecure_skip_verify' is not supported by the Arrow Flight connectors"
+                    .to_string(),
+            );
+        }
+        tls.validate()?;
+    }
+    Ok(())
+}
+
+/// Arrow Flight input connector configuration.
+///
+/// The connector calls `DoGet` on a Flight server with the configured ticket
+/// and ingests the record batches that the server returns into the table,
+/// matching Arrow columns to table columns by name.  The connector signals
+/// end of input once the server ends the stream.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct ArrowFlightInputConfig {
+    /// URI of the Flight server, e.g., `grpc://flight.example.com:8815`, or
+    /// `grpc+tls://flight.example.com:8815` to connect with TLS.
+    pub uri: String,
+
+    /// Ticket to pass to `DoGet`, which identifies the data to read.  The
+    /// UTF-8 bytes of the string are sent as the ticket.
+    pub ticket: String,
+
+    /// Token to send in an `authorization: Bearer <token>` metadata entry
+    /// with each call.
+    ///
+    /// Default: no token.
+    pub auth_token: Option<String>,
+
+    /// Additional gRPC metadata to send with each call, e.g.,
+    /// `{"x-tenant": "analytics"}`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// TLS settings, for `grpc+tls://` URIs.
+    ///
+    /// Default: verify the server certificate against the Mozilla root
+    /// certificates.
+    pub tls: Option<TlsConfig>,
+
+    /// Timeout, in seconds, for connecting to the server.
+    ///
+    /// Default: 10.
+    pub connect_timeout_secs: Option<u64>,
+}
+
+impl ArrowFlightInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the server.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_connection(&self.uri, &self.auth_token, &self.tls)?;
+        if self.ticket.is_empty() {
+            return Err("'ticket' must not be empty".to_string());
+        }
+        if self.connect_timeout_secs == Some(0) {
+            return Err("'connect_timeout_secs' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Arrow Flight output connector configuration.
+///
+/// The connector sends the changes to the view in each step to a Flight
+/// server as a `DoPut` stream of Arrow record batches.  Each record carries
+/// two additional columns: `__feldera_op` is `i` for an insertion, `d` for a
+/// deletion, or `u` for an update, and `__feldera_ts` is the time of the step,
+/// in microseconds since the Unix epoch.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct ArrowFlightOutputConfig {
+    /// URI of the Flight server, e.g., `grpc://flight.example.com:8815`, or
+    /// `grpc+tls://flight.example.com:8815` to connect with TLS.
+    pub uri: String,
+
+    /// Path of the `FlightDescriptor` sent with each `DoPut` stream, which
+    /// tells the server where to store the data.
+    ///
+    /// Default: `[<view name>]`.
+    pub path: Option<Vec<String>>,
+
+    /// Token to send in an `authorization: Bearer <token>` metadata entry
+    /// with each call.
+    ///
+    /// Default: no token.
+    pub auth_token: Option<String>,
+
+    /// Additional gRPC metadata to send with each call, e.g.,
+    /// `{"x-tenant": "analytics"}`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// TLS settings, for `grpc+tls://` URIs.
+    ///
+    /// Default: verify the server certificate against the Mozilla root
+    /// certificates.
+    pub tls: Option<TlsConfig>,
+
+    /// The maximum number of records in an Arrow record batch.  Larger steps
+    /// are sent as multiple record batches in the same `DoPut` stream.
+    ///
+    /// Default: 100000.
+    pub max_batch_records: Option<usize>,
+
+    /// Timeout, in seconds, for connecting to the server.
+    ///
+    /// Default: 10.
+    pub connect_timeout_secs: Option<u64>,
+}
+
+impl ArrowFlightOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the server.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_connection(&self.uri, &self.auth_token, &self.tls)?;
+        if let Some(path) = &self.path {
+            if path.is_empty() || path.iter().any(|element| element.is_empty()) {
+                return Err("'path' must be a non-empty list of non-empty strings".to_string());
+            }
+        }
+        if self.max_batch_records == Some(0) {
+            return Err("'max_batch_records' must be at least 1".to_string());
+        }
+        if self.connect_timeout_secs == Some(0) {
+            return Err("'connect_timeout_secs' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+use crate::transport::s3::{validate_object_output, ObjectOutputFormat};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Configuration for reading data from Azure Blob Storage, including storage
+/// accounts with Azure Data Lake Storage Gen2 enabled.
+///
+/// The connector authenticates with the account access key in `access_key` or
+/// the shared access signature in `sas_token`.  If neither is set, it uses
+/// credentials given by `AZURE_STORAGE_*` environment variables, if any, or
+/// else the identity of the environment it runs in: workload identity on AKS,
+/// or a managed identity.  Set `managed_identity_client_id` to use a
+/// user-assigned managed identity.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AzureBlobInputConfig {
+    /// Storage account name.
+    pub account: String,
+
+    /// Name of the container to read from.
+    pub container: String,
+
+    /// Read a single blob specified by its name.
+    pub key: Option<String>,
+
+    /// Read all blobs whose names start with a prefix, in lexicographic
+    /// order. Set to an empty string to read all blobs in the container.
+    pub prefix: Option<String>,
+
+    /// Storage account access key.
+    pub access_key: Option<String>,
+
+    /// Shared access signature (SAS) token, e.g., `sv=...&sig=...`.
+    pub sas_token: Option<String>,
+
+    /// Client id of the user-assigned managed identity to authenticate with.
+    pub managed_identity_client_id: Option<String>,
+
+    /// The endpoint URL used to communicate with this service.  Can be used
+    /// to connect to national clouds or to the Azurite emulator.
+    pub endpoint_url: Option<String>,
+}
+
+impl AzureBlobInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// accessing the container.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_account(
+            &self.account,
+            &self.container,
+            &self.access_key,
+            &self.sas_token,
+            &self.managed_identity_client_id,
+        )?;
+        match (&self.key, &self.prefix) {
+            (None, None) => Err("either 'key' or 'prefix' property must be specified".to_string()),
+            (Some(_), Some(_)) => Err("connector configuration specifies both 'key' and 'prefix' properties; please specify only one".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Configuration for writing data to Azure Blob Storage, including storage
+/// accounts with Azure Data Lake Storage Gen2 enabled.
+///
+/// The connector writes files in the same way as the S3 output connector:
+/// the changes to a view are written into files under `prefix`, optionally
+/// partitioned into Hive-style `column=value/` directories.  Files are rolled
+/// over when they reach `max_file_size_bytes` or when they have been open for
+/// `rollover_interval_secs`.  Each file is written as a block blob whose
+/// blocks are only committed when the file is rolled over, so readers never
+/// observe a partially written file.
+///
+/// Authentication works in the same way as for [`AzureBlobInputConfig`].
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct AzureBlobOutputConfig {
+    /// Storage account name.
+    pub account: String,
+
+    /// Name of the container to write to.
+    pub container: String,
+
+    /// Storage account access key.
+    pub access_key: Option<String>,
+
+    /// Shared access signature (SAS) token, e.g., `sv=...&sig=...`.
+    pub sas_token: Option<String>,
+
+    /// Client id of the user-assigned managed identity to authenticate with.
+    pub managed_identity_client_id: Option<String>,
+
+    /// The endpoint URL used to communicate with this service.  Can be used
+    /// to connect to national clouds or to the Azurite emulator.
+    pub endpoint_url: Option<String>,
+
+    /// Prefix of the names of the written blobs, e.g., `data/orders/`.
+    #[serde(default)]
+    pub prefix: String,
+
+    /// Columns to partition the output by.
+    ///
+    /// Each file only contains records with the same values of these
+    /// columns and is written under a `column=value/` path for each of them,
+    /// in order, e.g., `data/orders/region=eu/day=2025-01-01/`.
+    #[serde(default)]
+    pub partition_by: Vec<String>,
+
+    /// Encoding of the written files.
+    ///
+    /// Default: `json`.
+    #[serde(default)]
+    pub format: ObjectOutputFormat,
+
+    /// Size, in bytes, at which a file is completed and a new one is started.
+    ///
+    /// Default: 128 MiB.
+    pub max_file_size_bytes: Option<u64>,
+
+    /// Time, in seconds, after which an open file is completed, even if it
+    /// hasn't reached `max_file_size_bytes`.
+    ///
+    /// Default: 300.
+    pub rollover_interval_secs: Option<u64>,
+}
+
+impl AzureBlobOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// accessing the container.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_account(
+            &self.account,
+            &self.container,
+            &self.access_key,
+            &self.sas_token,
+            &self.managed_identity_client_id,
+        )?;
+        validate_object_output(
+            &self.partition_by,
+            self.max_file_size_bytes,
+            self.rollover_interval_secs,
+        )
+    }
+}
+
+fn validate_account(
+    account: &str,
+    container: &str,
+    access_key: &Option<String>,
+    sas_token: &Option<String>,
+    managed_identity_client_id: &Option<String>,
+) -> Result<(), String> {
+    if account.is_empty() {
+        return Err("'account' must not be empty".to_string());
+    }
+    if container.is_empty() {
+        return Err("'container' must not be empty".to_string());
+    }
+    let credentials = [
+        ("access_key", access_key),
+        ("sas_token", sas_token),
+        ("managed_identity_client_id", managed_identity_client_id),
+    ]
+    .into_iter()
+    .filter(|(_, value)| value.is_some())
+    .map(|(name, _)| format!("'{name}'"))
+    .collect::<Vec<_>>();
+    if credentials.len() > 1 {
+        return Err(format!(
+            "{} are mutually exclusive; please specify only one",
+            credentials.join(" and ")
+        ));
+    }
+    Ok(())
+}
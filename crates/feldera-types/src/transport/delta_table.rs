@@ -47,7 +47,7 @@ pub struct DeltaTableWriterConfig {
 
 /// Delta table read mode.
 ///
-/// Three options are available:
+/// The following options are available:
 ///
 /// * `snapshot` - read a snapshot of the table and stop.
 ///
@@ -56,6 +56,11 @@ pub struct DeltaTableWriterConfig {
 ///
 /// * `snapshot_and_follow` - read a snapshot of the table before switching to continuous ingestion
 ///   mode.
+///
+/// * `cdc` - treat the table as an append-only log of insert and delete events.
+///
+/// * `change_data_feed` - continuously ingest row-level changes recorded in the table's
+///   Change Data Feed.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
 pub enum DeltaTableIngestMode {
     /// Read a snapshot of the table and stop.
@@ -82,6 +87,22 @@ pub enum DeltaTableIngestMode {
     /// specified by the `version` or `datetime` property.
     #[serde(rename = "cdc")]
     Cdc,
+
+    /// Change Data Feed mode.
+    ///
+    /// Ingest row-level changes (inserts, updates, and deletes) from the table's
+    /// [Change Data Feed](https://docs.delta.io/latest/delta-change-data-feed.html).
+    /// Inserted rows and update post-images are ingested as insertions; deleted rows
+    /// and update pre-images are ingested as deletions.  The table must be created
+    /// with the `delta.enableChangeDataFeed = true` property.  Table versions written
+    /// without change data files, e.g., append-only commits, are ingested based on
+    /// the data files added and removed by the commit.
+    ///
+    /// In this mode, the connector does not read the initial snapshot of the table
+    /// and follows the transaction log starting from the version of the table
+    /// specified by the `version` or `datetime` property.
+    #[serde(rename = "change_data_feed")]
+    ChangeDataFeed,
 }
 
 impl Display for DeltaTableIngestMode {
@@ -91,6 +112,7 @@ impl Display for DeltaTableIngestMode {
             DeltaTableIngestMode::Follow => write!(f, "follow"),
             DeltaTableIngestMode::SnapshotAndFollow => write!(f, "shapshot_and_follow"),
             DeltaTableIngestMode::Cdc => write!(f, "cdc"),
+            DeltaTableIngestMode::ChangeDataFeed => write!(f, "change_data_feed"),
         }
     }
 }
@@ -181,8 +203,8 @@ pub struct DeltaTableReaderConfig {
     ///
     /// When this option is set, the connector finds and opens the specified version of the table.
     /// In `snapshot` and `snapshot_and_follow` modes, it retrieves the snapshot of this version of
-    /// the table.  In `follow`, `snapshot_and_follow`, and `cdc` modes, it follows transaction log records
-    /// **after** this version.
+    /// the table.  In `follow`, `snapshot_and_follow`, `cdc`, and `change_data_feed` modes, it follows
+    /// transaction log records **after** this version.
     ///
     /// Note: at most one of `version` and `datetime` options can be specified.
     /// When neither of the two options is specified, the latest committed version of the table
@@ -196,8 +218,8 @@ pub struct DeltaTableReaderConfig {
     /// When this option is set, the connector finds and opens the version of the table as of the
     /// specified point in time (based on the server time recorded in the transaction log, not the
     /// event time encoded in the data).  In `snapshot` and `snapshot_and_follow` modes, it
-    /// retrieves the snapshot of this version of the table.  In `follow`, `snapshot_and_follow`, and
-    /// `cdc` modes, it follows transaction log records **after** this version.
+    /// retrieves the snapshot of this version of the table.  In `follow`, `snapshot_and_follow`,
+    /// `cdc`, and `change_data_feed` modes, it follows transaction log records **after** this version.
     ///
     /// Note: at most one of `version` and `datetime` options can be specified.
     /// When neither of the two options is specified, the latest committed version of the table
@@ -207,7 +229,8 @@ pub struct DeltaTableReaderConfig {
 
     /// Optional final table version.
     ///
-    /// Valid only when the connector is configured in `follow`, `snapshot_and_follow`, or `cdc` mode.
+    /// Valid only when the connector is configured in `follow`, `snapshot_and_follow`, `cdc`, or
+    /// `change_data_feed` mode.
     ///
     /// When set, the connector will stop scanning the table’s transaction log after reaching this version or any greater version.
     /// This bound is inclusive: if the specified version appears in the log, it will be processed before signaling end-of-input.
@@ -303,10 +326,15 @@ impl DeltaTableReaderConfig {
             DeltaTableIngestMode::SnapshotAndFollow
                 | DeltaTableIngestMode::Follow
                 | DeltaTableIngestMode::Cdc
+                | DeltaTableIngestMode::ChangeDataFeed
         )
     }
 
     pub fn is_cdc(&self) -> bool {
         matches!(&self.mode, DeltaTableIngestMode::Cdc)
     }
+
+    pub fn is_change_data_feed(&self) -> bool {
+        matches!(&self.mode, DeltaTableIngestMode::ChangeDataFeed)
+    }
 }
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Protocol used to access a remote directory.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FtpProtocol {
+    /// SSH File Transfer Protocol.
+    #[default]
+    Sftp,
+
+    /// File Transfer Protocol, without encryption.
+    Ftp,
+}
+
+impl FtpProtocol {
+    /// Default port for this protocol.
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Self::Sftp => 22,
+            Self::Ftp => 21,
+        }
+    }
+}
+
+/// SFTP/FTP input connector configuration.
+///
+/// The connector periodically lists a directory on a remote server and reads
+/// each file whose name matches `pattern` and that it hasn't read before,
+/// parsing its contents using the connector's `format`.  Files are identified
+/// by name: a file that is modified after the connector has read it is not
+/// read again.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct FtpInputConfig {
+    /// Protocol to connect with.
+    ///
+    /// Default: `sftp`.
+    #[serde(default)]
+    pub protocol: FtpProtocol,
+
+    /// Host name or IP address of the server.
+    pub host: String,
+
+    /// Port of the server.
+    ///
+    /// Default: 22 for SFTP, 21 for FTP.
+    pub port: Option<u16>,
+
+    /// User name to log in with.
+    pub username: String,
+
+    /// Password to log in with.
+    pub password: Option<String>,
+
+    /// Private key to authenticate with, in OpenSSH or PEM format.  Only
+    /// supported with SFTP.
+    pub private_key: Option<String>,
+
+    /// Passphrase that `private_key` is encrypted with, if any.
+    pub private_key_passphrase: Option<String>,
+
+    /// Public key of the SFTP server, in OpenSSH format, e.g.,
+    /// `ssh-ed25519 AAAAC3Nza...`.  The connector refuses to connect to a
+    /// server with a different key.
+    ///
+    /// Default: accept any key.  This is vulnerable to man-in-the-middle
+    /// attacks.
+    pub server_public_key: Option<String>,
+
+    /// Directory to read files from.
+    pub directory: String,
+
+    /// Glob pattern, e.g., `*.csv`, that the names of the files to read
+    /// must match.
+    ///
+    /// Default: `*`, which matches all files.
+    pub pattern: Option<String>,
+
+    /// Interval, in seconds, between listings of the directory.
+    ///
+    /// Default: 60.
+    pub poll_interval_secs: Option<u64>,
+}
+
+impl FtpInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the server.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.host.is_empty() {
+            return Err("'host' must not be empty".to_string());
+        }
+        if self.username.is_empty() {
+            return Err("'username' must not be empty".to_string());
+        }
+        if self.directory.is_empty() {
+            return Err("'directory' must not be empty".to_string());
+        }
+        match self.protocol {
+            FtpProtocol::Sftp => {
+                if self.password.is_none() && self.private_key.is_none() {
+                    return Err("either 'password' or 'private_key' must be specified".to_string());
+                }
+            }
+            FtpProtocol::Ftp => {
+                if self.private_key.is_some() {
+                    return Err(
+                        "'private_key' is only supported with the 'sftp' protocol".to_string()
+                    );
+                }
+                if self.server_public_key.is_some() {
+                    return Err(
+                        "'server_public_key' is only supported with the 'sftp' protocol"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        if self.private_key_passphrase.is_some() && self.private_key.is_none() {
+            return Err("'private_key_passphrase' is set but 'private_key' is not".to_string());
+        }
+        if self.poll_interval_secs == Some(0) {
+            return Err("'poll_interval_secs' must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
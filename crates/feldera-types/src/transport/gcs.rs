@@ -0,0 +1,119 @@
+use crate::transport::s3::{validate_object_output, ObjectOutputFormat};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Configuration for reading data from Google Cloud Storage.
+///
+/// The connector authenticates with the service account key given by
+/// `service_account_key` or `service_account_path`.  If neither is set, it
+/// uses application default credentials: the key file named by the
+/// `GOOGLE_APPLICATION_CREDENTIALS` environment variable, if any, or else the
+/// credentials of the environment it runs in, e.g., workload identity on GKE.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct GcsInputConfig {
+    /// GCS bucket name to access.
+    pub bucket_name: String,
+
+    /// Read a single object specified by its name.
+    pub key: Option<String>,
+
+    /// Read all objects whose names start with a prefix, in lexicographic
+    /// order. Set to an empty string to read all objects in the bucket.
+    pub prefix: Option<String>,
+
+    /// Contents of a service account key file, in JSON.
+    pub service_account_key: Option<String>,
+
+    /// Path to a service account key file.
+    pub service_account_path: Option<String>,
+}
+
+impl GcsInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// accessing the bucket.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_credentials(&self.service_account_key, &self.service_account_path)?;
+        if self.bucket_name.is_empty() {
+            return Err("'bucket_name' must not be empty".to_string());
+        }
+        match (&self.key, &self.prefix) {
+            (None, None) => Err("either 'key' or 'prefix' property must be specified".to_string()),
+            (Some(_), Some(_)) => Err("connector configuration specifies both 'key' and 'prefix' properties; please specify only one".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Configuration for writing data to Google Cloud Storage.
+///
+/// The connector writes files in the same way as the S3 output connector:
+/// the changes to a view are written into files under `prefix`, optionally
+/// partitioned into Hive-style `column=value/` directories.  Files are rolled
+/// over when they reach `max_file_size_bytes` or when they have been open for
+/// `rollover_interval_secs`, and only become visible once they have been
+/// written in full.
+///
+/// Authentication works in the same way as for [`GcsInputConfig`].
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct GcsOutputConfig {
+    /// GCS bucket name to write to.
+    pub bucket_name: String,
+
+    /// Contents of a service account key file, in JSON.
+    pub service_account_key: Option<String>,
+
+    /// Path to a service account key file.
+    pub service_account_path: Option<String>,
+
+    /// Prefix of the names of the written objects, e.g., `data/orders/`.
+    #[serde(default)]
+    pub prefix: String,
+
+    /// Columns to partition the output by.
+    ///
+    /// Each file only contains records with the same values of these
+    /// columns and is written under a `column=value/` path for each of them,
+    /// in order, e.g., `data/orders/region=eu/day=2025-01-01/`.
+    #[serde(default)]
+    pub partition_by: Vec<String>,
+
+    /// Encoding of the written files.
+    ///
+    /// Default: `json`.
+    #[serde(default)]
+    pub format: ObjectOutputFormat,
+
+    /// Size, in bytes, at which a file is completed and a new one is started.
+    ///
+    /// Default: 128 MiB.
+    pub max_file_size_bytes: Option<u64>,
+
+    /// Time, in seconds, after which an open file is completed, even if it
+    /// hasn't reached `max_file_size_bytes`.
+    ///
+    /// Default: 300.
+    pub rollover_interval_secs: Option<u64>,
+}
+
+impl GcsOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// accessing the bucket.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_credentials(&self.service_account_key, &self.service_account_path)?;
+        if self.bucket_name.is_empty() {
+            return Err("'bucket_name' must not be empty".to_string());
+        }
+        validate_object_output(
+            &self.partition_by,
+            self.max_file_size_bytes,
+            self.rollover_interval_secs,
+        )
+    }
+}
+
+fn validate_credentials(key: &Option<String>, path: &Option<String>) -> Result<(), String> {
+    if key.is_some() && path.is_some() {
+        return Err("'service_account_key' and 'service_account_path' are both set; please specify only one".to_string());
+    }
+    Ok(())
+}
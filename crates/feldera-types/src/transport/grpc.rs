@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// gRPC input connector configuration.
+///
+/// The connector runs a gRPC server that implements the
+/// `feldera.transport.v1.Ingest` service.  Clients call its `Push` method to
+/// stream data to the connector, which parses the data in each request using
+/// the connector's `format`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct GrpcInputConfig {
+    /// Address of the network interface to listen on, e.g., `127.0.0.1` to
+    /// only accept connections from the local host.
+    ///
+    /// Default: `0.0.0.0`, i.e., all IPv4 interfaces.
+    pub bind_address: Option<String>,
+
+    /// TCP port to listen on.
+    pub port: u16,
+
+    /// Token that clients must present in an `authorization: Bearer <token>`
+    /// metadata entry.
+    ///
+    /// Default: clients are not authenticated.
+    pub auth_token: Option<String>,
+
+    /// Acknowledge each request once its data has been passed to the
+    /// pipeline.  A client that resends the requests that were not
+    /// acknowledged when a stream fails gets at-least-once delivery.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub acks: bool,
+
+    /// Maximum number of requests from a stream whose data is waiting to be
+    /// passed to the pipeline.  Once a stream reaches this limit, the
+    /// connector stops reading from it, so that gRPC flow control makes the
+    /// client wait.
+    ///
+    /// Default: 100.
+    pub max_queued_requests: Option<usize>,
+
+    /// Maximum size of a request, in bytes.  A stream that sends a larger
+    /// request fails.
+    ///
+    /// Default: 4 MiB.
+    pub max_message_size: Option<usize>,
+}
+
+impl GrpcInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// listening on the port.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("'port' must not be 0".to_string());
+        }
+        if self
+            .bind_address
+            .as_ref()
+            .is_some_and(|address| address.is_empty())
+        {
+            return Err("'bind_address' must not be empty".to_string());
+        }
+        if self
+            .auth_token
+            .as_ref()
+            .is_some_and(|token| token.is_empty())
+        {
+            return Err("'auth_token' must not be empty".to_string());
+        }
+        if self.max_queued_requests == Some(0) {
+            return Err("'max_queued_requests' must be at least 1".to_string());
+        }
+        if self.max_message_size == Some(0) {
+            return Err("'max_message_size' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// gRPC output connector configuration.
+///
+/// The connector runs a gRPC server that implements the
+/// `feldera.transport.v1.Egress` service.  Clients call its `Subscribe` method
+/// to receive a stream of the changes to the view, encoded using the
+/// connector's `format`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct GrpcOutputConfig {
+    /// Address of the network interface to listen on, e.g., `127.0.0.1` to
+    /// only accept connections from the local host.
+    ///
+    /// Default: `0.0.0.0`, i.e., all IPv4 interfaces.
+    pub bind_address: Option<String>,
+
+    /// TCP port to listen on.
+    pub port: u16,
+
+    /// Token that clients must present in an `authorization: Bearer <token>`
+    /// metadata entry.
+    ///
+    /// Default: clients are not authenticated.
+    pub auth_token: Option<String>,
+
+    /// Maximum number of simultaneous subscribers.  Further calls fail.
+    ///
+    /// Default: 100.
+    pub max_subscribers: Option<usize>,
+
+    /// Maximum number of messages waiting to be sent to a subscriber.  A
+    /// subscriber that falls further behind is disconnected, so that slow
+    /// subscribers cannot stall the pipeline.
+    ///
+    /// Default: 1000.
+    pub max_queued_messages: Option<usize>,
+
+    /// Maximum size of a message, in bytes.
+    ///
+    /// Default: 4 MiB.
+    pub max_message_size: Option<usize>,
+}
+
+impl GrpcOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// listening on the port.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("'port' must not be 0".to_string());
+        }
+        if self
+            .bind_address
+            .as_ref()
+            .is_some_and(|address| address.is_empty())
+        {
+            return Err("'bind_address' must not be empty".to_string());
+        }
+        if self
+            .auth_token
+            .as_ref()
+            .is_some_and(|token| token.is_empty())
+        {
+            return Err("'auth_token' must not be empty".to_string());
+        }
+        if self.max_subscribers == Some(0) {
+            return Err("'max_subscribers' must be at least 1".to_string());
+        }
+        if self.max_queued_messages == Some(0) {
+            return Err("'max_queued_messages' must be at least 1".to_string());
+        }
+        if self.max_message_size == Some(0) {
+            return Err("'max_message_size' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
@@ -44,6 +44,8 @@ pub enum IcebergCatalogType {
     Rest,
     #[serde(rename = "glue")]
     Glue,
+    #[serde(rename = "hive")]
+    Hive,
 }
 
 /// AWS Glue catalog config.
@@ -136,6 +138,22 @@ pub struct RestCatalogConfig {
     pub resource: Option<String>,
 }
 
+/// Hive Metastore catalog config.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct HiveCatalogConfig {
+    /// Address of the Hive Metastore Thrift service.
+    ///
+    /// Example: `"thrift://metastore:9083"`
+    #[serde(rename = "hive.uri")]
+    pub uri: Option<String>,
+
+    /// Location for table metadata.
+    ///
+    /// Example: `"s3://my-data-warehouse/tables/"`
+    #[serde(rename = "hive.warehouse")]
+    pub warehouse: Option<String>,
+}
+
 /// Iceberg input connector configuration.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
 pub struct IcebergReaderConfig {
@@ -219,7 +237,7 @@ pub struct IcebergReaderConfig {
 
     /// Specifies the catalog type used to access the Iceberg table.
     ///
-    /// Supported options include "rest" and "glue". This property is mutually exclusive with `metadata_location`.
+    /// Supported options include "rest", "glue", and "hive". This property is mutually exclusive with `metadata_location`.
     pub catalog_type: Option<IcebergCatalogType>,
 
     #[serde(flatten)]
@@ -228,6 +246,9 @@ pub struct IcebergReaderConfig {
     #[serde(flatten)]
     pub rest_catalog_config: RestCatalogConfig,
 
+    #[serde(flatten)]
+    pub hive_catalog_config: HiveCatalogConfig,
+
     /// Storage options for configuring backend object store.
     ///
     /// See the [list of available options in PyIceberg documentation](https://py.iceberg.apache.org/configuration/#fileio).
@@ -241,56 +262,24 @@ impl IcebergReaderConfig {
         self.validate_table_name()?;
         self.validate_glue_catalog_config()?;
         self.validate_rest_catalog_config()?;
+        self.validate_hive_catalog_config()?;
 
         Ok(())
     }
 
     /// Reject Glue catalog config properties when 'catalog_type' isn't set to 'glue'.
     pub fn validate_glue_catalog_config(&self) -> Result<(), String> {
-        if self.catalog_type == Some(IcebergCatalogType::Glue) {
-            if self.glue_catalog_config.warehouse.is_none() {
-                return Err(r#"missing Iceberg warehouse location—set the 'glue.warehouse' property to the location of the Iceberg tables managed by the catalog (e.g., 's3://my-data-warehouse/tables/') when using "catalog_type" = "glue""#.to_string());
-            }
-        } else {
-            ensure_glue_property_not_set(&self.glue_catalog_config.warehouse, "warehouse")?;
-            ensure_glue_property_not_set(&self.glue_catalog_config.endpoint, "uri")?;
-            ensure_glue_property_not_set(&self.glue_catalog_config.access_key_id, "access-key-id")?;
-            ensure_glue_property_not_set(
-                &self.glue_catalog_config.secret_access_key,
-                "secret-access-key",
-            )?;
-            ensure_glue_property_not_set(&self.glue_catalog_config.profile_name, "profile-name")?;
-            ensure_glue_property_not_set(&self.glue_catalog_config.region, "region")?;
-            ensure_glue_property_not_set(&self.glue_catalog_config.session_token, "session-token")?;
-            ensure_glue_property_not_set(&self.glue_catalog_config.id, "id")?;
-        }
-
-        Ok(())
+        validate_glue_catalog_config(self.catalog_type.as_ref(), &self.glue_catalog_config)
     }
 
     /// Reject Rest catalog config when 'catalog_type' isn't set to 'rest'.
     pub fn validate_rest_catalog_config(&self) -> Result<(), String> {
-        if self.catalog_type == Some(IcebergCatalogType::Rest) {
-            if self.rest_catalog_config.uri.is_none() {
-                return Err(r#"missing Iceberg Rest catalog URI—set the 'rest.uri' property when using "catalog_type" = "rest""#.to_string());
-            }
-        } else {
-            ensure_rest_property_not_set(&self.rest_catalog_config.uri, "uri")?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.warehouse, "warehouse")?;
-            ensure_rest_property_not_set(
-                &self.rest_catalog_config.oauth2_server_uri,
-                "oauth2_server_uri",
-            )?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.credential, "credential")?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.token, "token")?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.scope, "scope")?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.prefix, "prefix")?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.headers, "headers")?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.audience, "audience")?;
-            ensure_rest_property_not_set(&self.rest_catalog_config.resource, "resource")?;
-        }
+        validate_rest_catalog_config(self.catalog_type.as_ref(), &self.rest_catalog_config)
+    }
 
-        Ok(())
+    /// Reject Hive catalog config when 'catalog_type' isn't set to 'hive'.
+    pub fn validate_hive_catalog_config(&self) -> Result<(), String> {
+        validate_hive_catalog_config(self.catalog_type.as_ref(), &self.hive_catalog_config)
     }
 
     /// Table name must be configured iff 'catalog_type' is set.
@@ -316,6 +305,71 @@ impl IcebergReaderConfig {
     }
 }
 
+fn validate_glue_catalog_config(
+    catalog_type: Option<&IcebergCatalogType>,
+    config: &GlueCatalogConfig,
+) -> Result<(), String> {
+    if catalog_type == Some(&IcebergCatalogType::Glue) {
+        if config.warehouse.is_none() {
+            return Err(r#"missing Iceberg warehouse location—set the 'glue.warehouse' property to the location of the Iceberg tables managed by the catalog (e.g., 's3://my-data-warehouse/tables/') when using "catalog_type" = "glue""#.to_string());
+        }
+    } else {
+        ensure_glue_property_not_set(&config.warehouse, "warehouse")?;
+        ensure_glue_property_not_set(&config.endpoint, "uri")?;
+        ensure_glue_property_not_set(&config.access_key_id, "access-key-id")?;
+        ensure_glue_property_not_set(&config.secret_access_key, "secret-access-key")?;
+        ensure_glue_property_not_set(&config.profile_name, "profile-name")?;
+        ensure_glue_property_not_set(&config.region, "region")?;
+        ensure_glue_property_not_set(&config.session_token, "session-token")?;
+        ensure_glue_property_not_set(&config.id, "id")?;
+    }
+
+    Ok(())
+}
+
+fn validate_rest_catalog_config(
+    catalog_type: Option<&IcebergCatalogType>,
+    config: &RestCatalogConfig,
+) -> Result<(), String> {
+    if catalog_type == Some(&IcebergCatalogType::Rest) {
+        if config.uri.is_none() {
+            return Err(r#"missing Iceberg Rest catalog URI—set the 'rest.uri' property when using "catalog_type" = "rest""#.to_string());
+        }
+    } else {
+        ensure_rest_property_not_set(&config.uri, "uri")?;
+        ensure_rest_property_not_set(&config.warehouse, "warehouse")?;
+        ensure_rest_property_not_set(&config.oauth2_server_uri, "oauth2_server_uri")?;
+        ensure_rest_property_not_set(&config.credential, "credential")?;
+        ensure_rest_property_not_set(&config.token, "token")?;
+        ensure_rest_property_not_set(&config.scope, "scope")?;
+        ensure_rest_property_not_set(&config.prefix, "prefix")?;
+        ensure_rest_property_not_set(&config.headers, "headers")?;
+        ensure_rest_property_not_set(&config.audience, "audience")?;
+        ensure_rest_property_not_set(&config.resource, "resource")?;
+    }
+
+    Ok(())
+}
+
+fn validate_hive_catalog_config(
+    catalog_type: Option<&IcebergCatalogType>,
+    config: &HiveCatalogConfig,
+) -> Result<(), String> {
+    if catalog_type == Some(&IcebergCatalogType::Hive) {
+        if config.uri.is_none() {
+            return Err(r#"missing Hive Metastore address—set the 'hive.uri' property when using "catalog_type" = "hive""#.to_string());
+        }
+        if config.warehouse.is_none() {
+            return Err(r#"missing Iceberg warehouse location—set the 'hive.warehouse' property to the location of the Iceberg tables managed by the catalog (e.g., 's3://my-data-warehouse/tables/') when using "catalog_type" = "hive""#.to_string());
+        }
+    } else {
+        ensure_hive_property_not_set(&config.uri, "uri")?;
+        ensure_hive_property_not_set(&config.warehouse, "warehouse")?;
+    }
+
+    Ok(())
+}
+
 fn ensure_glue_property_not_set<T>(property: &Option<T>, name: &str) -> Result<(), String> {
     if property.is_some() {
         Err(format!(
@@ -336,6 +390,16 @@ fn ensure_rest_property_not_set<T>(property: &Option<T>, name: &str) -> Result<(
     }
 }
 
+fn ensure_hive_property_not_set<T>(property: &Option<T>, name: &str) -> Result<(), String> {
+    if property.is_some() {
+        Err(format!(
+            r#"unexpected 'hive.{name}' property—Hive catalog configuration properties are only valid when "catalog_type" = "hive""#
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 impl IcebergReaderConfig {
     /// `true` if the configuration requires taking an initial snapshot of the table.
     pub fn snapshot(&self) -> bool {
@@ -354,3 +418,56 @@ impl IcebergReaderConfig {
         )
     }
 }
+
+/// Iceberg output connector configuration.
+///
+/// The connector appends the changes to a view to an existing, unpartitioned
+/// Iceberg table, committing the changes output by each step of the pipeline
+/// as one table snapshot.  The table must have a column of a compatible type
+/// for each column of the view.  If the table has `__feldera_op` and
+/// `__feldera_ts` columns, each row records the type of the change (`i` for
+/// insert, `d` for delete, `u` for upsert) and the time when the pipeline
+/// output it, in microseconds since the UNIX epoch.  Deletions can only be
+/// written to tables with an `__feldera_op` column.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct IcebergWriterConfig {
+    /// Specifies the Iceberg table name in the "namespace.table" format.
+    pub table_name: String,
+
+    /// Specifies the catalog type used to access the Iceberg table.
+    ///
+    /// Supported options include "rest", "glue", and "hive".
+    pub catalog_type: IcebergCatalogType,
+
+    #[serde(flatten)]
+    pub glue_catalog_config: GlueCatalogConfig,
+
+    #[serde(flatten)]
+    pub rest_catalog_config: RestCatalogConfig,
+
+    #[serde(flatten)]
+    pub hive_catalog_config: HiveCatalogConfig,
+
+    /// Storage options for configuring backend object store.
+    ///
+    /// See the [list of available options in PyIceberg documentation](https://py.iceberg.apache.org/configuration/#fileio).
+    #[serde(flatten)]
+    pub fileio_config: HashMap<String, String>,
+}
+
+impl IcebergWriterConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let parts = self.table_name.split('.').collect::<Vec<_>>();
+        if parts.len() < 2 || parts.iter().any(|part| part.is_empty()) {
+            return Err(format!(
+                "invalid 'table_name' property '{}': expected a table name in the \"namespace.table\" format",
+                self.table_name
+            ));
+        }
+        validate_glue_catalog_config(Some(&self.catalog_type), &self.glue_catalog_config)?;
+        validate_rest_catalog_config(Some(&self.catalog_type), &self.rest_catalog_config)?;
+        validate_hive_catalog_config(Some(&self.catalog_type), &self.hive_catalog_config)?;
+
+        Ok(())
+    }
+}
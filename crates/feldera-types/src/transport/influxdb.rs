@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Precision of the timestamps written to InfluxDB.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InfluxDbPrecision {
+    /// Nanoseconds.
+    #[default]
+    Ns,
+
+    /// Microseconds.
+    Us,
+
+    /// Milliseconds.
+    Ms,
+
+    /// Seconds.
+    S,
+}
+
+impl InfluxDbPrecision {
+    /// The value of the `precision` parameter of the write API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ns => "ns",
+            Self::Us => "us",
+            Self::Ms => "ms",
+            Self::S => "s",
+        }
+    }
+}
+
+/// InfluxDB output connector configuration.
+///
+/// The connector writes each record added to the view as a point in
+/// [line protocol](https://docs.influxdata.com/influxdb/v2/reference/syntax/line-protocol/)
+/// to the InfluxDB v2 write API.  Deleted records are ignored.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct InfluxDbOutputConfig {
+    /// URL of the InfluxDB server, e.g., `http://localhost:8086`.
+    pub url: String,
+
+    /// Organization name or ID.
+    pub org: String,
+
+    /// Bucket to write to.
+    pub bucket: String,
+
+    /// API token used to authenticate with the server.
+    pub token: Option<String>,
+
+    /// Measurement name.
+    ///
+    /// Default: the name of the view.
+    pub measurement: Option<String>,
+
+    /// Columns written as tags.  Null values are omitted.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Columns written as fields.
+    ///
+    /// Default: all columns that are not tags or the timestamp.
+    pub fields: Option<Vec<String>>,
+
+    /// Column that holds the timestamp of each point.  It must have type
+    /// `TIMESTAMP`, or an integer type, in which case it holds a number of
+    /// `precision` units since the Unix epoch.
+    ///
+    /// Default: none, i.e., the server timestamps each point when it
+    /// receives it.
+    pub timestamp: Option<String>,
+
+    /// Precision of the timestamps.
+    #[serde(default)]
+    pub precision: InfluxDbPrecision,
+
+    /// The maximum number of points in a single write request.
+    ///
+    /// Default: 5000.
+    pub max_points_per_request: Option<usize>,
+
+    /// The number of times to retry a write request that failed because the
+    /// server was unavailable or rate-limited the connector, before
+    /// reporting an error.
+    ///
+    /// Default: 5.
+    pub max_retries: Option<u32>,
+}
+
+impl InfluxDbOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// looking at the schema of the view.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.url.is_empty() {
+            return Err("'url' must not be empty".to_string());
+        }
+        if self.org.is_empty() {
+            return Err("'org' must not be empty".to_string());
+        }
+        if self.bucket.is_empty() {
+            return Err("'bucket' must not be empty".to_string());
+        }
+        if self.measurement.as_deref() == Some("") {
+            return Err("'measurement' must not be empty".to_string());
+        }
+        if self.max_points_per_request == Some(0) {
+            return Err("'max_points_per_request' must not be 0".to_string());
+        }
+        if self.fields.as_ref().is_some_and(|fields| fields.is_empty()) {
+            return Err("'fields' must not be empty".to_string());
+        }
+        for tag in &self.tags {
+            if self
+                .fields
+                .as_ref()
+                .is_some_and(|fields| fields.contains(tag))
+            {
+                return Err(format!("column '{tag}' cannot be both a tag and a field"));
+            }
+            if self.timestamp.as_ref() == Some(tag) {
+                return Err(format!(
+                    "column '{tag}' cannot be both a tag and the timestamp"
+                ));
+            }
+        }
+        if let (Some(fields), Some(timestamp)) = (&self.fields, &self.timestamp) {
+            if fields.contains(timestamp) {
+                return Err(format!(
+                    "column '{timestamp}' cannot be both a field and the timestamp"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Maximum length of a Kinesis partition key, in Unicode characters.
+pub const MAX_PARTITION_KEY_LEN: usize = 256;
+
+/// AWS Kinesis Data Streams output connector configuration.
+///
+/// The connector publishes each buffer produced by the connector's `format`
+/// as a record to a Kinesis data stream, batching records into `PutRecords`
+/// requests.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct KinesisOutputConfig {
+    /// Name of the Kinesis data stream.
+    pub stream_name: String,
+
+    /// AWS region of the stream, e.g., `us-east-1`.
+    pub region: String,
+
+    /// AWS Access Key id.
+    ///
+    /// Default: credentials are read from the environment.
+    pub aws_access_key_id: Option<String>,
+
+    /// Secret Access Key.
+    ///
+    /// Default: credentials are read from the environment.
+    pub aws_secret_access_key: Option<String>,
+
+    /// The endpoint URL used to communicate with this service.  Can be used to
+    /// make this connector talk to non-AWS services with a Kinesis API, e.g.,
+    /// LocalStack.
+    pub endpoint_url: Option<String>,
+
+    /// Partition key for records that do not have a key.
+    ///
+    /// Records produced by formats with key/value pairs use the record's key
+    /// as the partition key, so that all changes to a record go to the same
+    /// shard.  Keys that are not valid UTF-8 or longer than 256 characters are
+    /// replaced by their SHA-256 hash.
+    ///
+    /// Default: a random partition key for each record, which spreads records
+    /// evenly across shards.
+    pub partition_key: Option<String>,
+
+    /// Maximum number of times to retry records that Kinesis rejects, e.g.,
+    /// because the stream's throughput limits were exceeded.  Retries back
+    /// off exponentially.  Records that still fail after the last retry are
+    /// reported as errors.
+    ///
+    /// Default: 10.
+    pub max_retries: Option<u32>,
+}
+
+impl KinesisOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to Kinesis.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.stream_name.is_empty() {
+            return Err("'stream_name' must not be empty".to_string());
+        }
+        if self.region.is_empty() {
+            return Err("'region' must not be empty".to_string());
+        }
+        match (&self.aws_access_key_id, &self.aws_secret_access_key) {
+            (Some(_), None) => return Err("'aws_access_key_id' set but 'aws_secret_access_key' not set; either set both or unset both to read from the environment".to_string()),
+            (None, Some(_)) => return Err("'aws_secret_access_key' set but 'aws_access_key_id' not set; either set both or unset both to read from the environment".to_string()),
+            _ => {}
+        }
+        if let Some(partition_key) = &self.partition_key {
+            let len = partition_key.chars().count();
+            if len == 0 || len > MAX_PARTITION_KEY_LEN {
+                return Err(format!(
+                    "'partition_key' must be between 1 and {MAX_PARTITION_KEY_LEN} characters long"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,390 @@
+use crate::secret_ref::is_secret_ref;
+use crate::transport::tls::TlsConfig;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Default port for unencrypted MQTT connections.
+const DEFAULT_PORT: u16 = 1883;
+
+/// Default port for MQTT connections over TLS.
+const DEFAULT_TLS_PORT: u16 = 8883;
+
+/// MQTT protocol version.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub enum MqttProtocolVersion {
+    /// MQTT 3.1.1.
+    #[default]
+    #[serde(rename = "3.1.1")]
+    V311,
+
+    /// MQTT 5.
+    #[serde(rename = "5")]
+    V5,
+}
+
+/// MQTT quality of service level.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttQos {
+    /// QoS 0: messages are delivered at most once and may be lost, e.g., if
+    /// the connection drops.
+    AtMostOnce,
+
+    /// QoS 1: messages are delivered at least once and may be duplicated.
+    #[default]
+    AtLeastOnce,
+}
+
+/// Options for connecting to an MQTT broker.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct MqttConnectionConfig {
+    /// URL of the broker, e.g., `mqtt://broker:1883` or `mqtts://broker:8883`.
+    ///
+    /// The `mqtts://` (or `ssl://`) scheme connects over TLS; `mqtt://` (or
+    /// `tcp://`) connects without encryption.  The port defaults to 1883 for
+    /// `mqtt://` and 8883 for `mqtts://`.
+    pub server_url: String,
+
+    /// MQTT protocol version.
+    ///
+    /// Default: `3.1.1`.
+    #[serde(default)]
+    pub protocol_version: MqttProtocolVersion,
+
+    /// Client identifier.
+    ///
+    /// Brokers allow only one connection per client identifier, so every
+    /// connector that connects to the same broker must use a different
+    /// identifier.  Required when `clean_session` is `false`, so that the
+    /// connector resumes its session after restarting.
+    ///
+    /// Default: a randomly generated identifier.
+    pub client_id: Option<String>,
+
+    /// User name for authentication.
+    pub username: Option<String>,
+
+    /// Password for authentication.  Requires `username`.
+    pub password: Option<String>,
+
+    /// Interval between keep-alive pings sent to the broker, in seconds.
+    ///
+    /// Default: 60 seconds.
+    pub keep_alive_secs: Option<u64>,
+
+    /// Timeout for establishing a connection to the broker, in seconds.
+    ///
+    /// Default: 5 seconds.
+    pub connection_timeout_secs: Option<u64>,
+
+    /// Start a new session when connecting to the broker.
+    ///
+    /// When `false`, the broker keeps the session, including subscriptions
+    /// and undelivered QoS 1 messages, while the connector is disconnected.
+    /// This is called a clean start in MQTT 5.
+    ///
+    /// Default: `true`.
+    #[serde(default = "default_clean_session")]
+    pub clean_session: bool,
+
+    /// Maximum size of an MQTT packet, in bytes.  Messages whose packets
+    /// exceed this size are rejected.
+    ///
+    /// Default: 1 MiB.
+    pub max_packet_size: Option<u32>,
+
+    /// TLS settings.  Requires an `mqtts://` URL.
+    ///
+    /// Default: for `mqtts://` URLs, verify the broker certificate against the
+    /// Mozilla root certificates, without a client certificate.
+    pub tls: Option<TlsConfig>,
+}
+
+fn default_clean_session() -> bool {
+    true
+}
+
+/// Broker address parsed from [`MqttConnectionConfig::server_url`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MqttBrokerAddress {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+}
+
+impl MqttConnectionConfig {
+    /// Parses `server_url`.
+    pub fn broker_address(&self) -> Result<MqttBrokerAddress, String> {
+        let url = &self.server_url;
+        let invalid = |reason: &str| format!("invalid MQTT server URL '{url}': {reason}");
+
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| invalid("expected a URL such as 'mqtt://broker:1883'"))?;
+        let tls = match scheme {
+            "mqtt" | "tcp" => false,
+            "mqtts" | "ssl" => true,
+            _ => {
+                return Err(invalid(
+                    "the scheme must be one of 'mqtt', 'tcp', 'mqtts', or 'ssl'",
+                ))
+            }
+        };
+        let authority = rest.strip_suffix('/').unwrap_or(rest);
+        if authority.contains(['/', '?', '#', '@']) {
+            return Err(invalid(
+                "the URL must consist of a scheme, host, and optional port",
+            ));
+        }
+
+        // IPv6 addresses are enclosed in brackets, e.g., `[::1]:1883`.
+        let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| invalid("missing ']' after IPv6 address"))?;
+            match rest {
+                "" => (host, None),
+                _ => (
+                    host,
+                    Some(
+                        rest.strip_prefix(':')
+                            .ok_or_else(|| invalid("expected ':' after IPv6 address"))?,
+                    ),
+                ),
+            }
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+        let port = match port {
+            Some(port) => port
+                .parse::<u16>()
+                .map_err(|_| invalid(&format!("invalid port '{port}'")))?,
+            None if tls => DEFAULT_TLS_PORT,
+            None => DEFAULT_PORT,
+        };
+
+        Ok(MqttBrokerAddress {
+            host: host.to_string(),
+            port,
+            tls,
+        })
+    }
+
+    /// Checks the options for errors that can be detected without connecting
+    /// to the broker or reading files.
+    pub fn validate(&self) -> Result<(), String> {
+        // The URL is parsed once secrets are resolved.
+        let address = if is_secret_ref(&self.server_url) {
+            None
+        } else {
+            Some(self.broker_address()?)
+        };
+        if let Some(tls) = &self.tls {
+            if address.is_some_and(|address| !address.tls) {
+                return Err(format!(
+                    "'tls' requires an 'mqtts://' server URL, but the URL is '{}'",
+                    self.server_url
+                ));
+            }
+            tls.validate()?;
+        }
+
+        match &self.client_id {
+            Some(client_id) if client_id.is_empty() => {
+                return Err("'client_id' must not be empty".to_string());
+            }
+            None if !self.clean_session => {
+                return Err(
+                    "'client_id' is required when 'clean_session' is false, so that the session can be resumed"
+                        .to_string(),
+                );
+            }
+            _ => {}
+        }
+
+        if self.password.is_some() && self.username.is_none() {
+            return Err("'password' requires a 'username'".to_string());
+        }
+        if self
+            .keep_alive_secs
+            .is_some_and(|secs| secs > u16::MAX as u64)
+        {
+            return Err(format!(
+                "'keep_alive_secs' must be at most {} seconds",
+                u16::MAX
+            ));
+        }
+        if self.connection_timeout_secs == Some(0) {
+            return Err("'connection_timeout_secs' must be greater than 0".to_string());
+        }
+        if self.max_packet_size == Some(0) {
+            return Err("'max_packet_size' must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that `filter` is a valid MQTT topic filter.
+///
+/// A filter consists of levels separated by `/`.  The single-level wildcard
+/// `+` and the multi-level wildcard `#` must occupy a whole level, and `#`
+/// must be the last level.
+pub fn validate_topic_filter(filter: &str) -> Result<(), String> {
+    if filter.is_empty() {
+        return Err("topic filters must not be empty".to_string());
+    }
+    if filter.contains('\0') {
+        return Err(format!(
+            "'{filter}' is not a valid topic filter: it contains a NUL character"
+        ));
+    }
+    let levels = filter.split('/').collect::<Vec<_>>();
+    for (i, level) in levels.iter().enumerate() {
+        if level.len() > 1 && level.contains(['+', '#']) {
+            return Err(format!(
+                "'{filter}' is not a valid topic filter: wildcards must occupy a whole level"
+            ));
+        }
+        if *level == "#" && i != levels.len() - 1 {
+            return Err(format!(
+                "'{filter}' is not a valid topic filter: '#' must be the last level"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// MQTT input connector configuration.
+///
+/// The connector subscribes to one or more topic filters and parses the
+/// payload of each message it receives using the connector's `format`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct MqttInputConfig {
+    /// Options for connecting to the MQTT broker.
+    pub connection_config: MqttConnectionConfig,
+
+    /// Topic filters to subscribe to, e.g., `["sensors/+/temperature",
+    /// "devices/#"]`.
+    pub topics: Vec<String>,
+
+    /// Maximum quality of service level of the subscriptions.
+    ///
+    /// With `at_least_once`, the connector acknowledges each message after
+    /// passing it to the pipeline, so that the broker redelivers messages
+    /// that were not acknowledged before a disconnection.
+    ///
+    /// Default: `at_least_once`.
+    #[serde(default)]
+    pub qos: MqttQos,
+
+    /// Subscribe to the topics as a member of this shared subscription group.
+    ///
+    /// Brokers distribute the messages of a shared subscription among all of
+    /// its members, instead of delivering every message to each of them, which
+    /// allows several pipelines or connectors to split the load.  The
+    /// connector subscribes to `$share/<shared_group>/<topic>` for each topic
+    /// filter.
+    pub shared_group: Option<String>,
+}
+
+impl MqttInputConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        self.connection_config.validate()?;
+        if self.topics.is_empty() {
+            return Err("'topics' must specify at least one topic filter".to_string());
+        }
+        for topic in &self.topics {
+            validate_topic_filter(topic)?;
+            if self.shared_group.is_some() && topic.starts_with("$share/") {
+                return Err(format!(
+                    "topic filter '{topic}' is already a shared subscription; remove 'shared_group' or the '$share/' prefix"
+                ));
+            }
+        }
+        if let Some(group) = &self.shared_group {
+            if group.is_empty() || group.contains(['/', '+', '#']) {
+                return Err(format!(
+                    "invalid 'shared_group' '{group}': it must be nonempty and must not contain '/', '+', or '#'"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the topic filters to subscribe to, including the shared
+    /// subscription prefix, if any.
+    pub fn topic_filters(&self) -> Vec<String> {
+        match &self.shared_group {
+            Some(group) => self
+                .topics
+                .iter()
+                .map(|topic| format!("$share/{group}/{topic}"))
+                .collect(),
+            None => self.topics.clone(),
+        }
+    }
+}
+
+/// Checks that `topic` is a valid MQTT topic name, i.e., a topic that
+/// messages can be published to.  Unlike topic filters, topic names must not
+/// contain wildcards.
+pub fn validate_topic_name(topic: &str) -> Result<(), String> {
+    if topic.is_empty() {
+        return Err("topic names must not be empty".to_string());
+    }
+    if topic.contains(['+', '#', '\0']) {
+        return Err(format!(
+            "'{topic}' is not a valid topic name: it must not contain wildcards ('+' or '#') or NUL characters"
+        ));
+    }
+    Ok(())
+}
+
+/// MQTT output connector configuration.
+///
+/// The connector publishes each buffer produced by the connector's `format`
+/// as a message to `topic`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct MqttOutputConfig {
+    /// Options for connecting to the MQTT broker.
+    pub connection_config: MqttConnectionConfig,
+
+    /// Topic to publish messages to, e.g., `alerts/temperature`.
+    pub topic: String,
+
+    /// Quality of service level of published messages.
+    ///
+    /// With `at_least_once`, the connector retransmits each message until the
+    /// broker acknowledges it, so messages may be duplicated if the
+    /// connection drops.  With `at_most_once`, messages that are in transit
+    /// when the connection drops are lost.
+    ///
+    /// Default: `at_least_once`.
+    #[serde(default)]
+    pub qos: MqttQos,
+
+    /// Publish messages as retained messages.
+    ///
+    /// The broker keeps the last retained message published to a topic and
+    /// delivers it to clients as soon as they subscribe, so that, e.g., a
+    /// dashboard shows the latest output without waiting for the next
+    /// update.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub retain: bool,
+}
+
+impl MqttOutputConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        self.connection_config.validate()?;
+        validate_topic_name(&self.topic)
+    }
+}
@@ -0,0 +1,370 @@
+use crate::transport::tls::TlsConfig;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Options for connecting to a NATS server.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct ConnectOptions {
+    /// URLs of one or more servers in the NATS cluster, e.g.,
+    /// `["nats://nats1:4222", "nats://nats2:4222"]`.
+    ///
+    /// The client connects to one of these servers and fails over to another
+    /// one if the connection is lost.  Servers advertised by the cluster are
+    /// also used for failover.
+    ///
+    /// Use `ws://` or `wss://` URLs to connect to the server's WebSocket
+    /// port, e.g., when only HTTPS traffic can leave the network.
+    ///
+    /// May be omitted if `context` specifies the server URLs.
+    #[serde(default)]
+    pub server_urls: Vec<String>,
+
+    /// Name of a `nats` CLI context, or path to a context file, to load
+    /// connection settings from.
+    ///
+    /// A context name refers to a file in `~/.config/nats/context/`, created
+    /// with `nats context save`, on the host that runs the pipeline.  The
+    /// server URLs, credentials, TLS files, and inbox prefix stored in the
+    /// context are used unless the corresponding setting is specified here
+    /// as well.
+    pub context: Option<String>,
+
+    /// Credentials used to authenticate with the server.
+    ///
+    /// When not specified, the connector connects without authentication.
+    pub auth: Option<Auth>,
+
+    /// TLS settings.
+    ///
+    /// When specified, the connector requires TLS when connecting to the
+    /// server.  TLS is also used when the server requests it or when a
+    /// server URL uses the `tls://` or `wss://` scheme.
+    pub tls: Option<TlsConfig>,
+
+    /// Keep retrying if no server is reachable when the connector starts,
+    /// instead of failing.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub retry_on_initial_connect: bool,
+
+    /// Try servers in the order listed in `server_urls`, instead of in random
+    /// order.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub retain_servers_order: bool,
+
+    /// Interval between pings sent to the server to check that the connection
+    /// is alive, in seconds.
+    ///
+    /// Default: 60.
+    pub ping_interval_secs: Option<u64>,
+
+    /// Timeout for establishing a connection to a server, in seconds.
+    ///
+    /// Default: 5.
+    pub connection_timeout_secs: Option<u64>,
+
+    /// Maximum number of consecutive reconnection attempts before the client
+    /// gives up.
+    ///
+    /// Default: unlimited.
+    pub max_reconnects: Option<usize>,
+
+    /// Fixed delay between reconnection attempts, in milliseconds.
+    ///
+    /// By default, the client backs off exponentially, up to a few seconds
+    /// between attempts.
+    pub reconnect_delay_ms: Option<u64>,
+
+    /// Number of client operations, such as publishes and subscription
+    /// requests, buffered while the client is disconnected or the connection
+    /// is busy.
+    ///
+    /// Default: 2048.
+    pub reconnect_buffer_size: Option<usize>,
+
+    /// Client connection name, reported to the server and shown in server
+    /// monitoring endpoints.
+    ///
+    /// Default: `feldera/<endpoint name>`.
+    pub name: Option<String>,
+
+    /// Prefix of the inbox subjects used by the client to receive replies,
+    /// e.g., from JetStream API requests.
+    ///
+    /// Servers that restrict the subjects an account can subscribe to may
+    /// require a custom prefix.
+    ///
+    /// Default: `_INBOX`.
+    pub inbox_prefix: Option<String>,
+}
+
+impl ConnectOptions {
+    /// Checks the options for errors that can be detected without connecting
+    /// to the server or reading files.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.server_urls.is_empty() && self.context.is_none() {
+            return Err(
+                "'server_urls' must specify at least one server, unless 'context' is specified"
+                    .to_string(),
+            );
+        }
+        for url in &self.server_urls {
+            if url.trim().is_empty() {
+                return Err("'server_urls' must not contain empty URLs".to_string());
+            }
+        }
+
+        match &self.auth {
+            None | Some(Auth::UserPassword { .. }) | Some(Auth::Token { .. }) => {}
+            Some(Auth::Nkey { seed, seed_path }) => {
+                exactly_one("NKey", "seed", seed, seed_path)?;
+            }
+            Some(Auth::Credentials {
+                credentials,
+                credentials_path,
+            }) => {
+                exactly_one("credentials", "credentials", credentials, credentials_path)?;
+            }
+            Some(Auth::AuthCallout {
+                sentinel_credentials,
+                sentinel_credentials_path,
+                token,
+                username,
+                password,
+            }) => {
+                exactly_one(
+                    "auth callout",
+                    "sentinel_credentials",
+                    sentinel_credentials,
+                    sentinel_credentials_path,
+                )?;
+                if password.is_some() && username.is_none() {
+                    return Err("auth callout 'password' requires a 'username'".to_string());
+                }
+                if token.is_none() && username.is_none() {
+                    return Err(
+                        "auth callout authentication requires a 'token' or a 'username'"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+
+        if let Some(tls) = &self.tls {
+            tls.validate()?;
+        }
+
+        if self.ping_interval_secs == Some(0) {
+            return Err("'ping_interval_secs' must be greater than 0".to_string());
+        }
+        if self.reconnect_buffer_size == Some(0) {
+            return Err("'reconnect_buffer_size' must be greater than 0".to_string());
+        }
+        if let Some(prefix) = &self.inbox_prefix {
+            validate_subject_pattern(prefix).map_err(|e| format!("invalid 'inbox_prefix': {e}"))?;
+            if prefix.contains(['*', '>']) {
+                return Err(format!(
+                    "invalid 'inbox_prefix': '{prefix}' must not contain wildcards"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that exactly one of `{name}` and `{name}_path` is set.
+fn exactly_one(
+    auth: &str,
+    name: &str,
+    value: &Option<String>,
+    path: &Option<String>,
+) -> Result<(), String> {
+    match (value, path) {
+        (Some(_), Some(_)) => Err(format!(
+            "'{name}' and '{name}_path' are mutually exclusive; please specify only one"
+        )),
+        (None, None) => Err(format!(
+            "{auth} authentication requires either '{name}' or '{name}_path'"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `pattern` is a valid NATS subject, optionally with `*` and `>`
+/// wildcards.
+fn validate_subject_pattern(pattern: &str) -> Result<(), String> {
+    let tokens = pattern.split('.').collect::<Vec<_>>();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_empty() {
+            return Err(format!("'{pattern}' is not a valid subject: empty token"));
+        }
+        if token.chars().any(char::is_whitespace) {
+            return Err(format!(
+                "'{pattern}' is not a valid subject: subjects must not contain whitespace"
+            ));
+        }
+        if *token == ">" && i != tokens.len() - 1 {
+            return Err(format!(
+                "'{pattern}' is not a valid subject: '>' is only allowed as the last token"
+            ));
+        }
+        if token.len() > 1 && (token.contains('*') || token.contains('>')) {
+            return Err(format!(
+                "'{pattern}' is not a valid subject: wildcards must be whole tokens"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// NATS authentication methods.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Auth {
+    /// Authenticate with a user name and password.
+    UserPassword { username: String, password: String },
+
+    /// Authenticate with a token.
+    Token { token: String },
+
+    /// Authenticate with an NKey.
+    ///
+    /// Exactly one of `seed` and `seed_path` must be specified.
+    Nkey {
+        /// NKey seed, e.g., `SUAC...`.
+        seed: Option<String>,
+
+        /// Path to a file that contains the NKey seed.
+        seed_path: Option<String>,
+    },
+
+    /// Authenticate with a user JWT and NKey seed, in the format of a NATS
+    /// `.creds` file.
+    ///
+    /// Exactly one of `credentials` and `credentials_path` must be specified.
+    Credentials {
+        /// Contents of the credentials file.
+        credentials: Option<String>,
+
+        /// Path to the credentials file.
+        credentials_path: Option<String>,
+    },
+
+    /// Authenticate with a server that delegates authentication to an
+    /// external service through [auth
+    /// callout](https://docs.nats.io/running-a-nats-service/configuration/securing_nats/auth_callout).
+    ///
+    /// The client connects with the sentinel credentials of the account and
+    /// passes `token` and/or `username` and `password` to the auth callout
+    /// service, which issues the actual user JWT.  A token is typically a JWT
+    /// issued by an identity provider.
+    ///
+    /// Exactly one of `sentinel_credentials` and `sentinel_credentials_path`
+    /// must be specified.
+    AuthCallout {
+        /// Contents of the sentinel credentials file.
+        sentinel_credentials: Option<String>,
+
+        /// Path to the sentinel credentials file.
+        sentinel_credentials_path: Option<String>,
+
+        /// Token passed to the auth callout service.
+        token: Option<String>,
+
+        /// User name passed to the auth callout service.
+        username: Option<String>,
+
+        /// Password passed to the auth callout service.
+        password: Option<String>,
+    },
+}
+
+/// NATS JetStream key-value bucket input connector configuration.
+///
+/// The connector watches a KV bucket and mirrors its contents into a table.
+/// Values must be JSON-encoded table records.  Every `put` operation on a key
+/// replaces the record previously stored under that key; `delete` and `purge`
+/// operations retract it.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct NatsKvInputConfig {
+    /// Options for connecting to the NATS server.
+    pub connection_config: ConnectOptions,
+
+    /// Name of the key-value bucket to watch.
+    pub bucket: String,
+
+    /// Only watch keys that match this pattern.
+    ///
+    /// The pattern uses NATS subject syntax, e.g., `orders.*` or `orders.>`.
+    ///
+    /// Default: all keys in the bucket.
+    pub keys: Option<String>,
+
+    /// Ingest the current contents of the bucket before watching for updates.
+    ///
+    /// When `false`, the connector only ingests changes made after it starts.
+    ///
+    /// Default: `true`.
+    #[serde(default = "default_snapshot")]
+    pub snapshot: bool,
+}
+
+impl NatsKvInputConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        self.connection_config.validate()?;
+        if self.bucket.is_empty() {
+            return Err("'bucket' must not be empty".to_string());
+        }
+        if let Some(keys) = &self.keys {
+            validate_subject_pattern(keys).map_err(|e| format!("invalid 'keys' pattern: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+fn default_snapshot() -> bool {
+    true
+}
+
+/// NATS JetStream object store input connector configuration.
+///
+/// The connector reads objects from an object store bucket and feeds their
+/// contents to the configured parser.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct NatsObjectStoreInputConfig {
+    /// Options for connecting to the NATS server.
+    pub connection_config: ConnectOptions,
+
+    /// Name of the object store bucket to read from.
+    pub bucket: String,
+
+    /// Only read objects whose names start with this prefix.
+    ///
+    /// Default: all objects in the bucket.
+    pub prefix: Option<String>,
+
+    /// Keep watching the bucket for new objects after reading its current
+    /// contents.
+    ///
+    /// When `true`, an object that is replaced with a new version is read
+    /// again.  When `false`, the connector signals end of input after reading
+    /// all objects that exist when it starts.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub watch: bool,
+}
+
+impl NatsObjectStoreInputConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        self.connection_config.validate()?;
+        if self.bucket.is_empty() {
+            return Err("'bucket' must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
@@ -56,8 +56,96 @@ pub struct PostgresWriterConfig {
     /// Default: `false`
     #[serde(default)]
     pub on_conflict_do_nothing: bool,
+
+    /// Name of a table in which the connector records the last step that it
+    /// wrote to `table`, in the same transaction as the step's changes.  This
+    /// makes the output exactly-once in a fault-tolerant pipeline: after a
+    /// restart, the connector skips the steps that it already wrote.
+    ///
+    /// The connector creates the table if it does not exist.  It keeps one
+    /// row per pipeline, endpoint, and output table, so any number of
+    /// connectors can share the table.  The name can be qualified with a
+    /// schema, e.g., `feldera.metadata`.
+    ///
+    /// Default: none, i.e., steps that the pipeline replays after a restart
+    /// are written again.
+    pub metadata_table: Option<String>,
 }
 
 fn default_max_buffer_size() -> usize {
     usize::pow(2, 20)
 }
+
+/// Postgres change data capture (CDC) input connector configuration.
+///
+/// The connector ingests the current contents of a table and then follows
+/// the changes to it using logical replication with the `pgoutput` plugin.
+/// The database must be configured with `wal_level = logical`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct PostgresCdcReaderConfig {
+    /// Postgres URI.
+    /// See: <https://docs.rs/tokio-postgres/0.7.12/tokio_postgres/config/struct.Config.html>
+    pub uri: String,
+
+    /// Table to read, optionally qualified with a schema name, e.g.,
+    /// `public.orders`.  The default schema is `public`.
+    pub table: String,
+
+    /// Name of a publication that includes `table`, created with, e.g.,
+    /// `CREATE PUBLICATION feldera FOR TABLE orders`.
+    pub publication: String,
+
+    /// Name of the logical replication slot to read changes from.  The
+    /// connector creates the slot if it does not exist.
+    ///
+    /// Each connector needs a slot of its own.  Postgres retains the
+    /// write-ahead log that the slot has not consumed, so the slot should be
+    /// dropped once it is no longer used.
+    pub slot: String,
+
+    /// Whether to ingest the contents of the table before following changes
+    /// to it.
+    ///
+    /// Default: `true`.
+    #[serde(default = "default_snapshot")]
+    #[schema(default = default_snapshot)]
+    pub snapshot: bool,
+
+    /// Interval between polls for new changes, in milliseconds.
+    ///
+    /// Default: 1000.
+    pub poll_interval_ms: Option<u64>,
+}
+
+fn default_snapshot() -> bool {
+    true
+}
+
+impl PostgresCdcReaderConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the database.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, value) in [
+            ("uri", &self.uri),
+            ("table", &self.table),
+            ("publication", &self.publication),
+        ] {
+            if value.is_empty() {
+                return Err(format!("'{name}' must not be empty"));
+            }
+        }
+        if self.slot.is_empty()
+            || self.slot.len() > 63
+            || !self
+                .slot
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+        {
+            return Err(format!("invalid 'slot' value '{}': a replication slot name must consist of 1 to 63 lowercase letters, digits, and underscores", self.slot));
+        }
+        if self.poll_interval_ms == Some(0) {
+            return Err("'poll_interval_ms' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use crate::secret_ref::is_secret_ref;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Pulsar subscription type, which determines how messages are distributed
+/// among the consumers of a subscription.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PulsarSubscriptionType {
+    /// Only one consumer may attach to the subscription.
+    #[default]
+    Exclusive,
+
+    /// Consumers attach to the subscription in turn; only one of them
+    /// receives messages at a time.
+    Failover,
+
+    /// Messages are distributed across all consumers of the subscription.
+    Shared,
+
+    /// Messages are distributed across all consumers of the subscription,
+    /// but all messages with the same key go to the same consumer.
+    KeyShared,
+}
+
+impl PulsarSubscriptionType {
+    /// Returns true if at most one consumer receives messages from the
+    /// subscription at a time, so that a consumer receives each topic's
+    /// messages in order.
+    pub fn is_ordered(&self) -> bool {
+        matches!(self, Self::Exclusive | Self::Failover)
+    }
+}
+
+/// Position in a topic from which a new subscription starts reading.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PulsarStartPosition {
+    /// Read the entire topic, starting from the oldest retained message.
+    Earliest,
+
+    /// Read only messages published after the subscription is created.
+    #[default]
+    Latest,
+}
+
+/// Apache Pulsar input connector configuration.
+///
+/// The connector consumes messages from one or more topics through a
+/// subscription and parses the payload of each message using the connector's
+/// `format`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct PulsarInputConfig {
+    /// URL of the Pulsar service, e.g., `pulsar://localhost:6650`, or
+    /// `pulsar+ssl://broker:6651` to connect over TLS.
+    pub service_url: String,
+
+    /// Topics to consume from, e.g., `persistent://public/default/events`.
+    pub topics: Vec<String>,
+
+    /// Name of the subscription.
+    pub subscription: String,
+
+    /// Type of the subscription.
+    ///
+    /// The connector only supports fault tolerance with `exclusive` and
+    /// `failover` subscriptions.
+    ///
+    /// Default: `exclusive`.
+    #[serde(default)]
+    pub subscription_type: PulsarSubscriptionType,
+
+    /// Position from which a subscription created by the connector starts
+    /// reading.  Existing subscriptions continue from where they left off.
+    ///
+    /// Default: `latest`.
+    #[serde(default)]
+    pub start_position: PulsarStartPosition,
+
+    /// Name of the consumer, which identifies the connector to the broker.
+    ///
+    /// Default: a name generated by the client.
+    pub consumer_name: Option<String>,
+
+    /// Token for authenticating to the broker with JSON Web Token
+    /// authentication.
+    ///
+    /// Default: no authentication.
+    pub auth_token: Option<String>,
+
+    /// Maximum number of messages that the broker sends to the connector
+    /// before the connector asks for more.
+    ///
+    /// Default: 1000.
+    pub receiver_queue_size: Option<u32>,
+}
+
+/// Checks that `service_url` is a Pulsar service URL, unless it is a secret
+/// reference.
+fn validate_service_url(service_url: &str) -> Result<(), String> {
+    let scheme = service_url.split_once("://").map(|(scheme, _)| scheme);
+    if !is_secret_ref(service_url) && !matches!(scheme, Some("pulsar" | "pulsar+ssl")) {
+        return Err(format!(
+            "invalid Pulsar service URL '{service_url}': expected a URL such as 'pulsar://broker:6650' or 'pulsar+ssl://broker:6651'"
+        ));
+    }
+    Ok(())
+}
+
+impl PulsarInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the broker.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_service_url(&self.service_url)?;
+        if self.topics.is_empty() {
+            return Err("'topics' must specify at least one topic".to_string());
+        }
+        if self.topics.iter().any(|topic| topic.is_empty()) {
+            return Err("'topics' must not contain empty topic names".to_string());
+        }
+        if self.subscription.is_empty() {
+            return Err("'subscription' must not be empty".to_string());
+        }
+        if self.receiver_queue_size == Some(0) {
+            return Err("'receiver_queue_size' must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Apache Pulsar output connector configuration.
+///
+/// The connector publishes each buffer produced by the connector's `format`
+/// as a message to a topic.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct PulsarOutputConfig {
+    /// URL of the Pulsar service, e.g., `pulsar://localhost:6650`, or
+    /// `pulsar+ssl://broker:6651` to connect over TLS.
+    pub service_url: String,
+
+    /// Topic to publish to, e.g., `persistent://public/default/events`.
+    ///
+    /// With fault tolerance enabled, the topic must not be partitioned.
+    pub topic: String,
+
+    /// Name of the producer, which identifies the connector to the broker.
+    ///
+    /// Default: a name generated by the broker.
+    pub producer_name: Option<String>,
+
+    /// Token for authenticating to the broker with JSON Web Token
+    /// authentication.
+    ///
+    /// Default: no authentication.
+    pub auth_token: Option<String>,
+
+    /// Maximum size of a message in bytes, which must not exceed the
+    /// broker's `maxMessageSize` setting.
+    ///
+    /// Default: 5 MiB, the broker's default limit.
+    pub max_message_size: Option<usize>,
+}
+
+impl PulsarOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the broker.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_service_url(&self.service_url)?;
+        if self.topic.is_empty() {
+            return Err("'topic' must not be empty".to_string());
+        }
+        if self.max_message_size == Some(0) {
+            return Err("'max_message_size' must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
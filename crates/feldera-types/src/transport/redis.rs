@@ -11,8 +11,276 @@ pub struct RedisOutputConfig {
     /// ":" by default.
     #[serde(default = "default_separator")]
     pub key_separator: String,
+
+    /// Redis data structure that holds the records of the view.
+    ///
+    /// Default: `keys`.
+    #[serde(default)]
+    pub data_structure: RedisDataStructure,
+
+    /// Key of the hash or sorted set that holds the records of the view.
+    ///
+    /// Required when `data_structure` is `hash` or `sorted_set`.
+    pub key: Option<String>,
+
+    /// Column whose value is the score of the record in the sorted set.
+    ///
+    /// Required when `data_structure` is `sorted_set`.
+    pub score_field: Option<String>,
 }
 
 fn default_separator() -> String {
     ":".to_string()
 }
+
+/// Redis data structure that the Redis output connector stores records in.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RedisDataStructure {
+    /// Store each record under its own Redis key (`SET`/`DEL`).
+    #[default]
+    Keys,
+
+    /// Store each record as a field of a single hash, named by the record key
+    /// (`HSET`/`HDEL`).
+    Hash,
+
+    /// Store the key of each record as a member of a single sorted set, with
+    /// the value of `score_field` as its score (`ZADD`/`ZREM`).
+    SortedSet,
+}
+
+impl RedisOutputConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.data_structure {
+            RedisDataStructure::Keys => {
+                if self.key.is_some() {
+                    return Err(
+                        "'key' only applies to the 'hash' and 'sorted_set' data structures"
+                            .to_string(),
+                    );
+                }
+            }
+            RedisDataStructure::Hash | RedisDataStructure::SortedSet => {
+                if self.key.as_ref().is_none_or(|key| key.is_empty()) {
+                    return Err(
+                        "'key' must be set for the 'hash' and 'sorted_set' data structures"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        match (self.data_structure, &self.score_field) {
+            (RedisDataStructure::SortedSet, None) => {
+                Err("'score_field' must be set for the 'sorted_set' data structure".to_string())
+            }
+            (RedisDataStructure::Keys | RedisDataStructure::Hash, Some(_)) => {
+                Err("'score_field' only applies to the 'sorted_set' data structure".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Position in a stream from which a newly created consumer group starts
+/// reading.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RedisStreamStartPosition {
+    /// Read the entire stream, starting from the first entry.
+    Earliest,
+
+    /// Read only entries added after the group is created.
+    #[default]
+    Latest,
+
+    /// Read entries following the entry with this ID, e.g.,
+    /// `1526919030474-55`.
+    Id(String),
+}
+
+/// Redis Streams input connector configuration.
+///
+/// The connector reads entries from one or more streams as a member of a
+/// consumer group and acknowledges them with `XACK` after they have been
+/// passed to the pipeline.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct RedisStreamsInputConfig {
+    /// The URL format: `redis://[<username>][:<password>@]<hostname>[:port][/[<db>][?protocol=<protocol>]]`
+    /// This is parsed by the [redis](https://docs.rs/redis/latest/redis/#connection-parameters) crate.
+    pub connection_string: String,
+
+    /// Keys of the streams to read.
+    pub streams: Vec<String>,
+
+    /// Name of the consumer group.
+    pub group: String,
+
+    /// Name of the consumer within the group.
+    ///
+    /// Entries that were delivered to the consumer but not acknowledged
+    /// before the connector stopped are read again when a connector with
+    /// the same consumer name starts, so this name should be stable across
+    /// restarts, and every connector in the group should use a different
+    /// name.
+    ///
+    /// Default: `feldera`.
+    #[serde(default = "default_consumer")]
+    pub consumer: String,
+
+    /// Create the consumer group, and the streams, if they do not exist.
+    ///
+    /// Default: `true`.
+    #[serde(default = "default_create_group")]
+    pub create_group: bool,
+
+    /// Position from which a consumer group created by the connector starts
+    /// reading.  Ignored for existing groups, which continue from where they
+    /// left off.
+    ///
+    /// Default: `latest`.
+    #[serde(default)]
+    pub start_position: RedisStreamStartPosition,
+
+    /// When the connector starts, claim entries that were delivered to other
+    /// consumers in the group but have not been acknowledged for at least
+    /// this many milliseconds, e.g., because the consumer failed.
+    ///
+    /// Default: entries pending for other consumers are not claimed.
+    pub claim_min_idle_ms: Option<u64>,
+
+    /// Parse the value of this field of each entry.
+    ///
+    /// When not set, each entry is converted to a JSON object that maps the
+    /// entry's fields to their values, which requires the `json` format.
+    pub payload_field: Option<String>,
+
+    /// Maximum number of entries to read from a stream with each request.
+    ///
+    /// Default: 1000.
+    pub batch_size: Option<usize>,
+}
+
+fn default_consumer() -> String {
+    "feldera".to_string()
+}
+
+fn default_create_group() -> bool {
+    true
+}
+
+impl RedisStreamsInputConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.streams.is_empty() {
+            return Err("'streams' must specify at least one stream key".to_string());
+        }
+        if self.group.is_empty() {
+            return Err("'group' must not be empty".to_string());
+        }
+        if self.consumer.is_empty() {
+            return Err("'consumer' must not be empty".to_string());
+        }
+        if let RedisStreamStartPosition::Id(id) = &self.start_position {
+            let valid = match id.split_once('-') {
+                Some((ms, seq)) => ms.parse::<u64>().is_ok() && seq.parse::<u64>().is_ok(),
+                None => id.parse::<u64>().is_ok(),
+            };
+            if !valid {
+                return Err(format!(
+                    "invalid 'start_position' ID '{id}': expected an ID such as '1526919030474-55'"
+                ));
+            }
+        }
+        if self.batch_size == Some(0) {
+            return Err("'batch_size' must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Redis Streams and Pub/Sub output connector configuration.
+///
+/// The connector either appends output to a stream with `XADD`, or publishes
+/// it to a Pub/Sub channel with `PUBLISH`.  Exactly one of `stream` and
+/// `channel` must be set.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct RedisStreamsOutputConfig {
+    /// The URL format: `redis://[<username>][:<password>@]<hostname>[:port][/[<db>][?protocol=<protocol>]]`
+    /// This is parsed by the [redis](https://docs.rs/redis/latest/redis/#connection-parameters) crate.
+    pub connection_string: String,
+
+    /// Key of the stream to append output to.
+    pub stream: Option<String>,
+
+    /// Pub/Sub channel to publish output to.
+    ///
+    /// Messages published to a channel are only received by clients that are
+    /// subscribed to it at the time.
+    pub channel: Option<String>,
+
+    /// Name of the stream entry field that holds the encoded output.
+    ///
+    /// Default: `payload`.
+    #[serde(default = "default_payload_field")]
+    pub payload_field: String,
+
+    /// Name of the stream entry field that holds the key of the record, for
+    /// formats that produce a separate key for each record, such as `avro`
+    /// or `json` with `key_fields`.
+    ///
+    /// Default: `key`.
+    #[serde(default = "default_key_field")]
+    pub key_field: String,
+
+    /// Trim the stream to about this many entries when appending to it
+    /// (`XADD ... MAXLEN ~ <max_len>`).
+    ///
+    /// Default: the stream is not trimmed.
+    pub max_len: Option<u64>,
+
+    /// Trim the stream to exactly `max_len` entries, rather than letting
+    /// Redis keep a few more entries when it can trim more efficiently.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub exact_trimming: bool,
+}
+
+fn default_payload_field() -> String {
+    "payload".to_string()
+}
+
+fn default_key_field() -> String {
+    "key".to_string()
+}
+
+impl RedisStreamsOutputConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.stream, &self.channel) {
+            (Some(_), Some(_)) => {
+                return Err("only one of 'stream' and 'channel' may be set".to_string())
+            }
+            (None, None) => return Err("one of 'stream' or 'channel' must be set".to_string()),
+            (Some(stream), None) if stream.is_empty() => {
+                return Err("'stream' must not be empty".to_string())
+            }
+            (None, Some(channel)) if channel.is_empty() => {
+                return Err("'channel' must not be empty".to_string())
+            }
+            _ => (),
+        }
+        if self.channel.is_some() && self.max_len.is_some() {
+            return Err("'max_len' only applies to streams, not channels".to_string());
+        }
+        if self.payload_field.is_empty() {
+            return Err("'payload_field' must not be empty".to_string());
+        }
+        if self.key_field.is_empty() {
+            return Err("'key_field' must not be empty".to_string());
+        }
+        if self.payload_field == self.key_field {
+            return Err("'payload_field' and 'key_field' must be different".to_string());
+        }
+        Ok(())
+    }
+}
@@ -43,3 +43,130 @@ pub struct S3InputConfig {
     #[serde(default = "default_max_concurrent_fetches")]
     pub max_concurrent_fetches: u32,
 }
+
+/// Encoding of the files written by the S3 and GCS output connectors.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectOutputFormat {
+    /// Newline-delimited JSON, with each change wrapped in an `insert` or
+    /// `delete` object, e.g., `{"insert": {"id": 1, "name": "x"}}`.
+    #[default]
+    Json,
+
+    /// CSV without a header, with the weight of each change (`1` for an
+    /// insertion, `-1` for a deletion) in an extra last column.
+    Csv,
+}
+
+impl ObjectOutputFormat {
+    /// File name extension for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// Checks the properties shared by the S3, GCS, and Azure Blob Storage output
+/// connectors.
+pub(crate) fn validate_object_output(
+    partition_by: &[String],
+    max_file_size_bytes: Option<u64>,
+    rollover_interval_secs: Option<u64>,
+) -> Result<(), String> {
+    for (i, column) in partition_by.iter().enumerate() {
+        if column.is_empty() {
+            return Err("'partition_by' must not contain empty column names".to_string());
+        }
+        if partition_by[..i].contains(column) {
+            return Err(format!(
+                "column '{column}' occurs more than once in 'partition_by'"
+            ));
+        }
+    }
+    if max_file_size_bytes == Some(0) {
+        return Err("'max_file_size_bytes' must be greater than 0".to_string());
+    }
+    if rollover_interval_secs == Some(0) {
+        return Err("'rollover_interval_secs' must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// Configuration for writing data to AWS S3.
+///
+/// The connector writes the changes to a view into files under `prefix`,
+/// optionally partitioned into Hive-style `column=value/` directories.  Files
+/// are rolled over when they reach `max_file_size_bytes` or when they have
+/// been open for `rollover_interval_secs`.  Each file is uploaded as a
+/// multipart upload that is only completed when the file is rolled over, so
+/// readers never observe a partially written file.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct S3OutputConfig {
+    /// AWS Access Key id. If neither this nor `aws_secret_access_key` is set,
+    /// credentials are read from the environment.
+    pub aws_access_key_id: Option<String>,
+
+    /// Secret Access Key.
+    pub aws_secret_access_key: Option<String>,
+
+    /// AWS region.
+    pub region: String,
+
+    /// S3 bucket name to write to.
+    pub bucket_name: String,
+
+    /// The endpoint URL used to communicate with this service. Can be used to make this connector
+    /// talk to non-AWS services with an S3 API.
+    pub endpoint_url: Option<String>,
+
+    /// Prefix of the keys of the written objects, e.g., `data/orders/`.
+    #[serde(default)]
+    pub prefix: String,
+
+    /// Columns to partition the output by.
+    ///
+    /// Each file only contains records with the same values of these
+    /// columns and is written under a `column=value/` path for each of them,
+    /// in order, e.g., `data/orders/region=eu/day=2025-01-01/`.
+    #[serde(default)]
+    pub partition_by: Vec<String>,
+
+    /// Encoding of the written files.
+    ///
+    /// Default: `json`.
+    #[serde(default)]
+    pub format: ObjectOutputFormat,
+
+    /// Size, in bytes, at which a file is completed and a new one is started.
+    ///
+    /// Default: 128 MiB.
+    pub max_file_size_bytes: Option<u64>,
+
+    /// Time, in seconds, after which an open file is completed, even if it
+    /// hasn't reached `max_file_size_bytes`.
+    ///
+    /// Default: 300.
+    pub rollover_interval_secs: Option<u64>,
+}
+
+impl S3OutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// accessing the bucket.
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.aws_access_key_id, &self.aws_secret_access_key) {
+            (Some(_), None) => return Err("'aws_access_key_id' set but 'aws_secret_access_key' not set; either set both or unset both to read from the environment".to_string()),
+            (None, Some(_)) => return Err("'aws_secret_access_key' set but 'aws_access_key_id' not set; either set both or unset both to read from the environment".to_string()),
+            _ => (),
+        }
+        if self.bucket_name.is_empty() {
+            return Err("'bucket_name' must not be empty".to_string());
+        }
+        validate_object_output(
+            &self.partition_by,
+            self.max_file_size_bytes,
+            self.rollover_interval_secs,
+        )
+    }
+}
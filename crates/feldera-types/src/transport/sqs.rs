@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Maximum value of [`SqsInputConfig::wait_time_secs`].
+pub const MAX_WAIT_TIME_SECS: u32 = 20;
+
+/// Maximum value of [`SqsInputConfig::max_messages`].
+pub const MAX_MESSAGES: u32 = 10;
+
+/// Maximum value of [`SqsInputConfig::visibility_timeout_secs`] (12 hours).
+pub const MAX_VISIBILITY_TIMEOUT_SECS: u32 = 43_200;
+
+/// AWS SQS input connector configuration.
+///
+/// The connector receives messages from an SQS queue and parses the body of
+/// each message using the connector's `format`.  Messages are deleted from
+/// the queue after they have been passed to the pipeline.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct SqsInputConfig {
+    /// URL of the queue, e.g.,
+    /// `https://sqs.us-east-1.amazonaws.com/123456789012/events`.
+    ///
+    /// FIFO queues, whose names end in `.fifo`, are supported.
+    pub queue_url: String,
+
+    /// AWS region of the queue, e.g., `us-east-1`.
+    pub region: String,
+
+    /// AWS Access Key id.
+    ///
+    /// Default: credentials are read from the environment.
+    pub aws_access_key_id: Option<String>,
+
+    /// Secret Access Key.
+    ///
+    /// Default: credentials are read from the environment.
+    pub aws_secret_access_key: Option<String>,
+
+    /// The endpoint URL used to communicate with this service.  Can be used to
+    /// make this connector talk to non-AWS services with an SQS API, e.g.,
+    /// LocalStack.
+    pub endpoint_url: Option<String>,
+
+    /// How long each request waits for messages to arrive when the queue is
+    /// empty (long polling), in seconds, between 0 and 20.
+    ///
+    /// Default: 20.
+    pub wait_time_secs: Option<u32>,
+
+    /// Maximum number of messages to receive with each request, between 1 and
+    /// 10.
+    ///
+    /// Default: 10.
+    pub max_messages: Option<u32>,
+
+    /// How long received messages stay hidden from other consumers of the
+    /// queue, in seconds.  The connector keeps extending the visibility
+    /// timeout of messages until it deletes them, so this only determines
+    /// how soon messages are redelivered if the connector stops.
+    ///
+    /// Default: 30.
+    pub visibility_timeout_secs: Option<u32>,
+}
+
+impl SqsInputConfig {
+    /// Returns true if the queue is a FIFO queue.
+    pub fn is_fifo(&self) -> bool {
+        self.queue_url.trim_end_matches('/').ends_with(".fifo")
+    }
+
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to SQS.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.queue_url.is_empty() {
+            return Err("'queue_url' must not be empty".to_string());
+        }
+        if self.region.is_empty() {
+            return Err("'region' must not be empty".to_string());
+        }
+        match (&self.aws_access_key_id, &self.aws_secret_access_key) {
+            (Some(_), None) => return Err("'aws_access_key_id' set but 'aws_secret_access_key' not set; either set both or unset both to read from the environment".to_string()),
+            (None, Some(_)) => return Err("'aws_secret_access_key' set but 'aws_access_key_id' not set; either set both or unset both to read from the environment".to_string()),
+            _ => {}
+        }
+        if self
+            .wait_time_secs
+            .is_some_and(|wait_time| wait_time > MAX_WAIT_TIME_SECS)
+        {
+            return Err(format!(
+                "'wait_time_secs' must be at most {MAX_WAIT_TIME_SECS}"
+            ));
+        }
+        if self
+            .max_messages
+            .is_some_and(|max_messages| !(1..=MAX_MESSAGES).contains(&max_messages))
+        {
+            return Err(format!(
+                "'max_messages' must be between 1 and {MAX_MESSAGES}"
+            ));
+        }
+        if self
+            .visibility_timeout_secs
+            .is_some_and(|timeout| !(1..=MAX_VISIBILITY_TIMEOUT_SECS).contains(&timeout))
+        {
+            return Err(format!(
+                "'visibility_timeout_secs' must be between 1 and {MAX_VISIBILITY_TIMEOUT_SECS}"
+            ));
+        }
+        Ok(())
+    }
+}
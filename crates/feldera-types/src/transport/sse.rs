@@ -0,0 +1,96 @@
+use crate::secret_ref::is_secret_ref;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Server-sent events (SSE) input connector configuration.
+///
+/// The connector maintains a long-lived connection to a URL that serves an
+/// [event stream](https://html.spec.whatwg.org/multipage/server-sent-events.html)
+/// and parses the data of each event using the connector's `format`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct SseInputConfig {
+    /// URL of the event stream, e.g., `https://stream.example.com/changes`.
+    pub url: String,
+
+    /// Additional HTTP headers to send with each request, e.g.,
+    /// `{"Authorization": "Bearer <token>"}`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Types of events to ingest, e.g., `["update"]`.  Events without an
+    /// `event` field have type `message`.
+    ///
+    /// Default: events of all types are ingested.
+    pub event_types: Option<Vec<String>>,
+
+    /// Value of the `Last-Event-ID` header to send with the first request,
+    /// so that the server starts the stream after this event.
+    ///
+    /// Default: the first request doesn't include `Last-Event-ID`.
+    pub last_event_id: Option<String>,
+
+    /// Delay, in milliseconds, before reconnecting after the connection is
+    /// closed.  A `retry` field in the event stream overrides this value.
+    ///
+    /// Default: 3000.
+    pub reconnect_delay_ms: Option<u64>,
+
+    /// Maximum delay, in milliseconds, before reconnecting.  The delay
+    /// doubles with each consecutive failed connection attempt, up to this
+    /// value.
+    ///
+    /// Default: 60000.
+    pub max_reconnect_delay_ms: Option<u64>,
+
+    /// Maximum number of consecutive failed connection attempts before the
+    /// connector reports a fatal error.
+    ///
+    /// Default: the connector keeps trying to reconnect.
+    pub max_reconnect_attempts: Option<u32>,
+}
+
+impl SseInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the server.
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_secret_ref(&self.url)
+            && !self.url.starts_with("http://")
+            && !self.url.starts_with("https://")
+        {
+            return Err(format!(
+                "'url' must be an 'http://' or 'https://' URL, but it is '{}'",
+                self.url
+            ));
+        }
+        if self
+            .event_types
+            .as_ref()
+            .is_some_and(|event_types| event_types.is_empty())
+        {
+            return Err("'event_types' must not be empty".to_string());
+        }
+        if self
+            .last_event_id
+            .as_ref()
+            .is_some_and(|id| id.is_empty() || id.contains(['\0', '\n', '\r']))
+        {
+            return Err(
+                "'last_event_id' must be a non-empty string without NUL or line break characters"
+                    .to_string(),
+            );
+        }
+        if self.reconnect_delay_ms == Some(0) {
+            return Err("'reconnect_delay_ms' must be greater than 0".to_string());
+        }
+        if let (Some(delay), Some(max)) = (self.reconnect_delay_ms, self.max_reconnect_delay_ms) {
+            if delay > max {
+                return Err(
+                    "'reconnect_delay_ms' must not be greater than 'max_reconnect_delay_ms'"
+                        .to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Transport protocol over which the syslog connector receives messages.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogProtocol {
+    /// One message per UDP datagram (RFC 5426).
+    #[default]
+    Udp,
+
+    /// Messages over TCP connections, framed either by octet counting or by
+    /// a trailing newline (RFC 6587).
+    Tcp,
+}
+
+/// Syslog input connector configuration.
+///
+/// The connector receives syslog messages in either the RFC 5424 or the
+/// RFC 3164 (BSD) format and decodes each of them into a JSON object, with
+/// fields `facility`, `severity`, `timestamp`, `hostname`, `app_name`,
+/// `proc_id`, `msg_id`, `structured_data`, and `message`, which it passes to
+/// the connector's `format`, normally `json`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct SyslogInputConfig {
+    /// Transport protocol to receive messages over.
+    ///
+    /// Default: `udp`.
+    #[serde(default)]
+    pub protocol: SyslogProtocol,
+
+    /// Address of the network interface to listen on, e.g., `127.0.0.1` to
+    /// only accept messages from the local host.
+    ///
+    /// Default: `0.0.0.0`, i.e., all IPv4 interfaces.
+    pub bind_address: Option<String>,
+
+    /// UDP or TCP port to listen on.
+    pub port: u16,
+
+    /// Maximum number of simultaneous TCP connections, with `tcp`.  Further
+    /// connections are closed immediately.
+    ///
+    /// Default: 100.
+    pub max_connections: Option<usize>,
+
+    /// Maximum size of a message, in bytes.  With `udp`, longer datagrams
+    /// are truncated.  With `tcp`, a client that sends a longer message is
+    /// disconnected.
+    ///
+    /// Default: 65535.
+    pub max_message_size: Option<usize>,
+
+    /// Pass each message to the connector's `format` as is, without decoding
+    /// it, e.g., for use with the `raw` format.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub raw: bool,
+}
+
+impl SyslogInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// listening on the port.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("'port' must not be 0".to_string());
+        }
+        if self
+            .bind_address
+            .as_ref()
+            .is_some_and(|address| address.is_empty())
+        {
+            return Err("'bind_address' must not be empty".to_string());
+        }
+        match self.max_connections {
+            Some(_) if self.protocol != SyslogProtocol::Tcp => {
+                return Err("'max_connections' requires the 'tcp' protocol".to_string());
+            }
+            Some(0) => return Err("'max_connections' must be at least 1".to_string()),
+            _ => (),
+        }
+        if self.max_message_size == Some(0) {
+            return Err("'max_message_size' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use crate::transport::tls::TlsServerConfig;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// TCP input connector configuration.
+///
+/// The connector listens for TCP connections and parses each line of text
+/// that clients send, i.e., each sequence of bytes terminated by `\n`, using
+/// the connector's `format`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct TcpInputConfig {
+    /// Address of the network interface to listen on, e.g., `127.0.0.1` to
+    /// only accept connections from the local host.
+    ///
+    /// Default: `0.0.0.0`, i.e., all IPv4 interfaces.
+    pub bind_address: Option<String>,
+
+    /// TCP port to listen on.
+    pub port: u16,
+
+    /// Maximum number of simultaneous client connections.  Further
+    /// connections are closed immediately.
+    ///
+    /// Default: 100.
+    pub max_connections: Option<usize>,
+
+    /// Maximum length of a line, in bytes, including the terminating `\n`.  A
+    /// client that sends a longer line is disconnected.
+    ///
+    /// Default: 1 MiB.
+    pub max_line_length: Option<usize>,
+
+    /// TLS settings.  If specified, clients must connect using TLS.
+    ///
+    /// Default: clients connect without TLS.
+    pub tls: Option<TlsServerConfig>,
+}
+
+impl TcpInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// listening on the port or reading files.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("'port' must not be 0".to_string());
+        }
+        if self
+            .bind_address
+            .as_ref()
+            .is_some_and(|address| address.is_empty())
+        {
+            return Err("'bind_address' must not be empty".to_string());
+        }
+        if self.max_connections == Some(0) {
+            return Err("'max_connections' must be at least 1".to_string());
+        }
+        if self.max_line_length == Some(0) {
+            return Err("'max_line_length' must be at least 1".to_string());
+        }
+        if let Some(tls) = &self.tls {
+            tls.validate()?;
+        }
+        Ok(())
+    }
+}
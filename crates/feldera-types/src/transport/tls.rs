@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// TLS settings for connections to a server.
+///
+/// Certificates and keys can be specified inline, in PEM format, or as paths
+/// to PEM files.  The inline and path variants of each setting are mutually
+/// exclusive.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct TlsConfig {
+    /// CA certificate(s) used to verify the server certificate, in PEM format.
+    ///
+    /// When neither `ca_cert_pem` nor `ca_cert_path` is specified, the server
+    /// certificate is verified against the Mozilla root certificates.
+    pub ca_cert_pem: Option<String>,
+
+    /// Path to a file with CA certificate(s) in PEM format.
+    pub ca_cert_path: Option<String>,
+
+    /// Client certificate chain in PEM format, used for mutual TLS.
+    ///
+    /// Requires a client private key.
+    pub client_cert_pem: Option<String>,
+
+    /// Path to a file with the client certificate chain in PEM format.
+    pub client_cert_path: Option<String>,
+
+    /// Client private key in PEM format.
+    pub client_key_pem: Option<String>,
+
+    /// Path to a file with the client private key in PEM format.
+    pub client_key_path: Option<String>,
+
+    /// Skip verification of the server certificate.
+    ///
+    /// This makes the connection vulnerable to man-in-the-middle attacks and
+    /// should only be used for testing.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+}
+
+impl TlsConfig {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        at_most_one("ca_cert", &self.ca_cert_pem, &self.ca_cert_path)?;
+        let client_cert =
+            at_most_one("client_cert", &self.client_cert_pem, &self.client_cert_path)?;
+        let client_key = at_most_one("client_key", &self.client_key_pem, &self.client_key_path)?;
+        match (client_cert, client_key) {
+            (true, false) => Err("client certificate specified without a client key".to_string()),
+            (false, true) => Err("client key specified without a client certificate".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// TLS settings for a server that accepts connections from clients.
+///
+/// Certificates and keys can be specified inline, in PEM format, or as paths
+/// to PEM files.  The inline and path variants of each setting are mutually
+/// exclusive.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct TlsServerConfig {
+    /// Server certificate chain in PEM format.
+    pub cert_pem: Option<String>,
+
+    /// Path to a file with the server certificate chain in PEM format.
+    pub cert_path: Option<String>,
+
+    /// Server private key in PEM format.
+    pub key_pem: Option<String>,
+
+    /// Path to a file with the server private key in PEM format.
+    pub key_path: Option<String>,
+
+    /// CA certificate(s) used to verify client certificates, in PEM format.
+    ///
+    /// When either `client_ca_cert_pem` or `client_ca_cert_path` is
+    /// specified, clients must present a certificate signed by one of these
+    /// CAs (mutual TLS).  Otherwise, clients are not asked for a certificate.
+    pub client_ca_cert_pem: Option<String>,
+
+    /// Path to a file with CA certificate(s) in PEM format used to verify
+    /// client certificates.
+    pub client_ca_cert_path: Option<String>,
+}
+
+impl TlsServerConfig {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !at_most_one("cert", &self.cert_pem, &self.cert_path)? {
+            return Err("server certificate ('cert_pem' or 'cert_path') is required".to_string());
+        }
+        if !at_most_one("key", &self.key_pem, &self.key_path)? {
+            return Err("server private key ('key_pem' or 'key_path') is required".to_string());
+        }
+        at_most_one(
+            "client_ca_cert",
+            &self.client_ca_cert_pem,
+            &self.client_ca_cert_path,
+        )?;
+        Ok(())
+    }
+}
+
+/// Checks that at most one of `{name}_pem` and `{name}_path` is set, and
+/// returns whether either one is.
+fn at_most_one(name: &str, pem: &Option<String>, path: &Option<String>) -> Result<bool, String> {
+    match (pem, path) {
+        (Some(_), Some(_)) => Err(format!(
+            "'{name}_pem' and '{name}_path' are mutually exclusive; please specify only one"
+        )),
+        (None, None) => Ok(false),
+        _ => Ok(true),
+    }
+}
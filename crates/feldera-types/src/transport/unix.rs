@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Unix domain socket input connector configuration.
+///
+/// The connector listens for connections on a Unix domain socket and parses
+/// the data that clients write to it using the connector's `format`.  The
+/// data is a stream, like a file, so the format must be able to find the
+/// boundaries between records, e.g., CSV or newline-delimited JSON.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct UnixInputConfig {
+    /// Path of the socket to create, e.g., `/run/feldera/input.sock`.
+    ///
+    /// A stale socket left at the path, e.g., by a pipeline that crashed, is
+    /// replaced.  The connector removes the socket when it stops.
+    pub path: String,
+
+    /// Maximum number of simultaneous client connections.  Further
+    /// connections are closed immediately.
+    ///
+    /// Default: 100.
+    pub max_connections: Option<usize>,
+}
+
+impl UnixInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// creating the socket.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.path.is_empty() {
+            return Err("'path' must not be empty".to_string());
+        }
+        if self.max_connections == Some(0) {
+            return Err("'max_connections' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Unix domain socket output connector configuration.
+///
+/// The connector connects to a Unix domain socket that another process, such
+/// as a sidecar, listens on, and writes the output produced by the
+/// connector's `format` to it as a stream.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct UnixOutputConfig {
+    /// Path of the socket to connect to, e.g., `/run/sidecar/output.sock`.
+    pub path: String,
+
+    /// Maximum number of consecutive attempts to reconnect to the socket after
+    /// a connection fails, with exponential backoff between attempts, before
+    /// reporting an error.
+    ///
+    /// Default: 10.
+    pub max_reconnect_attempts: Option<u32>,
+}
+
+impl UnixOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the socket.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.path.is_empty() {
+            return Err("'path' must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::secret_ref::is_secret_ref;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Webhook output connector configuration.
+///
+/// The connector sends each buffer of encoded output records to a URL in the
+/// body of an HTTP `POST` request.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct WebhookOutputConfig {
+    /// URL to send requests to, e.g., `https://hooks.example.com/feldera`.
+    pub url: String,
+
+    /// Additional HTTP headers to send with each request, e.g.,
+    /// `{"Authorization": "Bearer <token>"}`.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Value of the `Content-Type` header.
+    ///
+    /// Default: `application/json`.
+    pub content_type: Option<String>,
+
+    /// Timeout, in seconds, for each request.
+    ///
+    /// Default: 30.
+    pub timeout_secs: Option<u64>,
+
+    /// The number of times to retry a request that failed because of a
+    /// network error, a timeout, or a response with status 408, 429, or 5xx,
+    /// before reporting an error.
+    ///
+    /// Default: 5.
+    pub max_retries: Option<u32>,
+
+    /// Delay, in milliseconds, before the first retry.  The delay doubles
+    /// with each retry, up to `max_backoff_ms`.  A `Retry-After` header in
+    /// the response overrides the delay.
+    ///
+    /// Default: 100.
+    pub initial_backoff_ms: Option<u64>,
+
+    /// Maximum delay, in milliseconds, between retries.
+    ///
+    /// Default: 10000.
+    pub max_backoff_ms: Option<u64>,
+
+    /// Secret key used to sign requests.
+    ///
+    /// When set, each request includes the `X-Feldera-Timestamp` header with
+    /// the time of the request, in seconds since the Unix epoch, and the
+    /// `signature_header` header with value `sha256=<signature>`, where
+    /// `<signature>` is the hex-encoded HMAC-SHA256 of
+    /// `<timestamp>.<body>` computed with this key.
+    ///
+    /// Default: requests are not signed.
+    pub hmac_secret: Option<String>,
+
+    /// Name of the header that carries the request signature.
+    ///
+    /// Default: `X-Feldera-Signature`.
+    pub signature_header: Option<String>,
+}
+
+impl WebhookOutputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// sending requests.
+    pub fn validate(&self) -> Result<(), String> {
+        if !is_secret_ref(&self.url)
+            && !self.url.starts_with("http://")
+            && !self.url.starts_with("https://")
+        {
+            return Err(format!(
+                "'url' must be an 'http://' or 'https://' URL, but it is '{}'",
+                self.url
+            ));
+        }
+        if self.timeout_secs == Some(0) {
+            return Err("'timeout_secs' must be greater than 0".to_string());
+        }
+        if self.initial_backoff_ms == Some(0) {
+            return Err("'initial_backoff_ms' must be greater than 0".to_string());
+        }
+        if let (Some(initial), Some(max)) = (self.initial_backoff_ms, self.max_backoff_ms) {
+            if initial > max {
+                return Err(
+                    "'initial_backoff_ms' must not be greater than 'max_backoff_ms'".to_string(),
+                );
+            }
+        }
+        if self.hmac_secret.as_ref().is_some_and(|key| key.is_empty()) {
+            return Err("'hmac_secret' must not be empty".to_string());
+        }
+        if self.signature_header.is_some() && self.hmac_secret.is_none() {
+            return Err("'signature_header' is set but 'hmac_secret' is not".to_string());
+        }
+        Ok(())
+    }
+}
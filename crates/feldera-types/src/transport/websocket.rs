@@ -0,0 +1,171 @@
+use crate::secret_ref::is_secret_ref;
+use crate::transport::tls::TlsConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// WebSocket server input connector configuration.
+///
+/// The connector listens for WebSocket connections and parses each text or
+/// binary message that clients send using the connector's `format`.  Each
+/// message must contain whole records.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct WebsocketInputConfig {
+    /// Address of the network interface to listen on, e.g., `127.0.0.1` to
+    /// only accept connections from the local host.
+    ///
+    /// Default: `0.0.0.0`, i.e., all IPv4 interfaces.
+    pub bind_address: Option<String>,
+
+    /// TCP port to listen on.
+    pub port: u16,
+
+    /// Path that clients connect to, e.g., `/ingest`.  Connection requests
+    /// for other paths are rejected.
+    ///
+    /// Default: `/`.
+    pub path: Option<String>,
+
+    /// Token that clients must present to connect, either in an
+    /// `Authorization: Bearer <token>` header or, for clients such as web
+    /// browsers that cannot set headers, in a `token` query parameter.
+    ///
+    /// Default: clients are not authenticated.
+    pub auth_token: Option<String>,
+
+    /// Maximum number of simultaneous client connections.  Further connection
+    /// requests are rejected.
+    ///
+    /// Default: 100.
+    pub max_connections: Option<usize>,
+
+    /// Maximum size of a message, in bytes.  A client that sends a larger
+    /// message is disconnected.
+    ///
+    /// Default: 16 MiB.
+    pub max_message_size: Option<usize>,
+}
+
+impl WebsocketInputConfig {
+    /// Checks the configuration for errors that can be detected without
+    /// listening on the port.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port == 0 {
+            return Err("'port' must not be 0".to_string());
+        }
+        if self
+            .bind_address
+            .as_ref()
+            .is_some_and(|address| address.is_empty())
+        {
+            return Err("'bind_address' must not be empty".to_string());
+        }
+        if self
+            .path
+            .as_ref()
+            .is_some_and(|path| !path.starts_with('/'))
+        {
+            return Err("'path' must start with '/'".to_string());
+        }
+        if self
+            .auth_token
+            .as_ref()
+            .is_some_and(|token| token.is_empty())
+        {
+            return Err("'auth_token' must not be empty".to_string());
+        }
+        if self.max_connections == Some(0) {
+            return Err("'max_connections' must be at least 1".to_string());
+        }
+        if self.max_message_size == Some(0) {
+            return Err("'max_message_size' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// WebSocket client output connector configuration.
+///
+/// The connector connects to a WebSocket server and sends each buffer of
+/// output produced by the connector's `format` as a message.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+pub struct WebsocketOutputConfig {
+    /// URL of the server, e.g., `wss://example.com/feed`.
+    pub url: String,
+
+    /// HTTP headers to send with the connection request, e.g., an
+    /// `Authorization` header.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Require the server to acknowledge each message by sending a message
+    /// back, with any content, in the order that it received them.
+    ///
+    /// With acknowledgments, each step of output is complete only when the
+    /// server has acknowledged all of its messages, and messages that were not
+    /// acknowledged when a connection fails are sent again after reconnecting.
+    ///
+    /// Default: `false`.
+    #[serde(default)]
+    pub acks: bool,
+
+    /// How long to wait for the server to acknowledge the messages of a step,
+    /// in milliseconds, if `acks` is set.
+    ///
+    /// Default: 30000.
+    pub ack_timeout_ms: Option<u64>,
+
+    /// Maximum number of consecutive attempts to reconnect to the server after
+    /// a connection fails, with exponential backoff between attempts, before
+    /// reporting an error.
+    ///
+    /// Default: 10.
+    pub max_reconnect_attempts: Option<u32>,
+
+    /// Maximum size of a message, in bytes.
+    ///
+    /// Default: 1 MiB.
+    pub max_message_size: Option<usize>,
+
+    /// TLS settings.  Requires a `wss://` URL.
+    ///
+    /// Default: for `wss://` URLs, verify the server certificate against the
+    /// Mozilla root certificates, without a client certificate.
+    pub tls: Option<TlsConfig>,
+}
+
+impl WebsocketOutputConfig {
+    /// Returns true if the URL requires TLS.
+    pub fn is_tls(&self) -> bool {
+        self.url.starts_with("wss://")
+    }
+
+    /// Checks the configuration for errors that can be detected without
+    /// connecting to the server or reading files.
+    pub fn validate(&self) -> Result<(), String> {
+        // The URL is checked once secrets are resolved.
+        let url_is_secret = is_secret_ref(&self.url);
+        if !url_is_secret && !self.url.starts_with("ws://") && !self.is_tls() {
+            return Err(format!(
+                "invalid WebSocket URL '{}': the URL must start with 'ws://' or 'wss://'",
+                self.url
+            ));
+        }
+        if let Some(tls) = &self.tls {
+            if !url_is_secret && !self.is_tls() {
+                return Err(format!(
+                    "'tls' requires a 'wss://' URL, but the URL is '{}'",
+                    self.url
+                ));
+            }
+            tls.validate()?;
+        }
+        if self.ack_timeout_ms == Some(0) {
+            return Err("'ack_timeout_ms' must be at least 1".to_string());
+        }
+        if self.max_message_size == Some(0) {
+            return Err("'max_message_size' must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Catalog clients shared by the Iceberg input and output connectors.
+
+use anyhow::{anyhow, Result as AnyResult};
+use feldera_types::transport::iceberg::{
+    GlueCatalogConfig, HiveCatalogConfig, IcebergCatalogType, RestCatalogConfig,
+};
+use iceberg::{Catalog, TableIdent};
+use iceberg_catalog_glue::{
+    GlueCatalog, GlueCatalogConfig as GlueClientConfig, AWS_ACCESS_KEY_ID, AWS_PROFILE_NAME,
+    AWS_REGION_NAME, AWS_SECRET_ACCESS_KEY, AWS_SESSION_TOKEN,
+};
+use iceberg_catalog_hms::{HmsCatalog, HmsCatalogConfig, HmsThriftTransport};
+use iceberg_catalog_rest::{RestCatalog, RestCatalogConfig as RestClientConfig};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Catalog properties of an Iceberg connector configuration.
+pub(crate) struct CatalogConfig<'a> {
+    pub catalog_type: &'a IcebergCatalogType,
+    pub glue: &'a GlueCatalogConfig,
+    pub rest: &'a RestCatalogConfig,
+    pub hive: &'a HiveCatalogConfig,
+    pub fileio_config: &'a HashMap<String, String>,
+}
+
+impl CatalogConfig<'_> {
+    /// Creates a client for the catalog.
+    ///
+    /// Assumes that the configuration has been validated, so that the
+    /// properties required by the catalog type are set.
+    pub(crate) async fn connect(&self) -> AnyResult<Arc<dyn Catalog>> {
+        match self.catalog_type {
+            IcebergCatalogType::Glue => self.connect_glue().await,
+            IcebergCatalogType::Rest => Ok(self.connect_rest()),
+            IcebergCatalogType::Hive => self.connect_hive(),
+        }
+    }
+
+    async fn connect_glue(&self) -> AnyResult<Arc<dyn Catalog>> {
+        let builder = GlueClientConfig::builder()
+            .warehouse(self.glue.warehouse.as_ref().unwrap().clone())
+            .catalog_id_opt(self.glue.id.clone())
+            .uri_opt(self.glue.endpoint.clone());
+
+        let mut props = self.fileio_config.clone();
+        for (key, value) in [
+            (AWS_ACCESS_KEY_ID, &self.glue.access_key_id),
+            (AWS_SECRET_ACCESS_KEY, &self.glue.secret_access_key),
+            (AWS_SESSION_TOKEN, &self.glue.session_token),
+            (AWS_PROFILE_NAME, &self.glue.profile_name),
+            (AWS_REGION_NAME, &self.glue.region),
+        ] {
+            if let Some(value) = value {
+                props.insert(key.to_string(), value.clone());
+            }
+        }
+
+        let catalog = GlueCatalog::new(builder.props(props).build())
+            .await
+            .map_err(|e| anyhow!("error creating Glue catalog client: {e}"))?;
+        Ok(Arc::new(catalog))
+    }
+
+    fn connect_rest(&self) -> Arc<dyn Catalog> {
+        let builder = RestClientConfig::builder()
+            .uri(self.rest.uri.as_ref().unwrap().clone())
+            .warehouse_opt(self.rest.warehouse.clone());
+
+        let mut props = self.fileio_config.clone();
+        for (key, value) in [
+            ("audience", &self.rest.audience),
+            ("resource", &self.rest.resource),
+            ("credential", &self.rest.credential),
+            ("oauth2-server-uri", &self.rest.oauth2_server_uri),
+            ("prefix", &self.rest.prefix),
+            ("scope", &self.rest.scope),
+            ("token", &self.rest.token),
+        ] {
+            if let Some(value) = value {
+                props.insert(key.to_string(), value.clone());
+            }
+        }
+        if let Some(headers) = &self.rest.headers {
+            for (header, val) in headers.iter() {
+                props.insert(format!("header.{header}"), val.clone());
+            }
+        };
+
+        Arc::new(RestCatalog::new(builder.props(props).build()))
+    }
+
+    fn connect_hive(&self) -> AnyResult<Arc<dyn Catalog>> {
+        let uri = self.hive.uri.as_ref().unwrap();
+        let config = HmsCatalogConfig::builder()
+            .address(uri.trim_start_matches("thrift://").to_string())
+            .thrift_transport(HmsThriftTransport::Buffered)
+            .warehouse(self.hive.warehouse.as_ref().unwrap().clone())
+            .props(self.fileio_config.clone())
+            .build();
+
+        let catalog = HmsCatalog::new(config).map_err(|e| {
+            anyhow!("error creating Hive Metastore catalog client for '{uri}': {e}")
+        })?;
+        Ok(Arc::new(catalog))
+    }
+}
+
+/// Parses a table name in the "namespace.table" format.
+pub(crate) fn parse_table_name(table_name: &str) -> AnyResult<TableIdent> {
+    TableIdent::from_strs(table_name.split('.'))
+        .map_err(|e| anyhow!("'table_name' property specifies an invalid Iceberg table name: {e}"))
+}
@@ -1,8 +1,9 @@
+use crate::catalog::{parse_table_name, CatalogConfig};
 use crate::iceberg_input_serde_config;
 use anyhow::{anyhow, bail, Error as AnyError, Result as AnyResult};
 use chrono::{DateTime, Utc};
 use datafusion::{
-    arrow::array::AsArray,
+    arrow::array::{AsArray, RecordBatch},
     prelude::{DataFrame, SQLOptions, SessionContext},
 };
 use dbsp::circuit::tokio::TOKIO;
@@ -11,12 +12,15 @@ use feldera_adapterlib::{
     errors::journal::ControllerError,
     format::ParseError,
     transport::{
-        InputConsumer, InputEndpoint, InputQueue, InputReader, InputReaderCommand,
-        IntegratedInputEndpoint, NonFtInputReaderCommand,
+        parse_resume_info, InputConsumer, InputEndpoint, InputQueue, InputReader,
+        InputReaderCommand, IntegratedInputEndpoint, Resume, Watermark,
     },
-    utils::datafusion::{
-        execute_query_collect, execute_singleton_query, timestamp_to_sql_expression,
-        validate_sql_expression, validate_timestamp_column,
+    utils::{
+        calculate_backoff_delay,
+        datafusion::{
+            execute_query_collect, execute_singleton_query, timestamp_to_sql_expression,
+            validate_sql_expression, validate_timestamp_column,
+        },
     },
     PipelineState,
 };
@@ -25,24 +29,35 @@ use feldera_types::{
     program_schema::Relation,
     transport::iceberg::{IcebergCatalogType, IcebergReaderConfig},
 };
-use futures_util::StreamExt;
-use iceberg::{io::FileIO, spec::TableMetadata, table::Table as IcebergTable, Catalog, TableIdent};
-use iceberg_catalog_glue::{
-    GlueCatalog, GlueCatalogConfig, AWS_ACCESS_KEY_ID, AWS_PROFILE_NAME, AWS_REGION_NAME,
-    AWS_SECRET_ACCESS_KEY, AWS_SESSION_TOKEN,
+use futures_util::{stream, StreamExt, TryStreamExt};
+use iceberg::{
+    io::FileIO,
+    scan::FileScanTask,
+    spec::{Operation, SnapshotRef, TableMetadata},
+    table::Table as IcebergTable,
+    Catalog, TableIdent,
 };
-use iceberg_catalog_rest::{RestCatalog, RestCatalogConfig};
 use iceberg_datafusion::IcebergTableProvider;
-use log::{debug, info, trace};
-use std::{sync::Arc, thread};
+use log::{debug, info, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
 use tokio::{
     select,
     sync::{
         mpsc,
         watch::{channel, Receiver, Sender},
     },
+    time::sleep,
 };
 
+/// Polling interval when following an Iceberg table.
+const POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
 enum SnapshotDescr {
     /// Open the latest snapshot (default)
     Latest,
@@ -75,7 +90,7 @@ impl IcebergInputEndpoint {
 
 impl InputEndpoint for IcebergInputEndpoint {
     fn fault_tolerance(&self) -> Option<FtModel> {
-        None
+        Some(FtModel::AtLeastOnce)
     }
 }
 
@@ -83,11 +98,12 @@ impl IntegratedInputEndpoint for IcebergInputEndpoint {
     fn open(
         self: Box<Self>,
         input_handle: &InputCollectionHandle,
-        _seek: Option<serde_json::Value>,
+        resume_info: Option<serde_json::Value>,
     ) -> AnyResult<Box<dyn InputReader>> {
         Ok(Box::new(IcebergInputReader::new(
             &self.inner,
             input_handle,
+            resume_info,
         )?))
     }
 }
@@ -101,6 +117,7 @@ impl IcebergInputReader {
     fn new(
         endpoint: &Arc<IcebergInputEndpointInner>,
         input_handle: &InputCollectionHandle,
+        resume_info: Option<serde_json::Value>,
     ) -> AnyResult<Self> {
         // TODO: perform validation as part of config deserialization.
         endpoint
@@ -108,11 +125,46 @@ impl IcebergInputReader {
             .validate_catalog_config()
             .map_err(|e| anyhow!(e))?;
 
-        if endpoint.config.follow() {
-            bail!("'{}' mode is not yet supported", endpoint.config.mode);
+        if endpoint.config.follow() && endpoint.config.catalog_type.is_none() {
+            bail!(
+                "'{}' mode requires an Iceberg catalog: set the 'catalog_type' property instead of 'metadata_location'",
+                endpoint.config.mode
+            );
         }
 
         let (sender, receiver) = channel(PipelineState::Paused);
+
+        if let Some(resume_info) = resume_info {
+            let resume_info = parse_resume_info::<IcebergResumeInfo>(&resume_info)?;
+            if let Some(snapshot_id) = resume_info.snapshot_id {
+                info!(
+                    "iceberg {}: resuming from table snapshot {snapshot_id}",
+                    &endpoint.endpoint_name
+                );
+            } else {
+                info!(
+                    "iceberg {}: resuming from clean state",
+                    &endpoint.endpoint_name
+                );
+            }
+            *endpoint.last_resume_status.lock().unwrap() = Some(resume_info);
+        }
+
+        if matches!(
+            *endpoint.last_resume_status.lock().unwrap(),
+            Some(IcebergResumeInfo { eoi: true, .. })
+        ) {
+            endpoint.consumer.eoi();
+            info!(
+                "iceberg {}: skipping connector initialization because the connector is already in the end-of-input state",
+                &endpoint.endpoint_name
+            );
+            return Ok(Self {
+                sender,
+                inner: endpoint.clone(),
+            });
+        }
+
         let endpoint_clone = endpoint.clone();
         let receiver_clone = receiver.clone();
 
@@ -149,9 +201,57 @@ impl IcebergInputReader {
 
 impl InputReader for IcebergInputReader {
     fn request(&self, command: InputReaderCommand) {
-        match command.as_nonft().unwrap() {
-            NonFtInputReaderCommand::Queue => self.inner.queue.queue(),
-            NonFtInputReaderCommand::Transition(state) => drop(self.sender.send_replace(state)),
+        match command {
+            InputReaderCommand::Replay { .. } => panic!(
+                "replay command is not supported by IcebergInputReader; this is a bug, please report it to developers"),
+            InputReaderCommand::Extend => {
+                let _ = self.sender.send_replace(PipelineState::Running);
+            }
+            InputReaderCommand::Pause => {
+                let _ = self.sender.send_replace(PipelineState::Paused);
+            }
+            InputReaderCommand::Queue {
+                checkpoint_requested,
+            } => {
+                // When initiating a checkpoint, try to stop at a snapshot boundary.
+                let stop_at: &dyn Fn(&Option<IcebergResumeInfo>) -> bool = if checkpoint_requested
+                {
+                    &|resume_info: &Option<IcebergResumeInfo>| resume_info.is_some()
+                } else {
+                    &|_: &Option<IcebergResumeInfo>| false
+                };
+                let (total, _, resume_info) = self.inner.queue.flush_with_aux_until(stop_at);
+                let resume_status = resume_info
+                    .last()
+                    .map(|(_ts, resume_info)| resume_info.clone())
+                    .unwrap_or_else(|| self.inner.last_resume_status.lock().unwrap().clone());
+                *self.inner.last_resume_status.lock().unwrap() = resume_status.clone();
+
+                let resume = match resume_status {
+                    None => Resume::Barrier,
+                    Some(iceberg_resume_info) => Resume::Seek {
+                        seek: serde_json::to_value(iceberg_resume_info).unwrap(),
+                    },
+                };
+
+                // We use the same format (IcebergResumeInfo) for resume info and watermark metadata.
+                self.inner.consumer.extended(
+                    total,
+                    Some(resume),
+                    resume_info
+                        .into_iter()
+                        .map(|(timestamp, metadata)| {
+                            Watermark::new(
+                                timestamp,
+                                metadata.map(|m| serde_json::to_value(m).unwrap()),
+                            )
+                        })
+                        .collect(),
+                );
+            }
+            InputReaderCommand::Disconnect => {
+                let _ = self.sender.send_replace(PipelineState::Terminated);
+            }
         }
     }
 
@@ -166,12 +266,34 @@ impl Drop for IcebergInputReader {
     }
 }
 
+/// Resume info stored in each checkpoint for the IcebergInputEndpoint.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+struct IcebergResumeInfo {
+    /// Table snapshot where the connector stopped reading before the checkpoint,
+    /// `None` if the connector hasn't started reading yet.
+    snapshot_id: Option<i64>,
+    /// True if the connector reached the end-of-input state.
+    eoi: bool,
+}
+
+impl IcebergResumeInfo {
+    fn new(snapshot_id: Option<i64>, eoi: bool) -> Self {
+        Self { snapshot_id, eoi }
+    }
+}
+
 struct IcebergInputEndpointInner {
     endpoint_name: String,
     config: IcebergReaderConfig,
     consumer: Box<dyn InputConsumer>,
     datafusion: SessionContext,
-    queue: InputQueue,
+
+    /// The latest resume status of this endpoint:
+    /// * Initialized to `None` or `Some(snapshot_id)` (when resume_info is specified) on initialization.
+    /// * Updated to `Some(new_snapshot_id)` after ingesting the initial snapshot and after
+    ///   ingesting each new snapshot of the table in follow mode.
+    last_resume_status: Mutex<Option<IcebergResumeInfo>>,
+    queue: InputQueue<Option<IcebergResumeInfo>>,
 }
 
 impl IcebergInputEndpointInner {
@@ -186,16 +308,28 @@ impl IcebergInputEndpointInner {
             config,
             consumer,
             datafusion: SessionContext::new(),
+            // Set snapshot to None by default so that the connector is checkpointable in the initial state.
+            last_resume_status: Mutex::new(Some(IcebergResumeInfo::new(None, false))),
             queue,
         }
     }
 
+    /// Snapshot of the table where the connector stopped reading before the
+    /// checkpoint it resumed from, if any.
+    fn resume_snapshot_id(&self) -> Option<i64> {
+        self.last_resume_status
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|resume_info| resume_info.snapshot_id)
+    }
+
     fn table_ident(&self) -> Option<Result<TableIdent, ControllerError>> {
         self.config.table_name.as_ref().map(|table_name| {
-            TableIdent::from_strs(table_name.split('.')).map_err(|e| {
+            parse_table_name(table_name).map_err(|e| {
                 ControllerError::invalid_transport_configuration(
                     &self.endpoint_name,
-                    &format!("'table_name' property specifies an invalid Iceberg table name: {e}"),
+                    &e.to_string(),
                 )
             })
         })
@@ -406,7 +540,18 @@ impl IcebergInputEndpointInner {
         mut receiver: Receiver<PipelineState>,
         init_status_sender: mpsc::Sender<Result<(), ControllerError>>,
     ) {
-        let table = match self.open_table().await {
+        let catalog = match &self.config.catalog_type {
+            None => None,
+            Some(catalog_type) => match self.connect_catalog(catalog_type).await {
+                Err(e) => {
+                    let _ = init_status_sender.send(Err(e)).await;
+                    return;
+                }
+                Ok(catalog) => Some(catalog),
+            },
+        };
+
+        let table = match self.open_table(catalog.as_deref()).await {
             Err(e) => {
                 let _ = init_status_sender.send(Err(e)).await;
                 return;
@@ -416,37 +561,293 @@ impl IcebergInputEndpointInner {
 
         let table = Arc::new(table);
 
-        if let Err(e) = self.prepare_snapshot_query(&table, &schema).await {
-            let _ = init_status_sender.send(Err(e)).await;
-            return;
+        // When resuming from a checkpoint, skip the initial snapshot and follow the table
+        // from the snapshot recorded in the checkpoint.
+        let resume_snapshot_id = self.resume_snapshot_id();
+        let clean = resume_snapshot_id.is_none();
+
+        let snapshot_id = match resume_snapshot_id {
+            Some(snapshot_id) => Some(snapshot_id),
+            None => match self.start_snapshot_id(&table) {
+                Err(e) => {
+                    let _ = init_status_sender.send(Err(e)).await;
+                    return;
+                }
+                Ok(snapshot_id) => snapshot_id,
+            },
         };
 
+        if clean {
+            if let Err(e) = self
+                .prepare_snapshot_query(&table, snapshot_id, &schema)
+                .await
+            {
+                let _ = init_status_sender.send(Err(e)).await;
+                return;
+            };
+        }
+
+        // If we are about to follow the table, set resume state to the starting snapshot, otherwise
+        // the connector will remain in the barrier state until at least one snapshot is added to the table.
+        if clean && !self.config.snapshot() {
+            *self.last_resume_status.lock().unwrap() =
+                Some(IcebergResumeInfo::new(snapshot_id, false));
+        }
+
         // Code before this point is part of endpoint initialization.
         // After this point, the thread should continue running until it receives a
         // shutdown command from the controller.
         let _ = init_status_sender.send(Ok(())).await;
 
-        if self.config.snapshot() && self.config.timestamp_column.is_none() {
-            // Read snapshot chunk-by-chunk.
-            self.read_unordered_snapshot(input_stream.as_mut(), &mut receiver)
-                .await;
-        } else if self.config.snapshot() {
-            // Read the entire snapshot in one query.
-            self.read_ordered_snapshot(input_stream.as_mut(), &schema, &mut receiver)
-                .await;
+        if clean && self.config.snapshot() {
+            // Use the time when we started reading the snapshot as the ingestion timestamp for the snapshot.
+            let timestamp = Utc::now();
+
+            if self.config.timestamp_column.is_none() {
+                // Read snapshot chunk-by-chunk.
+                self.read_unordered_snapshot(input_stream.as_mut(), &mut receiver)
+                    .await;
+            } else {
+                // Read the entire snapshot in one query.
+                self.read_ordered_snapshot(input_stream.as_mut(), &schema, &mut receiver)
+                    .await;
+            }
+
+            // Empty buffer to indicate checkpointable state.
+            self.queue.push_with_aux(
+                (None, Vec::new()),
+                timestamp,
+                Some(IcebergResumeInfo::new(snapshot_id, !self.config.follow())),
+            );
         };
 
-        self.consumer.eoi();
+        // Start following the table if required by the configuration.
+        if self.config.follow() {
+            // Safe due to the check in `IcebergInputReader::new`.
+            let catalog = catalog.unwrap();
+            if let Err(e) = self
+                .follow(
+                    catalog.as_ref(),
+                    snapshot_id,
+                    input_stream.as_mut(),
+                    &mut receiver,
+                )
+                .await
+            {
+                self.consumer.error(true, e, Some("iceberg-follow"));
+            }
+        } else {
+            self.consumer.eoi();
+        }
+    }
+
+    /// Ingest the changes made by each new snapshot of the table, starting
+    /// from the snapshot after `snapshot_id`.
+    ///
+    /// Polls the catalog for new snapshots every [`POLL_INTERVAL`]. Each
+    /// snapshot is ingested by comparing its set of data files with the set of
+    /// data files of the previous snapshot: records in data files that were
+    /// added by the snapshot are inserted, and records in data files that it
+    /// removed are deleted.
+    async fn follow(
+        &self,
+        catalog: &dyn Catalog,
+        mut snapshot_id: Option<i64>,
+        input_stream: &mut dyn ArrowStream,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        // Safe due to checks in 'validate_catalog_config'.
+        let table_ident = self.table_ident().unwrap()?;
+
+        let mut files = None;
+        let mut retry_count = 0;
+
+        loop {
+            wait_running(receiver).await;
+
+            let table = match catalog.load_table(&table_ident).await {
+                Ok(table) => {
+                    retry_count = 0;
+                    table
+                }
+                Err(e) => {
+                    retry_count += 1;
+                    if retry_count == 20 {
+                        bail!("error loading Iceberg table after {retry_count} attempts: {e}");
+                    }
+                    let backoff_delay = calculate_backoff_delay(retry_count - 1);
+                    warn!(
+                        "iceberg {}: error loading Iceberg table after {retry_count} attempts: {e}; retrying in {backoff_delay:?}",
+                        &self.endpoint_name,
+                    );
+                    sleep(backoff_delay).await;
+                    continue;
+                }
+            };
+
+            let snapshots = snapshots_after(table.metadata(), snapshot_id)?;
+            if snapshots.is_empty() {
+                sleep(POLL_INTERVAL).await;
+                continue;
+            }
+
+            let mut old_files = match files.take() {
+                Some(files) => files,
+                None => data_files(&table, snapshot_id).await?,
+            };
+
+            for snapshot in snapshots {
+                let new_files = data_files(&table, Some(snapshot.snapshot_id())).await?;
+                self.ingest_snapshot(
+                    &table,
+                    &snapshot,
+                    &old_files,
+                    &new_files,
+                    input_stream,
+                    receiver,
+                )
+                .await?;
+                old_files = new_files;
+                snapshot_id = Some(snapshot.snapshot_id());
+            }
+            files = Some(old_files);
+        }
+    }
+
+    /// Ingest the changes made by `snapshot`, given the data files of the table
+    /// before (`old_files`) and after (`new_files`) the snapshot.
+    async fn ingest_snapshot(
+        &self,
+        table: &IcebergTable,
+        snapshot: &SnapshotRef,
+        old_files: &BTreeMap<String, FileScanTask>,
+        new_files: &BTreeMap<String, FileScanTask>,
+        input_stream: &mut dyn ArrowStream,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        let snapshot_id = snapshot.snapshot_id();
+        let summary = snapshot.summary();
+
+        // Use the time when we _started_ reading the snapshot as the ingestion timestamp.
+        let timestamp = Utc::now();
+
+        if summary.operation == Operation::Replace {
+            // Replace operations, e.g., compaction, rewrite data files without changing the
+            // contents of the table.
+            debug!(
+                "iceberg {}: skipping snapshot {snapshot_id}, which doesn't change table contents",
+                &self.endpoint_name,
+            );
+        } else {
+            let num_delete_files = summary
+                .additional_properties
+                .get("added-delete-files")
+                .and_then(|n| n.parse::<u64>().ok())
+                .unwrap_or(0);
+            if num_delete_files > 0 {
+                bail!("table snapshot {snapshot_id} adds {num_delete_files} delete file(s): the Iceberg connector does not yet support following tables with row-level deletes; configure the table to use copy-on-write mode for deletes, updates, and merges");
+            }
+
+            info!(
+                "iceberg {}: reading changes in table snapshot {snapshot_id}",
+                &self.endpoint_name,
+            );
+
+            let removed = old_files
+                .iter()
+                .filter(|(path, _)| !new_files.contains_key(*path))
+                .map(|(_, task)| task.clone())
+                .collect::<Vec<_>>();
+            let added = new_files
+                .iter()
+                .filter(|(path, _)| !old_files.contains_key(*path))
+                .map(|(_, task)| task.clone())
+                .collect::<Vec<_>>();
+
+            self.read_data_files(table, removed, false, snapshot_id, input_stream, receiver)
+                .await?;
+            self.read_data_files(table, added, true, snapshot_id, input_stream, receiver)
+                .await?;
+        }
+
+        // Empty buffer to indicate checkpointable state.
+        self.queue.push_with_aux(
+            (None, Vec::new()),
+            timestamp,
+            Some(IcebergResumeInfo::new(Some(snapshot_id), false)),
+        );
+
+        Ok(())
+    }
+
+    /// Read data files and push their records to the circuit.
+    ///
+    /// * `polarity` - determines whether records in the files should be
+    ///   inserted to or deleted from the table.
+    async fn read_data_files(
+        &self,
+        table: &IcebergTable,
+        tasks: Vec<FileScanTask>,
+        polarity: bool,
+        snapshot_id: i64,
+        input_stream: &mut dyn ArrowStream,
+        receiver: &mut Receiver<PipelineState>,
+    ) -> AnyResult<()> {
+        if tasks.is_empty() {
+            return Ok(());
+        }
+
+        wait_running(receiver).await;
+
+        let descr = format!(
+            "{} data files in table snapshot {snapshot_id}",
+            if polarity { "added" } else { "removed" }
+        );
+
+        let mut stream = table
+            .reader_builder()
+            .build()
+            .read(stream::iter(tasks.into_iter().map(Ok)).boxed())
+            .map_err(|e| anyhow!("error reading {descr}: {e}"))?;
+
+        let mut num_batches = 0;
+
+        // Use the timestamp when we start retrieving the next batch as the ingestion timestamp.
+        let mut timestamp = Utc::now();
+
+        while let Some(batch) = stream.next().await {
+            wait_running(receiver).await;
+
+            let batch = match batch {
+                Ok(batch) => batch,
+                Err(e) => {
+                    self.consumer.error(
+                        false,
+                        anyhow!("error retrieving batch {num_batches} of {descr}: {e:?}"),
+                        Some("iceberg-batch"),
+                    );
+                    continue;
+                }
+            };
+            num_batches += 1;
+            self.push_batch(&batch, polarity, input_stream, timestamp);
+
+            timestamp = Utc::now();
+        }
+
+        Ok(())
     }
 
     /// Open existing iceberg table.  Use snapshot id or timestamp specified in the configuration, if any.
-    async fn open_table(&self) -> Result<IcebergTable, ControllerError> {
+    async fn open_table(
+        &self,
+        catalog: Option<&dyn Catalog>,
+    ) -> Result<IcebergTable, ControllerError> {
         debug!("iceberg {}: opening iceberg table", &self.endpoint_name);
 
-        match self.config.catalog_type {
+        match catalog {
             None => self.open_table_no_catalog().await,
-            Some(IcebergCatalogType::Glue) => self.open_table_glue().await,
-            Some(IcebergCatalogType::Rest) => self.open_table_rest().await,
+            Some(catalog) => self.open_table_catalog(catalog).await,
         }
 
         // // TODO: Validate that table schema matches relation schema
@@ -509,153 +910,26 @@ impl IcebergInputEndpointInner {
             })
     }
 
-    async fn open_table_glue(&self) -> Result<IcebergTable, ControllerError> {
-        let builder = GlueCatalogConfig::builder().warehouse(
-            self.config
-                .glue_catalog_config
-                .warehouse
-                .as_ref()
-                .unwrap()
-                .clone(),
-        );
-
-        let builder = builder.catalog_id_opt(self.config.glue_catalog_config.id.clone());
-        let builder = builder.uri_opt(self.config.glue_catalog_config.endpoint.clone());
-
-        let mut props = self.config.fileio_config.clone();
-
-        self.config
-            .glue_catalog_config
-            .access_key_id
-            .as_ref()
-            .map(|aws_access_key_id| {
-                props.insert(AWS_ACCESS_KEY_ID.to_string(), aws_access_key_id.clone())
-            });
-
-        self.config
-            .glue_catalog_config
-            .secret_access_key
-            .as_ref()
-            .map(|aws_secret_access_key| {
-                props.insert(
-                    AWS_SECRET_ACCESS_KEY.to_string(),
-                    aws_secret_access_key.clone(),
-                )
-            });
-
-        self.config
-            .glue_catalog_config
-            .session_token
-            .as_ref()
-            .map(|session_token| {
-                props.insert(AWS_SESSION_TOKEN.to_string(), session_token.clone())
-            });
-
-        self.config
-            .glue_catalog_config
-            .profile_name
-            .as_ref()
-            .map(|profile_name| props.insert(AWS_PROFILE_NAME.to_string(), profile_name.clone()));
-
-        self.config
-            .glue_catalog_config
-            .region
-            .as_ref()
-            .map(|region_name| props.insert(AWS_REGION_NAME.to_string(), region_name.clone()));
-
-        let builder = builder.props(props);
-
-        let catalog_config = builder.build();
-
-        let catalog = GlueCatalog::new(catalog_config).await.map_err(|e| {
-            ControllerError::input_transport_error(
-                &self.endpoint_name,
-                true,
-                anyhow!("error creating Glue catalog client: {e}"),
-            )
-        })?;
-
-        let table_ident = self.table_ident().unwrap()?;
-
-        catalog.load_table(&table_ident).await.map_err(|e| {
-            ControllerError::input_transport_error(
-                &self.endpoint_name,
-                true,
-                anyhow!("error loading Iceberg table: {e}"),
-            )
-        })
+    async fn connect_catalog(
+        &self,
+        catalog_type: &IcebergCatalogType,
+    ) -> Result<Arc<dyn Catalog>, ControllerError> {
+        CatalogConfig {
+            catalog_type,
+            glue: &self.config.glue_catalog_config,
+            rest: &self.config.rest_catalog_config,
+            hive: &self.config.hive_catalog_config,
+            fileio_config: &self.config.fileio_config,
+        }
+        .connect()
+        .await
+        .map_err(|e| ControllerError::input_transport_error(&self.endpoint_name, true, e))
     }
 
-    async fn open_table_rest(&self) -> Result<IcebergTable, ControllerError> {
-        let builder = RestCatalogConfig::builder().uri(
-            self.config
-                .rest_catalog_config
-                .uri
-                .as_ref()
-                .unwrap()
-                .clone(),
-        );
-
-        let builder = builder.warehouse_opt(self.config.rest_catalog_config.warehouse.clone());
-
-        let mut props = self.config.fileio_config.clone();
-
-        self.config
-            .rest_catalog_config
-            .audience
-            .as_ref()
-            .map(|audience| props.insert("audience".to_string(), audience.clone()));
-
-        self.config
-            .rest_catalog_config
-            .resource
-            .as_ref()
-            .map(|resource| props.insert("resource".to_string(), resource.clone()));
-
-        self.config
-            .rest_catalog_config
-            .credential
-            .as_ref()
-            .map(|credential| props.insert("credential".to_string(), credential.clone()));
-
-        self.config
-            .rest_catalog_config
-            .oauth2_server_uri
-            .as_ref()
-            .map(|oauth2_server_uri| {
-                props.insert("oauth2-server-uri".to_string(), oauth2_server_uri.clone())
-            });
-
-        self.config
-            .rest_catalog_config
-            .prefix
-            .as_ref()
-            .map(|prefix| props.insert("prefix".to_string(), prefix.clone()));
-
-        self.config
-            .rest_catalog_config
-            .scope
-            .as_ref()
-            .map(|scope| props.insert("scope".to_string(), scope.clone()));
-
-        self.config
-            .rest_catalog_config
-            .token
-            .as_ref()
-            .map(|token| props.insert("token".to_string(), token.clone()));
-
-        if let Some(headers) = &self.config.rest_catalog_config.headers {
-            for (header, val) in headers.iter() {
-                props.insert(format!("header.{header}"), val.clone());
-            }
-        };
-
-        let builder = builder.props(props);
-
-        let catalog_config = builder.build();
-
-        let catalog = RestCatalog::new(catalog_config);
-
+    async fn open_table_catalog(
+        &self,
+        catalog: &dyn Catalog,
+    ) -> Result<IcebergTable, ControllerError> {
         let table_ident = self.table_ident().unwrap()?;
 
         catalog.load_table(&table_ident).await.map_err(|e| {
@@ -681,6 +955,39 @@ impl IcebergInputEndpointInner {
         Ok(())
     }
 
+    /// Find the snapshot specified in the configuration: the snapshot to read in
+    /// `snapshot` and `snapshot_and_follow` modes, and the snapshot after which to
+    /// start following the table in `follow` and `snapshot_and_follow` modes.
+    ///
+    /// Returns `None` if the table doesn't have any snapshots yet.
+    fn start_snapshot_id(&self, table: &IcebergTable) -> Result<Option<i64>, ControllerError> {
+        match self.snapshot_descr()? {
+            SnapshotDescr::SnapshotId(snapshot_id) => Ok(Some(snapshot_id)),
+            SnapshotDescr::Timestamp(ts) => {
+                let ts_ms = ts.timestamp_millis();
+                let snapshot_log = table
+                    .metadata()
+                    .history()
+                    .iter()
+                    .rev()
+                    .find(|log| log.timestamp_ms() <= ts_ms);
+                if let Some(snapshot_log) = snapshot_log {
+                    Ok(Some(snapshot_log.snapshot_id))
+                } else {
+                    Err(ControllerError::input_transport_error(
+                        &self.endpoint_name,
+                        true,
+                        anyhow!("Iceberg connector configuration specifies timestamp {ts}; however Iceberg table does not contain a snapshot with the same or earlier timestamp"),
+                    ))
+                }
+            }
+            SnapshotDescr::Latest => Ok(table
+                .metadata()
+                .current_snapshot()
+                .map(|snapshot| snapshot.snapshot_id())),
+        }
+    }
+
     /// Prepare to read initial snapshot, if required by endpoint configuration.
     ///
     /// * register snapshot as a datafusion table
@@ -688,6 +995,7 @@ impl IcebergInputEndpointInner {
     async fn prepare_snapshot_query(
         &self,
         table: &IcebergTable,
+        snapshot_id: Option<i64>,
         schema: &Relation,
     ) -> Result<(), ControllerError> {
         if !self.config.snapshot() {
@@ -699,29 +1007,6 @@ impl IcebergInputEndpointInner {
             &self.endpoint_name,
         );
 
-        let snapshot_id = match self.snapshot_descr()? {
-            SnapshotDescr::SnapshotId(snapshot_id) => Some(snapshot_id),
-            SnapshotDescr::Timestamp(ts) => {
-                let ts_ms = ts.timestamp_millis();
-                let snapshot_log = table
-                    .metadata()
-                    .history()
-                    .iter()
-                    .rev()
-                    .find(|log| log.timestamp_ms() <= ts_ms);
-                if let Some(snapshot_log) = snapshot_log {
-                    Some(snapshot_log.snapshot_id)
-                } else {
-                    return Err(ControllerError::input_transport_error(
-                        &self.endpoint_name,
-                        true,
-                        anyhow!("Iceberg connector configuration specifies timestamp {ts}; however Iceberg table does not contain a snapshot with the same or earlier timestamp"),
-                    ));
-                }
-            }
-            SnapshotDescr::Latest => None,
-        };
-
         let provider = match snapshot_id {
             Some(snapshot_id) => {
                 IcebergTableProvider::try_new_from_table_snapshot(table.clone(), snapshot_id).await
@@ -842,27 +1127,99 @@ impl IcebergInputEndpointInner {
             };
             // info!("schema: {}", batch.schema());
             num_batches += 1;
-            let result = if polarity {
-                input_stream.insert(&batch)
-            } else {
-                input_stream.delete(&batch)
-            };
-            let errors = result.map_or_else(
-                |e| {
-                    vec![ParseError::bin_envelope_error(
-                        format!("error deserializing table records from Parquet data: {e}"),
-                        &[],
-                        None,
-                    )]
-                },
-                |()| Vec::new(),
-            );
-            self.queue
-                .push((input_stream.take_all(), errors), timestamp);
+            self.push_batch(&batch, polarity, input_stream, timestamp);
 
             timestamp = Utc::now();
         }
     }
+
+    /// Deserialize a record batch and push it to the circuit.
+    fn push_batch(
+        &self,
+        batch: &RecordBatch,
+        polarity: bool,
+        input_stream: &mut dyn ArrowStream,
+        timestamp: DateTime<Utc>,
+    ) {
+        let result = if polarity {
+            input_stream.insert(batch)
+        } else {
+            input_stream.delete(batch)
+        };
+        let errors = result.map_or_else(
+            |e| {
+                vec![ParseError::bin_envelope_error(
+                    format!("error deserializing table records from Parquet data: {e}"),
+                    &[],
+                    None,
+                )]
+            },
+            |()| Vec::new(),
+        );
+        self.queue
+            .push_with_aux((input_stream.take_all(), errors), timestamp, None);
+    }
+}
+
+/// Returns the snapshots of the table committed after the snapshot
+/// `snapshot_id`, oldest first, following the ancestry of the current
+/// snapshot of the table.  `None` stands for the empty table before its first
+/// snapshot.
+fn snapshots_after(
+    metadata: &TableMetadata,
+    snapshot_id: Option<i64>,
+) -> AnyResult<Vec<SnapshotRef>> {
+    let mut snapshots = Vec::new();
+    let mut next = metadata.current_snapshot().cloned();
+
+    while let Some(snapshot) = next {
+        if Some(snapshot.snapshot_id()) == snapshot_id {
+            break;
+        }
+        next = match snapshot.parent_snapshot_id() {
+            Some(parent_id) => Some(metadata.snapshot_by_id(parent_id).cloned().ok_or_else(|| {
+                anyhow!("snapshot {parent_id}, which is an ancestor of the current table snapshot, has expired before the connector could read it")
+            })?),
+            None if snapshot_id.is_some() => bail!(
+                "snapshot {} is not an ancestor of the current table snapshot {}; this can happen when the table is rolled back to an earlier snapshot",
+                snapshot_id.unwrap(),
+                metadata.current_snapshot().unwrap().snapshot_id()
+            ),
+            None => None,
+        };
+        snapshots.push(snapshot);
+    }
+
+    snapshots.reverse();
+    Ok(snapshots)
+}
+
+/// Returns the data files of the table as of `snapshot_id`, indexed by path.
+async fn data_files(
+    table: &IcebergTable,
+    snapshot_id: Option<i64>,
+) -> AnyResult<BTreeMap<String, FileScanTask>> {
+    let Some(snapshot_id) = snapshot_id else {
+        return Ok(BTreeMap::new());
+    };
+
+    let tasks = table
+        .scan()
+        .snapshot_id(snapshot_id)
+        .select_all()
+        .build()
+        .map_err(|e| anyhow!("error scanning table snapshot {snapshot_id}: {e}"))?
+        .plan_files()
+        .await
+        .map_err(|e| anyhow!("error listing data files in table snapshot {snapshot_id}: {e}"))?
+        .try_collect::<Vec<_>>()
+        .await
+        .map_err(|e| anyhow!("error listing data files in table snapshot {snapshot_id}: {e}"))?;
+
+    Ok(tasks
+        .into_iter()
+        .map(|task| (task.data_file_path.clone(), task))
+        .collect())
 }
 
 /// Block until the state is `Running`.
@@ -874,3 +1231,100 @@ async fn wait_running(receiver: &mut Receiver<PipelineState>) {
         .wait_for(|state| state == &PipelineState::Running)
         .await;
 }
+
+#[cfg(test)]
+mod test {
+    use super::{data_files, snapshots_after};
+    use crate::IcebergTableWriter;
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use dbsp::circuit::tokio::TOKIO;
+    use iceberg::io::FileIOBuilder;
+    use iceberg::spec::{NestedField, PrimitiveType, Schema as IcebergSchema, Type};
+    use iceberg::{Catalog, NamespaceIdent, TableCreation};
+    use iceberg_catalog_memory::MemoryCatalog;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshots_after() {
+        TOKIO.block_on(async {
+            let dir = TempDir::new().unwrap();
+            let catalog: Arc<dyn Catalog> = Arc::new(MemoryCatalog::new(
+                FileIOBuilder::new_fs_io().build().unwrap(),
+                Some(dir.path().to_str().unwrap().to_string()),
+            ));
+            let namespace = NamespaceIdent::new("ns".to_string());
+            catalog
+                .create_namespace(&namespace, HashMap::new())
+                .await
+                .unwrap();
+            let table = catalog
+                .create_table(
+                    &namespace,
+                    TableCreation::builder()
+                        .name("t".to_string())
+                        .schema(
+                            IcebergSchema::builder()
+                                .with_fields(vec![NestedField::required(
+                                    1,
+                                    "id",
+                                    Type::Primitive(PrimitiveType::Long),
+                                )
+                                .into()])
+                                .build()
+                                .unwrap(),
+                        )
+                        .build(),
+                )
+                .await
+                .unwrap();
+            let table_ident = table.identifier().clone();
+
+            assert!(snapshots_after(table.metadata(), None).unwrap().is_empty());
+            assert!(data_files(&table, None).await.unwrap().is_empty());
+
+            // Commit three snapshots, each adding one data file.
+            let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+            let mut writer =
+                IcebergTableWriter::new(catalog.clone(), table, schema.clone(), &[]).unwrap();
+            for id in 0..3 {
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int64Array::from(vec![id]))],
+                )
+                .unwrap();
+                writer.write(&batch).await.unwrap();
+                writer.commit().await.unwrap().unwrap();
+            }
+
+            let table = catalog.load_table(&table_ident).await.unwrap();
+            let metadata = table.metadata();
+            let ids = snapshots_after(metadata, None)
+                .unwrap()
+                .iter()
+                .map(|snapshot| snapshot.snapshot_id())
+                .collect::<Vec<_>>();
+            assert_eq!(ids.len(), 3);
+            assert_eq!(ids[2], metadata.current_snapshot().unwrap().snapshot_id());
+
+            let after_first = snapshots_after(metadata, Some(ids[0]))
+                .unwrap()
+                .iter()
+                .map(|snapshot| snapshot.snapshot_id())
+                .collect::<Vec<_>>();
+            assert_eq!(after_first, ids[1..]);
+            assert!(snapshots_after(metadata, Some(ids[2])).unwrap().is_empty());
+
+            // Unknown snapshot.
+            assert!(snapshots_after(metadata, Some(-1)).is_err());
+
+            let first_files = data_files(&table, Some(ids[0])).await.unwrap();
+            let last_files = data_files(&table, Some(ids[2])).await.unwrap();
+            assert_eq!(first_files.len(), 1);
+            assert_eq!(last_files.len(), 3);
+            assert!(first_files.keys().all(|path| last_files.contains_key(path)));
+        })
+    }
+}
@@ -1,9 +1,13 @@
+mod catalog;
 mod input;
+mod output;
 
 pub use input::IcebergInputEndpoint;
+pub use output::{CommitStats, IcebergTableWriter};
 
 use feldera_types::serde_with_context::{
-    serde_config::DecimalFormat, DateFormat, SqlSerdeConfig, TimestampFormat,
+    serde_config::{BinaryFormat, DecimalFormat, UuidFormat, VariantFormat},
+    DateFormat, SqlSerdeConfig, TimeFormat, TimestampFormat,
 };
 
 pub fn iceberg_input_serde_config() -> SqlSerdeConfig {
@@ -20,3 +24,17 @@ pub fn iceberg_input_serde_config() -> SqlSerdeConfig {
         .with_date_format(DateFormat::DaysSinceEpoch)
         .with_decimal_format(DecimalFormat::String)
 }
+
+/// Arrow serde config for records written to Iceberg tables by
+/// [`IcebergTableWriter`].
+pub const fn iceberg_output_serde_config() -> &'static SqlSerdeConfig {
+    &SqlSerdeConfig {
+        timestamp_format: TimestampFormat::MicrosSinceEpoch,
+        time_format: TimeFormat::NanosSigned,
+        date_format: DateFormat::String("%Y-%m-%d"),
+        decimal_format: DecimalFormat::String,
+        variant_format: VariantFormat::JsonString,
+        binary_format: BinaryFormat::Array,
+        uuid_format: UuidFormat::String,
+    }
+}
@@ -0,0 +1,513 @@
+use crate::catalog::{parse_table_name, CatalogConfig};
+use anyhow::{anyhow, bail, Result as AnyResult};
+use arrow::array::{new_null_array, ArrayRef, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::SchemaRef;
+use feldera_types::transport::iceberg::IcebergWriterConfig;
+use iceberg::arrow::schema_to_arrow_schema;
+use iceberg::spec::DataFileFormat;
+use iceberg::table::Table as IcebergTable;
+use iceberg::transaction::Transaction;
+use iceberg::writer::base_writer::data_file_writer::{DataFileWriter, DataFileWriterBuilder};
+use iceberg::writer::file_writer::location_generator::{
+    DefaultFileNameGenerator, DefaultLocationGenerator,
+};
+use iceberg::writer::file_writer::ParquetWriterBuilder;
+use iceberg::writer::{IcebergWriter, IcebergWriterBuilder};
+use iceberg::Catalog;
+use log::{debug, info};
+use parquet::file::properties::WriterProperties;
+use std::sync::Arc;
+use uuid::Uuid;
+
+type FileWriter =
+    DataFileWriter<ParquetWriterBuilder<DefaultLocationGenerator, DefaultFileNameGenerator>>;
+
+/// Size and number of records of a committed snapshot.
+pub struct CommitStats {
+    pub num_bytes: usize,
+    pub num_records: usize,
+}
+
+/// Appends Arrow record batches to an existing Iceberg table.
+///
+/// The batches written between two calls to [`IcebergTableWriter::commit`]
+/// are committed to the table as a single snapshot.
+pub struct IcebergTableWriter {
+    catalog: Arc<dyn Catalog>,
+    table: IcebergTable,
+
+    /// Schema of the table, converted to Arrow.
+    table_schema: SchemaRef,
+
+    /// For each column of `table_schema`, the index of the column of the
+    /// input batches that supplies its values, or `None` to write nulls.
+    columns: Vec<Option<usize>>,
+
+    /// Writer for the snapshot in progress, created when the first batch of
+    /// the snapshot is written.
+    writer: Option<FileWriter>,
+}
+
+impl IcebergTableWriter {
+    /// Opens the table specified in `config`.
+    ///
+    /// `input_schema` is the schema of the batches that will be passed to
+    /// [`IcebergTableWriter::write`].  The table must have a column for each
+    /// of its columns, except for `optional_columns`, which are dropped if the
+    /// table doesn't have them.
+    pub async fn open(
+        config: &IcebergWriterConfig,
+        input_schema: SchemaRef,
+        optional_columns: &[&str],
+    ) -> AnyResult<Self> {
+        let catalog = CatalogConfig {
+            catalog_type: &config.catalog_type,
+            glue: &config.glue_catalog_config,
+            rest: &config.rest_catalog_config,
+            hive: &config.hive_catalog_config,
+            fileio_config: &config.fileio_config,
+        }
+        .connect()
+        .await?;
+
+        let table_ident = parse_table_name(&config.table_name)?;
+        let table = catalog
+            .load_table(&table_ident)
+            .await
+            .map_err(|e| anyhow!("error loading Iceberg table '{}': {e}", &config.table_name))?;
+
+        Self::new(catalog, table, input_schema, optional_columns)
+    }
+
+    pub(crate) fn new(
+        catalog: Arc<dyn Catalog>,
+        table: IcebergTable,
+        input_schema: SchemaRef,
+        optional_columns: &[&str],
+    ) -> AnyResult<Self> {
+        let metadata = table.metadata();
+        if !metadata.default_partition_spec().is_unpartitioned() {
+            bail!("writing to partitioned Iceberg tables is not supported");
+        }
+
+        let table_schema = Arc::new(
+            schema_to_arrow_schema(metadata.current_schema())
+                .map_err(|e| anyhow!("error converting Iceberg table schema to Arrow: {e}"))?,
+        );
+
+        let mut columns = Vec::with_capacity(table_schema.fields().len());
+        for field in table_schema.fields() {
+            match input_schema.index_of(field.name()) {
+                Ok(index) => columns.push(Some(index)),
+                Err(_) if field.is_nullable() => columns.push(None),
+                Err(_) => bail!(
+                    "the Iceberg table has a required column '{}' that the view doesn't have",
+                    field.name()
+                ),
+            }
+        }
+        for field in input_schema.fields() {
+            if table_schema.index_of(field.name()).is_err()
+                && !optional_columns.contains(&field.name().as_str())
+            {
+                bail!(
+                    "the Iceberg table doesn't have a column for view column '{}'",
+                    field.name()
+                );
+            }
+        }
+
+        info!(
+            "iceberg: opened table '{}' (current snapshot: {:?})",
+            table.identifier(),
+            metadata.current_snapshot_id()
+        );
+
+        Ok(Self {
+            catalog,
+            table,
+            table_schema,
+            columns,
+            writer: None,
+        })
+    }
+
+    /// Returns true if the table has a column named `name`.
+    pub fn has_column(&self, name: &str) -> bool {
+        self.table_schema.index_of(name).is_ok()
+    }
+
+    /// Writes `batch` into data files for the snapshot in progress.
+    pub async fn write(&mut self, batch: &RecordBatch) -> AnyResult<()> {
+        let num_rows = batch.num_rows();
+        let columns = self
+            .table_schema
+            .fields()
+            .iter()
+            .zip(&self.columns)
+            .map(|(field, column)| match column {
+                Some(index) => cast(batch.column(*index), field.data_type()).map_err(|e| {
+                    anyhow!(
+                        "error converting column '{}' to Iceberg type {}: {e}",
+                        field.name(),
+                        field.data_type()
+                    )
+                }),
+                None => Ok(new_null_array(field.data_type(), num_rows)),
+            })
+            .collect::<AnyResult<Vec<ArrayRef>>>()?;
+        let batch = RecordBatch::try_new(self.table_schema.clone(), columns)?;
+
+        if self.writer.is_none() {
+            self.writer = Some(self.new_writer().await?);
+        }
+        self.writer
+            .as_mut()
+            .unwrap()
+            .write(batch)
+            .await
+            .map_err(|e| anyhow!("error writing {num_rows} records: {e}"))
+    }
+
+    async fn new_writer(&self) -> AnyResult<FileWriter> {
+        let metadata = self.table.metadata();
+        let location_generator = DefaultLocationGenerator::new(metadata.clone())?;
+
+        // Make file names unique across pipeline restarts.
+        let file_name_generator = DefaultFileNameGenerator::new(
+            "feldera".to_string(),
+            Some(Uuid::now_v7().to_string()),
+            DataFileFormat::Parquet,
+        );
+        let parquet_writer_builder = ParquetWriterBuilder::new(
+            WriterProperties::default(),
+            metadata.current_schema().clone(),
+            self.table.file_io().clone(),
+            location_generator,
+            file_name_generator,
+        );
+        Ok(DataFileWriterBuilder::new(
+            parquet_writer_builder,
+            None,
+            metadata.default_partition_spec_id(),
+        )
+        .build()
+        .await?)
+    }
+
+    /// Commits the data written since the previous commit as a new snapshot
+    /// of the table.
+    ///
+    /// Returns `None` if no data was written.
+    pub async fn commit(&mut self) -> AnyResult<Option<CommitStats>> {
+        let Some(mut writer) = self.writer.take() else {
+            return Ok(None);
+        };
+        let data_files = writer
+            .close()
+            .await
+            .map_err(|e| anyhow!("error flushing data files: {e}"))?;
+        if data_files.is_empty() {
+            return Ok(None);
+        }
+
+        let stats = CommitStats {
+            num_bytes: data_files
+                .iter()
+                .map(|file| file.file_size_in_bytes() as usize)
+                .sum(),
+            num_records: data_files
+                .iter()
+                .map(|file| file.record_count() as usize)
+                .sum(),
+        };
+
+        let mut action = Transaction::new(&self.table).fast_append(None, vec![])?;
+        action.add_data_files(data_files)?;
+        self.table = action
+            .apply()
+            .await?
+            .commit(self.catalog.as_ref())
+            .await
+            .map_err(|e| anyhow!("error committing snapshot to Iceberg table: {e}"))?;
+
+        debug!(
+            "iceberg: committed snapshot {:?} with {} records to table '{}'",
+            self.table.metadata().current_snapshot_id(),
+            stats.num_records,
+            self.table.identifier()
+        );
+
+        Ok(Some(stats))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IcebergTableWriter;
+    use arrow::array::{Array, Int32Array, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use async_trait::async_trait;
+    use dbsp::circuit::tokio::TOKIO;
+    use futures_util::TryStreamExt;
+    use iceberg::io::{FileIO, FileIOBuilder};
+    use iceberg::spec::{
+        NestedField, PrimitiveType, Schema as IcebergSchema, TableMetadataBuilder, Type,
+    };
+    use iceberg::table::Table as IcebergTable;
+    use iceberg::{
+        Catalog, Namespace, NamespaceIdent, Result as IcebergResult, TableCommit, TableCreation,
+        TableIdent,
+    };
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    /// Catalog that keeps the metadata of a single table in memory, which is
+    /// all that [`IcebergTableWriter`] needs.
+    #[derive(Debug)]
+    struct TestCatalog {
+        file_io: FileIO,
+        table: Mutex<Option<IcebergTable>>,
+    }
+
+    impl TestCatalog {
+        fn set_table(
+            &self,
+            identifier: TableIdent,
+            builder: TableMetadataBuilder,
+        ) -> IcebergResult<IcebergTable> {
+            let table = IcebergTable::builder()
+                .file_io(self.file_io.clone())
+                .identifier(identifier)
+                .metadata(builder.build()?.metadata)
+                .build()?;
+            *self.table.lock().unwrap() = Some(table.clone());
+            Ok(table)
+        }
+    }
+
+    #[async_trait]
+    impl Catalog for TestCatalog {
+        async fn list_namespaces(
+            &self,
+            _parent: Option<&NamespaceIdent>,
+        ) -> IcebergResult<Vec<NamespaceIdent>> {
+            unimplemented!()
+        }
+
+        async fn create_namespace(
+            &self,
+            _namespace: &NamespaceIdent,
+            _properties: HashMap<String, String>,
+        ) -> IcebergResult<Namespace> {
+            unimplemented!()
+        }
+
+        async fn get_namespace(&self, _namespace: &NamespaceIdent) -> IcebergResult<Namespace> {
+            unimplemented!()
+        }
+
+        async fn namespace_exists(&self, _namespace: &NamespaceIdent) -> IcebergResult<bool> {
+            unimplemented!()
+        }
+
+        async fn update_namespace(
+            &self,
+            _namespace: &NamespaceIdent,
+            _properties: HashMap<String, String>,
+        ) -> IcebergResult<()> {
+            unimplemented!()
+        }
+
+        async fn drop_namespace(&self, _namespace: &NamespaceIdent) -> IcebergResult<()> {
+            unimplemented!()
+        }
+
+        async fn list_tables(&self, _namespace: &NamespaceIdent) -> IcebergResult<Vec<TableIdent>> {
+            unimplemented!()
+        }
+
+        async fn create_table(
+            &self,
+            namespace: &NamespaceIdent,
+            creation: TableCreation,
+        ) -> IcebergResult<IcebergTable> {
+            let identifier = TableIdent::new(namespace.clone(), creation.name.clone());
+            self.set_table(
+                identifier,
+                TableMetadataBuilder::from_table_creation(creation)?,
+            )
+        }
+
+        async fn load_table(&self, _table: &TableIdent) -> IcebergResult<IcebergTable> {
+            Ok(self.table.lock().unwrap().clone().unwrap())
+        }
+
+        async fn drop_table(&self, _table: &TableIdent) -> IcebergResult<()> {
+            unimplemented!()
+        }
+
+        async fn table_exists(&self, _table: &TableIdent) -> IcebergResult<bool> {
+            unimplemented!()
+        }
+
+        async fn rename_table(&self, _src: &TableIdent, _dest: &TableIdent) -> IcebergResult<()> {
+            unimplemented!()
+        }
+
+        async fn update_table(&self, mut commit: TableCommit) -> IcebergResult<IcebergTable> {
+            let metadata = self
+                .table
+                .lock()
+                .unwrap()
+                .as_ref()
+                .unwrap()
+                .metadata()
+                .clone();
+            let mut builder = TableMetadataBuilder::new_from_metadata(metadata, None);
+            for update in commit.take_updates() {
+                builder = update.apply(builder)?;
+            }
+            self.set_table(commit.identifier().clone(), builder)
+        }
+    }
+
+    /// Creates table `ns.t` with columns `id`, `name`, and `__feldera_op`.
+    async fn create_table(dir: &TempDir) -> (Arc<dyn Catalog>, IcebergTable) {
+        let catalog = TestCatalog {
+            file_io: FileIOBuilder::new_fs_io().build().unwrap(),
+            table: Mutex::new(None),
+        };
+        let namespace = NamespaceIdent::new("ns".to_string());
+        let schema = IcebergSchema::builder()
+            .with_fields(vec![
+                NestedField::required(1, "id", Type::Primitive(PrimitiveType::Long)).into(),
+                NestedField::optional(2, "name", Type::Primitive(PrimitiveType::String)).into(),
+                NestedField::optional(3, "__feldera_op", Type::Primitive(PrimitiveType::String))
+                    .into(),
+            ])
+            .build()
+            .unwrap();
+        let table = catalog
+            .create_table(
+                &namespace,
+                TableCreation::builder()
+                    .name("t".to_string())
+                    .location(dir.path().join("t").to_str().unwrap().to_string())
+                    .schema(schema)
+                    .build(),
+            )
+            .await
+            .unwrap();
+        (Arc::new(catalog), table)
+    }
+
+    fn input_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("__feldera_op", DataType::Utf8, true),
+            Field::new("__feldera_ts", DataType::Int64, true),
+        ]))
+    }
+
+    fn batch(ids: &[i32], op: &str) -> RecordBatch {
+        RecordBatch::try_new(
+            input_schema(),
+            vec![
+                Arc::new(Int32Array::from(ids.to_vec())),
+                Arc::new(StringArray::from(vec![op; ids.len()])),
+                Arc::new(Int64Array::from(vec![0; ids.len()])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_iceberg_writer() {
+        TOKIO.block_on(async {
+            let dir = TempDir::new().unwrap();
+            let (catalog, table) = create_table(&dir).await;
+            let mut writer =
+                IcebergTableWriter::new(catalog, table, input_schema(), &["__feldera_ts"]).unwrap();
+            assert!(writer.has_column("__feldera_op"));
+            assert!(!writer.has_column("__feldera_ts"));
+
+            // Each commit creates one snapshot; empty commits create none.
+            writer.write(&batch(&[1, 2], "i")).await.unwrap();
+            assert_eq!(writer.commit().await.unwrap().unwrap().num_records, 2);
+            assert!(writer.commit().await.unwrap().is_none());
+            writer.write(&batch(&[1], "d")).await.unwrap();
+            writer.write(&batch(&[3], "i")).await.unwrap();
+            assert_eq!(writer.commit().await.unwrap().unwrap().num_records, 2);
+            assert_eq!(writer.table.metadata().snapshots().count(), 2);
+
+            let batches = writer
+                .table
+                .scan()
+                .select_all()
+                .build()
+                .unwrap()
+                .to_arrow()
+                .await
+                .unwrap()
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap();
+            let mut rows = Vec::new();
+            for batch in &batches {
+                let ids = batch
+                    .column_by_name("id")
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                let ops = batch
+                    .column_by_name("__feldera_op")
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .unwrap();
+                assert_eq!(
+                    batch.column_by_name("name").unwrap().null_count(),
+                    ids.len()
+                );
+                for i in 0..ids.len() {
+                    rows.push((ids.value(i), ops.value(i).to_string()));
+                }
+            }
+            rows.sort();
+            assert_eq!(
+                rows,
+                vec![
+                    (1, "d".to_string()),
+                    (1, "i".to_string()),
+                    (2, "i".to_string()),
+                    (3, "i".to_string())
+                ]
+            );
+        })
+    }
+
+    #[test]
+    fn test_iceberg_writer_schema_mismatch() {
+        TOKIO.block_on(async {
+            let dir = TempDir::new().unwrap();
+            let (catalog, table) = create_table(&dir).await;
+
+            // `__feldera_ts` is not in the table.
+            let err = IcebergTableWriter::new(catalog.clone(), table.clone(), input_schema(), &[])
+                .err()
+                .unwrap();
+            assert!(err.to_string().contains("'__feldera_ts'"));
+
+            // Required column `id` is not in the view.
+            let schema = Arc::new(Schema::new(vec![Field::new("name", DataType::Utf8, true)]));
+            let err = IcebergTableWriter::new(catalog, table, schema, &[])
+                .err()
+                .unwrap();
+            assert!(err.to_string().contains("'id'"));
+        })
+    }
+}
@@ -318,8 +318,47 @@ It contains the following fields:
         feldera_types::transport::kafka::KafkaOutputConfig,
         feldera_types::transport::kafka::KafkaOutputFtConfig,
         feldera_types::transport::kafka::KafkaStartFromConfig,
+        feldera_types::transport::kinesis::KinesisOutputConfig,
         feldera_types::transport::pubsub::PubSubInputConfig,
+        feldera_types::transport::nats::ConnectOptions,
+        feldera_types::transport::nats::Auth,
+        feldera_types::transport::tls::TlsConfig,
+        feldera_types::transport::tls::TlsServerConfig,
+        feldera_types::transport::nats::NatsKvInputConfig,
+        feldera_types::transport::nats::NatsObjectStoreInputConfig,
+        feldera_types::transport::mqtt::MqttProtocolVersion,
+        feldera_types::transport::mqtt::MqttQos,
+        feldera_types::transport::mqtt::MqttConnectionConfig,
+        feldera_types::transport::mqtt::MqttInputConfig,
+        feldera_types::transport::mqtt::MqttOutputConfig,
+        feldera_types::transport::amqp::AmqpExchangeKind,
+        feldera_types::transport::amqp::AmqpQueueDeclaration,
+        feldera_types::transport::amqp::AmqpExchangeDeclaration,
+        feldera_types::transport::amqp::AmqpQueueBinding,
+        feldera_types::transport::amqp::AmqpInputConfig,
         feldera_types::transport::s3::S3InputConfig,
+        feldera_types::transport::s3::S3OutputConfig,
+        feldera_types::transport::s3::ObjectOutputFormat,
+        feldera_types::transport::gcs::GcsInputConfig,
+        feldera_types::transport::gcs::GcsOutputConfig,
+        feldera_types::transport::azure_blob::AzureBlobInputConfig,
+        feldera_types::transport::azure_blob::AzureBlobOutputConfig,
+        feldera_types::transport::ftp::FtpInputConfig,
+        feldera_types::transport::ftp::FtpProtocol,
+        feldera_types::transport::http_poller::HttpPollerInputConfig,
+        feldera_types::transport::http_poller::HttpPollerPagination,
+        feldera_types::transport::sqs::SqsInputConfig,
+        feldera_types::transport::sse::SseInputConfig,
+        feldera_types::transport::websocket::WebsocketInputConfig,
+        feldera_types::transport::websocket::WebsocketOutputConfig,
+        feldera_types::transport::grpc::GrpcInputConfig,
+        feldera_types::transport::grpc::GrpcOutputConfig,
+        feldera_types::transport::tcp::TcpInputConfig,
+        feldera_types::transport::syslog::SyslogInputConfig,
+        feldera_types::transport::syslog::SyslogProtocol,
+        feldera_types::transport::unix::UnixInputConfig,
+        feldera_types::transport::unix::UnixOutputConfig,
+        feldera_types::transport::webhook::WebhookOutputConfig,
         feldera_types::transport::datagen::DatagenStrategy,
         feldera_types::transport::datagen::RngFieldSettings,
         feldera_types::transport::datagen::GenerationPlan,
@@ -332,13 +371,36 @@ It contains the following fields:
         feldera_types::transport::delta_table::DeltaTableReaderConfig,
         feldera_types::transport::delta_table::DeltaTableWriterConfig,
         feldera_types::transport::iceberg::IcebergReaderConfig,
+        feldera_types::transport::iceberg::IcebergWriterConfig,
         feldera_types::transport::iceberg::IcebergIngestMode,
         feldera_types::transport::iceberg::IcebergCatalogType,
         feldera_types::transport::iceberg::RestCatalogConfig,
         feldera_types::transport::iceberg::GlueCatalogConfig,
+        feldera_types::transport::iceberg::HiveCatalogConfig,
         feldera_types::transport::postgres::PostgresReaderConfig,
         feldera_types::transport::postgres::PostgresWriterConfig,
+        feldera_types::transport::postgres::PostgresCdcReaderConfig,
+        feldera_types::transport::mysql::MySqlCdcReaderConfig,
+        feldera_types::transport::mysql::MySqlWriterConfig,
+        feldera_types::transport::influxdb::InfluxDbOutputConfig,
+        feldera_types::transport::alert::AlertOutputConfig,
+        feldera_types::transport::alert::AlertEmailConfig,
+        feldera_types::transport::alert::SmtpSecurity,
+        feldera_types::transport::arrow_flight::ArrowFlightInputConfig,
+        feldera_types::transport::arrow_flight::ArrowFlightOutputConfig,
+        feldera_types::transport::influxdb::InfluxDbPrecision,
+        feldera_types::transport::mongodb::MongoDbCdcReaderConfig,
+        feldera_types::transport::mongodb::MongoDbWriterConfig,
+        feldera_types::transport::mongodb::MongoDbWriteConcern,
+        feldera_types::transport::pulsar::PulsarSubscriptionType,
+        feldera_types::transport::pulsar::PulsarStartPosition,
+        feldera_types::transport::pulsar::PulsarInputConfig,
+        feldera_types::transport::pulsar::PulsarOutputConfig,
+        feldera_types::transport::redis::RedisDataStructure,
         feldera_types::transport::redis::RedisOutputConfig,
+        feldera_types::transport::redis::RedisStreamStartPosition,
+        feldera_types::transport::redis::RedisStreamsInputConfig,
+        feldera_types::transport::redis::RedisStreamsOutputConfig,
         feldera_types::transport::http::Chunk,
         feldera_types::transport::clock::ClockConfig,
         feldera_types::query::AdhocQueryArgs,
@@ -545,6 +545,18 @@ fn parse_named_connectors(
                         }
                     })?;
                 }
+                validate_transport_config(&connector.config.transport).map_err(|e| {
+                    ConnectorGenerationError::InvalidPropertyValue {
+                        position: value.value_position,
+                        relation: relation.sql_name(),
+                        key: "connectors".to_string(),
+                        value: value.value.clone(),
+                        reason: Box::new(format!(
+                            "connector '{}' is not valid: {e}",
+                            connector.name.as_deref().unwrap_or("<unnamed>")
+                        )),
+                    }
+                })?;
             }
             Ok((connectors, Some(value.clone())))
         }
@@ -552,6 +564,57 @@ fn parse_named_connectors(
     }
 }
 
+/// Checks transport configurations that can be validated before the pipeline
+/// starts, so that errors are reported when the program is compiled rather than
+/// when the connector is created.
+///
+/// Secret references are not yet resolved at this point, so only checks that
+/// hold regardless of the values of secrets are performed.
+fn validate_transport_config(transport: &TransportConfig) -> Result<(), String> {
+    match transport {
+        TransportConfig::NatsKvInput(config) => config.validate(),
+        TransportConfig::NatsObjectStoreInput(config) => config.validate(),
+        TransportConfig::MqttInput(config) => config.validate(),
+        TransportConfig::MqttOutput(config) => config.validate(),
+        TransportConfig::AmqpInput(config) => config.validate(),
+        TransportConfig::RedisOutput(config) => config.validate(),
+        TransportConfig::RedisStreamsInput(config) => config.validate(),
+        TransportConfig::RedisStreamsOutput(config) => config.validate(),
+        TransportConfig::PulsarInput(config) => config.validate(),
+        TransportConfig::PulsarOutput(config) => config.validate(),
+        TransportConfig::KinesisOutput(config) => config.validate(),
+        TransportConfig::SqsInput(config) => config.validate(),
+        TransportConfig::WebsocketInput(config) => config.validate(),
+        TransportConfig::WebsocketOutput(config) => config.validate(),
+        TransportConfig::GrpcInput(config) => config.validate(),
+        TransportConfig::GrpcOutput(config) => config.validate(),
+        TransportConfig::TcpInput(config) => config.validate(),
+        TransportConfig::SyslogInput(config) => config.validate(),
+        TransportConfig::UnixInput(config) => config.validate(),
+        TransportConfig::UnixOutput(config) => config.validate(),
+        TransportConfig::PostgresCdcInput(config) => config.validate(),
+        TransportConfig::MySqlCdcInput(config) => config.validate(),
+        TransportConfig::MySqlOutput(config) => config.validate(),
+        TransportConfig::InfluxDbOutput(config) => config.validate(),
+        TransportConfig::MongoDbCdcInput(config) => config.validate(),
+        TransportConfig::MongoDbOutput(config) => config.validate(),
+        TransportConfig::S3Output(config) => config.validate(),
+        TransportConfig::GcsInput(config) => config.validate(),
+        TransportConfig::GcsOutput(config) => config.validate(),
+        TransportConfig::AzureBlobInput(config) => config.validate(),
+        TransportConfig::AzureBlobOutput(config) => config.validate(),
+        TransportConfig::FtpInput(config) => config.validate(),
+        TransportConfig::HttpPollerInput(config) => config.validate(),
+        TransportConfig::WebhookOutput(config) => config.validate(),
+        TransportConfig::SseInput(config) => config.validate(),
+        TransportConfig::AlertOutput(config) => config.validate(),
+        TransportConfig::ArrowFlightInput(config) => config.validate(),
+        TransportConfig::ArrowFlightOutput(config) => config.validate(),
+        TransportConfig::IcebergOutput(config) => config.validate(),
+        _ => Ok(()),
+    }
+}
+
 /// Receives a list of all connectors with for each:
 /// - The name of the stream it belongs to
 /// - (Optionally) given connector name scoped to stream
@@ -647,10 +710,31 @@ pub fn generate_program_info(
                 TransportConfig::FileInput(_)
                 | TransportConfig::KafkaInput(_)
                 | TransportConfig::PubSubInput(_)
+                | TransportConfig::NatsKvInput(_)
+                | TransportConfig::NatsObjectStoreInput(_)
+                | TransportConfig::MqttInput(_)
+                | TransportConfig::AmqpInput(_)
+                | TransportConfig::RedisStreamsInput(_)
+                | TransportConfig::PulsarInput(_)
+                | TransportConfig::SqsInput(_)
+                | TransportConfig::WebsocketInput(_)
+                | TransportConfig::GrpcInput(_)
+                | TransportConfig::TcpInput(_)
+                | TransportConfig::SyslogInput(_)
+                | TransportConfig::UnixInput(_)
                 | TransportConfig::UrlInput(_)
                 | TransportConfig::S3Input(_)
+                | TransportConfig::GcsInput(_)
+                | TransportConfig::AzureBlobInput(_)
+                | TransportConfig::FtpInput(_)
+                | TransportConfig::HttpPollerInput(_)
+                | TransportConfig::SseInput(_)
+                | TransportConfig::ArrowFlightInput(_)
                 | TransportConfig::DeltaTableInput(_)
                 | TransportConfig::PostgresInput(_)
+                | TransportConfig::PostgresCdcInput(_)
+                | TransportConfig::MySqlCdcInput(_)
+                | TransportConfig::MongoDbCdcInput(_)
                 | TransportConfig::IcebergInput(_)
                 | TransportConfig::Datagen(_)
                 | TransportConfig::Nexmark(_) => {}
@@ -698,9 +782,26 @@ pub fn generate_program_info(
             match connector.config.transport {
                 TransportConfig::FileOutput(_)
                 | TransportConfig::PostgresOutput(_)
+                | TransportConfig::MySqlOutput(_)
+                | TransportConfig::InfluxDbOutput(_)
+                | TransportConfig::MongoDbOutput(_)
+                | TransportConfig::S3Output(_)
+                | TransportConfig::GcsOutput(_)
+                | TransportConfig::AzureBlobOutput(_)
                 | TransportConfig::KafkaOutput(_)
                 | TransportConfig::DeltaTableOutput(_)
-                | TransportConfig::RedisOutput(_) => {}
+                | TransportConfig::IcebergOutput(_)
+                | TransportConfig::RedisOutput(_)
+                | TransportConfig::RedisStreamsOutput(_)
+                | TransportConfig::PulsarOutput(_)
+                | TransportConfig::KinesisOutput(_)
+                | TransportConfig::WebsocketOutput(_)
+                | TransportConfig::GrpcOutput(_)
+                | TransportConfig::UnixOutput(_)
+                | TransportConfig::WebhookOutput(_)
+                | TransportConfig::AlertOutput(_)
+                | TransportConfig::ArrowFlightOutput(_)
+                | TransportConfig::MqttOutput(_) => {}
                 _ => {
                     return Err(ConnectorGenerationError::ExpectedOutputConnector {
                         position: origin_value.value_position,
@@ -763,11 +864,18 @@ pub fn generate_pipeline_config(
 
 #[cfg(test)]
 mod tests {
-    use super::{determine_connector_endpoint_names, RuntimeSelector};
-    use crate::db::types::program::ConnectorGenerationError::RelationConnectorNameCollision;
+    use super::{
+        determine_connector_endpoint_names, parse_named_connectors, ConnectorGenerationError,
+        NamedConnector, RuntimeSelector,
+    };
+    use crate::db::types::program::ConnectorGenerationError::{
+        InvalidPropertyValue, RelationConnectorNameCollision,
+    };
     use feldera_types::config::{ConnectorConfig, TransportConfig};
-    use feldera_types::program_schema::{PropertyValue, SourcePosition};
+    use feldera_types::program_schema::{PropertyValue, SourcePosition, SqlIdentifier};
     use feldera_types::transport::datagen::DatagenInputConfig;
+    use serde_json::json;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_runtime_version_validation() {
@@ -885,4 +993,177 @@ mod tests {
             }
         );
     }
+
+    /// Parses the `connectors` property of table `t1` with a single connector
+    /// `c1` whose transport configuration is `config`.
+    fn parse_connector(
+        config: serde_json::Value,
+    ) -> Result<(Vec<NamedConnector>, Option<PropertyValue>), ConnectorGenerationError> {
+        let position = SourcePosition {
+            start_line_number: 1,
+            start_column: 1,
+            end_line_number: 1,
+            end_column: 1,
+        };
+        let properties = BTreeMap::from([(
+            "connectors".to_string(),
+            PropertyValue {
+                value: json!([{ "name": "c1", "transport": config }]).to_string(),
+                key_position: position,
+                value_position: position,
+            },
+        )]);
+        parse_named_connectors(SqlIdentifier::from("t1"), &properties)
+    }
+
+    #[test]
+    fn test_connector_config_validation() {
+        let nats_kv = |connection_config: serde_json::Value, keys: &str| {
+            json!({
+                "name": "nats_kv_input",
+                "config": {
+                    "connection_config": connection_config,
+                    "bucket": "b1",
+                    "keys": keys
+                }
+            })
+        };
+
+        let (connectors, _) = parse_connector(nats_kv(
+            json!({ "server_urls": ["nats://localhost:4222"] }),
+            "orders.*.>",
+        ))
+        .unwrap();
+        assert_eq!(connectors.len(), 1);
+
+        for (config, expected) in [
+            (
+                nats_kv(json!({ "server_urls": [] }), ">"),
+                "at least one server",
+            ),
+            (
+                nats_kv(
+                    json!({ "server_urls": ["nats://localhost:4222"] }),
+                    "orders.>.new",
+                ),
+                "'>' is only allowed as the last token",
+            ),
+            (
+                nats_kv(
+                    json!({ "server_urls": ["nats://localhost:4222"] }),
+                    "orders..new",
+                ),
+                "empty token",
+            ),
+            (
+                nats_kv(
+                    json!({
+                        "server_urls": ["nats://localhost:4222"],
+                        "auth": { "nkey": { "seed": "SU", "seed_path": "/seed" } }
+                    }),
+                    ">",
+                ),
+                "mutually exclusive",
+            ),
+            (
+                nats_kv(
+                    json!({
+                        "server_urls": ["nats://localhost:4222"],
+                        "tls": { "client_cert_path": "/cert.pem" }
+                    }),
+                    ">",
+                ),
+                "without a client key",
+            ),
+            (
+                nats_kv(
+                    json!({
+                        "server_urls": ["nats://localhost:4222"],
+                        "inbox_prefix": "_INBOX.feldera.>"
+                    }),
+                    ">",
+                ),
+                "must not contain wildcards",
+            ),
+        ] {
+            match parse_connector(config) {
+                Err(InvalidPropertyValue { reason, .. }) => {
+                    assert!(reason.contains(expected), "{reason}");
+                    assert!(reason.contains("connector 'c1'"), "{reason}");
+                }
+                result => panic!("expected validation error, got {result:?}"),
+            }
+        }
+    }
+
+    /// Settings that may be given as secret references are not checked
+    /// before the references are resolved.
+    #[test]
+    fn test_connector_config_secret_references() {
+        let secret = "${secret:kubernetes:connectors/value}";
+        for config in [
+            json!({
+                "name": "mqtt_input",
+                "config": {
+                    "connection_config": { "server_url": secret, "tls": {} },
+                    "topics": ["sensors/#"]
+                }
+            }),
+            json!({
+                "name": "mqtt_output",
+                "config": {
+                    "connection_config": { "server_url": secret },
+                    "topic": "readings"
+                }
+            }),
+            json!({
+                "name": "amqp_input",
+                "config": { "uri": secret, "queue": "events" }
+            }),
+            json!({
+                "name": "pulsar_input",
+                "config": {
+                    "service_url": secret,
+                    "topics": ["events"],
+                    "subscription": "feldera"
+                }
+            }),
+            json!({
+                "name": "pulsar_output",
+                "config": { "service_url": secret, "topic": "readings" }
+            }),
+            json!({
+                "name": "websocket_output",
+                "config": { "url": secret, "tls": {} }
+            }),
+            json!({
+                "name": "http_poller_input",
+                "config": { "url": secret }
+            }),
+            json!({
+                "name": "webhook_output",
+                "config": { "url": secret }
+            }),
+            json!({
+                "name": "sse_input",
+                "config": { "url": secret }
+            }),
+            json!({
+                "name": "alert_output",
+                "config": { "slack_webhook_url": secret, "template": "{{ name }}" }
+            }),
+            json!({
+                "name": "arrow_flight_output",
+                "config": { "uri": secret, "tls": {} }
+            }),
+            json!({
+                "name": "arrow_flight_input",
+                "config": { "uri": secret, "ticket": "sensors", "tls": {} }
+            }),
+        ] {
+            if let Err(e) = parse_connector(config.clone()) {
+                panic!("{config} was rejected: {e:?}");
+            }
+        }
+    }
 }
@@ -258,6 +258,21 @@ const connectors = {
                     id: 'connectors/sources/http-get',
                     label: 'HTTP GET (URL)'
                 },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/http-poller',
+                    label: 'HTTP Poller'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/sse',
+                    label: 'Server-Sent Events'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/arrow-flight',
+                    label: 'Arrow Flight'
+                },
                 {
                     type: 'doc',
                     id: 'connectors/sources/delta',
@@ -278,6 +293,66 @@ const connectors = {
                     id: 'connectors/sources/pubsub',
                     label: 'Google Pub/Sub'
                 },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/nats-kv',
+                    label: 'NATS Key-Value'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/nats-object-store',
+                    label: 'NATS Object Store'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/mqtt',
+                    label: 'MQTT'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/rabbitmq',
+                    label: 'RabbitMQ'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/redis',
+                    label: 'Redis Streams'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/pulsar',
+                    label: 'Apache Pulsar'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/sqs',
+                    label: 'AWS SQS'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/websocket',
+                    label: 'WebSocket'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/grpc',
+                    label: 'gRPC'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/tcp',
+                    label: 'TCP'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/syslog',
+                    label: 'Syslog'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/unix',
+                    label: 'Unix domain socket'
+                },
                 {
                     type: 'doc',
                     id: 'connectors/sources/debezium',
@@ -288,11 +363,41 @@ const connectors = {
                     id: 'connectors/sources/postgresql',
                     label: 'PostgreSQL'
                 },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/postgresql-cdc',
+                    label: 'PostgreSQL CDC'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/mysql-cdc',
+                    label: 'MySQL CDC'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/mongodb',
+                    label: 'MongoDB'
+                },
                 {
                     type: 'doc',
                     id: 'connectors/sources/s3',
                     label: 'AWS S3'
                 },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/gcs',
+                    label: 'Google Cloud Storage'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/azure-blob',
+                    label: 'Azure Blob Storage'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sources/ftp',
+                    label: 'SFTP/FTP'
+                },
                 {
                     type: 'doc',
                     id: 'connectors/sources/datagen',
@@ -323,6 +428,26 @@ const connectors = {
                     id: 'connectors/sinks/delta',
                     label: 'Delta Lake'
                 },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/iceberg',
+                    label: 'Apache Iceberg'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/s3',
+                    label: 'AWS S3'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/gcs',
+                    label: 'Google Cloud Storage'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/azure-blob',
+                    label: 'Azure Blob Storage'
+                },
                 {
                     type: 'doc',
                     id: 'connectors/sinks/kafka',
@@ -338,11 +463,76 @@ const connectors = {
                     id: 'connectors/sinks/redis',
                     label: 'Redis'
                 },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/redis-streams',
+                    label: 'Redis Streams'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/mqtt',
+                    label: 'MQTT'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/pulsar',
+                    label: 'Apache Pulsar'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/kinesis',
+                    label: 'AWS Kinesis'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/websocket',
+                    label: 'WebSocket'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/grpc',
+                    label: 'gRPC'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/unix',
+                    label: 'Unix domain socket'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/webhook',
+                    label: 'Webhook'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/alert',
+                    label: 'Slack & e-mail alerts'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/arrow-flight',
+                    label: 'Arrow Flight'
+                },
                 {
                     type: 'doc',
                     id: 'connectors/sinks/postgresql',
                     label: 'PostgreSQL'
                 },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/mysql',
+                    label: 'MySQL'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/mongodb',
+                    label: 'MongoDB'
+                },
+                {
+                    type: 'doc',
+                    id: 'connectors/sinks/influxdb',
+                    label: 'InfluxDB'
+                },
                 {
                     type: 'doc',
                     id: 'connectors/sinks/snowflake',